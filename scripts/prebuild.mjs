@@ -508,12 +508,6 @@ const resolveGeoIP = () =>
     file: "geoip.dat",
     downloadURL: `https://github.com/MetaCubeX/meta-rules-dat/releases/download/latest/geoip.dat`,
   });
-const resolveEnableLoopback = () =>
-  resolveResource({
-    file: "enableLoopback.exe",
-    downloadURL: `https://github.com/Kuingsmile/uwp-tool/releases/download/latest/enableLoopback.exe`,
-  });
-
 const resolveWinSysproxy = () =>
   resolveResource({
     file: "sysproxy.exe",
@@ -541,12 +535,6 @@ const tasks = [
   { name: "mmdb", func: resolveMmdb, retry: 5 },
   { name: "geosite", func: resolveGeosite, retry: 5 },
   { name: "geoip", func: resolveGeoIP, retry: 5 },
-  {
-    name: "enableLoopback",
-    func: resolveEnableLoopback,
-    retry: 5,
-    winOnly: true,
-  },
   {
     name: "service_chmod",
     func: resolveServicePermission,
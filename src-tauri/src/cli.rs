@@ -0,0 +1,268 @@
+//! 无界面命令行模式：`--cli <子命令>`，不创建窗口，直接复用 cmd/feat 中的函数
+//!
+//! 指定子命令时执行一次后退出；未指定时从标准输入逐行读取子命令，
+//! 每条结果以一行 JSON 输出到标准输出。
+
+use crate::{
+    cmd::{self, DiagnosticsOptions, SpeedTestConfig},
+    config::Config,
+    core::{CoreManager, RunningMode, handle, service},
+    feat, logging,
+    process::AsyncHandler,
+    utils::{init, logging::Type},
+};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+
+const USAGE: &str = "\
+用法: liebesu-clash --cli [子命令] [参数]
+
+子命令:
+  start-core                      启动内核（Sidecar 模式下前台运行，Ctrl-C 停止）
+  stop-core                       停止内核
+  update-profiles [uid...]        更新指定订阅，省略时更新全部远程订阅
+  run-speed-test [--max-nodes N] [--profile UID]
+                                  执行全局节点测速并输出结果摘要
+  export-diagnostics [--output DIR]
+                                  生成脱敏诊断包
+  help                            显示本帮助
+
+未指定子命令时从标准输入逐行读取子命令，输入 exit 退出。";
+
+/// 命令行模式入口，返回进程退出码
+pub fn run() -> i32 {
+    let args: Vec<String> = std::env::args()
+        .skip_while(|arg| arg != "--cli")
+        .skip(1)
+        .collect();
+    if matches!(
+        args.first().map(String::as_str),
+        Some("help" | "-h" | "--help")
+    ) {
+        println!("{USAGE}");
+        return 0;
+    }
+
+    // Sidecar 模式需要 AppHandle，只构建应用而不运行事件循环、不创建窗口
+    let _app = build_app();
+
+    AsyncHandler::block_on(async move {
+        if let Err(e) = init::init_config().await {
+            print_response(&Err(format!("初始化配置失败: {e}")));
+            return 1;
+        }
+        // 标准输出只保留 JSON 结果
+        crate::utils::logging::set_server_mode(true);
+        let _ = init::init_resources().await;
+        if let Err(e) = Config::init_config().await {
+            logging!(warn, Type::Config, "[CLI] 初始化运行时配置失败: {}", e);
+        }
+
+        let code = if args.is_empty() {
+            run_interactive().await
+        } else {
+            let result = execute(&args).await;
+            let code = i32::from(result.is_err());
+            print_response(&result);
+            if code == 0 && args[0] == "start-core" {
+                wait_sidecar_core().await;
+            }
+            code
+        };
+
+        handle::Handle::global().set_is_exiting();
+        code
+    })
+}
+
+/// 逐行读取标准输入中的子命令，退出前停止本进程启动的 Sidecar 内核
+async fn run_interactive() -> i32 {
+    loop {
+        let line = AsyncHandler::spawn_blocking(|| {
+            let mut line = String::new();
+            match std::io::stdin().lock().read_line(&mut line) {
+                Ok(0) | Err(_) => None,
+                Ok(_) => Some(line),
+            }
+        })
+        .await
+        .ok()
+        .flatten();
+
+        let Some(line) = line else {
+            break;
+        };
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        match args.first().map(String::as_str) {
+            None => {}
+            Some("exit" | "quit") => break,
+            Some(_) => print_response(&execute(&args).await),
+        }
+    }
+
+    if CoreManager::global().get_running_mode() == RunningMode::Sidecar {
+        let _ = CoreManager::global().stop_core().await;
+    }
+    0
+}
+
+async fn execute(args: &[String]) -> Result<Value, String> {
+    let (command, rest) = args.split_first().ok_or("缺少子命令")?;
+    logging!(info, Type::System, "[CLI] 执行子命令: {}", command);
+
+    match command.as_str() {
+        "start-core" => start_core().await,
+        "stop-core" => stop_core().await,
+        "update-profiles" => update_profiles(rest).await,
+        "run-speed-test" => run_speed_test(rest).await,
+        "export-diagnostics" => export_diagnostics(rest).await,
+        "help" => Ok(Value::String(USAGE.to_string())),
+        other => Err(format!("未知子命令: {other}，使用 help 查看帮助")),
+    }
+}
+
+async fn start_core() -> Result<Value, String> {
+    let manager = CoreManager::global();
+    if manager.get_running_mode() == RunningMode::NotRunning {
+        manager.start_core().await.map_err(|e| e.to_string())?;
+    }
+    Ok(json!({ "running_mode": manager.get_running_mode().to_string() }))
+}
+
+async fn stop_core() -> Result<Value, String> {
+    let manager = CoreManager::global();
+    // 由服务托管的内核可以跨进程停止
+    if manager.get_running_mode() == RunningMode::NotRunning
+        && service::is_service_available().await.is_ok()
+    {
+        service::stop_core_by_service()
+            .await
+            .map_err(|e| e.to_string())?;
+    } else {
+        manager.stop_core().await.map_err(|e| e.to_string())?;
+    }
+    Ok(json!({ "running_mode": manager.get_running_mode().to_string() }))
+}
+
+async fn update_profiles(uids: &[String]) -> Result<Value, String> {
+    let uids = if uids.is_empty() {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        profiles
+            .items
+            .iter()
+            .flatten()
+            .filter(|item| item.itype.as_deref() == Some("remote"))
+            .filter_map(|item| item.uid.clone())
+            .collect()
+    } else {
+        uids.to_vec()
+    };
+
+    // 内核未运行时只更新订阅文件
+    let auto_refresh = CoreManager::global().get_running_mode() != RunningMode::NotRunning;
+    let mut updated = Vec::new();
+    let mut failed = Vec::new();
+    for (uid, result) in feat::update_profiles(uids, Some(auto_refresh)).await {
+        match result {
+            Ok(()) => updated.push(uid),
+            Err(e) => failed.push(json!({ "uid": uid, "error": e.to_string() })),
+        }
+    }
+
+    if updated.is_empty() && !failed.is_empty() {
+        return Err(format!("全部 {} 个订阅更新失败", failed.len()));
+    }
+    Ok(json!({ "updated": updated, "failed": failed }))
+}
+
+async fn run_speed_test(args: &[String]) -> Result<Value, String> {
+    let mut config = SpeedTestConfig::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--max-nodes" => {
+                let value = iter.next().ok_or("--max-nodes 缺少参数")?;
+                config.max_nodes = Some(value.parse().map_err(|_| "--max-nodes 需要数字")?);
+            }
+            "--profile" => {
+                let value = iter.next().ok_or("--profile 缺少参数")?;
+                config
+                    .profile_uids
+                    .get_or_insert_with(Vec::new)
+                    .push(value.clone());
+            }
+            other => return Err(format!("未知参数: {other}")),
+        }
+    }
+
+    cmd::run_global_speed_test(None, Some(config)).await?;
+    let summary = cmd::latest_speed_test_summary().ok_or("没有测速结果")?;
+    Ok(json!({
+        "total_nodes": summary.total_nodes,
+        "successful_tests": summary.successful_tests,
+        "failed_tests": summary.failed_tests,
+        "duration_seconds": summary.duration_seconds,
+        "best_node": summary.best_node,
+        "top_10_nodes": summary.top_10_nodes,
+    }))
+}
+
+async fn export_diagnostics(args: &[String]) -> Result<Value, String> {
+    let mut options = DiagnosticsOptions::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" => {
+                options.output_dir = Some(iter.next().ok_or("--output 缺少参数")?.clone());
+            }
+            other => return Err(format!("未知参数: {other}")),
+        }
+    }
+
+    let bundle = cmd::generate_diagnostics_bundle(Some(options)).await?;
+    serde_json::to_value(bundle).map_err(|e| e.to_string())
+}
+
+/// Sidecar 内核随本进程退出，前台等待 Ctrl-C 后再停止
+async fn wait_sidecar_core() {
+    if CoreManager::global().get_running_mode() != RunningMode::Sidecar {
+        return;
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    if let Err(e) = ctrlc::set_handler(move || {
+        let _ = tx.send(());
+    }) {
+        logging!(warn, Type::System, "[CLI] 注册 Ctrl-C 处理失败: {}", e);
+        return;
+    }
+    let _ = AsyncHandler::spawn_blocking(move || rx.recv()).await;
+    let _ = CoreManager::global().stop_core().await;
+}
+
+fn build_app() -> Option<tauri::App> {
+    // 没有图形环境时无法创建事件循环，只能使用服务模式
+    #[cfg(target_os = "linux")]
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return None;
+    }
+
+    let app = tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .build(crate::app_context())
+        .map_err(|e| eprintln!("构建 Tauri 应用失败: {e}"))
+        .ok()?;
+    handle::Handle::global().init(app.handle().clone());
+    Some(app)
+}
+
+fn print_response(result: &Result<Value, String>) {
+    let response = match result {
+        Ok(data) => json!({ "ok": true, "data": data }),
+        Err(error) => json!({ "ok": false, "error": error }),
+    };
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{response}");
+    let _ = stdout.flush();
+}
@@ -9,6 +9,8 @@
     clippy::match_like_matches_macro
 )]
 // TODO: 移除临时的 lint 豁免，逐步落地对应优化。
+use super::CmdResult;
+use crate::utils::field_mask::apply_field_mask;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use nanoid::nanoid;
@@ -202,8 +204,20 @@ struct SearchIndexItem {
 }
 
 /// 执行高级搜索
+/// fields 为可选的字段路径列表（如 `items.name`），仅返回指定字段
 #[tauri::command]
-pub async fn advanced_search(criteria: SearchCriteria) -> Result<SearchResult, String> {
+pub async fn advanced_search(
+    criteria: SearchCriteria,
+    fields: Option<Vec<String>>,
+) -> CmdResult<serde_json::Value> {
+    let result = run_advanced_search(criteria).await?;
+    let value = serde_json::to_value(result)
+        .map_err(|e| format!("Failed to serialize search result: {}", e))?;
+    Ok(apply_field_mask(value, fields.as_deref()))
+}
+
+/// 执行高级搜索并返回完整结果
+async fn run_advanced_search(criteria: SearchCriteria) -> Result<SearchResult, String> {
     let start_time = std::time::Instant::now();
 
     // 获取所有订阅数据（模拟）
@@ -271,7 +285,7 @@ pub async fn advanced_search(criteria: SearchCriteria) -> Result<SearchResult, S
 pub async fn quick_search(
     query: String,
     limit: Option<u32>,
-) -> Result<Vec<SubscriptionSearchItem>, String> {
+) -> CmdResult<Vec<SubscriptionSearchItem>> {
     let criteria = SearchCriteria {
         query,
         filters: Vec::new(),
@@ -281,7 +295,7 @@ pub async fn quick_search(
         offset: Some(0),
     };
 
-    let result = advanced_search(criteria).await?;
+    let result = run_advanced_search(criteria).await?;
     Ok(result.items)
 }
 
@@ -291,7 +305,7 @@ pub async fn save_search(
     name: String,
     description: String,
     criteria: SearchCriteria,
-) -> Result<String, String> {
+) -> CmdResult<String> {
     let search_id = nanoid!();
     let timestamp = Utc::now().timestamp();
 
@@ -314,13 +328,13 @@ pub async fn save_search(
 
 /// 获取保存的搜索
 #[tauri::command]
-pub async fn get_saved_searches() -> Result<Vec<SavedSearch>, String> {
-    load_saved_searches().map_err(|e| format!("Failed to load saved searches: {}", e))
+pub async fn get_saved_searches() -> CmdResult<Vec<SavedSearch>> {
+    load_saved_searches().map_err(|e| format!("Failed to load saved searches: {}", e).into())
 }
 
 /// 删除保存的搜索
 #[tauri::command]
-pub async fn delete_saved_search(search_id: String) -> Result<(), String> {
+pub async fn delete_saved_search(search_id: String) -> CmdResult<()> {
     let mut searches =
         load_saved_searches().map_err(|e| format!("Failed to load saved searches: {}", e))?;
 
@@ -333,7 +347,7 @@ pub async fn delete_saved_search(search_id: String) -> Result<(), String> {
 
 /// 执行保存的搜索
 #[tauri::command]
-pub async fn execute_saved_search(search_id: String) -> Result<SearchResult, String> {
+pub async fn execute_saved_search(search_id: String) -> CmdResult<SearchResult> {
     let mut searches =
         load_saved_searches().map_err(|e| format!("Failed to load saved searches: {}", e))?;
 
@@ -349,15 +363,15 @@ pub async fn execute_saved_search(search_id: String) -> Result<SearchResult, Str
             .map_err(|e| format!("Failed to update search stats: {}", e))?;
 
         // 执行搜索
-        advanced_search(criteria).await
+        Ok(run_advanced_search(criteria).await?)
     } else {
-        Err("Saved search not found".to_string())
+        Err("Saved search not found".into())
     }
 }
 
 /// 获取搜索历史
 #[tauri::command]
-pub async fn get_search_history(limit: Option<u32>) -> Result<Vec<SearchHistory>, String> {
+pub async fn get_search_history(limit: Option<u32>) -> CmdResult<Vec<SearchHistory>> {
     let history =
         load_search_history().map_err(|e| format!("Failed to load search history: {}", e))?;
 
@@ -373,7 +387,7 @@ pub async fn get_search_history(limit: Option<u32>) -> Result<Vec<SearchHistory>
 
 /// 清理搜索历史
 #[tauri::command]
-pub async fn clear_search_history() -> Result<(), String> {
+pub async fn clear_search_history() -> CmdResult<()> {
     save_search_history(&Vec::new())
         .map_err(|e| format!("Failed to clear search history: {}", e))?;
 
@@ -382,18 +396,18 @@ pub async fn clear_search_history() -> Result<(), String> {
 
 /// 获取搜索建议
 #[tauri::command]
-pub async fn get_search_suggestions(query: String) -> Result<Vec<SearchSuggestion>, String> {
+pub async fn get_search_suggestions(query: String) -> CmdResult<Vec<SearchSuggestion>> {
     let subscriptions = get_all_subscriptions_for_search()
         .await
         .map_err(|e| format!("Failed to get subscriptions: {}", e))?;
 
     generate_smart_suggestions(&query, &subscriptions)
-        .map_err(|e| format!("Failed to generate suggestions: {}", e))
+        .map_err(|e| format!("Failed to generate suggestions: {}", e).into())
 }
 
 /// 获取字段值建议
 #[tauri::command]
-pub async fn get_field_value_suggestions(field: SearchField) -> Result<Vec<String>, String> {
+pub async fn get_field_value_suggestions(field: SearchField) -> CmdResult<Vec<String>> {
     let subscriptions = get_all_subscriptions_for_search()
         .await
         .map_err(|e| format!("Failed to get subscriptions: {}", e))?;
@@ -435,7 +449,7 @@ pub async fn get_field_value_suggestions(field: SearchField) -> Result<Vec<Strin
 
 /// 更新搜索索引
 #[tauri::command]
-pub async fn update_search_index() -> Result<(), String> {
+pub async fn update_search_index() -> CmdResult<()> {
     let subscriptions = get_all_subscriptions_for_search()
         .await
         .map_err(|e| format!("Failed to get subscriptions: {}", e))?;
@@ -516,7 +530,7 @@ pub async fn update_search_index() -> Result<(), String> {
 
 /// 获取搜索统计
 #[tauri::command]
-pub async fn get_search_statistics() -> Result<SearchStatistics, String> {
+pub async fn get_search_statistics() -> CmdResult<SearchStatistics> {
     let history =
         load_search_history().map_err(|e| format!("Failed to load search history: {}", e))?;
 
@@ -1141,7 +1155,7 @@ fn save_saved_search(search: &SavedSearch) -> Result<()> {
 }
 
 /// 保存已保存的搜索列表
-fn save_saved_searches(searches: &[SavedSearch]) -> Result<()> {
+pub(crate) fn save_saved_searches(searches: &[SavedSearch]) -> Result<()> {
     let data_dir = get_search_data_dir()?;
     let searches_file = data_dir.join("saved_searches.json");
 
@@ -1152,7 +1166,7 @@ fn save_saved_searches(searches: &[SavedSearch]) -> Result<()> {
 }
 
 /// 加载已保存的搜索
-fn load_saved_searches() -> Result<Vec<SavedSearch>> {
+pub(crate) fn load_saved_searches() -> Result<Vec<SavedSearch>> {
     let data_dir = get_search_data_dir()?;
     let searches_file = data_dir.join("saved_searches.json");
 
@@ -0,0 +1,27 @@
+use super::{CmdError, CmdResult};
+use crate::utils::server::api::{ApiServer, ApiServerConfig};
+
+/// 获取本地 REST API 配置
+#[tauri::command]
+pub fn get_api_server_config() -> CmdResult<ApiServerConfig> {
+    Ok(ApiServer::global().config())
+}
+
+/// 启用或停用本地 REST API，可修改端口
+#[tauri::command]
+pub async fn set_api_server_config(enabled: bool, port: Option<u16>) -> CmdResult<ApiServerConfig> {
+    ApiServer::global()
+        .update(enabled, port, false)
+        .await
+        .map_err(CmdError::from)
+}
+
+/// 重新生成 API 令牌，旧令牌立即失效
+#[tauri::command]
+pub async fn regenerate_api_server_token() -> CmdResult<ApiServerConfig> {
+    let config = ApiServer::global().config();
+    ApiServer::global()
+        .update(config.enabled, None, true)
+        .await
+        .map_err(CmdError::from)
+}
@@ -1,7 +1,11 @@
 use super::CmdResult;
 use crate::{
+    config::IVerge,
     feat, logging,
-    utils::{dirs, logging::Type},
+    utils::{
+        dirs, init,
+        logging::{LoggingProfile, Type},
+    },
     wrap_err,
 };
 use tauri::{AppHandle, Manager};
@@ -28,6 +32,20 @@ pub async fn open_logs_dir() -> CmdResult<()> {
     wrap_err!(open::that(log_dir))
 }
 
+/// 切换日志配置档：interactive | server
+#[tauri::command]
+pub async fn set_logging_profile(profile: String) -> CmdResult<()> {
+    let profile = LoggingProfile::parse(&profile)
+        .ok_or_else(|| format!("Unknown logging profile: {profile}"))?;
+    let patch = IVerge {
+        logging_profile: Some(profile.as_str().to_string()),
+        ..IVerge::default()
+    };
+    wrap_err!(feat::patch_verge(patch, false).await)?;
+    init::apply_logging_profile().await;
+    Ok(())
+}
+
 /// 打开网页链接
 #[tauri::command]
 pub fn open_web_url(url: String) -> CmdResult<()> {
@@ -146,7 +164,7 @@ pub async fn download_icon_cache(url: String, name: String) -> CmdResult<String>
         Ok(icon_path.to_string_lossy().to_string())
     } else {
         let _ = std::fs::remove_file(&temp_path);
-        Err(format!("下载的内容不是有效图片: {url}"))
+        Err(format!("下载的内容不是有效图片: {url}").into())
     }
 }
 
@@ -198,10 +216,10 @@ pub fn copy_icon_file(path: String, icon_info: IconInfo) -> CmdResult<String> {
         );
         match fs::copy(file_path, &dest_path) {
             Ok(_) => Ok(dest_path.to_string_lossy().to_string()),
-            Err(err) => Err(err.to_string()),
+            Err(err) => Err(err.into()),
         }
     } else {
-        Err("file not found".to_string())
+        Err("file not found".into())
     }
 }
 
@@ -228,7 +246,7 @@ pub fn update_ui_stage(stage: String) -> CmdResult<()> {
         "Ready" => UiReadyStage::Ready,
         _ => {
             log::warn!(target: "app", "未知的UI加载阶段: {stage}");
-            return Err(format!("未知的UI加载阶段: {stage}"));
+            return Err(format!("未知的UI加载阶段: {stage}").into());
         }
     };
 
@@ -0,0 +1,48 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::module::app_lock::{AppLock, AppLockStatus, InvalidPasscode};
+
+/// 获取应用锁状态
+#[tauri::command]
+pub fn get_app_lock_status() -> CmdResult<AppLockStatus> {
+    Ok(AppLock::global().status())
+}
+
+/// 设置或修改应用密码，passcode 为空时清除密码
+#[tauri::command]
+pub async fn set_app_passcode(
+    current: Option<String>,
+    passcode: Option<String>,
+) -> CmdResult<AppLockStatus> {
+    AppLock::global().set_passcode(current, passcode).await?;
+    Ok(AppLock::global().status())
+}
+
+/// 设置进入轻量模式后自动锁定的延迟（分钟），为空时不自动锁定
+#[tauri::command]
+pub fn set_app_lock_options(auto_lock_minutes: Option<u64>) -> CmdResult<AppLockStatus> {
+    AppLock::global().set_auto_lock(auto_lock_minutes)?;
+    Ok(AppLock::global().status())
+}
+
+/// 使用密码解锁应用
+#[tauri::command]
+pub async fn unlock_app(passcode: String) -> CmdResult<AppLockStatus> {
+    // 密码错误与应用已锁定区分开，等待秒数放在 details 中
+    AppLock::global().unlock(passcode).await.map_err(|e| {
+        match e.downcast_ref::<InvalidPasscode>() {
+            Some(invalid) => CmdError {
+                details: invalid.retry_after_secs.map(|secs| secs.to_string()),
+                ..CmdError::new(ErrorCode::InvalidPasscode, invalid.message.clone())
+            },
+            None => e.into(),
+        }
+    })?;
+    Ok(AppLock::global().status())
+}
+
+/// 立即锁定应用
+#[tauri::command]
+pub fn lock_app() -> CmdResult<AppLockStatus> {
+    AppLock::global().lock()?;
+    Ok(AppLock::global().status())
+}
@@ -0,0 +1,308 @@
+use super::{CmdError, CmdResult, ErrorCode, custom_rules::ensure_target_exists};
+use crate::{
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Sequence, Value};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use sysinfo::{ProcessesToUpdate, System};
+use tokio::sync::Mutex;
+
+const APP_ROUTES_FILE: &str = "app_routes.yaml";
+
+const APP_ROUTES_HEADER: &str = "# Application Routes for Liebesu_Clash
+# 由分应用代理管理生成，请勿手动修改
+
+";
+
+static ROUTES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 进程匹配方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum AppRouteMatch {
+    ProcessName,
+    ProcessPath,
+}
+
+impl AppRouteMatch {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::ProcessName => "PROCESS-NAME",
+            Self::ProcessPath => "PROCESS-PATH",
+        }
+    }
+}
+
+/// 分应用路由
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppRoute {
+    pub process: String, // 进程名或完整路径
+    pub match_type: AppRouteMatch,
+    pub outbound: String, // 出站策略：代理组、节点或 DIRECT/REJECT
+}
+
+impl AppRoute {
+    fn render(&self) -> String {
+        format!(
+            "{},{},{}",
+            self.match_type.as_str(),
+            self.process,
+            self.outbound
+        )
+    }
+
+    fn parse(rule: &str) -> Option<Self> {
+        let mut parts = rule.splitn(3, ',').map(str::trim);
+        let match_type = match parts.next()? {
+            "PROCESS-NAME" => AppRouteMatch::ProcessName,
+            "PROCESS-PATH" => AppRouteMatch::ProcessPath,
+            _ => return None,
+        };
+        Some(Self {
+            match_type,
+            process: parts.next()?.to_string(),
+            outbound: parts.next()?.to_string(),
+        })
+    }
+}
+
+/// 正在运行的应用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningApplication {
+    pub name: String,         // 用于 PROCESS-NAME 匹配的进程名
+    pub display_name: String, // 界面显示名称
+    pub path: Option<String>,
+    pub pid: u32,
+}
+
+/// 添加或更新应用的出站策略，包含路径分隔符时按完整路径匹配
+#[tauri::command]
+pub async fn add_app_route(process: String, outbound: String) -> CmdResult<Vec<AppRoute>> {
+    let process = process.trim().to_string();
+    let outbound = outbound.trim().to_string();
+    if process.is_empty() || outbound.is_empty() {
+        return Err("进程和出站策略不能为空".into());
+    }
+    if process.contains(',') || outbound.contains(',') {
+        return Err("进程和出站策略不能包含逗号".into());
+    }
+    ensure_target_exists(&outbound).await?;
+
+    let match_type = if process.contains('/') || process.contains('\\') {
+        AppRouteMatch::ProcessPath
+    } else {
+        AppRouteMatch::ProcessName
+    };
+    let route = AppRoute {
+        process,
+        match_type,
+        outbound,
+    };
+
+    let _guard = ROUTES_LOCK.lock().await;
+    let previous = load_routes().context("加载分应用路由失败")?;
+    let mut routes = previous.clone();
+    match routes.iter_mut().find(|r| r.process == route.process) {
+        Some(existing) => existing.outbound = route.outbound.clone(),
+        None => routes.push(route.clone()),
+    }
+    apply_routes(&routes, &previous).await?;
+
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[分应用代理] {} -> {}",
+        route.process,
+        route.outbound
+    );
+    Ok(routes)
+}
+
+/// 删除应用的出站策略
+#[tauri::command]
+pub async fn remove_app_route(process: String) -> CmdResult<Vec<AppRoute>> {
+    let _guard = ROUTES_LOCK.lock().await;
+    let previous = load_routes().context("加载分应用路由失败")?;
+    let routes: Vec<AppRoute> = previous
+        .iter()
+        .filter(|r| r.process != process)
+        .cloned()
+        .collect();
+    if routes.len() == previous.len() {
+        return Err(format!("分应用路由不存在: {}", process).into());
+    }
+
+    apply_routes(&routes, &previous).await?;
+    Ok(routes)
+}
+
+/// 获取分应用路由列表
+#[tauri::command]
+pub async fn list_app_routes() -> CmdResult<Vec<AppRoute>> {
+    load_routes()
+        .context("加载分应用路由失败")
+        .map_err(CmdError::from)
+}
+
+/// 获取正在运行的应用，供界面选择进程
+#[tauri::command]
+pub async fn list_running_applications() -> CmdResult<Vec<RunningApplication>> {
+    let apps = tokio::task::spawn_blocking(collect_running_applications).await?;
+    Ok(apps)
+}
+
+/// 读取分应用路由合并文件中的规则，供增强链使用
+pub fn app_routes_prepend() -> Option<Sequence> {
+    let content = fs::read_to_string(routes_path().ok()?).ok()?;
+    let merge: Mapping = serde_yaml_ng::from_str(&content).ok()?;
+    merge
+        .get("prepend-rules")
+        .and_then(Value::as_sequence)
+        .filter(|seq| !seq.is_empty())
+        .cloned()
+}
+
+// ===== 内部实现函数 =====
+
+fn collect_running_applications() -> Vec<RunningApplication> {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    // 同名进程只保留一个
+    let mut apps: HashMap<String, RunningApplication> = HashMap::new();
+    for (pid, process) in system.processes() {
+        // 没有可执行文件路径的多为内核线程或无权限访问的系统进程
+        let Some(exe) = process.exe().filter(|p| !p.as_os_str().is_empty()) else {
+            continue;
+        };
+        let name = process.name().to_string_lossy().to_string();
+        if name.is_empty() || is_system_process(exe) {
+            continue;
+        }
+
+        apps.entry(name.clone())
+            .or_insert_with(|| RunningApplication {
+                display_name: display_name(&name, exe),
+                name,
+                path: Some(exe.to_string_lossy().to_string()),
+                pid: pid.as_u32(),
+            });
+    }
+
+    let mut apps: Vec<RunningApplication> = apps.into_values().collect();
+    apps.sort_by_key(|a| a.display_name.to_lowercase());
+    apps
+}
+
+#[cfg(target_os = "windows")]
+fn is_system_process(exe: &Path) -> bool {
+    exe.to_string_lossy()
+        .to_lowercase()
+        .starts_with("c:\\windows\\system32")
+}
+
+#[cfg(target_os = "macos")]
+fn is_system_process(exe: &Path) -> bool {
+    ["/usr/libexec/", "/usr/sbin/", "/System/Library/"]
+        .iter()
+        .any(|prefix| exe.starts_with(prefix))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn is_system_process(exe: &Path) -> bool {
+    ["/usr/lib/systemd/", "/usr/libexec/", "/usr/sbin/", "/sbin/"]
+        .iter()
+        .any(|prefix| exe.starts_with(prefix))
+}
+
+/// macOS 下使用应用包名称作为显示名称
+#[cfg(target_os = "macos")]
+fn display_name(name: &str, exe: &Path) -> String {
+    exe.ancestors()
+        .find_map(|p| {
+            p.file_name()?
+                .to_str()?
+                .strip_suffix(".app")
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| name.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn display_name(name: &str, _exe: &Path) -> String {
+    name.strip_suffix(".exe").unwrap_or(name).to_string()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn display_name(name: &str, _exe: &Path) -> String {
+    name.to_string()
+}
+
+/// 写入路由并重新生成配置，内核验证失败时恢复原路由
+async fn apply_routes(routes: &[AppRoute], previous: &[AppRoute]) -> CmdResult<()> {
+    save_routes(routes).context("保存分应用路由失败")?;
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            save_routes(previous).context("恢复分应用路由失败")?;
+            Err(CmdError::new(
+                ErrorCode::ConfigValidationFailed,
+                format!("路由未通过内核验证，已恢复: {}", error),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 获取合并文件路径
+fn routes_path() -> Result<PathBuf> {
+    Ok(dirs::app_profiles_dir()?.join(APP_ROUTES_FILE))
+}
+
+fn load_routes() -> Result<Vec<AppRoute>> {
+    let path = routes_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let merge: Mapping = serde_yaml_ng::from_str(&fs::read_to_string(path)?)?;
+    Ok(merge
+        .get("prepend-rules")
+        .and_then(Value::as_sequence)
+        .map(|seq| {
+            seq.iter()
+                .filter_map(Value::as_str)
+                .filter_map(AppRoute::parse)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn save_routes(routes: &[AppRoute]) -> Result<()> {
+    let mut merge = Mapping::new();
+    merge.insert(
+        "prepend-rules".into(),
+        routes
+            .iter()
+            .map(|r| Value::from(r.render()))
+            .collect::<Vec<_>>()
+            .into(),
+    );
+    let content = format!("{}{}", APP_ROUTES_HEADER, serde_yaml_ng::to_string(&merge)?);
+    fs::write(routes_path()?, content)?;
+    Ok(())
+}
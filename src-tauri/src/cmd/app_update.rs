@@ -0,0 +1,331 @@
+use super::{CmdError, CmdResult};
+use crate::{
+    core::handle,
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose};
+use futures::StreamExt;
+use minisign_verify::{PublicKey, Signature};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fs, path::PathBuf, time::Duration};
+use tauri::{AppHandle, Emitter};
+
+const RELEASE_API: &str = "https://api.github.com/repos/liebesu/LIebesu_Clash/releases/latest";
+const UPDATES_DIR: &str = "updates";
+const DOWNLOAD_TIMEOUT_SECONDS: u64 = 600;
+/// 更新签名公钥（minisign，base64），与发布流程中 TAURI_PRIVATE_KEY 对应
+const UPDATER_PUBKEY: &str = "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXk6IEQyOEMyRjBCQkVGOUJEREYKUldUZnZmbStDeStNMHU5Mmo1N24xQXZwSVRYbXA2NUpzZE5oVzlqeS9Bc0t6RVV4MmtwVjBZaHgK";
+
+static UPDATE_STATE: Lazy<Mutex<UpdateState>> = Lazy::new(|| Mutex::new(UpdateState::default()));
+
+/// 可用更新
+#[derive(Debug, Clone, Serialize)]
+pub struct AppUpdateInfo {
+    pub current_version: String,
+    pub version: String,
+    pub available: bool,
+    pub notes: String,
+    pub published_at: Option<String>,
+    pub asset_name: Option<String>, // 当前平台没有对应安装包时为空
+    pub size: Option<u64>,
+}
+
+/// 下载进度，通过 app-update-progress 事件发送
+#[derive(Debug, Clone, Serialize)]
+struct AppUpdateProgress {
+    version: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    body: Option<String>,
+    published_at: Option<String>,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubAsset {
+    name: String,
+    size: u64,
+    browser_download_url: String,
+    digest: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct UpdateState {
+    release: Option<GithubRelease>,
+    staged: Option<(String, PathBuf)>, // 已校验的版本和安装包路径
+}
+
+/// 检查应用更新
+#[tauri::command]
+pub async fn check_app_update() -> CmdResult<AppUpdateInfo> {
+    let release: GithubRelease = http_client()?
+        .get(RELEASE_API)
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .context("获取最新版本失败")?
+        .json()
+        .await
+        .context("解析版本信息失败")?;
+
+    let current_version = env!("CARGO_PKG_VERSION").to_string();
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    let asset = select_asset(&release.assets);
+    let info = AppUpdateInfo {
+        available: is_newer(&version, &current_version),
+        current_version,
+        version,
+        notes: release.body.clone().unwrap_or_default(),
+        published_at: release.published_at.clone(),
+        asset_name: asset.map(|asset| asset.name.clone()),
+        size: asset.map(|asset| asset.size),
+    };
+    logging!(
+        info,
+        Type::System,
+        true,
+        "[应用更新] 当前 {}，最新 {}",
+        info.current_version,
+        info.version
+    );
+    UPDATE_STATE.lock().release = Some(release);
+    Ok(info)
+}
+
+/// 下载更新安装包并校验摘要和签名，完成后等待安装
+#[tauri::command]
+pub async fn download_app_update(app_handle: AppHandle) -> CmdResult<String> {
+    let release = UPDATE_STATE.lock().release.clone().ok_or("请先检查更新")?;
+    let path = download(&app_handle, &release)
+        .await
+        .context("下载更新失败")?;
+
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    UPDATE_STATE.lock().staged = Some((version.clone(), path.clone()));
+    logging!(
+        info,
+        Type::System,
+        true,
+        "[应用更新] {} 已下载并通过校验: {}",
+        version,
+        path.display()
+    );
+    handle::Handle::notice_message("app_update::ready", &version);
+    Ok(version)
+}
+
+/// 安装已下载的更新并重启应用
+#[tauri::command]
+pub async fn install_app_update() -> CmdResult<()> {
+    let (version, path) = UPDATE_STATE
+        .lock()
+        .staged
+        .clone()
+        .ok_or("没有已下载的更新")?;
+    if !path.exists() {
+        return Err("更新安装包不存在，请重新下载".into());
+    }
+    logging!(info, Type::System, true, "[应用更新] 开始安装 {}", version);
+    install(path)
+        .await
+        .context("安装更新失败")
+        .map_err(CmdError::from)
+}
+
+// ===== 内部实现函数 =====
+
+fn http_client() -> Result<Client> {
+    Ok(Client::builder()
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECONDS))
+        .user_agent(format!("liebseu-clash/v{}", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+/// 当前平台的安装包：Windows 为 NSIS 安装程序，macOS 为 .app.tar.gz
+fn select_asset(assets: &[GithubAsset]) -> Option<&GithubAsset> {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => {
+            if cfg!(windows) {
+                "arm64"
+            } else {
+                "aarch64"
+            }
+        }
+        arch => arch,
+    };
+    let suffix = if cfg!(windows) {
+        format!("_{arch}-setup.exe")
+    } else if cfg!(target_os = "macos") {
+        format!("_{arch}.app.tar.gz")
+    } else {
+        return None;
+    };
+    assets.iter().find(|asset| asset.name.ends_with(&suffix))
+}
+
+/// 按数字段比较版本号，忽略预发布后缀
+fn is_newer(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    parse(latest) > parse(current)
+}
+
+async fn download(app_handle: &AppHandle, release: &GithubRelease) -> Result<PathBuf> {
+    let asset = select_asset(&release.assets).ok_or_else(|| anyhow!("当前平台没有可用的安装包"))?;
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    let client = http_client()?;
+
+    let response = client
+        .get(&asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?;
+    let total = response.content_length();
+    let mut data = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        data.extend_from_slice(&chunk?);
+        let progress = AppUpdateProgress {
+            version: version.clone(),
+            downloaded: data.len() as u64,
+            total,
+        };
+        if let Err(err) = app_handle.emit("app-update-progress", progress) {
+            log::warn!(target: "app", "app-update-progress emit failed: {err}");
+        }
+    }
+
+    let digest = asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"));
+    if let Some(expected) = digest {
+        let actual = hex::encode(Sha256::digest(&data));
+        if !actual.eq_ignore_ascii_case(expected) {
+            bail!("sha256 校验失败，期望 {}，实际 {}", expected, actual);
+        }
+    }
+
+    // 安装包必须带有有效签名，缺少签名文件或校验失败时中止更新
+    let sig_name = format!("{}.sig", asset.name);
+    let sig_asset = release
+        .assets
+        .iter()
+        .find(|candidate| candidate.name == sig_name)
+        .ok_or_else(|| anyhow!("发布缺少签名文件 {}", sig_name))?;
+    let signature = client
+        .get(&sig_asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    verify_signature(&data, signature.trim(), UPDATER_PUBKEY)?;
+
+    let dir = dirs::app_home_dir()?.join(UPDATES_DIR).join(&version);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(&asset.name);
+    fs::write(&path, data)?;
+    Ok(path)
+}
+
+/// 校验 minisign 签名，签名和公钥均为 base64 编码的文本
+fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> Result<()> {
+    let decode = |value: &str| -> Result<String> {
+        Ok(String::from_utf8(general_purpose::STANDARD.decode(value)?)?)
+    };
+    let public_key = PublicKey::decode(&decode(pubkey)?)?;
+    let signature = Signature::decode(&decode(signature)?)?;
+    public_key
+        .verify(data, &signature, true)
+        .map_err(|e| anyhow!("签名校验失败: {e}"))
+}
+
+#[cfg(target_os = "windows")]
+async fn install(path: PathBuf) -> Result<()> {
+    // 通过 start 启动安装程序以便请求管理员权限，/P 被动安装，/R 安装后重启应用
+    std::process::Command::new("cmd")
+        .args(["/C", "start", ""])
+        .arg(&path)
+        .args(["/P", "/R", "/UPDATE"])
+        .spawn()?;
+    crate::feat::quit().await;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+async fn install(path: PathBuf) -> Result<()> {
+    let bundle = std::env::current_exe()?
+        .ancestors()
+        .find(|dir| dir.extension().is_some_and(|ext| ext == "app"))
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("未找到应用程序包"))?;
+    let parent = bundle
+        .parent()
+        .ok_or_else(|| anyhow!("无效的应用程序路径"))?;
+
+    let extract_dir = path.with_extension("extract");
+    if extract_dir.exists() {
+        fs::remove_dir_all(&extract_dir)?;
+    }
+    fs::create_dir_all(&extract_dir)?;
+    let status = std::process::Command::new("tar")
+        .arg("-xzf")
+        .arg(&path)
+        .arg("-C")
+        .arg(&extract_dir)
+        .status()?;
+    if !status.success() {
+        bail!("解压安装包失败");
+    }
+    let new_bundle = fs::read_dir(&extract_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|entry| entry.extension().is_some_and(|ext| ext == "app"))
+        .ok_or_else(|| anyhow!("安装包中没有应用程序"))?;
+
+    // 先备份当前应用，替换失败时恢复
+    let backup = parent.join(format!(
+        ".{}.bak",
+        bundle.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    if backup.exists() {
+        fs::remove_dir_all(&backup)?;
+    }
+    fs::rename(&bundle, &backup)?;
+    if let Err(e) = fs::rename(&new_bundle, &bundle) {
+        fs::rename(&backup, &bundle)?;
+        bail!("替换应用程序失败: {e}");
+    }
+    let _ = fs::remove_dir_all(&backup);
+    let _ = fs::remove_dir_all(&extract_dir);
+
+    crate::feat::restart_app().await;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+async fn install(_path: PathBuf) -> Result<()> {
+    bail!("当前平台请使用内置更新器或系统包管理器更新")
+}
@@ -1,8 +1,20 @@
 // use crate::utils::{config, help};
+use super::{CmdResult, OperationKind, ProgressReporter, register_operation};
+use crate::{
+    config::{Config, IVerge, PrfItem, profiles_append_item_safe, profiles_patch_item_safe},
+    feat,
+    utils::dirs,
+};
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
 use anyhow::{Context, Result};
 use chrono::Utc;
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -25,6 +37,12 @@ pub struct BackupData {
     pub groups: Option<GroupsBackup>,
     pub traffic_stats: Option<TrafficStatsBackup>,
     pub tasks: Option<TasksBackup>,
+    #[serde(default)]
+    pub saved_searches: Option<SavedSearchesBackup>,
+    #[serde(default)]
+    pub base_backup_id: Option<String>, // 增量备份所基于的备份，未变化的内容从中读取
+    #[serde(default)]
+    pub manifest: BTreeMap<String, String>, // 备份内容清单：条目 -> 内容摘要
 }
 
 /// 备份类型
@@ -51,6 +69,10 @@ pub struct ProfileBackup {
     pub option: Option<String>,
     pub home: Option<String>,
     pub extra: Option<String>,
+    #[serde(default)]
+    pub itype: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>, // 订阅文件内容
 }
 
 /// 设置备份数据
@@ -79,6 +101,12 @@ pub struct TasksBackup {
     pub tasks_data: String,
 }
 
+/// 已保存搜索备份数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearchesBackup {
+    pub searches_data: String,
+}
+
 /// 备份选项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupOptions {
@@ -88,6 +116,8 @@ pub struct BackupOptions {
     pub include_groups: bool,
     pub include_traffic_stats: bool,
     pub include_tasks: bool,
+    #[serde(default)]
+    pub include_saved_searches: bool,
     pub encrypt: bool,
     pub password: Option<String>,
     pub compression_level: u32, // 0-9
@@ -104,6 +134,10 @@ pub struct RestoreOptions {
     pub restore_groups: bool,
     pub restore_traffic_stats: bool,
     pub restore_tasks: bool,
+    #[serde(default)]
+    pub restore_saved_searches: bool,
+    #[serde(default)]
+    pub profile_uids: Option<Vec<String>>, // 仅恢复指定订阅，为空时恢复全部
     pub merge_mode: bool, // true=合并, false=覆盖
     pub password: Option<String>,
     pub create_backup_before_restore: bool,
@@ -124,6 +158,10 @@ pub struct BackupInfo {
     pub is_encrypted: bool,
     pub checksum: String,
     pub is_valid: bool,
+    #[serde(default)]
+    pub scheduled: bool, // 由定时备份创建
+    #[serde(default)]
+    pub base_backup_id: Option<String>, // 增量备份所基于的备份
 }
 
 /// 恢复结果
@@ -138,6 +176,42 @@ pub struct RestoreResult {
     pub backup_created: Option<String>, // 恢复前创建的备份ID
 }
 
+/// 备份内容类别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BackupCategory {
+    Profiles,
+    Settings,
+    Groups,
+    TrafficStats,
+    SavedSearches,
+    Tasks,
+}
+
+/// 恢复时对本地数据的影响
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RestoreAction {
+    Create,    // 本地不存在，将新建
+    Overwrite, // 本地已存在，覆盖模式下将被替换
+}
+
+/// 恢复预览条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreviewItem {
+    pub category: BackupCategory,
+    pub id: String,
+    pub name: String,
+    pub action: RestoreAction,
+}
+
+/// 恢复预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreview {
+    pub backup_id: String,
+    pub backup_name: String,
+    pub created_at: i64,
+    pub items: Vec<RestorePreviewItem>,
+}
+
 /// WebDAV同步配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebDAVConfig {
@@ -236,44 +310,138 @@ fn decompress_data(data: &[u8]) -> Result<Vec<u8>> {
     Ok(data.to_vec())
 }
 
+/// 加密备份文件头
+const ENCRYPTED_MAGIC: &[u8; 4] = b"LCBK";
+/// 加密格式版本
+const ENCRYPTED_FORMAT_VERSION: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const HEADER_LENGTH: usize = ENCRYPTED_MAGIC.len() + 1 + SALT_LENGTH + NONCE_LENGTH;
+
+/// 使用 argon2 从密码派生 AES-256 密钥
+fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+/// 判断数据是否为加密备份
+fn is_encrypted_data(data: &[u8]) -> bool {
+    data.len() >= HEADER_LENGTH && data.starts_with(ENCRYPTED_MAGIC)
+}
+
 /// 加密数据
-fn encrypt_data(data: &[u8], password: &str) -> Result<Vec<u8>> {
-    // 简单的XOR加密 - 实际应用应使用AES等强加密
-    let key = password.as_bytes();
-    let mut encrypted = Vec::new();
+/// 格式: magic(4) | version(1) | salt(16) | nonce(12) | ciphertext
+pub(crate) fn encrypt_data(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    getrandom::fill(&mut salt).map_err(|e| anyhow::anyhow!("Failed to generate salt: {}", e))?;
+    getrandom::fill(&mut nonce).map_err(|e| anyhow::anyhow!("Failed to generate nonce: {}", e))?;
+
+    let key = derive_key(password, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), data)
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    let mut encrypted = Vec::with_capacity(HEADER_LENGTH + ciphertext.len());
+    encrypted.extend_from_slice(ENCRYPTED_MAGIC);
+    encrypted.push(ENCRYPTED_FORMAT_VERSION);
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend(ciphertext);
+    Ok(encrypted)
+}
 
-    for (i, &byte) in data.iter().enumerate() {
-        encrypted.push(byte ^ key[i % key.len()]);
+/// 解密数据，密码错误或数据被篡改时返回错误
+pub(crate) fn decrypt_data(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    if !is_encrypted_data(data) {
+        return Err(anyhow::anyhow!("Backup is not encrypted"));
+    }
+    let version = data[ENCRYPTED_MAGIC.len()];
+    if version != ENCRYPTED_FORMAT_VERSION {
+        return Err(anyhow::anyhow!(
+            "Unsupported encrypted backup version: {}",
+            version
+        ));
     }
 
-    Ok(encrypted)
+    let salt_start = ENCRYPTED_MAGIC.len() + 1;
+    let nonce_start = salt_start + SALT_LENGTH;
+    let salt = &data[salt_start..nonce_start];
+    let nonce = &data[nonce_start..HEADER_LENGTH];
+
+    let key = derive_key(password, salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), &data[HEADER_LENGTH..])
+        .map_err(|_| anyhow::anyhow!("Invalid backup password"))
 }
 
-/// 解密数据
-#[allow(dead_code)] // 保留用于未来加密功能
-fn decrypt_data(data: &[u8], password: &str) -> Result<Vec<u8>> {
-    // XOR解密（与加密相同）
-    encrypt_data(data, password)
+/// 解密旧版本的 XOR 加密备份
+/// 该格式没有文件头和校验，以解密结果能否解析为 JSON 判断密码是否正确
+fn decrypt_legacy_data(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    if password.is_empty() {
+        return Err(anyhow::anyhow!("Invalid backup password"));
+    }
+    let decrypted: Vec<u8> = data
+        .iter()
+        .zip(password.as_bytes().iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+    serde_json::from_slice::<serde::de::IgnoredAny>(&decompress_data(&decrypted)?)
+        .map_err(|_| anyhow::anyhow!("Invalid backup password"))?;
+    Ok(decrypted)
 }
 
-/// 创建备份
+/// 解密备份数据，没有加密文件头的按旧版本 XOR 格式解密
+fn decrypt_backup(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    if is_encrypted_data(data) {
+        decrypt_data(data, password)
+    } else {
+        decrypt_legacy_data(data, password)
+    }
+}
+
+/// 读取备份文件，必要时解密
+/// 解密需要派生密钥，异步命令中应通过 spawn_blocking 调用
+fn read_backup_file(backup_info: &BackupInfo, password: Option<&str>) -> Result<Vec<u8>> {
+    let data = fs::read(&backup_info.file_path).context("Failed to read backup file")?;
+    if !backup_info.is_encrypted {
+        return Ok(data);
+    }
+
+    let password = password.ok_or_else(|| anyhow::anyhow!("Encrypted backup requires password"))?;
+    decrypt_backup(&data, password)
+}
+
+/// 创建备份，写入文件前取消则不会留下备份
 #[tauri::command]
-pub async fn create_backup(options: BackupOptions) -> Result<String, String> {
-    let backup_id = nanoid!();
-    let timestamp = Utc::now().timestamp();
+pub async fn create_backup(options: BackupOptions, task_id: Option<String>) -> CmdResult<String> {
+    let operation = register_operation(OperationKind::Backup, task_id);
+    let backup_data = collect_backup_data(&options).await?;
+    operation.checkpoint()?;
+    // 加密时需要派生密钥，放到阻塞线程执行
+    let backup_id =
+        tokio::task::spawn_blocking(move || write_backup(backup_data, &options, false)).await??;
+    Ok(backup_id)
+}
 
-    // 收集备份数据
+/// 按选项收集备份数据
+pub(crate) async fn collect_backup_data(options: &BackupOptions) -> Result<BackupData, String> {
     let mut backup_data = BackupData {
-        backup_id: backup_id.clone(),
-        backup_name: options.backup_name,
-        description: options.description,
+        backup_id: nanoid!(),
+        backup_name: options.backup_name.clone(),
+        description: options.description.clone(),
         version: "1.0".to_string(),
         app_version: env!("CARGO_PKG_VERSION").to_string(),
-        created_at: timestamp,
+        created_at: Utc::now().timestamp(),
         file_size: 0,
         checksum: String::new(),
         is_encrypted: options.encrypt,
-        backup_type: options.backup_type,
+        backup_type: options.backup_type.clone(),
         profiles: Vec::new(),
         settings: SettingsBackup {
             clash_config: String::new(),
@@ -283,59 +451,72 @@ pub async fn create_backup(options: BackupOptions) -> Result<String, String> {
         groups: None,
         traffic_stats: None,
         tasks: None,
+        saved_searches: None,
+        base_backup_id: None,
+        manifest: BTreeMap::new(),
     };
 
     // 备份订阅数据
     if options.include_profiles {
-        // TODO: 从实际的配置文件读取订阅数据
-        // 这里使用模拟数据
-        backup_data.profiles = vec![ProfileBackup {
-            uid: "profile1".to_string(),
-            name: "示例订阅1".to_string(),
-            desc: Some("示例描述".to_string()),
-            file: None,
-            url: Some("https://example.com/sub1".to_string()),
-            selected: vec!["proxy1".to_string()],
-            chain: vec![],
-            valid: true,
-            updated: Some(timestamp as u64),
-            option: None,
-            home: None,
-            extra: None,
-        }];
+        backup_data.profiles = collect_profiles()
+            .await
+            .map_err(|e| format!("Failed to collect profiles: {}", e))?;
     }
 
     // 备份设置数据
     if options.include_settings {
-        // TODO: 从实际的配置文件读取设置
-        backup_data.settings = SettingsBackup {
-            clash_config: "clash_config_data".to_string(),
-            verge_config: "verge_config_data".to_string(),
-            profiles_config: "profiles_config_data".to_string(),
-        };
+        backup_data.settings =
+            collect_settings().map_err(|e| format!("Failed to collect settings: {}", e))?;
     }
 
     // 备份分组数据
     if options.include_groups {
         backup_data.groups = Some(GroupsBackup {
-            groups: "groups_data".to_string(),
+            groups: super::export_subscription_groups().await?,
         });
     }
 
     // 备份流量统计
     if options.include_traffic_stats {
+        let records = super::traffic_stats::export_traffic_records().await;
         backup_data.traffic_stats = Some(TrafficStatsBackup {
-            traffic_data: "traffic_stats_data".to_string(),
+            traffic_data: serde_json::to_string(&records)
+                .map_err(|e| format!("Failed to serialize traffic stats: {}", e))?,
         });
     }
 
     // 备份任务数据
     if options.include_tasks {
+        let tasks = super::task_manager::load_tasks_list()
+            .map_err(|e| format!("Failed to load tasks: {}", e))?;
         backup_data.tasks = Some(TasksBackup {
-            tasks_data: "tasks_data".to_string(),
+            tasks_data: serde_json::to_string(&tasks)
+                .map_err(|e| format!("Failed to serialize tasks: {}", e))?,
+        });
+    }
+
+    // 备份已保存的搜索
+    if options.include_saved_searches {
+        let searches = super::advanced_search::load_saved_searches()
+            .map_err(|e| format!("Failed to load saved searches: {}", e))?;
+        backup_data.saved_searches = Some(SavedSearchesBackup {
+            searches_data: serde_json::to_string(&searches)
+                .map_err(|e| format!("Failed to serialize saved searches: {}", e))?,
         });
     }
 
+    Ok(backup_data)
+}
+
+/// 写入备份文件并登记到索引，返回备份 ID
+pub(crate) fn write_backup(
+    backup_data: BackupData,
+    options: &BackupOptions,
+    scheduled: bool,
+) -> Result<String, String> {
+    let backup_id = backup_data.backup_id.clone();
+    let timestamp = backup_data.created_at;
+
     // 序列化备份数据
     let json_data = serde_json::to_string_pretty(&backup_data)
         .map_err(|e| format!("Failed to serialize backup data: {}", e))?;
@@ -350,11 +531,12 @@ pub async fn create_backup(options: BackupOptions) -> Result<String, String> {
 
     // 加密数据
     if options.encrypt {
-        if let Some(password) = &options.password {
-            data = encrypt_data(&data, password)
-                .map_err(|e| format!("Failed to encrypt backup: {}", e))?;
-        } else {
-            return Err("Password required for encryption".to_string());
+        match options.password.as_deref() {
+            Some(password) if !password.is_empty() => {
+                data = encrypt_data(&data, password)
+                    .map_err(|e| format!("Failed to encrypt backup: {}", e))?;
+            }
+            _ => return Err("Password required for encryption".to_string()),
         }
     }
 
@@ -390,6 +572,8 @@ pub async fn create_backup(options: BackupOptions) -> Result<String, String> {
         is_encrypted: backup_data.is_encrypted,
         checksum,
         is_valid: true,
+        scheduled,
+        base_backup_id: backup_data.base_backup_id,
     };
 
     // 保存备份索引
@@ -400,45 +584,213 @@ pub async fn create_backup(options: BackupOptions) -> Result<String, String> {
 
 /// 获取所有备份
 #[tauri::command]
-pub async fn get_all_backups() -> Result<Vec<BackupInfo>, String> {
-    load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))
+pub async fn get_all_backups() -> CmdResult<Vec<BackupInfo>> {
+    load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e).into())
 }
 
 /// 获取备份详情
 #[tauri::command]
-pub async fn get_backup_details(backup_id: String) -> Result<BackupData, String> {
+pub async fn get_backup_details(
+    backup_id: String,
+    password: Option<String>,
+) -> CmdResult<BackupData> {
     let backups = load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))?;
 
     let backup_info = backups
         .iter()
         .find(|b| b.backup_id == backup_id)
+        .cloned()
         .ok_or("Backup not found")?;
 
-    // 读取备份文件
-    let file_data = fs::read(&backup_info.file_path)
-        .map_err(|e| format!("Failed to read backup file: {}", e))?;
-
-    let mut data = file_data;
+    // 解密时需要派生密钥，放到阻塞线程执行
+    let backup_data = tokio::task::spawn_blocking(move || {
+        let mut backup_data = read_backup_data(&backup_info, password.as_deref())?;
+        fill_from_base_chain(&mut backup_data, &backups, password.as_deref())?;
+        Ok::<_, String>(backup_data)
+    })
+    .await??;
+    Ok(backup_data)
+}
 
-    // 解密数据
-    if backup_info.is_encrypted {
-        return Err("Encrypted backup requires password".to_string());
-    }
+/// 读取并解析单个备份文件
+fn read_backup_data(
+    backup_info: &BackupInfo,
+    password: Option<&str>,
+) -> Result<BackupData, String> {
+    // 读取并解密备份文件
+    let mut data = read_backup_file(backup_info, password).map_err(|e| e.to_string())?;
 
     // 解压数据
     // 假设所有备份都是压缩的，实际应记录压缩状态
     data = decompress_data(&data).unwrap_or(data); // 如果解压失败，可能未压缩
 
     // 反序列化备份数据
-    let backup_data: BackupData =
-        serde_json::from_slice(&data).map_err(|e| format!("Failed to parse backup data: {}", e))?;
+    serde_json::from_slice(&data).map_err(|e| format!("Failed to parse backup data: {}", e))
+}
 
-    Ok(backup_data)
+/// 增量备份只保存变化的条目，沿基础备份链补齐清单中缺失的条目
+fn fill_from_base_chain(
+    backup_data: &mut BackupData,
+    backups: &[BackupInfo],
+    password: Option<&str>,
+) -> Result<(), String> {
+    let mut base_id = backup_data.base_backup_id.take();
+    let mut visited = HashSet::new();
+    while let Some(id) = base_id {
+        if !visited.insert(id.clone()) {
+            return Err("Backup chain contains a cycle".to_string());
+        }
+        let base_info = backups
+            .iter()
+            .find(|b| b.backup_id == id)
+            .ok_or_else(|| format!("Base backup {} not found", id))?;
+        let base = read_backup_data(base_info, password)?;
+        base_id = base.base_backup_id.clone();
+        merge_missing_items(backup_data, base);
+    }
+
+    let missing: Vec<_> = backup_data
+        .manifest
+        .keys()
+        .filter(|key| !manifest_has_item(backup_data, key))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Backup is missing items: {}", missing.join(", ")));
+    }
+    Ok(())
+}
+
+/// 用基础备份中的同一版本内容补齐缺失条目
+fn merge_missing_items(backup_data: &mut BackupData, base: BackupData) {
+    let needs = |data: &BackupData, key: &str| {
+        data.manifest.get(key).is_some_and(|digest| {
+            !manifest_has_item(data, key) && base.manifest.get(key) == Some(digest)
+        })
+    };
+
+    let mut profiles = Vec::new();
+    for profile in &base.profiles {
+        if needs(backup_data, &format!("profile:{}", profile.uid)) {
+            profiles.push(profile.clone());
+        }
+    }
+    backup_data.profiles.extend(profiles);
+    if needs(backup_data, "settings") {
+        backup_data.settings = base.settings.clone();
+    }
+    if needs(backup_data, "groups") {
+        backup_data.groups = base.groups.clone();
+    }
+    if needs(backup_data, "traffic_stats") {
+        backup_data.traffic_stats = base.traffic_stats.clone();
+    }
+    if needs(backup_data, "tasks") {
+        backup_data.tasks = base.tasks.clone();
+    }
+    if needs(backup_data, "saved_searches") {
+        backup_data.saved_searches = base.saved_searches.clone();
+    }
+}
+
+fn manifest_has_item(backup_data: &BackupData, key: &str) -> bool {
+    match key {
+        "settings" => !backup_data.settings.verge_config.is_empty(),
+        "groups" => backup_data.groups.is_some(),
+        "traffic_stats" => backup_data.traffic_stats.is_some(),
+        "tasks" => backup_data.tasks.is_some(),
+        "saved_searches" => backup_data.saved_searches.is_some(),
+        _ => key
+            .strip_prefix("profile:")
+            .is_some_and(|uid| backup_data.profiles.iter().any(|p| p.uid == uid)),
+    }
+}
+
+/// 计算备份内容清单，忽略导出时间等每次都会变化的字段
+pub(crate) fn backup_manifest(backup_data: &BackupData) -> Result<BTreeMap<String, String>> {
+    fn digest<T: Serialize>(value: &T) -> Result<String> {
+        Ok(hex::encode(Sha256::digest(serde_json::to_vec(value)?)))
+    }
+
+    let mut manifest = BTreeMap::new();
+    for profile in &backup_data.profiles {
+        manifest.insert(format!("profile:{}", profile.uid), digest(profile)?);
+    }
+    if !backup_data.settings.verge_config.is_empty() {
+        manifest.insert("settings".to_string(), digest(&backup_data.settings)?);
+    }
+    if let Some(groups) = &backup_data.groups {
+        let mut groups = serde_json::from_str::<serde_json::Value>(&groups.groups)?;
+        if let serde_json::Value::Object(groups) = &mut groups {
+            groups.remove("export_time");
+        }
+        manifest.insert("groups".to_string(), digest(&groups)?);
+    }
+    if let Some(traffic_stats) = &backup_data.traffic_stats {
+        manifest.insert("traffic_stats".to_string(), digest(traffic_stats)?);
+    }
+    if let Some(tasks) = &backup_data.tasks {
+        manifest.insert("tasks".to_string(), digest(tasks)?);
+    }
+    if let Some(saved_searches) = &backup_data.saved_searches {
+        manifest.insert("saved_searches".to_string(), digest(saved_searches)?);
+    }
+    Ok(manifest)
+}
+
+/// 移除与基础清单相同的条目，只保留变化的内容
+pub(crate) fn strip_unchanged_items(
+    backup_data: &mut BackupData,
+    base_manifest: &BTreeMap<String, String>,
+) {
+    let unchanged = |manifest: &BTreeMap<String, String>, key: &str| {
+        manifest.get(key).is_some() && manifest.get(key) == base_manifest.get(key)
+    };
+    let manifest = backup_data.manifest.clone();
+
+    backup_data
+        .profiles
+        .retain(|profile| !unchanged(&manifest, &format!("profile:{}", profile.uid)));
+    if unchanged(&manifest, "settings") {
+        backup_data.settings = SettingsBackup {
+            clash_config: String::new(),
+            verge_config: String::new(),
+            profiles_config: String::new(),
+        };
+    }
+    if unchanged(&manifest, "groups") {
+        backup_data.groups = None;
+    }
+    if unchanged(&manifest, "traffic_stats") {
+        backup_data.traffic_stats = None;
+    }
+    if unchanged(&manifest, "tasks") {
+        backup_data.tasks = None;
+    }
+    if unchanged(&manifest, "saved_searches") {
+        backup_data.saved_searches = None;
+    }
+}
+
+/// 保留备份所依赖的整条基础备份链
+pub(crate) fn retain_base_chains(backups: &[BackupInfo], keep: &mut HashSet<String>) {
+    let bases: HashMap<_, _> = backups
+        .iter()
+        .filter_map(|b| Some((b.backup_id.as_str(), b.base_backup_id.as_deref()?)))
+        .collect();
+    let mut pending: Vec<String> = keep.iter().cloned().collect();
+    while let Some(id) = pending.pop() {
+        if let Some(base) = bases.get(id.as_str())
+            && keep.insert(base.to_string())
+        {
+            pending.push(base.to_string());
+        }
+    }
 }
 
 /// 恢复备份
 #[tauri::command]
-pub async fn restore_backup(options: RestoreOptions) -> Result<RestoreResult, String> {
+pub async fn restore_backup(options: RestoreOptions) -> CmdResult<RestoreResult> {
     let start_time = std::time::Instant::now();
     let mut result = RestoreResult {
         success: false,
@@ -449,6 +801,8 @@ pub async fn restore_backup(options: RestoreOptions) -> Result<RestoreResult, St
         operation_duration_ms: 0,
         backup_created: None,
     };
+    let mut progress = ProgressReporter::new(OperationKind::Restore, 0);
+    progress.emit("preparing", 0, None);
 
     // 恢复前创建备份
     if options.create_backup_before_restore {
@@ -459,6 +813,7 @@ pub async fn restore_backup(options: RestoreOptions) -> Result<RestoreResult, St
             include_groups: true,
             include_traffic_stats: true,
             include_tasks: true,
+            include_saved_searches: true,
             encrypt: false,
             password: None,
             compression_level: 6,
@@ -466,7 +821,7 @@ pub async fn restore_backup(options: RestoreOptions) -> Result<RestoreResult, St
             description: "Automatic backup before restore".to_string(),
         };
 
-        match create_backup(backup_options).await {
+        match create_backup(backup_options, None).await {
             Ok(backup_id) => result.backup_created = Some(backup_id),
             Err(e) => result
                 .warnings
@@ -475,59 +830,253 @@ pub async fn restore_backup(options: RestoreOptions) -> Result<RestoreResult, St
     }
 
     // 获取备份详情
-    let backup_data = match get_backup_details(options.backup_id).await {
+    let backup_data = match get_backup_details(options.backup_id, options.password).await {
         Ok(data) => data,
         Err(e) => {
-            result
-                .errors
-                .push(format!("Failed to get backup details: {}", e));
+            let message = format!("Failed to get backup details: {}", e);
+            progress.fail(0, message.clone());
+            result.errors.push(message);
             result.operation_duration_ms = start_time.elapsed().as_millis() as u64;
             return Ok(result);
         }
     };
 
+    // 按需要恢复的部分统计进度
+    progress.set_total(
+        [
+            options.restore_profiles,
+            options.restore_settings && !backup_data.settings.verge_config.is_empty(),
+            options.restore_groups && backup_data.groups.is_some(),
+            options.restore_traffic_stats && backup_data.traffic_stats.is_some(),
+            options.restore_tasks && backup_data.tasks.is_some(),
+            options.restore_saved_searches && backup_data.saved_searches.is_some(),
+        ]
+        .into_iter()
+        .filter(|enabled| *enabled)
+        .count(),
+    );
+    let mut step = 0;
+
     // 恢复订阅
-    if options.restore_profiles && !backup_data.profiles.is_empty() {
-        // TODO: 实现实际的订阅恢复逻辑
-        result.restored_items += backup_data.profiles.len() as u32;
+    if options.restore_profiles {
+        for profile in &backup_data.profiles {
+            if options
+                .profile_uids
+                .as_ref()
+                .is_some_and(|uids| !uids.contains(&profile.uid))
+            {
+                continue;
+            }
+            match restore_profile(profile, options.merge_mode).await {
+                Ok(true) => result.restored_items += 1,
+                Ok(false) => result
+                    .warnings
+                    .push(format!("Skipped existing profile: {}", profile.name)),
+                Err(e) => {
+                    result.failed_items += 1;
+                    result
+                        .errors
+                        .push(format!("Failed to restore profile {}: {}", profile.name, e));
+                }
+            }
+        }
+        step += 1;
+        progress.emit("restoring", step, Some("profiles".to_string()));
     }
 
     // 恢复设置
-    if options.restore_settings {
-        // TODO: 实现实际的设置恢复逻辑
-        result.restored_items += 1;
+    if options.restore_settings && !backup_data.settings.verge_config.is_empty() {
+        record_restore(
+            &mut result,
+            "settings",
+            restore_settings(&backup_data.settings).await,
+        );
+        step += 1;
+        progress.emit("restoring", step, Some("settings".to_string()));
     }
 
     // 恢复分组
-    if options.restore_groups && backup_data.groups.is_some() {
-        // TODO: 实现实际的分组恢复逻辑
-        result.restored_items += 1;
+    if options.restore_groups
+        && let Some(groups) = &backup_data.groups
+    {
+        if !options.merge_mode {
+            super::subscription_groups::clear_subscription_groups().await;
+        }
+        record_restore(
+            &mut result,
+            "groups",
+            super::import_subscription_groups(groups.groups.clone())
+                .await
+                .map(|_| ())
+                .map_err(anyhow::Error::msg),
+        );
+        step += 1;
+        progress.emit("restoring", step, Some("groups".to_string()));
     }
 
     // 恢复流量统计
-    if options.restore_traffic_stats && backup_data.traffic_stats.is_some() {
-        // TODO: 实现实际的流量统计恢复逻辑
-        result.restored_items += 1;
+    if options.restore_traffic_stats
+        && let Some(traffic_stats) = &backup_data.traffic_stats
+    {
+        record_restore(
+            &mut result,
+            "traffic stats",
+            restore_traffic_stats(traffic_stats, options.merge_mode).await,
+        );
+        step += 1;
+        progress.emit("restoring", step, Some("traffic stats".to_string()));
     }
 
     // 恢复任务
-    if options.restore_tasks && backup_data.tasks.is_some() {
-        // TODO: 实现实际的任务恢复逻辑
-        result.restored_items += 1;
+    if options.restore_tasks
+        && let Some(tasks) = &backup_data.tasks
+    {
+        record_restore(
+            &mut result,
+            "tasks",
+            restore_tasks(tasks, options.merge_mode),
+        );
+        step += 1;
+        progress.emit("restoring", step, Some("tasks".to_string()));
+    }
+
+    // 恢复已保存的搜索
+    if options.restore_saved_searches
+        && let Some(searches) = &backup_data.saved_searches
+    {
+        record_restore(
+            &mut result,
+            "saved searches",
+            restore_saved_searches(searches, options.merge_mode),
+        );
+        step += 1;
+        progress.emit("restoring", step, Some("saved searches".to_string()));
     }
 
     result.success = result.errors.is_empty();
     result.operation_duration_ms = start_time.elapsed().as_millis() as u64;
+    progress.finish(Some(format!(
+        "Restored {} items, {} failed",
+        result.restored_items, result.failed_items
+    )));
 
     Ok(result)
 }
 
+/// 预览恢复备份时将新建或覆盖的本地数据
+#[tauri::command]
+pub async fn preview_restore(
+    backup_id: String,
+    password: Option<String>,
+) -> CmdResult<RestorePreview> {
+    let backup_data = get_backup_details(backup_id, password).await?;
+    let mut items = Vec::new();
+
+    let existing_profiles: Vec<String> = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        profiles
+            .items
+            .iter()
+            .flatten()
+            .filter_map(|item| item.uid.clone())
+            .collect()
+    };
+    for profile in &backup_data.profiles {
+        items.push(RestorePreviewItem {
+            category: BackupCategory::Profiles,
+            id: profile.uid.clone(),
+            name: profile.name.clone(),
+            action: action_for(existing_profiles.contains(&profile.uid)),
+        });
+    }
+
+    if !backup_data.settings.verge_config.is_empty() {
+        for name in ["clash.yaml", "verge.yaml"] {
+            items.push(RestorePreviewItem {
+                category: BackupCategory::Settings,
+                id: name.to_string(),
+                name: name.to_string(),
+                action: RestoreAction::Overwrite,
+            });
+        }
+    }
+
+    if backup_data.groups.is_some() {
+        let has_groups = !super::get_all_subscription_groups()
+            .await
+            .unwrap_or_default()
+            .is_empty();
+        items.push(RestorePreviewItem {
+            category: BackupCategory::Groups,
+            id: "groups".to_string(),
+            name: "Subscription groups".to_string(),
+            action: action_for(has_groups),
+        });
+    }
+
+    if backup_data.traffic_stats.is_some() {
+        let has_records = !super::traffic_stats::export_traffic_records()
+            .await
+            .is_empty();
+        items.push(RestorePreviewItem {
+            category: BackupCategory::TrafficStats,
+            id: "traffic_stats".to_string(),
+            name: "Traffic statistics".to_string(),
+            action: action_for(has_records),
+        });
+    }
+
+    if let Some(tasks) = &backup_data.tasks {
+        let backup_tasks: Vec<super::TaskConfig> = serde_json::from_str(&tasks.tasks_data)
+            .map_err(|e| format!("Failed to parse tasks: {}", e))?;
+        let local_tasks = super::task_manager::load_tasks_list().unwrap_or_default();
+        for task in backup_tasks {
+            items.push(RestorePreviewItem {
+                category: BackupCategory::Tasks,
+                action: action_for(local_tasks.iter().any(|t| t.id == task.id)),
+                id: task.id,
+                name: task.name,
+            });
+        }
+    }
+
+    if let Some(searches) = &backup_data.saved_searches {
+        let backup_searches: Vec<super::SavedSearch> =
+            serde_json::from_str(&searches.searches_data)
+                .map_err(|e| format!("Failed to parse saved searches: {}", e))?;
+        let local_searches = super::advanced_search::load_saved_searches().unwrap_or_default();
+        for search in backup_searches {
+            items.push(RestorePreviewItem {
+                category: BackupCategory::SavedSearches,
+                action: action_for(local_searches.iter().any(|s| s.id == search.id)),
+                id: search.id,
+                name: search.name,
+            });
+        }
+    }
+
+    Ok(RestorePreview {
+        backup_id: backup_data.backup_id,
+        backup_name: backup_data.backup_name,
+        created_at: backup_data.created_at,
+        items,
+    })
+}
+
 /// 删除备份
 #[tauri::command]
-pub async fn delete_backup(backup_id: String) -> Result<(), String> {
+pub async fn delete_backup(backup_id: String) -> CmdResult<()> {
     let mut backups =
         load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))?;
 
+    if backups
+        .iter()
+        .any(|b| b.base_backup_id.as_deref() == Some(backup_id.as_str()))
+    {
+        return Err("Backup is the base of other incremental backups".into());
+    }
+
     if let Some(pos) = backups.iter().position(|b| b.backup_id == backup_id) {
         let backup_info = backups.remove(pos);
 
@@ -543,13 +1092,14 @@ pub async fn delete_backup(backup_id: String) -> Result<(), String> {
 
         Ok(())
     } else {
-        Err("Backup not found".to_string())
+        Err("Backup not found".into())
     }
 }
 
 /// 验证备份
+/// 对加密备份提供密码时，同时校验密码是否正确
 #[tauri::command]
-pub async fn validate_backup(backup_id: String) -> Result<bool, String> {
+pub async fn validate_backup(backup_id: String, password: Option<String>) -> CmdResult<bool> {
     let backups = load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))?;
 
     let backup_info = backups
@@ -565,13 +1115,47 @@ pub async fn validate_backup(backup_id: String) -> Result<bool, String> {
     // 验证校验和
     let current_checksum = calculate_checksum(Path::new(&backup_info.file_path))
         .map_err(|e| format!("Failed to calculate checksum: {}", e))?;
+    if current_checksum != backup_info.checksum {
+        return Ok(false);
+    }
 
-    Ok(current_checksum == backup_info.checksum)
+    // 验证密码
+    if backup_info.is_encrypted
+        && let Some(password) = password
+    {
+        let backup_info = backup_info.clone();
+        tokio::task::spawn_blocking(move || read_backup_file(&backup_info, Some(&password)))
+            .await??;
+    }
+
+    Ok(true)
+}
+
+/// 轮换备份密码，使用新密码重新加密已有的加密备份
+/// 返回重新加密的备份数量
+#[tauri::command]
+pub async fn rotate_backup_password(
+    old_password: String,
+    new_password: String,
+    backup_ids: Option<Vec<String>>,
+) -> CmdResult<u32> {
+    if new_password.is_empty() {
+        return Err("New password must not be empty".into());
+    }
+
+    // 每个备份都要派生密钥，放到阻塞线程执行
+    let rotated = tokio::task::spawn_blocking(move || {
+        rotate_backups(&old_password, &new_password, backup_ids.as_deref())
+    })
+    .await??;
+
+    log::info!(target: "app", "Rotated password for {} backups", rotated);
+    Ok(rotated)
 }
 
 /// 导出备份
 #[tauri::command]
-pub async fn export_backup(backup_id: String, export_path: String) -> Result<(), String> {
+pub async fn export_backup(backup_id: String, export_path: String) -> CmdResult<()> {
     let backups = load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))?;
 
     let backup_info = backups
@@ -579,6 +1163,18 @@ pub async fn export_backup(backup_id: String, export_path: String) -> Result<(),
         .find(|b| b.backup_id == backup_id)
         .ok_or("Backup not found")?;
 
+    // 增量备份导出时合并基础备份，保证导出文件可以独立导入
+    if backup_info.base_backup_id.is_some() && !backup_info.is_encrypted {
+        let mut backup_data = read_backup_data(backup_info, None)?;
+        fill_from_base_chain(&mut backup_data, &backups, None)?;
+        let json_data = serde_json::to_vec_pretty(&backup_data)
+            .map_err(|e| format!("Failed to serialize backup data: {}", e))?;
+        let data = compress_data(&json_data, 6)
+            .map_err(|e| format!("Failed to compress backup: {}", e))?;
+        fs::write(&export_path, data).map_err(|e| format!("Failed to export backup: {}", e))?;
+        return Ok(());
+    }
+
     // 复制备份文件
     fs::copy(&backup_info.file_path, &export_path)
         .map_err(|e| format!("Failed to export backup: {}", e))?;
@@ -588,7 +1184,7 @@ pub async fn export_backup(backup_id: String, export_path: String) -> Result<(),
 
 /// 导入备份
 #[tauri::command]
-pub async fn import_backup(import_path: String, backup_name: String) -> Result<String, String> {
+pub async fn import_backup(import_path: String, backup_name: String) -> CmdResult<String> {
     let backup_id = nanoid!();
 
     // 读取导入文件
@@ -622,9 +1218,11 @@ pub async fn import_backup(import_path: String, backup_name: String) -> Result<S
         version: "1.0".to_string(),
         app_version: "imported".to_string(),
         backup_type: BackupType::Full,
-        is_encrypted: false, // 假设导入的备份未加密
+        is_encrypted: is_encrypted_data(&data),
         checksum,
         is_valid: true,
+        scheduled: false,
+        base_backup_id: None,
     };
 
     // 保存到索引
@@ -635,14 +1233,14 @@ pub async fn import_backup(import_path: String, backup_name: String) -> Result<S
 
 /// WebDAV 同步配置
 #[tauri::command]
-pub async fn set_webdav_config(_config: WebDAVConfig) -> Result<(), String> {
+pub async fn set_webdav_config(_config: WebDAVConfig) -> CmdResult<()> {
     // TODO: 实现WebDAV配置保存
     Ok(())
 }
 
 /// 获取WebDAV配置
 #[tauri::command]
-pub async fn get_webdav_config() -> Result<WebDAVConfig, String> {
+pub async fn get_webdav_config() -> CmdResult<WebDAVConfig> {
     // TODO: 实现WebDAV配置读取
     Ok(WebDAVConfig {
         enabled: false,
@@ -659,7 +1257,7 @@ pub async fn get_webdav_config() -> Result<WebDAVConfig, String> {
 
 /// 同步到WebDAV
 #[tauri::command]
-pub async fn sync_to_webdav() -> Result<SyncStatus, String> {
+pub async fn sync_to_webdav() -> CmdResult<SyncStatus> {
     // TODO: 实现WebDAV上传同步
     Ok(SyncStatus {
         last_sync: Some(Utc::now().timestamp()),
@@ -674,7 +1272,7 @@ pub async fn sync_to_webdav() -> Result<SyncStatus, String> {
 
 /// 从WebDAV同步
 #[tauri::command]
-pub async fn sync_from_webdav() -> Result<SyncStatus, String> {
+pub async fn sync_from_webdav() -> CmdResult<SyncStatus> {
     // TODO: 实现WebDAV下载同步
     Ok(SyncStatus {
         last_sync: Some(Utc::now().timestamp()),
@@ -689,7 +1287,7 @@ pub async fn sync_from_webdav() -> Result<SyncStatus, String> {
 
 /// 获取同步状态
 #[tauri::command]
-pub async fn get_sync_status() -> Result<SyncStatus, String> {
+pub async fn get_sync_status() -> CmdResult<SyncStatus> {
     // TODO: 实现同步状态获取
     Ok(SyncStatus {
         last_sync: None,
@@ -704,18 +1302,26 @@ pub async fn get_sync_status() -> Result<SyncStatus, String> {
 
 /// 清理旧备份
 #[tauri::command]
-pub async fn cleanup_old_backups(keep_days: u32, keep_count: u32) -> Result<u32, String> {
+pub async fn cleanup_old_backups(keep_days: u32, keep_count: u32) -> CmdResult<u32> {
     let backups = load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))?;
 
     let cutoff_time = Utc::now().timestamp() - (keep_days as i64 * 24 * 3600);
     let mut sorted_backups = backups.clone();
     sorted_backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
 
+    let mut keep: HashSet<String> = sorted_backups
+        .iter()
+        .enumerate()
+        .filter(|(index, backup)| *index < keep_count as usize || backup.created_at > cutoff_time)
+        .map(|(_, backup)| backup.backup_id.clone())
+        .collect();
+    retain_base_chains(&sorted_backups, &mut keep);
+
     let mut deleted_count = 0;
     let mut remaining_backups = Vec::new();
 
-    for (index, backup) in sorted_backups.iter().enumerate() {
-        if index < keep_count as usize || backup.created_at > cutoff_time {
+    for backup in &sorted_backups {
+        if keep.contains(&backup.backup_id) {
             remaining_backups.push(backup.clone());
         } else {
             // 删除文件
@@ -733,6 +1339,255 @@ pub async fn cleanup_old_backups(keep_days: u32, keep_count: u32) -> Result<u32,
     Ok(deleted_count)
 }
 
+/// 收集订阅及其文件内容
+async fn collect_profiles() -> Result<Vec<ProfileBackup>> {
+    let items = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        profiles.items.clone().unwrap_or_default()
+    };
+
+    let mut backups = Vec::new();
+    for item in items {
+        if !matches!(item.itype.as_deref(), Some("remote" | "local")) {
+            continue;
+        }
+        let Some(uid) = item.uid.clone() else {
+            continue;
+        };
+
+        backups.push(ProfileBackup {
+            name: item.name.clone().unwrap_or_else(|| uid.clone()),
+            uid,
+            desc: item.desc.clone(),
+            file: item.file.clone(),
+            url: item.url.clone(),
+            selected: item
+                .selected
+                .iter()
+                .flatten()
+                .filter_map(|s| Some(format!("{}={}", s.name.as_ref()?, s.now.as_ref()?)))
+                .collect(),
+            chain: Vec::new(),
+            valid: true,
+            updated: item.updated.map(|u| u as u64),
+            option: item
+                .option
+                .as_ref()
+                .and_then(|o| serde_json::to_string(o).ok()),
+            home: item.home.clone(),
+            extra: item
+                .extra
+                .as_ref()
+                .and_then(|e| serde_json::to_string(e).ok()),
+            itype: item.itype.clone(),
+            content: item.read_file().ok(),
+        });
+    }
+
+    Ok(backups)
+}
+
+/// 读取配置文件原文
+fn collect_settings() -> Result<SettingsBackup> {
+    Ok(SettingsBackup {
+        clash_config: fs::read_to_string(dirs::clash_path()?)?,
+        verge_config: fs::read_to_string(dirs::verge_path()?)?,
+        profiles_config: fs::read_to_string(dirs::profiles_path()?)?,
+    })
+}
+
+/// 恢复单个订阅，合并模式下跳过已存在的订阅
+/// 返回是否实际写入
+async fn restore_profile(profile: &ProfileBackup, merge_mode: bool) -> Result<bool> {
+    let content = profile
+        .content
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Backup does not contain profile content"))?;
+    let existing = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        profiles.get_item(&profile.uid).ok().cloned()
+    };
+
+    match existing {
+        Some(_) if merge_mode => Ok(false),
+        Some(item) => {
+            item.save_file(content)?;
+            let patch = PrfItem {
+                name: Some(profile.name.clone()),
+                desc: profile.desc.clone(),
+                url: profile.url.clone(),
+                ..PrfItem::default()
+            };
+            profiles_patch_item_safe(profile.uid.clone(), patch).await?;
+            Ok(true)
+        }
+        None => {
+            let itype = profile.itype.clone().unwrap_or_else(|| {
+                if profile.url.is_some() {
+                    "remote".to_string()
+                } else {
+                    "local".to_string()
+                }
+            });
+            let item = PrfItem {
+                uid: Some(profile.uid.clone()),
+                itype: Some(itype),
+                name: Some(profile.name.clone()),
+                file: Some(
+                    profile
+                        .file
+                        .clone()
+                        .unwrap_or_else(|| format!("{}.yaml", profile.uid)),
+                ),
+                desc: profile.desc.clone(),
+                url: profile.url.clone(),
+                updated: profile.updated.map(|u| u as usize),
+                option: profile
+                    .option
+                    .as_deref()
+                    .and_then(|o| serde_json::from_str(o).ok()),
+                extra: profile
+                    .extra
+                    .as_deref()
+                    .and_then(|e| serde_json::from_str(e).ok()),
+                home: profile.home.clone(),
+                file_data: Some(content),
+                ..PrfItem::default()
+            };
+            profiles_append_item_safe(item).await?;
+            Ok(true)
+        }
+    }
+}
+
+/// 恢复 Clash 与 Verge 设置
+/// 订阅列表由订阅恢复负责，这里不覆盖 profiles.yaml
+async fn restore_settings(settings: &SettingsBackup) -> Result<()> {
+    if !settings.clash_config.is_empty() {
+        let clash = serde_yaml_ng::from_str(&settings.clash_config)
+            .context("Failed to parse clash config")?;
+        feat::patch_clash(clash).await?;
+    }
+
+    let verge: IVerge =
+        serde_yaml_ng::from_str(&settings.verge_config).context("Failed to parse verge config")?;
+    feat::patch_verge(verge, false).await
+}
+
+/// 恢复流量统计，覆盖模式下先清空本地记录
+async fn restore_traffic_stats(traffic_stats: &TrafficStatsBackup, merge_mode: bool) -> Result<()> {
+    let records = serde_json::from_str(&traffic_stats.traffic_data)?;
+    if !merge_mode {
+        super::traffic_stats::clear_traffic_records().await;
+    }
+    super::traffic_stats::import_traffic_records(records).await
+}
+
+/// 恢复任务，合并模式下保留本地已有的同 ID 任务
+fn restore_tasks(tasks: &TasksBackup, merge_mode: bool) -> Result<()> {
+    let backup_tasks: Vec<super::TaskConfig> = serde_json::from_str(&tasks.tasks_data)?;
+    let mut local_tasks = if merge_mode {
+        super::task_manager::load_tasks_list().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    for task in backup_tasks {
+        if !local_tasks.iter().any(|t| t.id == task.id) {
+            local_tasks.push(task);
+        }
+    }
+    super::task_manager::save_tasks_list(&local_tasks)
+}
+
+/// 恢复已保存的搜索，合并模式下保留本地已有的同 ID 搜索
+fn restore_saved_searches(searches: &SavedSearchesBackup, merge_mode: bool) -> Result<()> {
+    let backup_searches: Vec<super::SavedSearch> = serde_json::from_str(&searches.searches_data)?;
+    let mut local_searches = if merge_mode {
+        super::advanced_search::load_saved_searches().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    for search in backup_searches {
+        if !local_searches.iter().any(|s| s.id == search.id) {
+            local_searches.push(search);
+        }
+    }
+    super::advanced_search::save_saved_searches(&local_searches)
+}
+
+/// 记录单项恢复结果
+fn record_restore(result: &mut RestoreResult, item: &str, outcome: Result<()>) {
+    match outcome {
+        Ok(()) => result.restored_items += 1,
+        Err(e) => {
+            result.failed_items += 1;
+            result
+                .errors
+                .push(format!("Failed to restore {}: {}", item, e));
+        }
+    }
+}
+
+/// 根据本地是否已存在判断恢复动作
+fn action_for(exists: bool) -> RestoreAction {
+    if exists {
+        RestoreAction::Overwrite
+    } else {
+        RestoreAction::Create
+    }
+}
+
+/// 使用新密码重新加密备份，旧版本 XOR 格式的备份同时迁移为新格式
+fn rotate_backups(
+    old_password: &str,
+    new_password: &str,
+    backup_ids: Option<&[String]>,
+) -> Result<u32, String> {
+    let mut backups =
+        load_backup_index().map_err(|e| format!("Failed to load backup index: {}", e))?;
+
+    // 先全部解密，任一失败则不做任何修改
+    let mut decrypted = Vec::new();
+    for (index, backup) in backups.iter().enumerate() {
+        if !backup.is_encrypted || backup_ids.is_some_and(|ids| !ids.contains(&backup.backup_id)) {
+            continue;
+        }
+        let data = read_backup_file(backup, Some(old_password))
+            .map_err(|e| format!("Failed to decrypt backup {}: {}", backup.backup_name, e))?;
+        decrypted.push((index, data));
+    }
+
+    let mut rotated = 0;
+    for (index, data) in decrypted {
+        let backup = &mut backups[index];
+        let encrypted = encrypt_data(&data, new_password)
+            .map_err(|e| format!("Failed to encrypt backup: {}", e))?;
+
+        // 先写临时文件再替换，避免中途失败损坏备份
+        let file_path = PathBuf::from(&backup.file_path);
+        let temp_path = file_path.with_extension("bak.tmp");
+        fs::write(&temp_path, &encrypted)
+            .map_err(|e| format!("Failed to write backup file: {}", e))?;
+        fs::rename(&temp_path, &file_path)
+            .map_err(|e| format!("Failed to replace backup file: {}", e))?;
+
+        backup.file_size = encrypted.len() as u64;
+        backup.checksum = calculate_checksum(&file_path)
+            .map_err(|e| format!("Failed to calculate checksum: {}", e))?;
+        rotated += 1;
+
+        // 每个文件替换后立即更新索引，保持校验和一致
+        save_backup_index_list(&backups)
+            .map_err(|e| format!("Failed to update backup index: {}", e))?;
+    }
+
+    Ok(rotated)
+}
+
 /// 保存备份索引
 fn save_backup_index(backup_info: &BackupInfo) -> Result<()> {
     let mut backups = load_backup_index().unwrap_or_default();
@@ -765,3 +1620,42 @@ fn load_backup_index() -> Result<Vec<BackupInfo>> {
 
     Ok(backups)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &[u8] = br#"{"backup_id":"legacy","profiles":[]}"#;
+
+    /// 旧版本的加密方式：逐字节与密码循环异或
+    fn legacy_encrypt(data: &[u8], password: &str) -> Vec<u8> {
+        let key = password.as_bytes();
+        data.iter()
+            .enumerate()
+            .map(|(i, byte)| byte ^ key[i % key.len()])
+            .collect()
+    }
+
+    #[test]
+    fn decrypts_legacy_xor_backup() {
+        let encrypted = legacy_encrypt(PAYLOAD, "old-password");
+        assert!(!is_encrypted_data(&encrypted));
+        assert_eq!(decrypt_backup(&encrypted, "old-password").unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn rejects_legacy_backup_with_wrong_password() {
+        let encrypted = legacy_encrypt(PAYLOAD, "old-password");
+        let err = decrypt_backup(&encrypted, "wrong").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid backup password");
+        assert!(decrypt_backup(&encrypted, "").is_err());
+    }
+
+    #[test]
+    fn decrypts_current_format_backup() {
+        let encrypted = encrypt_data(PAYLOAD, "password").unwrap();
+        assert!(is_encrypted_data(&encrypted));
+        assert_eq!(decrypt_backup(&encrypted, "password").unwrap(), PAYLOAD);
+        assert!(decrypt_backup(&encrypted, "wrong").is_err());
+    }
+}
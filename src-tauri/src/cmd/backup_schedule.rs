@@ -0,0 +1,364 @@
+use super::{
+    CmdError, CmdResult,
+    backup_restore::{
+        BackupCategory, BackupOptions, BackupType, backup_manifest, collect_backup_data,
+        retain_base_chains, strip_unchanged_items, write_backup,
+    },
+    job_manager::{JobClass, JobKind, JobPriority, run_job, submit_job},
+};
+use crate::{
+    core::{backup::BackupProvider, handle},
+    feat, logging,
+    process::AsyncHandler,
+    utils::{
+        dirs,
+        logging::Type,
+        notification::{NotificationEvent, notify_event},
+    },
+};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+/// 调度检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(300);
+/// 连续增量备份的最大次数，达到后重新创建完整备份
+const MAX_INCREMENTAL_CHAIN: u32 = 6;
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+/// 避免定时备份与手动触发同时执行
+static BACKUP_RUNNING: Mutex<()> = Mutex::const_new(());
+
+/// 定时备份计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub keep_daily: u32,  // 保留最近 N 天，每天一份
+    pub keep_weekly: u32, // 保留最近 M 周，每周一份
+    pub categories: Vec<BackupCategory>,
+    pub upload_to_remote: bool, // 同时上传到远程备份存储
+}
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            keep_daily: 7,
+            keep_weekly: 4,
+            categories: vec![
+                BackupCategory::Profiles,
+                BackupCategory::Settings,
+                BackupCategory::Groups,
+                BackupCategory::SavedSearches,
+                BackupCategory::Tasks,
+            ],
+            upload_to_remote: false,
+        }
+    }
+}
+
+/// 定时备份计划及运行状态
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BackupScheduleState {
+    pub schedule: BackupSchedule,
+    pub last_run: Option<i64>,
+    pub last_backup_id: Option<String>,
+    pub last_error: Option<String>,
+    #[serde(default)]
+    last_manifest: BTreeMap<String, String>, // 上次备份的内容清单，用于跳过未变化的备份并计算增量
+    #[serde(default)]
+    incremental_count: u32, // 自上次完整备份以来的增量备份次数
+}
+
+/// 设置定时备份计划
+#[tauri::command]
+pub async fn set_backup_schedule(schedule: BackupSchedule) -> CmdResult<()> {
+    if schedule.interval_hours == 0 {
+        return Err("备份间隔必须大于 0".into());
+    }
+    logging!(
+        info,
+        Type::Backup,
+        true,
+        "[定时备份] 更新计划: enabled={}, 间隔 {} 小时, 保留 {} 天 / {} 周",
+        schedule.enabled,
+        schedule.interval_hours,
+        schedule.keep_daily,
+        schedule.keep_weekly
+    );
+
+    let _guard = BACKUP_RUNNING.lock().await;
+    let mut state = load_state().unwrap_or_default();
+    state.schedule = schedule;
+    save_state(&state)
+        .context("保存定时备份计划失败")
+        .map_err(CmdError::from)
+}
+
+/// 获取定时备份计划及运行状态
+#[tauri::command]
+pub async fn get_backup_schedule() -> CmdResult<BackupScheduleState> {
+    load_state()
+        .context("加载定时备份计划失败")
+        .map_err(CmdError::from)
+}
+
+/// 立即执行一次定时备份
+#[tauri::command]
+pub async fn run_scheduled_backup_now() -> CmdResult<Option<String>> {
+    let previous = load_state().ok().and_then(|state| state.last_backup_id);
+    run_job(
+        "Scheduled backup",
+        JobKind::ScheduledBackup,
+        JobClass::Disk,
+        JobPriority::High,
+    )
+    .await?;
+
+    // 内容未变化时不会生成新备份
+    let latest = load_state().ok().and_then(|state| state.last_backup_id);
+    Ok(latest.filter(|id| previous.as_ref() != Some(id)))
+}
+
+/// 启动定时备份调度器
+pub fn init_backup_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+
+            let state = load_state().unwrap_or_default();
+            let due = state.schedule.enabled
+                && state.last_run.is_none_or(|last| {
+                    chrono::Utc::now().timestamp() - last
+                        >= i64::from(state.schedule.interval_hours) * 3600
+                });
+            if !due {
+                continue;
+            }
+
+            submit_job(
+                "Scheduled backup",
+                JobKind::ScheduledBackup,
+                JobClass::Disk,
+                JobPriority::Low,
+            );
+        }
+    });
+}
+
+/// 由后台任务队列调用，失败时发送通知
+pub(crate) async fn run_backup_job() -> Result<String, String> {
+    match run_scheduled_backup().await {
+        Ok(Some(backup_id)) => Ok(format!("已创建备份 {}", backup_id)),
+        Ok(None) => Ok("内容未变化，已跳过".to_string()),
+        Err(e) => {
+            logging!(error, Type::Backup, true, "[定时备份] 执行失败: {}", e);
+            if let Some(app_handle) = handle::Handle::global().app_handle() {
+                notify_event(
+                    app_handle,
+                    NotificationEvent::ScheduledBackupFailed {
+                        error: &e.to_string(),
+                    },
+                )
+                .await;
+            }
+            Err(format!("定时备份失败: {}", e))
+        }
+    }
+}
+
+/// 定时备份被取消后推迟到下一个周期
+pub(crate) async fn postpone_backup() -> Result<()> {
+    let _guard = BACKUP_RUNNING.lock().await;
+    let mut state = load_state().unwrap_or_default();
+    state.last_run = Some(chrono::Utc::now().timestamp());
+    save_state(&state)
+}
+
+// ===== 内部实现函数 =====
+
+/// 执行定时备份，内容未变化时跳过，返回新建的备份 ID
+async fn run_scheduled_backup() -> Result<Option<String>> {
+    let _guard = BACKUP_RUNNING.lock().await;
+    let mut state = load_state().unwrap_or_default();
+    let result = backup_if_changed(&mut state).await;
+
+    state.last_run = Some(chrono::Utc::now().timestamp());
+    state.last_error = result.as_ref().err().map(|e| e.to_string());
+    save_state(&state)?;
+    result
+}
+
+async fn backup_if_changed(state: &mut BackupScheduleState) -> Result<Option<String>> {
+    let schedule = state.schedule.clone();
+    let options = BackupOptions {
+        backup_type: BackupType::Custom,
+        include_profiles: schedule.categories.contains(&BackupCategory::Profiles),
+        include_settings: schedule.categories.contains(&BackupCategory::Settings),
+        include_groups: schedule.categories.contains(&BackupCategory::Groups),
+        include_traffic_stats: schedule.categories.contains(&BackupCategory::TrafficStats),
+        include_tasks: schedule.categories.contains(&BackupCategory::Tasks),
+        include_saved_searches: schedule.categories.contains(&BackupCategory::SavedSearches),
+        encrypt: false,
+        password: None,
+        compression_level: 6,
+        backup_name: "Scheduled backup".to_string(),
+        description: "Automatic scheduled backup".to_string(),
+    };
+
+    let mut data = collect_backup_data(&options)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    data.manifest = backup_manifest(&data)?;
+    if data.manifest == state.last_manifest {
+        logging!(info, Type::Backup, true, "[定时备份] 内容未变化，跳过");
+        return Ok(None);
+    }
+
+    // 上次备份仍存在时只保存变化的条目，链过长时重新创建完整备份
+    let base = match state.last_backup_id.clone() {
+        Some(id) if state.incremental_count < MAX_INCREMENTAL_CHAIN => super::get_all_backups()
+            .await
+            .map_err(anyhow::Error::msg)?
+            .into_iter()
+            .any(|b| b.backup_id == id)
+            .then_some(id),
+        _ => None,
+    };
+    let incremental = base.is_some();
+    if let Some(base) = base {
+        strip_unchanged_items(&mut data, &state.last_manifest);
+        data.base_backup_id = Some(base);
+    }
+
+    let manifest = data.manifest.clone();
+    let backup_id = write_backup(data, &options, true).map_err(anyhow::Error::msg)?;
+    state.last_manifest = manifest;
+    state.last_backup_id = Some(backup_id.clone());
+    state.incremental_count = if incremental {
+        state.incremental_count + 1
+    } else {
+        0
+    };
+    logging!(
+        info,
+        Type::Backup,
+        true,
+        "[定时备份] 已创建{}备份 {}",
+        if incremental { "增量" } else { "完整" },
+        backup_id
+    );
+
+    let removed = apply_retention(schedule.keep_daily, schedule.keep_weekly).await?;
+    if removed > 0 {
+        logging!(
+            info,
+            Type::Backup,
+            true,
+            "[定时备份] 按保留策略清理 {} 个备份",
+            removed
+        );
+    }
+
+    // 远程备份只包含订阅与设置文件，按计划选择的类别打包
+    let upload_profiles = schedule.categories.contains(&BackupCategory::Profiles);
+    let upload_settings = schedule.categories.contains(&BackupCategory::Settings);
+    if schedule.upload_to_remote && (upload_profiles || upload_settings) {
+        let storage = BackupProvider::current().await.storage();
+        feat::create_backup_and_upload_with(storage, upload_profiles, upload_settings).await?;
+    }
+
+    Ok(Some(backup_id))
+}
+
+/// 按保留策略清理定时备份：每天保留最新一份共 N 天，每周保留最新一份共 M 周
+/// 最新的一份始终保留，保留的增量备份所依赖的基础备份同样保留，返回删除数量
+async fn apply_retention(keep_daily: u32, keep_weekly: u32) -> Result<u32> {
+    let all_backups = super::get_all_backups().await.map_err(anyhow::Error::msg)?;
+    let mut backups: Vec<_> = all_backups
+        .iter()
+        .filter(|b| b.scheduled)
+        .cloned()
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+    let mut keep = HashSet::new();
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    for (index, backup) in backups.iter().enumerate() {
+        let Some(time) = Local.timestamp_opt(backup.created_at, 0).single() else {
+            continue;
+        };
+        let week = time.iso_week();
+        let new_day = days.len() < keep_daily as usize && days.insert(time.date_naive());
+        let new_week =
+            weeks.len() < keep_weekly as usize && weeks.insert((week.year(), week.week()));
+        if index == 0 || new_day || new_week {
+            keep.insert(backup.backup_id.clone());
+        }
+    }
+    retain_base_chains(&all_backups, &mut keep);
+
+    // 由新到旧删除，依赖某个基础备份的增量备份总是先于它被删除
+    let mut removed = 0;
+    for backup in backups {
+        if keep.contains(&backup.backup_id) {
+            continue;
+        }
+        match super::delete_backup(backup.backup_id.clone()).await {
+            Ok(()) => removed += 1,
+            Err(e) => {
+                logging!(
+                    warn,
+                    Type::Backup,
+                    true,
+                    "[定时备份] 删除过期备份 {} 失败: {}",
+                    backup.backup_id,
+                    e
+                );
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// 获取存储文件路径
+fn state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("backup_schedule.json"))
+}
+
+/// 加载定时备份状态
+fn load_state() -> Result<BackupScheduleState> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(BackupScheduleState::default());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存定时备份状态
+fn save_state(state: &BackupScheduleState) -> Result<()> {
+    let json_data = serde_json::to_string_pretty(state)?;
+    fs::write(state_path()?, json_data)?;
+    Ok(())
+}
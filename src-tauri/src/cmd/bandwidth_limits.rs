@@ -0,0 +1,319 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+use tokio::sync::Mutex;
+
+const BANDWIDTH_LIMITS_FILE: &str = "bandwidth_limits.yaml";
+
+/// 支持 up / down 带宽参数的节点类型
+const SHAPEABLE_TYPES: &[&str] = &["hysteria", "hysteria2"];
+
+static LIMITS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 代理组带宽限制，0 表示不限制
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BandwidthLimit {
+    pub up_kbps: u32,
+    pub down_kbps: u32,
+}
+
+impl BandwidthLimit {
+    const fn is_unlimited(self) -> bool {
+        self.up_kbps == 0 && self.down_kbps == 0
+    }
+
+    /// 节点同时属于多个受限分组时取更严格的限制
+    fn stricter(self, other: Self) -> Self {
+        let min = |a: u32, b: u32| match (a, b) {
+            (0, v) | (v, 0) => v,
+            (a, b) => a.min(b),
+        };
+        Self {
+            up_kbps: min(self.up_kbps, other.up_kbps),
+            down_kbps: min(self.down_kbps, other.down_kbps),
+        }
+    }
+}
+
+/// 设置结果：限制作用于分组内（含嵌套分组）支持带宽参数的节点
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupBandwidthResult {
+    pub group: String,
+    pub limit: BandwidthLimit,
+    pub applied: Vec<String>,     // 已应用限制的节点
+    pub unsupported: Vec<String>, // 协议不支持带宽参数的节点
+}
+
+/// 设置代理组的上传 / 下载带宽限制，两者均为 0 时移除限制
+/// mihomo 仅 Hysteria / Hysteria2 节点支持带宽参数，限制作用于分组内的每个节点
+#[tauri::command]
+pub async fn set_group_bandwidth_limit(
+    group: String,
+    up_kbps: u32,
+    down_kbps: u32,
+) -> CmdResult<GroupBandwidthResult> {
+    let group = group.trim().to_string();
+    let limit = BandwidthLimit { up_kbps, down_kbps };
+
+    let (applied, unsupported) = {
+        let runtime = Config::runtime().await;
+        let runtime = runtime.latest_ref();
+        let config = runtime.config.as_ref().ok_or("运行配置不存在")?;
+        if find_group(config, &group).is_none() {
+            return Err(CmdError::new(
+                ErrorCode::InvalidArgument,
+                format!("代理组不存在: {group}"),
+            ));
+        }
+        classify_members(config, &group)
+    };
+    if !limit.is_unlimited() && applied.is_empty() {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            format!("代理组 {group} 中没有支持带宽限制的节点（仅支持 Hysteria / Hysteria2）"),
+        ));
+    }
+
+    let _guard = LIMITS_LOCK.lock().await;
+    let previous = load_limits().context("加载带宽限制失败")?;
+    let mut limits = previous.clone();
+    if limit.is_unlimited() {
+        limits.remove(&group);
+    } else {
+        limits.insert(group.clone(), limit);
+    }
+    apply_limits(&limits, &previous).await?;
+
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[带宽限制] {} 上传 {} Kbps, 下载 {} Kbps, 应用于 {} 个节点",
+        group,
+        up_kbps,
+        down_kbps,
+        applied.len()
+    );
+    Ok(GroupBandwidthResult {
+        group,
+        limit,
+        applied,
+        unsupported,
+    })
+}
+
+/// 为配置中受限分组的节点写入 up / down，供增强链使用
+pub fn use_bandwidth_limits(mut config: Mapping) -> Mapping {
+    let limits = match load_limits() {
+        Ok(limits) if !limits.is_empty() => limits,
+        _ => return config,
+    };
+
+    let mut node_limits: HashMap<String, BandwidthLimit> = HashMap::new();
+    for (group, limit) in &limits {
+        if find_group(&config, group).is_none() {
+            continue;
+        }
+        for node in classify_members(&config, group).0 {
+            node_limits
+                .entry(node)
+                .and_modify(|existing| *existing = existing.stricter(*limit))
+                .or_insert(*limit);
+        }
+    }
+
+    let Some(Value::Sequence(proxies)) = config.get_mut("proxies") else {
+        return config;
+    };
+    for proxy in proxies.iter_mut().filter_map(Value::as_mapping_mut) {
+        let Some(limit) = proxy
+            .get("name")
+            .and_then(Value::as_str)
+            .and_then(|name| node_limits.get(name))
+            .copied()
+        else {
+            continue;
+        };
+        for (key, kbps) in [("up", limit.up_kbps), ("down", limit.down_kbps)] {
+            // 节点自带更低的带宽参数时保留原值
+            let existing = proxy.get(key).and_then(parse_kbps);
+            if kbps > 0 && existing.is_none_or(|existing| u64::from(kbps) < existing) {
+                proxy.insert(key.into(), format!("{kbps} Kbps").into());
+            }
+        }
+    }
+    config
+}
+
+/// 在 get_proxies 返回的分组信息中附加当前带宽限制
+pub fn annotate_bandwidth_limits(value: &mut serde_json::Value) {
+    let Ok(limits) = load_limits() else {
+        return;
+    };
+    let Some(proxies) = value.get_mut("proxies").and_then(|p| p.as_object_mut()) else {
+        return;
+    };
+    for (group, limit) in limits {
+        if let Some(info) = proxies.get_mut(&group).and_then(|g| g.as_object_mut()) {
+            info.insert(
+                "bandwidthLimit".into(),
+                serde_json::json!({ "up_kbps": limit.up_kbps, "down_kbps": limit.down_kbps }),
+            );
+        }
+    }
+}
+
+// ===== 内部实现函数 =====
+
+/// 解析节点的 up / down 参数并换算为 Kbps，纯数字按 Mbps 处理，单位中的大写 B 表示字节
+fn parse_kbps(value: &Value) -> Option<u64> {
+    let text = match value {
+        Value::Number(n) => return n.as_f64().map(|mbps| (mbps * 1000.0) as u64),
+        Value::String(s) => s.trim(),
+        _ => return None,
+    };
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let number: f64 = text[..split].parse().ok()?;
+    let unit = text[split..].trim();
+    let (prefix, rest) = match unit.chars().next() {
+        None => return Some((number * 1000.0) as u64),
+        Some(c) if c.eq_ignore_ascii_case(&'b') => ('b', unit),
+        Some(c) => (c.to_ascii_lowercase(), &unit[c.len_utf8()..]),
+    };
+    let scale = match prefix {
+        'b' => 0.001,
+        'k' => 1.0,
+        'm' => 1000.0,
+        'g' => 1_000_000.0,
+        't' => 1_000_000_000.0,
+        _ => return None,
+    };
+    let bytes = if rest.starts_with('B') { 8.0 } else { 1.0 };
+    Some((number * scale * bytes) as u64)
+}
+
+fn find_group<'a>(config: &'a Mapping, name: &str) -> Option<&'a Mapping> {
+    config
+        .get("proxy-groups")
+        .and_then(Value::as_sequence)?
+        .iter()
+        .filter_map(Value::as_mapping)
+        .find(|g| g.get("name").and_then(Value::as_str) == Some(name))
+}
+
+/// 递归展开分组成员，按是否支持带宽参数分类；订阅提供者中的节点无法改写，视为不支持
+fn classify_members(config: &Mapping, group: &str) -> (Vec<String>, Vec<String>) {
+    let node_types: HashMap<&str, &str> = config
+        .get("proxies")
+        .and_then(Value::as_sequence)
+        .map(|seq| {
+            seq.iter()
+                .filter_map(|p| {
+                    Some((
+                        p.get("name")?.as_str()?,
+                        p.get("type")?.as_str().unwrap_or_default(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut applied = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![group.to_string()];
+    while let Some(name) = pending.pop() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        let Some(info) = find_group(config, &name) else {
+            continue;
+        };
+        for member in info
+            .get("proxies")
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            match node_types.get(member) {
+                Some(node_type) if SHAPEABLE_TYPES.contains(&node_type.to_lowercase().as_str()) => {
+                    applied.push(member.to_string());
+                }
+                Some(_) => unsupported.push(member.to_string()),
+                None if find_group(config, member).is_some() => pending.push(member.to_string()),
+                None => {} // DIRECT / REJECT 等内置策略
+            }
+        }
+        for provider in info
+            .get("use")
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            unsupported.push(format!("provider:{provider}"));
+        }
+    }
+
+    for list in [&mut applied, &mut unsupported] {
+        list.sort();
+        list.dedup();
+    }
+    (applied, unsupported)
+}
+
+/// 写入限制并重新生成配置，内核验证失败时恢复原限制
+async fn apply_limits(
+    limits: &BTreeMap<String, BandwidthLimit>,
+    previous: &BTreeMap<String, BandwidthLimit>,
+) -> CmdResult<()> {
+    save_limits(limits).context("保存带宽限制失败")?;
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            save_limits(previous).context("恢复带宽限制失败")?;
+            Err(CmdError::new(
+                ErrorCode::ConfigValidationFailed,
+                format!("带宽限制未通过内核验证，已恢复: {error}"),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn limits_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(BANDWIDTH_LIMITS_FILE))
+}
+
+fn load_limits() -> Result<BTreeMap<String, BandwidthLimit>> {
+    let path = limits_path()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_yaml_ng::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_limits(limits: &BTreeMap<String, BandwidthLimit>) -> Result<()> {
+    fs::write(limits_path()?, serde_yaml_ng::to_string(limits)?)?;
+    Ok(())
+}
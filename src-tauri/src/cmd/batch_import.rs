@@ -1,9 +1,9 @@
-use super::CmdResult;
+use super::{CancelToken, CmdResult, OperationKind, ProgressReporter, register_operation};
 use crate::{
     config::{Config, PrfItem, PrfOption},
     core::handle::Handle,
     logging,
-    utils::logging::Type,
+    utils::{logging::Type, redact},
 };
 use nanoid::nanoid;
 use percent_encoding::percent_decode_str;
@@ -78,19 +78,22 @@ pub struct ImportProgressPayload {
     pub message: Option<String>,
 }
 
+/// 同时发送 batch-import-progress 与统一的 operation-progress 事件
 #[derive(Debug, Clone)]
 struct ProgressTracker {
     app_handle: AppHandle,
     task_id: u64,
     total: usize,
+    reporter: ProgressReporter,
 }
 
 impl ProgressTracker {
-    fn new(app_handle: AppHandle, task_id: u64, total: usize) -> Self {
+    fn new(app_handle: AppHandle, task_id: u64, total: usize, token: &CancelToken) -> Self {
         Self {
             app_handle,
             task_id,
             total,
+            reporter: ProgressReporter::for_operation(token, total),
         }
     }
 
@@ -102,6 +105,10 @@ impl ProgressTracker {
         message: Option<String>,
     ) {
         let total = total_override.unwrap_or(self.total);
+        let mut reporter = self.reporter.clone();
+        reporter.set_total(total);
+        reporter.emit(stage, completed, message.clone());
+
         let payload = ImportProgressPayload {
             task_id: self.task_id,
             stage: stage.to_string(),
@@ -116,15 +123,17 @@ impl ProgressTracker {
     }
 }
 
-/// 从文本批量导入订阅
+/// 从文本批量导入订阅，取消后返回已完成部分的结果
 #[tauri::command]
 pub async fn batch_import_from_text(
     app_handle: AppHandle,
     text_content: String,
     options: Option<BatchImportOptions>,
+    task_id: Option<String>,
 ) -> CmdResult<BatchImportResult> {
     let start_time = std::time::Instant::now();
     let options = options.unwrap_or_default();
+    let operation = register_operation(OperationKind::BatchImport, task_id);
 
     logging!(
         info,
@@ -176,7 +185,7 @@ pub async fn batch_import_from_text(
     );
 
     let task_id = IMPORT_TASK_SEQ.fetch_add(1, Ordering::SeqCst);
-    let tracker = ProgressTracker::new(app_handle.clone(), task_id, new_urls.len());
+    let tracker = ProgressTracker::new(app_handle.clone(), task_id, new_urls.len(), &operation);
 
     tracker.emit(
         "preparing",
@@ -195,7 +204,7 @@ pub async fn batch_import_from_text(
 
     // 执行导入
     let (success_results, failed_results) =
-        import_subscriptions(new_urls, &options, tracker.clone()).await;
+        import_subscriptions(new_urls, &options, tracker.clone(), &operation).await;
     let imported_count = success_results.len();
     let failed_count = failed_results.len();
 
@@ -218,6 +227,20 @@ pub async fn batch_import_from_text(
         import_duration,
     };
 
+    if operation.is_cancelled() {
+        tracker.emit(
+            "cancelled",
+            imported_count + failed_count,
+            Some(valid_count),
+            Some(format!(
+                "导入已取消，成功 {} 条，失败 {} 条",
+                imported_count, failed_count
+            )),
+        );
+        operation.emit_cancelled(Some(&result));
+        return Ok(result);
+    }
+
     tracker.emit(
         "completed",
         imported_count + failed_count,
@@ -249,6 +272,7 @@ pub async fn batch_import_from_text(
 pub async fn batch_import_from_file(
     file_path: String,
     options: Option<BatchImportOptions>,
+    task_id: Option<String>,
 ) -> CmdResult<BatchImportResult> {
     logging!(
         info,
@@ -266,7 +290,7 @@ pub async fn batch_import_from_file(
     // 调用文本导入逻辑
     // 使用全局 Handle 提供的 AppHandle，再复用文本导入逻辑
     if let Some(handle) = crate::core::handle::Handle::global().app_handle() {
-        batch_import_from_text(handle, content, options).await
+        batch_import_from_text(handle, content, options, task_id).await
     } else {
         Err("AppHandle not initialized".into())
     }
@@ -281,7 +305,7 @@ pub async fn batch_import_from_clipboard(
 
     // 这里需要前端传入剪贴板内容，因为后端无法直接访问剪贴板
     // 暂时返回错误，前端应该先获取剪贴板内容再调用 batch_import_from_text
-    Err("请先获取剪贴板内容，然后使用 batch_import_from_text".to_string())
+    Err("请先获取剪贴板内容，然后使用 batch_import_from_text".into())
 }
 
 /// 获取导入预览（不实际导入）
@@ -353,7 +377,7 @@ pub async fn preview_batch_import(
 }
 
 /// 解析文本内容中的订阅URL
-fn parse_subscription_urls(content: &str) -> CmdResult<Vec<String>> {
+pub(crate) fn parse_subscription_urls(content: &str) -> CmdResult<Vec<String>> {
     let mut urls = Vec::new();
 
     // 尝试解析JSON格式（仅当解析出非空结果时返回）
@@ -560,7 +584,7 @@ fn extract_url_from_line(line: &str) -> Option<String> {
 }
 
 /// 验证URL格式
-fn validate_urls(urls: Vec<String>) -> (Vec<String>, Vec<ImportResult>) {
+pub(crate) fn validate_urls(urls: Vec<String>) -> (Vec<String>, Vec<ImportResult>) {
     let mut valid_urls = Vec::new();
     let mut invalid_results = Vec::new();
 
@@ -634,11 +658,15 @@ async fn import_subscriptions(
     urls: Vec<String>,
     options: &BatchImportOptions,
     tracker: ProgressTracker,
+    token: &CancelToken,
 ) -> (Vec<ImportResult>, Vec<ImportResult>) {
     let mut success_results = Vec::new();
     let mut failed_results = Vec::new();
 
     for (index, url) in urls.into_iter().enumerate() {
+        if token.is_cancelled() {
+            break;
+        }
         let name = generate_subscription_name(&url, options);
 
         // 创建订阅项
@@ -751,6 +779,8 @@ pub struct ExportOptions {
     pub compress: bool,           // 是否压缩
     pub encrypt: bool,            // 是否加密
     pub password: Option<String>, // 加密密码
+    #[serde(default)]
+    pub redact: Option<bool>, // 是否脱敏，未指定时使用脱敏策略
 }
 
 /// 批量导出订阅
@@ -758,15 +788,59 @@ pub struct ExportOptions {
 pub async fn batch_export_subscriptions(
     subscription_uids: Vec<String>,
     options: ExportOptions,
-) -> Result<String, String> {
+    task_id: Option<String>,
+) -> CmdResult<String> {
     let _start_time = std::time::Instant::now();
+    let operation = register_operation(OperationKind::Export, task_id);
+    let progress = ProgressReporter::for_operation(&operation, subscription_uids.len());
+    progress.emit(
+        "exporting",
+        0,
+        Some(format!("正在导出 {} 个订阅", subscription_uids.len())),
+    );
 
-    match options.format.as_str() {
+    let data = match options.format.as_str() {
         "json" => export_as_json(subscription_uids, &options).await,
         "yaml" => export_as_yaml(subscription_uids, &options).await,
         "txt" => export_as_text(subscription_uids).await,
         "clash" => export_as_clash_config(subscription_uids, &options).await,
-        _ => Err("不支持的导出格式".to_string()),
+        _ => Err("不支持的导出格式".into()),
+    }
+    .inspect_err(|e| progress.fail(0, e.clone()))?;
+    operation.checkpoint()?;
+
+    let data = if options
+        .redact
+        .unwrap_or_else(|| redact::policy().redact_exports)
+    {
+        redact_export(&options.format, &data).inspect_err(|e| progress.fail(0, e.clone()))?
+    } else {
+        data
+    };
+    progress.finish(Some("导出完成".to_string()));
+    Ok(data)
+}
+
+/// 脱敏导出内容中的订阅地址与凭据
+fn redact_export(format: &str, data: &str) -> Result<String, String> {
+    match format {
+        "json" => {
+            let value: serde_json::Value =
+                serde_json::from_str(data).map_err(|e| format!("JSON解析失败: {}", e))?;
+            serde_json::to_string_pretty(&redact::redact_json(value))
+                .map_err(|e| format!("JSON序列化失败: {}", e))
+        }
+        "yaml" | "clash" => {
+            let value: serde_yaml_ng::Value =
+                serde_yaml_ng::from_str(data).map_err(|e| format!("YAML解析失败: {}", e))?;
+            serde_yaml_ng::to_string(&redact::redact_yaml(value))
+                .map_err(|e| format!("YAML序列化失败: {}", e))
+        }
+        _ => Ok(data
+            .lines()
+            .map(redact::redact_text)
+            .collect::<Vec<_>>()
+            .join("\n")),
     }
 }
 
@@ -776,8 +850,9 @@ pub async fn export_subscriptions_to_file(
     subscription_uids: Vec<String>,
     file_path: String,
     options: ExportOptions,
-) -> Result<(), String> {
-    let export_data = batch_export_subscriptions(subscription_uids, options).await?;
+    task_id: Option<String>,
+) -> CmdResult<()> {
+    let export_data = batch_export_subscriptions(subscription_uids, options, task_id).await?;
 
     std::fs::write(&file_path, export_data).map_err(|e| format!("写入文件失败: {}", e))?;
 
@@ -789,9 +864,9 @@ pub async fn export_subscriptions_to_file(
 pub async fn preview_export(
     subscription_uids: Vec<String>,
     options: ExportOptions,
-) -> Result<ExportPreview, String> {
+) -> CmdResult<ExportPreview> {
     let export_data =
-        batch_export_subscriptions(subscription_uids.clone(), options.clone()).await?;
+        batch_export_subscriptions(subscription_uids.clone(), options.clone(), None).await?;
 
     let preview = ExportPreview {
         format: options.format,
@@ -810,7 +885,7 @@ pub async fn preview_export(
 
 /// 获取所有订阅用于导出
 #[tauri::command]
-pub async fn get_all_subscriptions_for_export() -> Result<Vec<ExportableSubscription>, String> {
+pub async fn get_all_subscriptions_for_export() -> CmdResult<Vec<ExportableSubscription>> {
     let profiles = Config::profiles().await;
     let profiles_ref = profiles.latest_ref();
     let empty_vec = Vec::new();
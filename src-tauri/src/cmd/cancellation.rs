@@ -0,0 +1,212 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{core::handle, logging, utils::logging::Type};
+use nanoid::nanoid;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::Deref,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use tauri::Emitter;
+
+static OPERATIONS: Lazy<Mutex<HashMap<String, CancelToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 支持取消的长时间操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    BatchImport,
+    Enhance,
+    SubscriptionTest,
+    Backup,
+    Export,
+    SubscriptionUpdate,
+    Restore,
+    GeoUpdate,
+}
+
+/// 正在运行的操作
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationInfo {
+    pub task_id: String,
+    pub kind: OperationKind,
+    pub started_at: i64,
+    pub cancelled: bool,
+}
+
+/// 操作开始事件
+#[derive(Debug, Clone, Serialize)]
+struct OperationStartedPayload<'a> {
+    task_id: &'a str,
+    kind: OperationKind,
+}
+
+/// 操作取消事件，partial 为取消前已完成部分的结果
+#[derive(Debug, Clone, Serialize)]
+struct OperationCancelledPayload<'a, T: Serialize> {
+    task_id: &'a str,
+    kind: OperationKind,
+    partial: Option<&'a T>,
+}
+
+/// 操作被取消时返回的错误，会被转换为 `common.cancelled` 错误码
+#[derive(Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("操作已取消")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// 取消令牌，由长时间运行的命令持有并在各阶段之间检查
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    task_id: String,
+    kind: OperationKind,
+    started_at: i64,
+    flag: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+
+    pub const fn kind(&self) -> OperationKind {
+        self.kind
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+
+    /// 取消检查点，已取消时发送不带部分结果的取消事件并返回 Cancelled 错误
+    pub fn checkpoint(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            self.emit_cancelled::<()>(None);
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 发送取消事件，没有部分结果的操作传 None
+    pub fn emit_cancelled<T: Serialize>(&self, partial: Option<&T>) {
+        logging!(
+            info,
+            Type::Cmd,
+            true,
+            "[操作取消] {:?} 已取消: {}",
+            self.kind,
+            self.task_id
+        );
+        if let Some(app_handle) = handle::Handle::global().app_handle() {
+            let payload = OperationCancelledPayload {
+                task_id: &self.task_id,
+                kind: self.kind,
+                partial,
+            };
+            if let Err(err) = app_handle.emit("operation-cancelled", payload) {
+                log::warn!(target: "app", "operation-cancelled emit failed: {err}");
+            }
+        }
+    }
+
+    fn info(&self) -> OperationInfo {
+        OperationInfo {
+            task_id: self.task_id.clone(),
+            kind: self.kind,
+            started_at: self.started_at,
+            cancelled: self.is_cancelled(),
+        }
+    }
+}
+
+/// 注册期间持有的令牌，离开作用域时自动从注册表移除
+#[derive(Debug)]
+pub struct OperationGuard(CancelToken);
+
+impl Deref for OperationGuard {
+    type Target = CancelToken;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        OPERATIONS.lock().remove(&self.0.task_id);
+    }
+}
+
+/// 注册可取消的操作，task_id 为空或已被占用时生成新的 ID
+/// 注册后发送 operation-started 事件，前端据此获得可用于取消的 ID
+pub fn register_operation(kind: OperationKind, task_id: Option<String>) -> OperationGuard {
+    let token = {
+        let mut operations = OPERATIONS.lock();
+        let task_id = task_id
+            .filter(|id| !id.is_empty() && !operations.contains_key(id))
+            .unwrap_or_else(|| nanoid!());
+        let token = CancelToken {
+            task_id: task_id.clone(),
+            kind,
+            started_at: chrono::Local::now().timestamp(),
+            flag: Arc::new(AtomicBool::new(false)),
+        };
+        operations.insert(task_id, token.clone());
+        token
+    };
+
+    if let Some(app_handle) = handle::Handle::global().app_handle() {
+        let payload = OperationStartedPayload {
+            task_id: &token.task_id,
+            kind,
+        };
+        if let Err(err) = app_handle.emit("operation-started", payload) {
+            log::warn!(target: "app", "operation-started emit failed: {err}");
+        }
+    }
+    OperationGuard(token)
+}
+
+/// 取消正在运行的操作，操作会在下一个检查点停止
+#[tauri::command]
+pub fn cancel_operation(task_id: String) -> CmdResult<()> {
+    let operations = OPERATIONS.lock();
+    let Some(token) = operations.get(&task_id) else {
+        return Err(CmdError::new(
+            ErrorCode::NotFound,
+            format!("操作不存在或已结束: {task_id}"),
+        ));
+    };
+    token.flag.store(true, Ordering::SeqCst);
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[操作取消] 请求取消 {:?}: {}",
+        token.kind,
+        task_id
+    );
+    Ok(())
+}
+
+/// 获取正在运行的可取消操作
+#[tauri::command]
+pub fn get_running_operations() -> CmdResult<Vec<OperationInfo>> {
+    let mut operations: Vec<OperationInfo> =
+        OPERATIONS.lock().values().map(CancelToken::info).collect();
+    operations.sort_by_key(|info| info.started_at);
+    Ok(operations)
+}
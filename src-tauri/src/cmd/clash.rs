@@ -1,4 +1,6 @@
-use super::CmdResult;
+use super::{
+    CmdResult, OperationKind, ProgressReporter, core_registry::CoreChannel, delay_targets,
+};
 use crate::{
     config::Config,
     core::{CoreManager, handle},
@@ -6,16 +8,14 @@ use crate::{
 use crate::{
     config::*,
     feat,
-    ipc::{self, IpcManager},
+    ipc::{self, CoreCapabilities, IpcManager},
     logging,
-    state::proxy::ProxyRequestCache,
-    utils::logging::Type,
+    state::proxy::{CacheEndpoint, ProxyRequestCache},
+    utils::{field_mask::apply_field_mask, logging::Type},
     wrap_err,
 };
 use serde_yaml_ng::Mapping;
-use std::time::Duration;
-
-const CONFIG_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+use std::collections::HashMap;
 
 /// 复制Clash环境变量
 #[tauri::command]
@@ -138,23 +138,51 @@ pub async fn restart_core() -> CmdResult {
     result
 }
 
-/// 获取代理延迟
+/// 获取代理延迟，未指定 url 时使用配置的测速目标，
+/// 多个目标时返回平均延迟及 targets 中各目标的结果
 #[tauri::command]
 pub async fn clash_api_get_proxy_delay(
     name: String,
     url: Option<String>,
     timeout: i32,
 ) -> CmdResult<serde_json::Value> {
-    wrap_err!(
-        IpcManager::global()
-            .test_proxy_delay(&name, url, timeout)
-            .await
-    )
+    if let Some(url) = url.filter(|url| !url.is_empty()) {
+        return wrap_err!(
+            IpcManager::global()
+                .test_proxy_delay(&name, Some(url), None, timeout)
+                .await
+        );
+    }
+
+    let targets = delay_targets::configured_targets();
+    if let [target] = targets.as_slice() {
+        return wrap_err!(
+            IpcManager::global()
+                .test_proxy_delay(
+                    &name,
+                    Some(target.url.clone()),
+                    target.expected_status.as_deref(),
+                    timeout,
+                )
+                .await
+        );
+    }
+    let (delay, results) = delay_targets::test_proxy_delay_by_targets(&name, timeout).await;
+    let mut response = serde_json::json!({ "targets": results });
+    match delay {
+        Some(delay) => response["delay"] = delay.into(),
+        None => response["message"] = "所有测速目标均失败".into(),
+    }
+    Ok(response)
 }
 
-/// 测试URL延迟
+/// 测试URL延迟，url 为空时使用配置的测速目标的平均延迟
 #[tauri::command]
 pub async fn test_delay(url: String) -> CmdResult<u32> {
+    if url.trim().is_empty() {
+        let results = delay_targets::test_delay_targets().await?;
+        return Ok(delay_targets::average_delay(&results).map_or(10000u32, |delay| delay as u32));
+    }
     let result = match feat::test_delay(url).await {
         Ok(delay) => delay,
         Err(e) => {
@@ -173,15 +201,11 @@ pub async fn save_dns_config(dns_config: Mapping) -> CmdResult {
     use tokio::fs;
 
     // 获取DNS配置文件路径
-    let dns_path = dirs::app_home_dir()
-        .map_err(|e| e.to_string())?
-        .join("dns_config.yaml");
+    let dns_path = dirs::app_home_dir()?.join("dns_config.yaml");
 
     // 保存DNS配置到文件
-    let yaml_str = serde_yaml_ng::to_string(&dns_config).map_err(|e| e.to_string())?;
-    fs::write(&dns_path, yaml_str)
-        .await
-        .map_err(|e| e.to_string())?;
+    let yaml_str = serde_yaml_ng::to_string(&dns_config)?;
+    fs::write(&dns_path, yaml_str).await?;
     logging!(info, Type::Config, "DNS config saved to {dns_path:?}");
 
     Ok(())
@@ -198,9 +222,7 @@ pub async fn apply_dns_config(apply: bool) -> CmdResult {
 
     if apply {
         // 读取DNS配置文件
-        let dns_path = dirs::app_home_dir()
-            .map_err(|e| e.to_string())?
-            .join("dns_config.yaml");
+        let dns_path = dirs::app_home_dir()?.join("dns_config.yaml");
 
         if !dns_path.exists() {
             logging!(warn, Type::Config, "DNS config file not found");
@@ -284,9 +306,7 @@ pub async fn apply_dns_config(apply: bool) -> CmdResult {
 pub fn check_dns_config_exists() -> CmdResult<bool> {
     use crate::utils::dirs;
 
-    let dns_path = dirs::app_home_dir()
-        .map_err(|e| e.to_string())?
-        .join("dns_config.yaml");
+    let dns_path = dirs::app_home_dir()?.join("dns_config.yaml");
 
     Ok(dns_path.exists())
 }
@@ -297,17 +317,13 @@ pub async fn get_dns_config_content() -> CmdResult<String> {
     use crate::utils::dirs;
     use tokio::fs;
 
-    let dns_path = dirs::app_home_dir()
-        .map_err(|e| e.to_string())?
-        .join("dns_config.yaml");
+    let dns_path = dirs::app_home_dir()?.join("dns_config.yaml");
 
-    if !fs::try_exists(&dns_path).await.map_err(|e| e.to_string())? {
+    if !fs::try_exists(&dns_path).await? {
         return Err("DNS config file not found".into());
     }
 
-    let content = fs::read_to_string(&dns_path)
-        .await
-        .map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&dns_path).await?;
     Ok(content)
 }
 
@@ -316,7 +332,7 @@ pub async fn get_dns_config_content() -> CmdResult<String> {
 pub async fn validate_dns_config() -> CmdResult<(bool, String)> {
     use crate::{core::CoreManager, utils::dirs};
 
-    let app_dir = dirs::app_home_dir().map_err(|e| e.to_string())?;
+    let app_dir = dirs::app_home_dir()?;
     let dns_path = app_dir.join("dns_config.yaml");
     let dns_path_str = dns_path.to_str().unwrap_or_default();
 
@@ -329,7 +345,7 @@ pub async fn validate_dns_config() -> CmdResult<(bool, String)> {
         .await
     {
         Ok(result) => Ok(result),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -339,7 +355,7 @@ pub async fn get_clash_version() -> CmdResult<serde_json::Value> {
     // 先检查核心是否运行
     let core_manager = crate::core::CoreManager::global();
     let running_mode = core_manager.get_running_mode();
-    
+
     if running_mode == crate::core::RunningMode::NotRunning {
         log::warn!(target: "app", "Clash核心未运行，无法获取版本信息");
         return Ok(serde_json::json!({
@@ -349,11 +365,11 @@ pub async fn get_clash_version() -> CmdResult<serde_json::Value> {
             "error": "Core not running"
         }));
     }
-    
+
     // 尝试获取版本信息，带重试机制
     let mut retries = 3;
     let mut last_error = None;
-    
+
     while retries > 0 {
         match IpcManager::global().get_version().await {
             Ok(version) => {
@@ -364,7 +380,7 @@ pub async fn get_clash_version() -> CmdResult<serde_json::Value> {
                 last_error = Some(e);
                 retries -= 1;
                 log::warn!(target: "app", "获取版本信息失败，剩余重试次数: {}, 错误: {}", retries, last_error.as_ref().unwrap());
-                
+
                 if retries > 0 {
                     // 等待一段时间后重试
                     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
@@ -372,11 +388,13 @@ pub async fn get_clash_version() -> CmdResult<serde_json::Value> {
             }
         }
     }
-    
+
     // 所有重试都失败了
-    let error_msg = last_error.map(|e| e.to_string()).unwrap_or_else(|| "Unknown error".to_string());
+    let error_msg = last_error
+        .map(|e| e.to_string())
+        .unwrap_or_else(|| "Unknown error".to_string());
     log::error!(target: "app", "获取Clash版本信息最终失败: {}", error_msg);
-    
+
     // 返回错误信息而不是抛出异常，避免前端崩溃
     Ok(serde_json::json!({
         "version": "unknown",
@@ -389,22 +407,23 @@ pub async fn get_clash_version() -> CmdResult<serde_json::Value> {
 /// 获取IP信息（通过后端代理，避免CORS问题）
 #[tauri::command]
 pub async fn get_ip_info() -> CmdResult<serde_json::Value> {
+    use anyhow::Context;
     use reqwest::Client;
     use std::time::Duration;
-    
+
     let client = Client::builder()
         .timeout(Duration::from_secs(10))
         .user_agent("LIebesu_Clash/2.4.3")
         .build()
-        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
-    
+        .context("创建HTTP客户端失败")?;
+
     // 尝试多个IP查询服务
     let services = vec![
         "https://ipapi.co/json/",
         "https://ipwho.is/",
         "https://ipinfo.io/json",
     ];
-    
+
     for service_url in services {
         match client.get(service_url).send().await {
             Ok(response) => {
@@ -430,7 +449,7 @@ pub async fn get_ip_info() -> CmdResult<serde_json::Value> {
             }
         }
     }
-    
+
     // 所有服务都失败了，返回默认值
     log::error!(target: "app", "所有IP查询服务都失败了");
     Ok(serde_json::json!({
@@ -443,7 +462,7 @@ pub async fn get_ip_info() -> CmdResult<serde_json::Value> {
 }
 
 fn normalize_ip_info_response(data: serde_json::Value, source: &str) -> serde_json::Value {
-    use serde_json::{json, Map, Value};
+    use serde_json::{Map, Value, json};
 
     fn get_string(map: &Map<String, Value>, key: &str) -> Option<String> {
         map.get(key).and_then(|value| match value {
@@ -477,10 +496,7 @@ fn normalize_ip_info_response(data: serde_json::Value, source: &str) -> serde_js
     match data {
         Value::Object(mut map) => {
             if map.contains_key("ip") && map.contains_key("country") {
-                map.insert(
-                    "source".to_string(),
-                    Value::String(source.to_string()),
-                );
+                map.insert("source".to_string(), Value::String(source.to_string()));
                 return Value::Object(map);
             }
 
@@ -592,9 +608,8 @@ fn normalize_ip_info_response(data: serde_json::Value, source: &str) -> serde_js
 pub async fn get_clash_config() -> CmdResult<serde_json::Value> {
     let manager = IpcManager::global();
     let cache = ProxyRequestCache::global();
-    let key = ProxyRequestCache::make_key("clash_config", "default");
     let value = cache
-        .get_or_fetch(key, CONFIG_REFRESH_INTERVAL, || async {
+        .get_or_fetch(CacheEndpoint::ClashConfig, || async {
             manager.get_config().await.unwrap_or_else(|e| {
                 logging!(error, Type::Cmd, "Failed to fetch clash config: {e}");
                 serde_json::Value::Object(serde_json::Map::new())
@@ -607,22 +622,30 @@ pub async fn get_clash_config() -> CmdResult<serde_json::Value> {
 /// 强制刷新Clash配置缓存
 #[tauri::command]
 pub async fn force_refresh_clash_config() -> CmdResult<serde_json::Value> {
-    let cache = ProxyRequestCache::global();
-    let key = ProxyRequestCache::make_key("clash_config", "default");
-    cache.map.remove(&key);
+    ProxyRequestCache::global().invalidate(CacheEndpoint::ClashConfig);
     get_clash_config().await
 }
 
 /// 更新地理数据
 #[tauri::command]
 pub async fn update_geo_data() -> CmdResult {
-    wrap_err!(IpcManager::global().update_geo_data().await)
+    let progress = ProgressReporter::new(OperationKind::GeoUpdate, 1);
+    progress.emit("updating", 0, None);
+    let result = wrap_err!(IpcManager::global().update_geo_data().await);
+    match &result {
+        Ok(()) => progress.finish(None),
+        Err(e) => progress.fail(0, e.to_string()),
+    }
+    result
 }
 
-/// 升级Clash核心
+/// 升级Clash核心，指定渠道时分阶段升级并在失败时回滚，否则由内核原地升级
 #[tauri::command]
-pub async fn upgrade_clash_core() -> CmdResult {
-    wrap_err!(IpcManager::global().upgrade_core().await)
+pub async fn upgrade_clash_core(channel: Option<CoreChannel>) -> CmdResult {
+    match channel {
+        Some(channel) => super::core_upgrade::staged_upgrade(channel).await,
+        None => wrap_err!(IpcManager::global().upgrade_core().await),
+    }
 }
 
 /// 获取规则
@@ -672,9 +695,11 @@ pub async fn update_rule_provider(name: String) -> CmdResult {
 }
 
 /// 获取连接
+/// fields 为可选的字段路径列表（如 `connections.metadata.host`），仅返回指定字段
 #[tauri::command]
-pub async fn get_clash_connections() -> CmdResult<serde_json::Value> {
-    wrap_err!(IpcManager::global().get_connections().await)
+pub async fn get_clash_connections(fields: Option<Vec<String>>) -> CmdResult<serde_json::Value> {
+    let connections = wrap_err!(IpcManager::global().get_connections().await)?;
+    Ok(apply_field_mask(connections, fields.as_deref()))
 }
 
 /// 删除连接
@@ -721,27 +746,29 @@ pub async fn get_memory_data() -> CmdResult<serde_json::Value> {
     Ok(result)
 }
 
-/// 启动流量监控服务 (IPC流式监控自动启动，此函数为兼容性保留)
+/// 前端订阅数据流，由数据流多路复用器通过 verge://clash-stream 事件推送
 #[tauri::command]
-pub async fn start_traffic_service() -> CmdResult {
-    logging!(trace, Type::Ipc, "启动流量监控服务 (IPC流式监控)");
-    // 新的IPC监控在首次访问时自动启动
-    // 触发一次访问以确保监控器已初始化
-    let _ = crate::ipc::get_current_traffic().await;
-    let _ = crate::ipc::get_current_memory().await;
-    logging!(info, Type::Ipc, "IPC流式监控已激活");
+pub async fn subscribe_clash_stream(channel: ipc::StreamChannel) -> CmdResult {
+    logging!(trace, Type::Ipc, "前端订阅数据流: {:?}", channel);
+    ipc::StreamMultiplexer::global().subscribe_frontend(channel);
     Ok(())
 }
 
-/// 停止流量监控服务 (IPC流式监控无需显式停止，此函数为兼容性保留)
+/// 前端取消订阅数据流，没有内部消费者时断开上游连接
 #[tauri::command]
-pub async fn stop_traffic_service() -> CmdResult {
-    logging!(trace, Type::Ipc, "停止流量监控服务请求 (IPC流式监控)");
-    // 新的IPC监控是持久的，无需显式停止
-    logging!(info, Type::Ipc, "IPC流式监控继续运行");
+pub async fn unsubscribe_clash_stream(channel: ipc::StreamChannel) -> CmdResult {
+    logging!(trace, Type::Ipc, "前端取消订阅数据流: {:?}", channel);
+    ipc::StreamMultiplexer::global().unsubscribe_frontend(channel);
     Ok(())
 }
 
+/// 获取各数据流的连接状态
+#[tauri::command]
+pub async fn get_stream_status() -> CmdResult<serde_json::Value> {
+    let status = ipc::StreamMultiplexer::global().status();
+    Ok(serde_json::to_value(status).unwrap_or_default())
+}
+
 /// 获取格式化的流量数据 (包含单位，便于前端显示)
 #[tauri::command]
 pub async fn get_formatted_traffic_data() -> CmdResult<serde_json::Value> {
@@ -840,16 +867,78 @@ pub async fn get_system_monitor_overview() -> CmdResult<serde_json::Value> {
     Ok(result)
 }
 
-/// 获取代理组延迟
+/// 获取代理组延迟，完成后刷新托盘中的延迟徽标
+/// 未指定 url 时使用配置的测速目标，多个目标时返回各节点的平均延迟
 #[tauri::command]
 pub async fn get_group_proxy_delays(
     group_name: String,
     url: Option<String>,
     timeout: Option<i32>,
+) -> CmdResult<serde_json::Value> {
+    let ipc = IpcManager::global();
+    let timeout = timeout.unwrap_or(10000);
+    let targets = delay_targets::configured_targets();
+    let delays = match (url.filter(|url| !url.is_empty()), targets.as_slice()) {
+        (Some(url), _) => wrap_err!(
+            ipc.get_group_proxy_delays(&group_name, Some(url), None, timeout)
+                .await
+        )?,
+        (None, [target]) => wrap_err!(
+            ipc.get_group_proxy_delays(
+                &group_name,
+                Some(target.url.clone()),
+                target.expected_status.as_deref(),
+                timeout,
+            )
+            .await
+        )?,
+        (None, _) => {
+            let mut samples: HashMap<String, Vec<u64>> = HashMap::new();
+            for target in &targets {
+                let delays = wrap_err!(
+                    ipc.get_group_proxy_delays(
+                        &group_name,
+                        Some(target.url.clone()),
+                        target.expected_status.as_deref(),
+                        timeout,
+                    )
+                    .await
+                )?;
+                for (name, delay) in delays.as_object().into_iter().flatten() {
+                    if let Some(delay) = delay.as_u64().filter(|delay| *delay > 0) {
+                        samples.entry(name.clone()).or_default().push(delay);
+                    }
+                }
+            }
+            samples
+                .into_iter()
+                .map(|(name, delays)| {
+                    let average = delays.iter().sum::<u64>() / delays.len() as u64;
+                    (name, serde_json::Value::from(average))
+                })
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        }
+    };
+    crate::core::tray::Tray::global().refresh_menu_async();
+    Ok(delays)
+}
+
+/// 获取当前内核支持的可选接口
+#[tauri::command]
+pub async fn get_core_capabilities() -> CmdResult<CoreCapabilities> {
+    wrap_err!(IpcManager::global().capabilities().await)
+}
+
+/// 通过内核 DNS 查询域名
+#[tauri::command]
+pub async fn query_clash_dns(
+    name: String,
+    query_type: Option<String>,
 ) -> CmdResult<serde_json::Value> {
     wrap_err!(
         IpcManager::global()
-            .get_group_proxy_delays(&group_name, url, timeout.unwrap_or(10000))
+            .dns_query(&name, query_type.as_deref())
             .await
     )
 }
@@ -875,6 +964,34 @@ pub async fn get_clash_logs() -> CmdResult<serde_json::Value> {
     Ok(ipc::get_logs_json().await)
 }
 
+/// 按条件查询结构化日志，按时间倒序返回
+#[tauri::command]
+pub async fn query_logs(
+    filter: Option<ipc::LogFilter>,
+    range: Option<ipc::LogTimeRange>,
+    limit: Option<usize>,
+) -> CmdResult<Vec<ipc::LogRecord>> {
+    let filter = filter.unwrap_or_default();
+    let range = range.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        ipc::log_pipeline::query_logs(&filter, range, limit.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string().into())
+}
+
+/// 导出时间范围内的日志，返回导出文件路径
+#[tauri::command]
+pub async fn export_logs(
+    range: Option<ipc::LogTimeRange>,
+    format: ipc::LogExportFormat,
+) -> CmdResult<String> {
+    let range = range.unwrap_or_default();
+    let path = tokio::task::spawn_blocking(move || ipc::log_pipeline::export_logs(range, format))
+        .await??;
+    Ok(path.to_string_lossy().to_string())
+}
+
 /// 启动日志监控
 #[tauri::command]
 pub async fn start_logs_monitoring(level: Option<String>) -> CmdResult {
@@ -0,0 +1,581 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::{
+        Config, IProfiles, IVerge, PrfItem, PrfOption, PrfSelected,
+        profiles::profiles_append_item_safe,
+    },
+    feat, logging,
+    utils::{help, logging::Type},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::Mapping;
+use std::path::{Path, PathBuf};
+
+/// 迁移的 Clash 基础设置项
+const CLASH_SETTING_KEYS: &[&str] = &["mixed-port", "allow-lan", "mode", "ipv6", "log-level"];
+
+/// 可迁移的客户端
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationClient {
+    ClashVerge,      // Clash Verge / Clash Verge Rev
+    ClashForWindows, // Clash for Windows
+    #[serde(rename = "v2rayn")]
+    V2rayN, // v2rayN
+}
+
+/// 迁移内容类别
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationCategory {
+    Profile,
+    SelectedNodes,
+    CustomRules,
+    Enhancement,
+    Settings,
+}
+
+/// 单项迁移结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationItem {
+    pub category: MigrationCategory,
+    pub name: String,
+    pub imported: bool,
+    pub reason: Option<String>, // 未能迁移的原因
+}
+
+/// 迁移汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationSummary {
+    pub client: MigrationClient,
+    pub source_dir: String,
+    pub imported: usize,
+    pub skipped: usize,
+    pub items: Vec<MigrationItem>,
+}
+
+impl MigrationSummary {
+    fn add_imported(&mut self, category: MigrationCategory, name: impl Into<String>) {
+        self.imported += 1;
+        self.items.push(MigrationItem {
+            category,
+            name: name.into(),
+            imported: true,
+            reason: None,
+        });
+    }
+
+    fn add_skipped(
+        &mut self,
+        category: MigrationCategory,
+        name: impl Into<String>,
+        reason: impl Into<String>,
+    ) {
+        self.skipped += 1;
+        self.items.push(MigrationItem {
+            category,
+            name: name.into(),
+            imported: false,
+            reason: Some(reason.into()),
+        });
+    }
+}
+
+/// Clash for Windows 的 profiles/list.yml
+#[derive(Debug, Default, Deserialize)]
+struct CfwProfileList {
+    #[serde(default)]
+    files: Vec<CfwProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CfwProfile {
+    time: String, // 订阅文件名
+    name: Option<String>,
+    url: Option<String>,
+    interval: Option<u64>, // 更新间隔（小时）
+    #[serde(default)]
+    selected: Vec<PrfSelected>,
+}
+
+/// v2rayN 的 guiNConfig.json，只读取需要的字段
+#[derive(Debug, Default, Deserialize)]
+struct V2rayNConfig {
+    #[serde(default, rename = "subItem")]
+    sub_item: Vec<V2rayNSubscription>,
+    #[serde(default)]
+    vmess: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct V2rayNSubscription {
+    #[serde(default)]
+    remarks: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default = "default_true")]
+    enabled: bool,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// 从其他客户端迁移订阅、节点选择、自定义规则和基础设置
+/// path 为空时自动查找客户端的默认数据目录
+#[tauri::command]
+pub async fn migrate_from_client(
+    client: MigrationClient,
+    path: Option<String>,
+) -> CmdResult<MigrationSummary> {
+    let source_dir = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => locate_client_dir(client).ok_or_else(|| {
+            CmdError::new(
+                ErrorCode::NotFound,
+                format!("未找到 {:?} 的数据目录，请手动指定", client),
+            )
+        })?,
+    };
+    if !source_dir.is_dir() {
+        return Err(CmdError::new(
+            ErrorCode::NotFound,
+            format!("目录不存在: {}", source_dir.display()),
+        ));
+    }
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[客户端迁移] 开始从 {:?} 迁移: {}",
+        client,
+        source_dir.display()
+    );
+
+    let mut summary = MigrationSummary {
+        client,
+        source_dir: source_dir.to_string_lossy().to_string(),
+        imported: 0,
+        skipped: 0,
+        items: Vec::new(),
+    };
+    match client {
+        MigrationClient::ClashVerge => migrate_clash_verge(&source_dir, &mut summary).await?,
+        MigrationClient::ClashForWindows => migrate_cfw(&source_dir, &mut summary).await?,
+        MigrationClient::V2rayN => migrate_v2rayn(&source_dir, &mut summary).await?,
+    }
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[客户端迁移] 完成: 成功 {} 项, 跳过 {} 项",
+        summary.imported,
+        summary.skipped
+    );
+    Ok(summary)
+}
+
+// ===== 内部实现函数 =====
+
+/// 各客户端的默认数据目录
+fn locate_client_dir(client: MigrationClient) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let candidates: Vec<PathBuf> = match client {
+        MigrationClient::ClashVerge => vec![
+            dirs::data_dir()?.join("io.github.clash-verge-rev.clash-verge-rev"),
+            home.join(".config").join("clash-verge"),
+        ],
+        MigrationClient::ClashForWindows => vec![home.join(".config").join("clash")],
+        // v2rayN 为便携版，没有固定的数据目录
+        MigrationClient::V2rayN => Vec::new(),
+    };
+    candidates.into_iter().find(|dir| dir.is_dir())
+}
+
+async fn migrate_clash_verge(source_dir: &Path, summary: &mut MigrationSummary) -> Result<()> {
+    let source_profiles: IProfiles = help::read_yaml(&source_dir.join("profiles.yaml"))
+        .await
+        .context("读取 profiles.yaml 失败")?;
+    let source_items = source_profiles.get_items().cloned().unwrap_or_default();
+    let profiles_dir = source_dir.join("profiles");
+
+    for source in &source_items {
+        if !matches!(source.itype.as_deref(), Some("remote" | "local")) {
+            continue;
+        }
+        let name = source.name.clone().unwrap_or_else(|| "Unnamed".to_string());
+        let Some(file) = source.file.as_ref() else {
+            summary.add_skipped(MigrationCategory::Profile, name, "缺少订阅文件");
+            continue;
+        };
+        let content = match tokio::fs::read_to_string(profiles_dir.join(file)).await {
+            Ok(content) => content,
+            Err(e) => {
+                summary.add_skipped(
+                    MigrationCategory::Profile,
+                    name,
+                    format!("读取订阅文件失败: {e}"),
+                );
+                continue;
+            }
+        };
+
+        let Some(item) = import_profile(
+            summary,
+            name.clone(),
+            content,
+            source.url.clone(),
+            source.option.as_ref().map(remote_option),
+            source.selected.clone().unwrap_or_default(),
+        )
+        .await
+        else {
+            continue;
+        };
+
+        // 复制订阅关联的增强项内容，自定义规则位于 rules 项中
+        let (Some(source_option), Some(option)) = (source.option.as_ref(), item.option.as_ref())
+        else {
+            continue;
+        };
+        let chains = [
+            (
+                MigrationCategory::CustomRules,
+                "rules",
+                &source_option.rules,
+                &option.rules,
+            ),
+            (
+                MigrationCategory::Enhancement,
+                "merge",
+                &source_option.merge,
+                &option.merge,
+            ),
+            (
+                MigrationCategory::Enhancement,
+                "script",
+                &source_option.script,
+                &option.script,
+            ),
+            (
+                MigrationCategory::Enhancement,
+                "proxies",
+                &source_option.proxies,
+                &option.proxies,
+            ),
+            (
+                MigrationCategory::Enhancement,
+                "groups",
+                &source_option.groups,
+                &option.groups,
+            ),
+        ];
+        for (category, label, source_uid, target_uid) in chains {
+            let (Some(source_uid), Some(target_uid)) = (source_uid, target_uid) else {
+                continue;
+            };
+            let Some(source_file) = source_items
+                .iter()
+                .find(|i| i.uid.as_ref() == Some(source_uid))
+                .and_then(|i| i.file.as_ref())
+            else {
+                continue;
+            };
+            let label = format!("{name} ({label})");
+            match copy_chain_file(&profiles_dir.join(source_file), target_uid).await {
+                Ok(()) => summary.add_imported(category, label),
+                Err(e) => summary.add_skipped(category, label, e.to_string()),
+            }
+        }
+    }
+
+    migrate_clash_settings(&source_dir.join("config.yaml"), summary).await;
+
+    // 只迁移与平台无关的界面设置
+    match help::read_mapping(&source_dir.join("verge.yaml")).await {
+        Ok(verge) => {
+            let language = verge.get("language").and_then(|v| v.as_str());
+            let theme_mode = verge.get("theme_mode").and_then(|v| v.as_str());
+            let patch = IVerge {
+                language: language.map(str::to_string),
+                theme_mode: theme_mode.map(str::to_string),
+                ..IVerge::default()
+            };
+            match feat::patch_verge(patch, false).await {
+                Ok(()) => summary.add_imported(MigrationCategory::Settings, "verge.yaml"),
+                Err(e) => {
+                    summary.add_skipped(MigrationCategory::Settings, "verge.yaml", e.to_string())
+                }
+            }
+        }
+        Err(e) => summary.add_skipped(MigrationCategory::Settings, "verge.yaml", e.to_string()),
+    }
+
+    Ok(())
+}
+
+async fn migrate_cfw(source_dir: &Path, summary: &mut MigrationSummary) -> Result<()> {
+    let profiles_dir = source_dir.join("profiles");
+    let list: CfwProfileList = help::read_yaml(&profiles_dir.join("list.yml"))
+        .await
+        .context("读取 profiles/list.yml 失败")?;
+
+    for source in list.files {
+        let name = source.name.unwrap_or_else(|| source.time.clone());
+        match tokio::fs::read_to_string(profiles_dir.join(&source.time)).await {
+            Ok(content) => {
+                let option = PrfOption {
+                    update_interval: source.interval.map(|hours| hours * 60),
+                    ..PrfOption::default()
+                };
+                import_profile(
+                    summary,
+                    name,
+                    content,
+                    source.url,
+                    Some(option),
+                    source.selected,
+                )
+                .await;
+            }
+            Err(e) => summary.add_skipped(
+                MigrationCategory::Profile,
+                name,
+                format!("读取订阅文件失败: {e}"),
+            ),
+        }
+    }
+
+    // CFW 的规则直接写在订阅中，随订阅一起迁移；parsers 为 JavaScript/YAML 混合格式，无法转换
+    if let Ok(settings) = help::read_mapping(&source_dir.join("cfw-settings.yaml")).await
+        && settings
+            .get("parsers")
+            .and_then(|v| v.as_sequence())
+            .is_some_and(|parsers| !parsers.is_empty())
+    {
+        summary.add_skipped(
+            MigrationCategory::CustomRules,
+            "parsers",
+            "CFW 预处理器无法转换，请在订阅的扩展脚本中重新配置",
+        );
+    }
+
+    migrate_clash_settings(&source_dir.join("config.yaml"), summary).await;
+    Ok(())
+}
+
+async fn migrate_v2rayn(source_dir: &Path, summary: &mut MigrationSummary) -> Result<()> {
+    let config_path = [
+        source_dir.join("guiConfigs").join("guiNConfig.json"),
+        source_dir.join("guiNConfig.json"),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .context("未找到 guiNConfig.json")?;
+    let config: V2rayNConfig =
+        serde_json::from_str(&tokio::fs::read_to_string(&config_path).await?)
+            .context("解析 guiNConfig.json 失败")?;
+
+    // 新版本 v2rayN 将订阅保存在 SQLite 数据库中
+    if config.sub_item.is_empty() && config_path.with_file_name("guiNDB.db").is_file() {
+        summary.add_skipped(
+            MigrationCategory::Profile,
+            "guiNDB.db",
+            "新版 v2rayN 的数据库格式暂不支持，请手动导出订阅地址",
+        );
+    }
+
+    // v2rayN 订阅通常为分享链接格式，通过订阅地址重新下载，由服务端返回 Clash 格式
+    for sub in config.sub_item {
+        let name = if sub.remarks.is_empty() {
+            sub.url.clone()
+        } else {
+            sub.remarks
+        };
+        if !sub.enabled || sub.url.is_empty() {
+            summary.add_skipped(MigrationCategory::Profile, name, "订阅未启用或地址为空");
+            continue;
+        }
+        if profile_url_exists(&sub.url).await {
+            summary.add_skipped(MigrationCategory::Profile, name, "已存在相同地址的订阅");
+            continue;
+        }
+        let result = async {
+            let item = PrfItem::from_url(&sub.url, Some(name.clone()), None, None).await?;
+            profiles_append_item_safe(item).await
+        }
+        .await;
+        match result {
+            Ok(()) => summary.add_imported(MigrationCategory::Profile, name),
+            Err(e) => summary.add_skipped(
+                MigrationCategory::Profile,
+                name,
+                format!("下载订阅失败，可能不是 Clash 格式: {e}"),
+            ),
+        }
+    }
+
+    if !config.vmess.is_empty() {
+        summary.add_skipped(
+            MigrationCategory::Profile,
+            format!("{} 个手动添加的节点", config.vmess.len()),
+            "v2rayN 节点格式无法直接转换为 Clash 配置",
+        );
+    }
+    summary.add_skipped(
+        MigrationCategory::CustomRules,
+        "routing",
+        "v2rayN 路由规则格式与 Clash 不兼容",
+    );
+    Ok(())
+}
+
+/// 有订阅地址时按原下载设置重新下载，否则以本地文件内容导入
+async fn import_profile(
+    summary: &mut MigrationSummary,
+    name: String,
+    content: String,
+    url: Option<String>,
+    option: Option<PrfOption>,
+    selected: Vec<PrfSelected>,
+) -> Option<PrfItem> {
+    if let Some(url) = url.as_deref()
+        && profile_url_exists(url).await
+    {
+        summary.add_skipped(MigrationCategory::Profile, name, "已存在相同地址的订阅");
+        return None;
+    }
+    if let Err(e) = serde_yaml_ng::from_str::<Mapping>(&content) {
+        summary.add_skipped(
+            MigrationCategory::Profile,
+            name,
+            format!("订阅文件不是有效的 YAML: {e}"),
+        );
+        return None;
+    }
+
+    let result = async {
+        let mut item = match url.as_deref() {
+            Some(url) => {
+                match PrfItem::from_url(url, Some(name.clone()), None, option.clone()).await {
+                    Ok(item) => item,
+                    Err(e) => {
+                        // 下载失败时使用原客户端保存的内容，下载设置保留，之后按原间隔更新
+                        logging!(
+                            warn,
+                            Type::Config,
+                            true,
+                            "[客户端迁移] 下载订阅 {} 失败，使用已保存的内容: {}",
+                            name,
+                            e
+                        );
+                        let mut item =
+                            PrfItem::from_local(name.clone(), String::new(), Some(content), None)
+                                .await?;
+                        item.itype = Some("remote".into());
+                        item.url = Some(url.to_string());
+                        if let (Some(target), Some(source)) = (item.option.as_mut(), option) {
+                            *target = PrfOption {
+                                merge: target.merge.take(),
+                                script: target.script.take(),
+                                rules: target.rules.take(),
+                                proxies: target.proxies.take(),
+                                groups: target.groups.take(),
+                                ..source
+                            };
+                        }
+                        item
+                    }
+                }
+            }
+            None => PrfItem::from_local(name.clone(), String::new(), Some(content), None).await?,
+        };
+        if !selected.is_empty() {
+            item.selected = Some(selected.clone());
+        }
+        profiles_append_item_safe(item.clone()).await?;
+        anyhow::Ok(item)
+    }
+    .await;
+
+    match result {
+        Ok(item) => {
+            summary.add_imported(MigrationCategory::Profile, name.clone());
+            if !selected.is_empty() {
+                summary.add_imported(
+                    MigrationCategory::SelectedNodes,
+                    format!("{name} ({} 个代理组)", selected.len()),
+                );
+            }
+            Some(item)
+        }
+        Err(e) => {
+            summary.add_skipped(MigrationCategory::Profile, name, e.to_string());
+            None
+        }
+    }
+}
+
+/// 原订阅中与下载和更新相关的设置，增强项引用原客户端的 uid，不能沿用
+fn remote_option(option: &PrfOption) -> PrfOption {
+    PrfOption {
+        user_agent: option.user_agent.clone(),
+        with_proxy: option.with_proxy,
+        self_proxy: option.self_proxy,
+        update_interval: option.update_interval,
+        timeout_seconds: option.timeout_seconds,
+        danger_accept_invalid_certs: option.danger_accept_invalid_certs,
+        ..PrfOption::default()
+    }
+}
+
+async fn profile_url_exists(url: &str) -> bool {
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    profiles
+        .get_items()
+        .is_some_and(|items| items.iter().any(|i| i.url.as_deref() == Some(url)))
+}
+
+/// 用源文件内容覆盖新订阅对应增强项的模板
+async fn copy_chain_file(source: &Path, target_uid: &str) -> Result<()> {
+    let content = tokio::fs::read_to_string(source)
+        .await
+        .context("读取增强项文件失败")?;
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    profiles
+        .get_item(&target_uid.to_string())?
+        .save_file(content)
+}
+
+/// 迁移 Clash 基础设置
+async fn migrate_clash_settings(path: &Path, summary: &mut MigrationSummary) {
+    let config = match help::read_mapping(&path.to_path_buf()).await {
+        Ok(config) => config,
+        Err(e) => {
+            summary.add_skipped(MigrationCategory::Settings, "config.yaml", e.to_string());
+            return;
+        }
+    };
+
+    let patch: Mapping = CLASH_SETTING_KEYS
+        .iter()
+        .filter_map(|key| config.get(*key).map(|value| ((*key).into(), value.clone())))
+        .collect();
+    if patch.is_empty() {
+        summary.add_skipped(
+            MigrationCategory::Settings,
+            "config.yaml",
+            "没有可迁移的设置",
+        );
+        return;
+    }
+    match feat::patch_clash(patch).await {
+        Ok(()) => summary.add_imported(MigrationCategory::Settings, "config.yaml"),
+        Err(e) => summary.add_skipped(MigrationCategory::Settings, "config.yaml", e.to_string()),
+    }
+}
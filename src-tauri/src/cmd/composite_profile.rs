@@ -0,0 +1,515 @@
+use super::{CmdError, CmdResult};
+use crate::{
+    config::{Config, PrfItem, profiles_append_item_safe},
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// 组合订阅选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompositeProfileOptions {
+    pub name: String,
+    pub prefix_groups: bool,   // 分组名添加来源订阅前缀
+    pub include_rules: bool,   // 沿用第一个来源订阅的规则
+    pub auto_regenerate: bool, // 来源订阅更新后自动重新生成
+}
+
+impl Default for CompositeProfileOptions {
+    fn default() -> Self {
+        Self {
+            name: "组合订阅".to_string(),
+            prefix_groups: true,
+            include_rules: true,
+            auto_regenerate: true,
+        }
+    }
+}
+
+/// 组合订阅定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeProfile {
+    pub uid: String,
+    pub sources: Vec<String>,
+    pub options: CompositeProfileOptions,
+    pub node_count: usize,
+    pub updated_at: i64,
+}
+
+/// 创建组合订阅，合并多个来源订阅的节点为一个配置
+#[tauri::command]
+pub async fn create_composite_profile(
+    sources: Vec<String>,
+    options: Option<CompositeProfileOptions>,
+) -> CmdResult<String> {
+    let options = options.unwrap_or_default();
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[组合订阅] 创建组合订阅 {}，来源: {:?}",
+        options.name,
+        sources
+    );
+
+    let (yaml, node_count) = build_composite_config(&sources, &options)
+        .await
+        .context("生成组合订阅失败")?;
+
+    let desc = format!("由 {} 个订阅合并生成", sources.len());
+    let item = PrfItem::from_local(options.name.clone(), desc, Some(yaml), None)
+        .await
+        .context("创建组合订阅失败")?;
+    let uid = item
+        .uid
+        .clone()
+        .ok_or_else(|| "组合订阅缺少 uid".to_string())?;
+    profiles_append_item_safe(item)
+        .await
+        .context("保存组合订阅失败")?;
+
+    let mut composites = load_composites().unwrap_or_default();
+    composites.push(CompositeProfile {
+        uid: uid.clone(),
+        sources,
+        options,
+        node_count,
+        updated_at: chrono::Utc::now().timestamp(),
+    });
+    save_composites(&composites).context("保存组合订阅定义失败")?;
+
+    Ok(uid)
+}
+
+/// 获取所有组合订阅
+#[tauri::command]
+pub async fn get_composite_profiles() -> CmdResult<Vec<CompositeProfile>> {
+    let composites = load_composites().context("加载组合订阅失败")?;
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    Ok(composites
+        .into_iter()
+        .filter(|c| profiles.get_item(&c.uid).is_ok())
+        .collect())
+}
+
+/// 修改组合订阅的来源和选项，并重新生成
+#[tauri::command]
+pub async fn update_composite_profile(
+    uid: String,
+    sources: Vec<String>,
+    options: CompositeProfileOptions,
+) -> CmdResult<()> {
+    let mut composites = load_composites().context("加载组合订阅失败")?;
+    let composite = composites
+        .iter_mut()
+        .find(|c| c.uid == uid)
+        .ok_or_else(|| "组合订阅不存在".to_string())?;
+    composite.sources = sources;
+    composite.options = options;
+    save_composites(&composites).context("保存组合订阅定义失败")?;
+
+    regenerate_composite(&uid)
+        .await
+        .context("重新生成组合订阅失败")
+        .map_err(CmdError::from)
+}
+
+/// 立即重新生成组合订阅
+#[tauri::command]
+pub async fn regenerate_composite_profile(uid: String) -> CmdResult<()> {
+    regenerate_composite(&uid)
+        .await
+        .context("重新生成组合订阅失败")
+        .map_err(CmdError::from)
+}
+
+/// 来源订阅更新后，重新生成包含它的组合订阅
+pub async fn refresh_composites_for_source(source_uid: &str) {
+    let targets: Vec<String> = load_composites()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| c.options.auto_regenerate && c.sources.iter().any(|s| s == source_uid))
+        .map(|c| c.uid)
+        .collect();
+
+    for uid in targets {
+        if let Err(e) = regenerate_composite(&uid).await {
+            logging!(
+                warn,
+                Type::Cmd,
+                true,
+                "[组合订阅] 重新生成 {} 失败: {}",
+                uid,
+                e
+            );
+        }
+    }
+}
+
+/// 所有组合订阅的 uid
+pub fn composite_profile_uids() -> Vec<String> {
+    load_composites()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| c.uid)
+        .collect()
+}
+
+// ===== 内部实现函数 =====
+
+/// 重新生成组合订阅文件，如为当前订阅则刷新内核配置
+async fn regenerate_composite(uid: &str) -> Result<()> {
+    let mut composites = load_composites()?;
+    let Some(composite) = composites.iter_mut().find(|c| c.uid == uid) else {
+        bail!("composite profile not found: {uid}");
+    };
+
+    let (yaml, node_count) = build_composite_config(&composite.sources, &composite.options).await?;
+    let (item, is_current) = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        (
+            profiles.get_item(&uid.to_string())?.clone(),
+            profiles.get_current().as_deref() == Some(uid),
+        )
+    };
+    item.save_file(yaml)?;
+
+    composite.node_count = node_count;
+    composite.updated_at = chrono::Utc::now().timestamp();
+    save_composites(&composites)?;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[组合订阅] {} 已重新生成，共 {} 个节点",
+        uid,
+        node_count
+    );
+
+    if is_current {
+        CoreManager::global().update_config().await?;
+        handle::Handle::refresh_clash();
+    }
+    handle::Handle::notify_profile_changed(uid.to_string());
+    Ok(())
+}
+
+/// 合并来源订阅，返回生成的 YAML 和节点数量
+async fn build_composite_config(
+    sources: &[String],
+    options: &CompositeProfileOptions,
+) -> Result<(String, usize)> {
+    if sources.len() < 2 {
+        bail!("at least two source profiles are required");
+    }
+
+    let mut source_configs = Vec::new();
+    {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        for uid in sources {
+            let item = profiles.get_item(uid)?;
+            if !matches!(item.itype.as_deref(), Some("remote" | "local")) {
+                bail!("profile {uid} is not a subscription");
+            }
+            let label = item.name.clone().unwrap_or_else(|| uid.clone());
+            let config: Mapping = serde_yaml_ng::from_str(&item.read_file()?)?;
+            source_configs.push((label, config));
+        }
+    }
+
+    Ok(merge_configs(source_configs, options))
+}
+
+/// 合并多个配置，节点重名时添加来源前缀
+fn merge_configs(
+    source_configs: Vec<(String, Mapping)>,
+    options: &CompositeProfileOptions,
+) -> (String, usize) {
+    let mut used_names = HashSet::new();
+    let mut proxies = Vec::new();
+    let mut providers = Mapping::new();
+    let mut rule_providers = Mapping::new();
+    let mut groups = Vec::new();
+    let mut top_groups = Vec::new();
+    let mut all_nodes = Vec::new();
+    let mut rules = Vec::new();
+
+    for (index, (label, config)) in source_configs.into_iter().enumerate() {
+        // 来源内名称 -> 合并后名称
+        let mut renamed: HashMap<String, String> = HashMap::new();
+
+        for proxy in sequence(&config, "proxies") {
+            let Value::Mapping(mut proxy) = proxy else {
+                continue;
+            };
+            let Some(name) = proxy
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            let new_name = unique_name(&name, &label, &mut used_names);
+            proxy.insert("name".into(), new_name.clone().into());
+            renamed.insert(name, new_name.clone());
+            all_nodes.push(Value::from(new_name));
+            proxies.push(Value::Mapping(proxy));
+        }
+
+        if let Some(Value::Mapping(source_providers)) = config.get("proxy-providers") {
+            for (name, provider) in source_providers {
+                let Some(name) = name.as_str() else {
+                    continue;
+                };
+                // provider 总是带上来源前缀，避免不同来源的同名 provider 互相覆盖
+                let new_name = unique_name(&format!("{label}/{name}"), &label, &mut used_names);
+                renamed.insert(name.to_string(), new_name.clone());
+                providers.insert(
+                    new_name.into(),
+                    namespace_provider(provider, "proxies", index),
+                );
+            }
+        }
+
+        // 只有第一个来源的规则会被使用，其引用的 rule-provider 随规则一起合并
+        let mut rule_sets: HashMap<String, String> = HashMap::new();
+        if index == 0
+            && options.include_rules
+            && let Some(Value::Mapping(source_providers)) = config.get("rule-providers")
+        {
+            for (name, provider) in source_providers {
+                let Some(name) = name.as_str() else {
+                    continue;
+                };
+                let new_name = format!("{label}/{name}");
+                rule_sets.insert(name.to_string(), new_name.clone());
+                rule_providers.insert(
+                    new_name.into(),
+                    namespace_provider(provider, "rules", index),
+                );
+            }
+        }
+
+        // 先确定分组名，分组之间可能互相引用
+        let source_groups = sequence(&config, "proxy-groups");
+        for group in &source_groups {
+            if let Some(name) = group.get("name").and_then(Value::as_str) {
+                let new_name = if options.prefix_groups {
+                    unique_name(&format!("{label}/{name}"), &label, &mut used_names)
+                } else {
+                    unique_name(name, &label, &mut used_names)
+                };
+                renamed.insert(name.to_string(), new_name);
+            }
+        }
+
+        // 每个来源的第一个有效分组作为该来源的入口
+        let mut top_group = None;
+        for group in source_groups {
+            let Value::Mapping(mut group) = group else {
+                continue;
+            };
+            for key in ["name", "proxies", "use"] {
+                if let Some(value) = group.get_mut(key) {
+                    rename_refs(value, &renamed);
+                }
+            }
+            if top_group.is_none() {
+                top_group = group.get("name").cloned();
+            }
+            groups.push(Value::Mapping(group));
+        }
+        top_groups.extend(top_group);
+
+        if index == 0 && options.include_rules {
+            rules = sequence(&config, "rules")
+                .into_iter()
+                .filter_map(|rule| {
+                    rule.as_str()
+                        .map(|r| rename_member_rule(r, &renamed, &rule_sets))
+                })
+                .filter(|rule| !rule.starts_with("MATCH,"))
+                .map(Value::from)
+                .collect();
+        }
+    }
+
+    let node_count = all_nodes.len();
+    let select_name = unique_name(&options.name, "", &mut used_names);
+    let auto_name = unique_name("自动选择", "", &mut used_names);
+
+    let mut select_proxies = vec![Value::from(auto_name.clone())];
+    select_proxies.extend(top_groups);
+    select_proxies.extend(all_nodes.iter().cloned());
+
+    let mut select = Mapping::new();
+    select.insert("name".into(), select_name.clone().into());
+    select.insert("type".into(), "select".into());
+    select.insert("proxies".into(), select_proxies.into());
+
+    let mut auto = Mapping::new();
+    auto.insert("name".into(), auto_name.into());
+    auto.insert("type".into(), "url-test".into());
+    auto.insert("url".into(), "http://www.gstatic.com/generate_204".into());
+    auto.insert("interval".into(), 300.into());
+    auto.insert("proxies".into(), all_nodes.into());
+
+    let mut all_groups = vec![Value::Mapping(select), Value::Mapping(auto)];
+    all_groups.extend(groups);
+    rules.push(format!("MATCH,{select_name}").into());
+
+    let mut merged = Mapping::new();
+    merged.insert("proxies".into(), proxies.into());
+    if !providers.is_empty() {
+        merged.insert("proxy-providers".into(), Value::Mapping(providers));
+    }
+    if !rule_providers.is_empty() {
+        merged.insert("rule-providers".into(), Value::Mapping(rule_providers));
+    }
+    merged.insert("proxy-groups".into(), all_groups.into());
+    merged.insert("rules".into(), rules.into());
+
+    (
+        serde_yaml_ng::to_string(&merged).unwrap_or_default(),
+        node_count,
+    )
+}
+
+/// 读取配置中的列表字段
+fn sequence(config: &Mapping, key: &str) -> Vec<Value> {
+    config
+        .get(key)
+        .and_then(Value::as_sequence)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// 生成不冲突的名称：重名时添加来源前缀，仍冲突则追加序号
+fn unique_name(name: &str, label: &str, used: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    if used.contains(&candidate) && !label.is_empty() {
+        candidate = format!("[{label}] {name}");
+    }
+    let base = candidate.clone();
+    let mut suffix = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{base} #{suffix}");
+        suffix += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+/// 替换分组中引用的节点、分组和 provider 名称
+pub(crate) fn rename_refs(value: &mut Value, renamed: &HashMap<String, String>) {
+    match value {
+        Value::String(name) => {
+            if let Some(new_name) = renamed.get(name.as_str()) {
+                *name = new_name.clone();
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                rename_refs(item, renamed);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 远程 provider 的缓存文件放到来源独立的目录，避免不同来源写入同一文件
+fn namespace_provider(provider: &Value, dir: &str, index: usize) -> Value {
+    let mut provider = provider.clone();
+    let Some(map) = provider.as_mapping_mut() else {
+        return provider;
+    };
+    if map.get("type").and_then(Value::as_str) != Some("http") {
+        return provider;
+    }
+    let file_name = map
+        .get("path")
+        .and_then(Value::as_str)
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().to_string());
+    if let Some(file_name) = file_name {
+        map.insert(
+            "path".into(),
+            format!("./{dir}/composite-{index}/{file_name}").into(),
+        );
+    }
+    provider
+}
+
+/// 替换合并来源规则中的名称，RULE-SET 的第二段为 rule-provider 名称
+fn rename_member_rule(
+    rule: &str,
+    renamed: &HashMap<String, String>,
+    rule_sets: &HashMap<String, String>,
+) -> String {
+    let is_rule_set = rule
+        .split(',')
+        .next()
+        .is_some_and(|kind| kind.trim() == "RULE-SET");
+    rule.split(',')
+        .enumerate()
+        .map(|(i, part)| {
+            let names = if is_rule_set && i == 1 {
+                rule_sets
+            } else {
+                renamed
+            };
+            match names.get(part.trim()) {
+                Some(new_name) if i > 0 => new_name.as_str(),
+                _ => part,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 替换规则中的策略名称
+pub(crate) fn rename_rule(rule: &str, renamed: &HashMap<String, String>) -> String {
+    rule.split(',')
+        .enumerate()
+        .map(|(i, part)| match renamed.get(part.trim()) {
+            Some(new_name) if i > 0 => new_name.as_str(),
+            _ => part,
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 获取组合订阅定义文件路径
+fn composites_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("composite_profiles.json"))
+}
+
+/// 加载组合订阅定义
+fn load_composites() -> Result<Vec<CompositeProfile>> {
+    let path = composites_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存组合订阅定义
+fn save_composites(composites: &[CompositeProfile]) -> Result<()> {
+    let json_data = serde_json::to_string_pretty(composites)?;
+    fs::write(composites_path()?, json_data)?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use super::CmdResult;
+use crate::{
+    config::Config,
+    core::CoreManager,
+    feat, logging,
+    utils::{dirs, help, logging::Type},
+};
+use anyhow::Context;
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_yaml_ng::{Mapping, Value};
+use tokio::sync::Mutex;
+
+const DRY_RUN_CONFIG: &str = "liebesu-clash-dry-run.yaml";
+
+/// 预览通过后暂存的补丁，等待确认应用
+static STAGED_PATCH: Lazy<Mutex<Option<StagedPatch>>> = Lazy::new(|| Mutex::new(None));
+
+struct StagedPatch {
+    patch: Mapping,
+    base: Mapping, // 预览时的 clash 配置，应用前确认未变化
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// clash 配置的单项变化，path 为以 `.` 连接的键路径
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub kind: ConfigChangeKind,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// 补丁预览结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunResult {
+    pub valid: bool,
+    pub validation_output: String,
+    pub changes: Vec<ConfigChange>,
+    pub staged: bool, // 校验通过且有变化时暂存，可通过 apply_staged_patch 应用
+    pub checked_at: i64,
+}
+
+/// 只比较本次补丁前后的 clash 配置，其他增强步骤带来的差异不计入；
+/// 补丁合并到运行时配置的副本后用内核校验，不影响正在运行的内核
+#[tauri::command]
+pub async fn dry_run_patch_clash_config(payload: Mapping) -> CmdResult<DryRunResult> {
+    let base = Config::clash().await.latest_ref().0.clone();
+    let mut edited = base.clone();
+    for (key, value) in &payload {
+        edited.insert(key.clone(), value.clone());
+    }
+    let mut changes = Vec::new();
+    diff_mapping("", &base, &edited, &mut changes);
+
+    let runtime = Config::runtime()
+        .await
+        .latest_ref()
+        .config
+        .clone()
+        .ok_or("运行时配置不存在")?;
+    let patched = merge_patch(runtime, &payload);
+
+    let path = dirs::app_home_dir()?.join(DRY_RUN_CONFIG);
+    help::save_yaml(&path, &patched, Some("# Generated by Liebesu_Clash"))
+        .await
+        .context("写入预览配置失败")?;
+    let validation = CoreManager::global()
+        .validate_config_file(&path.to_string_lossy(), None)
+        .await;
+    let _ = std::fs::remove_file(&path);
+    let (valid, validation_output) = validation.context("校验预览配置失败")?;
+
+    let staged = valid && !changes.is_empty();
+    *STAGED_PATCH.lock().await = staged.then(|| StagedPatch {
+        patch: payload,
+        base,
+    });
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[配置预览] 变化 {} 项，校验{}",
+        changes.len(),
+        if valid { "通过" } else { "失败" }
+    );
+
+    Ok(DryRunResult {
+        valid,
+        validation_output,
+        changes,
+        staged,
+        checked_at: Local::now().timestamp(),
+    })
+}
+
+/// 应用最近一次预览通过的补丁
+#[tauri::command]
+pub async fn apply_staged_patch() -> CmdResult {
+    let mut staged = STAGED_PATCH.lock().await;
+    let Some(patch) = staged.as_ref() else {
+        return Err("没有待应用的配置补丁，请先预览".into());
+    };
+    if Config::clash().await.latest_ref().0 != patch.base {
+        *staged = None;
+        return Err("配置在预览后已变化，请重新预览".into());
+    }
+
+    let Some(patch) = staged.take() else {
+        return Err("没有待应用的配置补丁，请先预览".into());
+    };
+    feat::patch_clash(patch.patch)
+        .await
+        .context("应用配置补丁失败")?;
+    logging!(info, Type::Config, true, "[配置预览] 已应用暂存的补丁");
+    Ok(())
+}
+
+/// 放弃暂存的补丁
+#[tauri::command]
+pub async fn discard_staged_patch() -> CmdResult {
+    *STAGED_PATCH.lock().await = None;
+    Ok(())
+}
+
+// ===== 内部实现函数 =====
+
+/// 与增强时合并 clash 配置的方式一致：顶层键直接覆盖，tun 按键合并
+fn merge_patch(mut config: Mapping, patch: &Mapping) -> Mapping {
+    for (key, value) in patch {
+        if key.as_str() == Some("tun")
+            && let Some(patch_tun) = value.as_mapping()
+        {
+            let mut tun = config
+                .get("tun")
+                .and_then(Value::as_mapping)
+                .cloned()
+                .unwrap_or_default();
+            for (key, value) in patch_tun {
+                tun.insert(key.clone(), value.clone());
+            }
+            config.insert(key.clone(), tun.into());
+        } else {
+            config.insert(key.clone(), value.clone());
+        }
+    }
+    config
+}
+
+/// 递归比较映射，列表整体比较
+fn diff_mapping(prefix: &str, before: &Mapping, after: &Mapping, changes: &mut Vec<ConfigChange>) {
+    let path_of = |key: &Value| {
+        let key = match key {
+            Value::String(key) => key.clone(),
+            other => serde_yaml_ng::to_string(other)
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        };
+        if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    for (key, old) in before {
+        match after.get(key) {
+            None => changes.push(ConfigChange {
+                path: path_of(key),
+                kind: ConfigChangeKind::Removed,
+                before: Some(old.clone()),
+                after: None,
+            }),
+            Some(new) if new == old => {}
+            Some(new) => match (old.as_mapping(), new.as_mapping()) {
+                (Some(old), Some(new)) => diff_mapping(&path_of(key), old, new, changes),
+                _ => changes.push(ConfigChange {
+                    path: path_of(key),
+                    kind: ConfigChangeKind::Modified,
+                    before: Some(old.clone()),
+                    after: Some(new.clone()),
+                }),
+            },
+        }
+    }
+    for (key, new) in after {
+        if !before.contains_key(key) {
+            changes.push(ConfigChange {
+                path: path_of(key),
+                kind: ConfigChangeKind::Added,
+                before: None,
+                after: Some(new.clone()),
+            });
+        }
+    }
+}
@@ -0,0 +1,539 @@
+use super::CmdResult;
+use crate::{
+    config::{Config, IVerge},
+    core::{CoreManager, handle},
+    ipc::IpcManager,
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    env::current_exe,
+    fs,
+    io::{Cursor, Read},
+    path::PathBuf,
+    time::Duration,
+};
+use tauri::AppHandle;
+use tauri_plugin_shell::{ShellExt, process::Command};
+
+const CORES_DIR: &str = "cores";
+const REGISTRY_FILE: &str = "registry.json";
+const DOWNLOAD_TIMEOUT_SECONDS: u64 = 300;
+const BIN_EXT: &str = if cfg!(windows) { ".exe" } else { "" };
+
+/// 当前运行的内核二进制，None 表示使用默认内核
+static RUNNING_BINARY: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+/// 内核发布渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoreChannel {
+    Stable,
+    Alpha,
+    Smart,
+}
+
+impl CoreChannel {
+    fn release_api(self) -> &'static str {
+        match self {
+            Self::Stable => "https://api.github.com/repos/MetaCubeX/mihomo/releases/latest",
+            Self::Alpha => {
+                "https://api.github.com/repos/MetaCubeX/mihomo/releases/tags/Prerelease-Alpha"
+            }
+            Self::Smart => {
+                "https://api.github.com/repos/vernesong/mihomo/releases/tags/Prerelease-Alpha"
+            }
+        }
+    }
+
+    /// 服务模式下传递的内核类型
+    fn core_type(self) -> &'static str {
+        match self {
+            Self::Stable => "verge-mihomo",
+            Self::Alpha | Self::Smart => "verge-mihomo-alpha",
+        }
+    }
+}
+
+/// 已下载的内核
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledCore {
+    pub id: String,
+    pub channel: CoreChannel,
+    pub version: String,
+    pub path: String,
+    pub sha256: String,
+    pub installed_at: i64,
+}
+
+/// 可用内核：随应用打包的内核和已下载的内核
+#[derive(Debug, Clone, Serialize)]
+pub struct AvailableCore {
+    pub id: String,
+    pub channel: Option<CoreChannel>,
+    pub version: Option<String>,
+    pub bundled: bool,
+    pub path: String,
+    pub sha256: Option<String>,
+    pub installed_at: Option<i64>,
+    pub pinned_by: Vec<String>, // 固定使用该内核的订阅 uid
+    pub is_default: bool,
+}
+
+/// 内核注册表：已下载的内核、订阅固定的内核和默认内核
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CoreRegistry {
+    cores: Vec<InstalledCore>,
+    pins: HashMap<String, String>,
+    /// 未固定内核的订阅使用的内核，为空时使用设置中的内核
+    default: Option<String>,
+}
+
+/// 启动时使用的内核二进制
+pub struct CoreBinary {
+    pub core_type: String,
+    pub path: PathBuf,
+    pub bundled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GithubRelease {
+    pub tag_name: String,
+    pub body: Option<String>,
+    pub published_at: Option<String>,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    digest: Option<String>,
+}
+
+/// 列出所有可用内核
+#[tauri::command]
+pub async fn list_available_cores() -> CmdResult<Vec<AvailableCore>> {
+    let registry = load_registry().context("加载内核注册表失败")?;
+    let pinned_by = |id: &str| {
+        let mut uids: Vec<String> = registry
+            .pins
+            .iter()
+            .filter(|(_, core)| core.as_str() == id)
+            .map(|(uid, _)| uid.clone())
+            .collect();
+        uids.sort();
+        uids
+    };
+
+    let mut cores = Vec::new();
+    for name in IVerge::VALID_CLASH_CORES {
+        let path = bundled_path(name)?;
+        cores.push(AvailableCore {
+            id: name.to_string(),
+            channel: None,
+            version: None,
+            bundled: true,
+            path: path.to_string_lossy().to_string(),
+            sha256: None,
+            installed_at: None,
+            pinned_by: pinned_by(name),
+            is_default: registry.default.as_deref() == Some(name),
+        });
+    }
+    cores.extend(registry.cores.iter().map(|core| AvailableCore {
+        id: core.id.clone(),
+        channel: Some(core.channel),
+        version: Some(core.version.clone()),
+        bundled: false,
+        path: core.path.clone(),
+        sha256: Some(core.sha256.clone()),
+        installed_at: Some(core.installed_at),
+        pinned_by: pinned_by(&core.id),
+        is_default: registry.default.as_ref() == Some(&core.id),
+    }));
+    Ok(cores)
+}
+
+/// 下载指定渠道的最新内核，校验 sha256 后加入注册表
+/// 发布未提供摘要时需要传入 sha256
+#[tauri::command]
+pub async fn install_core(
+    channel: CoreChannel,
+    sha256: Option<String>,
+) -> CmdResult<InstalledCore> {
+    logging!(
+        info,
+        Type::Core,
+        true,
+        "[内核管理] 开始下载 {:?} 渠道内核",
+        channel
+    );
+    let core = download_core(channel, sha256, |_, _| {})
+        .await
+        .context("下载内核失败")?;
+    logging!(
+        info,
+        Type::Core,
+        true,
+        "[内核管理] 已安装内核 {} ({})",
+        core.id,
+        core.sha256
+    );
+    Ok(core)
+}
+
+/// 删除已下载的内核，仍被订阅固定时拒绝
+#[tauri::command]
+pub async fn remove_core(id: String) -> CmdResult<()> {
+    let mut registry = load_registry().context("加载内核注册表失败")?;
+    if registry.pins.values().any(|core| *core == id) {
+        return Err(format!("内核 {id} 仍被订阅固定使用，请先取消固定").into());
+    }
+    if registry.default.as_ref() == Some(&id) {
+        return Err(format!("内核 {id} 是默认内核").into());
+    }
+    let Some(index) = registry.cores.iter().position(|core| core.id == id) else {
+        return Err(format!("内核 {id} 不存在").into());
+    };
+    if RUNNING_BINARY.lock().as_deref()
+        == Some(PathBuf::from(&registry.cores[index].path).as_path())
+    {
+        return Err(format!("内核 {id} 正在运行").into());
+    }
+    let core = registry.cores.remove(index);
+
+    if let Some(dir) = PathBuf::from(&core.path).parent()
+        && dir.exists()
+    {
+        fs::remove_dir_all(dir).context("删除内核文件失败")?;
+    }
+    save_registry(&registry).context("保存内核注册表失败")?;
+    logging!(info, Type::Core, true, "[内核管理] 已删除内核 {}", id);
+    Ok(())
+}
+
+/// 设置订阅固定使用的内核，core_id 为空时取消固定
+/// 为当前订阅时立即重启内核
+#[tauri::command]
+pub async fn set_profile_core(uid: String, core_id: Option<String>) -> CmdResult<()> {
+    let mut registry = load_registry().context("加载内核注册表失败")?;
+    match core_id {
+        Some(id) => {
+            let known = IVerge::VALID_CLASH_CORES.contains(&id.as_str())
+                || registry.cores.iter().any(|core| core.id == id);
+            if !known {
+                return Err(format!("内核 {id} 不存在").into());
+            }
+            registry.pins.insert(uid.clone(), id);
+        }
+        None => {
+            registry.pins.remove(&uid);
+        }
+    }
+    save_registry(&registry).context("保存内核注册表失败")?;
+
+    let is_current = Config::profiles().await.latest_ref().get_current() == Some(uid);
+    if is_current {
+        apply_current_core_pin().await.context("切换内核失败")?;
+    }
+    Ok(())
+}
+
+/// 当前订阅固定的内核，未固定时为默认内核，都未设置时返回 None 并使用设置中的内核
+/// 已下载的内核每次解析时都重新校验 sha256，文件被替换时不会被启动
+pub async fn resolve_core_binary() -> Option<CoreBinary> {
+    let current = Config::profiles().await.latest_ref().get_current();
+    let registry = load_registry().ok()?;
+    let id = current
+        .and_then(|uid| registry.pins.get(&uid))
+        .or(registry.default.as_ref())?;
+
+    if IVerge::VALID_CLASH_CORES.contains(&id.as_str()) {
+        return Some(CoreBinary {
+            core_type: id.clone(),
+            path: bundled_path(id).ok()?,
+            bundled: true,
+        });
+    }
+    match registry.cores.iter().find(|core| core.id == *id) {
+        Some(core) if PathBuf::from(&core.path).exists() => match verify_installed(core) {
+            Ok(()) => Some(CoreBinary {
+                core_type: core.channel.core_type().to_string(),
+                path: PathBuf::from(&core.path),
+                bundled: false,
+            }),
+            Err(e) => {
+                logging!(
+                    warn,
+                    Type::Core,
+                    true,
+                    "[内核管理] 内核 {} 校验失败，使用设置中的内核: {}",
+                    id,
+                    e
+                );
+                None
+            }
+        },
+        _ => {
+            logging!(
+                warn,
+                Type::Core,
+                true,
+                "[内核管理] 内核 {} 不存在，使用设置中的内核",
+                id
+            );
+            None
+        }
+    }
+}
+
+/// 创建内核进程命令：使用已下载的内核时直接运行该文件，否则使用 sidecar
+pub async fn core_command(app_handle: &AppHandle, clash_core: &str) -> Result<Command> {
+    Ok(match resolve_core_binary().await {
+        Some(core) if !core.bundled => app_handle.shell().command(core.path),
+        Some(core) => app_handle.shell().sidecar(&core.core_type)?,
+        None => app_handle.shell().sidecar(clash_core)?,
+    })
+}
+
+/// 记录实际启动的内核，用于切换订阅时判断是否需要重启
+pub fn record_running_binary(path: Option<PathBuf>) {
+    *RUNNING_BINARY.lock() = path;
+}
+
+/// 当前订阅固定的内核 id
+pub(crate) async fn current_pin() -> Option<String> {
+    let current = Config::profiles().await.latest_ref().get_current()?;
+    load_registry().ok()?.pins.remove(&current)
+}
+
+/// 设置默认内核，返回之前的默认内核
+pub(crate) fn set_default_core(id: Option<String>) -> Result<Option<String>> {
+    let mut registry = load_registry()?;
+    let previous = std::mem::replace(&mut registry.default, id);
+    save_registry(&registry)?;
+    Ok(previous)
+}
+
+/// 切换订阅后，固定内核与运行中的内核不同时重启内核
+pub async fn apply_current_core_pin() -> Result<()> {
+    let target = resolve_core_binary().await.map(|core| core.path);
+    if *RUNNING_BINARY.lock() == target {
+        return Ok(());
+    }
+
+    logging!(
+        info,
+        Type::Core,
+        true,
+        "[内核管理] 订阅固定的内核已变化，重启内核: {:?}",
+        target
+    );
+    IpcManager::global().reset_capabilities();
+    CoreManager::global().restart_core().await?;
+    handle::Handle::refresh_clash();
+    Ok(())
+}
+
+// ===== 内部实现函数 =====
+
+/// 获取渠道的最新发布信息
+pub(crate) async fn fetch_release(channel: CoreChannel) -> Result<GithubRelease> {
+    Ok(http_client()?
+        .get(channel.release_api())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// 下载并校验渠道的最新内核，加入注册表，on_progress 接收已下载和总字节数
+pub(crate) async fn download_core(
+    channel: CoreChannel,
+    expected: Option<String>,
+    on_progress: impl Fn(u64, Option<u64>),
+) -> Result<InstalledCore> {
+    let release = fetch_release(channel).await?;
+    let asset = select_asset(channel, &release.assets)
+        .ok_or_else(|| anyhow!("发布 {} 中没有适用于当前平台的内核", release.tag_name))?;
+
+    let expected = expected
+        .or_else(|| {
+            asset
+                .digest
+                .as_deref()
+                .and_then(|digest| digest.strip_prefix("sha256:"))
+                .map(str::to_string)
+        })
+        .map(|hash| hash.trim().to_lowercase())
+        .ok_or_else(|| anyhow!("发布未提供 sha256 摘要，请手动提供校验值"))?;
+
+    let response = http_client()?
+        .get(&asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?;
+    let total = response.content_length();
+    let mut archive = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        archive.extend_from_slice(&chunk?);
+        on_progress(archive.len() as u64, total);
+    }
+    let actual = hex::encode(Sha256::digest(&archive));
+    if actual != expected {
+        bail!("sha256 校验失败，期望 {}，实际 {}", expected, actual);
+    }
+
+    let binary = extract(&asset.name, &archive)?;
+    let version = asset_version(&asset.name);
+    let id = format!("{}-{}", channel_name(channel), version).replace(
+        |c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '.',
+        "-",
+    );
+
+    let dir = cores_dir()?.join(&id);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("mihomo{BIN_EXT}"));
+    fs::write(&path, binary)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    let core = InstalledCore {
+        id: id.clone(),
+        channel,
+        version,
+        path: path.to_string_lossy().to_string(),
+        sha256: actual,
+        installed_at: chrono::Local::now().timestamp(),
+    };
+    let mut registry = load_registry()?;
+    registry.cores.retain(|existing| existing.id != id);
+    registry.cores.push(core.clone());
+    save_registry(&registry)?;
+    Ok(core)
+}
+
+/// 重新计算已下载内核的 sha256，与安装时记录的值比较
+fn verify_installed(core: &InstalledCore) -> Result<()> {
+    let actual = hex::encode(Sha256::digest(fs::read(&core.path)?));
+    if !actual.eq_ignore_ascii_case(&core.sha256) {
+        bail!("sha256 不匹配，记录 {}，实际 {}", core.sha256, actual);
+    }
+    Ok(())
+}
+
+fn http_client() -> Result<Client> {
+    Ok(Client::builder()
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECONDS))
+        .user_agent(format!("liebseu-clash/v{}", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+/// 选择当前平台的标准构建，跳过 compatible、v1/v2/v3 等变体
+fn select_asset(channel: CoreChannel, assets: &[GithubAsset]) -> Option<&GithubAsset> {
+    let prefix = format!("mihomo-{}-{}-", platform_os(), platform_arch());
+    assets.iter().find(|asset| {
+        let Some(rest) = asset.name.strip_prefix(&prefix) else {
+            return false;
+        };
+        let is_archive = rest.ends_with(".gz") || rest.ends_with(".zip");
+        let is_variant = ["compatible", "v1-", "v2-", "v3-", "go1"]
+            .iter()
+            .any(|variant| rest.starts_with(variant));
+        let is_smart = rest.contains("smart");
+        is_archive && !is_variant && is_smart == (channel == CoreChannel::Smart)
+    })
+}
+
+/// 资源名称中平台之后的部分，例如 v1.19.13、alpha-6f5b3d1
+fn asset_version(name: &str) -> String {
+    let prefix = format!("mihomo-{}-{}-", platform_os(), platform_arch());
+    name.trim_start_matches(&prefix)
+        .trim_end_matches(".gz")
+        .trim_end_matches(".zip")
+        .to_string()
+}
+
+fn channel_name(channel: CoreChannel) -> &'static str {
+    match channel {
+        CoreChannel::Stable => "stable",
+        CoreChannel::Alpha => "alpha",
+        CoreChannel::Smart => "smart",
+    }
+}
+
+/// 解压内核：Windows 发布为 zip，其余平台为 gz
+fn extract(name: &str, archive: &[u8]) -> Result<Vec<u8>> {
+    let mut binary = Vec::new();
+    if name.ends_with(".zip") {
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive))?;
+        let index = (0..zip.len())
+            .find(|&i| zip.by_index(i).map(|file| file.is_file()).unwrap_or(false))
+            .context("压缩包中没有内核文件")?;
+        zip.by_index(index)?.read_to_end(&mut binary)?;
+    } else {
+        flate2::read::GzDecoder::new(archive).read_to_end(&mut binary)?;
+    }
+    if binary.is_empty() {
+        bail!("内核文件为空");
+    }
+    Ok(binary)
+}
+
+fn platform_os() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    }
+}
+
+fn platform_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        "arm" => "armv7",
+        arch => arch,
+    }
+}
+
+fn bundled_path(name: &str) -> Result<PathBuf> {
+    Ok(current_exe()?.with_file_name(format!("{name}{BIN_EXT}")))
+}
+
+fn cores_dir() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(CORES_DIR))
+}
+
+fn load_registry() -> Result<CoreRegistry> {
+    let path = cores_dir()?.join(REGISTRY_FILE);
+    if !path.exists() {
+        return Ok(CoreRegistry::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_registry(registry: &CoreRegistry) -> Result<()> {
+    let dir = cores_dir()?;
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join(REGISTRY_FILE),
+        serde_json::to_string_pretty(registry)?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use super::CmdResult;
+use crate::{
+    cmd::core_registry::{self, CoreChannel, InstalledCore},
+    config::{Config, ConfigType},
+    core::{CoreManager, StartupState, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::path::Path;
+use tauri_plugin_shell::ShellExt;
+
+static UPGRADE_STATUS: Lazy<Mutex<CoreUpgradeStatus>> =
+    Lazy::new(|| Mutex::new(CoreUpgradeStatus::default()));
+
+/// 升级阶段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreUpgradeStage {
+    #[default]
+    Idle,
+    Downloading,
+    Validating,
+    Switching,
+    Completed,
+    RolledBack,
+    Failed,
+}
+
+impl CoreUpgradeStage {
+    fn is_running(self) -> bool {
+        matches!(self, Self::Downloading | Self::Validating | Self::Switching)
+    }
+}
+
+/// 升级进度
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoreUpgradeStatus {
+    pub stage: CoreUpgradeStage,
+    pub channel: Option<CoreChannel>,
+    pub version: Option<String>,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+/// 发布说明
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreReleaseNotes {
+    pub channel: CoreChannel,
+    pub version: String,
+    pub published_at: Option<String>,
+    pub notes: String,
+}
+
+/// 获取渠道最新版本的发布说明
+#[tauri::command]
+pub async fn get_core_release_notes(channel: CoreChannel) -> CmdResult<CoreReleaseNotes> {
+    let release = core_registry::fetch_release(channel)
+        .await
+        .context("获取发布说明失败")?;
+    Ok(CoreReleaseNotes {
+        channel,
+        version: release.tag_name,
+        published_at: release.published_at,
+        notes: release.body.unwrap_or_default(),
+    })
+}
+
+/// 获取内核升级进度
+#[tauri::command]
+pub async fn get_core_upgrade_status() -> CmdResult<CoreUpgradeStatus> {
+    Ok(UPGRADE_STATUS.lock().clone())
+}
+
+/// 分阶段升级内核：下载并校验渠道最新内核，用新内核验证当前配置，
+/// 切换为默认内核后重启，启动失败时回滚到之前的内核
+pub async fn staged_upgrade(channel: CoreChannel) -> CmdResult {
+    {
+        let mut status = UPGRADE_STATUS.lock();
+        if status.stage.is_running() {
+            return Err("内核升级正在进行中".into());
+        }
+        *status = CoreUpgradeStatus {
+            stage: CoreUpgradeStage::Downloading,
+            channel: Some(channel),
+            started_at: Some(Local::now().timestamp()),
+            ..CoreUpgradeStatus::default()
+        };
+    }
+
+    let result = upgrade(channel).await;
+    let mut status = UPGRADE_STATUS.lock();
+    status.finished_at = Some(Local::now().timestamp());
+    match result {
+        Ok(()) => {
+            status.stage = CoreUpgradeStage::Completed;
+            Ok(())
+        }
+        Err(e) => {
+            if status.stage != CoreUpgradeStage::RolledBack {
+                status.stage = CoreUpgradeStage::Failed;
+            }
+            status.error = Some(e.to_string());
+            Err(e.into())
+        }
+    }
+}
+
+// ===== 内部实现函数 =====
+
+fn set_stage(stage: CoreUpgradeStage) {
+    UPGRADE_STATUS.lock().stage = stage;
+}
+
+async fn upgrade(channel: CoreChannel) -> Result<()> {
+    if let Some(pin) = core_registry::current_pin().await {
+        bail!("当前订阅固定了内核 {pin}，请先取消固定");
+    }
+
+    let core = core_registry::download_core(channel, None, |downloaded, total| {
+        let mut status = UPGRADE_STATUS.lock();
+        status.downloaded = downloaded;
+        status.total = total;
+    })
+    .await?;
+    UPGRADE_STATUS.lock().version = Some(core.version.clone());
+    logging!(
+        info,
+        Type::Core,
+        true,
+        "[内核升级] 已下载 {}，开始验证配置",
+        core.id
+    );
+
+    set_stage(CoreUpgradeStage::Validating);
+    validate_with(&core).await?;
+
+    set_stage(CoreUpgradeStage::Switching);
+    let previous = core_registry::set_default_core(Some(core.id.clone()))?;
+    let started =
+        CoreManager::global().restart_core().await.and_then(|_| {
+            match CoreManager::global().startup_report().state {
+                StartupState::Ready => Ok(()),
+                state => Err(anyhow!("内核未就绪: {state:?}")),
+            }
+        });
+    if let Err(e) = started {
+        logging!(
+            warn,
+            Type::Core,
+            true,
+            "[内核升级] 新内核 {} 启动失败，回滚到 {:?}: {}",
+            core.id,
+            previous,
+            e
+        );
+        core_registry::set_default_core(previous)?;
+        CoreManager::global().restart_core().await?;
+        handle::Handle::refresh_clash();
+        set_stage(CoreUpgradeStage::RolledBack);
+        handle::Handle::notice_message("core_upgrade::rolled_back", e.to_string());
+        bail!("新内核启动失败，已回滚: {e}");
+    }
+
+    handle::Handle::refresh_clash();
+    handle::Handle::notice_message("core_upgrade::success", &core.version);
+    logging!(info, Type::Core, true, "[内核升级] 已切换到 {}", core.id);
+    Ok(())
+}
+
+/// 使用新内核验证当前运行配置
+async fn validate_with(core: &InstalledCore) -> Result<()> {
+    let app_handle = handle::Handle::global()
+        .app_handle()
+        .ok_or_else(|| anyhow!("failed to get app handle"))?;
+    let config_file = Config::generate_file(ConfigType::Run).await?;
+    let app_dir = dirs::app_home_dir()?;
+
+    let output = app_handle
+        .shell()
+        .command(Path::new(&core.path))
+        .args([
+            "-t",
+            "-d",
+            dirs::path_to_str(&app_dir)?,
+            "-f",
+            dirs::path_to_str(&config_file)?,
+        ])
+        .output()
+        .await?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let fatal = ["FATA", "fatal", "Parse config error", "level=fatal"]
+        .iter()
+        .any(|kw| stderr.contains(kw));
+    if !output.status.success() || fatal {
+        let detail = if stdout.trim().is_empty() {
+            stderr
+        } else {
+            stdout
+        };
+        bail!("当前配置未通过新内核验证: {}", detail.trim());
+    }
+    Ok(())
+}
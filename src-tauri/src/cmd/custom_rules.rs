@@ -0,0 +1,371 @@
+use super::{CmdError, CmdResult};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Sequence, Value};
+use std::{collections::HashSet, fs, net::IpAddr, path::PathBuf};
+use tokio::sync::Mutex;
+
+const CUSTOM_RULES_FILE: &str = "custom_rules.yaml";
+
+const CUSTOM_RULES_HEADER: &str = "# Custom Rules for Liebesu_Clash
+# 由自定义规则管理生成，请勿手动修改
+
+";
+
+/// 内置策略，不需要在配置中存在
+const BUILTIN_TARGETS: &[&str] = &["DIRECT", "REJECT", "REJECT-DROP", "PASS", "COMPATIBLE"];
+
+static RULES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 自定义规则类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum CustomRuleType {
+    DomainSuffix,
+    IpCidr,
+    ProcessName,
+    RuleSet,
+}
+
+impl CustomRuleType {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::DomainSuffix => "DOMAIN-SUFFIX",
+            Self::IpCidr => "IP-CIDR",
+            Self::ProcessName => "PROCESS-NAME",
+            Self::RuleSet => "RULE-SET",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "DOMAIN-SUFFIX" => Some(Self::DomainSuffix),
+            "IP-CIDR" | "IP-CIDR6" => Some(Self::IpCidr),
+            "PROCESS-NAME" => Some(Self::ProcessName),
+            "RULE-SET" => Some(Self::RuleSet),
+            _ => None,
+        }
+    }
+}
+
+/// 自定义规则
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomRule {
+    pub rule_type: CustomRuleType,
+    pub payload: String,
+    pub target: String, // 策略：代理组、节点或 DIRECT/REJECT
+    #[serde(default)]
+    pub no_resolve: bool,
+}
+
+impl CustomRule {
+    /// 生成 mihomo 规则字符串
+    fn render(&self) -> String {
+        let mut rule = format!(
+            "{},{},{}",
+            self.rule_type.as_str(),
+            self.payload.trim(),
+            self.target.trim()
+        );
+        if self.no_resolve {
+            rule.push_str(",no-resolve");
+        }
+        rule
+    }
+
+    fn parse(rule: &str) -> Option<Self> {
+        let mut parts = rule.split(',').map(str::trim);
+        let rule_type = CustomRuleType::parse(parts.next()?)?;
+        let payload = parts.next()?.to_string();
+        let target = parts.next()?.to_string();
+        let no_resolve = parts.next() == Some("no-resolve");
+        Some(Self {
+            rule_type,
+            payload,
+            target,
+            no_resolve,
+        })
+    }
+}
+
+/// 获取自定义规则（按匹配顺序）
+#[tauri::command]
+pub async fn get_custom_rules() -> CmdResult<Vec<CustomRule>> {
+    load_rules()
+        .context("加载自定义规则失败")
+        .map_err(CmdError::from)
+}
+
+/// 添加自定义规则，index 为空时追加到末尾
+#[tauri::command]
+pub async fn add_custom_rule(rule: CustomRule, index: Option<usize>) -> CmdResult<Vec<CustomRule>> {
+    validate_rule(&rule).await?;
+
+    let _guard = RULES_LOCK.lock().await;
+    let previous = load_rules().context("加载自定义规则失败")?;
+    if previous.contains(&rule) {
+        return Err(format!("规则已存在: {}", rule.render()).into());
+    }
+
+    let mut rules = previous.clone();
+    let index = index.unwrap_or(rules.len()).min(rules.len());
+    rules.insert(index, rule);
+    apply_rules(&rules, &previous).await?;
+    Ok(rules)
+}
+
+/// 删除指定位置的自定义规则
+#[tauri::command]
+pub async fn remove_custom_rule(index: usize) -> CmdResult<Vec<CustomRule>> {
+    let _guard = RULES_LOCK.lock().await;
+    let previous = load_rules().context("加载自定义规则失败")?;
+    if index >= previous.len() {
+        return Err(format!("规则不存在: {}", index).into());
+    }
+
+    let mut rules = previous.clone();
+    rules.remove(index);
+    apply_rules(&rules, &previous).await?;
+    Ok(rules)
+}
+
+/// 调整自定义规则顺序，order 为原规则下标的新排列
+#[tauri::command]
+pub async fn reorder_custom_rules(order: Vec<usize>) -> CmdResult<Vec<CustomRule>> {
+    let _guard = RULES_LOCK.lock().await;
+    let previous = load_rules().context("加载自定义规则失败")?;
+
+    let mut sorted = order.clone();
+    sorted.sort_unstable();
+    if !sorted.iter().copied().eq(0..previous.len()) {
+        return Err("规则顺序无效".into());
+    }
+
+    let rules: Vec<CustomRule> = order.iter().map(|&i| previous[i].clone()).collect();
+    apply_rules(&rules, &previous).await?;
+    Ok(rules)
+}
+
+/// 读取自定义规则合并文件中的规则，供增强链使用
+pub fn custom_rules_prepend() -> Option<Sequence> {
+    let content = fs::read_to_string(rules_path().ok()?).ok()?;
+    let merge: Mapping = serde_yaml_ng::from_str(&content).ok()?;
+    merge
+        .get("prepend-rules")
+        .and_then(Value::as_sequence)
+        .filter(|seq| !seq.is_empty())
+        .cloned()
+}
+
+/// 移除策略或规则集在当前配置中不存在的规则并记录日志，避免内核因此拒绝整个配置
+pub(crate) fn retain_resolvable_rules(rules: Sequence, config: &Mapping, source: &str) -> Sequence {
+    let names: HashSet<&str> = ["proxies", "proxy-groups"]
+        .iter()
+        .filter_map(|key| config.get(*key).and_then(Value::as_sequence))
+        .flatten()
+        .filter_map(|item| item.get("name").and_then(Value::as_str))
+        .collect();
+    let rule_sets: HashSet<&str> = config
+        .get("rule-providers")
+        .and_then(Value::as_mapping)
+        .map(|providers| providers.keys().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    rules
+        .into_iter()
+        .filter(|rule| {
+            let Some(text) = rule.as_str() else {
+                return false;
+            };
+            let parts: Vec<&str> = text.split(',').map(str::trim).collect();
+            let target = match parts.as_slice() {
+                [_, target] | [_, _, target, ..] => Some(*target),
+                _ => None,
+            };
+            let target_exists = target
+                .is_some_and(|target| BUILTIN_TARGETS.contains(&target) || names.contains(target));
+            let rule_set_exists = match parts.as_slice() {
+                ["RULE-SET", name, ..] => rule_sets.contains(name),
+                _ => true,
+            };
+            let resolvable = target_exists && rule_set_exists;
+            if !resolvable {
+                logging!(
+                    warn,
+                    Type::Config,
+                    true,
+                    "[{}] 策略或规则集不存在，已跳过规则: {}",
+                    source,
+                    text
+                );
+            }
+            resolvable
+        })
+        .collect()
+}
+
+// ===== 内部实现函数 =====
+
+/// 校验规则内容及策略、规则集是否存在于当前配置
+async fn validate_rule(rule: &CustomRule) -> CmdResult<()> {
+    let payload = rule.payload.trim();
+    let target = rule.target.trim();
+    if payload.is_empty() || target.is_empty() {
+        return Err("规则内容和策略不能为空".into());
+    }
+    if payload.contains(',') || target.contains(',') {
+        return Err("规则内容和策略不能包含逗号".into());
+    }
+    if rule.rule_type == CustomRuleType::IpCidr && !is_valid_cidr(payload) {
+        return Err(format!("无效的 IP-CIDR: {}", payload).into());
+    }
+
+    ensure_target_exists(target).await?;
+
+    let runtime = Config::runtime().await;
+    let runtime = runtime.latest_ref();
+    let Some(config) = runtime.config.as_ref() else {
+        return Ok(());
+    };
+    if rule.rule_type == CustomRuleType::RuleSet
+        && !config
+            .get("rule-providers")
+            .and_then(Value::as_mapping)
+            .is_some_and(|providers| providers.contains_key(payload))
+    {
+        return Err(format!("规则集不存在: {}", payload).into());
+    }
+    Ok(())
+}
+
+/// 校验策略为内置策略或当前配置中的节点、代理组
+pub(crate) async fn ensure_target_exists(target: &str) -> CmdResult<()> {
+    if BUILTIN_TARGETS.contains(&target) {
+        return Ok(());
+    }
+
+    let runtime = Config::runtime().await;
+    let runtime = runtime.latest_ref();
+    let Some(config) = runtime.config.as_ref() else {
+        return Ok(());
+    };
+
+    let names = |key: &str| -> Vec<String> {
+        config
+            .get(key)
+            .and_then(Value::as_sequence)
+            .map(|seq| {
+                seq.iter()
+                    .filter_map(|v| v.get("name").and_then(Value::as_str).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    if !names("proxy-groups").iter().any(|n| n == target)
+        && !names("proxies").iter().any(|n| n == target)
+    {
+        return Err(format!("策略不存在: {}", target).into());
+    }
+    Ok(())
+}
+
+pub(crate) fn is_valid_cidr(cidr: &str) -> bool {
+    let Some((ip, prefix)) = cidr.split_once('/') else {
+        return false;
+    };
+    let (Ok(ip), Ok(prefix)) = (ip.parse::<IpAddr>(), prefix.parse::<u8>()) else {
+        return false;
+    };
+    match ip {
+        IpAddr::V4(_) => prefix <= 32,
+        IpAddr::V6(_) => prefix <= 128,
+    }
+}
+
+/// 写入规则并重新生成配置，内核验证失败时恢复原规则
+async fn apply_rules(rules: &[CustomRule], previous: &[CustomRule]) -> CmdResult<()> {
+    save_rules(rules).context("保存自定义规则失败")?;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[自定义规则] 已更新，共 {} 条",
+        rules.len()
+    );
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            save_rules(previous).context("恢复自定义规则失败")?;
+            Err(format!("规则未通过内核验证，已恢复: {}", error).into())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 获取合并文件路径
+fn rules_path() -> Result<PathBuf> {
+    Ok(dirs::app_profiles_dir()?.join(CUSTOM_RULES_FILE))
+}
+
+fn load_rules() -> Result<Vec<CustomRule>> {
+    let path = rules_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let merge: Mapping = serde_yaml_ng::from_str(&fs::read_to_string(path)?)?;
+    let rules = merge
+        .get("prepend-rules")
+        .and_then(Value::as_sequence)
+        .map(|seq| {
+            seq.iter()
+                .filter_map(Value::as_str)
+                .filter_map(|rule| {
+                    let parsed = CustomRule::parse(rule);
+                    if parsed.is_none() {
+                        logging!(
+                            warn,
+                            Type::Config,
+                            true,
+                            "[自定义规则] 无法解析规则: {}",
+                            rule
+                        );
+                    }
+                    parsed
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(rules)
+}
+
+/// 以合并文件格式保存，规则插入到订阅规则之前
+fn save_rules(rules: &[CustomRule]) -> Result<()> {
+    let mut merge = Mapping::new();
+    merge.insert(
+        "prepend-rules".into(),
+        rules
+            .iter()
+            .map(|r| Value::from(r.render()))
+            .collect::<Vec<_>>()
+            .into(),
+    );
+    let content = format!(
+        "{}{}",
+        CUSTOM_RULES_HEADER,
+        serde_yaml_ng::to_string(&merge)?
+    );
+    fs::write(rules_path()?, content)?;
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    feat,
+    ipc::IpcManager,
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs, path::PathBuf};
+
+const DELAY_TARGETS_FILE: &str = "delay_test_targets.json";
+const MAX_TARGETS: usize = 5;
+
+/// 已加载的测速目标，首次使用时从文件读取
+static TARGETS: Lazy<RwLock<Option<Vec<DelayTestTarget>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 延迟测试目标
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DelayTestTarget {
+    pub name: String,
+    pub url: String,
+    /// 期望的状态码，格式同内核，如 204 或 200-299/302，为空时接受 2xx
+    #[serde(default)]
+    pub expected_status: Option<String>,
+}
+
+impl DelayTestTarget {
+    fn new(name: &str, url: &str, expected_status: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            expected_status: expected_status.map(str::to_string),
+        }
+    }
+
+    /// 状态码是否符合期望
+    pub fn accepts(&self, status: u16) -> bool {
+        match self.expected_status.as_deref().map(parse_expected) {
+            Some(Ok(ranges)) => ranges
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&status)),
+            _ => (200..300).contains(&status),
+        }
+    }
+}
+
+/// 单个测速目标的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelayTargetResult {
+    pub name: String,
+    pub url: String,
+    pub delay: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// 内置的测速目标预设
+#[tauri::command]
+pub async fn get_delay_test_presets() -> CmdResult<Vec<DelayTestTarget>> {
+    Ok(presets())
+}
+
+/// 获取当前使用的测速目标
+#[tauri::command]
+pub async fn get_delay_test_targets() -> CmdResult<Vec<DelayTestTarget>> {
+    Ok(configured_targets())
+}
+
+/// 设置测速目标，按顺序使用，多个目标时返回各目标的结果和平均延迟
+#[tauri::command]
+pub async fn set_delay_test_targets(targets: Vec<DelayTestTarget>) -> CmdResult<()> {
+    if targets.is_empty() {
+        return Err("至少需要一个测速目标".into());
+    }
+    if targets.len() > MAX_TARGETS {
+        return Err(format!("测速目标最多 {MAX_TARGETS} 个").into());
+    }
+
+    let mut names = HashSet::new();
+    let mut normalized = Vec::with_capacity(targets.len());
+    for target in targets {
+        let name = target.name.trim().to_string();
+        let url = target.url.trim().to_string();
+        if name.is_empty() {
+            return Err("测速目标名称不能为空".into());
+        }
+        if !names.insert(name.clone()) {
+            return Err(format!("测速目标名称重复: {name}").into());
+        }
+        match reqwest::Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            _ => return Err(format!("测速目标 {name} 的地址无效: {url}").into()),
+        }
+        let expected_status = match target.expected_status.as_deref().map(str::trim) {
+            Some("") | None => None,
+            Some(expected) => {
+                parse_expected(expected).map_err(|e| {
+                    CmdError::new(ErrorCode::InvalidArgument, format!("测速目标 {name} {e}"))
+                })?;
+                Some(expected.to_string())
+            }
+        };
+        normalized.push(DelayTestTarget {
+            name,
+            url,
+            expected_status,
+        });
+    }
+
+    save_targets(&normalized).context("保存测速目标失败")?;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[测速目标] 已更新: {}",
+        normalized
+            .iter()
+            .map(|target| target.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    *TARGETS.write() = Some(normalized);
+    Ok(())
+}
+
+/// 通过本机代理测试所有测速目标的延迟
+#[tauri::command]
+pub async fn test_delay_targets() -> CmdResult<Vec<DelayTargetResult>> {
+    let mut results = Vec::new();
+    for target in configured_targets() {
+        let result =
+            feat::test_delay_with(target.url.clone(), |status| target.accepts(status)).await;
+        results.push(match result {
+            Ok(delay) if delay < 10000 => DelayTargetResult {
+                name: target.name,
+                url: target.url,
+                delay: Some(delay as u64),
+                error: None,
+            },
+            Ok(_) => DelayTargetResult {
+                name: target.name,
+                url: target.url,
+                delay: None,
+                error: Some("状态码不符合期望".into()),
+            },
+            Err(e) => DelayTargetResult {
+                name: target.name,
+                url: target.url,
+                delay: None,
+                error: Some(e.to_string()),
+            },
+        });
+    }
+    Ok(results)
+}
+
+// ===== 内部实现函数 =====
+
+fn presets() -> Vec<DelayTestTarget> {
+    vec![
+        DelayTestTarget::new(
+            "Cloudflare",
+            "https://cp.cloudflare.com/generate_204",
+            Some("204"),
+        ),
+        DelayTestTarget::new(
+            "Google",
+            "https://www.gstatic.com/generate_204",
+            Some("204"),
+        ),
+        DelayTestTarget::new("GitHub", "https://github.com", Some("200")),
+    ]
+}
+
+/// 当前测速目标，未配置时使用 Cloudflare
+pub(crate) fn configured_targets() -> Vec<DelayTestTarget> {
+    if let Some(targets) = TARGETS.read().as_ref() {
+        return targets.clone();
+    }
+    let targets = match load_targets() {
+        Ok(Some(targets)) if !targets.is_empty() => targets,
+        Ok(_) => presets().into_iter().take(1).collect(),
+        Err(e) => {
+            logging!(
+                warn,
+                Type::Cmd,
+                true,
+                "[测速目标] 加载失败，使用默认目标: {}",
+                e
+            );
+            presets().into_iter().take(1).collect()
+        }
+    };
+    *TARGETS.write() = Some(targets.clone());
+    targets
+}
+
+/// 未指定测速地址时使用的默认目标，即当前测速目标中的第一项
+pub(crate) fn default_target() -> DelayTestTarget {
+    configured_targets()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| presets().remove(0))
+}
+
+/// 使用所有测速目标测试代理延迟，返回成功目标的平均延迟和各目标结果
+pub(crate) async fn test_proxy_delay_by_targets(
+    name: &str,
+    timeout: i32,
+) -> (Option<u64>, Vec<DelayTargetResult>) {
+    let ipc = IpcManager::global();
+    let mut results = Vec::new();
+    for target in configured_targets() {
+        let response = ipc
+            .test_proxy_delay(
+                name,
+                Some(target.url.clone()),
+                target.expected_status.as_deref(),
+                timeout,
+            )
+            .await;
+        let (delay, error) = match response {
+            Ok(value) => match value.get("delay").and_then(|delay| delay.as_u64()) {
+                Some(delay) if delay > 0 => (Some(delay), None),
+                _ => (
+                    None,
+                    Some(
+                        value
+                            .get("message")
+                            .and_then(|message| message.as_str())
+                            .unwrap_or("测速失败")
+                            .to_string(),
+                    ),
+                ),
+            },
+            Err(e) => (None, Some(e.to_string())),
+        };
+        results.push(DelayTargetResult {
+            name: target.name,
+            url: target.url,
+            delay,
+            error,
+        });
+    }
+    (average_delay(&results), results)
+}
+
+/// 成功目标的平均延迟
+pub(crate) fn average_delay(results: &[DelayTargetResult]) -> Option<u64> {
+    let delays: Vec<u64> = results.iter().filter_map(|result| result.delay).collect();
+    if delays.is_empty() {
+        return None;
+    }
+    Some(delays.iter().sum::<u64>() / delays.len() as u64)
+}
+
+/// 解析内核格式的期望状态码，如 204、200-299/302
+fn parse_expected(expected: &str) -> Result<Vec<(u16, u16)>, String> {
+    expected
+        .split('/')
+        .map(|part| {
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let parse = |code: &str| {
+                code.trim()
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|code| (100..=599).contains(code))
+                    .ok_or_else(|| format!("期望状态码无效: {expected}"))
+            };
+            let (start, end) = (parse(start)?, parse(end)?);
+            if start > end {
+                return Err(format!("期望状态码范围无效: {part}"));
+            }
+            Ok((start, end))
+        })
+        .collect()
+}
+
+fn targets_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(DELAY_TARGETS_FILE))
+}
+
+fn load_targets() -> Result<Option<Vec<DelayTestTarget>>> {
+    let path = targets_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+fn save_targets(targets: &[DelayTestTarget]) -> Result<()> {
+    fs::write(targets_path()?, serde_json::to_string_pretty(targets)?)?;
+    Ok(())
+}
@@ -0,0 +1,406 @@
+use super::CmdResult;
+use super::global_speed_test::{GlobalSpeedTestSummary, SpeedTestResult, TrafficInfo};
+use super::traffic_stats::TrafficRecord;
+use crate::{
+    config::{Config, PrfExtra, PrfItem, profiles_append_item_safe},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// 假数据使用的地区：(代码, 中文名, 旗帜)
+const REGIONS: &[(&str, &str, &str)] = &[
+    ("HK", "香港", "🇭🇰"),
+    ("TW", "台湾", "🇹🇼"),
+    ("JP", "日本", "🇯🇵"),
+    ("SG", "新加坡", "🇸🇬"),
+    ("KR", "韩国", "🇰🇷"),
+    ("US", "美国", "🇺🇸"),
+    ("UK", "英国", "🇬🇧"),
+    ("DE", "德国", "🇩🇪"),
+];
+
+const PROXY_TYPES: &[&str] = &["ss", "vmess", "trojan", "vless", "hysteria2"];
+
+/// 测试数据生成选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DevFixtureOptions {
+    pub profile_count: usize,
+    pub nodes_per_profile: usize,
+    pub traffic_days: u32,
+    pub with_speed_test: bool,
+}
+
+impl Default for DevFixtureOptions {
+    fn default() -> Self {
+        Self {
+            profile_count: 3,
+            nodes_per_profile: 30,
+            traffic_days: 30,
+            with_speed_test: true,
+        }
+    }
+}
+
+/// 测试数据生成结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevFixtureReport {
+    pub data_dir: String,
+    pub profile_uids: Vec<String>,
+    pub node_count: usize,
+    pub traffic_records: usize,
+    pub speed_test_results: usize,
+}
+
+/// 生成的订阅记录，用于清理
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct FixtureManifest {
+    profile_uids: Vec<String>,
+}
+
+/// 生成订阅、节点、流量历史和测速结果等测试数据
+/// 仅在 dev-fixtures 特性下编译，数据写入开发版独立的数据目录
+#[tauri::command]
+pub async fn generate_dev_fixtures(
+    options: Option<DevFixtureOptions>,
+) -> CmdResult<DevFixtureReport> {
+    let options = options.unwrap_or_default();
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[测试数据] 生成 {} 个订阅，每个 {} 个节点",
+        options.profile_count,
+        options.nodes_per_profile
+    );
+
+    let data_dir = dirs::app_home_dir()?;
+    let mut manifest = load_manifest().unwrap_or_default();
+    let mut profiles = Vec::new();
+
+    for index in 0..options.profile_count {
+        let (item, nodes) = build_fixture_profile(index, options.nodes_per_profile)
+            .await
+            .context("生成测试订阅失败")?;
+        let uid = item.uid.clone().unwrap_or_default();
+        profiles_append_item_safe(item.clone())
+            .await
+            .context("保存测试订阅失败")?;
+        manifest.profile_uids.push(uid);
+        profiles.push((item, nodes));
+    }
+    save_manifest(&manifest).context("保存测试数据清单失败")?;
+
+    let records = build_traffic_records(&profiles, options.traffic_days);
+    let traffic_records = records.len();
+    super::traffic_stats::import_traffic_records(records)
+        .await
+        .context("导入流量历史失败")?;
+
+    let mut speed_test_results = 0;
+    if options.with_speed_test {
+        let summary = build_speed_test_summary(&profiles);
+        speed_test_results = summary.all_results.len();
+        super::global_speed_test::store_speed_test_summary(summary);
+    }
+
+    Ok(DevFixtureReport {
+        data_dir: data_dir.to_string_lossy().to_string(),
+        profile_uids: profiles
+            .iter()
+            .filter_map(|(item, _)| item.uid.clone())
+            .collect(),
+        node_count: profiles.iter().map(|(_, nodes)| nodes.len()).sum(),
+        traffic_records,
+        speed_test_results,
+    })
+}
+
+/// 删除所有生成的测试订阅
+#[tauri::command]
+pub async fn clear_dev_fixtures() -> CmdResult<usize> {
+    let manifest = load_manifest().unwrap_or_default();
+    let mut removed = 0;
+
+    for uid in &manifest.profile_uids {
+        let exists = Config::profiles().await.latest_ref().get_item(uid).is_ok();
+        if !exists {
+            continue;
+        }
+        super::delete_profile(uid.clone()).await?;
+        removed += 1;
+    }
+
+    save_manifest(&FixtureManifest::default()).context("保存测试数据清单失败")?;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[测试数据] 已删除 {} 个订阅",
+        removed
+    );
+    Ok(removed)
+}
+
+// ===== 内部实现函数 =====
+
+/// 假节点信息
+#[derive(Debug, Clone)]
+struct FixtureNode {
+    name: String,
+    proxy_type: &'static str,
+    server: String,
+    port: u16,
+    region: &'static str,
+}
+
+/// 构建一个本地订阅及其节点
+async fn build_fixture_profile(
+    index: usize,
+    node_count: usize,
+) -> Result<(PrfItem, Vec<FixtureNode>)> {
+    // ThreadRng 不能跨 await 持有，先生成所有随机数据
+    let (nodes, extra) = {
+        let mut rng = rand::thread_rng();
+        let nodes: Vec<FixtureNode> = (0..node_count)
+            .map(|i| {
+                let (code, name, flag) = REGIONS[rng.gen_range(0..REGIONS.len())];
+                FixtureNode {
+                    name: format!("{flag} {name} {:02} | {code}", i + 1),
+                    proxy_type: PROXY_TYPES[rng.gen_range(0..PROXY_TYPES.len())],
+                    server: format!("{}-{:02}.fixture.invalid", code.to_lowercase(), i + 1),
+                    port: rng.gen_range(10000..60000),
+                    region: code,
+                }
+            })
+            .collect();
+
+        let total = rng.gen_range(100..1000) * GB;
+        let used = rng.gen_range(0..total);
+        let upload = used / 10;
+        let expire_days = rng.gen_range(-5..180_i64);
+        let extra = PrfExtra {
+            upload,
+            download: used - upload,
+            total,
+            expire: (chrono::Utc::now().timestamp() + expire_days * 86400).max(0) as u64,
+        };
+        (nodes, extra)
+    };
+
+    let mut item = PrfItem::from_local(
+        format!("[Fixture] 测试订阅 {}", index + 1),
+        "开发用测试数据".to_string(),
+        Some(build_profile_yaml(&nodes)?),
+        None,
+    )
+    .await?;
+    item.extra = Some(extra);
+
+    Ok((item, nodes))
+}
+
+/// 生成订阅 YAML 内容
+fn build_profile_yaml(nodes: &[FixtureNode]) -> Result<String> {
+    let proxies: Vec<serde_yaml_ng::Value> = nodes
+        .iter()
+        .map(|node| {
+            let mut proxy = serde_yaml_ng::Mapping::new();
+            proxy.insert("name".into(), node.name.clone().into());
+            proxy.insert("type".into(), node.proxy_type.into());
+            proxy.insert("server".into(), node.server.clone().into());
+            proxy.insert("port".into(), node.port.into());
+            match node.proxy_type {
+                "ss" => {
+                    proxy.insert("cipher".into(), "aes-128-gcm".into());
+                    proxy.insert("password".into(), "fixture".into());
+                }
+                "vmess" | "vless" => {
+                    proxy.insert("uuid".into(), uuid::Uuid::new_v4().to_string().into());
+                    proxy.insert("alterId".into(), 0.into());
+                    proxy.insert("cipher".into(), "auto".into());
+                }
+                _ => {
+                    proxy.insert("password".into(), "fixture".into());
+                }
+            }
+            serde_yaml_ng::Value::Mapping(proxy)
+        })
+        .collect();
+
+    let names: Vec<serde_yaml_ng::Value> = nodes.iter().map(|n| n.name.clone().into()).collect();
+    let mut groups = vec![
+        fixture_group("节点选择", "select", &names),
+        fixture_group("自动选择", "url-test", &names),
+    ];
+    for (code, name, _) in REGIONS {
+        let region_names: Vec<serde_yaml_ng::Value> = nodes
+            .iter()
+            .filter(|n| n.region == *code)
+            .map(|n| n.name.clone().into())
+            .collect();
+        if !region_names.is_empty() {
+            groups.push(fixture_group(name, "url-test", &region_names));
+        }
+    }
+
+    let mut config = serde_yaml_ng::Mapping::new();
+    config.insert("proxies".into(), proxies.into());
+    config.insert("proxy-groups".into(), groups.into());
+    config.insert(
+        "rules".into(),
+        vec![serde_yaml_ng::Value::from("MATCH,节点选择")].into(),
+    );
+    Ok(serde_yaml_ng::to_string(&config)?)
+}
+
+fn fixture_group(
+    name: &str,
+    group_type: &str,
+    proxies: &[serde_yaml_ng::Value],
+) -> serde_yaml_ng::Value {
+    let mut group = serde_yaml_ng::Mapping::new();
+    group.insert("name".into(), name.into());
+    group.insert("type".into(), group_type.into());
+    group.insert("proxies".into(), proxies.to_vec().into());
+    if group_type == "url-test" {
+        group.insert("url".into(), "http://www.gstatic.com/generate_204".into());
+        group.insert("interval".into(), 300.into());
+    }
+    serde_yaml_ng::Value::Mapping(group)
+}
+
+/// 生成按天分布的流量历史
+fn build_traffic_records(
+    profiles: &[(PrfItem, Vec<FixtureNode>)],
+    days: u32,
+) -> Vec<TrafficRecord> {
+    let mut rng = rand::thread_rng();
+    let now = chrono::Utc::now().timestamp();
+    let mut records = Vec::new();
+
+    for (item, _) in profiles {
+        let uid = item.uid.clone().unwrap_or_default();
+        let name = item.name.clone().unwrap_or_default();
+        for day in 0..days {
+            for _ in 0..rng.gen_range(1..4) {
+                let duration = rng.gen_range(600..14400_u64);
+                let download = rng.gen_range(50..2048_u64) * 1024 * 1024;
+                let upload = download / rng.gen_range(5..20);
+                let end_time = now - i64::from(day) * 86400 - rng.gen_range(0..86400);
+                let avg_speed_mbps = ((upload + download) * 8) as f64 / duration as f64 / 1e6;
+                records.push(TrafficRecord {
+                    subscription_uid: uid.clone(),
+                    subscription_name: name.clone(),
+                    upload_bytes: upload,
+                    download_bytes: download,
+                    total_bytes: upload + download,
+                    session_duration_seconds: duration,
+                    start_time: end_time - duration as i64,
+                    end_time,
+                    avg_speed_mbps,
+                    peak_speed_mbps: avg_speed_mbps * rng.gen_range(1.5..4.0),
+                });
+            }
+        }
+    }
+
+    records
+}
+
+/// 生成测速结果
+fn build_speed_test_summary(profiles: &[(PrfItem, Vec<FixtureNode>)]) -> GlobalSpeedTestSummary {
+    let mut rng = rand::thread_rng();
+    let mut all_results = Vec::new();
+    let mut results_by_profile: HashMap<String, Vec<SpeedTestResult>> = HashMap::new();
+
+    for (item, nodes) in profiles {
+        let profile_name = item.name.clone().unwrap_or_default();
+        let traffic_info = item.extra.as_ref().map(|extra| {
+            let used = extra.upload + extra.download;
+            let remaining = extra.total.saturating_sub(used);
+            TrafficInfo {
+                total: Some(extra.total),
+                used: Some(used),
+                remaining: Some(remaining),
+                remaining_percentage: Some(remaining as f64 / extra.total as f64 * 100.0),
+                expire_time: Some(extra.expire as i64),
+                expire_days: Some((extra.expire as i64 - chrono::Utc::now().timestamp()) / 86400),
+            }
+        });
+
+        for node in nodes {
+            let is_available = rng.gen_bool(0.85);
+            let latency = is_available.then(|| rng.gen_range(20..800_u64));
+            let result = SpeedTestResult {
+                node_name: node.name.clone(),
+                node_type: node.proxy_type.to_string(),
+                server: node.server.clone(),
+                port: node.port,
+                profile_name: profile_name.clone(),
+                profile_uid: item.uid.clone().unwrap_or_default(),
+                subscription_url: None,
+                latency,
+                is_available,
+                error_message: (!is_available).then(|| "连接超时".to_string()),
+                score: latency.map_or(0.0, |l| (1000.0 - l as f64) / 10.0),
+                region: Some(node.region.to_string()),
+                traffic_info: traffic_info.clone(),
+                ipv6_supported: is_available.then(|| rng.gen_bool(0.5)),
+                target_latencies: None,
+            };
+            results_by_profile
+                .entry(profile_name.clone())
+                .or_default()
+                .push(result.clone());
+            all_results.push(result);
+        }
+    }
+
+    all_results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    let successful_tests = all_results.iter().filter(|r| r.is_available).count();
+
+    GlobalSpeedTestSummary {
+        total_nodes: all_results.len(),
+        tested_nodes: all_results.len(),
+        successful_tests,
+        failed_tests: all_results.len() - successful_tests,
+        best_node: all_results.first().cloned(),
+        top_10_nodes: all_results.iter().take(10).cloned().collect(),
+        results_by_profile,
+        duration_seconds: rng.gen_range(30..300),
+        all_results,
+        run_id: uuid::Uuid::new_v4().to_string(),
+        finished_at: chrono::Local::now().timestamp(),
+    }
+}
+
+/// 获取清单文件路径
+fn manifest_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("dev_fixtures.json"))
+}
+
+/// 加载清单
+fn load_manifest() -> Result<FixtureManifest> {
+    let path = manifest_path()?;
+    if !path.exists() {
+        return Ok(FixtureManifest::default());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存清单
+fn save_manifest(manifest: &FixtureManifest) -> Result<()> {
+    let path = manifest_path()?;
+    let json_data = serde_json::to_string_pretty(manifest)?;
+    fs::write(path, json_data)?;
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use super::CmdResult;
+use crate::{
+    feat::{self, ConflictStrategy, SyncConflict, SyncReport},
+    wrap_err,
+};
+
+/// 立即与远程存储进行多设备同步
+#[tauri::command]
+pub async fn sync_now() -> CmdResult<SyncReport> {
+    wrap_err!(feat::sync_devices().await)
+}
+
+/// 获取未解决的同步冲突
+#[tauri::command]
+pub async fn get_sync_conflicts() -> CmdResult<Vec<SyncConflict>> {
+    wrap_err!(feat::get_device_sync_conflicts())
+}
+
+/// 解决同步冲突，保留本地或远程版本
+#[tauri::command]
+pub async fn resolve_sync_conflict(item: String, strategy: ConflictStrategy) -> CmdResult<()> {
+    wrap_err!(feat::resolve_device_sync_conflict(&item, strategy).await)
+}
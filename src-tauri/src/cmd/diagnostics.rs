@@ -0,0 +1,291 @@
+use super::CmdResult;
+use crate::{
+    config::Config,
+    core::{CoreManager, service},
+    ipc::{IpcManager, LogFilter, LogTimeRange, log_pipeline},
+    logging,
+    module::sysinfo::PlatformSpecification,
+    utils::{dirs, logging::Type, redact},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::Value;
+use std::{
+    fs,
+    io::Write,
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use zip::write::SimpleFileOptions;
+
+/// 诊断包选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsOptions {
+    pub include_verge_config: bool,
+    pub include_runtime_config: bool,
+    pub include_logs: bool,
+    pub log_lines: usize,
+    pub check_ports: bool,
+    pub output_dir: Option<String>, // 默认保存到日志目录
+}
+
+impl Default for DiagnosticsOptions {
+    fn default() -> Self {
+        Self {
+            include_verge_config: true,
+            include_runtime_config: true,
+            include_logs: true,
+            log_lines: 500,
+            check_ports: true,
+            output_dir: None,
+        }
+    }
+}
+
+/// 生成的诊断包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsBundle {
+    pub path: String,
+    pub size: u64,
+    pub files: Vec<String>,
+    pub generated_at: i64,
+}
+
+/// 服务与内核状态
+#[derive(Debug, Clone, Serialize)]
+struct ServiceReport {
+    running_mode: String,
+    service_available: bool,
+    service_error: Option<String>,
+    service_version: Option<String>,
+    core_version: Option<serde_json::Value>,
+    core_version_error: Option<String>,
+}
+
+/// 端口检查结果
+#[derive(Debug, Clone, Serialize)]
+struct PortReport {
+    name: &'static str,
+    port: u16,
+    bindable: bool,  // 端口空闲
+    listening: bool, // 端口有程序监听，内核运行时应为 true
+}
+
+/// 生成脱敏后的诊断包，供用户附加到问题反馈中
+#[tauri::command]
+pub async fn generate_diagnostics_bundle(
+    options: Option<DiagnosticsOptions>,
+) -> CmdResult<DiagnosticsBundle> {
+    let options = options.unwrap_or_default();
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    entries.push(("environment.txt".to_string(), collect_environment()));
+    entries.push((
+        "service.json".to_string(),
+        to_json(&collect_service_report().await),
+    ));
+
+    if options.include_verge_config {
+        let verge = Config::verge().await;
+        let verge = serde_yaml_ng::to_value(&**verge.latest_ref()).unwrap_or_default();
+        entries.push((
+            "verge.yaml".to_string(),
+            to_yaml(redact::redact_yaml(verge)),
+        ));
+    }
+    if options.include_runtime_config {
+        let runtime = Config::runtime().await;
+        let config = runtime.latest_ref().config.clone();
+        let content = match config {
+            Some(config) => to_yaml(redact::redact_yaml(Value::Mapping(config))),
+            None => "# 当前没有运行时配置\n".to_string(),
+        };
+        entries.push(("runtime.yaml".to_string(), content));
+    }
+    if options.check_ports {
+        entries.push(("ports.json".to_string(), to_json(&check_ports().await)));
+    }
+    if options.include_logs {
+        let lines = options.log_lines.max(1);
+        entries.push(("app.log".to_string(), tail_app_log(lines)));
+        entries.push(("clash.log".to_string(), tail_core_log(lines).await));
+    }
+
+    let output_dir = match options.output_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::app_logs_dir()?,
+    };
+    let path = tokio::task::spawn_blocking(move || write_bundle(&output_dir, &entries))
+        .await?
+        .context("生成诊断包失败")?;
+
+    logging!(
+        info,
+        Type::System,
+        true,
+        "[诊断] 已生成诊断包: {}",
+        path.0.display()
+    );
+    Ok(DiagnosticsBundle {
+        size: fs::metadata(&path.0).map(|m| m.len()).unwrap_or_default(),
+        path: path.0.to_string_lossy().to_string(),
+        files: path.1,
+        generated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+// ===== 内部实现函数 =====
+
+fn collect_environment() -> String {
+    let mut lines = vec![format!("{:?}", PlatformSpecification::new_sync())];
+    lines.push(format!(
+        "Portable: {}",
+        dirs::PORTABLE_FLAG.get().copied().unwrap_or_default()
+    ));
+    lines.push(format!(
+        "Generated At: {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S %z")
+    ));
+    for key in [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "NO_PROXY",
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+        "no_proxy",
+        "LANG",
+        "XDG_CURRENT_DESKTOP",
+        "XDG_SESSION_TYPE",
+    ] {
+        if let Ok(value) = std::env::var(key) {
+            lines.push(format!("{key}={}", redact::redact_text(&value)));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+async fn collect_service_report() -> ServiceReport {
+    let service = service::is_service_available().await;
+    let service_version = match &service {
+        Ok(()) => service::check_service_version().await.ok(),
+        Err(_) => None,
+    };
+    let core_version = IpcManager::global().get_version().await;
+
+    ServiceReport {
+        running_mode: CoreManager::global().get_running_mode().to_string(),
+        service_available: service.is_ok(),
+        service_error: service.err().map(|e| e.to_string()),
+        service_version,
+        core_version_error: core_version.as_ref().err().map(|e| e.to_string()),
+        core_version: core_version.ok(),
+    }
+}
+
+async fn check_ports() -> Vec<PortReport> {
+    let clash = Config::clash().await;
+    let info = clash.latest_ref().get_client_info();
+    let controller = info
+        .server
+        .rsplit_once(':')
+        .and_then(|(_, port)| port.parse().ok());
+
+    let mut ports = vec![
+        ("mixed-port", info.mixed_port),
+        ("socks-port", info.socks_port),
+        ("port", info.port),
+    ];
+    if let Some(port) = controller {
+        ports.push(("external-controller", port));
+    }
+
+    ports
+        .into_iter()
+        .filter(|(_, port)| *port != 0)
+        .map(|(name, port)| {
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+            PortReport {
+                name,
+                port,
+                bindable: TcpListener::bind(addr).is_ok(),
+                listening: TcpStream::connect_timeout(&addr, Duration::from_millis(300)).is_ok(),
+            }
+        })
+        .collect()
+}
+
+/// 最新应用日志的最后若干行
+fn tail_app_log(lines: usize) -> String {
+    let latest = dirs::app_logs_dir().ok().and_then(|dir| {
+        fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+            .max_by_key(|e| e.metadata().and_then(|m| m.modified()).ok())
+    });
+    let Some(entry) = latest else {
+        return "# 未找到应用日志\n".to_string();
+    };
+
+    let content = fs::read(entry.path()).unwrap_or_default();
+    let content = String::from_utf8_lossy(&content);
+    let all: Vec<&str> = content.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    all[start..]
+        .iter()
+        .map(|line| redact::redact_text(line) + "\n")
+        .collect()
+}
+
+/// 内核日志的最后若干条
+async fn tail_core_log(lines: usize) -> String {
+    let records = tokio::task::spawn_blocking(move || {
+        log_pipeline::query_logs(&LogFilter::default(), LogTimeRange::default(), lines)
+    })
+    .await
+    .unwrap_or_default();
+
+    records
+        .iter()
+        .rev()
+        .map(|r| {
+            let time = chrono::DateTime::from_timestamp_millis(r.time)
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%H:%M:%S%.3f")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            format!("{time} [{}] {}\n", r.level, redact::redact_text(&r.message))
+        })
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn to_yaml(value: Value) -> String {
+    serde_yaml_ng::to_string(&value).unwrap_or_default()
+}
+
+/// 写入 zip，返回文件路径和包含的文件列表
+fn write_bundle(dir: &Path, entries: &[(String, String)]) -> Result<(PathBuf, Vec<String>)> {
+    fs::create_dir_all(dir)?;
+    let now = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let path = dir.join(format!("diagnostics-{now}.zip"));
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(&path)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in entries {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+
+    Ok((path, entries.iter().map(|(name, _)| name.clone()).collect()))
+}
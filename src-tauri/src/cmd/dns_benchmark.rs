@@ -0,0 +1,710 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    logging,
+    utils::{
+        dirs, fetcher,
+        logging::Type,
+        network::{ProxyType, resolve_proxy_url},
+    },
+};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Local;
+use futures::{StreamExt, stream};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::Mutex,
+    time::timeout,
+};
+
+const DNS_CONFIG_FILE: &str = "dns_config.yaml";
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+const LATENCY_ROUNDS: usize = 3;
+const MAX_UPSTREAMS: usize = 32;
+const BENCHMARK_CONCURRENCY: usize = 4;
+/// 生成配置时每类选用的上游数量
+const SELECTED_UPSTREAMS: usize = 3;
+/// 延迟测试使用的域名
+const LATENCY_DOMAINS: &[&str] = &["www.baidu.com", "www.apple.com", "www.cloudflare.com"];
+/// 污染探测：这些域名的随机子域名应返回 NXDOMAIN，返回 A 记录说明应答被篡改
+const POISON_PROBE_ZONES: &[&str] = &["google.com", "twitter.com", "youtube.com"];
+/// 配置中没有 default-nameserver 时用于解析上游域名
+const BOOTSTRAP_NAMESERVERS: &[&str] = &["223.5.5.5", "119.29.29.29"];
+
+static BENCHMARK_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static LAST_REPORT: Lazy<Mutex<Option<DnsBenchmarkReport>>> = Lazy::new(|| Mutex::new(None));
+
+/// 加密 DNS 协议
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsProtocol {
+    Doh, // https://
+    Dot, // tls://
+    Doq, // quic://
+}
+
+/// 查询路径
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsPath {
+    Direct,
+    Proxy,
+}
+
+/// 单条路径的测试结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DnsPathResult {
+    pub tested: bool,
+    pub latency_ms: Option<u64>, // 复用连接后成功查询的中位延迟
+    pub connect_ms: Option<u64>, // 首次查询耗时，包含建立连接与握手
+    pub success: usize,
+    pub attempts: usize,
+    pub poisoned: Option<bool>, // None 表示污染探测未完成
+    pub error: Option<String>,
+}
+
+impl DnsPathResult {
+    fn untested(reason: &str) -> Self {
+        Self {
+            error: Some(reason.into()),
+            ..Self::default()
+        }
+    }
+
+    /// 可达且未检测到污染
+    fn usable_latency(&self) -> Option<u64> {
+        self.latency_ms.filter(|_| self.poisoned == Some(false))
+    }
+}
+
+/// 上游测试结果，rank 为空表示两条路径均不可用或存在污染
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsBenchmarkEntry {
+    pub upstream: String,
+    pub protocol: DnsProtocol,
+    pub direct: DnsPathResult,
+    pub proxied: DnsPathResult,
+    pub best_path: Option<DnsPath>,
+    pub best_latency_ms: Option<u64>,
+    pub rank: Option<usize>,
+}
+
+/// DNS 基准测试报告
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsBenchmarkReport {
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub proxy_url: Option<String>,
+    pub entries: Vec<DnsBenchmarkEntry>,
+}
+
+/// 测试 DoH / DoT / DoQ 上游的延迟与抗污染能力，分别通过直连和本机代理测试后排序
+/// 上游格式与 mihomo nameserver 一致，如 https://dns.google/dns-query、tls://1.1.1.1、quic://dns.adguard-dns.com
+#[tauri::command]
+pub async fn run_dns_benchmark(upstreams: Vec<String>) -> CmdResult<DnsBenchmarkReport> {
+    let mut parsed = Vec::new();
+    for raw in upstreams.iter().map(|u| u.trim()).filter(|u| !u.is_empty()) {
+        if parsed.iter().all(|u: &Upstream| u.raw != raw) {
+            parsed.push(Upstream::parse(raw)?);
+        }
+    }
+    if parsed.is_empty() {
+        return Err(invalid("请至少提供一个 DNS 上游".into()));
+    }
+    if parsed.len() > MAX_UPSTREAMS {
+        return Err(invalid(format!("一次最多测试 {MAX_UPSTREAMS} 个上游")));
+    }
+
+    let _guard = BENCHMARK_LOCK
+        .try_lock()
+        .map_err(|_| "DNS 基准测试正在进行")?;
+    let started_at = Local::now().timestamp();
+    let proxy_url = resolve_proxy_url(ProxyType::Localhost).await;
+
+    let mut entries: Vec<DnsBenchmarkEntry> = stream::iter(parsed)
+        .map(|upstream| {
+            let proxy_url = proxy_url.clone();
+            async move { benchmark_upstream(upstream, proxy_url.as_deref()).await }
+        })
+        .buffer_unordered(BENCHMARK_CONCURRENCY)
+        .collect()
+        .await;
+    rank_entries(&mut entries);
+
+    let report = DnsBenchmarkReport {
+        started_at,
+        finished_at: Local::now().timestamp(),
+        proxy_url,
+        entries,
+    };
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[DNS基准测试] 测试 {} 个上游, 可用 {} 个",
+        report.entries.len(),
+        report.entries.iter().filter(|e| e.rank.is_some()).count()
+    );
+    *LAST_REPORT.lock().await = Some(report.clone());
+    Ok(report)
+}
+
+/// 获取最近一次 DNS 基准测试报告
+#[tauri::command]
+pub async fn get_dns_benchmark_report() -> CmdResult<Option<DnsBenchmarkReport>> {
+    Ok(LAST_REPORT.lock().await.clone())
+}
+
+/// 按最近一次基准测试结果写入 dns_config.yaml，保留其余 DNS 设置与 hosts，返回写入的内容
+/// 生成的文件与 save_dns_config 格式一致，通过 apply_dns_config 生效
+#[tauri::command]
+pub async fn generate_dns_config_from_benchmark() -> CmdResult<String> {
+    let report = LAST_REPORT
+        .lock()
+        .await
+        .clone()
+        .ok_or_else(|| invalid("请先运行 DNS 基准测试".into()))?;
+
+    let ranked: Vec<&DnsBenchmarkEntry> =
+        report.entries.iter().filter(|e| e.rank.is_some()).collect();
+    if ranked.is_empty() {
+        return Err(invalid("没有可用且未被污染的 DNS 上游".into()));
+    }
+    let nameservers: Vec<&str> = ranked
+        .iter()
+        .take(SELECTED_UPSTREAMS)
+        .map(|e| e.upstream.as_str())
+        .collect();
+    // 有上游只能通过代理获得干净结果时，按规则经代理查询
+    let respect_rules = ranked
+        .iter()
+        .take(SELECTED_UPSTREAMS)
+        .any(|e| e.best_path == Some(DnsPath::Proxy));
+
+    // 代理节点域名与直连域名必须直连解析
+    let mut direct: Vec<&DnsBenchmarkEntry> = report
+        .entries
+        .iter()
+        .filter(|e| e.direct.usable_latency().is_some())
+        .collect();
+    direct.sort_by_key(|e| e.direct.latency_ms);
+    let direct: Vec<&str> = direct
+        .iter()
+        .take(SELECTED_UPSTREAMS)
+        .map(|e| e.upstream.as_str())
+        .collect();
+
+    let path = dirs::app_home_dir()?.join(DNS_CONFIG_FILE);
+    let mut file = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => {
+            serde_yaml_ng::from_str::<Mapping>(&content).context("现有 DNS 配置解析失败")?
+        }
+        Err(_) => Mapping::new(),
+    };
+    // 与增强链一致：没有 dns 键时整个文件即为 dns 配置
+    let mut dns = match file.remove("dns") {
+        Some(Value::Mapping(dns)) => dns,
+        _ => std::mem::take(&mut file),
+    };
+    if dns.is_empty() {
+        dns.insert("enable".into(), true.into());
+        dns.insert("enhanced-mode".into(), "fake-ip".into());
+        dns.insert("fake-ip-range".into(), "198.18.0.1/16".into());
+    }
+    if !dns.contains_key("default-nameserver") {
+        dns.insert(
+            "default-nameserver".into(),
+            string_sequence(BOOTSTRAP_NAMESERVERS),
+        );
+    }
+    dns.insert("nameserver".into(), string_sequence(&nameservers));
+    if !direct.is_empty() {
+        dns.insert("proxy-server-nameserver".into(), string_sequence(&direct));
+        dns.insert("direct-nameserver".into(), string_sequence(&direct));
+    }
+    if respect_rules && !dns.contains_key("proxy-server-nameserver") {
+        return Err(invalid(
+            "所选上游需经代理查询，但没有可直连的上游用于解析代理节点".into(),
+        ));
+    }
+    dns.insert("respect-rules".into(), respect_rules.into());
+    file.insert("dns".into(), dns.into());
+
+    let content = serde_yaml_ng::to_string(&file).context("序列化 DNS 配置失败")?;
+    tokio::fs::write(&path, &content)
+        .await
+        .context("写入 DNS 配置失败")?;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[DNS基准测试] 已生成 DNS 配置: nameserver={:?}, respect-rules={}",
+        nameservers,
+        respect_rules
+    );
+    Ok(content)
+}
+
+// ===== 内部实现函数 =====
+
+fn invalid(message: String) -> CmdError {
+    CmdError::new(ErrorCode::InvalidArgument, message)
+}
+
+fn string_sequence(items: &[&str]) -> Value {
+    Value::Sequence(items.iter().map(|&s| s.into()).collect())
+}
+
+#[derive(Debug, Clone)]
+struct Upstream {
+    raw: String,
+    protocol: DnsProtocol,
+    host: String, // IPv6 地址不含方括号
+    port: u16,
+    url: url::Url,
+}
+
+impl Upstream {
+    fn parse(raw: &str) -> CmdResult<Self> {
+        // mihomo 允许用 # 指定出站，测试时忽略
+        let address = raw.split('#').next().unwrap_or_default();
+        let url =
+            url::Url::parse(address).map_err(|_| invalid(format!("DNS 上游格式无效: {raw}")))?;
+        let (protocol, default_port) = match url.scheme() {
+            "https" => (DnsProtocol::Doh, 443),
+            "tls" => (DnsProtocol::Dot, 853),
+            "quic" => (DnsProtocol::Doq, 853),
+            scheme => {
+                return Err(invalid(format!(
+                    "不支持的 DNS 协议 {scheme}，仅支持 https:// tls:// quic://"
+                )));
+            }
+        };
+        let host = match url.host() {
+            Some(url::Host::Domain(domain)) => domain.to_string(),
+            Some(url::Host::Ipv4(ip)) => ip.to_string(),
+            Some(url::Host::Ipv6(ip)) => ip.to_string(),
+            None => return Err(invalid(format!("DNS 上游缺少主机: {raw}"))),
+        };
+        Ok(Self {
+            raw: raw.to_string(),
+            protocol,
+            host,
+            port: url.port().unwrap_or(default_port),
+            url,
+        })
+    }
+
+    fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+struct DnsAnswer {
+    rcode: u8,
+    addresses: Vec<Ipv4Addr>,
+}
+
+async fn benchmark_upstream(upstream: Upstream, proxy_url: Option<&str>) -> DnsBenchmarkEntry {
+    let direct = benchmark_path(&upstream, None).await;
+    let proxied = match (upstream.protocol, proxy_url) {
+        (DnsProtocol::Doq, _) => DnsPathResult::untested("DoQ 基于 UDP，无法通过 HTTP 代理测试"),
+        (_, None) => DnsPathResult::untested("本机代理不可用"),
+        (_, Some(proxy_url)) => benchmark_path(&upstream, Some(proxy_url)).await,
+    };
+    DnsBenchmarkEntry {
+        upstream: upstream.raw,
+        protocol: upstream.protocol,
+        direct,
+        proxied,
+        best_path: None,
+        best_latency_ms: None,
+        rank: None,
+    }
+}
+
+/// 可用路径中取延迟较低者，相同时优先直连；按最佳延迟排序并编号
+fn rank_entries(entries: &mut [DnsBenchmarkEntry]) {
+    for entry in entries.iter_mut() {
+        let candidates = [
+            (DnsPath::Direct, entry.direct.usable_latency()),
+            (DnsPath::Proxy, entry.proxied.usable_latency()),
+        ];
+        if let Some((path, latency)) = candidates
+            .into_iter()
+            .filter_map(|(path, latency)| latency.map(|l| (path, l)))
+            .min_by_key(|&(_, latency)| latency)
+        {
+            entry.best_path = Some(path);
+            entry.best_latency_ms = Some(latency);
+        }
+    }
+    entries.sort_by_key(|e| (e.best_latency_ms.is_none(), e.best_latency_ms));
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.rank = entry.best_latency_ms.map(|_| index + 1);
+    }
+}
+
+async fn benchmark_path(upstream: &Upstream, proxy_url: Option<&str>) -> DnsPathResult {
+    let mut result = DnsPathResult {
+        tested: true,
+        ..DnsPathResult::default()
+    };
+    let mut session = DnsSession::new(upstream, proxy_url);
+
+    // 首次查询包含握手，单独记录，延迟只统计复用连接的查询，避免各协议握手开销不同影响排序
+    let started = Instant::now();
+    match session.query(LATENCY_DOMAINS[0]).await {
+        Ok(_) => result.connect_ms = Some(started.elapsed().as_millis() as u64),
+        Err(e) => result.error = Some(e.to_string()),
+    }
+
+    let mut latencies = Vec::new();
+    for round in 0..LATENCY_ROUNDS {
+        let domain = LATENCY_DOMAINS[round % LATENCY_DOMAINS.len()];
+        result.attempts += 1;
+        let started = Instant::now();
+        match session.query(domain).await {
+            Ok(answer) if answer.rcode == 0 => {
+                latencies.push(started.elapsed().as_millis() as u64);
+                result.success += 1;
+            }
+            Ok(answer) => result.error = Some(format!("应答错误码 {}", answer.rcode)),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+    }
+    if latencies.is_empty() {
+        return result;
+    }
+    latencies.sort_unstable();
+    result.latency_ms = Some(latencies[latencies.len() / 2]);
+
+    let mut probed = false;
+    let mut poisoned = false;
+    for zone in POISON_PROBE_ZONES {
+        let label: String = (0..12).map(|_| fastrand::lowercase()).collect();
+        if let Ok(answer) = session.query(&format!("{label}.{zone}")).await {
+            probed = true;
+            if answer.rcode == 0 && !answer.addresses.is_empty() {
+                poisoned = true;
+                break;
+            }
+        }
+    }
+    result.poisoned = probed.then_some(poisoned);
+    result
+}
+
+/// 已建立的上游连接：DoH 使用共享客户端的连接池，DoT / DoQ 保持单条连接
+enum DnsConnection {
+    Doh(reqwest::Client),
+    Dot(Box<tokio_rustls::client::TlsStream<TcpStream>>),
+    Doq(quinn::Connection),
+}
+
+/// 单条路径上的查询会话，各协议都复用同一连接，查询出错或超时后重新连接
+struct DnsSession<'a> {
+    upstream: &'a Upstream,
+    proxy_url: Option<&'a str>,
+    connection: Option<DnsConnection>,
+}
+
+impl<'a> DnsSession<'a> {
+    fn new(upstream: &'a Upstream, proxy_url: Option<&'a str>) -> Self {
+        Self {
+            upstream,
+            proxy_url,
+            connection: None,
+        }
+    }
+
+    async fn query(&mut self, name: &str) -> Result<DnsAnswer> {
+        // 连接在查询期间取出，失败或超时时随之丢弃
+        let exchange = async {
+            let mut connection = match self.connection.take() {
+                Some(connection) => connection,
+                None => connect(self.upstream, self.proxy_url).await?,
+            };
+            let answer = match &mut connection {
+                DnsConnection::Doh(client) => query_doh(client, self.upstream, name).await?,
+                DnsConnection::Dot(tls) => query_dot(tls, name).await?,
+                DnsConnection::Doq(connection) => query_doq(connection, name).await?,
+            };
+            self.connection = Some(connection);
+            Ok::<_, anyhow::Error>(answer)
+        };
+        timeout(QUERY_TIMEOUT, exchange)
+            .await
+            .map_err(|_| anyhow!("查询超时"))?
+    }
+}
+
+async fn connect(upstream: &Upstream, proxy_url: Option<&str>) -> Result<DnsConnection> {
+    match upstream.protocol {
+        DnsProtocol::Doh => Ok(DnsConnection::Doh(fetcher::shared_client(
+            proxy_url, false,
+        )?)),
+        DnsProtocol::Dot => {
+            let tcp = connect_tcp(upstream, proxy_url).await?;
+            let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config(None)?));
+            let server_name = rustls::pki_types::ServerName::try_from(upstream.host.clone())?;
+            let tls = connector.connect(server_name, tcp).await?;
+            Ok(DnsConnection::Dot(Box::new(tls)))
+        }
+        DnsProtocol::Doq => {
+            let addr: SocketAddr = tokio::net::lookup_host((upstream.host.as_str(), upstream.port))
+                .await?
+                .next()
+                .ok_or_else(|| anyhow!("无法解析 {}", upstream.host))?;
+            let bind: SocketAddr = if addr.is_ipv6() {
+                "[::]:0".parse()?
+            } else {
+                "0.0.0.0:0".parse()?
+            };
+            let mut endpoint = quinn::Endpoint::client(bind)?;
+            let crypto =
+                quinn::crypto::rustls::QuicClientConfig::try_from(tls_config(Some(b"doq"))?)?;
+            endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(crypto)));
+            let connection = endpoint.connect(addr, &upstream.host)?.await?;
+            Ok(DnsConnection::Doq(connection))
+        }
+    }
+}
+
+/// RFC 8484：POST application/dns-message，消息 ID 固定为 0
+async fn query_doh(client: &reqwest::Client, upstream: &Upstream, name: &str) -> Result<DnsAnswer> {
+    let body = client
+        .post(upstream.url.as_str())
+        .header(reqwest::header::CONTENT_TYPE, "application/dns-message")
+        .header(reqwest::header::ACCEPT, "application/dns-message")
+        .body(build_query(0, name))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    parse_response(&body, 0)
+}
+
+/// RFC 7858：TLS 上以 2 字节长度前缀传输 DNS 消息，同一连接依次发送多个查询
+async fn query_dot(
+    tls: &mut tokio_rustls::client::TlsStream<TcpStream>,
+    name: &str,
+) -> Result<DnsAnswer> {
+    let id = fastrand::u16(..);
+    tls.write_all(&frame(build_query(id, name))).await?;
+    let len = tls.read_u16().await? as usize;
+    let mut packet = vec![0; len];
+    tls.read_exact(&mut packet).await?;
+    parse_response(&packet, id)
+}
+
+/// RFC 9250：同一连接上每个查询使用独立的双向流，消息 ID 固定为 0
+async fn query_doq(connection: &quinn::Connection, name: &str) -> Result<DnsAnswer> {
+    let (mut send, mut recv) = connection.open_bi().await?;
+    send.write_all(&frame(build_query(0, name))).await?;
+    send.finish()?;
+    let data = recv.read_to_end(u16::MAX as usize + 2).await?;
+
+    let len = data
+        .get(..2)
+        .map(|prefix| u16::from_be_bytes([prefix[0], prefix[1]]) as usize)
+        .ok_or_else(|| anyhow!("应答过短"))?;
+    let packet = data
+        .get(2..2 + len)
+        .ok_or_else(|| anyhow!("应答长度不匹配"))?;
+    parse_response(packet, 0)
+}
+
+/// 直连或通过本机代理的 HTTP CONNECT 建立 TCP 连接
+async fn connect_tcp(upstream: &Upstream, proxy_url: Option<&str>) -> Result<TcpStream> {
+    let Some(proxy_url) = proxy_url else {
+        return Ok(TcpStream::connect((upstream.host.as_str(), upstream.port)).await?);
+    };
+    let proxy = url::Url::parse(proxy_url)?;
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| anyhow!("代理地址无效: {proxy_url}"))?;
+    let proxy_port = proxy
+        .port_or_known_default()
+        .ok_or_else(|| anyhow!("代理地址无效: {proxy_url}"))?;
+
+    let mut stream = TcpStream::connect((proxy_host, proxy_port)).await?;
+    let authority = upstream.authority();
+    stream
+        .write_all(format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n\r\n").as_bytes())
+        .await?;
+
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > 4096 {
+            bail!("代理响应过长");
+        }
+        response.push(stream.read_u8().await?);
+    }
+    let status = String::from_utf8_lossy(&response);
+    if status.split_whitespace().nth(1) != Some("200") {
+        bail!(
+            "代理拒绝连接: {}",
+            status.lines().next().unwrap_or_default()
+        );
+    }
+    Ok(stream)
+}
+
+fn tls_config(alpn: Option<&[u8]>) -> Result<rustls::ClientConfig> {
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let builder = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ));
+    // QUIC 只能使用 TLS 1.3
+    let builder = if alpn.is_some() {
+        builder.with_protocol_versions(&[&rustls::version::TLS13])?
+    } else {
+        builder.with_safe_default_protocol_versions()?
+    };
+    let mut config = builder.with_root_certificates(roots).with_no_client_auth();
+    if let Some(alpn) = alpn {
+        config.alpn_protocols = vec![alpn.to_vec()];
+    }
+    Ok(config)
+}
+
+fn frame(query: Vec<u8>) -> Vec<u8> {
+    let mut framed = (query.len() as u16).to_be_bytes().to_vec();
+    framed.extend(query);
+    framed
+}
+
+/// 构造 A 记录查询报文
+fn build_query(id: u16, name: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(18 + name.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]); // RD，QDCOUNT = 1
+    for label in name.trim_end_matches('.').split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&[0, 1, 0, 1]); // QTYPE = A，QCLASS = IN
+    packet
+}
+
+fn parse_response(packet: &[u8], id: u16) -> Result<DnsAnswer> {
+    let truncated = || anyhow!("应答格式无效");
+    let read_u16 = |offset: usize| -> Result<u16> {
+        packet
+            .get(offset..offset + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .ok_or_else(truncated)
+    };
+
+    if read_u16(0)? != id {
+        bail!("应答 ID 不匹配");
+    }
+    let rcode = packet.get(3).ok_or_else(truncated)? & 0x0F;
+    let question_count = read_u16(4)?;
+    let answer_count = read_u16(6)?;
+
+    let mut offset = 12;
+    for _ in 0..question_count {
+        offset = skip_name(packet, offset)? + 4;
+    }
+    let mut addresses = Vec::new();
+    for _ in 0..answer_count {
+        offset = skip_name(packet, offset)?;
+        let record_type = read_u16(offset)?;
+        let data_len = read_u16(offset + 8)? as usize;
+        offset += 10;
+        let data = packet
+            .get(offset..offset + data_len)
+            .ok_or_else(truncated)?;
+        if record_type == 1 && data_len == 4 {
+            addresses.push(Ipv4Addr::new(data[0], data[1], data[2], data[3]));
+        }
+        offset += data_len;
+    }
+    Ok(DnsAnswer { rcode, addresses })
+}
+
+/// 跳过域名字段，压缩指针占 2 字节
+fn skip_name(packet: &[u8], mut offset: usize) -> Result<usize> {
+    loop {
+        let len = *packet.get(offset).ok_or_else(|| anyhow!("应答格式无效"))? as usize;
+        match len {
+            0 => return Ok(offset + 1),
+            len if len & 0xC0 == 0xC0 => return Ok(offset + 2),
+            len => offset += len + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// example.com 的应答：一条 CNAME 与一条 A 记录，名称均使用压缩指针
+    fn sample_response(id: u16, rcode: u8) -> Vec<u8> {
+        let mut packet = build_query(id, "example.com");
+        packet[2] = 0x81;
+        packet[3] = 0x80 | rcode;
+        packet[7] = 2; // ANCOUNT = 2
+        packet.extend_from_slice(&[0xC0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, 2, 0xC0, 12]);
+        packet.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+        packet
+    }
+
+    #[test]
+    fn build_query_encodes_header_and_question() {
+        let packet = build_query(0x1234, "www.example.com.");
+        assert_eq!(
+            &packet[..12],
+            &[0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(&packet[12..17], b"\x03www\x07");
+        assert_eq!(&packet[packet.len() - 5..], &[0, 0, 1, 0, 1]);
+        assert_eq!(skip_name(&packet, 12).unwrap(), packet.len() - 4);
+    }
+
+    #[test]
+    fn parse_response_collects_a_records() {
+        let answer = parse_response(&sample_response(7, 0), 7).unwrap();
+        assert_eq!(answer.rcode, 0);
+        assert_eq!(answer.addresses, vec![Ipv4Addr::new(93, 184, 216, 34)]);
+
+        let answer = parse_response(&sample_response(7, 3), 7).unwrap();
+        assert_eq!(answer.rcode, 3);
+    }
+
+    #[test]
+    fn parse_response_rejects_mismatched_or_truncated_packets() {
+        let packet = sample_response(7, 0);
+        assert!(parse_response(&packet, 8).is_err());
+        assert!(parse_response(&packet[..packet.len() - 2], 7).is_err());
+        assert!(parse_response(&packet[..3], 7).is_err());
+    }
+
+    #[test]
+    fn skip_name_handles_labels_and_pointers() {
+        let packet = [3, b'f', b'o', b'o', 0, 0xC0, 0, 1, b'a', 0xC0, 0];
+        assert_eq!(skip_name(&packet, 0).unwrap(), 5);
+        assert_eq!(skip_name(&packet, 5).unwrap(), 7);
+        assert_eq!(skip_name(&packet, 7).unwrap(), 11);
+        assert!(skip_name(&packet, 11).is_err());
+        assert!(skip_name(&[5, b'a'], 0).is_err());
+    }
+}
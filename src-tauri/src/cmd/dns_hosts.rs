@@ -0,0 +1,223 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::{Config, PrfItem, profiles_append_item_safe},
+    core::{CoreManager, handle},
+    logging,
+    utils::logging::Type,
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde_yaml_ng::{Mapping, Value};
+use std::{collections::BTreeMap, net::IpAddr};
+use tokio::sync::Mutex;
+
+/// 托管 hosts 覆盖的专用 Merge 项，与全局 Merge 一样不关联任何订阅
+pub const HOSTS_MERGE_UID: &str = "HostsOverrides";
+
+const HOSTS_MERGE_HEADER: &str = "# Managed hosts overrides for Liebesu_Clash, edited via API\n\n";
+
+static OVERRIDES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 添加或更新 hosts 覆盖（域名 → IP），与运行配置中已有的 hosts 冲突时拒绝
+#[tauri::command]
+pub async fn add_dns_host_override(
+    domain: String,
+    ip: String,
+) -> CmdResult<BTreeMap<String, String>> {
+    let domain = normalize_domain(&domain)?;
+    let ip: IpAddr = ip
+        .trim()
+        .parse()
+        .map_err(|_| invalid(format!("IP 地址无效: {ip}")))?;
+    let ip = ip.to_string();
+
+    let _guard = OVERRIDES_LOCK.lock().await;
+    let previous = load_overrides().await;
+    if !previous.contains_key(&domain)
+        && let Some(existing) = runtime_host(&domain).await
+        && existing != ip
+    {
+        return Err(CmdError::new(
+            ErrorCode::ConfigValidationFailed,
+            format!("{domain} 已在 hosts 中指向 {existing}，与覆盖 {ip} 冲突"),
+        ));
+    }
+
+    let mut overrides = previous.clone();
+    overrides.insert(domain.clone(), ip.clone());
+    apply_overrides(&overrides, &previous).await?;
+
+    logging!(info, Type::Config, true, "[hosts覆盖] {} -> {}", domain, ip);
+    Ok(overrides)
+}
+
+/// 移除 hosts 覆盖
+#[tauri::command]
+pub async fn remove_dns_host_override(domain: String) -> CmdResult<BTreeMap<String, String>> {
+    let domain = normalize_domain(&domain)?;
+    let _guard = OVERRIDES_LOCK.lock().await;
+    let previous = load_overrides().await;
+    if !previous.contains_key(&domain) {
+        return Err(invalid(format!("hosts 覆盖不存在: {domain}")));
+    }
+
+    let mut overrides = previous.clone();
+    overrides.remove(&domain);
+    apply_overrides(&overrides, &previous).await?;
+    Ok(overrides)
+}
+
+/// 获取所有 hosts 覆盖
+#[tauri::command]
+pub async fn list_dns_host_overrides() -> CmdResult<BTreeMap<String, String>> {
+    Ok(load_overrides().await)
+}
+
+/// 将 hosts 覆盖合并到配置，在独立 DNS 配置之后执行，供增强链使用
+pub async fn use_dns_host_overrides(mut config: Mapping) -> Mapping {
+    let overrides = load_overrides().await;
+    if overrides.is_empty() {
+        return config;
+    }
+
+    let mut hosts = config
+        .get("hosts")
+        .and_then(Value::as_mapping)
+        .cloned()
+        .unwrap_or_default();
+    for (domain, ip) in overrides {
+        hosts.insert(domain.into(), ip.into());
+    }
+    config.insert("hosts".into(), hosts.into());
+    config
+}
+
+// ===== 内部实现函数 =====
+
+fn invalid(message: String) -> CmdError {
+    CmdError::new(ErrorCode::InvalidArgument, message)
+}
+
+/// 域名转为小写，允许 mihomo 的 *. 与 +. 通配前缀
+fn normalize_domain(domain: &str) -> CmdResult<String> {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let bare = domain
+        .strip_prefix("*.")
+        .or_else(|| domain.strip_prefix("+."))
+        .unwrap_or(&domain);
+    let valid = !bare.is_empty()
+        && bare.len() <= 253
+        && bare.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    if !valid {
+        return Err(invalid(format!("域名无效: {domain}")));
+    }
+    Ok(domain)
+}
+
+/// 运行配置中该域名的 hosts 记录，多个地址时以逗号连接
+async fn runtime_host(domain: &str) -> Option<String> {
+    let runtime = Config::runtime().await;
+    let runtime = runtime.latest_ref();
+    let value = runtime.config.as_ref()?.get("hosts")?.get(domain)?;
+    match value {
+        Value::String(ip) => Some(ip.clone()),
+        Value::Sequence(ips) => Some(
+            ips.iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        _ => None,
+    }
+}
+
+async fn hosts_item() -> Option<PrfItem> {
+    Config::profiles()
+        .await
+        .latest_ref()
+        .get_item(&HOSTS_MERGE_UID.to_string())
+        .ok()
+        .cloned()
+}
+
+async fn load_overrides() -> BTreeMap<String, String> {
+    let Some(content) = hosts_item().await.and_then(|item| item.read_file().ok()) else {
+        return BTreeMap::new();
+    };
+    serde_yaml_ng::from_str::<Mapping>(&content)
+        .ok()
+        .and_then(|merge| merge.get("hosts").and_then(Value::as_mapping).cloned())
+        .map(|hosts| {
+            hosts
+                .iter()
+                .filter_map(|(domain, ip)| Some((domain.as_str()?.into(), ip.as_str()?.into())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn render(overrides: &BTreeMap<String, String>) -> Result<String> {
+    let mut merge = Mapping::new();
+    if !overrides.is_empty() {
+        let hosts: Mapping = overrides
+            .iter()
+            .map(|(domain, ip)| (domain.as_str().into(), ip.as_str().into()))
+            .collect();
+        merge.insert("hosts".into(), hosts.into());
+    }
+    let body = if merge.is_empty() {
+        String::new()
+    } else {
+        serde_yaml_ng::to_string(&merge)?
+    };
+    Ok(format!("{HOSTS_MERGE_HEADER}{body}"))
+}
+
+/// 写入专用 Merge 项，不存在时创建
+async fn save_overrides(overrides: &BTreeMap<String, String>) -> Result<()> {
+    let content = render(overrides)?;
+    match hosts_item().await {
+        Some(item) => item.save_file(content),
+        None => {
+            let mut item = PrfItem::from_merge(Some(HOSTS_MERGE_UID.into()))?;
+            item.name = Some("Hosts Overrides".into());
+            item.file_data = Some(content);
+            profiles_append_item_safe(item).await
+        }
+    }
+}
+
+/// 写入覆盖并重新生成配置，内核验证失败时恢复原覆盖
+async fn apply_overrides(
+    overrides: &BTreeMap<String, String>,
+    previous: &BTreeMap<String, String>,
+) -> CmdResult<()> {
+    save_overrides(overrides)
+        .await
+        .context("保存 hosts 覆盖失败")?;
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            save_overrides(previous)
+                .await
+                .context("恢复 hosts 覆盖失败")?;
+            Err(CmdError::new(
+                ErrorCode::ConfigValidationFailed,
+                format!("hosts 覆盖未通过内核验证，已恢复: {error}"),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
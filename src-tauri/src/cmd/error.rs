@@ -0,0 +1,250 @@
+use super::cancellation::Cancelled;
+use serde::Serialize;
+use std::{error::Error as StdError, fmt, io};
+
+/// 命令错误码，按模块划分，值保持稳定供前端做国际化与重试判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ErrorCode {
+    // ===== 通用 =====
+    #[serde(rename = "common.unknown")]
+    Unknown,
+    #[serde(rename = "common.invalid_argument")]
+    InvalidArgument,
+    #[serde(rename = "common.not_found")]
+    NotFound,
+    #[serde(rename = "common.io")]
+    Io,
+    #[serde(rename = "common.parse")]
+    Parse,
+    #[serde(rename = "common.timeout")]
+    Timeout,
+    #[serde(rename = "common.cancelled")]
+    Cancelled,
+
+    // ===== 网络请求 =====
+    #[serde(rename = "network.connect")]
+    NetworkConnect,
+    #[serde(rename = "network.timeout")]
+    NetworkTimeout,
+    #[serde(rename = "network.http_status")]
+    NetworkHttpStatus,
+
+    // ===== 订阅 =====
+    #[serde(rename = "profile.not_found")]
+    ProfileNotFound,
+    #[serde(rename = "profile.not_remote")]
+    ProfileNotRemote,
+
+    // ===== 配置 =====
+    #[serde(rename = "config.validation_failed")]
+    ConfigValidationFailed,
+
+    // ===== 内核 =====
+    #[serde(rename = "core.ipc")]
+    CoreIpc,
+
+    // ===== 系统服务 =====
+    #[serde(rename = "service.unavailable")]
+    ServiceUnavailable,
+
+    // ===== 应用锁 =====
+    #[serde(rename = "app_lock.locked")]
+    AppLocked,
+    #[serde(rename = "app_lock.invalid_passcode")]
+    InvalidPasscode,
+}
+
+impl ErrorCode {
+    /// 该类错误是否值得自动重试
+    pub const fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::Timeout
+                | Self::NetworkConnect
+                | Self::NetworkTimeout
+                | Self::CoreIpc
+                | Self::ServiceUnavailable
+        )
+    }
+}
+
+/// 命令返回给前端的结构化错误
+#[derive(Debug, Clone, Serialize)]
+pub struct CmdError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// 错误链中的底层原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    pub retryable: bool,
+}
+
+impl CmdError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            retryable: code.is_retryable(),
+        }
+    }
+
+    /// 从错误链推断错误码，完整的错误链作为 message，底层原因另存于 details
+    fn from_chain<'a>(
+        message: String,
+        chain: impl Iterator<Item = &'a (dyn StdError + 'static)>,
+        fallback: ErrorCode,
+    ) -> Self {
+        let mut classified = None;
+        let mut causes = Vec::new();
+        for (index, err) in chain.enumerate() {
+            if classified.is_none() {
+                classified = classify(err);
+            }
+            if index > 0 {
+                causes.push(err.to_string());
+            }
+        }
+
+        let (code, retryable) = classified.unwrap_or((fallback, fallback.is_retryable()));
+        Self {
+            code,
+            message,
+            details: (!causes.is_empty()).then(|| causes.join("\n")),
+            retryable,
+        }
+    }
+}
+
+impl fmt::Display for CmdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for CmdError {}
+
+impl From<anyhow::Error> for CmdError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::from_chain(format!("{err:#}"), err.chain(), ErrorCode::Unknown)
+    }
+}
+
+/// 内核 IPC 客户端返回的错误
+impl From<Box<dyn StdError + Send + Sync>> for CmdError {
+    fn from(err: Box<dyn StdError + Send + Sync>) -> Self {
+        let root: &(dyn StdError + 'static) = err.as_ref();
+        Self::from_chain(
+            err.to_string(),
+            std::iter::successors(Some(root), |e| e.source()),
+            ErrorCode::CoreIpc,
+        )
+    }
+}
+
+impl From<io::Error> for CmdError {
+    fn from(err: io::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<reqwest::Error> for CmdError {
+    fn from(err: reqwest::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<serde_json::Error> for CmdError {
+    fn from(err: serde_json::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<serde_yaml_ng::Error> for CmdError {
+    fn from(err: serde_yaml_ng::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<tokio::task::JoinError> for CmdError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<Cancelled> for CmdError {
+    fn from(err: Cancelled) -> Self {
+        Self::new(ErrorCode::Cancelled, err.to_string())
+    }
+}
+
+impl From<String> for CmdError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Unknown, message)
+    }
+}
+
+impl From<&str> for CmdError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::Unknown, message)
+    }
+}
+
+/// 供仍以 String 作为错误类型的内部调用方使用
+impl From<CmdError> for String {
+    fn from(err: CmdError) -> Self {
+        err.message
+    }
+}
+
+/// 为错误指定错误码
+pub trait WithErrorCode<T> {
+    fn with_code(self, code: ErrorCode) -> Result<T, CmdError>;
+}
+
+impl<T, E: Into<CmdError>> WithErrorCode<T> for Result<T, E> {
+    fn with_code(self, code: ErrorCode) -> Result<T, CmdError> {
+        self.map_err(|err| {
+            let mut err = err.into();
+            err.code = code;
+            err.retryable = code.is_retryable();
+            err
+        })
+    }
+}
+
+// ===== 内部实现函数 =====
+
+/// 根据底层错误类型确定错误码与是否可重试
+fn classify(err: &(dyn StdError + 'static)) -> Option<(ErrorCode, bool)> {
+    if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        let classified = if err.is_timeout() {
+            (ErrorCode::NetworkTimeout, true)
+        } else if err.is_connect() {
+            (ErrorCode::NetworkConnect, true)
+        } else if let Some(status) = err.status() {
+            let retryable = status.is_server_error() || status.as_u16() == 429;
+            (ErrorCode::NetworkHttpStatus, retryable)
+        } else {
+            return None;
+        };
+        return Some(classified);
+    }
+    if let Some(err) = err.downcast_ref::<io::Error>() {
+        return Some(match err.kind() {
+            io::ErrorKind::NotFound => (ErrorCode::NotFound, false),
+            io::ErrorKind::TimedOut => (ErrorCode::Timeout, true),
+            _ => (ErrorCode::Io, false),
+        });
+    }
+    if err.is::<Cancelled>() {
+        return Some((ErrorCode::Cancelled, false));
+    }
+    if err.is::<tokio::time::error::Elapsed>() {
+        return Some((ErrorCode::Timeout, true));
+    }
+    if err.is::<serde_json::Error>() || err.is::<serde_yaml_ng::Error>() {
+        return Some((ErrorCode::Parse, false));
+    }
+    None
+}
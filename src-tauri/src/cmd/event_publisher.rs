@@ -0,0 +1,462 @@
+use super::CmdResult;
+use crate::{
+    logging,
+    process::AsyncHandler,
+    utils::{dirs, logging::Type, secrets},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const EVENT_WEBHOOKS_FILE: &str = "event_webhooks.json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RETRIES: u32 = 10;
+const DEFAULT_MQTT_PORT: u16 = 1883;
+const MQTT_KEEP_ALIVE_SECS: u16 = 30;
+
+/// 已加载的推送设置，首次使用时从文件读取
+static CONFIG: Lazy<RwLock<Option<EventWebhookConfig>>> = Lazy::new(|| RwLock::new(None));
+
+/// 可推送的事件
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationEvent {
+    CoreStarted,
+    CoreStopped,
+    ProfileUpdated,
+    QuotaExceeded,
+    SpeedTestFinished,
+    BestNodeChanged,
+    Test, // 手动发送的测试事件，始终推送
+}
+
+impl AutomationEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::CoreStarted => "core_started",
+            Self::CoreStopped => "core_stopped",
+            Self::ProfileUpdated => "profile_updated",
+            Self::QuotaExceeded => "quota_exceeded",
+            Self::SpeedTestFinished => "speed_test_finished",
+            Self::BestNodeChanged => "best_node_changed",
+            Self::Test => "test",
+        }
+    }
+}
+
+/// 各事件的推送开关
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFlags {
+    pub core_started: bool,
+    pub core_stopped: bool,
+    pub profile_updated: bool,
+    pub quota_exceeded: bool,
+    pub speed_test_finished: bool,
+    pub best_node_changed: bool,
+}
+
+impl Default for EventFlags {
+    fn default() -> Self {
+        Self {
+            core_started: true,
+            core_stopped: true,
+            profile_updated: true,
+            quota_exceeded: true,
+            speed_test_finished: true,
+            best_node_changed: true,
+        }
+    }
+}
+
+impl EventFlags {
+    fn is_enabled(&self, event: AutomationEvent) -> bool {
+        match event {
+            AutomationEvent::CoreStarted => self.core_started,
+            AutomationEvent::CoreStopped => self.core_stopped,
+            AutomationEvent::ProfileUpdated => self.profile_updated,
+            AutomationEvent::QuotaExceeded => self.quota_exceeded,
+            AutomationEvent::SpeedTestFinished => self.speed_test_finished,
+            AutomationEvent::BestNodeChanged => self.best_node_changed,
+            AutomationEvent::Test => true,
+        }
+    }
+}
+
+/// Webhook 地址，事件以 JSON 通过 POST 发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// MQTT 推送设置，消息发布到 `{topic_prefix}/{事件名}`，QoS 0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttConfig {
+    /// 如 mqtt://192.168.1.2:1883
+    pub broker_url: String,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// 保存时写入系统钥匙串
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default = "default_topic_prefix")]
+    pub topic_prefix: String,
+}
+
+fn default_topic_prefix() -> String {
+    "clash".into()
+}
+
+/// 推送失败后的重试策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub retry_interval_secs: u64, // 第 n 次重试等待 n 倍间隔
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            retry_interval_secs: 5,
+        }
+    }
+}
+
+/// 事件推送设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventWebhookConfig {
+    pub enabled: bool,
+    pub webhooks: Vec<WebhookEndpoint>,
+    pub mqtt: Option<MqttConfig>,
+    pub events: EventFlags,
+    pub retry: RetryPolicy,
+}
+
+/// 获取事件推送设置
+#[tauri::command]
+pub async fn get_event_webhooks() -> CmdResult<EventWebhookConfig> {
+    Ok(current_config())
+}
+
+/// 设置事件推送
+#[tauri::command]
+pub async fn set_event_webhooks(config: EventWebhookConfig) -> CmdResult<()> {
+    let mut config = config;
+    for webhook in &mut config.webhooks {
+        webhook.url = webhook.url.trim().to_string();
+        match reqwest::Url::parse(&webhook.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => return Err(format!("Webhook 地址无效: {}", webhook.url).into()),
+        }
+    }
+    if let Some(mqtt) = config.mqtt.as_mut() {
+        mqtt.broker_url = mqtt.broker_url.trim().to_string();
+        parse_broker(&mqtt.broker_url)?;
+        if mqtt.topic_prefix.trim().is_empty() || mqtt.topic_prefix.contains(['#', '+']) {
+            return Err("MQTT 主题前缀无效".into());
+        }
+        mqtt.password = mqtt
+            .password
+            .take()
+            .filter(|password| !password.is_empty())
+            .map(|password| secrets::protect_secret(secrets::MQTT_PASSWORD, password));
+    }
+    if config.retry.max_retries > MAX_RETRIES {
+        return Err(format!("重试次数最多 {MAX_RETRIES} 次").into());
+    }
+
+    save_config(&config).context("保存事件推送设置失败")?;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[事件推送] 设置已更新: 启用={}, Webhook {} 个, MQTT={}",
+        config.enabled,
+        config.webhooks.len(),
+        config.mqtt.is_some()
+    );
+    *CONFIG.write() = Some(config);
+    Ok(())
+}
+
+/// 发送测试事件，返回各推送目标的结果，不重试
+#[tauri::command]
+pub async fn test_event_webhooks() -> CmdResult<Vec<String>> {
+    let config = current_config();
+    let body = event_body(AutomationEvent::Test, json!({ "message": "test" }));
+    let mut results = Vec::new();
+    for webhook in &config.webhooks {
+        results.push(
+            match post_webhook(webhook, AutomationEvent::Test, &body).await {
+                Ok(()) => format!("{}: OK", webhook.url),
+                Err(e) => format!("{}: {e}", webhook.url),
+            },
+        );
+    }
+    if let Some(mqtt) = &config.mqtt {
+        results.push(
+            match publish_mqtt(mqtt, AutomationEvent::Test, &body).await {
+                Ok(()) => format!("{}: OK", mqtt.broker_url),
+                Err(e) => format!("{}: {e}", mqtt.broker_url),
+            },
+        );
+    }
+    if results.is_empty() {
+        return Err("未配置推送目标".into());
+    }
+    Ok(results)
+}
+
+/// 推送事件，未启用或该事件已关闭时忽略，在后台发送不阻塞调用方
+pub fn publish_event(event: AutomationEvent, data: Value) {
+    let config = current_config();
+    if !config.enabled || !config.events.is_enabled(event) {
+        return;
+    }
+    if config.webhooks.is_empty() && config.mqtt.is_none() {
+        return;
+    }
+
+    let body = event_body(event, data);
+    AsyncHandler::spawn(move || async move {
+        for webhook in &config.webhooks {
+            let result = with_retry(&config.retry, || post_webhook(webhook, event, &body)).await;
+            if let Err(e) = result {
+                logging!(
+                    warn,
+                    Type::Network,
+                    true,
+                    "[事件推送] {} 推送到 {} 失败: {}",
+                    event.as_str(),
+                    webhook.url,
+                    e
+                );
+            }
+        }
+        if let Some(mqtt) = &config.mqtt {
+            let result = with_retry(&config.retry, || publish_mqtt(mqtt, event, &body)).await;
+            if let Err(e) = result {
+                logging!(
+                    warn,
+                    Type::Network,
+                    true,
+                    "[事件推送] {} 发布到 {} 失败: {}",
+                    event.as_str(),
+                    mqtt.broker_url,
+                    e
+                );
+            }
+        }
+    });
+}
+
+// ===== 内部实现函数 =====
+
+fn event_body(event: AutomationEvent, data: Value) -> Vec<u8> {
+    json!({
+        "event": event.as_str(),
+        "timestamp": Local::now().timestamp(),
+        "app": dirs::APP_ID,
+        "data": data,
+    })
+    .to_string()
+    .into_bytes()
+}
+
+async fn with_retry<F, Fut>(retry: &RetryPolicy, mut send: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut attempt = 0;
+    loop {
+        match send().await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= retry.max_retries => return Err(e),
+            Err(_) => {
+                attempt += 1;
+                tokio::time::sleep(Duration::from_secs(
+                    retry.retry_interval_secs * u64::from(attempt),
+                ))
+                .await;
+            }
+        }
+    }
+}
+
+async fn post_webhook(
+    webhook: &WebhookEndpoint,
+    event: AutomationEvent,
+    body: &[u8],
+) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    let mut request = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Clash-Event", event.as_str())
+        .body(body.to_vec());
+    for (name, value) in &webhook.headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+fn parse_broker(broker_url: &str) -> Result<(String, u16)> {
+    let url =
+        reqwest::Url::parse(broker_url).map_err(|_| anyhow!("MQTT 地址无效: {broker_url}"))?;
+    if !matches!(url.scheme(), "mqtt" | "tcp") {
+        bail!("仅支持 mqtt:// 地址: {broker_url}");
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("MQTT 地址无效: {broker_url}"))?
+        .trim_matches(['[', ']'])
+        .to_string();
+    Ok((host, url.port().unwrap_or(DEFAULT_MQTT_PORT)))
+}
+
+/// 以 MQTT 3.1.1 连接、发布一条 QoS 0 消息后断开
+async fn publish_mqtt(mqtt: &MqttConfig, event: AutomationEvent, body: &[u8]) -> Result<()> {
+    let (host, port) = parse_broker(&mqtt.broker_url)?;
+    let password = mqtt
+        .password
+        .as_deref()
+        .map(secrets::resolve_secret)
+        .transpose()?;
+
+    let mut connect = Vec::new();
+    put_str(&mut connect, "MQTT");
+    connect.push(4); // 协议级别 3.1.1
+    let mut flags = 0x02; // clean session
+    if mqtt.username.is_some() {
+        flags |= 0x80;
+    }
+    if password.is_some() {
+        flags |= 0x40;
+    }
+    connect.push(flags);
+    connect.extend(MQTT_KEEP_ALIVE_SECS.to_be_bytes());
+    let client_id = mqtt
+        .client_id
+        .clone()
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| {
+            format!(
+                "{}-{}",
+                dirs::APP_ID,
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            )
+        });
+    put_str(&mut connect, &client_id);
+    if let Some(username) = &mqtt.username {
+        put_str(&mut connect, username);
+    }
+    if let Some(password) = &password {
+        put_str(&mut connect, password);
+    }
+
+    let mut publish = Vec::new();
+    put_str(
+        &mut publish,
+        &format!(
+            "{}/{}",
+            mqtt.topic_prefix.trim_end_matches('/'),
+            event.as_str()
+        ),
+    );
+    publish.extend_from_slice(body);
+
+    tokio::time::timeout(REQUEST_TIMEOUT, async {
+        let mut stream = TcpStream::connect((host.as_str(), port)).await?;
+        stream.write_all(&packet(0x10, &connect)).await?;
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack).await?;
+        if connack[0] != 0x20 {
+            bail!("MQTT 服务器响应无效");
+        }
+        if connack[3] != 0 {
+            bail!("MQTT 连接被拒绝，返回码 {}", connack[3]);
+        }
+        stream.write_all(&packet(0x30, &publish)).await?;
+        stream.write_all(&[0xE0, 0x00]).await?; // DISCONNECT
+        stream.flush().await?;
+        Ok(())
+    })
+    .await
+    .map_err(|_| anyhow!("MQTT 连接超时"))?
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) {
+    buf.extend((value.len() as u16).to_be_bytes());
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// 组装 MQTT 报文：固定头 + 变长剩余长度 + 内容
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut len = body.len();
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn current_config() -> EventWebhookConfig {
+    if let Some(config) = CONFIG.read().as_ref() {
+        return config.clone();
+    }
+    let config = load_config().unwrap_or_else(|e| {
+        logging!(warn, Type::Cmd, true, "[事件推送] 加载设置失败: {}", e);
+        EventWebhookConfig::default()
+    });
+    *CONFIG.write() = Some(config.clone());
+    config
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(EVENT_WEBHOOKS_FILE))
+}
+
+fn load_config() -> Result<EventWebhookConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(EventWebhookConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_config(config: &EventWebhookConfig) -> Result<()> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
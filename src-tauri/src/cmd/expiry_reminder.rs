@@ -0,0 +1,275 @@
+use super::{
+    CmdError, CmdResult,
+    subscription_usage::{self, SubscriptionUsage},
+    traffic_stats::{self, AlertSeverity, AlertType, TrafficAlert},
+};
+use crate::{
+    core::handle,
+    logging,
+    utils::{
+        dirs,
+        logging::Type,
+        notification::{NotificationEvent, notify_event},
+    },
+};
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+use tokio::sync::Mutex;
+
+const REMINDER_FILE: &str = "expiry_reminders.json";
+const DEFAULT_DAYS_BEFORE: u32 = 7;
+
+static REMINDER_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 到期提醒设置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExpiryReminder {
+    pub enabled: bool,
+    pub days_before: u32,
+}
+
+impl Default for ExpiryReminder {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            days_before: DEFAULT_DAYS_BEFORE,
+        }
+    }
+}
+
+/// 全局提醒设置及按订阅覆盖，订阅覆盖优先
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpiryReminderConfig {
+    pub global: ExpiryReminder,
+    pub profiles: HashMap<String, ExpiryReminder>,
+    /// 各订阅已发送的提醒，同一到期时间的每个提醒阶段只提醒一次
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    sent: HashMap<String, SentReminders>,
+}
+
+/// 某个到期时间下已发送提醒的阶段（剩余天数阈值）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SentReminders {
+    expire: i64,
+    thresholds: Vec<u32>,
+}
+
+impl ExpiryReminderConfig {
+    fn reminder_for(&self, uid: &str) -> ExpiryReminder {
+        self.profiles.get(uid).copied().unwrap_or(self.global)
+    }
+}
+
+/// 获取指定天数内到期的订阅，按到期时间排序
+#[tauri::command]
+pub async fn get_expiring_subscriptions(days: u32) -> CmdResult<Vec<SubscriptionUsage>> {
+    let mut expiring: Vec<SubscriptionUsage> = subscription_usage::get_all_subscription_usage()
+        .await?
+        .into_iter()
+        .filter(|usage| {
+            !usage.is_expired
+                && usage
+                    .expire_in_days
+                    .is_some_and(|remaining| remaining <= i64::from(days))
+        })
+        .collect();
+    expiring.sort_by_key(|usage| usage.expire);
+    Ok(expiring)
+}
+
+/// 获取到期提醒设置
+#[tauri::command]
+pub async fn get_expiry_reminder_config() -> CmdResult<ExpiryReminderConfig> {
+    let mut config = load_config().context("加载到期提醒设置失败")?;
+    config.sent.clear();
+    Ok(config)
+}
+
+/// 设置到期提醒，uid 为空时设置全局提醒，reminder 为空时清除该订阅的覆盖
+#[tauri::command]
+pub async fn set_expiry_reminder(
+    uid: Option<String>,
+    reminder: Option<ExpiryReminder>,
+) -> CmdResult<()> {
+    if reminder.is_some_and(|reminder| reminder.days_before == 0 || reminder.days_before > 365) {
+        return Err("提醒天数需在 1 到 365 之间".into());
+    }
+
+    let _guard = REMINDER_LOCK.lock().await;
+    let mut config = load_config().context("加载到期提醒设置失败")?;
+    match (uid, reminder) {
+        (Some(uid), Some(reminder)) => {
+            config.profiles.insert(uid, reminder);
+        }
+        (Some(uid), None) => {
+            config.profiles.remove(&uid);
+        }
+        (None, reminder) => config.global = reminder.unwrap_or_default(),
+    }
+    save_config(&config)
+        .context("保存到期提醒设置失败")
+        .map_err(CmdError::from)
+}
+
+/// 检查即将到期的订阅并发送提醒，由任务调度器每天执行，返回本次提醒的订阅数量
+pub async fn check_expiring_subscriptions() -> Result<usize> {
+    let _guard = REMINDER_LOCK.lock().await;
+    let mut config = load_config()?;
+    let usages = subscription_usage::get_all_subscription_usage()
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    // 移除已删除或到期时间已变化（续费）的订阅的提醒记录
+    config.sent.retain(|uid, sent| {
+        usages
+            .iter()
+            .any(|usage| &usage.uid == uid && usage.expire == Some(sent.expire))
+    });
+
+    let mut reminded = 0;
+    for usage in &usages {
+        let Some(expire) = usage.expire else {
+            continue;
+        };
+        let reminder = config.reminder_for(&usage.uid);
+        if !reminder.enabled || usage.is_expired {
+            continue;
+        }
+        let Some(remaining) = days_until(expire) else {
+            continue;
+        };
+        let Some(threshold) = reminder_threshold(remaining, reminder.days_before) else {
+            continue;
+        };
+        let sent = config
+            .sent
+            .entry(usage.uid.clone())
+            .or_insert_with(|| SentReminders {
+                expire,
+                thresholds: Vec::new(),
+            });
+        if sent.thresholds.contains(&threshold) {
+            continue;
+        }
+        sent.thresholds.push(threshold);
+        remind(usage, remaining, threshold).await;
+        reminded += 1;
+    }
+
+    save_config(&config)?;
+    Ok(reminded)
+}
+
+/// 到期提醒设置是否已创建，用于首次启用时自动创建每日提醒任务
+pub(crate) fn is_configured() -> bool {
+    config_path().is_ok_and(|path| path.exists())
+}
+
+/// 写入默认设置，标记每日提醒任务已创建
+pub(crate) fn init_config() -> Result<()> {
+    save_config(&ExpiryReminderConfig::default())
+}
+
+// ===== 内部实现函数 =====
+
+/// 按本地日期计算距到期还有几天，到期当天为 0
+fn days_until(expire: i64) -> Option<i64> {
+    let expire = Local.timestamp_opt(expire, 0).single()?.date_naive();
+    Some((expire - Local::now().date_naive()).num_days())
+}
+
+/// 当前所处的提醒阶段：提前 days_before 天、到期前一天与到期当天各提醒一次
+fn reminder_threshold(remaining: i64, days_before: u32) -> Option<u32> {
+    match remaining {
+        0 => Some(0),
+        1 => Some(1),
+        remaining if (0..=i64::from(days_before)).contains(&remaining) => Some(days_before),
+        _ => None,
+    }
+}
+
+async fn remind(usage: &SubscriptionUsage, remaining: i64, threshold: u32) {
+    let message = if remaining == 0 {
+        "订阅今天到期".to_string()
+    } else {
+        format!("订阅将在 {} 天后到期", remaining)
+    };
+    logging!(
+        info,
+        Type::Timer,
+        true,
+        "[到期提醒] {}: {}",
+        usage.name,
+        message
+    );
+    traffic_stats::push_traffic_alert(TrafficAlert {
+        alert_id: uuid::Uuid::new_v4().to_string(),
+        subscription_uid: usage.uid.clone(),
+        subscription_name: usage.name.clone(),
+        alert_type: AlertType::ExpirationDate,
+        message,
+        threshold_value: f64::from(threshold),
+        current_value: remaining as f64,
+        created_at: Local::now().timestamp(),
+        is_read: false,
+        severity: if remaining <= 1 {
+            AlertSeverity::Critical
+        } else {
+            AlertSeverity::Warning
+        },
+    })
+    .await;
+
+    if let Some(app_handle) = handle::Handle::global().app_handle() {
+        notify_event(
+            app_handle,
+            NotificationEvent::SubscriptionExpiring {
+                name: &usage.name,
+                days: remaining,
+            },
+        )
+        .await;
+    }
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(REMINDER_FILE))
+}
+
+fn load_config() -> Result<ExpiryReminderConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(ExpiryReminderConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_config(config: &ExpiryReminderConfig) -> Result<()> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_threshold_is_reached_once() {
+        assert_eq!(reminder_threshold(8, 7), None);
+        assert_eq!(reminder_threshold(7, 7), Some(7));
+        assert_eq!(reminder_threshold(3, 7), Some(7));
+        assert_eq!(reminder_threshold(1, 7), Some(1));
+        assert_eq!(reminder_threshold(0, 7), Some(0));
+        assert_eq!(reminder_threshold(-1, 7), None);
+    }
+
+    #[test]
+    fn expiry_day_has_its_own_threshold() {
+        assert_eq!(reminder_threshold(1, 1), Some(1));
+        assert_eq!(reminder_threshold(0, 1), Some(0));
+    }
+}
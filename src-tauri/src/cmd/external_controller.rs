@@ -0,0 +1,130 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::{ClashInfo, Config},
+    feat, logging,
+    state::proxy::{CacheEndpoint, ProxyRequestCache},
+    utils::logging::Type,
+};
+use anyhow::Context;
+use once_cell::sync::Lazy;
+use serde_yaml_ng::{Mapping, Value};
+use tokio::sync::Mutex;
+
+/// 应用自身界面使用的来源，始终保留以免前端失去控制器访问
+const APP_ORIGINS: &[&str] = &["tauri://localhost", "http://tauri.localhost"];
+const MAX_ORIGINS: usize = 32;
+
+/// 密钥与 CORS 变更都需要重启内核，串行执行
+static CONTROLLER_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 生成新的控制器密钥并重启内核使其生效，失败时保留原密钥
+/// 内部 IPC 通过本地套接字访问内核，不依赖密钥；前端通过 refresh-clash 事件获取新密钥
+#[tauri::command]
+pub async fn rotate_external_controller_secret() -> CmdResult<ClashInfo> {
+    let _guard = CONTROLLER_LOCK.lock().await;
+    let secret = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut patch = Mapping::new();
+    patch.insert("secret".into(), secret.into());
+    feat::patch_clash(patch)
+        .await
+        .context("更新控制器密钥失败")?;
+    ProxyRequestCache::global().invalidate(CacheEndpoint::ClashConfig);
+
+    logging!(info, Type::Config, true, "[控制器] 已轮换外部控制器密钥");
+    Ok(Config::clash().await.latest_ref().get_client_info())
+}
+
+/// 获取外部控制器允许的 CORS 来源
+#[tauri::command]
+pub async fn get_external_controller_origins() -> CmdResult<Vec<String>> {
+    let clash = Config::clash().await;
+    let clash = clash.latest_ref();
+    Ok(current_cors(&clash.0)
+        .get("allow-origins")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect())
+}
+
+/// 设置外部控制器允许的 CORS 来源，例如局域网内的 yacd 面板地址
+/// 应用自身的来源始终保留，"*" 表示允许任意来源
+#[tauri::command]
+pub async fn set_external_controller_origins(origins: Vec<String>) -> CmdResult<Vec<String>> {
+    let mut normalized: Vec<String> = APP_ORIGINS.iter().map(|o| o.to_string()).collect();
+    for origin in origins.iter().map(|o| o.trim()).filter(|o| !o.is_empty()) {
+        let origin = normalize_origin(origin).ok_or_else(|| {
+            CmdError::new(
+                ErrorCode::InvalidArgument,
+                format!("来源格式无效: {origin}，应为 scheme://host[:port]"),
+            )
+        })?;
+        if !normalized.contains(&origin) {
+            normalized.push(origin);
+        }
+    }
+    if normalized.len() > MAX_ORIGINS {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            format!("最多允许 {MAX_ORIGINS} 个来源"),
+        ));
+    }
+
+    let _guard = CONTROLLER_LOCK.lock().await;
+    let mut cors = {
+        let clash = Config::clash().await;
+        let clash = clash.latest_ref();
+        current_cors(&clash.0)
+    };
+    cors.insert(
+        "allow-origins".into(),
+        Value::Sequence(normalized.iter().map(|o| o.as_str().into()).collect()),
+    );
+
+    let mut patch = Mapping::new();
+    patch.insert("external-controller-cors".into(), cors.into());
+    feat::patch_clash(patch)
+        .await
+        .context("更新 CORS 来源失败")?;
+
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[控制器] CORS 来源已更新: {:?}",
+        normalized
+    );
+    Ok(normalized)
+}
+
+// ===== 内部实现函数 =====
+
+fn current_cors(config: &Mapping) -> Mapping {
+    config
+        .get("external-controller-cors")
+        .and_then(Value::as_mapping)
+        .cloned()
+        .unwrap_or_else(|| {
+            let mut cors = Mapping::new();
+            cors.insert("allow-private-network".into(), true.into());
+            cors
+        })
+}
+
+/// 来源只包含 scheme、主机与端口，去掉末尾的斜杠
+fn normalize_origin(origin: &str) -> Option<String> {
+    if origin == "*" {
+        return Some(origin.into());
+    }
+    let url = url::Url::parse(origin).ok()?;
+    let valid = matches!(url.scheme(), "http" | "https" | "tauri")
+        && url.has_host()
+        && url.path().trim_end_matches('/').is_empty()
+        && url.query().is_none()
+        && url.fragment().is_none()
+        && url.username().is_empty();
+    valid.then(|| origin.trim_end_matches('/').to_ascii_lowercase())
+}
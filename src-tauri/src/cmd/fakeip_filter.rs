@@ -0,0 +1,447 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    ipc::log_pipeline::{self, LogFilter, LogTimeRange},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::PathBuf,
+};
+use tokio::sync::Mutex;
+
+const FAKEIP_EXCLUSIONS_FILE: &str = "fakeip_exclusions.yaml";
+
+/// 建议排除时分析的日志时间范围
+const SUGGEST_WINDOW_MS: i64 = 24 * 3600 * 1000;
+const SUGGEST_LOG_LIMIT: usize = 5000;
+/// 同一域名至少失败的次数
+const SUGGEST_MIN_FAILURES: usize = 3;
+const MAX_SUGGESTIONS: usize = 20;
+/// 局域网域名后缀，解析为 fake-ip 后设备发现通常会失败
+const LAN_SUFFIXES: &[&str] = &[".local", ".lan", ".home.arpa", ".localdomain"];
+
+static EXCLUSIONS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 内置的常见问题应用列表
+#[derive(Debug, Clone, Serialize)]
+pub struct FakeIpPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub patterns: &'static [&'static str],
+}
+
+const PRESETS: &[FakeIpPreset] = &[
+    FakeIpPreset {
+        id: "lan",
+        name: "局域网与设备发现",
+        patterns: &[
+            "*.lan",
+            "*.local",
+            "*.localdomain",
+            "+.home.arpa",
+            "+.msftconnecttest.com",
+            "+.msftncsi.com",
+            "localhost.ptlogin2.qq.com",
+            "localhost.sec.qq.com",
+        ],
+    },
+    FakeIpPreset {
+        id: "banking",
+        name: "银行与支付",
+        patterns: &[
+            "+.icbc.com.cn",
+            "+.ccb.com",
+            "+.abchina.com",
+            "+.boc.cn",
+            "+.bankcomm.com",
+            "+.cmbchina.com",
+            "+.psbc.com",
+            "+.95516.com",
+            "+.unionpay.com",
+            "+.alipay.com",
+            "+.tenpay.com",
+        ],
+    },
+    FakeIpPreset {
+        id: "time",
+        name: "时间同步",
+        patterns: &[
+            "time.*.com",
+            "time.*.gov",
+            "ntp.*.com",
+            "+.pool.ntp.org",
+            "time.*.apple.com",
+        ],
+    },
+    FakeIpPreset {
+        id: "gaming",
+        name: "游戏主机与 NAT 检测",
+        patterns: &[
+            "+.srv.nintendo.net",
+            "+.stun.playstation.net",
+            "xbox.*.microsoft.com",
+            "+.xboxlive.com",
+            "stun.*.*",
+            "stun.*.*.*",
+        ],
+    },
+];
+
+/// 批量导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FakeIpImportResult {
+    pub added: Vec<String>,
+    pub skipped: Vec<String>, // 已存在
+    pub invalid: Vec<String>,
+    pub exclusions: Vec<String>,
+}
+
+/// 排除建议
+#[derive(Debug, Clone, Serialize)]
+pub struct FakeIpSuggestion {
+    pub pattern: String,
+    pub failures: usize,
+    pub domains: Vec<String>,
+    pub processes: Vec<String>,
+    pub lan: bool, // 局域网域名
+}
+
+/// 获取 fake-ip 排除列表
+#[tauri::command]
+pub async fn list_fakeip_exclusions() -> CmdResult<Vec<String>> {
+    load_exclusions()
+        .context("加载 fake-ip 排除列表失败")
+        .map_err(CmdError::from)
+}
+
+/// 添加 fake-ip 排除项，语法与 mihomo fake-ip-filter 一致
+#[tauri::command]
+pub async fn add_fakeip_exclusion(pattern: String) -> CmdResult<Vec<String>> {
+    let pattern = normalize_pattern(&pattern)
+        .ok_or_else(|| invalid(format!("排除规则无效: {}", pattern.trim())))?;
+
+    let _guard = EXCLUSIONS_LOCK.lock().await;
+    let previous = load_exclusions().context("加载 fake-ip 排除列表失败")?;
+    if previous.contains(&pattern) {
+        return Ok(previous);
+    }
+    let mut exclusions = previous.clone();
+    exclusions.push(pattern.clone());
+    apply_exclusions(&exclusions, &previous).await?;
+
+    logging!(info, Type::Config, true, "[fake-ip排除] 添加 {}", pattern);
+    Ok(exclusions)
+}
+
+/// 移除 fake-ip 排除项
+#[tauri::command]
+pub async fn remove_fakeip_exclusion(pattern: String) -> CmdResult<Vec<String>> {
+    let pattern = pattern.trim().to_ascii_lowercase();
+
+    let _guard = EXCLUSIONS_LOCK.lock().await;
+    let previous = load_exclusions().context("加载 fake-ip 排除列表失败")?;
+    if !previous.contains(&pattern) {
+        return Err(invalid(format!("排除规则不存在: {pattern}")));
+    }
+    let exclusions: Vec<String> = previous
+        .iter()
+        .filter(|p| **p != pattern)
+        .cloned()
+        .collect();
+    apply_exclusions(&exclusions, &previous).await?;
+    Ok(exclusions)
+}
+
+/// 批量导入排除项，例如内置的常见问题应用列表，无效项跳过
+#[tauri::command]
+pub async fn import_fakeip_exclusions(patterns: Vec<String>) -> CmdResult<FakeIpImportResult> {
+    let _guard = EXCLUSIONS_LOCK.lock().await;
+    let previous = load_exclusions().context("加载 fake-ip 排除列表失败")?;
+    let mut exclusions = previous.clone();
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+    let mut invalid = Vec::new();
+
+    for raw in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        match normalize_pattern(raw) {
+            Some(pattern) if exclusions.contains(&pattern) => skipped.push(pattern),
+            Some(pattern) => {
+                exclusions.push(pattern.clone());
+                added.push(pattern);
+            }
+            None => invalid.push(raw.to_string()),
+        }
+    }
+    if !added.is_empty() {
+        apply_exclusions(&exclusions, &previous).await?;
+        logging!(
+            info,
+            Type::Config,
+            true,
+            "[fake-ip排除] 导入 {} 项, 跳过 {} 项, 无效 {} 项",
+            added.len(),
+            skipped.len(),
+            invalid.len()
+        );
+    }
+
+    Ok(FakeIpImportResult {
+        added,
+        skipped,
+        invalid,
+        exclusions,
+    })
+}
+
+/// 获取内置的常见问题应用列表
+#[tauri::command]
+pub fn get_fakeip_exclusion_presets() -> CmdResult<Vec<FakeIpPreset>> {
+    Ok(PRESETS.to_vec())
+}
+
+/// 根据最近 24 小时的连接失败日志建议排除的域名，已被排除的域名不再建议
+#[tauri::command]
+pub async fn suggest_fakeip_exclusions() -> CmdResult<Vec<FakeIpSuggestion>> {
+    // 运行配置中的 fake-ip-filter 已包含订阅与 DNS 设置中的排除项
+    let mut exclusions = load_exclusions().context("加载 fake-ip 排除列表失败")?;
+    {
+        let runtime = Config::runtime().await;
+        let runtime = runtime.latest_ref();
+        exclusions.extend(
+            runtime
+                .config
+                .as_ref()
+                .and_then(|config| config.get("dns"))
+                .and_then(|dns| dns.get("fake-ip-filter"))
+                .and_then(Value::as_sequence)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_ascii_lowercase),
+        );
+    }
+    let filter = LogFilter {
+        levels: Some(vec!["warning".into(), "error".into()]),
+        ..LogFilter::default()
+    };
+    let range = LogTimeRange {
+        start: Some(Local::now().timestamp_millis() - SUGGEST_WINDOW_MS),
+        end: None,
+    };
+    let records = tokio::task::spawn_blocking(move || {
+        log_pipeline::query_logs(&filter, range, SUGGEST_LOG_LIMIT)
+    })
+    .await?;
+
+    let mut grouped: HashMap<String, (usize, BTreeSet<String>, BTreeSet<String>)> = HashMap::new();
+    for record in records {
+        let Some(connection) = record.connection.as_ref() else {
+            continue;
+        };
+        let message = record.message.to_lowercase();
+        if !(message.contains("error") || message.contains("timeout") || message.contains("failed"))
+        {
+            continue;
+        }
+        let Some(domain) = connection
+            .destination
+            .as_deref()
+            .and_then(destination_domain)
+        else {
+            continue;
+        };
+        if exclusions.iter().any(|p| pattern_matches(p, &domain)) {
+            continue;
+        }
+        let entry = grouped.entry(suggested_pattern(&domain)).or_default();
+        entry.0 += 1;
+        entry.1.insert(domain);
+        if let Some(process) = connection.process.as_deref().filter(|p| !p.is_empty()) {
+            entry.2.insert(process.to_string());
+        }
+    }
+
+    let mut suggestions: Vec<FakeIpSuggestion> = grouped
+        .into_iter()
+        .filter(|(pattern, (failures, _, _))| {
+            *failures >= SUGGEST_MIN_FAILURES || is_lan_domain(pattern)
+        })
+        .map(
+            |(pattern, (failures, domains, processes))| FakeIpSuggestion {
+                lan: is_lan_domain(&pattern),
+                pattern,
+                failures,
+                domains: domains.into_iter().collect(),
+                processes: processes.into_iter().collect(),
+            },
+        )
+        .collect();
+    suggestions.sort_by(|a, b| b.lan.cmp(&a.lan).then(b.failures.cmp(&a.failures)));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    Ok(suggestions)
+}
+
+/// 将排除项追加到 dns.fake-ip-filter，供增强链使用
+/// 白名单模式下 fake-ip-filter 含义相反，不做修改
+pub fn use_fakeip_exclusions(mut config: Mapping) -> Mapping {
+    let exclusions = match load_exclusions() {
+        Ok(exclusions) if !exclusions.is_empty() => exclusions,
+        _ => return config,
+    };
+    let Some(Value::Mapping(dns)) = config.get_mut("dns") else {
+        return config;
+    };
+    if dns.get("fake-ip-filter-mode").and_then(Value::as_str) == Some("whitelist") {
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[fake-ip排除] fake-ip-filter 为白名单模式，跳过排除列表"
+        );
+        return config;
+    }
+
+    let mut filter: Vec<Value> = dns
+        .get("fake-ip-filter")
+        .and_then(Value::as_sequence)
+        .cloned()
+        .unwrap_or_default();
+    for pattern in exclusions {
+        if !filter.iter().any(|v| v.as_str() == Some(pattern.as_str())) {
+            filter.push(pattern.into());
+        }
+    }
+    dns.insert("fake-ip-filter".into(), Value::Sequence(filter));
+    config
+}
+
+// ===== 内部实现函数 =====
+
+fn invalid(message: String) -> CmdError {
+    CmdError::new(ErrorCode::InvalidArgument, message)
+}
+
+/// 校验并规范化排除规则：域名通配（* / +.）或 geosite: / rule-set: 引用
+fn normalize_pattern(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    if let Some(name) = pattern
+        .strip_prefix("geosite:")
+        .or_else(|| pattern.strip_prefix("rule-set:"))
+    {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '!' | '@' | '.'));
+        return valid.then_some(pattern);
+    }
+
+    let bare = pattern.strip_prefix("+.").unwrap_or(&pattern);
+    let valid = !bare.is_empty()
+        && bare.len() <= 253
+        && bare.split('.').all(|label| {
+            label == "*"
+                || (!label.is_empty()
+                    && label.len() <= 63
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '*')))
+        });
+    valid.then_some(pattern)
+}
+
+/// 按 mihomo 域名通配语义判断是否匹配：+. 匹配自身及所有子域名，* 匹配单级
+fn pattern_matches(pattern: &str, domain: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix("+.") {
+        return domain == suffix || domain.ends_with(&format!(".{suffix}"));
+    }
+    let pattern_labels: Vec<&str> = pattern.split('.').collect();
+    let domain_labels: Vec<&str> = domain.split('.').collect();
+    pattern_labels.len() == domain_labels.len()
+        && pattern_labels
+            .iter()
+            .zip(&domain_labels)
+            .all(|(p, d)| *p == "*" || p == d)
+}
+
+/// 从 "host:port" 中取出域名，IP 地址返回 None
+fn destination_domain(destination: &str) -> Option<String> {
+    let host = match destination.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => destination,
+    };
+    let host = host.trim_matches(['[', ']']).trim_end_matches('.');
+    if host.is_empty() || host.parse::<std::net::IpAddr>().is_ok() || !host.contains('.') {
+        return None;
+    }
+    Some(host.to_ascii_lowercase())
+}
+
+/// 局域网域名保持原样，其余建议排除主域名及其子域名
+fn suggested_pattern(domain: &str) -> String {
+    if is_lan_domain(domain) {
+        return domain.to_string();
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    // 形如 example.com.cn 的二级后缀保留三级
+    let keep = match labels.as_slice() {
+        [.., second, tld]
+            if tld.len() == 2
+                && matches!(*second, "com" | "net" | "org" | "gov" | "edu" | "co" | "ac") =>
+        {
+            3
+        }
+        _ => 2,
+    };
+    let start = labels.len().saturating_sub(keep);
+    format!("+.{}", labels[start..].join("."))
+}
+
+fn is_lan_domain(domain: &str) -> bool {
+    LAN_SUFFIXES.iter().any(|suffix| domain.ends_with(suffix))
+}
+
+/// 写入排除列表并重新生成配置，内核验证失败时恢复原列表
+async fn apply_exclusions(exclusions: &[String], previous: &[String]) -> CmdResult<()> {
+    save_exclusions(exclusions).context("保存 fake-ip 排除列表失败")?;
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            save_exclusions(previous).context("恢复 fake-ip 排除列表失败")?;
+            Err(CmdError::new(
+                ErrorCode::ConfigValidationFailed,
+                format!("fake-ip 排除列表未通过内核验证，已恢复: {error}"),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn exclusions_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(FAKEIP_EXCLUSIONS_FILE))
+}
+
+fn load_exclusions() -> Result<Vec<String>> {
+    let path = exclusions_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_yaml_ng::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_exclusions(exclusions: &[String]) -> Result<()> {
+    fs::write(exclusions_path()?, serde_yaml_ng::to_string(exclusions)?)?;
+    Ok(())
+}
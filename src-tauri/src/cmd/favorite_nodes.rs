@@ -0,0 +1,202 @@
+use super::CmdResult;
+use super::proxy::{
+    BulkSelectionItem, apply_bulk_selection, current_selection, fetch_proxy_map,
+    selectable_candidates,
+};
+use crate::{config::Config, core::tray::Tray, logging, utils::dirs, utils::logging::Type};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+use tokio::sync::RwLock;
+
+/// 收藏节点存储
+static FAVORITE_NODES: Lazy<Arc<RwLock<Option<FavoriteNodesStore>>>> =
+    Lazy::new(|| Arc::new(RwLock::new(None)));
+
+/// 收藏节点及其最近一次测速结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoriteNode {
+    /// 快速切换位置，从 1 开始，与快捷键参数一致
+    pub slot: usize,
+    pub node: String,
+    pub delay: Option<u64>,
+    /// 当前选中该节点的分组
+    pub selected_in: Vec<String>,
+}
+
+/// 订阅 -> 收藏节点（按添加顺序）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct FavoriteNodesStore {
+    favorites: HashMap<String, Vec<String>>,
+}
+
+/// 收藏节点
+#[tauri::command]
+pub async fn add_favorite_node(profile_uid: String, node: String) -> CmdResult<()> {
+    let mut guard = FAVORITE_NODES.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    let favorites = store.favorites.entry(profile_uid).or_default();
+    if favorites.contains(&node) {
+        return Ok(());
+    }
+    favorites.push(node);
+    save_store(store).context("保存收藏节点失败")?;
+    drop(guard);
+
+    Tray::global().refresh_menu_async();
+    Ok(())
+}
+
+/// 取消收藏节点
+#[tauri::command]
+pub async fn remove_favorite_node(profile_uid: String, node: String) -> CmdResult<()> {
+    let mut guard = FAVORITE_NODES.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    if let Some(favorites) = store.favorites.get_mut(&profile_uid) {
+        favorites.retain(|n| n != &node);
+        if favorites.is_empty() {
+            store.favorites.remove(&profile_uid);
+        }
+    }
+    save_store(store).context("保存收藏节点失败")?;
+    drop(guard);
+
+    Tray::global().refresh_menu_async();
+    Ok(())
+}
+
+/// 列出当前订阅的收藏节点，附带最近一次测速延迟
+#[tauri::command]
+pub async fn list_favorite_nodes() -> CmdResult<Vec<FavoriteNode>> {
+    let favorites = current_favorites().await;
+    if favorites.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let proxies = super::proxy::get_proxies(None).await?;
+    let proxies = proxies
+        .get("proxies")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default();
+    Ok(annotate_favorites(favorites, &proxies))
+}
+
+/// 切换到指定位置的收藏节点，在所有包含该节点的可选择分组中生效
+#[tauri::command]
+pub async fn switch_to_favorite(slot: usize) -> CmdResult<Vec<BulkSelectionItem>> {
+    let favorites = current_favorites().await;
+    let node = slot
+        .checked_sub(1)
+        .and_then(|index| favorites.get(index))
+        .ok_or_else(|| format!("收藏位置 {slot} 不存在"))?;
+
+    let proxies = fetch_proxy_map().await?;
+    let plan: Vec<BulkSelectionItem> = proxies
+        .keys()
+        .filter(|group| {
+            selectable_candidates(&proxies, group).is_some_and(|all| all.contains(node))
+        })
+        .map(|group| BulkSelectionItem {
+            group: group.clone(),
+            proxy: node.clone(),
+            changed: current_selection(&proxies, group) != Some(node.as_str()),
+            error: None,
+        })
+        .collect();
+    if plan.is_empty() {
+        return Err(format!("当前配置中没有分组包含节点 {node}").into());
+    }
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[收藏节点] 切换到 #{} {}，涉及 {} 个分组",
+        slot,
+        node,
+        plan.len()
+    );
+    Ok(apply_bulk_selection(plan).await)
+}
+
+/// 当前订阅的收藏节点，按快速切换位置排序
+pub async fn current_favorites() -> Vec<String> {
+    let Some(profile_uid) = Config::profiles().await.latest_ref().get_current() else {
+        return Vec::new();
+    };
+
+    let mut guard = FAVORITE_NODES.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    store
+        .favorites
+        .get(&profile_uid)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// 为收藏节点补充延迟与选中状态，已不在配置中的节点依然保留以便用户清理
+pub(crate) fn annotate_favorites(
+    favorites: Vec<String>,
+    proxies: &serde_json::Map<String, serde_json::Value>,
+) -> Vec<FavoriteNode> {
+    favorites
+        .into_iter()
+        .enumerate()
+        .map(|(index, node)| {
+            let delay = proxies
+                .get(&node)
+                .and_then(|p| p.get("history"))
+                .and_then(|h| h.as_array())
+                .and_then(|h| h.last())
+                .and_then(|r| r.get("delay"))
+                .and_then(|d| d.as_u64())
+                .filter(|delay| *delay > 0);
+            let selected_in = proxies
+                .keys()
+                .filter(|group| {
+                    selectable_candidates(proxies, group).is_some()
+                        && current_selection(proxies, group) == Some(node.as_str())
+                })
+                .cloned()
+                .collect();
+            FavoriteNode {
+                slot: index + 1,
+                node,
+                delay,
+                selected_in,
+            }
+        })
+        .collect()
+}
+
+// ===== 内部实现函数 =====
+
+/// 获取存储文件路径
+fn store_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("favorite_nodes.json"))
+}
+
+/// 加载收藏存储
+fn load_store() -> Result<FavoriteNodesStore> {
+    let path = store_path()?;
+    if !path.exists() {
+        return Ok(FavoriteNodesStore::default());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存收藏存储
+fn save_store(store: &FavoriteNodesStore) -> Result<()> {
+    let path = store_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let json_data = serde_json::to_string_pretty(store)?;
+    fs::write(path, json_data)?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use super::CmdResult;
+use serde::Serialize;
+
+/// 单条入站放行规则的状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallRuleStatus {
+    pub name: String,
+    pub protocol: String,
+    pub port: u16,
+    pub present: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallRulesStatus {
+    pub rules: Vec<FirewallRuleStatus>,
+    /// 本次是否新建或重建了规则
+    pub changed: bool,
+}
+
+/// Platform-specific implementation for firewall rules
+#[cfg(windows)]
+mod platform {
+    use super::{CmdResult, FirewallRulesStatus};
+    use crate::{
+        config::Config, core::win_firewall, logging, process::AsyncHandler, utils::logging::Type,
+    };
+    use anyhow::Context;
+
+    pub async fn ensure_firewall_rules() -> CmdResult<FirewallRulesStatus> {
+        let ports = proxy_ports().await;
+        let desired = win_firewall::desired_rules(&ports);
+
+        let status = AsyncHandler::spawn_blocking(move || win_firewall::ensure_rules(&desired))
+            .await
+            .context("设置防火墙规则任务失败")?
+            .context("设置防火墙规则失败")?;
+        if status.changed {
+            logging!(
+                info,
+                Type::Network,
+                true,
+                "[防火墙] 已重建入站规则: 端口 {:?}",
+                ports
+            );
+        }
+        Ok(status)
+    }
+
+    pub async fn remove_firewall_rules() -> CmdResult<bool> {
+        let removed = AsyncHandler::spawn_blocking(win_firewall::remove_rules)
+            .await
+            .context("移除防火墙规则任务失败")?
+            .context("移除防火墙规则失败")?;
+        if removed {
+            logging!(info, Type::Network, true, "[防火墙] 已移除入站规则");
+        }
+        Ok(removed)
+    }
+
+    /// mixed 端口始终放行，socks 端口仅在启用时放行
+    async fn proxy_ports() -> Vec<(&'static str, u16)> {
+        let clash = Config::clash().await;
+        let verge = Config::verge().await;
+        let clash = clash.latest_ref();
+        let verge = verge.latest_ref();
+
+        let mut ports = vec![(
+            "mixed-port",
+            verge.verge_mixed_port.unwrap_or(clash.get_mixed_port()),
+        )];
+        if verge.verge_socks_enabled.unwrap_or(false) {
+            ports.push((
+                "socks-port",
+                verge.verge_socks_port.unwrap_or(clash.get_socks_port()),
+            ));
+        }
+        ports
+    }
+}
+
+/// Stub implementation for non-Windows platforms
+#[cfg(not(windows))]
+mod platform {
+    use super::{CmdResult, FirewallRulesStatus};
+
+    pub async fn ensure_firewall_rules() -> CmdResult<FirewallRulesStatus> {
+        Ok(FirewallRulesStatus::default())
+    }
+
+    pub async fn remove_firewall_rules() -> CmdResult<bool> {
+        Ok(false)
+    }
+}
+
+/// 确保 mixed/socks 端口的入站放行规则存在，仅对专用网络的本地子网放行，
+/// 缺失时请求管理员权限创建（仅 Windows）
+#[tauri::command]
+pub async fn ensure_firewall_rules() -> CmdResult<FirewallRulesStatus> {
+    platform::ensure_firewall_rules().await
+}
+
+/// 移除本应用创建的入站放行规则，返回是否有规则被移除（仅 Windows）
+#[tauri::command]
+pub async fn remove_firewall_rules() -> CmdResult<bool> {
+    platform::remove_firewall_rules().await
+}
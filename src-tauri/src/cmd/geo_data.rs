@@ -0,0 +1,507 @@
+use super::{CmdError, CmdResult, ErrorCode, OperationKind, ProgressReporter};
+use crate::{
+    ipc::IpcManager,
+    logging,
+    utils::{
+        dirs, fetcher,
+        logging::Type,
+        network::{ProxyType, resolve_proxy_url},
+    },
+};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+const GEO_DATA_FILE: &str = "geo_data.yaml";
+const CHECKSUM_TIMEOUT_SECS: u64 = 15;
+const DOWNLOAD_TIMEOUT_SECS: u64 = 300;
+
+/// 同一时间只允许一次地理数据更新
+static UPDATE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 内核使用的地理数据集
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoDataset {
+    Country, // Country.mmdb
+    GeoIp,   // geoip.dat
+    GeoSite, // geosite.dat
+    Asn,     // GeoLite2-ASN.mmdb
+}
+
+impl GeoDataset {
+    const ALL: [Self; 4] = [Self::Country, Self::GeoIp, Self::GeoSite, Self::Asn];
+
+    /// 内核工作目录中的文件名
+    const fn file_name(self) -> &'static str {
+        match self {
+            Self::Country => "Country.mmdb",
+            Self::GeoIp => "geoip.dat",
+            Self::GeoSite => "geosite.dat",
+            Self::Asn => "GeoLite2-ASN.mmdb",
+        }
+    }
+
+    /// 镜像源中的文件名
+    const fn remote_name(self) -> &'static str {
+        match self {
+            Self::Country => "country.mmdb",
+            Self::GeoIp => "geoip.dat",
+            Self::GeoSite => "geosite.dat",
+            Self::Asn => "GeoLite2-ASN.mmdb",
+        }
+    }
+}
+
+/// 镜像源，数据集地址为 {base_url}/{文件名}，校验文件为 {文件名}.sha256sum
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GeoSource {
+    pub name: String,
+    pub base_url: String,
+}
+
+impl GeoSource {
+    fn new(name: &str, base_url: &str) -> Self {
+        Self {
+            name: name.into(),
+            base_url: base_url.into(),
+        }
+    }
+
+    fn url_for(&self, file: &str) -> String {
+        format!("{}/{file}", self.base_url.trim_end_matches('/'))
+    }
+}
+
+/// 地理数据更新设置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GeoDataSettings {
+    pub sources: Vec<GeoSource>, // 按顺序尝试，前一个失败时使用下一个
+    pub use_proxy: bool,         // 通过内核代理下载
+}
+
+impl Default for GeoDataSettings {
+    fn default() -> Self {
+        Self {
+            sources: vec![
+                GeoSource::new(
+                    "GitHub",
+                    "https://github.com/MetaCubeX/meta-rules-dat/releases/download/latest",
+                ),
+                GeoSource::new(
+                    "jsDelivr",
+                    "https://fastly.jsdelivr.net/gh/MetaCubeX/meta-rules-dat@release",
+                ),
+                GeoSource::new(
+                    "testingcf",
+                    "https://testingcf.jsdelivr.net/gh/MetaCubeX/meta-rules-dat@release",
+                ),
+            ],
+            use_proxy: true,
+        }
+    }
+}
+
+impl GeoDataSettings {
+    fn validate(&self) -> CmdResult<()> {
+        if self.sources.is_empty() {
+            return Err(invalid("至少需要一个镜像源".into()));
+        }
+        let mut names = Vec::new();
+        for source in &self.sources {
+            let name = source.name.trim();
+            if name.is_empty() {
+                return Err(invalid("镜像源名称不能为空".into()));
+            }
+            if names.contains(&name) {
+                return Err(invalid(format!("镜像源名称重复: {name}")));
+            }
+            names.push(name);
+            let valid = url::Url::parse(&source.base_url)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+            if !valid {
+                return Err(invalid(format!("镜像源地址无效: {}", source.base_url)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 单个数据集的最近一次更新结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoUpdateOutcome {
+    Updated,   // 已下载并替换
+    Unchanged, // 与镜像源一致，无需更新
+    Failed,    // 所有镜像源均失败
+}
+
+/// 已安装数据集的版本记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DatasetRecord {
+    sha256: Option<String>,
+    version: Option<String>, // 镜像源返回的 Last-Modified，缺失时为校验和前缀
+    source: Option<String>,
+    updated_at: Option<i64>,
+    checked_at: Option<i64>,
+    outcome: Option<GeoUpdateOutcome>,
+    error: Option<String>,
+}
+
+/// 最近一次更新汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoUpdateSummary {
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GeoDataState {
+    #[serde(default)]
+    settings: GeoDataSettings,
+    #[serde(default)]
+    datasets: BTreeMap<GeoDataset, DatasetRecord>,
+    #[serde(default)]
+    last_run: Option<GeoUpdateSummary>,
+}
+
+/// 数据集状态
+#[derive(Debug, Clone, Serialize)]
+pub struct GeoDatasetStatus {
+    pub dataset: GeoDataset,
+    pub file_name: String,
+    pub present: bool,
+    pub size: Option<u64>,
+    pub sha256: Option<String>,
+    pub version: Option<String>,
+    pub source: Option<String>,
+    pub updated_at: Option<i64>,
+    pub checked_at: Option<i64>,
+    pub last_result: Option<GeoUpdateOutcome>,
+    pub error: Option<String>,
+}
+
+/// 地理数据管理状态
+#[derive(Debug, Clone, Serialize)]
+pub struct GeoDataStatus {
+    pub settings: GeoDataSettings,
+    pub datasets: Vec<GeoDatasetStatus>,
+    pub last_run: Option<GeoUpdateSummary>,
+    pub updating: bool,
+}
+
+/// 获取地理数据集的版本与最近更新结果
+#[tauri::command]
+pub async fn get_geo_data_status() -> CmdResult<GeoDataStatus> {
+    let state = load_state().context("加载地理数据状态失败")?;
+    let home = dirs::app_home_dir()?;
+
+    let datasets = GeoDataset::ALL
+        .iter()
+        .map(|&dataset| {
+            let record = state.datasets.get(&dataset).cloned().unwrap_or_default();
+            let metadata = fs::metadata(home.join(dataset.file_name())).ok();
+            GeoDatasetStatus {
+                dataset,
+                file_name: dataset.file_name().into(),
+                present: metadata.is_some(),
+                size: metadata.map(|m| m.len()),
+                sha256: record.sha256,
+                version: record.version,
+                source: record.source,
+                updated_at: record.updated_at,
+                checked_at: record.checked_at,
+                last_result: record.outcome,
+                error: record.error,
+            }
+        })
+        .collect();
+
+    Ok(GeoDataStatus {
+        settings: state.settings,
+        datasets,
+        last_run: state.last_run,
+        updating: UPDATE_LOCK.try_lock().is_err(),
+    })
+}
+
+/// 设置地理数据镜像源
+#[tauri::command]
+pub async fn set_geo_data_settings(settings: GeoDataSettings) -> CmdResult<GeoDataSettings> {
+    settings.validate()?;
+    let _guard = UPDATE_LOCK
+        .try_lock()
+        .map_err(|_| "地理数据正在更新，请稍后再试")?;
+    let mut state = load_state().context("加载地理数据状态失败")?;
+    state.settings = settings.clone();
+    save_state(&state).context("保存地理数据设置失败")?;
+    Ok(settings)
+}
+
+/// 更新地理数据集：按顺序尝试镜像源，校验 sha256 后替换，force 为 false 时跳过未变化的数据集
+#[tauri::command]
+pub async fn update_geo_datasets(force: bool) -> CmdResult<GeoUpdateSummary> {
+    run_update(force).await
+}
+
+/// 计划任务入口
+pub(crate) async fn run_scheduled_update() -> Result<String> {
+    let summary = run_update(false).await.map_err(|e| anyhow!("{e}"))?;
+    if summary.failed > 0 {
+        bail!(
+            "{} 个数据集更新失败（已更新 {}，未变化 {}）",
+            summary.failed,
+            summary.updated,
+            summary.unchanged
+        );
+    }
+    Ok(format!(
+        "地理数据已更新 {} 个，未变化 {} 个",
+        summary.updated, summary.unchanged
+    ))
+}
+
+pub(crate) async fn run_update(force: bool) -> CmdResult<GeoUpdateSummary> {
+    let _guard = UPDATE_LOCK.try_lock().map_err(|_| "地理数据正在更新")?;
+    let mut state = load_state().context("加载地理数据状态失败")?;
+    let home = dirs::app_home_dir()?;
+    let proxy_url = if state.settings.use_proxy {
+        resolve_proxy_url(ProxyType::Localhost).await
+    } else {
+        None
+    };
+    let client =
+        fetcher::shared_client(proxy_url.as_deref(), false).context("创建HTTP客户端失败")?;
+
+    let progress = ProgressReporter::new(OperationKind::GeoUpdate, GeoDataset::ALL.len());
+    let started_at = Local::now().timestamp();
+    let mut summary = GeoUpdateSummary {
+        started_at,
+        finished_at: started_at,
+        updated: 0,
+        unchanged: 0,
+        failed: 0,
+    };
+
+    for (index, dataset) in GeoDataset::ALL.into_iter().enumerate() {
+        progress.emit("updating", index, Some(dataset.file_name().into()));
+        let record = state.datasets.entry(dataset).or_default();
+        record.checked_at = Some(Local::now().timestamp());
+        match update_dataset(&client, &state.settings.sources, &home, dataset, force).await {
+            Ok(Fetched::Unchanged { sha256, source }) => {
+                summary.unchanged += 1;
+                if record.sha256.as_deref() != Some(sha256.as_str()) {
+                    record.version = Some(sha256[..12].to_string());
+                    record.source = Some(source);
+                }
+                record.sha256 = Some(sha256);
+                record.outcome = Some(GeoUpdateOutcome::Unchanged);
+                record.error = None;
+            }
+            Ok(Fetched::Updated {
+                sha256,
+                version,
+                source,
+            }) => {
+                summary.updated += 1;
+                logging!(
+                    info,
+                    Type::Config,
+                    true,
+                    "[地理数据] {} 已从 {} 更新, sha256={}",
+                    dataset.file_name(),
+                    source,
+                    sha256
+                );
+                record.version = Some(version.unwrap_or_else(|| sha256[..12].to_string()));
+                record.sha256 = Some(sha256);
+                record.source = Some(source);
+                record.updated_at = record.checked_at;
+                record.outcome = Some(GeoUpdateOutcome::Updated);
+                record.error = None;
+            }
+            Err(e) => {
+                summary.failed += 1;
+                logging!(
+                    warn,
+                    Type::Config,
+                    true,
+                    "[地理数据] {} 更新失败: {}",
+                    dataset.file_name(),
+                    e
+                );
+                record.outcome = Some(GeoUpdateOutcome::Failed);
+                record.error = Some(e.to_string());
+            }
+        }
+    }
+
+    summary.finished_at = Local::now().timestamp();
+    state.last_run = Some(summary.clone());
+    save_state(&state).context("保存地理数据状态失败")?;
+
+    // 内核会缓存已加载的地理数据，通过 /configs/geo 重新加载，避免重启内核断开所有连接
+    if summary.updated > 0
+        && let Err(e) = IpcManager::global().update_geo_data().await
+    {
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[地理数据] 内核重新加载地理数据失败，将在下次启动内核时生效: {}",
+            e
+        );
+    }
+
+    let message = format!(
+        "更新 {}，未变化 {}，失败 {}",
+        summary.updated, summary.unchanged, summary.failed
+    );
+    if summary.failed == GeoDataset::ALL.len() {
+        progress.fail(GeoDataset::ALL.len(), message.clone());
+        return Err(format!("地理数据更新失败: {message}").into());
+    }
+    progress.finish(Some(message));
+    Ok(summary)
+}
+
+// ===== 内部实现函数 =====
+
+enum Fetched {
+    Unchanged {
+        sha256: String,
+        source: String,
+    },
+    Updated {
+        sha256: String,
+        version: Option<String>,
+        source: String,
+    },
+}
+
+fn invalid(message: String) -> CmdError {
+    CmdError::new(ErrorCode::InvalidArgument, message)
+}
+
+/// 依次尝试各镜像源，返回第一个成功的结果，全部失败时返回各源的错误
+async fn update_dataset(
+    client: &reqwest::Client,
+    sources: &[GeoSource],
+    home: &Path,
+    dataset: GeoDataset,
+    force: bool,
+) -> Result<Fetched> {
+    let target = home.join(dataset.file_name());
+    let local_sha = if force {
+        None
+    } else {
+        fs::read(&target)
+            .ok()
+            .map(|data| hex::encode(Sha256::digest(&data)))
+    };
+
+    let mut errors = Vec::new();
+    for source in sources {
+        match fetch_from_source(client, source, dataset, local_sha.as_deref(), &target).await {
+            Ok(fetched) => return Ok(fetched),
+            Err(e) => errors.push(format!("{}: {e}", source.name)),
+        }
+    }
+    bail!("{}", errors.join("; "))
+}
+
+async fn fetch_from_source(
+    client: &reqwest::Client,
+    source: &GeoSource,
+    dataset: GeoDataset,
+    local_sha: Option<&str>,
+    target: &Path,
+) -> Result<Fetched> {
+    let remote = dataset.remote_name();
+    let checksum = client
+        .get(source.url_for(&format!("{remote}.sha256sum")))
+        .timeout(Duration::from_secs(CHECKSUM_TIMEOUT_SECS))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let expected = parse_checksum(&checksum).ok_or_else(|| anyhow!("校验文件格式无效"))?;
+
+    if local_sha.is_some_and(|local| local.eq_ignore_ascii_case(&expected)) {
+        return Ok(Fetched::Unchanged {
+            sha256: expected,
+            source: source.name.clone(),
+        });
+    }
+
+    let response = client
+        .get(source.url_for(remote))
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .send()
+        .await?
+        .error_for_status()?;
+    let version = response
+        .headers()
+        .get(reqwest::header::LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let data = response.bytes().await?;
+    if data.is_empty() {
+        bail!("下载内容为空");
+    }
+    let actual = hex::encode(Sha256::digest(&data));
+    if !actual.eq_ignore_ascii_case(&expected) {
+        bail!("sha256 校验失败，期望 {expected}，实际 {actual}");
+    }
+
+    // 先写入临时文件再替换，避免内核读取到不完整的文件
+    let temp = target.with_extension("download");
+    fs::write(&temp, &data)?;
+    if let Err(e) = fs::rename(&temp, target) {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+
+    Ok(Fetched::Updated {
+        sha256: actual,
+        version,
+        source: source.name.clone(),
+    })
+}
+
+/// 解析 sha256sum 格式（"<hex>  <文件名>"）
+fn parse_checksum(content: &str) -> Option<String> {
+    let hash = content.split_whitespace().next()?;
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| hash.to_ascii_lowercase())
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(GEO_DATA_FILE))
+}
+
+fn load_state() -> Result<GeoDataState> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(GeoDataState::default());
+    }
+    Ok(serde_yaml_ng::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_state(state: &GeoDataState) -> Result<()> {
+    fs::write(state_path()?, serde_yaml_ng::to_string(state)?)?;
+    Ok(())
+}
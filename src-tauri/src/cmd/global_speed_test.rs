@@ -9,10 +9,24 @@
     clippy::manual_map
 )]
 // TODO: 清理临时豁免，逐步优化代码。
-use crate::{config::Config, ipc::IpcManager, utils::dirs};
+use super::{
+    CmdResult,
+    job_manager::{JobClass, JobKind, JobPriority, run_job_with},
+};
+use crate::{
+    cmd::{
+        delay_targets::{self, DelayTargetResult},
+        event_publisher::{AutomationEvent, publish_event},
+        ipv6::IPV6_TEST_URL,
+    },
+    config::Config,
+    ipc::IpcManager,
+    utils::dirs,
+};
 use anyhow::Result;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::{
     collections::HashMap,
     sync::atomic::{AtomicBool, Ordering},
@@ -26,6 +40,23 @@ static CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
 /// 最新测速结果，用于应用最佳节点
 static LATEST_RESULTS: Mutex<Option<GlobalSpeedTestSummary>> = Mutex::new(None);
 
+/// 测速是否正在进行，避免快捷键等重复触发
+static SPEED_TEST_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 测速结束（包括提前返回）时清除运行标志
+struct SpeedTestRunningGuard;
+
+impl Drop for SpeedTestRunningGuard {
+    fn drop(&mut self) {
+        SPEED_TEST_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 全局测速是否正在进行
+pub(crate) fn is_speed_test_running() -> bool {
+    SPEED_TEST_RUNNING.load(Ordering::SeqCst)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeedTestResult {
     pub node_name: String,
@@ -41,6 +72,17 @@ pub struct SpeedTestResult {
     pub score: f64,
     pub region: Option<String>,
     pub traffic_info: Option<TrafficInfo>,
+    #[serde(default)]
+    pub ipv6_supported: Option<bool>, // 节点能否访问仅 IPv6 的地址，未经代理测试时为空
+    #[serde(default)]
+    pub target_latencies: Option<Vec<DelayTargetResult>>, // 配置了多个测速目标时各目标的延迟
+}
+
+/// 通过内核测速的结果
+struct ProxyTestOutcome {
+    latency: u64,
+    ipv6_supported: Option<bool>,
+    target_latencies: Option<Vec<DelayTargetResult>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +132,10 @@ pub struct GlobalSpeedTestSummary {
     pub all_results: Vec<SpeedTestResult>, // 所有节点结果（按评分排序）
     pub results_by_profile: HashMap<String, Vec<SpeedTestResult>>,
     pub duration_seconds: u64,
+    #[serde(default)]
+    pub run_id: String, // 测速批次，用于导出报告
+    #[serde(default)]
+    pub finished_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,29 +145,66 @@ pub struct SpeedTestConfig {
     pub batch_timeout_seconds: u64,
     pub overall_timeout_seconds: u64,
     pub max_concurrent: usize,
+    /// 最多测试的节点数量，None 表示全部（轻量测速使用）
+    #[serde(default)]
+    pub max_nodes: Option<usize>,
+    /// 仅测试指定订阅，None 表示全部
+    #[serde(default)]
+    pub profile_uids: Option<Vec<String>>,
 }
 
-/// 全局节点测速
+impl Default for SpeedTestConfig {
+    fn default() -> Self {
+        // 🔧 修复：针对1000+节点的大批量测速优化配置
+        Self {
+            batch_size: 1,                 // 🔧 严格单节点处理，避免任何并发
+            node_timeout_seconds: 3,       // 🔧 减少单节点超时，提高效率
+            batch_timeout_seconds: 10,     // 🔧 批次超时大幅减少
+            overall_timeout_seconds: 1800, // 🔧 总超时增加到30分钟，适应1000+节点
+            max_concurrent: 1,             // 🔧 严格禁用并发
+            max_nodes: None,
+            profile_uids: None,
+        }
+    }
+}
+
+/// 全局节点测速，通过后台任务队列与健康检查等访问 Clash API 的任务串行执行
 #[tauri::command]
 pub async fn start_global_speed_test(
     app_handle: tauri::AppHandle,
     config: Option<SpeedTestConfig>,
-) -> Result<String, String> {
+) -> CmdResult<String> {
     log::info!(target: "app", "🚀 [前端请求] 开始全局节点测速");
+    Ok(run_job_with(
+        "Global speed test",
+        JobKind::SpeedTest,
+        JobClass::ClashApi,
+        JobPriority::High,
+        run_global_speed_test(Some(app_handle), config),
+    )
+    .await?)
+}
+
+/// 执行全局节点测速，app_handle 为 None 时不发送进度事件（命令行模式）
+pub async fn run_global_speed_test(
+    app_handle: Option<tauri::AppHandle>,
+    config: Option<SpeedTestConfig>,
+) -> Result<String, String> {
+    if SPEED_TEST_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("全局测速正在进行中".to_string());
+    }
+    let _running = SpeedTestRunningGuard;
+    // 节点解锁检测会临时切换代理，与测速同时进行会互相干扰
+    if super::media_unlock_checker::is_node_check_running() {
+        return Err("节点解锁检测正在进行中，请稍后再测速".to_string());
+    }
     log::info!(target: "app", "📋 [测速配置] {:?}", config);
 
     // 重置取消标志
     CANCEL_FLAG.store(false, Ordering::SeqCst);
     log::info!(target: "app", "✅ [测速状态] 已重置取消标志");
 
-    // 🔧 修复：针对1000+节点的大批量测速优化配置
-    let config = config.unwrap_or(SpeedTestConfig {
-        batch_size: 1,                 // 🔧 严格单节点处理，避免任何并发
-        node_timeout_seconds: 3,       // 🔧 减少单节点超时，提高效率
-        batch_timeout_seconds: 10,     // 🔧 批次超时大幅减少
-        overall_timeout_seconds: 1800, // 🔧 总超时增加到30分钟，适应1000+节点
-        max_concurrent: 1,             // 🔧 严格禁用并发
-    });
+    let config = config.unwrap_or_default();
 
     log::info!(target: "app", "⚙️ 测速配置: 批次大小={}, 节点超时={}s, 批次超时={}s, 总体超时={}s, 最大并发={}", 
               config.batch_size, config.node_timeout_seconds, config.batch_timeout_seconds, 
@@ -172,6 +255,14 @@ pub async fn start_global_speed_test(
         log::debug!(target: "app", "🔍 处理订阅 {}/{}: {} (类型: {})", 
                   index + 1, profiles.len(), profile_name, profile_type);
 
+        // 仅测试指定的订阅
+        if let Some(uids) = &config.profile_uids
+            && !uids.is_empty()
+            && !uids.iter().any(|uid| uid == profile_uid)
+        {
+            continue;
+        }
+
         // 跳过系统配置项（script、merge 等）
         if matches!(profile_type.to_lowercase().as_str(), "script" | "merge") {
             log::debug!(target: "app", "⏭️ 跳过系统配置项: {} (类型: {})", profile_name, profile_type);
@@ -246,6 +337,11 @@ pub async fn start_global_speed_test(
         }
     }
 
+    // 轻量测速：限制节点数量
+    if let Some(max_nodes) = config.max_nodes {
+        all_nodes_with_profile.truncate(max_nodes);
+    }
+
     let total_nodes = all_nodes_with_profile.len();
 
     if total_nodes == 0 {
@@ -316,7 +412,7 @@ pub async fn start_global_speed_test(
             total_batches,
             estimated_remaining_seconds: ((total_batches - batch_index) * 15).max(1) as u64,
         };
-        let _ = app_handle.emit("global-speed-test-progress", progress);
+        emit_event(&app_handle, "global-speed-test-progress", progress);
 
         // 🔧 修复：顺序测试批次节点，避免并发竞争导致假死
         log::info!(target: "app", "🔄 [批次处理] 开始顺序测试批次 {}/{} 的 {} 个节点", 
@@ -345,7 +441,7 @@ pub async fn start_global_speed_test(
                 completed: completed_count,
                 total: total_nodes,
             };
-            let _ = app_handle.emit("node-test-update", update);
+            emit_event(&app_handle, "node-test-update", update);
 
             // 🔧 修复：顺序测试单个节点，避免并发竞争
             let node_start_time = Instant::now();
@@ -405,7 +501,7 @@ pub async fn start_global_speed_test(
                             completed: all_results.len() + 1,
                             total: total_nodes,
                         };
-                        let _ = app_handle.emit("node-test-update", update);
+                        emit_event(&app_handle, "node-test-update", update);
 
                         all_results.push(test_result);
                     }
@@ -443,10 +539,44 @@ pub async fn start_global_speed_test(
     let summary = analyze_results(all_results, duration);
 
     // 保存结果供后续使用
-    *LATEST_RESULTS.lock() = Some(summary.clone());
+    let previous_best = LATEST_RESULTS
+        .lock()
+        .replace(summary.clone())
+        .and_then(|previous| previous.best_node)
+        .map(|node| node.node_name);
+    if let Err(e) = super::speed_test_report::save_run(&summary) {
+        log::warn!(target: "app", "⚠️ 保存测速记录失败: {}", e);
+    }
+
+    publish_event(
+        AutomationEvent::SpeedTestFinished,
+        json!({
+            "run_id": summary.run_id,
+            "total_nodes": summary.total_nodes,
+            "successful_tests": summary.successful_tests,
+            "failed_tests": summary.failed_tests,
+            "duration_seconds": summary.duration_seconds,
+        }),
+    );
+    if let Some(best) = &summary.best_node
+        && previous_best.as_deref() != Some(best.node_name.as_str())
+    {
+        publish_event(
+            AutomationEvent::BestNodeChanged,
+            json!({
+                "previous": previous_best,
+                "current": best.node_name,
+                "profile_name": best.profile_name,
+                "latency": best.latency,
+            }),
+        );
+    }
+
+    // 检测多数节点连续失败的订阅
+    super::provider_outage::observe_node_results(&summary.all_results).await;
 
     // 发送完成事件
-    let _ = app_handle.emit("global-speed-test-complete", summary.clone());
+    emit_event(&app_handle, "global-speed-test-complete", summary.clone());
 
     log::info!(target: "app", "📈 测速统计: 总计 {} 个节点，成功 {} 个，失败 {} 个",
               summary.total_nodes, summary.successful_tests, summary.failed_tests);
@@ -461,9 +591,30 @@ pub async fn start_global_speed_test(
     Ok("全局节点测速完成".to_string())
 }
 
+fn emit_event<S: Serialize + Clone>(
+    app_handle: &Option<tauri::AppHandle>,
+    event: &str,
+    payload: S,
+) {
+    if let Some(app_handle) = app_handle {
+        let _ = app_handle.emit(event, payload);
+    }
+}
+
+/// 获取最近一次全局测速结果
+pub fn latest_speed_test_summary() -> Option<GlobalSpeedTestSummary> {
+    LATEST_RESULTS.lock().clone()
+}
+
+/// 写入测速结果（测试数据生成使用）
+#[cfg(feature = "dev-fixtures")]
+pub(crate) fn store_speed_test_summary(summary: GlobalSpeedTestSummary) {
+    *LATEST_RESULTS.lock() = Some(summary);
+}
+
 /// 取消全局节点测速
 #[tauri::command]
-pub async fn cancel_global_speed_test(app_handle: tauri::AppHandle) -> Result<(), String> {
+pub async fn cancel_global_speed_test(app_handle: tauri::AppHandle) -> CmdResult<()> {
     log::info!(target: "app", "🛑 [前端请求] 用户取消全局测速");
 
     // 设置取消标志
@@ -482,7 +633,7 @@ pub async fn cancel_global_speed_test(app_handle: tauri::AppHandle) -> Result<()
 
 /// 应用最佳节点
 #[tauri::command]
-pub async fn apply_best_node() -> Result<String, String> {
+pub async fn apply_best_node() -> CmdResult<String> {
     log::info!(target: "app", "🎯 尝试应用最佳节点");
 
     let best_node = {
@@ -491,7 +642,7 @@ pub async fn apply_best_node() -> Result<String, String> {
             Some(summary) => summary.best_node.clone(),
             None => {
                 log::warn!(target: "app", "⚠️ 没有找到测速结果");
-                return Err("没有可用的测速结果，请先进行全局测速".to_string());
+                return Err("没有可用的测速结果，请先进行全局测速".into());
             }
         }
     };
@@ -515,20 +666,20 @@ pub async fn apply_best_node() -> Result<String, String> {
                 Err(e) => {
                     let error_msg = format!("切换节点失败: {}", e);
                     log::error!(target: "app", "❌ {}", error_msg);
-                    Err(error_msg)
+                    Err(error_msg.into())
                 }
             }
         }
         None => {
             log::warn!(target: "app", "⚠️ 没有找到可用的最佳节点");
-            Err("没有找到可用的最佳节点".to_string())
+            Err("没有找到可用的最佳节点".into())
         }
     }
 }
 
 /// 切换到指定节点
 #[tauri::command]
-pub async fn switch_to_node(profile_uid: String, node_name: String) -> Result<String, String> {
+pub async fn switch_to_node(profile_uid: String, node_name: String) -> CmdResult<String> {
     log::info!(target: "app", "🔄 切换到指定节点: {} (订阅: {})", node_name, profile_uid);
 
     // 使用 IpcManager 来切换节点
@@ -542,7 +693,7 @@ pub async fn switch_to_node(profile_uid: String, node_name: String) -> Result<St
         Err(e) => {
             let error_msg = format!("切换节点失败: {}", e);
             log::error!(target: "app", "❌ {}", error_msg);
-            Err(error_msg)
+            Err(error_msg.into())
         }
     }
 }
@@ -792,7 +943,11 @@ async fn test_single_node(node: &NodeInfo, timeout_seconds: u64) -> SpeedTestRes
 
     // 首先尝试使用Clash API进行真实的代理延迟测试
     match test_proxy_via_clash(&node.node_name, timeout_seconds).await {
-        Ok(latency) => {
+        Ok(ProxyTestOutcome {
+            latency,
+            ipv6_supported,
+            target_latencies,
+        }) => {
             let score = calculate_score(Some(latency), true);
 
             log::info!(target: "app", "✅ 节点 {} 代理测试成功，延迟: {}ms, 评分: {:.2}", 
@@ -812,6 +967,8 @@ async fn test_single_node(node: &NodeInfo, timeout_seconds: u64) -> SpeedTestRes
                 score,
                 region: identify_region(&node.server),
                 traffic_info: node.traffic_info.clone(),
+                ipv6_supported,
+                target_latencies,
             }
         }
         Err(e) => {
@@ -841,6 +998,8 @@ async fn test_single_node(node: &NodeInfo, timeout_seconds: u64) -> SpeedTestRes
                         score,
                         region: identify_region(&node.server),
                         traffic_info: node.traffic_info.clone(),
+                        ipv6_supported: None,
+                        target_latencies: None,
                     }
                 }
                 Err(tcp_error) => {
@@ -860,6 +1019,8 @@ async fn test_single_node(node: &NodeInfo, timeout_seconds: u64) -> SpeedTestRes
                         score: 0.0,
                         region: identify_region(&node.server),
                         traffic_info: node.traffic_info.clone(),
+                        ipv6_supported: None,
+                        target_latencies: None,
                     }
                 }
             }
@@ -905,7 +1066,7 @@ async fn check_clash_availability() -> Result<()> {
 }
 
 /// 通过临时切换节点进行真实代理延迟测试（修复测速逻辑）
-async fn test_proxy_via_clash(node_name: &str, timeout_seconds: u64) -> Result<u64> {
+async fn test_proxy_via_clash(node_name: &str, timeout_seconds: u64) -> Result<ProxyTestOutcome> {
     // 获取IPC管理器实例
     let ipc = IpcManager::global();
 
@@ -946,49 +1107,60 @@ async fn test_proxy_via_clash(node_name: &str, timeout_seconds: u64) -> Result<u
     // 🚀 优化：减少等待时间，避免累积延迟
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
-    // Step 5: 进行真实的延迟测试（现在通过目标节点）
-    let test_url = Some("https://cp.cloudflare.com/generate_204".to_string());
-    let timeout_ms = (timeout_seconds * 1000) as i32;
-    let start_time = std::time::Instant::now();
-
-    let test_result = {
-        let api_call = ipc.test_proxy_delay("GLOBAL", test_url, timeout_ms); // 测试当前生效的代理
-        let overall_timeout = std::time::Duration::from_secs(timeout_seconds + 3);
-
-        // 取消检查
-        let cancel_check = async {
-            loop {
-                if CANCEL_FLAG.load(Ordering::SeqCst) {
-                    return Err(anyhow::anyhow!("测速已被用户取消")) as Result<serde_json::Value>;
+    // Step 5: 进行真实的延迟测试（现在通过目标节点），依次测试所有测速目标
+    // 同时测试仅有 AAAA 记录的地址，判断节点是否支持 IPv6 出口，避免测速耗时翻倍
+    let targets = delay_targets::configured_targets();
+    let multiple_targets = targets.len() > 1;
+    let targets_test = async {
+        let mut target_results = Vec::with_capacity(targets.len());
+        let mut first_error = None;
+        for target in targets {
+            let result = measure_global_delay(
+                &target.url,
+                target.expected_status.as_deref(),
+                timeout_seconds,
+            )
+            .await;
+            let (delay, error) = match result {
+                Ok(delay) => (Some(delay), None),
+                Err(e) => {
+                    let message = e.to_string();
+                    first_error.get_or_insert(e);
+                    (None, Some(message))
                 }
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            }
-        };
-
-        // 竞争执行
-        match tokio::select! {
-            result = api_call => Ok(result),
-            _ = tokio::time::sleep(overall_timeout) => Err(anyhow::anyhow!("测试超时")),
-            cancel_result = cancel_check => Err(cancel_result.unwrap_err()),
-        } {
-            Ok(result) => match result {
-                Ok(response) => {
-                    if let Some(delay_obj) = response.as_object() {
-                        if let Some(delay) = delay_obj.get("delay").and_then(|v| v.as_u64()) {
-                            let elapsed = start_time.elapsed();
-                            log::debug!(target: "app", "✅ 真实代理延迟: {}ms (耗时: {:?})", delay, elapsed);
-                            Ok(delay)
-                        } else {
-                            Err(anyhow::anyhow!("API响应格式无效"))
-                        }
-                    } else {
-                        Err(anyhow::anyhow!("API响应不是有效JSON"))
-                    }
+            };
+            target_results.push(DelayTargetResult {
+                name: target.name,
+                url: target.url,
+                delay,
+                error,
+            });
+        }
+        (target_results, first_error)
+    };
+    let ipv6_test = measure_global_delay(IPV6_TEST_URL, None, timeout_seconds);
+    let ((target_results, first_error), ipv6_result) = tokio::join!(targets_test, ipv6_test);
+
+    let test_result = match delay_targets::average_delay(&target_results) {
+        Some(delay) => {
+            let ipv6_supported = match ipv6_result {
+                Ok(ipv6_delay) => {
+                    log::debug!(target: "app", "✅ IPv6 代理延迟: {}ms", ipv6_delay);
+                    Some(true)
+                }
+                Err(_) if CANCEL_FLAG.load(Ordering::SeqCst) => None,
+                Err(e) => {
+                    log::debug!(target: "app", "节点 '{}' 不支持 IPv6: {}", node_name, e);
+                    Some(false)
                 }
-                Err(e) => Err(anyhow::anyhow!("API调用失败: {}", e)),
-            },
-            Err(e) => Err(e),
+            };
+            Ok(ProxyTestOutcome {
+                latency: delay,
+                ipv6_supported,
+                target_latencies: multiple_targets.then_some(target_results),
+            })
         }
+        None => Err(first_error.unwrap_or_else(|| anyhow::anyhow!("没有可用的测速目标"))),
     };
 
     // Step 6: 恢复原始代理配置（无论测试成功与否）
@@ -1022,6 +1194,55 @@ async fn test_proxy_via_clash(node_name: &str, timeout_seconds: u64) -> Result<u
     test_result
 }
 
+/// 通过 GLOBAL 组测试当前生效节点到指定地址的延迟，支持取消
+async fn measure_global_delay(
+    test_url: &str,
+    expected: Option<&str>,
+    timeout_seconds: u64,
+) -> Result<u64> {
+    let ipc = IpcManager::global();
+    let timeout_ms = (timeout_seconds * 1000) as i32;
+    let start_time = std::time::Instant::now();
+
+    let api_call = ipc.test_proxy_delay("GLOBAL", Some(test_url.to_string()), expected, timeout_ms); // 测试当前生效的代理
+    let overall_timeout = std::time::Duration::from_secs(timeout_seconds + 3);
+
+    // 取消检查
+    let cancel_check = async {
+        loop {
+            if CANCEL_FLAG.load(Ordering::SeqCst) {
+                return Err(anyhow::anyhow!("测速已被用户取消")) as Result<serde_json::Value>;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    };
+
+    // 竞争执行
+    match tokio::select! {
+        result = api_call => Ok(result),
+        _ = tokio::time::sleep(overall_timeout) => Err(anyhow::anyhow!("测试超时")),
+        cancel_result = cancel_check => Err(cancel_result.unwrap_err()),
+    } {
+        Ok(result) => match result {
+            Ok(response) => {
+                if let Some(delay_obj) = response.as_object() {
+                    if let Some(delay) = delay_obj.get("delay").and_then(|v| v.as_u64()) {
+                        let elapsed = start_time.elapsed();
+                        log::debug!(target: "app", "✅ 真实代理延迟: {}ms (耗时: {:?})", delay, elapsed);
+                        Ok(delay)
+                    } else {
+                        Err(anyhow::anyhow!("API响应格式无效"))
+                    }
+                } else {
+                    Err(anyhow::anyhow!("API响应不是有效JSON"))
+                }
+            }
+            Err(e) => Err(anyhow::anyhow!("API调用失败: {}", e)),
+        },
+        Err(e) => Err(e),
+    }
+}
+
 /// TCP连接测试（作为备用方案）
 async fn test_tcp_connection(server: &str, port: u16, timeout_seconds: u64) -> Result<u64> {
     let start_time = Instant::now();
@@ -1169,6 +1390,8 @@ fn analyze_results(
         all_results: results,
         results_by_profile,
         duration_seconds: duration.as_secs(),
+        run_id: uuid::Uuid::new_v4().to_string(),
+        finished_at: chrono::Local::now().timestamp(),
     }
 }
 
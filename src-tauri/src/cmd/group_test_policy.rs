@@ -0,0 +1,245 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use tokio::sync::Mutex;
+
+const GROUP_TEST_POLICIES_FILE: &str = "group_test_policies.yaml";
+
+/// 测速间隔范围（秒）
+const MIN_INTERVAL: u32 = 10;
+const MAX_INTERVAL: u32 = 24 * 3600;
+/// 容差上限（毫秒）
+const MAX_TOLERANCE: u32 = 10_000;
+
+static POLICIES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 自动选择策略，对应 mihomo 的分组类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoSelectStrategy {
+    UrlTest,  // 选择延迟最低的节点
+    Fallback, // 按顺序选择第一个可用节点
+}
+
+impl AutoSelectStrategy {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::UrlTest => "url-test",
+            Self::Fallback => "fallback",
+        }
+    }
+
+    fn parse(group_type: &str) -> Option<Self> {
+        match group_type {
+            "url-test" => Some(Self::UrlTest),
+            "fallback" => Some(Self::Fallback),
+            _ => None,
+        }
+    }
+}
+
+/// 分组测速策略，未设置的字段保留订阅中的原值
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GroupTestPolicy {
+    pub url: Option<String>,
+    pub interval: Option<u32>,  // 秒
+    pub tolerance: Option<u32>, // 毫秒，仅 url-test 生效
+    pub strategy: Option<AutoSelectStrategy>,
+}
+
+impl GroupTestPolicy {
+    fn is_empty(&self) -> bool {
+        self.url.is_none()
+            && self.interval.is_none()
+            && self.tolerance.is_none()
+            && self.strategy.is_none()
+    }
+
+    fn validate(&self) -> CmdResult<()> {
+        if let Some(url) = &self.url {
+            let valid = url::Url::parse(url)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+            if !valid {
+                return Err(invalid(format!("测速地址无效: {url}")));
+            }
+        }
+        if let Some(interval) = self.interval
+            && !(MIN_INTERVAL..=MAX_INTERVAL).contains(&interval)
+        {
+            return Err(invalid(format!(
+                "测速间隔需在 {MIN_INTERVAL} 到 {MAX_INTERVAL} 秒之间"
+            )));
+        }
+        if let Some(tolerance) = self.tolerance
+            && tolerance > MAX_TOLERANCE
+        {
+            return Err(invalid(format!("容差不能超过 {MAX_TOLERANCE} 毫秒")));
+        }
+        Ok(())
+    }
+
+    /// 写入分组配置
+    fn apply(&self, group: &mut Mapping) {
+        if let Some(strategy) = self.strategy {
+            group.insert("type".into(), strategy.as_str().into());
+        }
+        if let Some(url) = &self.url {
+            group.insert("url".into(), url.as_str().into());
+        }
+        if let Some(interval) = self.interval {
+            group.insert("interval".into(), interval.into());
+        }
+        if let Some(tolerance) = self.tolerance
+            && group.get("type").and_then(Value::as_str) == Some("url-test")
+        {
+            group.insert("tolerance".into(), tolerance.into());
+        }
+    }
+}
+
+/// 设置 url-test / fallback 分组的测速策略，所有字段为空时移除覆盖
+#[tauri::command]
+pub async fn set_group_test_policy(
+    group: String,
+    policy: GroupTestPolicy,
+) -> CmdResult<BTreeMap<String, GroupTestPolicy>> {
+    let group = group.trim().to_string();
+    policy.validate()?;
+    ensure_auto_group(&group).await?;
+
+    let _guard = POLICIES_LOCK.lock().await;
+    let previous = load_policies().context("加载分组测速策略失败")?;
+    let mut policies = previous.clone();
+    if policy.is_empty() {
+        policies.remove(&group);
+    } else {
+        policies.insert(group.clone(), policy.clone());
+    }
+    apply_policies(&policies, &previous).await?;
+
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[分组测速策略] {}: {:?}",
+        group,
+        policy
+    );
+    Ok(policies)
+}
+
+/// 获取所有分组测速策略
+#[tauri::command]
+pub async fn list_group_test_policies() -> CmdResult<BTreeMap<String, GroupTestPolicy>> {
+    load_policies()
+        .context("加载分组测速策略失败")
+        .map_err(CmdError::from)
+}
+
+/// 将分组测速策略写入配置，供增强链使用；分组已不是自动选择类型时跳过
+pub fn use_group_test_policies(mut config: Mapping) -> Mapping {
+    let policies = match load_policies() {
+        Ok(policies) if !policies.is_empty() => policies,
+        _ => return config,
+    };
+    let Some(Value::Sequence(groups)) = config.get_mut("proxy-groups") else {
+        return config;
+    };
+
+    for group in groups.iter_mut().filter_map(Value::as_mapping_mut) {
+        let Some(policy) = group
+            .get("name")
+            .and_then(Value::as_str)
+            .and_then(|name| policies.get(name))
+        else {
+            continue;
+        };
+        let is_auto = group
+            .get("type")
+            .and_then(Value::as_str)
+            .and_then(AutoSelectStrategy::parse)
+            .is_some();
+        if is_auto {
+            policy.apply(group);
+        }
+    }
+    config
+}
+
+// ===== 内部实现函数 =====
+
+fn invalid(message: String) -> CmdError {
+    CmdError::new(ErrorCode::InvalidArgument, message)
+}
+
+/// 分组必须存在于运行配置中且为 url-test / fallback 类型
+async fn ensure_auto_group(group: &str) -> CmdResult<()> {
+    let runtime = Config::runtime().await;
+    let runtime = runtime.latest_ref();
+    let config = runtime.config.as_ref().ok_or("运行配置不存在")?;
+    let group_type = config
+        .get("proxy-groups")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .find(|g| g.get("name").and_then(Value::as_str) == Some(group))
+        .ok_or_else(|| invalid(format!("代理组不存在: {group}")))?
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if AutoSelectStrategy::parse(group_type).is_none() {
+        return Err(invalid(format!(
+            "代理组 {group} 的类型为 {group_type}，仅支持 url-test / fallback"
+        )));
+    }
+    Ok(())
+}
+
+/// 写入策略并重新生成配置，内核验证失败时恢复原策略
+async fn apply_policies(
+    policies: &BTreeMap<String, GroupTestPolicy>,
+    previous: &BTreeMap<String, GroupTestPolicy>,
+) -> CmdResult<()> {
+    save_policies(policies).context("保存分组测速策略失败")?;
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            save_policies(previous).context("恢复分组测速策略失败")?;
+            Err(CmdError::new(
+                ErrorCode::ConfigValidationFailed,
+                format!("分组测速策略未通过内核验证，已恢复: {error}"),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn policies_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(GROUP_TEST_POLICIES_FILE))
+}
+
+fn load_policies() -> Result<BTreeMap<String, GroupTestPolicy>> {
+    let path = policies_path()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_yaml_ng::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_policies(policies: &BTreeMap<String, GroupTestPolicy>) -> Result<()> {
+    fs::write(policies_path()?, serde_yaml_ng::to_string(policies)?)?;
+    Ok(())
+}
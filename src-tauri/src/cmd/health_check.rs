@@ -10,15 +10,21 @@
     clippy::needless_pass_by_value
 )]
 // TODO: 后续分阶段处理健康检查模块的 Clippy 提示。
-use super::CmdResult;
+use super::{
+    CmdResult,
+    job_manager::{JobClass, JobKind, JobPriority, run_job_with},
+    task_manager::TaskType,
+};
 use crate::{
-    config::{Config, PrfItem},
+    config::{Config, FetchRoute, PrfExtra, PrfItem, PrfOption},
     logging,
-    utils::logging::Type,
+    utils::{dirs, fetcher, help, logging::Type, network::resolve_proxy_url},
 };
-use reqwest::Client;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::error::Error as _;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
@@ -35,6 +41,42 @@ pub struct SubscriptionHealthResult {
     pub last_update: Option<i64>,
     pub error_message: Option<String>,
     pub last_checked: i64,
+    #[serde(default)]
+    pub report: Option<SubscriptionHealthReport>, // 远程订阅的 HTTP 诊断
+}
+
+/// 每个订阅保留的诊断历史条数
+const HEALTH_HISTORY_LIMIT: usize = 50;
+
+/// 诊断历史读写锁，批量检查时并发写入
+static HEALTH_HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 订阅 HTTP 层诊断报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionHealthReport {
+    pub checked_at: i64,
+    pub http_status: Option<u16>,
+    pub error_kind: Option<DiagnosticErrorKind>,
+    pub error_message: Option<String>,
+    pub tls_error: Option<String>,
+    pub response_time: Option<u64>, // 毫秒
+    pub content_length: Option<usize>,
+    pub userinfo: Option<PrfExtra>, // subscription-userinfo 响应头
+    pub expires_in_days: Option<i64>,
+    pub traffic_used_percent: Option<f64>,
+    pub node_count: Option<usize>,
+    pub node_count_delta: Option<i64>, // 与上次检查相比的节点数变化
+}
+
+/// 诊断错误类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticErrorKind {
+    Timeout,    // 请求超时
+    Connect,    // 无法建立连接
+    Tls,        // TLS 握手或证书错误
+    HttpStatus, // 非 2xx 状态码
+    Body,       // 读取响应内容失败
 }
 
 /// 健康状态枚举
@@ -90,9 +132,21 @@ pub async fn check_subscription_health(uid: String) -> CmdResult<SubscriptionHea
     Ok(result)
 }
 
-/// 批量检查所有订阅的健康状态
+/// 批量检查所有订阅的健康状态，通过后台任务队列与计划任务中的健康检查、测速串行执行
 #[tauri::command]
 pub async fn check_all_subscriptions_health() -> CmdResult<BatchHealthResult> {
+    Ok(run_job_with(
+        "Subscription health check",
+        JobKind::HealthCheck,
+        JobClass::for_task(&TaskType::HealthCheck),
+        JobPriority::High,
+        async { Ok(run_all_health_checks().await) },
+    )
+    .await?)
+}
+
+/// 检查所有订阅的健康状态，已在任务队列中执行的计划任务直接调用
+pub(crate) async fn run_all_health_checks() -> BatchHealthResult {
     let start_time = Instant::now();
     logging!(info, Type::Cmd, true, "[批量健康检查] 开始检查所有订阅");
 
@@ -172,7 +226,7 @@ pub async fn check_all_subscriptions_health() -> CmdResult<BatchHealthResult> {
         check_duration
     );
 
-    Ok(batch_result)
+    batch_result
 }
 
 /// 获取订阅详细信息（节点数量等）
@@ -231,6 +285,7 @@ async fn check_single_subscription(profile: &PrfItem) -> SubscriptionHealthResul
         last_update: last_update.map(|u| u as i64),
         error_message: None,
         last_checked: now,
+        report: None,
     };
 
     // 如果是本地文件，检查文件是否存在
@@ -251,39 +306,121 @@ async fn check_single_subscription(profile: &PrfItem) -> SubscriptionHealthResul
 
     // 检查远程订阅
     if let Some(subscription_url) = url {
-        let start_time = Instant::now();
+        // 配置了拉取策略时按相同路径检查，否则保持直接请求
+        let routes = if PrfOption::has_fetch_policy(profile.option.as_ref()) {
+            PrfOption::fetch_routes(profile.option.as_ref())
+        } else {
+            vec![FetchRoute::Direct]
+        };
+        let report =
+            diagnose_remote_subscription(&uid, &result.name, &subscription_url, &routes).await;
+        result.response_time = report.response_time;
+        result.node_count = report.node_count;
+        result.error_message = report.error_message.clone();
+        result.status = if report.error_kind.is_some() {
+            HealthStatus::Unhealthy
+        } else {
+            HealthStatus::Healthy
+        };
+
+        if report.error_kind.is_none() {
+            let warning = if report.response_time.unwrap_or(0) > 10000 {
+                Some("响应时间过长")
+            } else if report.node_count == Some(0) {
+                Some("订阅中没有可用节点")
+            } else if report.expires_in_days.is_some_and(|days| days < 0) {
+                Some("订阅已过期")
+            } else if report.traffic_used_percent.is_some_and(|p| p >= 100.0) {
+                Some("订阅流量已用尽")
+            } else if report
+                .node_count_delta
+                .zip(report.node_count)
+                .is_some_and(|(delta, count)| delta < 0 && (count as i64) < -delta)
+            {
+                Some("节点数量较上次减少过半")
+            } else {
+                None
+            };
+            if let Some(warning) = warning {
+                result.status = HealthStatus::Warning;
+                result.error_message = Some(warning.to_string());
+            }
+        }
 
-        match check_remote_subscription(&subscription_url).await {
-            Ok(response_info) => {
-                result.response_time = Some(start_time.elapsed().as_millis() as u64);
-                result.status = HealthStatus::Healthy;
+        result.report = Some(report);
+    }
 
-                // 检查响应时间是否过长
-                if result.response_time.unwrap_or(0) > 10000 {
-                    result.status = HealthStatus::Warning;
-                    result.error_message = Some("响应时间过长".to_string());
-                }
+    result
+}
 
-                // 尝试解析节点数量
-                if let Some(content) = response_info.content {
-                    let node_count = count_nodes_in_config(&content);
-                    result.node_count = Some(node_count);
+/// 诊断远程订阅并记录到历史
+async fn diagnose_remote_subscription(
+    uid: &str,
+    name: &str,
+    url: &str,
+    routes: &[FetchRoute],
+) -> SubscriptionHealthReport {
+    let start_time = Instant::now();
+    let mut report = SubscriptionHealthReport {
+        checked_at: chrono::Utc::now().timestamp(),
+        http_status: None,
+        error_kind: None,
+        error_message: None,
+        tls_error: None,
+        response_time: None,
+        content_length: None,
+        userinfo: None,
+        expires_in_days: None,
+        traffic_used_percent: None,
+        node_count: None,
+        node_count_delta: None,
+    };
 
-                    if node_count == 0 {
-                        result.status = HealthStatus::Warning;
-                        result.error_message = Some("订阅中没有可用节点".to_string());
-                    }
+    match check_with_routes(url, routes).await {
+        Ok(response) => {
+            report.http_status = Some(response.status_code);
+            report.userinfo = response
+                .headers
+                .get("subscription-userinfo")
+                .map(|info| parse_userinfo(info));
+            if let Some(info) = &report.userinfo {
+                if info.expire > 0 {
+                    report.expires_in_days = Some((info.expire as i64 - report.checked_at) / 86400);
+                }
+                if info.total > 0 {
+                    report.traffic_used_percent =
+                        Some((info.upload + info.download) as f64 / info.total as f64 * 100.0);
                 }
             }
-            Err(error_msg) => {
-                result.status = HealthStatus::Unhealthy;
-                result.error_message = Some(error_msg);
-                result.response_time = Some(start_time.elapsed().as_millis() as u64);
+            match response.content {
+                Some(content) => {
+                    report.content_length = Some(content.len());
+                    report.node_count = Some(count_nodes_in_config(&content));
+                }
+                None => {
+                    report.error_kind = Some(DiagnosticErrorKind::Body);
+                    report.error_message = Some("无法读取订阅内容".to_string());
+                }
+            }
+        }
+        Err(failure) => {
+            report.http_status = failure.status_code;
+            report.error_kind = Some(failure.kind);
+            if failure.kind == DiagnosticErrorKind::Tls {
+                report.tls_error = Some(failure.message.clone());
             }
+            report.error_message = Some(failure.message);
         }
     }
+    report.response_time = Some(start_time.elapsed().as_millis() as u64);
 
-    result
+    match record_health_report(uid, &mut report) {
+        Ok(history) => super::provider_outage::observe_endpoint(uid, name, &history).await,
+        Err(e) => {
+            logging!(warn, Type::Cmd, true, "[健康检查] 保存诊断历史失败: {}", e);
+        }
+    }
+    report
 }
 
 /// 检查远程订阅的响应信息
@@ -294,23 +431,108 @@ struct SubscriptionResponse {
     headers: HashMap<String, String>,
 }
 
+/// 远程订阅请求失败信息
+#[derive(Debug)]
+struct SubscriptionFailure {
+    kind: DiagnosticErrorKind,
+    message: String,
+    status_code: Option<u16>,
+}
+
+impl SubscriptionFailure {
+    fn new(kind: DiagnosticErrorKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            status_code: None,
+        }
+    }
+
+    /// 根据 reqwest 错误链区分超时、TLS 与连接错误
+    fn from_request_error(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return Self::new(DiagnosticErrorKind::Timeout, "请求超时".to_string());
+        }
+
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(cause) = source {
+            chain.push(cause.to_string());
+            source = cause.source();
+        }
+        let detail = chain.join(": ");
+        let lower = detail.to_lowercase();
+        if ["certificate", "tls", "ssl", "handshake"]
+            .iter()
+            .any(|k| lower.contains(k))
+        {
+            Self::new(DiagnosticErrorKind::Tls, format!("TLS错误: {}", detail))
+        } else {
+            Self::new(
+                DiagnosticErrorKind::Connect,
+                format!("请求失败: {}", detail),
+            )
+        }
+    }
+}
+
+/// 按顺序经各下载路径检查，返回第一个成功的结果或最后一个失败
+async fn check_with_routes(
+    url: &str,
+    routes: &[FetchRoute],
+) -> Result<SubscriptionResponse, SubscriptionFailure> {
+    let mut last_failure = SubscriptionFailure::new(
+        DiagnosticErrorKind::Connect,
+        "没有可用的下载路径".to_string(),
+    );
+    for route in routes {
+        let Some(proxy_type) = route.proxy_type() else {
+            last_failure = SubscriptionFailure::new(
+                DiagnosticErrorKind::Connect,
+                format!("{route:?}: 节点监听端口不可用"),
+            );
+            continue;
+        };
+        let proxy_url = resolve_proxy_url(proxy_type).await;
+        match check_remote_subscription(url, proxy_url.as_deref()).await {
+            Ok(response) => return Ok(response),
+            Err(failure) => last_failure = failure,
+        }
+    }
+    Err(last_failure)
+}
+
 /// 检查远程订阅
-async fn check_remote_subscription(url: &str) -> Result<SubscriptionResponse, String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .user_agent("liebseu-clash/health-checker")
-        .build()
-        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
-
-    let response = timeout(Duration::from_secs(30), client.get(url).send())
+async fn check_remote_subscription(
+    url: &str,
+    proxy_url: Option<&str>,
+) -> Result<SubscriptionResponse, SubscriptionFailure> {
+    let client = fetcher::shared_client(proxy_url, false).map_err(|e| {
+        SubscriptionFailure::new(
+            DiagnosticErrorKind::Connect,
+            format!("创建HTTP客户端失败: {}", e),
+        )
+    })?;
+    let request = client
+        .get(url)
+        .header(reqwest::header::USER_AGENT, "liebseu-clash/health-checker")
+        .send();
+
+    let response = timeout(Duration::from_secs(30), request)
         .await
-        .map_err(|_| "请求超时".to_string())?
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(|_| {
+            SubscriptionFailure::new(DiagnosticErrorKind::Timeout, "请求超时".to_string())
+        })?
+        .map_err(|e| SubscriptionFailure::from_request_error(&e))?;
 
     let status_code = response.status().as_u16();
 
     if !response.status().is_success() {
-        return Err(format!("HTTP错误: {}", status_code));
+        return Err(SubscriptionFailure {
+            kind: DiagnosticErrorKind::HttpStatus,
+            message: format!("HTTP错误: {}", status_code),
+            status_code: Some(status_code),
+        });
     }
 
     // 收集响应头
@@ -341,6 +563,16 @@ async fn check_remote_subscription(url: &str) -> Result<SubscriptionResponse, St
     })
 }
 
+/// 解析 subscription-userinfo 响应头
+fn parse_userinfo(info: &str) -> PrfExtra {
+    PrfExtra {
+        upload: help::parse_str(info, "upload").unwrap_or(0),
+        download: help::parse_str(info, "download").unwrap_or(0),
+        total: help::parse_str(info, "total").unwrap_or(0),
+        expire: help::parse_str(info, "expire").unwrap_or(0),
+    }
+}
+
 /// 统计配置文件中的节点数量
 fn count_nodes_in_config(content: &str) -> usize {
     // 尝试解析YAML格式
@@ -368,6 +600,64 @@ fn count_nodes_in_config(content: &str) -> usize {
     lines_with_proxy_fields / 4
 }
 
+/// 获取订阅的诊断历史，按时间从新到旧排列
+#[tauri::command]
+pub async fn get_subscription_health_history(
+    uid: String,
+) -> CmdResult<Vec<SubscriptionHealthReport>> {
+    let _guard = HEALTH_HISTORY_LOCK.lock();
+    let mut history = load_health_history().map_err(|e| format!("加载诊断历史失败: {}", e))?;
+    let mut reports = history.remove(&uid).unwrap_or_default();
+    reports.reverse();
+    Ok(reports)
+}
+
+/// 写入诊断历史，并计算与上次检查相比的节点数变化，返回该订阅的历史
+fn record_health_report(
+    uid: &str,
+    report: &mut SubscriptionHealthReport,
+) -> anyhow::Result<Vec<SubscriptionHealthReport>> {
+    let _guard = HEALTH_HISTORY_LOCK.lock();
+    let mut history = load_health_history()?;
+    let reports = history.entry(uid.to_string()).or_default();
+
+    let previous = reports.iter().rev().find_map(|r| r.node_count);
+    report.node_count_delta = report
+        .node_count
+        .zip(previous)
+        .map(|(current, previous)| current as i64 - previous as i64);
+
+    reports.push(report.clone());
+    if reports.len() > HEALTH_HISTORY_LIMIT {
+        reports.drain(..reports.len() - HEALTH_HISTORY_LIMIT);
+    }
+    let reports = reports.clone();
+    save_health_history(&history)?;
+    Ok(reports)
+}
+
+fn health_history_path() -> anyhow::Result<std::path::PathBuf> {
+    Ok(dirs::app_home_dir()?.join("subscription_health_history.json"))
+}
+
+fn load_health_history() -> anyhow::Result<HashMap<String, Vec<SubscriptionHealthReport>>> {
+    let path = health_history_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn save_health_history(
+    history: &HashMap<String, Vec<SubscriptionHealthReport>>,
+) -> anyhow::Result<()> {
+    std::fs::write(
+        health_history_path()?,
+        serde_json::to_string_pretty(history)?,
+    )?;
+    Ok(())
+}
+
 /// 清理过期的健康检查缓存
 #[tauri::command]
 pub async fn cleanup_health_check_cache() -> CmdResult<()> {
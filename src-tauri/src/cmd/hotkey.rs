@@ -0,0 +1,108 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::{Config, IVerge},
+    core::{
+        handle,
+        hotkey::{Hotkey, HotkeyActionInfo, HotkeyFunction},
+    },
+    feat, logging,
+    utils::logging::Type,
+};
+use anyhow::Context;
+use std::str::FromStr;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// 可绑定快捷键的动作
+#[tauri::command]
+pub async fn get_hotkey_actions() -> CmdResult<Vec<HotkeyActionInfo>> {
+    Ok(HotkeyFunction::registry())
+}
+
+/// 为动作绑定快捷键，accelerator 为空时解除绑定，与其他动作冲突时返回错误
+#[tauri::command]
+pub async fn set_hotkey(action: String, accelerator: Option<String>) -> CmdResult<Vec<String>> {
+    let action = HotkeyFunction::from_str(&action)?.to_string();
+    let accelerator = accelerator
+        .map(|accelerator| accelerator.trim().to_string())
+        .filter(|accelerator| !accelerator.is_empty());
+
+    let previous = Config::verge()
+        .await
+        .latest_ref()
+        .hotkeys
+        .clone()
+        .unwrap_or_default();
+    let mut hotkeys: Vec<String> = previous
+        .iter()
+        .filter(|hotkey| Hotkey::parse_binding(hotkey).is_none_or(|(func, _)| func != action))
+        .cloned()
+        .collect();
+
+    if let Some(accelerator) = &accelerator {
+        let shortcut = Shortcut::from_str(accelerator).map_err(|e| {
+            CmdError::new(
+                ErrorCode::InvalidArgument,
+                format!("快捷键无效 {accelerator}: {e}"),
+            )
+        })?;
+        #[cfg(target_os = "macos")]
+        if ["CMD+Q", "CMD+W"]
+            .iter()
+            .any(|system| Shortcut::from_str(system).ok() == Some(shortcut))
+        {
+            return Err(format!("快捷键 {accelerator} 为系统保留").into());
+        }
+        if let Some((func, _)) = hotkeys
+            .iter()
+            .filter_map(|hotkey| Hotkey::parse_binding(hotkey))
+            .find(|(_, key)| Shortcut::from_str(key).ok() == Some(shortcut))
+        {
+            return Err(format!("快捷键 {accelerator} 已被 {func} 使用").into());
+        }
+        hotkeys.push(format!("{action},{accelerator}"));
+    }
+
+    patch_hotkeys(hotkeys.clone()).await?;
+
+    // 快捷键可能已被其他程序占用，注册失败时恢复原设置
+    let enable_global_hotkey = Config::verge()
+        .await
+        .latest_ref()
+        .enable_global_hotkey
+        .unwrap_or(true);
+    if let Some(accelerator) = &accelerator
+        && enable_global_hotkey
+        && let Some(app_handle) = handle::Handle::global().app_handle()
+        && !app_handle
+            .global_shortcut()
+            .is_registered(accelerator.as_str())
+    {
+        patch_hotkeys(previous).await?;
+        return Err(format!("快捷键 {accelerator} 已被其他程序占用").into());
+    }
+
+    logging!(
+        info,
+        Type::Hotkey,
+        true,
+        "快捷键 {} 已{}",
+        action,
+        accelerator.map_or("解除绑定".to_string(), |accelerator| format!(
+            "绑定到 {accelerator}"
+        ))
+    );
+    Ok(hotkeys)
+}
+
+async fn patch_hotkeys(hotkeys: Vec<String>) -> CmdResult {
+    feat::patch_verge(
+        IVerge {
+            hotkeys: Some(hotkeys),
+            ..IVerge::default()
+        },
+        false,
+    )
+    .await
+    .context("保存快捷键失败")
+    .map_err(CmdError::from)
+}
@@ -0,0 +1,127 @@
+use super::CmdResult;
+use crate::{config::Config, logging, utils::logging::Type};
+use anyhow::{Result, bail};
+use chrono::Local;
+use reqwest::{Client, Proxy};
+use serde::Serialize;
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    time::Duration,
+};
+
+/// 仅有 AAAA 记录的探测地址，用于测速时判断节点的 IPv6 出口
+pub(crate) const IPV6_TEST_URL: &str = "https://ipv6.google.com/generate_204";
+/// 返回出口地址的 IPv6 / IPv4 探测地址
+const IPV6_CANARY_URL: &str = "https://api6.ipify.org";
+const IPV4_CANARY_URL: &str = "https://api4.ipify.org";
+const CANARY_TIMEOUT_SECONDS: u64 = 8;
+
+/// IPv6 支持情况
+#[derive(Debug, Clone, Serialize)]
+pub struct Ipv6Status {
+    pub host_supported: bool,
+    pub host_address: Option<String>,
+    pub core_ipv6_enabled: bool,      // 运行配置中的 ipv6 开关
+    pub node_supported: Option<bool>, // 当前节点不可用时为空
+    pub node_address: Option<String>,
+    pub checked_at: i64,
+}
+
+/// 检测本机和当前节点的 IPv6 支持情况
+#[tauri::command]
+pub async fn get_ipv6_status() -> CmdResult<Ipv6Status> {
+    let core_ipv6_enabled = Config::runtime()
+        .await
+        .latest_ref()
+        .config
+        .as_ref()
+        .and_then(|config| config.get("ipv6"))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    let port = {
+        let verge_port = Config::verge().await.latest_ref().verge_mixed_port;
+        match verge_port {
+            Some(port) => port,
+            None => Config::clash().await.latest_ref().get_mixed_port(),
+        }
+    };
+    let proxy = format!("http://127.0.0.1:{port}");
+
+    let (host, node) = tokio::join!(check_host(), check_node(&proxy));
+    let host_address = match host {
+        Ok(address) => Some(address),
+        Err(e) => {
+            logging!(debug, Type::Network, true, "[IPv6] 本机直连探测失败: {}", e);
+            None
+        }
+    };
+    let (node_supported, node_address) = match node {
+        Ok(address) => (Some(true), Some(address)),
+        Err(e) => {
+            logging!(debug, Type::Network, true, "[IPv6] 节点探测失败: {}", e);
+            // 节点本身可用但没有 IPv6 出口时为 false，节点不可用时无法判断
+            let reachable = fetch_canary(IPV4_CANARY_URL, Some(&proxy)).await.is_ok();
+            (reachable.then_some(false), None)
+        }
+    };
+
+    let status = Ipv6Status {
+        host_supported: host_address.is_some(),
+        host_address,
+        core_ipv6_enabled,
+        node_supported,
+        node_address,
+        checked_at: Local::now().timestamp(),
+    };
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[IPv6] 本机: {}，内核开关: {}，当前节点: {:?}",
+        status.host_supported,
+        status.core_ipv6_enabled,
+        status.node_supported
+    );
+    Ok(status)
+}
+
+// ===== 内部实现函数 =====
+
+/// 绑定 IPv6 本地地址直连探测，确保只走 IPv6
+async fn check_host() -> Result<String> {
+    let client = Client::builder()
+        .no_proxy()
+        .local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+        .timeout(Duration::from_secs(CANARY_TIMEOUT_SECONDS))
+        .build()?;
+    fetch_with(&client, IPV6_CANARY_URL).await
+}
+
+async fn check_node(proxy: &str) -> Result<String> {
+    fetch_canary(IPV6_CANARY_URL, Some(proxy)).await
+}
+
+async fn fetch_canary(url: &str, proxy: Option<&str>) -> Result<String> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(CANARY_TIMEOUT_SECONDS));
+    builder = match proxy {
+        Some(proxy) => builder.proxy(Proxy::all(proxy)?),
+        None => builder.no_proxy(),
+    };
+    fetch_with(&builder.build()?, url).await
+}
+
+async fn fetch_with(client: &Client, url: &str) -> Result<String> {
+    let address = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?
+        .trim()
+        .to_string();
+    if address.parse::<IpAddr>().is_err() {
+        bail!("探测地址返回了无效的 IP: {}", address);
+    }
+    Ok(address)
+}
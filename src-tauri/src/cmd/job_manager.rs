@@ -0,0 +1,637 @@
+use super::{CmdError, CmdResult, ErrorCode, task_manager::TaskType};
+use crate::{
+    core::handle,
+    logging,
+    process::AsyncHandler,
+    utils::{dirs, logging::Type},
+};
+use anyhow::Result;
+use futures::{FutureExt, future::BoxFuture};
+use nanoid::nanoid;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    future::Future,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use tauri::{Emitter, async_runtime::JoinHandle};
+use tokio::sync::oneshot;
+
+/// 已结束的任务最多保留条数
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// 单个任务日志最多保留行数
+const MAX_LOG_LINES: usize = 200;
+
+static MANAGER_STARTED: AtomicBool = AtomicBool::new(false);
+
+static JOBS: Lazy<Mutex<JobQueue>> = Lazy::new(|| Mutex::new(JobQueue::default()));
+
+/// 任务优先级，同一并发类别中优先级高的先执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    Low,
+    Normal,
+    High,
+}
+
+/// 并发类别，同类任务共享并发上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobClass {
+    ClashApi, // 访问 Clash API 的任务（测速、健康检查），串行执行避免互相干扰
+    Network,  // 访问外部网络的任务（订阅更新）
+    Disk,     // 本地读写任务（备份、清理）
+}
+
+impl JobClass {
+    const fn concurrency(self) -> usize {
+        match self {
+            Self::ClashApi => 1,
+            Self::Network => 3,
+            Self::Disk => 1,
+        }
+    }
+
+    /// 计划任务按类型归入并发类别
+    pub const fn for_task(task_type: &TaskType) -> Self {
+        match task_type {
+            TaskType::SpeedTest | TaskType::HealthCheck => Self::ClashApi,
+            TaskType::SubscriptionUpdate | TaskType::ExpiryReminder | TaskType::GeoDataUpdate => {
+                Self::Network
+            }
+            TaskType::AutoCleanup | TaskType::TrafficReport | TaskType::Custom => Self::Disk,
+        }
+    }
+}
+
+/// 任务内容，只保存参数以便重启后恢复未完成的任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobKind {
+    ScheduledTask { task_id: String }, // 任务管理中的计划任务
+    ScheduledBackup,                   // 定时备份
+    SpeedTest,                         // 全局测速
+    HealthCheck,                       // 批量订阅健康检查
+    ProfileUpdate { uid: String },     // 定时器触发的订阅更新
+    SubscriptionBatchUpdate,           // 批量更新所有订阅
+}
+
+impl JobKind {
+    /// 只有计划任务和定时备份可以在重启后按参数恢复，其余任务由调用方提交执行内容
+    const fn is_restorable(&self) -> bool {
+        matches!(self, Self::ScheduledTask { .. } | Self::ScheduledBackup)
+    }
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    const fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// 任务信息
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub name: String,
+    pub kind: JobKind,
+    pub class: JobClass,
+    pub priority: JobPriority,
+    pub status: JobStatus,
+    pub submitted_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    pub message: Option<String>,
+}
+
+/// 任务日志
+#[derive(Debug, Clone, Serialize)]
+pub struct JobLogEntry {
+    pub time: i64,
+    pub message: String,
+}
+
+/// 持久化的未完成任务
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedJob {
+    id: String,
+    name: String,
+    kind: JobKind,
+    class: JobClass,
+    priority: JobPriority,
+    submitted_at: i64,
+}
+
+/// 任务结果，成功时为结果说明，失败时为错误信息
+pub type JobResult = Result<String, String>;
+
+/// 调用方提交的执行内容
+type JobWork = BoxFuture<'static, JobResult>;
+
+struct JobEntry {
+    info: JobInfo,
+    log: Vec<JobLogEntry>,
+    handle: Option<JoinHandle<()>>,
+    waiters: Vec<oneshot::Sender<JobResult>>,
+    work: Option<JobWork>,
+}
+
+impl JobEntry {
+    fn push_log(&mut self, message: impl Into<String>) {
+        self.log.push(JobLogEntry {
+            time: chrono::Utc::now().timestamp(),
+            message: message.into(),
+        });
+        if self.log.len() > MAX_LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+
+    /// 标记任务结束，返回需要通知的等待者
+    fn finish(&mut self, status: JobStatus, message: String) -> Vec<oneshot::Sender<JobResult>> {
+        self.info.status = status;
+        self.info.finished_at = Some(chrono::Utc::now().timestamp());
+        self.push_log(format!("{:?}: {}", status, message));
+        self.info.message = Some(message);
+        self.handle = None;
+        std::mem::take(&mut self.waiters)
+    }
+}
+
+#[derive(Default)]
+struct JobQueue {
+    jobs: Vec<JobEntry>,
+    running: HashMap<JobClass, usize>,
+}
+
+impl JobQueue {
+    fn get_mut(&mut self, id: &str) -> Option<&mut JobEntry> {
+        self.jobs.iter_mut().find(|entry| entry.info.id == id)
+    }
+
+    fn release(&mut self, class: JobClass) {
+        if let Some(count) = self.running.get_mut(&class) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// 选出下一个可执行的任务：所属类别未满，优先级最高，同优先级先提交先执行
+    fn next_runnable(&self) -> Option<usize> {
+        self.jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.info.status == JobStatus::Pending)
+            .filter(|(_, entry)| {
+                let class = entry.info.class;
+                self.running.get(&class).copied().unwrap_or(0) < class.concurrency()
+            })
+            .max_by(|(_, a), (_, b)| {
+                a.info
+                    .priority
+                    .cmp(&b.info.priority)
+                    .then(b.info.submitted_at.cmp(&a.info.submitted_at))
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// 只保留最近结束的任务
+    fn prune_finished(&mut self) {
+        let finished = self
+            .jobs
+            .iter()
+            .filter(|entry| entry.info.status.is_finished())
+            .count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|entry| {
+            if excess > 0 && entry.info.status.is_finished() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn persisted(&self) -> Vec<PersistedJob> {
+        self.jobs
+            .iter()
+            .filter(|entry| !entry.info.status.is_finished() && entry.info.kind.is_restorable())
+            .map(|entry| PersistedJob {
+                id: entry.info.id.clone(),
+                name: entry.info.name.clone(),
+                kind: entry.info.kind.clone(),
+                class: entry.info.class,
+                priority: entry.info.priority,
+                submitted_at: entry.info.submitted_at,
+            })
+            .collect()
+    }
+}
+
+/// 获取任务列表，未结束的任务在前
+#[tauri::command]
+pub fn list_jobs() -> CmdResult<Vec<JobInfo>> {
+    let queue = JOBS.lock();
+    let mut jobs: Vec<JobInfo> = queue.jobs.iter().map(|entry| entry.info.clone()).collect();
+    jobs.sort_by(|a, b| {
+        a.status
+            .is_finished()
+            .cmp(&b.status.is_finished())
+            .then(b.submitted_at.cmp(&a.submitted_at))
+    });
+    Ok(jobs)
+}
+
+/// 取消任务，排队中的任务直接移出队列，运行中的任务在下一个等待点中止
+#[tauri::command]
+pub fn cancel_job(id: String) -> CmdResult<()> {
+    let (info, waiters) = {
+        let mut queue = JOBS.lock();
+        let Some(entry) = queue.get_mut(&id) else {
+            return Err(CmdError::new(
+                ErrorCode::NotFound,
+                format!("任务不存在: {id}"),
+            ));
+        };
+        if entry.info.status.is_finished() {
+            return Err(CmdError::new(
+                ErrorCode::InvalidArgument,
+                format!("任务已结束: {id}"),
+            ));
+        }
+
+        let was_running = entry.info.status == JobStatus::Running;
+        if let Some(handle) = entry.handle.take() {
+            handle.abort();
+        }
+        let waiters = entry.finish(JobStatus::Cancelled, "任务已取消".to_string());
+        let info = entry.info.clone();
+        if was_running {
+            queue.release(info.class);
+        }
+        (info, waiters)
+    };
+
+    logging!(info, Type::Cmd, true, "[任务队列] 取消任务: {}", info.name);
+    for waiter in waiters {
+        let _ = waiter.send(Err("任务已取消".to_string()));
+    }
+    emit_job_updated(&info);
+    persist_jobs();
+    schedule();
+
+    let kind = info.kind;
+    AsyncHandler::spawn(move || async move { reschedule_cancelled(kind).await });
+    Ok(())
+}
+
+/// 获取任务日志
+#[tauri::command]
+pub fn get_job_log(id: String) -> CmdResult<Vec<JobLogEntry>> {
+    JOBS.lock()
+        .get_mut(&id)
+        .map(|entry| entry.log.clone())
+        .ok_or_else(|| CmdError::new(ErrorCode::NotFound, format!("任务不存在: {id}")))
+}
+
+/// 提交任务，相同内容的任务已在排队或运行时返回该任务的 ID
+pub fn submit_job(
+    name: impl Into<String>,
+    kind: JobKind,
+    class: JobClass,
+    priority: JobPriority,
+) -> String {
+    enqueue(name.into(), kind, class, priority, None, None)
+}
+
+/// 提交任务并等待其结束
+pub async fn run_job(
+    name: impl Into<String>,
+    kind: JobKind,
+    class: JobClass,
+    priority: JobPriority,
+) -> JobResult {
+    let (tx, rx) = oneshot::channel();
+    enqueue(name.into(), kind, class, priority, Some(tx), None);
+    rx.await.unwrap_or_else(|_| Err("任务已中止".to_string()))
+}
+
+/// 提交由调用方提供执行内容的任务并等待其结束，返回执行内容的结果
+/// 相同内容的任务已在排队或运行时只等待该任务结束，不会重复执行
+pub async fn run_job_with<T, F>(
+    name: impl Into<String>,
+    kind: JobKind,
+    class: JobClass,
+    priority: JobPriority,
+    work: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T, String>> + Send + 'static,
+{
+    let output = Arc::new(Mutex::new(None));
+    let slot = Arc::clone(&output);
+    let work = async move {
+        let value = work.await?;
+        *slot.lock() = Some(value);
+        Ok("完成".to_string())
+    };
+
+    let (tx, rx) = oneshot::channel();
+    enqueue(
+        name.into(),
+        kind,
+        class,
+        priority,
+        Some(tx),
+        Some(work.boxed()),
+    );
+    rx.await.unwrap_or_else(|_| Err("任务已中止".to_string()))?;
+    output
+        .lock()
+        .take()
+        .ok_or_else(|| "相同任务已在执行，结果请在任务列表中查看".to_string())
+}
+
+/// 启动任务管理器，恢复上次退出时未完成的任务
+pub fn init_job_manager() {
+    if MANAGER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let restored = match load_persisted_jobs() {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            logging!(
+                warn,
+                Type::Cmd,
+                true,
+                "[任务队列] 加载未完成任务失败: {}",
+                e
+            );
+            Vec::new()
+        }
+    };
+    if !restored.is_empty() {
+        logging!(
+            info,
+            Type::Cmd,
+            true,
+            "[任务队列] 恢复 {} 个未完成任务",
+            restored.len()
+        );
+        let mut queue = JOBS.lock();
+        for job in restored {
+            if queue.jobs.iter().any(|entry| entry.info.kind == job.kind) {
+                continue;
+            }
+            let mut entry = new_entry(job.id, job.name, job.kind, job.class, job.priority);
+            entry.info.submitted_at = job.submitted_at;
+            entry.push_log("从上次运行恢复");
+            queue.jobs.push(entry);
+        }
+    }
+    schedule();
+}
+
+// ===== 内部实现函数 =====
+
+fn new_entry(
+    id: String,
+    name: String,
+    kind: JobKind,
+    class: JobClass,
+    priority: JobPriority,
+) -> JobEntry {
+    JobEntry {
+        info: JobInfo {
+            id,
+            name,
+            kind,
+            class,
+            priority,
+            status: JobStatus::Pending,
+            submitted_at: chrono::Utc::now().timestamp(),
+            started_at: None,
+            finished_at: None,
+            message: None,
+        },
+        log: Vec::new(),
+        handle: None,
+        waiters: Vec::new(),
+        work: None,
+    }
+}
+
+fn enqueue(
+    name: String,
+    kind: JobKind,
+    class: JobClass,
+    priority: JobPriority,
+    waiter: Option<oneshot::Sender<JobResult>>,
+    work: Option<JobWork>,
+) -> String {
+    let info = {
+        let mut queue = JOBS.lock();
+        let existing = queue
+            .jobs
+            .iter_mut()
+            .find(|entry| !entry.info.status.is_finished() && entry.info.kind == kind);
+        if let Some(entry) = existing {
+            // 合并到已有任务，必要时提升优先级
+            if priority > entry.info.priority {
+                entry.info.priority = priority;
+                entry.push_log(format!("优先级提升为 {:?}", priority));
+            }
+            entry.waiters.extend(waiter);
+            return entry.info.id.clone();
+        }
+
+        let mut entry = new_entry(nanoid!(), name, kind, class, priority);
+        entry.push_log(format!("加入队列，类别 {:?}，优先级 {:?}", class, priority));
+        entry.waiters.extend(waiter);
+        entry.work = work;
+        let info = entry.info.clone();
+        queue.jobs.push(entry);
+        info
+    };
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[任务队列] 提交任务: {} ({:?}, {:?})",
+        info.name,
+        info.class,
+        info.priority
+    );
+    emit_job_updated(&info);
+    persist_jobs();
+    schedule();
+    info.id
+}
+
+/// 按类别并发上限启动排队中的任务
+fn schedule() {
+    let mut started = Vec::new();
+    {
+        let mut queue = JOBS.lock();
+        while let Some(index) = queue.next_runnable() {
+            let class = queue.jobs[index].info.class;
+            *queue.running.entry(class).or_default() += 1;
+
+            let entry = &mut queue.jobs[index];
+            entry.info.status = JobStatus::Running;
+            entry.info.started_at = Some(chrono::Utc::now().timestamp());
+            entry.push_log("开始执行");
+
+            let id = entry.info.id.clone();
+            let kind = entry.info.kind.clone();
+            let work = entry.work.take();
+            // 持有锁期间保存句柄，任务结束时的 finish_job 会等待锁释放
+            entry.handle = Some(AsyncHandler::spawn(move || async move {
+                let result = match work {
+                    Some(work) => work.await,
+                    None => execute(kind).await,
+                };
+                finish_job(&id, result);
+            }));
+            started.push(entry.info.clone());
+        }
+    }
+
+    for info in &started {
+        emit_job_updated(info);
+    }
+}
+
+fn finish_job(id: &str, result: JobResult) {
+    let (info, waiters) = {
+        let mut queue = JOBS.lock();
+        let Some(entry) = queue.get_mut(id) else {
+            return;
+        };
+        // 已被取消的任务由 cancel_job 负责收尾
+        if entry.info.status != JobStatus::Running {
+            return;
+        }
+        let (status, message) = match &result {
+            Ok(message) => (JobStatus::Succeeded, message.clone()),
+            Err(error) => (JobStatus::Failed, error.clone()),
+        };
+        let waiters = entry.finish(status, message);
+        let info = entry.info.clone();
+        queue.release(info.class);
+        queue.prune_finished();
+        (info, waiters)
+    };
+
+    if let Err(error) = &result {
+        logging!(
+            warn,
+            Type::Cmd,
+            true,
+            "[任务队列] 任务 {} 执行失败: {}",
+            info.name,
+            error
+        );
+    }
+    for waiter in waiters {
+        let _ = waiter.send(result.clone());
+    }
+    emit_job_updated(&info);
+    persist_jobs();
+    schedule();
+}
+
+/// 执行可恢复的任务内容
+async fn execute(kind: JobKind) -> JobResult {
+    match kind {
+        JobKind::ScheduledTask { task_id } => super::task_manager::run_task_job(&task_id).await,
+        JobKind::ScheduledBackup => super::backup_schedule::run_backup_job().await,
+        _ => Err("任务缺少执行内容".to_string()),
+    }
+}
+
+/// 计划任务被取消后推迟到下一个周期，避免调度器立即重新提交
+async fn reschedule_cancelled(kind: JobKind) {
+    let result = match kind {
+        JobKind::ScheduledTask { task_id } => {
+            super::task_manager::postpone_task(&task_id, chrono::Utc::now().timestamp())
+                .await
+                .map_err(|e| e.to_string())
+        }
+        JobKind::ScheduledBackup => super::backup_schedule::postpone_backup()
+            .await
+            .map_err(|e| e.to_string()),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        logging!(
+            warn,
+            Type::Cmd,
+            true,
+            "[任务队列] 推迟已取消的任务失败: {}",
+            e
+        );
+    }
+}
+
+fn emit_job_updated(info: &JobInfo) {
+    if let Some(app_handle) = handle::Handle::global().app_handle()
+        && let Err(err) = app_handle.emit("job-updated", info)
+    {
+        log::warn!(target: "app", "job-updated emit failed: {err}");
+    }
+}
+
+fn jobs_file() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("jobs.json"))
+}
+
+/// 保存未完成的任务，重启后继续执行；持有锁写入，避免并发写入互相覆盖
+fn persist_jobs() {
+    let queue = JOBS.lock();
+    let result = jobs_file().and_then(|path| {
+        fs::write(path, serde_json::to_string_pretty(&queue.persisted())?)?;
+        Ok(())
+    });
+    drop(queue);
+    if let Err(e) = result {
+        logging!(
+            warn,
+            Type::Cmd,
+            true,
+            "[任务队列] 保存未完成任务失败: {}",
+            e
+        );
+    }
+}
+
+fn load_persisted_jobs() -> Result<Vec<PersistedJob>> {
+    let path = jobs_file()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
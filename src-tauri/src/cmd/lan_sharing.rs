@@ -0,0 +1,548 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::Config,
+    feat, logging,
+    process::AsyncHandler,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, bail};
+use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    fs,
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
+use tokio::sync::Mutex;
+
+const LAN_SHARING_FILE: &str = "lan_sharing.yaml";
+
+/// 开启与关闭局域网共享需要串行执行
+static SHARING_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSharingOptions {
+    /// 监听地址，"*" 或本机某个 IP，默认 "*"
+    pub bind_address: Option<String>,
+    /// 是否为混合端口放行系统防火墙，前端需先征得用户同意，系统会再弹出提权确认
+    #[serde(default)]
+    pub open_firewall: bool,
+    /// 未配置 authentication 时必须提供，格式为 "用户名:密码"
+    pub authentication: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FirewallBackend {
+    /// 与 ensure_firewall_rules 共用同一规则分组
+    Windows,
+    Ufw,
+    Firewalld,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FirewallRule {
+    backend: FirewallBackend,
+    port: u16,
+    /// 放行的局域网网段，移除规则时需要与添加时一致
+    #[serde(default)]
+    sources: Vec<String>,
+}
+
+/// 开启前的配置与已添加的防火墙规则，用于关闭时恢复
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LanSharingState {
+    previous_allow_lan: Option<bool>,
+    previous_bind_address: Option<String>,
+    firewall: Option<FirewallRule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallStatus {
+    pub backend: Option<FirewallBackend>,
+    pub opened: bool,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanShareSummary {
+    pub port: u16,
+    pub bind_address: String,
+    /// 检测到的局域网 IPv4 地址，首个为推荐地址
+    pub lan_ips: Vec<String>,
+    pub http_proxy: Option<String>,
+    pub socks_proxy: Option<String>,
+    /// 供移动端客户端扫码导入的代理地址
+    pub qr_payload: Option<String>,
+    pub auth_required: bool,
+    pub firewall: FirewallStatus,
+}
+
+/// 开启局域网共享：允许局域网连接、按需放行防火墙并返回共享信息
+#[tauri::command]
+pub async fn enable_lan_sharing(options: LanSharingOptions) -> CmdResult<LanShareSummary> {
+    let bind_address = normalize_bind_address(options.bind_address.as_deref())?;
+    let new_credential = match options.authentication.as_deref().map(str::trim) {
+        Some(credential) if !credential.is_empty() => {
+            if !is_valid_credential(credential) {
+                return Err(CmdError::new(
+                    ErrorCode::InvalidArgument,
+                    "认证信息格式应为 用户名:密码",
+                ));
+            }
+            Some(credential.to_string())
+        }
+        _ => None,
+    };
+    // 无认证时局域网内任何设备都能使用代理，必须先设置认证
+    if new_credential.is_none() && first_credential().await.is_none() {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            "开启局域网共享前需要设置代理认证",
+        ));
+    }
+
+    let _guard = SHARING_LOCK.lock().await;
+    let already_enabled = state_path().is_ok_and(|path| path.exists());
+    let mut state = load_state().context("读取局域网共享状态失败")?;
+    // 重复开启时保留最初记录的设置，关闭时才能恢复到共享前的状态
+    if !already_enabled {
+        let clash = Config::clash().await;
+        let clash = clash.latest_ref();
+        state.previous_allow_lan = clash.0.get("allow-lan").and_then(Value::as_bool);
+        state.previous_bind_address = clash
+            .0
+            .get("bind-address")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+    }
+
+    let mut patch = Mapping::new();
+    patch.insert("allow-lan".into(), true.into());
+    patch.insert("bind-address".into(), bind_address.as_str().into());
+    if let Some(credential) = new_credential {
+        patch.insert(
+            "authentication".into(),
+            Value::Sequence(vec![credential.into()]),
+        );
+    }
+    feat::patch_clash(patch)
+        .await
+        .context("开启局域网连接失败")?;
+    save_state(&state).context("保存局域网共享状态失败")?;
+
+    let port = mixed_port().await;
+    let firewall = if options.open_firewall {
+        open_firewall(&mut state, port).await
+    } else {
+        FirewallStatus {
+            backend: state.firewall.as_ref().map(|rule| rule.backend),
+            opened: false,
+            message: None,
+        }
+    };
+    save_state(&state).context("保存局域网共享状态失败")?;
+
+    let lan_ips = match bind_address.parse::<IpAddr>() {
+        Ok(ip) => vec![ip.to_string()],
+        Err(_) => detect_lan_ips(),
+    };
+    let credential = first_credential().await;
+    let auth = credential
+        .as_deref()
+        .map(|c| format!("{c}@"))
+        .unwrap_or_default();
+    let http_proxy = lan_ips
+        .first()
+        .map(|ip| format!("http://{auth}{ip}:{port}"));
+    let socks_proxy = lan_ips
+        .first()
+        .map(|ip| format!("socks5://{auth}{ip}:{port}"));
+
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[局域网共享] 已开启: {:?}:{}, 防火墙已放行: {}",
+        lan_ips,
+        port,
+        firewall.opened
+    );
+    Ok(LanShareSummary {
+        port,
+        bind_address,
+        lan_ips,
+        qr_payload: http_proxy.clone(),
+        http_proxy,
+        socks_proxy,
+        auth_required: credential.is_some(),
+        firewall,
+    })
+}
+
+/// 关闭局域网共享：恢复开启前的 allow-lan/bind-address 并移除添加的防火墙规则
+#[tauri::command]
+pub async fn disable_lan_sharing() -> CmdResult<()> {
+    let _guard = SHARING_LOCK.lock().await;
+    let path = state_path()?;
+    if !path.exists() {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            "局域网共享未开启",
+        ));
+    }
+    let mut state = load_state().context("读取局域网共享状态失败")?;
+
+    let mut patch = Mapping::new();
+    patch.insert(
+        "allow-lan".into(),
+        state.previous_allow_lan.unwrap_or(false).into(),
+    );
+    patch.insert(
+        "bind-address".into(),
+        state.previous_bind_address.as_deref().unwrap_or("*").into(),
+    );
+    feat::patch_clash(patch)
+        .await
+        .context("恢复局域网设置失败")?;
+
+    // Windows 上应用的放行规则属于同一分组，关闭共享时一并移除
+    let rule = state.firewall.take().or_else(|| {
+        cfg!(target_os = "windows").then(|| FirewallRule {
+            backend: FirewallBackend::Windows,
+            port: 0,
+            sources: Vec::new(),
+        })
+    });
+    if let Some(rule) = rule
+        && let Err(e) = run_firewall(rule.clone(), false).await
+    {
+        // 保留规则记录，便于再次关闭时重试
+        state.firewall = Some(rule);
+        save_state(&state).context("保存局域网共享状态失败")?;
+        return Err(e.context("移除防火墙规则失败").into());
+    }
+    fs::remove_file(&path).context("清理局域网共享状态失败")?;
+
+    logging!(info, Type::Network, true, "[局域网共享] 已关闭并恢复原设置");
+    Ok(())
+}
+
+// ===== 内部实现函数 =====
+
+fn normalize_bind_address(address: Option<&str>) -> CmdResult<String> {
+    match address.map(str::trim).filter(|a| !a.is_empty()) {
+        None | Some("*") => Ok("*".into()),
+        Some(address) => address
+            .parse::<IpAddr>()
+            .map(|ip| ip.to_string())
+            .map_err(|_| {
+                CmdError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("监听地址无效: {address}，应为 * 或 IP 地址"),
+                )
+            }),
+    }
+}
+
+async fn mixed_port() -> u16 {
+    let verge_port = Config::verge().await.latest_ref().verge_mixed_port;
+    match verge_port {
+        Some(port) => port,
+        None => Config::clash().await.latest_ref().get_mixed_port(),
+    }
+}
+
+/// 配置了 authentication 时局域网设备同样需要认证，取第一组凭据
+async fn first_credential() -> Option<String> {
+    let clash = Config::clash().await;
+    let clash = clash.latest_ref();
+    clash
+        .0
+        .get("authentication")?
+        .as_sequence()?
+        .iter()
+        .filter_map(Value::as_str)
+        .find(|c| is_valid_credential(c))
+        .map(str::to_string)
+}
+
+fn is_valid_credential(credential: &str) -> bool {
+    credential
+        .split_once(':')
+        .is_some_and(|(user, pass)| !user.is_empty() && !pass.is_empty())
+}
+
+/// 局域网网卡上的私有 IPv4 地址及其前缀长度，排除容器、虚拟机与 TUN 等虚拟网卡
+fn lan_addresses() -> Vec<(Ipv4Addr, u32)> {
+    const VIRTUAL_PREFIXES: &[&str] = &["docker", "veth", "br-", "vEthernet", "utun", "Meta"];
+
+    NetworkInterface::show()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|iface| !VIRTUAL_PREFIXES.iter().any(|p| iface.name.starts_with(p)))
+        .flat_map(|iface| iface.addr.into_iter())
+        .filter_map(|addr| match (addr.ip(), addr.netmask()) {
+            (IpAddr::V4(v4), mask) if v4.is_private() => {
+                let prefix = match mask {
+                    Some(IpAddr::V4(mask)) => u32::from(mask).count_ones(),
+                    _ => 24,
+                };
+                Some((v4, prefix))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 局域网私有 IPv4 地址
+fn detect_lan_ips() -> Vec<String> {
+    let mut ips: Vec<Ipv4Addr> = lan_addresses().into_iter().map(|(ip, _)| ip).collect();
+    ips.sort();
+    ips.dedup();
+    // 家用网络最常见的 192.168.x.x 优先
+    ips.sort_by_key(|ip| match ip.octets() {
+        [192, 168, ..] => 0,
+        [10, ..] => 1,
+        _ => 2,
+    });
+    ips.into_iter().map(|ip| ip.to_string()).collect()
+}
+
+/// 本机所在的局域网网段（CIDR），防火墙只对这些网段放行
+fn lan_subnets() -> Vec<String> {
+    let mut subnets: Vec<String> = lan_addresses()
+        .into_iter()
+        .map(|(ip, prefix)| {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            format!("{}/{prefix}", Ipv4Addr::from(u32::from(ip) & mask))
+        })
+        .collect();
+    subnets.sort();
+    subnets.dedup();
+    subnets
+}
+
+async fn open_firewall(state: &mut LanSharingState, port: u16) -> FirewallStatus {
+    let Some(backend) = detect_firewall().await else {
+        return FirewallStatus {
+            backend: None,
+            opened: false,
+            message: Some(unsupported_firewall_message().into()),
+        };
+    };
+
+    let sources = lan_subnets();
+    if backend != FirewallBackend::Windows && sources.is_empty() {
+        return FirewallStatus {
+            backend: Some(backend),
+            opened: false,
+            message: Some("未检测到局域网网段，未放行防火墙".into()),
+        };
+    }
+
+    // 端口或网段变化时先移除旧规则
+    if let Some(rule) = state.firewall.take() {
+        if rule.port == port && rule.sources == sources {
+            state.firewall = Some(rule);
+            return FirewallStatus {
+                backend: Some(backend),
+                opened: true,
+                message: None,
+            };
+        }
+        if let Err(e) = run_firewall(rule, false).await {
+            logging!(
+                warn,
+                Type::Network,
+                true,
+                "[局域网共享] 移除旧防火墙规则失败: {}",
+                e
+            );
+        }
+    }
+
+    let rule = FirewallRule {
+        backend,
+        port,
+        sources,
+    };
+    match run_firewall(rule.clone(), true).await {
+        Ok(()) => {
+            state.firewall = Some(rule);
+            FirewallStatus {
+                backend: Some(backend),
+                opened: true,
+                message: None,
+            }
+        }
+        Err(e) => {
+            logging!(
+                warn,
+                Type::Network,
+                true,
+                "[局域网共享] 放行防火墙失败: {}",
+                e
+            );
+            FirewallStatus {
+                backend: Some(backend),
+                opened: false,
+                message: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Windows 上复用防火墙规则管理，规则仅对专用网络的本地子网放行
+#[cfg(target_os = "windows")]
+async fn run_firewall(_rule: FirewallRule, add: bool) -> Result<()> {
+    let result = if add {
+        super::firewall::ensure_firewall_rules().await.map(|_| ())
+    } else {
+        super::firewall::remove_firewall_rules().await.map(|_| ())
+    };
+    result.map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn run_firewall(rule: FirewallRule, add: bool) -> Result<()> {
+    AsyncHandler::spawn_blocking(move || firewall_command(&rule, add)).await?
+}
+
+fn unsupported_firewall_message() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macOS 应用防火墙按程序放行，首次有局域网连接时系统会询问是否允许"
+    } else {
+        "未检测到已启用的防火墙，无需放行"
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn detect_firewall() -> Option<FirewallBackend> {
+    Some(FirewallBackend::Windows)
+}
+
+#[cfg(target_os = "linux")]
+async fn detect_firewall() -> Option<FirewallBackend> {
+    AsyncHandler::spawn_blocking(|| {
+        use std::process::Command as StdCommand;
+
+        // ufw status 需要 root，读取配置文件判断是否启用
+        let ufw_enabled = fs::read_to_string("/etc/ufw/ufw.conf")
+            .is_ok_and(|conf| conf.lines().any(|l| l.trim() == "ENABLED=yes"));
+        if ufw_enabled {
+            return Some(FirewallBackend::Ufw);
+        }
+        let firewalld_running = StdCommand::new("firewall-cmd")
+            .arg("--state")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        firewalld_running.then_some(FirewallBackend::Firewalld)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// 通过 pkexec 请求授权，规则只允许来自局域网网段的连接；
+/// firewalld 规则只写入运行时配置，重启后自动失效
+#[cfg(target_os = "linux")]
+fn firewall_command(rule: &FirewallRule, add: bool) -> Result<()> {
+    use std::process::Command as StdCommand;
+
+    let port = rule.port;
+    let args: Vec<String> = match rule.backend {
+        // 旧版本记录的规则未限制来源，按端口移除
+        FirewallBackend::Ufw if rule.sources.is_empty() => {
+            let delete = if add { "" } else { "delete " };
+            vec![
+                "sh".into(),
+                "-c".into(),
+                format!("ufw {delete}allow {port}"),
+            ]
+        }
+        FirewallBackend::Firewalld if rule.sources.is_empty() => {
+            let action = if add { "--add-port" } else { "--remove-port" };
+            vec![
+                "firewall-cmd".into(),
+                format!("{action}={port}/tcp"),
+                format!("{action}={port}/udp"),
+            ]
+        }
+        // 多条 ufw 规则合并为一次授权
+        FirewallBackend::Ufw => {
+            let delete = if add { "" } else { "delete " };
+            let script = rule
+                .sources
+                .iter()
+                .flat_map(|cidr| {
+                    ["tcp", "udp"].map(|proto| {
+                        format!("ufw {delete}allow from {cidr} to any port {port} proto {proto}")
+                    })
+                })
+                .collect::<Vec<_>>()
+                .join(" && ");
+            vec!["sh".into(), "-c".into(), script]
+        }
+        FirewallBackend::Firewalld => {
+            let action = if add {
+                "--add-rich-rule"
+            } else {
+                "--remove-rich-rule"
+            };
+            let mut args = vec!["firewall-cmd".to_string()];
+            for cidr in &rule.sources {
+                for proto in ["tcp", "udp"] {
+                    args.push(format!(
+                        "{action}=rule family=\"ipv4\" source address=\"{cidr}\" port port=\"{port}\" protocol=\"{proto}\" accept"
+                    ));
+                }
+            }
+            args
+        }
+        FirewallBackend::Windows => bail!("unsupported firewall backend: {:?}", rule.backend),
+    };
+
+    let elevator = crate::utils::help::linux_elevator();
+    let status = StdCommand::new(&elevator).args(&args).status()?;
+    if !status.success() {
+        bail!(
+            "{} exited with status {}",
+            args[0],
+            status.code().unwrap_or(-1)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+async fn detect_firewall() -> Option<FirewallBackend> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn firewall_command(rule: &FirewallRule, _add: bool) -> Result<()> {
+    bail!("unsupported firewall backend: {:?}", rule.backend)
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(LAN_SHARING_FILE))
+}
+
+fn load_state() -> Result<LanSharingState> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(LanSharingState::default());
+    }
+    Ok(serde_yaml_ng::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_state(state: &LanSharingState) -> Result<()> {
+    fs::write(state_path()?, serde_yaml_ng::to_string(state)?)?;
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use crate::module::lightweight;
+use crate::{
+    config::IVerge,
+    feat, logging,
+    module::lightweight::{self, LightweightPolicy, LightweightStatus},
+    utils::logging::Type,
+};
 
 use super::CmdResult;
 
@@ -13,3 +18,37 @@ pub async fn exit_lightweight_mode() -> CmdResult {
     lightweight::exit_lightweight_mode().await;
     Ok(())
 }
+
+#[tauri::command]
+pub async fn get_lightweight_status() -> CmdResult<LightweightStatus> {
+    Ok(lightweight::lightweight_status().await)
+}
+
+/// 设置轻量模式策略，空闲进入的开关与时长保存到自动轻量模式设置中
+#[tauri::command]
+pub async fn set_lightweight_policy(policy: LightweightPolicy) -> CmdResult<LightweightStatus> {
+    if policy.idle_minutes == 0 {
+        return Err("空闲时长至少为 1 分钟".into());
+    }
+    feat::patch_verge(
+        IVerge {
+            enable_auto_light_weight_mode: Some(policy.enter_on_idle),
+            auto_light_weight_minutes: Some(policy.idle_minutes),
+            light_weight_exit_on_tray: Some(policy.exit_on_tray),
+            light_weight_exit_on_hotkey: Some(policy.exit_on_hotkey),
+            light_weight_unload_webview: Some(policy.unload_webview),
+            ..IVerge::default()
+        },
+        false,
+    )
+    .await
+    .map_err(|e| format!("保存轻量模式策略失败: {e}"))?;
+    logging!(
+        info,
+        Type::Lightweight,
+        true,
+        "轻量模式策略已更新: {:?}",
+        policy
+    );
+    Ok(lightweight::lightweight_status().await)
+}
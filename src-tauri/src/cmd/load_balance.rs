@@ -0,0 +1,433 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    ipc::IpcManager,
+    logging,
+    process::AsyncHandler,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+/// 调度检查间隔
+const REBUILD_TICK: Duration = Duration::from_secs(60);
+
+/// 分组类型，不作为负载均衡成员
+const GROUP_TYPES: &[&str] = &[
+    "Selector",
+    "URLTest",
+    "Fallback",
+    "LoadBalance",
+    "Relay",
+    "Direct",
+    "Reject",
+    "RejectDrop",
+    "Pass",
+    "Compatible",
+];
+
+static REBUILD_STARTED: AtomicBool = AtomicBool::new(false);
+/// 防止定时重建与手动重建并发写入
+static REBUILD_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 负载均衡策略，对应 mihomo 的 strategy 字段
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoadBalanceStrategy {
+    #[default]
+    ConsistentHashing, // 同一目标固定使用同一节点
+    RoundRobin, // 轮询
+}
+
+/// 负载均衡分组配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadBalanceGroup {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub strategy: LoadBalanceStrategy,
+    pub max_members: usize,
+    pub max_latency_ms: Option<u64>,
+    pub name_filter: Option<String>, // 节点名称正则过滤
+    pub profile_uid: Option<String>, // 仅在指定订阅下生效，None 表示全部
+    pub refresh_interval_minutes: u64,
+    pub enabled: bool,
+    #[serde(default)]
+    pub members: Vec<String>,
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+}
+
+/// 候选节点的健康数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeHealth {
+    pub name: String,
+    pub latency_ms: Option<u64>,
+    pub score: f64,
+}
+
+/// 保存负载均衡分组（新建或更新），返回分组 ID
+#[tauri::command]
+pub async fn save_load_balance_group(mut group: LoadBalanceGroup) -> CmdResult<String> {
+    if group.name.trim().is_empty() {
+        return Err("分组名称不能为空".into());
+    }
+    if group.max_members < 2 {
+        return Err("负载均衡分组至少需要 2 个节点".into());
+    }
+    if let Some(filter) = &group.name_filter {
+        Regex::new(filter).map_err(|e| {
+            CmdError::new(
+                ErrorCode::InvalidArgument,
+                format!("节点过滤正则无效: {}", e),
+            )
+        })?;
+    }
+
+    let _guard = REBUILD_LOCK.lock().await;
+    let mut groups = load_groups().context("加载负载均衡分组失败")?;
+    if group.id.is_empty() {
+        group.id = nanoid::nanoid!();
+    }
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[负载均衡] 保存分组 {} ({:?})",
+        group.name,
+        group.strategy
+    );
+
+    match groups.iter_mut().find(|g| g.id == group.id) {
+        Some(existing) => {
+            group.members = std::mem::take(&mut existing.members);
+            group.updated_at = existing.updated_at;
+            *existing = group.clone();
+        }
+        None => groups.push(group.clone()),
+    }
+    save_groups(&groups).context("保存负载均衡分组失败")?;
+
+    Ok(group.id)
+}
+
+/// 获取所有负载均衡分组
+#[tauri::command]
+pub async fn get_load_balance_groups() -> CmdResult<Vec<LoadBalanceGroup>> {
+    load_groups()
+        .context("加载负载均衡分组失败")
+        .map_err(CmdError::from)
+}
+
+/// 删除负载均衡分组
+#[tauri::command]
+pub async fn delete_load_balance_group(id: String) -> CmdResult<()> {
+    let guard = REBUILD_LOCK.lock().await;
+    let mut groups = load_groups().context("加载负载均衡分组失败")?;
+    let had_members = groups.iter().any(|g| g.id == id && !g.members.is_empty());
+    groups.retain(|g| g.id != id);
+    save_groups(&groups).context("保存负载均衡分组失败")?;
+    drop(guard);
+
+    if had_members {
+        apply_runtime_config().await?;
+    }
+    Ok(())
+}
+
+/// 获取当前订阅下的候选节点健康数据，按评分从高到低排序
+#[tauri::command]
+pub async fn get_load_balance_candidates() -> CmdResult<Vec<NodeHealth>> {
+    Ok(collect_node_health().await?)
+}
+
+/// 立即重建所有负载均衡分组的成员
+#[tauri::command]
+pub async fn rebuild_load_balance_groups() -> CmdResult<Vec<LoadBalanceGroup>> {
+    Ok(rebuild_groups(true).await?)
+}
+
+/// 启动定时重建
+pub fn init_load_balance_rebuilder() {
+    if REBUILD_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut interval = tokio::time::interval(REBUILD_TICK);
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+            if let Err(e) = rebuild_groups(false).await {
+                logging!(warn, Type::Timer, "[负载均衡] 定时重建失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 将负载均衡分组注入运行时配置
+/// 配置中直接定义的成员写入 proxies，其余成员来自 proxy-providers，通过 use + filter 精确匹配
+/// 分组加入第一个 select 分组供用户选择
+pub async fn use_load_balance_groups(mut config: Mapping) -> Mapping {
+    let current = Config::profiles().await.latest_ref().get_current();
+    let Ok(groups) = load_groups() else {
+        return config;
+    };
+
+    let proxy_names: Vec<String> = config
+        .get("proxies")
+        .and_then(Value::as_sequence)
+        .map(|proxies| {
+            proxies
+                .iter()
+                .filter_map(|p| p.get("name").and_then(Value::as_str).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let provider_names: Vec<Value> = config
+        .get("proxy-providers")
+        .and_then(Value::as_mapping)
+        .map(|providers| providers.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let mut injected = Vec::new();
+    for group in groups {
+        if !group.enabled || !applies_to(&group, current.as_deref()) {
+            continue;
+        }
+        // 不在配置 proxies 中的成员只可能来自 proxy-providers，没有提供者时为已失效节点
+        let (members, mut provider_members): (Vec<&String>, Vec<&String>) =
+            group.members.iter().partition(|m| proxy_names.contains(m));
+        if provider_names.is_empty() {
+            provider_members.clear();
+        }
+        if members.len() + provider_members.len() < 2 {
+            continue;
+        }
+
+        let mut lb = Mapping::new();
+        lb.insert("name".into(), group.name.clone().into());
+        lb.insert("type".into(), "load-balance".into());
+        lb.insert(
+            "strategy".into(),
+            serde_yaml_ng::to_value(group.strategy).unwrap_or(Value::Null),
+        );
+        lb.insert("url".into(), "http://www.gstatic.com/generate_204".into());
+        lb.insert("interval".into(), 300.into());
+        if !members.is_empty() {
+            let members: Vec<Value> = members.iter().map(|m| Value::from(m.as_str())).collect();
+            lb.insert("proxies".into(), members.into());
+        }
+        if !provider_members.is_empty() {
+            let names: Vec<String> = provider_members.iter().map(|m| regex::escape(m)).collect();
+            lb.insert("use".into(), provider_names.clone().into());
+            lb.insert("filter".into(), format!("^({})$", names.join("|")).into());
+        }
+        injected.push((group.name, Value::Mapping(lb)));
+    }
+
+    if injected.is_empty() {
+        return config;
+    }
+
+    let Some(Value::Sequence(proxy_groups)) = config.get_mut("proxy-groups") else {
+        return config;
+    };
+    proxy_groups.retain(|g| {
+        let name = g.get("name").and_then(Value::as_str);
+        !injected.iter().any(|(n, _)| Some(n.as_str()) == name)
+    });
+    if let Some(Value::Sequence(select_proxies)) = proxy_groups
+        .iter_mut()
+        .find(|g| g.get("type").and_then(Value::as_str) == Some("select"))
+        .and_then(|g| g.get_mut("proxies"))
+    {
+        for (name, _) in injected.iter().rev() {
+            select_proxies.insert(0, name.as_str().into());
+        }
+    }
+    proxy_groups.extend(injected.into_iter().map(|(_, group)| group));
+
+    config
+}
+
+// ===== 内部实现函数 =====
+
+/// 重建分组成员，force 为 false 时只重建到期的分组
+async fn rebuild_groups(force: bool) -> Result<Vec<LoadBalanceGroup>> {
+    let guard = REBUILD_LOCK.lock().await;
+    let mut groups = load_groups()?;
+    let current = Config::profiles().await.latest_ref().get_current();
+    let now = chrono::Utc::now().timestamp();
+
+    let due: Vec<usize> = groups
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| g.enabled && applies_to(g, current.as_deref()))
+        .filter(|(_, g)| {
+            force
+                || g.updated_at.is_none_or(|updated| {
+                    now - updated >= (g.refresh_interval_minutes.max(1) * 60) as i64
+                })
+        })
+        .map(|(i, _)| i)
+        .collect();
+    if due.is_empty() {
+        return Ok(groups);
+    }
+
+    let candidates = collect_node_health().await?;
+    let mut changed = false;
+    for index in due {
+        let group = &mut groups[index];
+        let members = select_members(group, &candidates);
+        if members != group.members {
+            logging!(
+                info,
+                Type::Cmd,
+                true,
+                "[负载均衡] 分组 {} 成员更新: {:?}",
+                group.name,
+                members
+            );
+            group.members = members;
+            changed = true;
+        }
+        group.updated_at = Some(now);
+    }
+    save_groups(&groups)?;
+    drop(guard);
+
+    if changed {
+        apply_runtime_config().await?;
+    }
+    Ok(groups)
+}
+
+/// 按健康数据挑选分组成员
+fn select_members(group: &LoadBalanceGroup, candidates: &[NodeHealth]) -> Vec<String> {
+    let filter = group
+        .name_filter
+        .as_deref()
+        .and_then(|f| Regex::new(f).ok());
+
+    candidates
+        .iter()
+        .filter(|node| filter.as_ref().is_none_or(|re| re.is_match(&node.name)))
+        .filter(|node| match (node.latency_ms, group.max_latency_ms) {
+            (Some(latency), Some(max)) => latency <= max,
+            (None, Some(_)) => false,
+            (_, None) => true,
+        })
+        .take(group.max_members)
+        .map(|node| node.name.clone())
+        .collect()
+}
+
+/// 汇总内核延迟记录和最近一次全局测速评分
+async fn collect_node_health() -> Result<Vec<NodeHealth>> {
+    let proxies = IpcManager::global()
+        .get_proxies()
+        .await
+        .map_err(|e| anyhow::anyhow!("获取代理列表失败: {}", e))?;
+    let proxies = proxies
+        .get("proxies")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    let speed_scores: HashMap<String, f64> = super::global_speed_test::latest_speed_test_summary()
+        .map(|summary| {
+            summary
+                .all_results
+                .into_iter()
+                .filter(|r| r.is_available)
+                .map(|r| (r.node_name, r.score))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut nodes: Vec<NodeHealth> = proxies
+        .iter()
+        .filter(|(_, info)| {
+            let node_type = info.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            !GROUP_TYPES.contains(&node_type)
+        })
+        .filter(|(_, info)| info.get("alive").and_then(|a| a.as_bool()) != Some(false))
+        .map(|(name, info)| {
+            let latency_ms = info
+                .get("history")
+                .and_then(|h| h.as_array())
+                .and_then(|h| h.last())
+                .and_then(|h| h.get("delay"))
+                .and_then(|d| d.as_u64())
+                .filter(|d| *d > 0);
+            let score = match (speed_scores.get(name), latency_ms) {
+                (Some(score), _) => *score,
+                (None, Some(latency)) => 1000.0 / (latency as f64 + 1.0),
+                (None, None) => 0.0,
+            };
+            NodeHealth {
+                name: name.clone(),
+                latency_ms,
+                score,
+            }
+        })
+        .collect();
+
+    nodes.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(nodes)
+}
+
+/// 分组是否适用于当前订阅
+fn applies_to(group: &LoadBalanceGroup, current: Option<&str>) -> bool {
+    group
+        .profile_uid
+        .as_deref()
+        .is_none_or(|uid| Some(uid) == current)
+}
+
+/// 重新生成运行时配置
+async fn apply_runtime_config() -> Result<()> {
+    CoreManager::global().update_config().await?;
+    handle::Handle::refresh_clash();
+    Ok(())
+}
+
+/// 获取存储文件路径
+fn groups_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("load_balance_groups.json"))
+}
+
+/// 加载负载均衡分组
+fn load_groups() -> Result<Vec<LoadBalanceGroup>> {
+    let path = groups_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存负载均衡分组
+fn save_groups(groups: &[LoadBalanceGroup]) -> Result<()> {
+    let json_data = serde_json::to_string_pretty(groups)?;
+    fs::write(groups_path()?, json_data)?;
+    Ok(())
+}
@@ -1,12 +1,58 @@
-use crate::{logging, utils::logging::Type};
+use super::CmdResult;
+use crate::{
+    config::Config,
+    ipc::IpcManager,
+    logging,
+    utils::{dirs, logging::Type},
+};
 use chrono::Local;
+use futures::future::BoxFuture;
+use once_cell::sync::Lazy;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
-use tauri::command;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    future::Future,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, command};
 use tokio::{sync::Mutex, task::JoinSet};
 
+const DEFINITIONS_FILE: &str = "media_unlock_definitions.json";
+/// 单项检测超时时间
+const CHECK_TIMEOUT_SECS: u64 = 20;
+/// 节点解锁结果缓存有效期
+const NODE_CACHE_TTL_SECS: i64 = 30 * 60;
+
+/// 节点检测需要临时切换全局代理，同一时间只允许一个节点检测，也不与全局测速同时进行
+static NODE_CHECK_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+/// 节点解锁结果缓存：订阅 uid -> 节点 -> 结果
+static NODE_UNLOCK_CACHE: Lazy<
+    parking_lot::Mutex<HashMap<String, HashMap<String, NodeUnlockRow>>>,
+> = Lazy::new(|| parking_lot::Mutex::new(HashMap::new()));
+
+/// 节点解锁检测是否正在进行
+pub(crate) fn is_node_check_running() -> bool {
+    NODE_CHECK_LOCK.try_lock().is_err()
+}
+
+const BUILTIN_CHECK_NAMES: &[&str] = &[
+    "哔哩哔哩大陆",
+    "哔哩哔哩港澳台",
+    "ChatGPT iOS",
+    "ChatGPT Web",
+    "Gemini",
+    "Youtube Premium",
+    "Bahamut Anime",
+    "Netflix",
+    "Disney+",
+    "Prime Video",
+];
+
 // 定义解锁测试项目的结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnlockItem {
@@ -16,6 +62,45 @@ pub struct UnlockItem {
     check_time: Option<String>,
 }
 
+/// 用户自定义的检测项：请求 url，根据响应（状态行和正文）中的标记判断是否解锁
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockCheckDefinition {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub expected_markers: Vec<String>, // 请求成功且任一命中视为解锁，至少需要一个
+    #[serde(default)]
+    pub blocked_markers: Vec<String>, // 任一命中视为未解锁，优先于 expected_markers
+    #[serde(default)]
+    pub markers_are_regex: bool,
+    #[serde(default)]
+    pub region_regex: Option<String>, // 第一个捕获组为地区代码
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// 单个节点的解锁结果
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeUnlockRow {
+    pub node: String,
+    pub checked_at: i64,
+    pub results: HashMap<String, UnlockItem>, // 检测项名称 -> 结果
+}
+
+/// 节点 × 检测项解锁矩阵
+#[derive(Debug, Clone, Serialize)]
+pub struct UnlockMatrix {
+    pub services: Vec<String>,
+    pub nodes: Vec<NodeUnlockRow>,
+}
+
+/// 一项待执行的检测
+struct UnlockCheck {
+    names: Vec<String>,
+    timeout: Duration,
+    future: BoxFuture<'static, Vec<UnlockItem>>,
+}
+
 // 获取当前本地时间字符串
 fn get_local_date_string() -> String {
     let now = Local::now();
@@ -1361,8 +1446,8 @@ async fn check_prime_video(client: &Client) -> UnlockItem {
 
 // 获取所有解锁项目的列表
 #[command]
-pub async fn get_unlock_items() -> Result<Vec<UnlockItem>, String> {
-    let items = vec![
+pub async fn get_unlock_items() -> CmdResult<Vec<UnlockItem>> {
+    let mut items = vec![
         UnlockItem {
             name: "哔哩哔哩大陆".to_string(),
             status: "Pending".to_string(),
@@ -1425,152 +1510,470 @@ pub async fn get_unlock_items() -> Result<Vec<UnlockItem>, String> {
         },
     ];
 
+    items.extend(
+        load_definitions()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|definition| UnlockItem {
+                name: definition.name,
+                status: "Pending".to_string(),
+                region: None,
+                check_time: None,
+            }),
+    );
+
     Ok(items)
 }
 
-// 开始检测流媒体解锁状态
+// 开始检测流媒体解锁状态，每项完成后通过 media-unlock-result 事件推送结果
 #[command]
-pub async fn check_media_unlock() -> Result<Vec<UnlockItem>, String> {
-    // 创建一个http客户端，增加更多配置
-    let client = match Client::builder()
-        .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36")
-        .timeout(std::time::Duration::from_secs(30)) // 全局超时设置
-        .danger_accept_invalid_certs(true) // 接受无效证书，防止SSL错误
-        .danger_accept_invalid_hostnames(true) // 接受无效主机名
-        .tcp_keepalive(std::time::Duration::from_secs(60)) // TCP keepalive
-        .connection_verbose(true) // 详细连接信息
-        .build() {
-        Ok(client) => client,
-        Err(e) => return Err(format!("创建HTTP客户端失败: {e}")),
-    };
+pub async fn check_media_unlock(app_handle: AppHandle) -> CmdResult<Vec<UnlockItem>> {
+    let client = build_client(None)?;
+    let checks = all_checks(Arc::new(client), load_definitions().unwrap_or_default());
+    Ok(run_checks(checks, Some(&app_handle)).await)
+}
 
-    // 创建共享的结果集
-    let results = Arc::new(Mutex::new(Vec::new()));
+/// 通过指定节点检测部分或全部项目，结果写入缓存，缓存有效期内直接返回
+/// 检测期间临时切换为全局模式并选中该节点，结束后恢复
+#[command]
+pub async fn check_unlock_for_node(
+    profile_uid: String,
+    node: String,
+    services: Option<Vec<String>>,
+    force: Option<bool>,
+) -> CmdResult<Vec<UnlockItem>> {
+    let wanted = |name: &str| {
+        services
+            .as_ref()
+            .is_none_or(|services| services.iter().any(|s| s == name))
+    };
 
-    // 创建一个任务集，用于并行处理所有检测
-    let mut tasks = JoinSet::new();
+    if !force.unwrap_or(false)
+        && let Some(row) = NODE_UNLOCK_CACHE
+            .lock()
+            .get(&profile_uid)
+            .and_then(|nodes| nodes.get(&node))
+        && Local::now().timestamp() - row.checked_at < NODE_CACHE_TTL_SECS
+    {
+        let cached: Vec<UnlockItem> = row
+            .results
+            .values()
+            .filter(|item| wanted(&item.name))
+            .cloned()
+            .collect();
+        let requested: Vec<String> = match &services {
+            Some(services) => services.clone(),
+            None => BUILTIN_CHECK_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .chain(
+                    load_definitions()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|d| d.name),
+                )
+                .collect(),
+        };
+        if requested.iter().all(|name| row.results.contains_key(name)) {
+            return Ok(cached);
+        }
+    }
 
-    // 共享的Client实例
-    let client_arc = Arc::new(client);
+    let current = Config::profiles().await.latest_ref().get_current();
+    if current.as_deref() != Some(profile_uid.as_str()) {
+        return Err("只能检测当前订阅中的节点".into());
+    }
 
-    // 添加哔哩哔哩大陆检测任务
-    {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_bilibili_china_mainland(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
+    let _guard = NODE_CHECK_LOCK.lock().await;
+    // 先持有检测锁再检查测速标志，测速开始时会检查检测锁，两者不会同时运行
+    if super::global_speed_test::is_speed_test_running() {
+        return Err("全局测速正在进行中，请稍后再检测".into());
+    }
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[解锁检测] 通过节点 {} 检测 {:?}",
+        node,
+        services
+    );
+    let results = run_checks_via_node(&node, &wanted).await?;
+
+    let mut cache = NODE_UNLOCK_CACHE.lock();
+    let row = cache
+        .entry(profile_uid)
+        .or_default()
+        .entry(node.clone())
+        .or_insert_with(|| NodeUnlockRow {
+            node,
+            checked_at: 0,
+            results: HashMap::new(),
         });
+    row.checked_at = Local::now().timestamp();
+    for item in &results {
+        row.results.insert(item.name.clone(), item.clone());
     }
+    Ok(results)
+}
+
+/// 获取订阅已检测节点的解锁矩阵
+#[command]
+pub async fn get_unlock_matrix(profile_uid: String) -> CmdResult<UnlockMatrix> {
+    let cache = NODE_UNLOCK_CACHE.lock();
+    let mut nodes: Vec<NodeUnlockRow> = cache
+        .get(&profile_uid)
+        .map(|nodes| nodes.values().cloned().collect())
+        .unwrap_or_default();
+    nodes.sort_by(|a, b| a.node.cmp(&b.node));
+    let services: BTreeSet<String> = nodes
+        .iter()
+        .flat_map(|row| row.results.keys().cloned())
+        .collect();
+    Ok(UnlockMatrix {
+        services: services.into_iter().collect(),
+        nodes,
+    })
+}
 
-    // 添加哔哩哔哩港澳台检测任务
+/// 获取自定义检测项
+#[command]
+pub async fn get_unlock_check_definitions() -> CmdResult<Vec<UnlockCheckDefinition>> {
+    load_definitions().map_err(|e| format!("加载自定义检测项失败: {e}").into())
+}
+
+/// 添加自定义检测项，同名时覆盖
+#[command]
+pub async fn add_unlock_check_definition(definition: UnlockCheckDefinition) -> CmdResult<()> {
+    if definition.name.trim().is_empty() {
+        return Err("检测项名称不能为空".into());
+    }
+    if BUILTIN_CHECK_NAMES.contains(&definition.name.as_str()) {
+        return Err(format!("{} 与内置检测项重名", definition.name).into());
+    }
+    reqwest::Url::parse(&definition.url).map_err(|e| format!("无效的检测地址: {e}"))?;
+    if definition
+        .expected_markers
+        .iter()
+        .all(|marker| marker.trim().is_empty())
     {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_bilibili_hk_mc_tw(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
-        });
+        return Err("至少需要一个解锁标记".into());
+    }
+    if definition.markers_are_regex {
+        for pattern in definition
+            .expected_markers
+            .iter()
+            .chain(&definition.blocked_markers)
+        {
+            Regex::new(pattern).map_err(|e| format!("无效的正则表达式 {pattern}: {e}"))?;
+        }
+    }
+    if let Some(pattern) = &definition.region_regex {
+        Regex::new(pattern).map_err(|e| format!("无效的地区正则 {pattern}: {e}"))?;
     }
 
-    // 添加合并的ChatGPT检测任务
-    {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let chatgpt_results = check_chatgpt_combined(&client).await;
-            let mut results = results.lock().await;
-            results.extend(chatgpt_results);
-        });
+    let mut definitions = load_definitions().map_err(|e| format!("加载自定义检测项失败: {e}"))?;
+    definitions.retain(|existing| existing.name != definition.name);
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[解锁检测] 添加自定义检测项: {}",
+        definition.name
+    );
+    definitions.push(definition);
+    save_definitions(&definitions).map_err(|e| format!("保存自定义检测项失败: {e}").into())
+}
+
+/// 删除自定义检测项
+#[command]
+pub async fn remove_unlock_check_definition(name: String) -> CmdResult<()> {
+    let mut definitions = load_definitions().map_err(|e| format!("加载自定义检测项失败: {e}"))?;
+    definitions.retain(|existing| existing.name != name);
+    save_definitions(&definitions).map_err(|e| format!("保存自定义检测项失败: {e}").into())
+}
+
+/// 临时切换到节点执行检测，完成后恢复代理模式和 GLOBAL 组的选择
+async fn run_checks_via_node(
+    node: &str,
+    wanted: &(dyn Fn(&str) -> bool + Sync),
+) -> Result<Vec<UnlockItem>, String> {
+    let ipc = IpcManager::global();
+    let proxies = ipc
+        .get_proxies()
+        .await
+        .map_err(|e| format!("获取代理信息失败: {e}"))?;
+    let global = proxies.get("proxies").unwrap_or(&proxies).get("GLOBAL");
+    let contains_node = global
+        .and_then(|group| group.get("all"))
+        .and_then(|all| all.as_array())
+        .is_some_and(|all| all.iter().any(|name| name.as_str() == Some(node)));
+    if !contains_node {
+        return Err(format!("节点 {node} 不存在"));
     }
+    let original_node = global
+        .and_then(|group| group.get("now"))
+        .and_then(|now| now.as_str())
+        .map(str::to_string);
+    let original_mode = ipc
+        .get_config()
+        .await
+        .ok()
+        .and_then(|config| {
+            config
+                .get("mode")
+                .and_then(|m| m.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "rule".to_string());
+
+    ipc.patch_configs(serde_json::json!({ "mode": "global" }))
+        .await
+        .map_err(|e| format!("切换全局模式失败: {e}"))?;
+    let results = match ipc.update_proxy("GLOBAL", node).await {
+        Ok(()) => {
+            let port = {
+                let verge_port = Config::verge().await.latest_ref().verge_mixed_port;
+                match verge_port {
+                    Some(port) => port,
+                    None => Config::clash().await.latest_ref().get_mixed_port(),
+                }
+            };
+            match build_client(Some(&format!("http://127.0.0.1:{port}"))) {
+                Ok(client) => {
+                    let mut checks =
+                        all_checks(Arc::new(client), load_definitions().unwrap_or_default());
+                    checks.retain(|check| check.names.iter().any(|name| wanted(name)));
+                    let mut results = run_checks(checks, None).await;
+                    results.retain(|item| wanted(&item.name));
+                    Ok(results)
+                }
+                Err(e) => Err(e),
+            }
+        }
+        Err(e) => Err(format!("切换到节点 {node} 失败: {e}")),
+    };
 
-    // 添加Gemini检测任务
+    // 无论检测成功与否都恢复原状态
+    if let Some(original) = original_node
+        && let Err(e) = ipc.update_proxy("GLOBAL", &original).await
     {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_gemini(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
-        });
+        logging!(
+            warn,
+            Type::Network,
+            true,
+            "[解锁检测] 恢复 GLOBAL 选择失败: {}",
+            e
+        );
     }
-
-    // 添加YouTube Premium检测任务
+    if let Err(e) = ipc
+        .patch_configs(serde_json::json!({ "mode": original_mode }))
+        .await
     {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_youtube_premium(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
-        });
+        logging!(
+            warn,
+            Type::Network,
+            true,
+            "[解锁检测] 恢复代理模式失败: {}",
+            e
+        );
     }
+    results
+}
 
-    // 添加动画疯检测任务
-    {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_bahamut_anime(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
-        });
+// 创建检测用的http客户端，proxy 为空时使用系统网络
+fn build_client(proxy: Option<&str>) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36")
+        .timeout(std::time::Duration::from_secs(30)) // 全局超时设置
+        .danger_accept_invalid_certs(true) // 接受无效证书，防止SSL错误
+        .danger_accept_invalid_hostnames(true) // 接受无效主机名
+        .tcp_keepalive(std::time::Duration::from_secs(60)) // TCP keepalive
+        .connection_verbose(true); // 详细连接信息
+    if let Some(proxy) = proxy {
+        builder =
+            builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| format!("无效的代理地址: {e}"))?);
     }
+    builder
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {e}"))
+}
 
-    // 添加 Netflix 检测任务
+/// 内置检测项及自定义检测项，names 为该检测产生的结果名称
+fn all_checks(client: Arc<Client>, definitions: Vec<UnlockCheckDefinition>) -> Vec<UnlockCheck> {
+    fn single<F, Fut>(name: &str, client: &Arc<Client>, check: F) -> UnlockCheck
+    where
+        F: FnOnce(Arc<Client>) -> Fut,
+        Fut: Future<Output = UnlockItem> + Send + 'static,
     {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_netflix(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
-        });
+        let fut = check(Arc::clone(client));
+        UnlockCheck {
+            names: vec![name.to_string()],
+            timeout: Duration::from_secs(CHECK_TIMEOUT_SECS),
+            future: Box::pin(async move { vec![fut.await] }),
+        }
     }
 
-    // 添加 Disney+ 检测任务
-    {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
-        tasks.spawn(async move {
-            let result = check_disney_plus(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
+    let mut checks = vec![
+        single("哔哩哔哩大陆", &client, |c| async move {
+            check_bilibili_china_mainland(&c).await
+        }),
+        single("哔哩哔哩港澳台", &client, |c| async move {
+            check_bilibili_hk_mc_tw(&c).await
+        }),
+        UnlockCheck {
+            names: vec!["ChatGPT iOS".to_string(), "ChatGPT Web".to_string()],
+            timeout: Duration::from_secs(CHECK_TIMEOUT_SECS),
+            future: {
+                let client = Arc::clone(&client);
+                Box::pin(async move { check_chatgpt_combined(&client).await })
+            },
+        },
+        single("Gemini", &client, |c| async move { check_gemini(&c).await }),
+        single("Youtube Premium", &client, |c| async move {
+            check_youtube_premium(&c).await
+        }),
+        single("Bahamut Anime", &client, |c| async move {
+            check_bahamut_anime(&c).await
+        }),
+        single(
+            "Netflix",
+            &client,
+            |c| async move { check_netflix(&c).await },
+        ),
+        single("Disney+", &client, |c| async move {
+            check_disney_plus(&c).await
+        }),
+        single("Prime Video", &client, |c| async move {
+            check_prime_video(&c).await
+        }),
+    ];
+
+    for definition in definitions {
+        let client = Arc::clone(&client);
+        checks.push(UnlockCheck {
+            names: vec![definition.name.clone()],
+            timeout: Duration::from_secs(definition.timeout_secs.unwrap_or(CHECK_TIMEOUT_SECS)),
+            future: Box::pin(async move { vec![check_custom(&client, &definition).await] }),
         });
     }
+    checks
+}
 
-    // 添加 Prime Video 检测任务
-    {
-        let client = Arc::clone(&client_arc);
-        let results = Arc::clone(&results);
+/// 并行执行检测，每项单独超时，完成一项推送一项
+async fn run_checks(checks: Vec<UnlockCheck>, app_handle: Option<&AppHandle>) -> Vec<UnlockItem> {
+    let mut tasks = JoinSet::new();
+    for check in checks {
         tasks.spawn(async move {
-            let result = check_prime_video(&client).await;
-            let mut results = results.lock().await;
-            results.push(result);
+            match tokio::time::timeout(check.timeout, check.future).await {
+                Ok(items) => items,
+                Err(_) => check
+                    .names
+                    .into_iter()
+                    .map(|name| UnlockItem {
+                        name,
+                        status: "Failed (Timeout)".to_string(),
+                        region: None,
+                        check_time: Some(get_local_date_string()),
+                    })
+                    .collect(),
+            }
         });
     }
 
-    // 等待所有任务完成
+    let mut results = Vec::new();
     while let Some(res) = tasks.join_next().await {
-        if let Err(e) = res {
-            eprintln!("任务执行失败: {e}");
+        match res {
+            Ok(items) => {
+                if let Some(app_handle) = app_handle {
+                    for item in &items {
+                        if let Err(err) = app_handle.emit("media-unlock-result", item) {
+                            log::warn!(target: "app", "media-unlock-result emit failed: {err}");
+                        }
+                    }
+                }
+                results.extend(items);
+            }
+            Err(e) => {
+                logging!(error, Type::Network, "解锁检测任务执行失败: {}", e);
+            }
         }
     }
+    results
+}
 
-    // 获取所有结果
-    let results = match Arc::try_unwrap(results) {
-        Ok(mutex) => mutex.into_inner(),
-        Err(_) => {
-            logging!(
-                error,
-                Type::Network,
-                "Failed to unwrap results Arc, references still exist"
-            );
-            return Err("Failed to collect results".to_string());
+/// 按自定义定义检测：命中屏蔽标记为 No，请求成功且命中期望标记为 Yes，
+/// 403 / 451 为 No，其余情况为 Failed
+async fn check_custom(client: &Client, definition: &UnlockCheckDefinition) -> UnlockItem {
+    let item = |status: &str, region: Option<String>| UnlockItem {
+        name: definition.name.clone(),
+        status: status.to_string(),
+        region,
+        check_time: Some(get_local_date_string()),
+    };
+
+    let (status, body) = match client.get(&definition.url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            match response.text().await {
+                Ok(body) => (status, format!("{status}\n{body}")),
+                Err(_) => return item("Failed (Error: Cannot read response)", None),
+            }
         }
+        Err(_) => return item("Failed (Network Connection)", None),
     };
 
-    Ok(results)
+    let matches = |markers: &[String]| {
+        markers
+            .iter()
+            .filter(|marker| !marker.trim().is_empty())
+            .any(|marker| {
+                if definition.markers_are_regex {
+                    Regex::new(marker).is_ok_and(|re| re.is_match(&body))
+                } else {
+                    body.contains(marker.as_str())
+                }
+            })
+    };
+    let region = definition
+        .region_regex
+        .as_deref()
+        .and_then(|pattern| Regex::new(pattern).ok())
+        .and_then(|re| re.captures(&body))
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(0)))
+        .map(|m| {
+            let code = m.as_str().to_uppercase();
+            format!("{}{}", country_code_to_emoji(&code), code)
+        });
+
+    if matches(&definition.blocked_markers) {
+        item("No", region)
+    } else if matches!(status.as_u16(), 403 | 451) {
+        item("No", region)
+    } else if !status.is_success() {
+        item(&format!("Failed (HTTP {})", status.as_u16()), region)
+    } else if matches(&definition.expected_markers) {
+        item("Yes", region)
+    } else {
+        item("Failed", region)
+    }
+}
+
+fn definitions_path() -> anyhow::Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(DEFINITIONS_FILE))
+}
+
+fn load_definitions() -> anyhow::Result<Vec<UnlockCheckDefinition>> {
+    let path = definitions_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_definitions(definitions: &[UnlockCheckDefinition]) -> anyhow::Result<()> {
+    fs::write(
+        definitions_path()?,
+        serde_json::to_string_pretty(definitions)?,
+    )?;
+    Ok(())
 }
@@ -1,29 +1,89 @@
 use anyhow::Result;
 
-pub type CmdResult<T = ()> = Result<T, String>;
+pub type CmdResult<T = ()> = Result<T, CmdError>;
 
 // Command modules
 pub mod advanced_search;
+pub mod api_server;
 pub mod app;
+pub mod app_lock;
+pub mod app_routes;
+pub mod app_update;
 pub mod backup_restore;
+pub mod backup_schedule;
+pub mod bandwidth_limits;
 pub mod batch_import;
+pub mod cancellation;
 pub mod clash;
+pub mod client_migration;
+pub mod composite_profile;
+pub mod config_sandbox;
+pub mod core_registry;
+pub mod core_upgrade;
+pub mod custom_rules;
+pub mod delay_targets;
+pub mod device_sync;
+pub mod diagnostics;
+pub mod dns_benchmark;
+pub mod dns_hosts;
+pub mod error;
+#[cfg(feature = "dev-fixtures")]
+pub mod dev_fixtures;
+pub mod event_publisher;
+pub mod expiry_reminder;
+pub mod external_controller;
+pub mod fakeip_filter;
+pub mod favorite_nodes;
+pub mod firewall;
+pub mod geo_data;
 pub mod global_speed_test;
+pub mod group_test_policy;
 pub mod health_check;
+pub mod hotkey;
+pub mod ipv6;
+pub mod job_manager;
+pub mod lan_sharing;
 pub mod lightweight;
+pub mod load_balance;
 pub mod media_unlock_checker;
 pub mod network;
+pub mod network_rules;
+pub mod node_dedup;
+pub mod node_filters;
+pub mod node_rename;
+pub mod offline;
+pub mod pac;
+pub mod portable_bundle;
+pub mod port_overrides;
 pub mod profile;
+pub mod profile_share;
+pub mod profile_template;
+pub mod progress;
+pub mod provider_outage;
 pub mod proxy;
+pub mod proxy_chain;
+pub mod quick_patch;
+pub mod remote_backup;
+pub mod resource_monitor;
+pub mod route_explain;
+pub mod ruleset_manager;
 pub mod runtime;
 pub mod save_profile;
+pub mod secrets;
+pub mod selection_memory;
 pub mod service;
+pub mod speed_test_report;
+pub mod startup_recovery;
 pub mod subscription_batch_manager;
 pub mod subscription_fetch;
 pub mod subscription_groups;
+pub mod subscription_index;
+pub mod subscription_migration;
 pub mod subscription_testing;
+pub mod subscription_usage;
 pub mod system;
 pub mod task_manager;
+pub mod traffic_report;
 pub mod traffic_stats;
 pub mod uwp;
 pub mod validate;
@@ -32,26 +92,86 @@ pub mod webdav;
 
 // Re-export all command functions for backwards compatibility
 pub use advanced_search::*;
+pub use api_server::*;
 pub use app::*;
+pub use app_lock::*;
+pub use app_routes::*;
+pub use app_update::*;
 pub use backup_restore::*;
+pub use backup_schedule::*;
+pub use bandwidth_limits::*;
 pub use batch_import::*;
+pub use cancellation::*;
 pub use clash::*;
+pub use client_migration::*;
+pub use composite_profile::*;
+pub use config_sandbox::*;
+pub use core_registry::*;
+pub use core_upgrade::*;
+pub use custom_rules::*;
+pub use delay_targets::*;
+pub use device_sync::*;
+pub use diagnostics::*;
+pub use dns_benchmark::*;
+pub use dns_hosts::*;
+pub use error::*;
+#[cfg(feature = "dev-fixtures")]
+pub use dev_fixtures::*;
+pub use event_publisher::*;
+pub use expiry_reminder::*;
+pub use external_controller::*;
+pub use fakeip_filter::*;
+pub use favorite_nodes::*;
+pub use firewall::*;
+pub use geo_data::*;
 pub use global_speed_test::*;
+pub use group_test_policy::*;
 pub use health_check::*;
+pub use hotkey::*;
+pub use ipv6::*;
+pub use job_manager::*;
+pub use lan_sharing::*;
 pub use lightweight::*;
+pub use load_balance::*;
 pub use media_unlock_checker::*;
 pub use network::*;
+pub use network_rules::*;
+pub use node_dedup::*;
+pub use node_filters::*;
+pub use node_rename::*;
+pub use offline::*;
+pub use pac::*;
+pub use portable_bundle::*;
+pub use port_overrides::*;
 pub use profile::*;
+pub use profile_share::*;
+pub use profile_template::*;
+pub use progress::*;
+pub use provider_outage::*;
 pub use proxy::*;
+pub use proxy_chain::*;
+pub use quick_patch::*;
+pub use remote_backup::*;
+pub use resource_monitor::*;
+pub use route_explain::*;
+pub use ruleset_manager::*;
 pub use runtime::*;
 pub use save_profile::*;
+pub use secrets::*;
+pub use selection_memory::*;
 pub use service::*;
+pub use speed_test_report::*;
+pub use startup_recovery::*;
 pub use subscription_batch_manager::*;
 pub use subscription_fetch::*;
 pub use subscription_groups::*;
+pub use subscription_index::*;
+pub use subscription_migration::*;
 pub use subscription_testing::*;
+pub use subscription_usage::*;
 pub use system::*;
 pub use task_manager::*;
+pub use traffic_report::*;
 pub use traffic_stats::*;
 pub use uwp::*;
 pub use validate::*;
@@ -1,8 +1,15 @@
-use super::CmdResult;
-use crate::core::{EventDrivenProxyManager, async_proxy_query::AsyncProxyQuery};
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::config::{Config, IVerge};
+use crate::core::{
+    EventDrivenProxyManager,
+    async_proxy_query::AsyncProxyQuery,
+    sysopt::{self, BypassRule, SysproxyBackendReport},
+};
 use crate::process::AsyncHandler;
-use crate::wrap_err;
+use crate::{feat, wrap_err};
+use anyhow::Context;
 use network_interface::NetworkInterface;
+use serde::Serialize;
 use serde_yaml_ng::Mapping;
 
 /// get the system proxy
@@ -45,6 +52,74 @@ pub async fn get_auto_proxy() -> CmdResult<Mapping> {
     Ok(map)
 }
 
+/// 系统代理排除规则
+#[derive(Debug, Serialize)]
+pub struct ProxyBypassRules {
+    pub use_default: bool,
+    pub default_rules: Vec<BypassRule>,
+    pub rules: Vec<BypassRule>, // 自定义规则
+    pub rendered: String,       // 当前平台实际写入的排除列表
+}
+
+/// 获取结构化的系统代理排除规则
+#[tauri::command]
+pub async fn get_proxy_bypass_rules() -> CmdResult<ProxyBypassRules> {
+    let (use_default, custom_bypass) = {
+        let verge = Config::verge().await;
+        let verge = verge.latest_ref();
+        (
+            verge.use_default_bypass.unwrap_or(true),
+            verge.system_proxy_bypass.clone().unwrap_or_default(),
+        )
+    };
+
+    Ok(ProxyBypassRules {
+        use_default,
+        default_rules: sysopt::default_bypass_rules(),
+        rules: sysopt::parse_bypass_list(&custom_bypass),
+        rendered: sysopt::get_bypass().await,
+    })
+}
+
+/// 校验并保存自定义排除规则，保存后刷新系统代理
+#[tauri::command]
+pub async fn set_proxy_bypass_rules(rules: Vec<String>) -> CmdResult<ProxyBypassRules> {
+    let mut parsed: Vec<BypassRule> = Vec::new();
+    let mut errors = Vec::new();
+    for rule in rules.iter().map(|r| r.trim()).filter(|r| !r.is_empty()) {
+        match BypassRule::parse(rule) {
+            Ok(rule) if !parsed.contains(&rule) => parsed.push(rule),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(CmdError::new(ErrorCode::InvalidArgument, errors.join("\n")));
+    }
+
+    let bypass = parsed
+        .iter()
+        .map(|r| r.value.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let patch = IVerge {
+        system_proxy_bypass: Some(bypass),
+        ..IVerge::default()
+    };
+    wrap_err!(feat::patch_verge(patch, false).await)?;
+
+    get_proxy_bypass_rules().await
+}
+
+/// 获取系统代理后端状态：当前桌面环境使用的机制及每个后端最近一次应用是否成功
+#[tauri::command]
+pub async fn get_sysproxy_backend_status() -> CmdResult<SysproxyBackendReport> {
+    AsyncHandler::spawn_blocking(sysopt::sysproxy_backend_report)
+        .await
+        .context("检测系统代理后端失败")
+        .map_err(CmdError::from)
+}
+
 /// 获取系统主机名
 #[tauri::command]
 pub fn get_system_hostname() -> CmdResult<String> {
@@ -82,7 +157,7 @@ pub fn get_network_interfaces_info() -> CmdResult<Vec<NetworkInterface>> {
     use network_interface::{NetworkInterface, NetworkInterfaceConfig};
 
     let names = get_network_interfaces();
-    let interfaces = wrap_err!(NetworkInterface::show())?;
+    let interfaces = wrap_err!(NetworkInterface::show().map_err(anyhow::Error::from))?;
 
     let mut result = Vec::new();
 
@@ -0,0 +1,256 @@
+use super::{
+    CmdError, CmdResult, ErrorCode, WithErrorCode, patch_profiles_config_by_profile_index,
+};
+use crate::{
+    config::{Config, IVerge},
+    core::system_events::{SystemEventKind, SystemEventMonitor},
+    feat, logging,
+    process::AsyncHandler,
+    utils::{
+        dirs,
+        logging::Type,
+        network::{NetworkContext, current_network_context},
+    },
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tokio::sync::Mutex;
+
+const NETWORK_RULES_FILE: &str = "network_rules.json";
+
+/// 匹配有线网络（未连接 Wi-Fi 时）的特殊 SSID
+pub const WIRED_NETWORK: &str = "<wired>";
+
+static RULES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 上一次应用规则时的网络环境，避免同一网络重复执行
+static LAST_CONTEXT: Lazy<Mutex<Option<NetworkContext>>> = Lazy::new(|| Mutex::new(None));
+
+static NETWORK_RULES_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 切换到指定网络后执行的操作
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetworkAction {
+    SystemProxy { enable: bool },
+    Tun { enable: bool },
+    SwitchProfile { uid: String },
+}
+
+impl NetworkAction {
+    /// 同一网络中每种操作只保留一个
+    fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// 网络规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRule {
+    pub ssid: String, // Wi-Fi 名称，或 WIRED_NETWORK
+    pub actions: Vec<NetworkAction>,
+    pub updated_at: i64,
+}
+
+/// 设置网络规则，同类操作会被替换
+#[tauri::command]
+pub async fn set_network_rule(ssid: String, action: NetworkAction) -> CmdResult<Vec<NetworkRule>> {
+    let ssid = ssid.trim().to_string();
+    if ssid.is_empty() {
+        return Err("网络名称不能为空".into());
+    }
+    if let NetworkAction::SwitchProfile { uid } = &action {
+        Config::profiles()
+            .await
+            .latest_ref()
+            .get_item(uid)
+            .with_code(ErrorCode::ProfileNotFound)?;
+    }
+
+    let _guard = RULES_LOCK.lock().await;
+    let mut rules = load_rules().context("加载网络规则失败")?;
+    let now = chrono::Utc::now().timestamp();
+    match rules.iter_mut().find(|r| r.ssid == ssid) {
+        Some(rule) => {
+            rule.actions.retain(|a| !a.same_kind(&action));
+            rule.actions.push(action.clone());
+            rule.updated_at = now;
+        }
+        None => rules.push(NetworkRule {
+            ssid: ssid.clone(),
+            actions: vec![action.clone()],
+            updated_at: now,
+        }),
+    }
+    save_rules(&rules).context("保存网络规则失败")?;
+
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[网络规则] {} -> {:?}",
+        ssid,
+        action
+    );
+    Ok(rules)
+}
+
+/// 删除网络规则
+#[tauri::command]
+pub async fn remove_network_rule(ssid: String) -> CmdResult<Vec<NetworkRule>> {
+    let _guard = RULES_LOCK.lock().await;
+    let mut rules = load_rules().context("加载网络规则失败")?;
+    rules.retain(|r| r.ssid != ssid);
+    save_rules(&rules).context("保存网络规则失败")?;
+    Ok(rules)
+}
+
+/// 获取网络规则列表
+#[tauri::command]
+pub async fn list_network_rules() -> CmdResult<Vec<NetworkRule>> {
+    load_rules()
+        .context("加载网络规则失败")
+        .map_err(CmdError::from)
+}
+
+/// 获取当前网络环境
+#[tauri::command]
+pub async fn get_network_context() -> CmdResult<NetworkContext> {
+    Ok(current_network_context().await)
+}
+
+/// 监听网络变化事件并执行匹配的网络规则
+pub fn init_network_rules() {
+    if NETWORK_RULES_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut receiver = SystemEventMonitor::global().subscribe();
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.kind == SystemEventKind::NetworkInterfaceChanged => {
+                    evaluate_network_rules().await;
+                }
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// ===== 内部实现函数 =====
+
+async fn evaluate_network_rules() {
+    let context = current_network_context().await;
+    {
+        let mut last = LAST_CONTEXT.lock().await;
+        if last.as_ref() == Some(&context) {
+            return;
+        }
+        *last = Some(context.clone());
+    }
+
+    let rules = match load_rules() {
+        Ok(rules) => rules,
+        Err(e) => {
+            logging!(
+                warn,
+                Type::Network,
+                true,
+                "[网络规则] 加载网络规则失败: {}",
+                e
+            );
+            return;
+        }
+    };
+    let Some(rule) = match_rule(&rules, &context) else {
+        return;
+    };
+
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[网络规则] 当前网络 {}，执行 {} 个操作",
+        rule.ssid,
+        rule.actions.len()
+    );
+    for action in &rule.actions {
+        if let Err(e) = apply_action(action).await {
+            logging!(
+                warn,
+                Type::Network,
+                true,
+                "[网络规则] 执行 {:?} 失败: {}",
+                action,
+                e
+            );
+        }
+    }
+}
+
+/// Wi-Fi 规则优先，未连接 Wi-Fi 时匹配有线网络规则
+fn match_rule<'a>(rules: &'a [NetworkRule], context: &NetworkContext) -> Option<&'a NetworkRule> {
+    match &context.ssid {
+        Some(ssid) => rules.iter().find(|r| &r.ssid == ssid),
+        None if context.wired => rules.iter().find(|r| r.ssid == WIRED_NETWORK),
+        None => None,
+    }
+}
+
+async fn apply_action(action: &NetworkAction) -> CmdResult<()> {
+    match action {
+        NetworkAction::SystemProxy { enable } => {
+            let patch = IVerge {
+                enable_system_proxy: Some(*enable),
+                ..IVerge::default()
+            };
+            feat::patch_verge(patch, false)
+                .await
+                .map_err(CmdError::from)
+        }
+        NetworkAction::Tun { enable } => {
+            let patch = IVerge {
+                enable_tun_mode: Some(*enable),
+                ..IVerge::default()
+            };
+            feat::patch_verge(patch, false)
+                .await
+                .map_err(CmdError::from)
+        }
+        NetworkAction::SwitchProfile { uid } => {
+            let current = Config::profiles().await.latest_ref().get_current();
+            if current.as_ref() == Some(uid) {
+                return Ok(());
+            }
+            patch_profiles_config_by_profile_index(uid.clone())
+                .await
+                .map(|_| ())
+        }
+    }
+}
+
+/// 获取存储文件路径
+fn rules_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(NETWORK_RULES_FILE))
+}
+
+fn load_rules() -> Result<Vec<NetworkRule>> {
+    let path = rules_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_rules(rules: &[NetworkRule]) -> Result<()> {
+    fs::write(rules_path()?, serde_json::to_string_pretty(rules)?)?;
+    Ok(())
+}
@@ -0,0 +1,375 @@
+use super::{CmdError, CmdResult};
+use crate::{
+    cmd::composite_profile_uids,
+    config::{Config, PrfItem, profiles_append_item_safe},
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+const DEDUP_PROFILE_FILE: &str = "dedup_profile.json";
+const DEDUP_PROFILE_NAME: &str = "全部节点（去重）";
+
+/// 去重策略：仅报告重复节点，或生成合并后的本地配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupStrategy {
+    Report,
+    Merge,
+}
+
+/// 重复节点所在位置
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateNode {
+    pub profile_uid: String,
+    pub profile_name: String,
+    pub name: String,
+}
+
+/// 一组指纹相同的节点，第一个为保留的节点
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub endpoint: String, // type server:port，不包含凭据
+    pub nodes: Vec<DuplicateNode>,
+}
+
+/// 单个订阅的重复统计
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderDuplicates {
+    pub uid: String,
+    pub name: String,
+    pub total: usize,
+    pub duplicates: usize, // 与其他节点（含本订阅内）重复而被去除的数量
+}
+
+/// 去重结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupReport {
+    pub total_nodes: usize,
+    pub unique_nodes: usize,
+    pub groups: Vec<DuplicateGroup>,
+    pub providers: Vec<ProviderDuplicates>,
+    pub merged_uid: Option<String>,
+}
+
+/// 去重生成的配置记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DedupProfileState {
+    uid: Option<String>,
+}
+
+struct ScannedNode {
+    profile: usize,
+    name: String,
+    proxy: Mapping,
+}
+
+/// 扫描所有订阅中的节点，按 server:port/凭据 指纹检测重复
+#[tauri::command]
+pub async fn deduplicate_nodes(strategy: DedupStrategy) -> CmdResult<DedupReport> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[节点去重] 开始扫描，策略: {:?}",
+        strategy
+    );
+    dedup(strategy)
+        .await
+        .context("节点去重失败")
+        .map_err(CmdError::from)
+}
+
+async fn dedup(strategy: DedupStrategy) -> Result<DedupReport> {
+    let mut state = load_state()?;
+    let (profiles, nodes) = scan_nodes(state.uid.as_deref()).await?;
+
+    let mut by_fingerprint: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut order = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let Some(fingerprint) = fingerprint(&node.proxy) else {
+            continue;
+        };
+        let entry = by_fingerprint.entry(fingerprint.clone()).or_default();
+        if entry.is_empty() {
+            order.push(fingerprint);
+        }
+        entry.push(index);
+    }
+
+    let mut providers: Vec<ProviderDuplicates> = profiles
+        .iter()
+        .map(|(uid, name)| ProviderDuplicates {
+            uid: uid.clone(),
+            name: name.clone(),
+            total: 0,
+            duplicates: 0,
+        })
+        .collect();
+    for node in &nodes {
+        providers[node.profile].total += 1;
+    }
+
+    let mut groups = Vec::new();
+    let mut unique = Vec::new();
+    for fingerprint in &order {
+        let indexes = &by_fingerprint[fingerprint];
+        unique.push(&nodes[indexes[0]]);
+        if indexes.len() < 2 {
+            continue;
+        }
+        for &index in &indexes[1..] {
+            providers[nodes[index].profile].duplicates += 1;
+        }
+        groups.push(DuplicateGroup {
+            endpoint: endpoint(&nodes[indexes[0]].proxy),
+            nodes: indexes
+                .iter()
+                .map(|&index| {
+                    let node = &nodes[index];
+                    let (uid, name) = &profiles[node.profile];
+                    DuplicateNode {
+                        profile_uid: uid.clone(),
+                        profile_name: name.clone(),
+                        name: node.name.clone(),
+                    }
+                })
+                .collect(),
+        });
+    }
+
+    let merged_uid = match strategy {
+        DedupStrategy::Report => None,
+        DedupStrategy::Merge => {
+            let yaml = build_merged_config(&unique, &profiles)?;
+            let uid = save_merged_profile(state.uid.as_deref(), yaml).await?;
+            state.uid = Some(uid.clone());
+            save_state(&state)?;
+            Some(uid)
+        }
+    };
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[节点去重] 共 {} 个节点，去重后 {} 个，重复 {} 组",
+        nodes.len(),
+        unique.len(),
+        groups.len()
+    );
+    Ok(DedupReport {
+        total_nodes: nodes.len(),
+        unique_nodes: unique.len(),
+        groups,
+        providers,
+        merged_uid,
+    })
+}
+
+/// 读取所有订阅的节点，跳过去重配置本身和组合订阅
+async fn scan_nodes(merged_uid: Option<&str>) -> Result<(Vec<(String, String)>, Vec<ScannedNode>)> {
+    let skip: HashSet<String> = composite_profile_uids()
+        .into_iter()
+        .chain(merged_uid.map(str::to_string))
+        .collect();
+
+    let mut profiles = Vec::new();
+    let mut nodes = Vec::new();
+    let config = Config::profiles().await;
+    let config = config.latest_ref();
+    for item in config.items.iter().flatten() {
+        if !matches!(item.itype.as_deref(), Some("remote" | "local")) {
+            continue;
+        }
+        let Some(uid) = item.uid.clone() else {
+            continue;
+        };
+        if skip.contains(&uid) {
+            continue;
+        }
+        let Ok(content) = item.read_file() else {
+            continue;
+        };
+        let Ok(mapping) = serde_yaml_ng::from_str::<Mapping>(&content) else {
+            continue;
+        };
+
+        let profile = profiles.len();
+        profiles.push((uid.clone(), item.name.clone().unwrap_or(uid)));
+        for proxy in mapping
+            .get("proxies")
+            .and_then(Value::as_sequence)
+            .into_iter()
+            .flatten()
+        {
+            let Some(proxy) = proxy.as_mapping() else {
+                continue;
+            };
+            let Some(name) = proxy.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            nodes.push(ScannedNode {
+                profile,
+                name: name.to_string(),
+                proxy: proxy.clone(),
+            });
+        }
+    }
+    Ok((profiles, nodes))
+}
+
+/// 节点指纹：类型、服务器、端口和凭据相同即视为同一节点
+fn fingerprint(proxy: &Mapping) -> Option<String> {
+    let server = proxy.get("server")?.as_str()?.trim().to_lowercase();
+    let port = match proxy.get("port")? {
+        Value::Number(port) => port.to_string(),
+        Value::String(port) => port.trim().to_string(),
+        _ => return None,
+    };
+    let kind = proxy
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let credential = [
+        "uuid",
+        "password",
+        "auth-str",
+        "psk",
+        "private-key",
+        "username",
+    ]
+    .iter()
+    .find_map(|key| proxy.get(*key).and_then(Value::as_str))
+    .unwrap_or_default();
+    Some(format!("{kind}://{credential}@{server}:{port}"))
+}
+
+fn endpoint(proxy: &Mapping) -> String {
+    let field = |key: &str| match proxy.get(key) {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Number(value)) => value.to_string(),
+        _ => String::new(),
+    };
+    format!("{} {}:{}", field("type"), field("server"), field("port"))
+}
+
+/// 生成去重后的配置，重名节点添加来源前缀
+fn build_merged_config(unique: &[&ScannedNode], profiles: &[(String, String)]) -> Result<String> {
+    let mut used = HashSet::new();
+    let mut proxies = Vec::new();
+    let mut names = Vec::new();
+    for node in unique {
+        let mut name = node.name.clone();
+        if used.contains(&name) {
+            name = format!("[{}] {}", profiles[node.profile].1, node.name);
+        }
+        let base = name.clone();
+        let mut suffix = 2;
+        while used.contains(&name) {
+            name = format!("{base} #{suffix}");
+            suffix += 1;
+        }
+        used.insert(name.clone());
+
+        let mut proxy = node.proxy.clone();
+        proxy.insert("name".into(), name.clone().into());
+        proxies.push(Value::Mapping(proxy));
+        names.push(Value::from(name));
+    }
+
+    let auto_name = "自动选择";
+    let mut select_proxies = vec![Value::from(auto_name)];
+    select_proxies.extend(names.iter().cloned());
+
+    let mut select = Mapping::new();
+    select.insert("name".into(), DEDUP_PROFILE_NAME.into());
+    select.insert("type".into(), "select".into());
+    select.insert("proxies".into(), select_proxies.into());
+
+    let mut auto = Mapping::new();
+    auto.insert("name".into(), auto_name.into());
+    auto.insert("type".into(), "url-test".into());
+    auto.insert("url".into(), "http://www.gstatic.com/generate_204".into());
+    auto.insert("interval".into(), 300.into());
+    auto.insert("proxies".into(), names.into());
+
+    let mut merged = Mapping::new();
+    merged.insert("proxies".into(), proxies.into());
+    merged.insert(
+        "proxy-groups".into(),
+        vec![Value::Mapping(select), Value::Mapping(auto)].into(),
+    );
+    merged.insert(
+        "rules".into(),
+        vec![Value::from(format!("MATCH,{DEDUP_PROFILE_NAME}"))].into(),
+    );
+    Ok(serde_yaml_ng::to_string(&merged)?)
+}
+
+/// 写入去重配置：已存在时覆盖文件，否则新建本地配置
+async fn save_merged_profile(existing: Option<&str>, yaml: String) -> Result<String> {
+    let existing = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        existing.and_then(|uid| {
+            let uid = uid.to_string();
+            profiles.get_item(&uid).ok().map(|item| {
+                (
+                    item.clone(),
+                    profiles.get_current().as_deref() == Some(uid.as_str()),
+                )
+            })
+        })
+    };
+
+    if let Some((item, is_current)) = existing {
+        let uid = item.uid.clone().unwrap_or_default();
+        item.save_file(yaml)?;
+        if is_current {
+            CoreManager::global().update_config().await?;
+            handle::Handle::refresh_clash();
+        }
+        handle::Handle::notify_profile_changed(uid.clone());
+        return Ok(uid);
+    }
+
+    let item = PrfItem::from_local(
+        DEDUP_PROFILE_NAME.to_string(),
+        "由所有订阅的节点去重合并生成".to_string(),
+        Some(yaml),
+        None,
+    )
+    .await?;
+    let uid = item
+        .uid
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("去重配置缺少 uid"))?;
+    profiles_append_item_safe(item).await?;
+    Ok(uid)
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(DEDUP_PROFILE_FILE))
+}
+
+fn load_state() -> Result<DedupProfileState> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(DedupProfileState::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_state(state: &DedupProfileState) -> Result<()> {
+    fs::write(state_path()?, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
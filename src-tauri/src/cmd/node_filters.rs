@@ -0,0 +1,238 @@
+use super::CmdResult;
+use crate::{
+    cmd::selection_memory::detect_region,
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+const NODE_FILTERS_FILE: &str = "node_filters.json";
+
+/// 节点名称中的倍率标记，例如 x2、2x、×0.5、2倍
+static MULTIPLIER_RE: Lazy<Option<Regex>> =
+    Lazy::new(|| Regex::new(r"(?i)(?:[x×]\s*(\d+(?:\.\d+)?)|(\d+(?:\.\d+)?)\s*(?:[x×]|倍))").ok());
+
+/// 订阅节点过滤条件，各条件同时生效
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NodeFilters {
+    pub exclude_keywords: Vec<String>, // 名称包含任一关键词时排除，如 "过期"、"官网"
+    pub include_keywords: Vec<String>, // 非空时仅保留名称包含任一关键词的节点
+    pub exclude_types: Vec<String>,    // 排除的协议类型，如 "ss"、"vmess"
+    pub include_regions: Vec<String>,  // 非空时仅保留这些地区，如 "HK"、"JP"
+    pub exclude_regions: Vec<String>,
+    pub min_multiplier: Option<f64>, // 排除倍率低于该值的节点，无倍率标记视为 1
+}
+
+impl NodeFilters {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 返回节点被过滤的原因，保留时返回 None
+    fn reject_reason(&self, proxy: &Mapping) -> Option<&'static str> {
+        let name = proxy.get("name").and_then(Value::as_str).unwrap_or("");
+        let lower = name.to_lowercase();
+        let contains = |keywords: &[String]| {
+            keywords
+                .iter()
+                .filter(|kw| !kw.trim().is_empty())
+                .any(|kw| lower.contains(&kw.trim().to_lowercase()))
+        };
+
+        if contains(&self.exclude_keywords) {
+            return Some("keyword");
+        }
+        if !self.include_keywords.is_empty() && !contains(&self.include_keywords) {
+            return Some("keyword");
+        }
+
+        let kind = proxy.get("type").and_then(Value::as_str).unwrap_or("");
+        if self
+            .exclude_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(kind))
+        {
+            return Some("type");
+        }
+
+        let region = detect_region(name);
+        let in_regions = |regions: &[String]| {
+            region.is_some_and(|code| regions.iter().any(|r| r.eq_ignore_ascii_case(code)))
+        };
+        if in_regions(&self.exclude_regions)
+            || (!self.include_regions.is_empty() && !in_regions(&self.include_regions))
+        {
+            return Some("region");
+        }
+
+        if let Some(min) = self.min_multiplier
+            && multiplier(name) < min
+        {
+            return Some("multiplier");
+        }
+        None
+    }
+}
+
+/// 获取订阅的节点过滤条件
+#[tauri::command]
+pub async fn get_profile_node_filters(uid: String) -> CmdResult<NodeFilters> {
+    let filters = load_filters().context("加载节点过滤条件失败")?;
+    Ok(filters.get(&uid).cloned().unwrap_or_default())
+}
+
+/// 设置订阅的节点过滤条件，为当前订阅时重新生成配置
+#[tauri::command]
+pub async fn set_profile_node_filters(uid: String, filters: NodeFilters) -> CmdResult<()> {
+    let mut all = load_filters().context("加载节点过滤条件失败")?;
+    if filters.is_empty() {
+        all.remove(&uid);
+    } else {
+        all.insert(uid.clone(), filters);
+    }
+    save_filters(&all).context("保存节点过滤条件失败")?;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[节点过滤] 已更新订阅 {} 的过滤条件",
+        uid
+    );
+
+    let is_current = Config::profiles().await.latest_ref().get_current() == Some(uid);
+    if !is_current {
+        return Ok(());
+    }
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => Err(format!("过滤后的配置未通过内核验证: {}", error).into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 增强链中过滤当前订阅的节点，同步移除代理组成员和指向被过滤节点的规则，
+/// 返回过滤后的配置和链路日志
+pub fn use_node_filters(mut config: Mapping, uid: &str) -> (Mapping, Vec<(String, String)>) {
+    let Some(filters) = load_filters().ok().and_then(|mut all| all.remove(uid)) else {
+        return (config, Vec::new());
+    };
+
+    let mut removed = HashSet::new();
+    let mut reasons: HashMap<&'static str, usize> = HashMap::new();
+    let mut total = 0;
+    if let Some(Value::Sequence(proxies)) = config.get_mut("proxies") {
+        total = proxies.len();
+        proxies.retain(|proxy| {
+            let Some(proxy) = proxy.as_mapping() else {
+                return true;
+            };
+            match filters.reject_reason(proxy) {
+                Some(reason) => {
+                    *reasons.entry(reason).or_default() += 1;
+                    if let Some(name) = proxy.get("name").and_then(Value::as_str) {
+                        removed.insert(name.to_string());
+                    }
+                    false
+                }
+                None => true,
+            }
+        });
+    }
+    if removed.is_empty() {
+        return (
+            config,
+            vec![(
+                "info".into(),
+                format!("节点过滤: 共 {total} 个节点，未过滤"),
+            )],
+        );
+    }
+
+    if let Some(Value::Sequence(groups)) = config.get_mut("proxy-groups") {
+        for group in groups.iter_mut().filter_map(Value::as_mapping_mut) {
+            let has_source = group.contains_key("use") || group.contains_key("include-all");
+            if let Some(Value::Sequence(members)) = group.get_mut("proxies") {
+                members.retain(|m| m.as_str().is_none_or(|name| !removed.contains(name)));
+                // 代理组不能为空
+                if members.is_empty() && !has_source {
+                    members.push("DIRECT".into());
+                }
+            }
+        }
+    }
+    if let Some(Value::Sequence(rules)) = config.get_mut("rules") {
+        rules.retain(|rule| {
+            rule.as_str()
+                .and_then(rule_target)
+                .is_none_or(|target| !removed.contains(target))
+        });
+    }
+
+    let mut detail: Vec<String> = reasons
+        .iter()
+        .map(|(reason, count)| format!("{reason}: {count}"))
+        .collect();
+    detail.sort();
+    let message = format!(
+        "节点过滤: 共 {} 个节点，过滤 {} 个，保留 {} 个（{}）",
+        total,
+        removed.len(),
+        total - removed.len(),
+        detail.join(", ")
+    );
+    logging!(info, Type::Config, true, "[节点过滤] {}", message);
+    (config, vec![("info".into(), message)])
+}
+
+// ===== 内部实现函数 =====
+
+/// 从名称解析倍率，无倍率标记时为 1
+fn multiplier(name: &str) -> f64 {
+    MULTIPLIER_RE
+        .as_ref()
+        .and_then(|re| re.captures(name))
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .and_then(|m| m.as_str().parse().ok())
+        .unwrap_or(1.0)
+}
+
+/// 规则的策略字段，MATCH 规则为第二个字段
+fn rule_target(rule: &str) -> Option<&str> {
+    let mut parts = rule.split(',').map(str::trim);
+    match parts.next()? {
+        "MATCH" => parts.next(),
+        _ => parts.nth(1),
+    }
+}
+
+fn filters_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(NODE_FILTERS_FILE))
+}
+
+fn load_filters() -> Result<HashMap<String, NodeFilters>> {
+    let path = filters_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_filters(filters: &HashMap<String, NodeFilters>) -> Result<()> {
+    fs::write(filters_path()?, serde_json::to_string_pretty(filters)?)?;
+    Ok(())
+}
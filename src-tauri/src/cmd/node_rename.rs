@@ -0,0 +1,318 @@
+use super::{CmdError, CmdResult, ErrorCode, WithErrorCode};
+use crate::{
+    cmd::{
+        composite_profile::{rename_refs, rename_rule},
+        latest_speed_test_summary,
+        selection_memory::detect_region,
+    },
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+const NODE_RENAME_FILE: &str = "node_rename_rules.json";
+
+/// 延迟分级阈值（毫秒）
+const LATENCY_TIERS: &[(u64, &str)] = &[(150, "低延迟"), (400, "中延迟"), (u64::MAX, "高延迟")];
+
+/// 节点重命名规则：名称匹配 pattern 时按 template 替换
+///
+/// template 支持正则捕获组（$1、${name}）以及占位符：
+/// `{flag}` 地区旗帜（名称中已有旗帜时为空）、`{region}` 地区代码、
+/// `{tier}` 最近一次测速的延迟分级
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NodeRenameRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub template: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 重命名预览
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeRenamePreview {
+    pub original: String,
+    pub renamed: String,
+}
+
+struct CompiledRule {
+    regex: Regex,
+    template: String,
+}
+
+/// 获取节点重命名规则（按应用顺序）
+#[tauri::command]
+pub async fn get_node_rename_rules() -> CmdResult<Vec<NodeRenameRule>> {
+    load_rules()
+        .context("加载重命名规则失败")
+        .map_err(CmdError::from)
+}
+
+/// 保存节点重命名规则并重新生成配置
+#[tauri::command]
+pub async fn set_node_rename_rules(rules: Vec<NodeRenameRule>) -> CmdResult<()> {
+    compile(&rules)?;
+    save_rules(&rules).context("保存重命名规则失败")?;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[节点重命名] 已更新，共 {} 条规则",
+        rules.len()
+    );
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => Err(format!("重命名后的配置未通过内核验证: {}", error).into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 预览重命名规则对指定订阅节点的效果，只返回名称有变化的节点
+#[tauri::command]
+pub async fn preview_node_rename(
+    uid: String,
+    rules: Option<Vec<NodeRenameRule>>,
+) -> CmdResult<Vec<NodeRenamePreview>> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => load_rules().context("加载重命名规则失败")?,
+    };
+    let compiled = compile(&rules)?;
+
+    let content = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        let item = profiles
+            .get_item(&uid)
+            .with_code(ErrorCode::ProfileNotFound)?;
+        item.read_file()?
+    };
+    let config: Mapping = serde_yaml_ng::from_str(&content)?;
+
+    let renamed = rename_map(&proxy_list(&config), &compiled, &latency_index());
+    Ok(proxy_list(&config)
+        .into_iter()
+        .filter_map(|(name, _)| {
+            renamed.get(&name).map(|new_name| NodeRenamePreview {
+                original: name,
+                renamed: new_name.clone(),
+            })
+        })
+        .collect())
+}
+
+/// 增强链中应用重命名规则，同步更新代理组成员、规则策略和 dialer-proxy 引用
+pub fn use_node_rename(mut config: Mapping) -> Mapping {
+    let Ok(rules) = load_rules() else {
+        return config;
+    };
+    let Ok(compiled) = compile(&rules) else {
+        return config;
+    };
+    if compiled.is_empty() {
+        return config;
+    }
+
+    let renamed = rename_map(&proxy_list(&config), &compiled, &latency_index());
+    if renamed.is_empty() {
+        return config;
+    }
+
+    if let Some(Value::Sequence(proxies)) = config.get_mut("proxies") {
+        for proxy in proxies.iter_mut().filter_map(Value::as_mapping_mut) {
+            for key in ["name", "dialer-proxy"] {
+                if let Some(value) = proxy.get_mut(key) {
+                    rename_refs(value, &renamed);
+                }
+            }
+        }
+    }
+    if let Some(Value::Sequence(groups)) = config.get_mut("proxy-groups") {
+        for group in groups.iter_mut().filter_map(Value::as_mapping_mut) {
+            if let Some(value) = group.get_mut("proxies") {
+                rename_refs(value, &renamed);
+            }
+        }
+    }
+    if let Some(Value::Sequence(rules)) = config.get_mut("rules") {
+        for rule in rules.iter_mut() {
+            if let Value::String(rule) = rule {
+                *rule = rename_rule(rule, &renamed);
+            }
+        }
+    }
+
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[节点重命名] 已重命名 {} 个节点",
+        renamed.len()
+    );
+    config
+}
+
+// ===== 内部实现函数 =====
+
+fn compile(rules: &[NodeRenameRule]) -> CmdResult<Vec<CompiledRule>> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| {
+            Regex::new(&rule.pattern)
+                .map(|regex| CompiledRule {
+                    regex,
+                    template: rule.template.clone(),
+                })
+                .map_err(|e| {
+                    CmdError::new(
+                        ErrorCode::InvalidArgument,
+                        format!("无效的正则表达式 {}: {}", rule.pattern, e),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// 节点名称和 server:port
+fn proxy_list(config: &Mapping) -> Vec<(String, String)> {
+    config
+        .get("proxies")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|proxy| {
+            let name = proxy.get("name")?.as_str()?.to_string();
+            let server = proxy.get("server").and_then(Value::as_str).unwrap_or("");
+            let port = proxy.get("port").and_then(Value::as_u64).unwrap_or(0);
+            Some((name, format!("{server}:{port}")))
+        })
+        .collect()
+}
+
+/// 最近一次全局测速的延迟，按 server:port 索引，不受重命名影响
+fn latency_index() -> HashMap<String, u64> {
+    latest_speed_test_summary()
+        .map(|summary| {
+            summary
+                .all_results
+                .into_iter()
+                .filter(|result| result.is_available)
+                .filter_map(|result| {
+                    let latency = result.latency?;
+                    Some((format!("{}:{}", result.server, result.port), latency))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 计算重命名结果（原名称 -> 新名称），新名称冲突时追加序号
+fn rename_map(
+    proxies: &[(String, String)],
+    rules: &[CompiledRule],
+    latencies: &HashMap<String, u64>,
+) -> HashMap<String, String> {
+    let mut used: HashSet<String> = HashSet::new();
+    let mut renamed = HashMap::new();
+    for (name, endpoint) in proxies {
+        let mut new_name = name.clone();
+        for rule in rules {
+            if !rule.regex.is_match(&new_name) {
+                continue;
+            }
+            let template = expand_placeholders(&rule.template, &new_name, latencies.get(endpoint));
+            new_name = rule
+                .regex
+                .replace_all(&new_name, template.as_str())
+                .trim()
+                .to_string();
+        }
+        if new_name.is_empty() {
+            new_name = name.clone();
+        }
+
+        let base = new_name.clone();
+        let mut suffix = 2;
+        while used.contains(&new_name) {
+            new_name = format!("{base} #{suffix}");
+            suffix += 1;
+        }
+        used.insert(new_name.clone());
+        if &new_name != name {
+            renamed.insert(name.clone(), new_name);
+        }
+    }
+    renamed
+}
+
+fn expand_placeholders(template: &str, name: &str, latency: Option<&u64>) -> String {
+    let region = detect_region(name);
+    let flag = match region {
+        Some(code) if !has_flag(name) => region_flag(code),
+        _ => String::new(),
+    };
+    let tier = latency
+        .and_then(|latency| {
+            LATENCY_TIERS
+                .iter()
+                .find(|(limit, _)| latency < limit)
+                .map(|(_, tier)| *tier)
+        })
+        .unwrap_or_default();
+
+    template
+        .replace("{flag}", &flag)
+        .replace("{region}", region.unwrap_or_default())
+        .replace("{tier}", tier)
+}
+
+/// 名称中是否已包含地区旗帜（区域指示符）
+fn has_flag(name: &str) -> bool {
+    name.chars()
+        .any(|c| ('\u{1F1E6}'..='\u{1F1FF}').contains(&c))
+}
+
+/// 地区代码转换为旗帜 emoji
+fn region_flag(code: &str) -> String {
+    let code = if code == "UK" { "GB" } else { code };
+    code.chars()
+        .filter_map(|c| char::from_u32(0x1F1E6 + (c as u32).checked_sub('A' as u32)?))
+        .collect()
+}
+
+fn rules_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(NODE_RENAME_FILE))
+}
+
+fn load_rules() -> Result<Vec<NodeRenameRule>> {
+    let path = rules_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_rules(rules: &[NodeRenameRule]) -> Result<()> {
+    fs::write(rules_path()?, serde_json::to_string_pretty(rules)?)?;
+    Ok(())
+}
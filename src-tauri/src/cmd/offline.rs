@@ -0,0 +1,15 @@
+use super::CmdResult;
+use crate::module::offline::{OfflineMode, OfflineStatus};
+
+/// 获取离线模式状态，供前端提示当前是否在使用旧配置
+#[tauri::command]
+pub async fn get_offline_status() -> CmdResult<OfflineStatus> {
+    Ok(OfflineMode::global().status())
+}
+
+/// 手动退出离线模式，恢复订阅自动更新
+#[tauri::command]
+pub async fn exit_offline_mode() -> CmdResult {
+    OfflineMode::global().exit().await;
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use super::CmdResult;
+use crate::{
+    config::{Config, DEFAULT_PAC, IVerge},
+    core::sysopt,
+    feat, logging,
+    utils::logging::Type,
+};
+use serde::{Deserialize, Serialize};
+
+/// 校验时使用的示例地址
+const SAMPLE_URLS: &[(&str, &str)] = &[
+    ("https://www.google.com/", "www.google.com"),
+    ("http://example.com/index.html", "example.com"),
+    ("https://www.baidu.com/", "www.baidu.com"),
+    ("http://localhost:8080/", "localhost"),
+    ("http://192.168.1.1/", "192.168.1.1"),
+];
+
+/// 浏览器提供给 PAC 脚本的辅助函数，DNS 相关函数不做真实解析
+const PAC_HELPERS: &str = r#"
+function isPlainHostName(host) { return host.indexOf(".") < 0; }
+function dnsDomainIs(host, domain) {
+  return host.length >= domain.length && host.substring(host.length - domain.length) === domain;
+}
+function localHostOrDomainIs(host, hostdom) {
+  return host === hostdom || hostdom.lastIndexOf(host + ".", 0) === 0;
+}
+function isResolvable(host) { return true; }
+function isResolvableEx(host) { return true; }
+function dnsResolve(host) { return /^\d+\.\d+\.\d+\.\d+$/.test(host) ? host : null; }
+function dnsResolveEx(host) { return dnsResolve(host) || ""; }
+function myIpAddress() { return "127.0.0.1"; }
+function myIpAddressEx() { return "127.0.0.1"; }
+function dnsDomainLevels(host) { return host.split(".").length - 1; }
+function convert_addr(ip) {
+  var p = ip.split(".");
+  return ((p[0] << 24) | (p[1] << 16) | (p[2] << 8) | p[3]) >>> 0;
+}
+function isInNet(host, pattern, mask) {
+  var ip = dnsResolve(host);
+  if (!ip) return false;
+  return (convert_addr(ip) & convert_addr(mask)) === (convert_addr(pattern) & convert_addr(mask));
+}
+function shExpMatch(str, shexp) {
+  var re = shexp.replace(/[.+^${}()|[\]\\]/g, "\\$&").replace(/\*/g, ".*").replace(/\?/g, ".");
+  return new RegExp("^" + re + "$").test(str);
+}
+function weekdayRange() { return true; }
+function dateRange() { return true; }
+function timeRange() { return true; }
+"#;
+
+/// 单个示例地址的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PacSampleResult {
+    pub url: String,
+    pub host: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+/// PAC 脚本校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PacValidation {
+    pub valid: bool,
+    pub error: Option<String>,
+    pub samples: Vec<PacSampleResult>,
+}
+
+/// 获取 PAC 脚本模板
+#[tauri::command]
+pub async fn get_pac_script() -> CmdResult<String> {
+    Ok(Config::verge()
+        .await
+        .latest_ref()
+        .pac_file_content
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PAC.to_string()))
+}
+
+/// 校验通过后保存 PAC 脚本并刷新系统代理
+#[tauri::command]
+pub async fn set_pac_script(content: String) -> CmdResult<PacValidation> {
+    let validation = validate_pac_script(content.clone()).await?;
+    if !validation.valid {
+        return Ok(validation);
+    }
+
+    let patch = IVerge {
+        pac_file_content: Some(content),
+        ..IVerge::default()
+    };
+    feat::patch_verge(patch, false).await?;
+    logging!(info, Type::Config, true, "[PAC] 已保存 PAC 脚本");
+    Ok(validation)
+}
+
+/// 渲染模板变量后执行 FindProxyForURL，检查示例地址的返回值
+#[tauri::command]
+pub async fn validate_pac_script(content: String) -> CmdResult<PacValidation> {
+    let script = render_pac_template(&content).await;
+    tokio::task::spawn_blocking(move || evaluate_pac(&script))
+        .await
+        .map_err(|e| e.to_string().into())
+}
+
+/// 生成内置服务器提供的 PAC 内容
+pub(crate) async fn render_pac_script() -> String {
+    let content = Config::verge()
+        .await
+        .latest_ref()
+        .pac_file_content
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PAC.to_string());
+    render_pac_template(&content).await
+}
+
+// ===== 内部实现函数 =====
+
+/// 替换模板变量：%mixed-port%、%socks-port%、%http-port%、%proxy-host%、%bypass%
+async fn render_pac_template(content: &str) -> String {
+    let clash = Config::clash().await;
+    let verge = Config::verge().await;
+    let (mixed_port, socks_port, http_port) = {
+        let clash = clash.latest_ref();
+        let verge = verge.latest_ref();
+        (
+            verge.verge_mixed_port.unwrap_or(clash.get_mixed_port()),
+            verge.verge_socks_port.unwrap_or(clash.get_socks_port()),
+            verge.verge_port.unwrap_or(clash.get_port()),
+        )
+    };
+
+    // 系统代理排除列表转换为 JS 数组，供 shExpMatch 使用
+    let bypass: Vec<String> = sysopt::bypass_rules()
+        .await
+        .into_iter()
+        .filter(|rule| rule.kind != sysopt::BypassKind::Local)
+        .map(|rule| rule.value)
+        .collect();
+    let bypass = serde_json::to_string(&bypass).unwrap_or_else(|_| "[]".to_string());
+
+    content
+        .replace("%mixed-port%", &mixed_port.to_string())
+        .replace("%socks-port%", &socks_port.to_string())
+        .replace("%http-port%", &http_port.to_string())
+        .replace("%proxy-host%", "127.0.0.1")
+        .replace("%bypass%", &bypass)
+}
+
+fn evaluate_pac(script: &str) -> PacValidation {
+    use boa_engine::{Context, Source};
+
+    let invalid = |error: String| PacValidation {
+        valid: false,
+        error: Some(error),
+        samples: Vec::new(),
+    };
+
+    let mut context = Context::default();
+    // 防止脚本死循环阻塞校验
+    context
+        .runtime_limits_mut()
+        .set_loop_iteration_limit(1_000_000);
+    context.runtime_limits_mut().set_recursion_limit(512);
+
+    if context.eval(Source::from_bytes(PAC_HELPERS)).is_err() {
+        return invalid("无法加载 PAC 辅助函数".to_string());
+    }
+    if let Err(e) = context.eval(Source::from_bytes(script)) {
+        return invalid(format!("PAC 脚本语法错误: {}", e));
+    }
+
+    let mut samples = Vec::new();
+    for (url, host) in SAMPLE_URLS {
+        let code = format!(
+            r#"typeof FindProxyForURL === "function" ? String(FindProxyForURL({}, {})) : "__missing__""#,
+            serde_json::to_string(url).unwrap_or_default(),
+            serde_json::to_string(host).unwrap_or_default()
+        );
+        let outcome = context
+            .eval(Source::from_bytes(code.as_str()))
+            .map_err(|e| e.to_string())
+            .and_then(|value| {
+                value
+                    .to_string(&mut context)
+                    .map_err(|e| e.to_string())?
+                    .to_std_string()
+                    .map_err(|_| "返回值不是有效的字符串".to_string())
+            });
+
+        let (result, error) = match outcome {
+            Ok(result) if result == "__missing__" => {
+                return invalid("未定义 FindProxyForURL 函数".to_string());
+            }
+            Ok(result) if is_valid_pac_result(&result) => (Some(result), None),
+            Ok(result) => (
+                Some(result.clone()),
+                Some(format!("无效的返回值: {}", result)),
+            ),
+            Err(e) => (None, Some(e)),
+        };
+        samples.push(PacSampleResult {
+            url: url.to_string(),
+            host: host.to_string(),
+            result,
+            error,
+        });
+    }
+
+    let error = samples.iter().find_map(|s| s.error.clone());
+    PacValidation {
+        valid: error.is_none(),
+        error,
+        samples,
+    }
+}
+
+/// 检查返回值是否为 "PROXY host:port; SOCKS5 host:port; DIRECT" 格式
+fn is_valid_pac_result(result: &str) -> bool {
+    let entries: Vec<&str> = result
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    !entries.is_empty()
+        && entries.iter().all(|entry| {
+            if entry.eq_ignore_ascii_case("DIRECT") {
+                return true;
+            }
+            let Some((kind, address)) = entry.split_once(char::is_whitespace) else {
+                return false;
+            };
+            let kind = kind.to_ascii_uppercase();
+            ["PROXY", "HTTP", "HTTPS", "SOCKS", "SOCKS4", "SOCKS5"].contains(&kind.as_str())
+                && address
+                    .trim()
+                    .rsplit_once(':')
+                    .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+        })
+}
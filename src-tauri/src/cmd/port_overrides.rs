@@ -0,0 +1,421 @@
+use super::CmdResult;
+use crate::{
+    config::{Config, IVerge},
+    core::{CoreManager, handle, sysopt},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, bail};
+use once_cell::sync::Lazy;
+use port_scanner::local_port_available;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+use tokio::sync::Mutex;
+
+const PORT_OVERRIDES_FILE: &str = "port_overrides.json";
+/// 应用后等待内核监听端口的时间
+const BIND_CHECK_DELAY: Duration = Duration::from_millis(1500);
+
+static APPLY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 端口与外部控制器覆盖，未设置的字段沿用基础配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PortOverrides {
+    pub mixed_port: Option<u16>,
+    pub socks_port: Option<u16>,
+    pub http_port: Option<u16>,
+    pub external_controller: Option<String>,
+    pub secret: Option<String>,
+}
+
+impl PortOverrides {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 以 self 为准，未设置的字段使用 fallback
+    fn or(&self, fallback: &PortOverrides) -> PortOverrides {
+        PortOverrides {
+            mixed_port: self.mixed_port.or(fallback.mixed_port),
+            socks_port: self.socks_port.or(fallback.socks_port),
+            http_port: self.http_port.or(fallback.http_port),
+            external_controller: self
+                .external_controller
+                .clone()
+                .or_else(|| fallback.external_controller.clone()),
+            secret: self.secret.clone().or_else(|| fallback.secret.clone()),
+        }
+    }
+
+    /// 以 self 为原始值，previous 为上次写入的值；current 中与上次写入不同的字段
+    /// 是在覆盖之外修改的，作为新的原始值
+    fn rebase(&self, previous: &PortOverrides, current: &PortOverrides) -> PortOverrides {
+        fn pick<T: Clone + PartialEq>(base: &T, previous: &T, current: &T) -> T {
+            if previous == current {
+                base.clone()
+            } else {
+                current.clone()
+            }
+        }
+        PortOverrides {
+            mixed_port: pick(&self.mixed_port, &previous.mixed_port, &current.mixed_port),
+            socks_port: pick(&self.socks_port, &previous.socks_port, &current.socks_port),
+            http_port: pick(&self.http_port, &previous.http_port, &current.http_port),
+            external_controller: pick(
+                &self.external_controller,
+                &previous.external_controller,
+                &current.external_controller,
+            ),
+            secret: pick(&self.secret, &previous.secret, &current.secret),
+        }
+    }
+}
+
+/// 覆盖配置：全局覆盖及按订阅覆盖，订阅覆盖优先
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortOverridesConfig {
+    pub global: PortOverrides,
+    pub profiles: HashMap<String, PortOverrides>,
+    /// 应用覆盖前的原始值，取消全部覆盖时恢复
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline: Option<PortOverrides>,
+    /// 上次应用后实际生效的值，用于发现覆盖之外的修改
+    #[serde(skip_serializing_if = "Option::is_none")]
+    applied: Option<PortOverrides>,
+}
+
+/// 获取端口覆盖配置
+#[tauri::command]
+pub async fn get_port_overrides() -> CmdResult<PortOverridesConfig> {
+    let mut config = load_config().context("加载端口覆盖失败")?;
+    config.baseline = None;
+    config.applied = None;
+    Ok(config)
+}
+
+/// 设置端口覆盖，uid 为空时设置全局覆盖，overrides 为空时清除
+/// 影响当前订阅时立即应用，端口被占用时拒绝，内核未能监听时回滚
+#[tauri::command]
+pub async fn set_port_overrides(
+    uid: Option<String>,
+    overrides: Option<PortOverrides>,
+) -> CmdResult<()> {
+    let overrides = overrides.unwrap_or_default();
+    if [
+        overrides.mixed_port,
+        overrides.socks_port,
+        overrides.http_port,
+    ]
+    .contains(&Some(0))
+    {
+        return Err("端口无效".into());
+    }
+
+    let _guard = APPLY_LOCK.lock().await;
+    let mut config = load_config().context("加载端口覆盖失败")?;
+    let previous = config.clone();
+    match &uid {
+        Some(uid) if overrides.is_empty() => {
+            config.profiles.remove(uid);
+        }
+        Some(uid) => {
+            config.profiles.insert(uid.clone(), overrides);
+        }
+        None => config.global = overrides,
+    }
+    save_config(&config).context("保存端口覆盖失败")?;
+
+    let current = Config::profiles().await.latest_ref().get_current();
+    if uid.is_some() && uid != current {
+        return Ok(());
+    }
+    if let Err(e) = apply(&mut config, current.as_deref()).await {
+        save_config(&previous).context("恢复端口覆盖失败")?;
+        return Err(format!("应用端口覆盖失败: {e}").into());
+    }
+    Ok(())
+}
+
+/// 切换订阅后应用该订阅的端口覆盖
+pub async fn apply_current_port_overrides() {
+    let _guard = APPLY_LOCK.lock().await;
+    let Ok(mut config) = load_config() else {
+        return;
+    };
+    let current = Config::profiles().await.latest_ref().get_current();
+    if let Err(e) = apply(&mut config, current.as_deref()).await {
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[端口覆盖] 应用订阅 {:?} 的端口覆盖失败: {}",
+            current,
+            e
+        );
+    }
+}
+
+/// 内核启动时为避开占用而换用的端口写回端口覆盖：被当前订阅或全局覆盖指定的端口
+/// 更新对应覆盖，其余更新覆盖前的原始值，避免之后应用覆盖时换回冲突端口
+pub async fn adopt_remapped_ports(remapped: &PortOverrides) {
+    // 正在应用覆盖时内核由其重启，应用结束后会记录实际生效的端口
+    let Ok(_guard) = APPLY_LOCK.try_lock() else {
+        return;
+    };
+    let Ok(mut config) = load_config() else {
+        return;
+    };
+    if config.baseline.is_none() {
+        return;
+    }
+
+    fn adopt<T: Clone>(
+        value: &Option<T>,
+        profile: Option<&mut Option<T>>,
+        global: &mut Option<T>,
+        baseline: Option<&mut Option<T>>,
+    ) {
+        let Some(value) = value else {
+            return;
+        };
+        match profile.filter(|field| field.is_some()) {
+            Some(field) => *field = Some(value.clone()),
+            None if global.is_some() => *global = Some(value.clone()),
+            None => {
+                if let Some(field) = baseline {
+                    *field = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    let current = Config::profiles().await.latest_ref().get_current();
+    let mut profile = current
+        .as_deref()
+        .and_then(|uid| config.profiles.get_mut(uid));
+    let mut baseline = config.baseline.as_mut();
+    let global = &mut config.global;
+    adopt(
+        &remapped.mixed_port,
+        profile.as_mut().map(|p| &mut p.mixed_port),
+        &mut global.mixed_port,
+        baseline.as_mut().map(|b| &mut b.mixed_port),
+    );
+    adopt(
+        &remapped.socks_port,
+        profile.as_mut().map(|p| &mut p.socks_port),
+        &mut global.socks_port,
+        baseline.as_mut().map(|b| &mut b.socks_port),
+    );
+    adopt(
+        &remapped.http_port,
+        profile.as_mut().map(|p| &mut p.http_port),
+        &mut global.http_port,
+        baseline.as_mut().map(|b| &mut b.http_port),
+    );
+    adopt(
+        &remapped.external_controller,
+        profile.as_mut().map(|p| &mut p.external_controller),
+        &mut global.external_controller,
+        baseline.as_mut().map(|b| &mut b.external_controller),
+    );
+
+    config.applied = Some(active_values().await);
+    if let Err(e) = save_config(&config) {
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[端口覆盖] 记录重映射端口失败: {}",
+            e
+        );
+    }
+}
+
+// ===== 内部实现函数 =====
+
+/// 计算生效的覆盖值并写入 clash/verge 配置
+async fn apply(config: &mut PortOverridesConfig, current: Option<&str>) -> Result<()> {
+    let effective = current
+        .and_then(|uid| config.profiles.get(uid))
+        .cloned()
+        .unwrap_or_default()
+        .or(&config.global);
+
+    let active = active_values().await;
+    // 覆盖生效期间在其他地方修改过的字段，以修改后的值作为原始值
+    if let (Some(baseline), Some(applied)) = (&config.baseline, &config.applied)
+        && *applied != active
+    {
+        config.baseline = Some(baseline.rebase(applied, &active));
+    }
+
+    let target = match &config.baseline {
+        // 已应用过覆盖，未覆盖的字段恢复到原始值
+        Some(baseline) => effective.or(baseline),
+        None if effective.is_empty() => return Ok(()),
+        None => effective.or(&active),
+    };
+    if target == active {
+        if effective.is_empty() {
+            config.baseline = None;
+            config.applied = None;
+        } else {
+            config.applied = Some(active);
+        }
+        save_config(config)?;
+        return Ok(());
+    }
+
+    check_conflicts(&target, &active)?;
+    if config.baseline.is_none() {
+        config.baseline = Some(active.clone());
+    }
+    write_values(&target).await?;
+
+    // 内核未能监听新端口时回滚
+    tokio::time::sleep(BIND_CHECK_DELAY).await;
+    let applied = active_values().await;
+    if let Some(port) = applied.mixed_port
+        && applied.mixed_port != active.mixed_port
+        && local_port_available(port)
+    {
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[端口覆盖] 内核未监听端口 {}，回滚",
+            port
+        );
+        write_values(&active).await?;
+        bail!("内核未能监听端口 {}，已回滚", port);
+    }
+
+    if effective.is_empty() {
+        config.baseline = None;
+        config.applied = None;
+    } else {
+        config.applied = Some(applied);
+    }
+    save_config(config)?;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[端口覆盖] 已应用，mixed-port: {:?}",
+        target.mixed_port
+    );
+    Ok(())
+}
+
+/// 当前 clash 配置中的端口与控制器设置
+async fn active_values() -> PortOverrides {
+    let clash = Config::clash().await;
+    let clash = clash.latest_ref();
+    let info = clash.get_client_info();
+    let string = |key: &str| clash.0.get(key).and_then(Value::as_str).map(str::to_string);
+    PortOverrides {
+        mixed_port: Some(info.mixed_port),
+        socks_port: Some(info.socks_port),
+        http_port: Some(info.port),
+        external_controller: string("external-controller"),
+        secret: info.secret,
+    }
+}
+
+/// 检查需要变更的端口是否重复或已被占用
+fn check_conflicts(target: &PortOverrides, active: &PortOverrides) -> Result<()> {
+    let ports = [
+        ("mixed-port", target.mixed_port, active.mixed_port),
+        ("socks-port", target.socks_port, active.socks_port),
+        ("port", target.http_port, active.http_port),
+    ];
+
+    let mut seen = HashMap::new();
+    for (key, port, _) in ports {
+        if let Some(port) = port
+            && let Some(other) = seen.insert(port, key)
+        {
+            bail!("{} 与 {} 使用了相同端口 {}", key, other, port);
+        }
+    }
+
+    let occupied: Vec<String> = ports
+        .iter()
+        .filter(|(_, port, current)| port != current)
+        .filter_map(|(key, port, _)| {
+            let port = (*port)?;
+            (!local_port_available(port)).then(|| format!("{key}: {port}"))
+        })
+        .collect();
+    if !occupied.is_empty() {
+        bail!("端口已被占用: {}", occupied.join(", "));
+    }
+    Ok(())
+}
+
+/// 同时写入 clash 与 verge 中的端口后只重启一次内核，并让系统代理使用新端口
+async fn write_values(values: &PortOverrides) -> Result<()> {
+    let mut patch = Mapping::new();
+    let mut insert = |key: &str, value: Option<Value>| {
+        if let Some(value) = value {
+            patch.insert(key.into(), value);
+        }
+    };
+    insert("mixed-port", values.mixed_port.map(Value::from));
+    insert("socks-port", values.socks_port.map(Value::from));
+    insert("port", values.http_port.map(Value::from));
+    insert(
+        "external-controller",
+        values.external_controller.clone().map(Value::from),
+    );
+    insert("secret", values.secret.clone().map(Value::from));
+
+    Config::clash().await.draft_mut().patch_config(patch);
+    Config::verge().await.draft_mut().patch_config(IVerge {
+        verge_mixed_port: values.mixed_port,
+        verge_socks_port: values.socks_port,
+        verge_port: values.http_port,
+        ..IVerge::default()
+    });
+
+    let reload = async {
+        Config::generate().await?;
+        CoreManager::global().restart_core().await
+    };
+    if let Err(e) = reload.await {
+        Config::clash().await.discard();
+        Config::verge().await.discard();
+        return Err(e);
+    }
+
+    Config::clash().await.apply();
+    Config::verge().await.apply();
+    let clash_data = Config::clash().await.data_mut().clone();
+    clash_data.save_config().await?;
+    let verge_data = Config::verge().await.data_mut().clone();
+    verge_data.save_file().await?;
+    handle::Handle::refresh_clash();
+    handle::Handle::refresh_verge();
+    sysopt::Sysopt::global().update_sysproxy().await
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(PORT_OVERRIDES_FILE))
+}
+
+fn load_config() -> Result<PortOverridesConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(PortOverridesConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_config(config: &PortOverridesConfig) -> Result<()> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
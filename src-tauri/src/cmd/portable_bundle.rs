@@ -0,0 +1,324 @@
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    logging,
+    process::AsyncHandler,
+    utils::{
+        dirs::{self, APP_ID},
+        logging::Type,
+    },
+};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// 与本机相关、不需要带到便携包中的数据
+const EXCLUDED_ENTRIES: &[&str] = &["logs", "backups", "service", "jobs.json"];
+
+/// 保存绝对路径的字段：启动脚本、内核注册表与 provider 的 path、external-ui
+const PATH_FIELDS: &[&str] = &["startup_script", "path", "external-ui"];
+
+/// 内核可执行文件名前缀
+const CORE_PREFIX: &str = "verge-mihomo";
+
+/// 便携包创建结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PortableBundleSummary {
+    pub target_dir: String,
+    pub files_copied: usize,
+    pub bytes_copied: u64,
+    pub rewritten_files: Vec<String>, // 重写了路径的配置文件，相对于数据目录
+    pub warnings: Vec<String>,
+}
+
+/// 创建便携包：复制程序、内核、资源和数据目录，写入便携标记并重写配置中的绝对路径
+/// 目标目录必须不存在或为空
+#[tauri::command]
+pub async fn create_portable_bundle(target_dir: String) -> CmdResult<PortableBundleSummary> {
+    let target_dir = PathBuf::from(target_dir.trim());
+    if target_dir.as_os_str().is_empty() {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            "目标目录不能为空",
+        ));
+    }
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[便携包] 开始创建: {}",
+        target_dir.display()
+    );
+    let summary = AsyncHandler::spawn_blocking(move || build_bundle(&target_dir))
+        .await
+        .context("创建便携包任务失败")??;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[便携包] 创建完成: {} 个文件, {} 字节",
+        summary.files_copied,
+        summary.bytes_copied
+    );
+    Ok(summary)
+}
+
+// ===== 内部实现函数 =====
+
+fn build_bundle(target_dir: &Path) -> CmdResult<PortableBundleSummary> {
+    let exe_path = dunce::canonicalize(tauri::utils::platform::current_exe()?)?;
+    let exe_dir = exe_path
+        .parent()
+        .context("无法获取程序所在目录")?
+        .to_path_buf();
+    let home_dir = dirs::app_home_dir()?;
+
+    if target_dir.exists() && fs::read_dir(target_dir)?.next().is_some() {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            format!("目标目录不为空: {}", target_dir.display()),
+        ));
+    }
+    fs::create_dir_all(target_dir)?;
+    let target_dir = dunce::canonicalize(target_dir)?;
+    // 避免把便携包复制进自身
+    if target_dir.starts_with(&home_dir) || target_dir.starts_with(&exe_dir) {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            "目标目录不能位于程序目录或数据目录内",
+        ));
+    }
+
+    let mut summary = PortableBundleSummary {
+        target_dir: target_dir.to_string_lossy().to_string(),
+        ..PortableBundleSummary::default()
+    };
+    if cfg!(target_os = "macos") {
+        summary
+            .warnings
+            .push("macOS 应用包不支持便携模式，便携包仅复制了可执行文件".to_string());
+    }
+
+    // 程序与内核
+    let exe_name = exe_path.file_name().context("无法获取程序文件名")?;
+    copy_file(&exe_path, &target_dir.join(exe_name), &mut summary)?;
+    for entry in fs::read_dir(&exe_dir)? {
+        let path = entry?.path();
+        let is_core = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with(CORE_PREFIX));
+        if is_core && path.is_file() {
+            copy_file(
+                &path,
+                &target_dir.join(path.file_name().unwrap_or_default()),
+                &mut summary,
+            )?;
+        }
+    }
+    let resources_dir = exe_dir.join("resources");
+    if resources_dir.is_dir() {
+        copy_dir(
+            &resources_dir,
+            &target_dir.join("resources"),
+            &[],
+            &mut summary,
+        )?;
+    }
+
+    // 数据目录与便携标记，与 init_portable_flag / app_home_dir 的约定保持一致
+    let config_dir = target_dir.join(".config");
+    let target_home = config_dir.join(APP_ID);
+    copy_dir(&home_dir, &target_home, EXCLUDED_ENTRIES, &mut summary)?;
+    fs::write(config_dir.join("PORTABLE"), "")?;
+
+    let replacements = [(home_dir, target_home.clone()), (exe_dir, target_dir)];
+    rewrite_paths(&target_home, &target_home, &replacements, &mut summary)?;
+    normalize_verge(
+        &target_home.join(dirs::VERGE_CONFIG),
+        &target_home,
+        &mut summary,
+    )?;
+
+    Ok(summary)
+}
+
+fn copy_file(from: &Path, to: &Path, summary: &mut PortableBundleSummary) -> Result<()> {
+    let bytes = fs::copy(from, to).with_context(|| format!("复制文件失败: {}", from.display()))?;
+    summary.files_copied += 1;
+    summary.bytes_copied += bytes;
+    Ok(())
+}
+
+fn copy_dir(
+    from: &Path,
+    to: &Path,
+    excluded: &[&str],
+    summary: &mut PortableBundleSummary,
+) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if excluded.iter().any(|excluded| name == *excluded) {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &to.join(&name), &[], summary)?;
+        } else {
+            copy_file(&path, &to.join(&name), summary)?;
+        }
+    }
+    Ok(())
+}
+
+/// 把配置文件中已知路径字段里指向原数据目录和程序目录的绝对路径替换为便携包中的路径
+fn rewrite_paths(
+    dir: &Path,
+    root: &Path,
+    replacements: &[(PathBuf, PathBuf)],
+    summary: &mut PortableBundleSummary,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            rewrite_paths(&path, root, replacements, summary)?;
+            continue;
+        }
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let rewritten = match extension.as_deref() {
+            Some("yaml" | "yml") => {
+                let Ok(mut value) = serde_yaml_ng::from_str::<Value>(&content) else {
+                    continue;
+                };
+                rewrite_yaml(&mut value, replacements)
+                    .then(|| serde_yaml_ng::to_string(&value))
+                    .transpose()?
+            }
+            Some("json") => {
+                let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&content) else {
+                    continue;
+                };
+                rewrite_json(&mut value, replacements)
+                    .then(|| serde_json::to_string_pretty(&value))
+                    .transpose()?
+            }
+            _ => None,
+        };
+        if let Some(rewritten) = rewritten {
+            fs::write(&path, rewritten)?;
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            summary
+                .rewritten_files
+                .push(relative.to_string_lossy().to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 递归处理 YAML，返回是否有字段被改写
+fn rewrite_yaml(value: &mut Value, replacements: &[(PathBuf, PathBuf)]) -> bool {
+    let mut changed = false;
+    match value {
+        Value::Mapping(map) => {
+            for (key, value) in map.iter_mut() {
+                let is_path_field = key.as_str().is_some_and(|key| PATH_FIELDS.contains(&key));
+                match value {
+                    Value::String(path) if is_path_field => {
+                        if let Some(new_path) = replace_path(path, replacements) {
+                            *path = new_path;
+                            changed = true;
+                        }
+                    }
+                    _ => changed |= rewrite_yaml(value, replacements),
+                }
+            }
+        }
+        Value::Sequence(seq) => {
+            for value in seq {
+                changed |= rewrite_yaml(value, replacements);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// 递归处理 JSON，返回是否有字段被改写
+fn rewrite_json(value: &mut serde_json::Value, replacements: &[(PathBuf, PathBuf)]) -> bool {
+    let mut changed = false;
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    serde_json::Value::String(path) if PATH_FIELDS.contains(&key.as_str()) => {
+                        if let Some(new_path) = replace_path(path, replacements) {
+                            *path = new_path;
+                            changed = true;
+                        }
+                    }
+                    _ => changed |= rewrite_json(value, replacements),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                changed |= rewrite_json(value, replacements);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// 按完整路径组件匹配前缀，避免 /data/app 误匹配 /data/app2
+fn replace_path(path: &str, replacements: &[(PathBuf, PathBuf)]) -> Option<String> {
+    let path = Path::new(path);
+    replacements.iter().find_map(|(from, to)| {
+        let rest = path.strip_prefix(from).ok()?;
+        let new_path = if rest.as_os_str().is_empty() {
+            to.clone()
+        } else {
+            to.join(rest)
+        };
+        Some(new_path.to_string_lossy().to_string())
+    })
+}
+
+/// 关闭依赖本机环境的设置：开机自启、TUN 模式（需要系统服务）以及数据目录外的启动脚本
+fn normalize_verge(path: &Path, home: &Path, summary: &mut PortableBundleSummary) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut verge: Mapping = serde_yaml_ng::from_str(&fs::read_to_string(path)?)?;
+
+    for key in ["enable_auto_launch", "enable_tun_mode"] {
+        if verge.get(key).and_then(Value::as_bool) == Some(true) {
+            verge.insert(key.into(), false.into());
+            summary.warnings.push(format!("便携包中已关闭 {key}"));
+        }
+    }
+    let outside_script = verge
+        .get("startup_script")
+        .and_then(Value::as_str)
+        .is_some_and(|script| !script.is_empty() && !Path::new(script).starts_with(home));
+    if outside_script {
+        verge.remove("startup_script");
+        summary
+            .warnings
+            .push("启动脚本位于数据目录之外，已从便携包中移除".to_string());
+    }
+
+    fs::write(path, serde_yaml_ng::to_string(&verge)?)?;
+    Ok(())
+}
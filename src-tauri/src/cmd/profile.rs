@@ -1,4 +1,4 @@
-use super::CmdResult;
+use super::{CmdError, CmdResult, ErrorCode, OperationKind, register_operation};
 use crate::{
     config::{
         Config, IProfiles, PrfItem, PrfOption,
@@ -8,7 +8,7 @@ use crate::{
         },
         profiles_append_item_safe,
     },
-    core::{CoreManager, handle, timer::Timer, tray::Tray},
+    core::{CoreManager, handle, sandbox, timer::Timer, tray::Tray},
     feat, logging,
     process::AsyncHandler,
     ret_err,
@@ -84,14 +84,15 @@ pub async fn get_profiles() -> CmdResult<IProfiles> {
     Ok(IProfiles::new().await)
 }
 
-/// 增强配置文件
+/// 增强配置文件，取消时保留原有的运行时配置
 #[tauri::command]
-pub async fn enhance_profiles() -> CmdResult {
-    match feat::enhance_profiles().await {
+pub async fn enhance_profiles(task_id: Option<String>) -> CmdResult {
+    let operation = register_operation(OperationKind::Enhance, task_id);
+    match feat::enhance_profiles(Some(&operation)).await {
         Ok(_) => {}
         Err(e) => {
             log::error!(target: "app", "{}", e);
-            return Err(e.to_string());
+            return Err(e.into());
         }
     }
     handle::Handle::refresh_clash();
@@ -143,6 +144,12 @@ pub async fn import_profile(url: String, option: Option<PrfOption>) -> CmdResult
 
         // 立即发送配置变更通知
         if let Some(uid) = &item.uid {
+            super::subscription_usage::record_subscription_usage(
+                uid,
+                item.name.as_deref().unwrap_or(uid),
+                item.extra,
+            )
+            .await;
             logging!(
                 info,
                 Type::Cmd,
@@ -182,11 +189,14 @@ pub async fn import_profile(url: String, option: Option<PrfOption>) -> CmdResult
         }
         Ok(Err(e)) => {
             logging!(error, Type::Cmd, true, "[导入订阅] 导入失败: {}", e);
-            Err(format!("导入订阅失败: {e}"))
+            Err(e.context("导入订阅失败").into())
         }
         Err(_) => {
             logging!(error, Type::Cmd, true, "[导入订阅] 导入超时(60秒): {}", url);
-            Err("导入订阅超时，请检查网络连接".into())
+            Err(CmdError::new(
+                ErrorCode::Timeout,
+                "导入订阅超时，请检查网络连接",
+            ))
         }
     }
 }
@@ -201,7 +211,7 @@ pub async fn reorder_profile(active_id: String, over_id: String) -> CmdResult {
         }
         Err(err) => {
             log::error!(target: "app", "重新排序配置文件失败: {}", err);
-            Err(format!("重新排序配置文件失败: {}", err))
+            Err(format!("重新排序配置文件失败: {}", err).into())
         }
     }
 }
@@ -214,7 +224,7 @@ pub async fn create_profile(item: PrfItem, file_data: Option<String>) -> CmdResu
         Ok(_) => Ok(()),
         Err(err) => match err.to_string().as_str() {
             "the file already exists" => Err("the file already exists".into()),
-            _ => Err(format!("add profile error: {err}")),
+            _ => Err(format!("add profile error: {err}").into()),
         },
     }
 }
@@ -226,7 +236,7 @@ pub async fn update_profile(index: String, option: Option<PrfOption>) -> CmdResu
         Ok(_) => Ok(()),
         Err(e) => {
             log::error!(target: "app", "{}", e);
-            Err(e.to_string())
+            Err(e.into())
         }
     }
 }
@@ -250,13 +260,13 @@ pub async fn delete_profile(index: String) -> CmdResult {
                 log::error!(target: "app", "删除订阅后配置验证失败: {}", error_msg);
                 handle::Handle::refresh_clash();
                 handle::Handle::notify_profile_changed("deleted".to_string());
-                return Err(format!("订阅已删除，但配置验证失败: {}", error_msg));
+                return Err(format!("订阅已删除，但配置验证失败: {}", error_msg).into());
             }
             Err(e) => {
                 log::error!(target: "app", "删除订阅后更新配置失败: {}", e);
                 handle::Handle::refresh_clash();
                 handle::Handle::notify_profile_changed("deleted".to_string());
-                return Err(format!("订阅已删除，但更新配置失败: {}", e));
+                return Err(format!("订阅已删除，但更新配置失败: {}", e).into());
             }
         }
     } else {
@@ -497,7 +507,16 @@ pub async fn patch_profiles_config(profiles: IProfiles) -> CmdResult<bool> {
             handle::Handle::refresh_clash();
 
             // 强制刷新代理缓存，确保profile切换后立即获取最新节点数据
+            let restore_uid = current_value.clone();
             crate::process::AsyncHandler::spawn(|| async move {
+                super::port_overrides::apply_current_port_overrides().await;
+                if let Err(e) = super::core_registry::apply_current_core_pin().await {
+                    logging!(warn, Type::Cmd, true, "切换订阅固定的内核失败: {}", e);
+                }
+                // 按选择记忆策略恢复分组节点
+                if let Some(uid) = restore_uid {
+                    super::selection_memory::restore_selections_after_reload(uid, false).await;
+                }
                 if let Err(e) = super::proxy::force_refresh_proxies().await {
                     log::warn!(target: "app", "强制刷新代理缓存失败: {e}");
                 }
@@ -710,3 +729,9 @@ pub async fn get_next_update_time(uid: String) -> CmdResult<Option<i64>> {
     let next_time = timer.get_next_update_time(&uid).await;
     Ok(next_time)
 }
+
+/// 用临时端口启动第二个内核加载订阅，验证订阅可用后再启用
+#[tauri::command]
+pub async fn test_profile_in_sandbox(uid: String) -> CmdResult<sandbox::SandboxTestReport> {
+    wrap_err!(sandbox::test_profile(&uid).await)
+}
@@ -0,0 +1,532 @@
+use super::{
+    CmdError, CmdResult, ErrorCode, WithErrorCode,
+    backup_restore::{decrypt_data, encrypt_data},
+};
+use crate::{
+    config::{Config, PrfItem, profiles_append_item_safe},
+    logging,
+    utils::logging::Type,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{fs, path::Path};
+
+/// 分享链接前缀，链接与分享文件使用相同的内容
+const SHARE_LINK_PREFIX: &str = "liebesu-clash://import-share?data=";
+/// 分享格式版本
+const SHARE_FORMAT_VERSION: u8 = 1;
+/// 节点中视为凭据的字段，包括 plugin-opts、reality-opts、peers 等嵌套结构中的同名字段
+const PROXY_CREDENTIAL_KEYS: &[&str] = &[
+    "username",
+    "password",
+    "uuid",
+    "auth",
+    "auth-str",
+    "token",
+    "psk",
+    "private-key",
+    "pre-shared-key",
+    "public-key",
+    "short-id",
+    "obfs-password",
+    "headers",
+];
+/// provider 中视为凭据的字段：订阅链接通常带 token，header 可能携带认证信息
+const PROVIDER_CREDENTIAL_KEYS: &[&str] = &["url", "header"];
+const SECTION_PROXIES: &str = "proxies";
+const PROVIDER_SECTIONS: &[&str] = &["proxy-providers", "rule-providers"];
+
+/// 分享时凭据的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareCredentials {
+    /// 原样保留
+    Keep,
+    /// 移除订阅地址、节点密码等凭据
+    #[default]
+    Strip,
+    /// 凭据使用口令加密后随分享内容一起分发
+    Encrypt,
+}
+
+/// 分享选项
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShareProfileOptions {
+    pub credentials: ShareCredentials,
+    pub passphrase: Option<String>,
+    pub output_path: Option<String>, // 填写时同时写入分享文件
+}
+
+/// 分享结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedProfilePackage {
+    pub link: String,
+    pub file_path: Option<String>,
+    pub credentials: ShareCredentials,
+    pub credential_count: usize, // 被移除或加密的凭据数量
+}
+
+/// 分享内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SharedProfile {
+    version: u8,
+    name: String,
+    desc: Option<String>,
+    url: Option<String>,
+    content: String,
+    credentials: ShareCredentials,
+    /// 加密后的凭据列表，base64 编码
+    secrets: Option<String>,
+    created_at: i64,
+}
+
+/// 凭据在配置中的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SecretPath {
+    Key(String),
+    Index(usize),
+}
+
+/// 从配置中取出的单个凭据，path 为空表示订阅地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SharedSecret {
+    path: Vec<SecretPath>,
+    value: Value,
+}
+
+/// 将配置打包为分享链接，可选移除或加密其中的凭据
+#[tauri::command]
+pub async fn share_profile(
+    uid: String,
+    options: Option<ShareProfileOptions>,
+) -> CmdResult<SharedProfilePackage> {
+    let options = options.unwrap_or_default();
+    let passphrase = options
+        .passphrase
+        .as_deref()
+        .filter(|passphrase| !passphrase.is_empty());
+    if options.credentials == ShareCredentials::Encrypt && passphrase.is_none() {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            "加密凭据需要提供口令",
+        ));
+    }
+
+    let item = {
+        let profiles = Config::profiles().await;
+        let profiles_ref = profiles.latest_ref();
+        profiles_ref
+            .get_item(&uid)
+            .with_code(ErrorCode::ProfileNotFound)?
+            .clone()
+    };
+    // 加密凭据时需要派生密钥，放到阻塞线程执行
+    let credentials = options.credentials;
+    let passphrase = passphrase.map(str::to_owned);
+    let (shared, credential_count) =
+        tokio::task::spawn_blocking(move || build_share(&item, credentials, passphrase.as_deref()))
+            .await?
+            .context("打包分享配置失败")?;
+    let link = encode_share(&shared)?;
+
+    let file_path = match options.output_path.as_deref().map(str::trim) {
+        Some(path) if !path.is_empty() => {
+            fs::write(path, &link).context("写入分享文件失败")?;
+            Some(path.to_string())
+        }
+        _ => None,
+    };
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[配置分享] 已打包配置 {}，凭据处理: {:?}，涉及凭据 {} 项",
+        uid,
+        options.credentials,
+        credential_count
+    );
+    Ok(SharedProfilePackage {
+        link,
+        file_path,
+        credentials: options.credentials,
+        credential_count,
+    })
+}
+
+/// 导入分享链接、分享内容或分享文件，加密凭据需提供口令，返回新配置 uid
+#[tauri::command]
+pub async fn import_shared_profile(
+    path_or_payload: String,
+    passphrase: Option<String>,
+) -> CmdResult<String> {
+    let source = path_or_payload.trim();
+    let text = if Path::new(source).is_file() {
+        fs::read_to_string(source).context("读取分享文件失败")?
+    } else {
+        source.to_string()
+    };
+    let mut shared = decode_share(&text)
+        .map_err(|e| CmdError::new(ErrorCode::InvalidArgument, format!("分享内容无效: {e:#}")))?;
+
+    if shared.credentials == ShareCredentials::Encrypt {
+        let passphrase = passphrase
+            .filter(|passphrase| !passphrase.is_empty())
+            .ok_or_else(|| {
+                CmdError::new(ErrorCode::InvalidArgument, "该分享包含加密凭据，请提供口令")
+            })?;
+        // 解密凭据需要派生密钥，放到阻塞线程执行
+        shared = tokio::task::spawn_blocking(move || {
+            restore_secrets(&mut shared, &passphrase).map(|()| shared)
+        })
+        .await?
+        .map_err(|e| CmdError::new(ErrorCode::InvalidArgument, format!("{e}")))?;
+    }
+
+    let name = shared.name.clone();
+    let item = match shared.url.as_deref() {
+        // 携带订阅地址时作为远程订阅导入，之后可正常更新
+        Some(url) => PrfItem::from_url(url, Some(name.clone()), shared.desc.clone(), None).await,
+        None => {
+            PrfItem::from_local(
+                name.clone(),
+                shared.desc.clone().unwrap_or_default(),
+                Some(shared.content.clone()),
+                None,
+            )
+            .await
+        }
+    }
+    .context("创建配置失败")?;
+    let uid = item.uid.clone().ok_or("配置缺少 uid")?;
+    profiles_append_item_safe(item)
+        .await
+        .context("保存配置失败")?;
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[配置分享] 已导入分享配置 {} -> {}",
+        name,
+        uid
+    );
+    Ok(uid)
+}
+
+// ===== 内部实现函数 =====
+
+fn build_share(
+    item: &PrfItem,
+    credentials: ShareCredentials,
+    passphrase: Option<&str>,
+) -> Result<(SharedProfile, usize)> {
+    let shared = SharedProfile {
+        version: SHARE_FORMAT_VERSION,
+        name: item.name.clone().unwrap_or_else(|| "Shared Profile".into()),
+        desc: item.desc.clone(),
+        url: item.url.clone(),
+        content: item.read_file()?,
+        credentials,
+        secrets: None,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    protect_credentials(shared, passphrase)
+}
+
+/// 按分享内容中的凭据处理方式移除或加密凭据，返回涉及的凭据数量
+fn protect_credentials(
+    mut shared: SharedProfile,
+    passphrase: Option<&str>,
+) -> Result<(SharedProfile, usize)> {
+    if shared.credentials == ShareCredentials::Keep {
+        return Ok((shared, 0));
+    }
+
+    let mut config: Mapping =
+        serde_yaml_ng::from_str(&shared.content).context("profile is not a valid yaml mapping")?;
+    let mut secrets = take_secrets(&mut config);
+    if let Some(url) = shared.url.take() {
+        secrets.push(SharedSecret {
+            path: Vec::new(),
+            value: Value::from(url),
+        });
+    }
+    shared.content = serde_yaml_ng::to_string(&config)?;
+
+    if shared.credentials == ShareCredentials::Encrypt {
+        let passphrase = passphrase.ok_or_else(|| anyhow!("passphrase is required"))?;
+        let encrypted = encrypt_data(&serde_json::to_vec(&secrets)?, passphrase)?;
+        shared.secrets = Some(STANDARD.encode(encrypted));
+    }
+    Ok((shared, secrets.len()))
+}
+
+/// 移除节点与 provider 中的凭据，返回被移除的值及其位置
+fn take_secrets(config: &mut Mapping) -> Vec<SharedSecret> {
+    let mut secrets = Vec::new();
+
+    if let Some(proxies) = config.get_mut(SECTION_PROXIES) {
+        let mut path = vec![SecretPath::Key(SECTION_PROXIES.into())];
+        take_nested_secrets(proxies, &mut path, &mut secrets);
+    }
+
+    // provider 只处理顶层字段，health-check 的测试地址不属于凭据
+    for section in PROVIDER_SECTIONS {
+        let Some(Value::Mapping(providers)) = config.get_mut(*section) else {
+            continue;
+        };
+        for (name, provider) in providers.iter_mut() {
+            let (Some(name), Some(provider)) = (name.as_str(), provider.as_mapping_mut()) else {
+                continue;
+            };
+            for key in PROVIDER_CREDENTIAL_KEYS {
+                if let Some(value) = provider.remove(*key) {
+                    secrets.push(SharedSecret {
+                        path: vec![
+                            SecretPath::Key(section.to_string()),
+                            SecretPath::Key(name.into()),
+                            SecretPath::Key(key.to_string()),
+                        ],
+                        value,
+                    });
+                }
+            }
+        }
+    }
+    secrets
+}
+
+/// 递归移除映射与序列中的凭据字段
+fn take_nested_secrets(
+    value: &mut Value,
+    path: &mut Vec<SecretPath>,
+    secrets: &mut Vec<SharedSecret>,
+) {
+    match value {
+        Value::Mapping(map) => {
+            for key in PROXY_CREDENTIAL_KEYS {
+                if let Some(value) = map.remove(*key) {
+                    let mut secret_path = path.clone();
+                    secret_path.push(SecretPath::Key(key.to_string()));
+                    secrets.push(SharedSecret {
+                        path: secret_path,
+                        value,
+                    });
+                }
+            }
+            for (key, value) in map.iter_mut() {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                path.push(SecretPath::Key(key.into()));
+                take_nested_secrets(value, path, secrets);
+                path.pop();
+            }
+        }
+        Value::Sequence(seq) => {
+            for (index, value) in seq.iter_mut().enumerate() {
+                path.push(SecretPath::Index(index));
+                take_nested_secrets(value, path, secrets);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 解密凭据并写回配置内容
+fn restore_secrets(shared: &mut SharedProfile, passphrase: &str) -> Result<()> {
+    let encrypted = STANDARD.decode(
+        shared
+            .secrets
+            .as_deref()
+            .ok_or_else(|| anyhow!("分享内容缺少加密凭据"))?,
+    )?;
+    let decrypted = decrypt_data(&encrypted, passphrase).map_err(|_| anyhow!("口令错误"))?;
+    let secrets: Vec<SharedSecret> = serde_json::from_slice(&decrypted)?;
+
+    let mut config: Value = serde_yaml_ng::from_str(&shared.content)?;
+    for secret in secrets {
+        let Some((last, parents)) = secret.path.split_last() else {
+            shared.url = secret.value.as_str().map(str::to_string);
+            continue;
+        };
+        let mut target = Some(&mut config);
+        for segment in parents {
+            target = target.and_then(|value| match (segment, value) {
+                (SecretPath::Key(key), Value::Mapping(map)) => map.get_mut(key.as_str()),
+                (SecretPath::Index(index), Value::Sequence(seq)) => seq.get_mut(*index),
+                _ => None,
+            });
+        }
+        if let (Some(Value::Mapping(map)), SecretPath::Key(key)) = (target, last) {
+            map.insert(Value::from(key.as_str()), secret.value);
+        }
+    }
+    shared.content = serde_yaml_ng::to_string(&config)?;
+    shared.secrets = None;
+    Ok(())
+}
+
+fn encode_share(shared: &SharedProfile) -> Result<String, String> {
+    let json = serde_json::to_vec(shared).map_err(|e| format!("序列化分享内容失败: {e}"))?;
+    Ok(format!(
+        "{SHARE_LINK_PREFIX}{}",
+        URL_SAFE_NO_PAD.encode(json)
+    ))
+}
+
+/// 接受完整链接或去掉前缀的内容
+fn decode_share(text: &str) -> Result<SharedProfile> {
+    let text = text.trim();
+    let data = text.strip_prefix(SHARE_LINK_PREFIX).unwrap_or(text);
+    let json = URL_SAFE_NO_PAD
+        .decode(data.trim_end_matches('='))
+        .context("payload is not valid base64")?;
+    let shared: SharedProfile = serde_json::from_slice(&json).context("payload is not valid")?;
+    if shared.version > SHARE_FORMAT_VERSION {
+        bail!("unsupported share format version: {}", shared.version);
+    }
+    Ok(shared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = r#"
+proxies:
+  - name: ss
+    type: ss
+    server: 1.1.1.1
+    password: ss-pass
+    plugin: obfs
+    plugin-opts:
+      mode: tls
+      password: plugin-pass
+  - name: vless
+    type: vless
+    server: 2.2.2.2
+    uuid: vless-uuid
+    reality-opts:
+      public-key: reality-key
+      short-id: abcd
+    ws-opts:
+      path: /ws
+      headers:
+        Authorization: Bearer secret
+  - name: wg
+    type: wireguard
+    peers:
+      - server: 3.3.3.3
+        private-key: wg-key
+  - name: http
+    type: http
+    username: user
+    password: http-pass
+proxy-providers:
+  sub:
+    type: http
+    url: https://example.com/sub?token=abc
+    header:
+      Authorization: [Bearer secret]
+    health-check:
+      url: https://www.gstatic.com/generate_204
+rule-providers:
+  private:
+    type: http
+    url: https://example.com/rules?token=abc
+"#;
+    const SECRETS: &[&str] = &[
+        "ss-pass",
+        "plugin-pass",
+        "vless-uuid",
+        "reality-key",
+        "abcd",
+        "Bearer secret",
+        "wg-key",
+        "http-pass",
+        "token=abc",
+        "sub-token",
+    ];
+
+    fn sample(credentials: ShareCredentials) -> SharedProfile {
+        SharedProfile {
+            version: SHARE_FORMAT_VERSION,
+            name: "test".into(),
+            desc: None,
+            url: Some("https://example.com/profile?sub-token".into()),
+            content: PROFILE.into(),
+            credentials,
+            secrets: None,
+            created_at: 0,
+        }
+    }
+
+    fn parse(content: &str) -> Value {
+        serde_yaml_ng::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_strip_removes_nested_credentials() {
+        let (shared, count) = protect_credentials(sample(ShareCredentials::Strip), None).unwrap();
+        assert_eq!(count, 13);
+        assert!(shared.url.is_none());
+        assert!(shared.secrets.is_none());
+        for secret in SECRETS {
+            assert!(!shared.content.contains(secret), "{secret} not stripped");
+        }
+        // 非凭据字段保持不变
+        let config = parse(&shared.content);
+        assert_eq!(config["proxies"][1]["ws-opts"]["path"], Value::from("/ws"));
+        assert_eq!(
+            config["proxy-providers"]["sub"]["health-check"]["url"],
+            Value::from("https://www.gstatic.com/generate_204")
+        );
+    }
+
+    #[test]
+    fn test_encrypt_import_round_trip() {
+        let (shared, count) =
+            protect_credentials(sample(ShareCredentials::Encrypt), Some("passphrase")).unwrap();
+        assert_eq!(count, 13);
+        for secret in SECRETS {
+            assert!(!shared.content.contains(secret), "{secret} not encrypted");
+        }
+
+        let mut imported = decode_share(&encode_share(&shared).unwrap()).unwrap();
+        restore_secrets(&mut imported, "passphrase").unwrap();
+        assert_eq!(parse(&imported.content), parse(PROFILE));
+        assert_eq!(
+            imported.url.as_deref(),
+            Some("https://example.com/profile?sub-token")
+        );
+        assert!(imported.secrets.is_none());
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let (shared, _) =
+            protect_credentials(sample(ShareCredentials::Encrypt), Some("passphrase")).unwrap();
+        let mut imported = decode_share(&encode_share(&shared).unwrap()).unwrap();
+        let err = restore_secrets(&mut imported, "wrong").unwrap_err();
+        assert_eq!(err.to_string(), "口令错误");
+        assert!(imported.secrets.is_some());
+    }
+
+    #[test]
+    fn test_keep_leaves_content_untouched() {
+        let (shared, count) = protect_credentials(sample(ShareCredentials::Keep), None).unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(shared.content, PROFILE);
+        assert!(shared.url.is_some());
+    }
+}
@@ -0,0 +1,324 @@
+use super::{CmdError, CmdResult};
+use crate::{
+    config::{PrfItem, PrfOption, profiles_append_item_safe},
+    logging,
+    utils::{logging::Type, tmpl},
+};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+const GROUP_SELECT: &str = "🚀 节点选择";
+const GROUP_AUTO: &str = "♻️ 自动选择";
+const GROUP_GAME: &str = "🎮 游戏";
+const GROUP_MEDIA: &str = "📺 流媒体";
+const PROVIDER_NAME: &str = "subscription";
+const DEFAULT_PROVIDER_INTERVAL: u64 = 86400;
+
+/// 内置配置模板
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateKind {
+    Gaming,
+    Streaming,
+    Minimal,
+    Whitelist,
+}
+
+impl TemplateKind {
+    const ALL: [TemplateKind; 4] = [
+        TemplateKind::Gaming,
+        TemplateKind::Streaming,
+        TemplateKind::Minimal,
+        TemplateKind::Whitelist,
+    ];
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            TemplateKind::Gaming => "gaming",
+            TemplateKind::Streaming => "streaming",
+            TemplateKind::Minimal => "minimal",
+            TemplateKind::Whitelist => "whitelist",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TemplateKind::Gaming => "游戏加速",
+            TemplateKind::Streaming => "流媒体",
+            TemplateKind::Minimal => "极简",
+            TemplateKind::Whitelist => "白名单",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            TemplateKind::Gaming => "游戏流量走独立分组，开启统一延迟和 UDP，国内游戏直连",
+            TemplateKind::Streaming => "Netflix、YouTube、Disney+ 等流媒体走独立分组，开启域名嗅探",
+            TemplateKind::Minimal => "仅一个节点选择分组，国内和局域网直连，其余走代理",
+            TemplateKind::Whitelist => "仅被墙和境外站点走代理，其余全部直连",
+        }
+    }
+}
+
+/// 模板信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileTemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+/// 模板参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProfileTemplateParams {
+    pub name: Option<String>,
+    pub provider_url: Option<String>, // 订阅地址，作为 proxy-provider 引入节点
+    pub provider_interval: Option<u64>,
+}
+
+/// 获取内置配置模板列表
+#[tauri::command]
+pub fn get_profile_templates() -> CmdResult<Vec<ProfileTemplateInfo>> {
+    Ok(TemplateKind::ALL
+        .into_iter()
+        .map(|kind| ProfileTemplateInfo {
+            id: kind.id().to_string(),
+            name: kind.name().to_string(),
+            description: kind.description().to_string(),
+        })
+        .collect())
+}
+
+/// 从模板创建本地配置，同时生成对应的 merge/script 增强项，返回新配置 uid
+#[tauri::command]
+pub async fn create_profile_from_template(
+    template_id: String,
+    params: Option<ProfileTemplateParams>,
+) -> CmdResult<String> {
+    let kind =
+        TemplateKind::from_id(&template_id).ok_or_else(|| format!("未知模板: {template_id}"))?;
+    let params = params.unwrap_or_default();
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[配置模板] 从模板 {} 创建配置",
+        kind.id()
+    );
+
+    create_from_template(kind, &params)
+        .await
+        .context("从模板创建配置失败")
+        .map_err(CmdError::from)
+}
+
+async fn create_from_template(
+    kind: TemplateKind,
+    params: &ProfileTemplateParams,
+) -> Result<String> {
+    let profile = build_profile(kind, params)?;
+
+    let mut merge = PrfItem::from_merge(None)?;
+    merge.file_data = Some(build_merge(kind)?);
+    let mut script = PrfItem::from_script(None)?;
+    script.file_data = Some(build_script(kind));
+    let option = PrfOption {
+        merge: merge.uid.clone(),
+        script: script.uid.clone(),
+        ..PrfOption::default()
+    };
+    profiles_append_item_safe(merge).await?;
+    profiles_append_item_safe(script).await?;
+
+    let name = params
+        .name
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| kind.name().to_string());
+    let item = PrfItem::from_local(
+        name,
+        kind.description().to_string(),
+        Some(profile),
+        Some(option),
+    )
+    .await?;
+    let uid = item
+        .uid
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("配置缺少 uid"))?;
+    profiles_append_item_safe(item).await?;
+    Ok(uid)
+}
+
+/// 生成配置主体：代理组与规则，填写订阅地址时通过 proxy-provider 引入节点
+fn build_profile(kind: TemplateKind, params: &ProfileTemplateParams) -> Result<String> {
+    let provider_url = params
+        .provider_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+    if let Some(url) = provider_url
+        && !url.starts_with("http://")
+        && !url.starts_with("https://")
+    {
+        bail!("订阅地址必须以 http:// 或 https:// 开头");
+    }
+
+    let source = |mut group: Value| -> Value {
+        match provider_url {
+            Some(_) => group["use"] = json!([PROVIDER_NAME]),
+            None => group["include-all"] = json!(true),
+        }
+        group
+    };
+    // 没有订阅地址时配置中没有节点，空的 url-test 分组会导致内核加载失败，因此不创建自动选择分组
+    let members = |names: &[&'static str]| -> Vec<&'static str> {
+        names
+            .iter()
+            .copied()
+            .filter(|name| provider_url.is_some() || *name != GROUP_AUTO)
+            .collect()
+    };
+
+    let mut groups = vec![source(json!({
+        "name": GROUP_SELECT,
+        "type": "select",
+        "proxies": members(&[GROUP_AUTO, "DIRECT"]),
+    }))];
+    if provider_url.is_some() {
+        groups.push(source(json!({
+            "name": GROUP_AUTO,
+            "type": "url-test",
+            "url": "https://www.gstatic.com/generate_204",
+            "interval": 300,
+            "tolerance": 50,
+        })));
+    }
+
+    let rules: Vec<String> = match kind {
+        TemplateKind::Gaming => {
+            groups.push(json!({
+                "name": GROUP_GAME,
+                "type": "select",
+                "proxies": members(&[GROUP_AUTO, GROUP_SELECT, "DIRECT"]),
+            }));
+            vec![
+                "GEOSITE,private,DIRECT".into(),
+                "GEOSITE,category-games@cn,DIRECT".into(),
+                format!("GEOSITE,category-games,{GROUP_GAME}"),
+                "GEOSITE,cn,DIRECT".into(),
+                "GEOIP,private,DIRECT,no-resolve".into(),
+                "GEOIP,CN,DIRECT".into(),
+                format!("MATCH,{GROUP_SELECT}"),
+            ]
+        }
+        TemplateKind::Streaming => {
+            groups.push(json!({
+                "name": GROUP_MEDIA,
+                "type": "select",
+                "proxies": members(&[GROUP_SELECT, GROUP_AUTO, "DIRECT"]),
+            }));
+            let mut rules: Vec<String> = ["netflix", "youtube", "disney", "hbo", "spotify"]
+                .iter()
+                .map(|site| format!("GEOSITE,{site},{GROUP_MEDIA}"))
+                .collect();
+            rules.extend([
+                "GEOSITE,private,DIRECT".into(),
+                "GEOSITE,cn,DIRECT".into(),
+                "GEOIP,private,DIRECT,no-resolve".into(),
+                "GEOIP,CN,DIRECT".into(),
+                format!("MATCH,{GROUP_SELECT}"),
+            ]);
+            rules
+        }
+        TemplateKind::Minimal => vec![
+            "GEOSITE,private,DIRECT".into(),
+            "GEOSITE,cn,DIRECT".into(),
+            "GEOIP,private,DIRECT,no-resolve".into(),
+            "GEOIP,CN,DIRECT".into(),
+            format!("MATCH,{GROUP_SELECT}"),
+        ],
+        TemplateKind::Whitelist => vec![
+            format!("GEOSITE,gfw,{GROUP_SELECT}"),
+            format!("GEOSITE,geolocation-!cn,{GROUP_SELECT}"),
+            format!("GEOIP,telegram,{GROUP_SELECT},no-resolve"),
+            "MATCH,DIRECT".into(),
+        ],
+    };
+
+    let mut config = json!({
+        "proxies": [],
+        "proxy-groups": groups,
+        "rules": rules,
+    });
+    if let Some(url) = provider_url {
+        config["proxy-providers"] = json!({
+            PROVIDER_NAME: {
+                "type": "http",
+                "url": url,
+                "path": format!("./proxy_providers/{PROVIDER_NAME}_{}.yaml", kind.id()),
+                "interval": params.provider_interval.unwrap_or(DEFAULT_PROVIDER_INTERVAL),
+                "health-check": {
+                    "enable": true,
+                    "url": "https://www.gstatic.com/generate_204",
+                    "interval": 600,
+                },
+            },
+        });
+    }
+
+    let header = format!("# {} Profile Template for Liebesu_Clash\n\n", kind.name());
+    Ok(header + &serde_yaml_ng::to_string(&config)?)
+}
+
+/// 生成模板对应的 merge 增强项
+fn build_merge(kind: TemplateKind) -> Result<String> {
+    let merge = match kind {
+        TemplateKind::Gaming => json!({
+            "unified-delay": true,
+            "tcp-concurrent": true,
+            "profile": { "store-selected": true },
+        }),
+        TemplateKind::Streaming => json!({
+            "profile": { "store-selected": true },
+            "sniffer": {
+                "enable": true,
+                "sniff": {
+                    "HTTP": { "ports": [80, "8080-8880"], "override-destination": true },
+                    "TLS": { "ports": [443, 8443] },
+                    "QUIC": { "ports": [443, 8443] },
+                },
+            },
+        }),
+        TemplateKind::Minimal | TemplateKind::Whitelist => json!({
+            "profile": { "store-selected": true },
+        }),
+    };
+    let header = "# Profile Enhancement Merge Template for Liebesu_Clash\n\n";
+    Ok(header.to_string() + &serde_yaml_ng::to_string(&merge)?)
+}
+
+/// 生成模板对应的 script 增强项
+fn build_script(kind: TemplateKind) -> String {
+    match kind {
+        TemplateKind::Gaming => "// Define main function (script entry)
+
+function main(config, profileName) {
+  // 游戏流量依赖 UDP，为未声明的节点开启
+  for (const proxy of config.proxies || []) {
+    if (proxy.udp === undefined) {
+      proxy.udp = true;
+    }
+  }
+  return config;
+}
+"
+        .to_string(),
+        _ => tmpl::ITEM_SCRIPT.to_string(),
+    }
+}
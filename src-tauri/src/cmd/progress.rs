@@ -0,0 +1,73 @@
+use super::{CancelToken, OperationKind};
+use crate::core::handle;
+use nanoid::nanoid;
+use serde::Serialize;
+use tauri::Emitter;
+
+/// 统一的操作进度事件，所有批量操作都通过 operation-progress 通道发送
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationProgress {
+    pub task_id: String,
+    pub kind: OperationKind,
+    pub stage: String,
+    pub completed: usize,
+    pub total: usize,
+    pub message: Option<String>,
+}
+
+/// 操作进度上报器，同一操作的所有进度事件共享 task_id
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    task_id: String,
+    kind: OperationKind,
+    total: usize,
+}
+
+impl ProgressReporter {
+    /// 为未注册取消令牌的操作创建上报器
+    pub fn new(kind: OperationKind, total: usize) -> Self {
+        Self {
+            task_id: nanoid!(),
+            kind,
+            total,
+        }
+    }
+
+    /// 使用取消令牌的 task_id，前端可据此取消对应操作
+    pub fn for_operation(token: &CancelToken, total: usize) -> Self {
+        Self {
+            task_id: token.task_id().to_string(),
+            kind: token.kind(),
+            total,
+        }
+    }
+
+    pub fn set_total(&mut self, total: usize) {
+        self.total = total;
+    }
+
+    pub fn emit(&self, stage: &str, completed: usize, message: Option<String>) {
+        let payload = OperationProgress {
+            task_id: self.task_id.clone(),
+            kind: self.kind,
+            stage: stage.to_string(),
+            completed: completed.min(self.total),
+            total: self.total,
+            message,
+        };
+        if let Some(app_handle) = handle::Handle::global().app_handle()
+            && let Err(err) = app_handle.emit("operation-progress", payload)
+        {
+            log::warn!(target: "app", "operation-progress emit failed: {err}");
+        }
+    }
+
+    /// 操作完成，completed 视为等于 total
+    pub fn finish(&self, message: Option<String>) {
+        self.emit("completed", self.total, message);
+    }
+
+    pub fn fail(&self, completed: usize, message: String) {
+        self.emit("failed", completed, Some(message));
+    }
+}
@@ -0,0 +1,251 @@
+use super::{
+    CmdResult, SpeedTestResult, SubscriptionHealthReport,
+    traffic_stats::{
+        AlertSeverity, AlertType, TrafficAlert, mark_alert_as_read, push_traffic_alert,
+    },
+};
+use crate::{
+    core::handle,
+    logging,
+    utils::{
+        dirs,
+        logging::Type,
+        notification::{NotificationEvent, notify_event},
+    },
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+const OUTAGE_STATE_FILE: &str = "provider_outages.json";
+
+/// 连续失败多少次判定为故障
+const OUTAGE_THRESHOLD: u32 = 3;
+
+/// 已恢复的故障保留天数
+const RESOLVED_RETENTION_DAYS: i64 = 7;
+
+static OUTAGE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 订阅故障记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderOutage {
+    pub id: String,
+    pub uid: String,
+    pub name: String,
+    pub kind: OutageKind,
+    pub message: String,
+    pub consecutive_failures: u32,
+    pub started_at: i64,
+    pub last_seen: i64,
+    pub resolved_at: Option<i64>,
+    pub acknowledged: bool,
+    pub alert_id: Option<String>, // 对应的流量警告
+}
+
+/// 故障类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutageKind {
+    Endpoint, // 订阅地址连续无法访问
+    Nodes,    // 多数节点连续测速失败
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OutageState {
+    outages: Vec<ProviderOutage>,
+    /// 每个订阅多数节点失败的连续测速轮数
+    node_failures: HashMap<String, u32>,
+}
+
+/// 获取订阅故障列表，未恢复的排在前面
+#[tauri::command]
+pub async fn get_provider_outages() -> CmdResult<Vec<ProviderOutage>> {
+    let _guard = OUTAGE_LOCK.lock().await;
+    let mut state = load_state().context("加载故障记录失败")?;
+    let mut outages = std::mem::take(&mut state.outages);
+    outages.sort_by_key(|o| (o.resolved_at.is_some(), std::cmp::Reverse(o.last_seen)));
+    Ok(outages)
+}
+
+/// 确认故障，并将对应的警告标记为已读
+#[tauri::command]
+pub async fn acknowledge_outage(id: String) -> CmdResult<()> {
+    let alert_id = {
+        let _guard = OUTAGE_LOCK.lock().await;
+        let mut state = load_state().context("加载故障记录失败")?;
+        let outage = state
+            .outages
+            .iter_mut()
+            .find(|o| o.id == id)
+            .ok_or_else(|| "Outage not found".to_string())?;
+        outage.acknowledged = true;
+        let alert_id = outage.alert_id.clone();
+        save_state(&state).context("保存故障记录失败")?;
+        alert_id
+    };
+
+    if let Some(alert_id) = alert_id {
+        mark_alert_as_read(alert_id).await?;
+    }
+    logging!(info, Type::Cmd, true, "[故障检测] 已确认故障: {}", id);
+    Ok(())
+}
+
+// ===== 内部实现函数 =====
+
+/// 根据健康检查历史判断订阅地址是否故障
+pub(crate) async fn observe_endpoint(uid: &str, name: &str, history: &[SubscriptionHealthReport]) {
+    let failures = history
+        .iter()
+        .rev()
+        .take_while(|r| r.error_kind.is_some())
+        .count() as u32;
+    let message = history
+        .last()
+        .and_then(|r| r.error_message.clone())
+        .unwrap_or_default();
+
+    observe(uid, name, OutageKind::Endpoint, failures, message).await;
+}
+
+/// 根据一轮节点测速结果判断各订阅是否多数节点失败
+pub(crate) async fn observe_node_results(results: &[SpeedTestResult]) {
+    let mut by_profile: HashMap<&str, (&str, usize, usize)> = HashMap::new();
+    for result in results {
+        let entry = by_profile
+            .entry(&result.profile_uid)
+            .or_insert((&result.profile_name, 0, 0));
+        entry.1 += 1;
+        if !result.is_available {
+            entry.2 += 1;
+        }
+    }
+
+    for (uid, (name, total, failed)) in by_profile {
+        let failures = {
+            let _guard = OUTAGE_LOCK.lock().await;
+            let mut state = load_state().unwrap_or_default();
+            let counter = state.node_failures.entry(uid.to_string()).or_default();
+            *counter = if failed * 2 > total { *counter + 1 } else { 0 };
+            let failures = *counter;
+            if let Err(e) = save_state(&state) {
+                logging!(warn, Type::Cmd, true, "[故障检测] 保存故障记录失败: {}", e);
+            }
+            failures
+        };
+        let message = format!("{}/{} 个节点测速失败", failed, total);
+        observe(uid, name, OutageKind::Nodes, failures, message).await;
+    }
+}
+
+/// 更新故障状态，新故障时写入警告并发送通知
+async fn observe(uid: &str, name: &str, kind: OutageKind, failures: u32, message: String) {
+    let now = chrono::Utc::now().timestamp();
+    let new_outage = {
+        let _guard = OUTAGE_LOCK.lock().await;
+        let mut state = load_state().unwrap_or_default();
+        let open = state
+            .outages
+            .iter_mut()
+            .find(|o| o.uid == uid && o.kind == kind && o.resolved_at.is_none());
+
+        let new_outage = match open {
+            Some(outage) if failures == 0 => {
+                outage.resolved_at = Some(now);
+                logging!(info, Type::Cmd, true, "[故障检测] 订阅 {} 已恢复", name);
+                None
+            }
+            Some(outage) => {
+                outage.consecutive_failures = failures;
+                outage.last_seen = now;
+                outage.message = message;
+                None
+            }
+            None if failures >= OUTAGE_THRESHOLD => {
+                let outage = ProviderOutage {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    uid: uid.to_string(),
+                    name: name.to_string(),
+                    kind,
+                    message,
+                    consecutive_failures: failures,
+                    started_at: now,
+                    last_seen: now,
+                    resolved_at: None,
+                    acknowledged: false,
+                    alert_id: Some(uuid::Uuid::new_v4().to_string()),
+                };
+                state.outages.push(outage.clone());
+                Some(outage)
+            }
+            None => None,
+        };
+
+        state.outages.retain(|o| {
+            o.resolved_at
+                .is_none_or(|at| now - at < RESOLVED_RETENTION_DAYS * 86400)
+        });
+        if let Err(e) = save_state(&state) {
+            logging!(warn, Type::Cmd, true, "[故障检测] 保存故障记录失败: {}", e);
+        }
+        new_outage
+    };
+
+    if let Some(outage) = new_outage {
+        raise_alert(&outage).await;
+    }
+}
+
+async fn raise_alert(outage: &ProviderOutage) {
+    logging!(
+        warn,
+        Type::Cmd,
+        true,
+        "[故障检测] 订阅 {} 连续 {} 次失败: {}",
+        outage.name,
+        outage.consecutive_failures,
+        outage.message
+    );
+
+    push_traffic_alert(TrafficAlert {
+        alert_id: outage.alert_id.clone().unwrap_or_default(),
+        subscription_uid: outage.uid.clone(),
+        subscription_name: outage.name.clone(),
+        alert_type: AlertType::ConnectionIssue,
+        message: outage.message.clone(),
+        threshold_value: f64::from(OUTAGE_THRESHOLD),
+        current_value: f64::from(outage.consecutive_failures),
+        created_at: outage.started_at,
+        is_read: false,
+        severity: AlertSeverity::Critical,
+    })
+    .await;
+
+    if let Some(app_handle) = handle::Handle::global().app_handle() {
+        notify_event(
+            app_handle,
+            NotificationEvent::ProviderOutage {
+                name: &outage.name,
+                reason: &outage.message,
+            },
+        )
+        .await;
+    }
+}
+
+fn load_state() -> Result<OutageState> {
+    let path = dirs::app_home_dir()?.join(OUTAGE_STATE_FILE);
+    if !path.exists() {
+        return Ok(OutageState::default());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn save_state(state: &OutageState) -> Result<()> {
+    let path = dirs::app_home_dir()?.join(OUTAGE_STATE_FILE);
+    std::fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
@@ -1,24 +1,27 @@
 use tauri::Emitter;
 
 use super::CmdResult;
+use super::selection_memory::{best_by_delay, detect_region};
 use crate::{
+    config::{Config, IProxyGroupPreference, IProxyGroupSort, IVerge},
     core::{handle::Handle, tray::Tray},
+    feat,
     ipc::IpcManager,
     logging,
-    state::proxy::ProxyRequestCache,
-    utils::logging::Type,
+    state::proxy::{CacheEndpoint, IpcCacheStats, ProxyRequestCache},
+    utils::{field_mask::apply_field_mask, logging::Type},
 };
-use std::time::Duration;
-
-const PROXIES_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
-const PROVIDERS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::HashMap;
 
+/// 获取代理信息
+/// fields 为可选的字段路径列表（如 `proxies.*.now`），仅返回指定字段
 #[tauri::command]
-pub async fn get_proxies() -> CmdResult<serde_json::Value> {
+pub async fn get_proxies(fields: Option<Vec<String>>) -> CmdResult<serde_json::Value> {
     let cache = ProxyRequestCache::global();
-    let key = ProxyRequestCache::make_key("proxies", "default");
     let value = cache
-        .get_or_fetch(key.clone(), PROXIES_REFRESH_INTERVAL, || async {
+        .get_or_fetch(CacheEndpoint::Proxies, || async {
             let manager = IpcManager::global();
             manager.get_proxies().await.unwrap_or_else(|e| {
                 logging!(error, Type::Cmd, "Failed to fetch proxies: {e}");
@@ -28,7 +31,7 @@ pub async fn get_proxies() -> CmdResult<serde_json::Value> {
         })
         .await;
     // 规范化返回值，确保一定包含 { "proxies": { ... } }
-    let normalized = match value.as_object() {
+    let mut normalized = match value.as_object() {
         Some(map) if map.contains_key("proxies") => (*value).clone(),
         _ => serde_json::json!({ "proxies": {} }),
     };
@@ -40,27 +43,138 @@ pub async fn get_proxies() -> CmdResult<serde_json::Value> {
         .map(|m| m.is_empty())
         .unwrap_or(true)
     {
-        cache.map.remove(&key);
+        cache.invalidate(CacheEndpoint::Proxies);
     }
 
-    Ok(normalized)
+    let preferences = Config::verge()
+        .await
+        .latest_ref()
+        .proxy_group_preferences
+        .clone()
+        .unwrap_or_default();
+    let favorites = super::favorite_nodes::current_favorites().await;
+    apply_group_preferences(&mut normalized, &preferences, &favorites);
+    super::bandwidth_limits::annotate_bandwidth_limits(&mut normalized);
+
+    Ok(apply_field_mask(normalized, fields.as_deref()))
+}
+
+/// 设置代理组显示偏好，prefs 为空时恢复默认
+#[tauri::command]
+pub async fn set_group_preferences(
+    group: String,
+    prefs: Option<IProxyGroupPreference>,
+) -> CmdResult<()> {
+    let mut preferences = Config::verge()
+        .await
+        .latest_ref()
+        .proxy_group_preferences
+        .clone()
+        .unwrap_or_default();
+    match prefs {
+        Some(prefs) => {
+            preferences.insert(group, prefs);
+        }
+        None => {
+            preferences.remove(&group);
+        }
+    }
+    feat::patch_verge(
+        IVerge {
+            proxy_group_preferences: Some(preferences),
+            ..IVerge::default()
+        },
+        false,
+    )
+    .await
+    .map_err(|e| format!("保存代理组偏好失败: {e}"))?;
+
+    Tray::global().refresh_menu_async();
+    if let Some(app_handle) = Handle::global().app_handle() {
+        let _ = app_handle.emit("verge://force-refresh-proxies", ());
+    }
+    Ok(())
+}
+
+/// 按偏好隐藏分组并重排组内节点，前端和托盘共用同一顺序
+fn apply_group_preferences(
+    value: &mut serde_json::Value,
+    preferences: &HashMap<String, IProxyGroupPreference>,
+    favorites: &[String],
+) {
+    if preferences.is_empty() {
+        return;
+    }
+    let Some(proxies) = value.get_mut("proxies").and_then(|p| p.as_object_mut()) else {
+        return;
+    };
+
+    // 排序依据的延迟来自节点记录，先取出避免与分组的可变借用冲突
+    let delays: HashMap<String, u64> = proxies
+        .iter()
+        .filter_map(|(name, info)| {
+            let delay = info
+                .get("history")?
+                .as_array()?
+                .last()?
+                .get("delay")?
+                .as_u64()?;
+            (delay > 0).then(|| (name.clone(), delay))
+        })
+        .collect();
+
+    for (group, prefs) in preferences {
+        let Some(info) = proxies.get_mut(group).and_then(|g| g.as_object_mut()) else {
+            continue;
+        };
+        if prefs.hidden {
+            info.insert("hidden".into(), serde_json::Value::Bool(true));
+        }
+        let Some(all) = info.get_mut("all").and_then(|a| a.as_array_mut()) else {
+            continue;
+        };
+        match prefs.sort_by {
+            IProxyGroupSort::Default => {}
+            IProxyGroupSort::Name => {
+                all.sort_by_cached_key(|name| name.as_str().unwrap_or_default().to_lowercase())
+            }
+            // 未测速或超时的节点排在最后
+            IProxyGroupSort::Latency => all.sort_by_key(|name| {
+                name.as_str()
+                    .and_then(|name| delays.get(name))
+                    .copied()
+                    .unwrap_or(u64::MAX)
+            }),
+        }
+        // 稳定排序，收藏节点之间保留上面的排序结果
+        if prefs.pin_favorites && !favorites.is_empty() {
+            all.sort_by_key(|name| {
+                !name
+                    .as_str()
+                    .is_some_and(|name| favorites.iter().any(|f| f == name))
+            });
+        }
+    }
 }
 
 /// 强制刷新代理缓存用于profile切换
 #[tauri::command]
 pub async fn force_refresh_proxies() -> CmdResult<serde_json::Value> {
-    let cache = ProxyRequestCache::global();
-    let key = ProxyRequestCache::make_key("proxies", "default");
-    cache.map.remove(&key);
-    get_proxies().await
+    ProxyRequestCache::global().invalidate(CacheEndpoint::Proxies);
+    get_proxies(None).await
+}
+
+/// 获取 IPC 缓存的命中统计
+#[tauri::command]
+pub async fn get_ipc_cache_stats() -> CmdResult<Vec<IpcCacheStats>> {
+    Ok(ProxyRequestCache::global().stats())
 }
 
 #[tauri::command]
 pub async fn get_providers_proxies() -> CmdResult<serde_json::Value> {
     let cache = ProxyRequestCache::global();
-    let key = ProxyRequestCache::make_key("providers", "default");
     let value = cache
-        .get_or_fetch(key.clone(), PROVIDERS_REFRESH_INTERVAL, || async {
+        .get_or_fetch(CacheEndpoint::Providers, || async {
             let manager = IpcManager::global();
             manager.get_providers_proxies().await.unwrap_or_else(|e| {
                 logging!(error, Type::Cmd, "Failed to fetch provider proxies: {e}");
@@ -82,27 +196,17 @@ pub async fn get_providers_proxies() -> CmdResult<serde_json::Value> {
         .map(|m| m.is_empty())
         .unwrap_or(true)
     {
-        cache.map.remove(&key);
+        cache.invalidate(CacheEndpoint::Providers);
     }
 
     Ok(normalized)
 }
 
-/// 同步托盘和GUI的代理选择状态
+/// 同步托盘和GUI的代理选择状态，在后台重建托盘菜单，不阻塞调用方
 #[tauri::command]
 pub async fn sync_tray_proxy_selection() -> CmdResult<()> {
-    use crate::core::tray::Tray;
-
-    match Tray::global().update_menu().await {
-        Ok(_) => {
-            logging!(info, Type::Cmd, "Tray proxy selection synced successfully");
-            Ok(())
-        }
-        Err(e) => {
-            logging!(error, Type::Cmd, "Failed to sync tray proxy selection: {e}");
-            Err(e.to_string())
-        }
-    }
+    Tray::global().refresh_menu_async();
+    Ok(())
 }
 
 /// 更新代理选择并同步托盘和GUI状态
@@ -119,9 +223,7 @@ pub async fn update_proxy_and_sync(group: String, proxy: String) -> CmdResult<()
                 proxy
             );
 
-            let cache = crate::state::proxy::ProxyRequestCache::global();
-            let key = crate::state::proxy::ProxyRequestCache::make_key("proxies", "default");
-            cache.map.remove(&key);
+            super::selection_memory::remember_selection(&group, &proxy).await;
 
             if let Err(e) = Tray::global().update_menu().await {
                 logging!(error, Type::Cmd, "Failed to sync tray menu: {}", e);
@@ -151,10 +253,164 @@ pub async fn update_proxy_and_sync(group: String, proxy: String) -> CmdResult<()
                 proxy,
                 e
             );
-            Err(e.to_string())
+            Err(e.into())
         }
     }
 }
+
+/// 批量选择中单个分组的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkSelectionItem {
+    pub group: String,
+    pub proxy: String,
+    /// 分组原本已选中该节点时为 false
+    pub changed: bool,
+    pub error: Option<String>,
+}
+
+/// 一次性为多个分组选择节点，只拉取一次代理列表、同步一次托盘
+#[tauri::command]
+pub async fn bulk_update_proxy_choices(
+    selections: Vec<(String, String)>,
+) -> CmdResult<Vec<BulkSelectionItem>> {
+    let proxies = fetch_proxy_map().await?;
+    let plan = selections
+        .into_iter()
+        .map(|(group, proxy)| {
+            let error = match selectable_candidates(&proxies, &group) {
+                None => Some(format!("分组不存在或不可选择: {group}")),
+                Some(candidates) if !candidates.contains(&proxy) => {
+                    Some(format!("分组 {group} 中不存在节点 {proxy}"))
+                }
+                Some(_) => None,
+            };
+            let changed =
+                error.is_none() && current_selection(&proxies, &group) != Some(proxy.as_str());
+            BulkSelectionItem {
+                group,
+                proxy,
+                changed,
+                error,
+            }
+        })
+        .collect();
+    Ok(apply_bulk_selection(plan).await)
+}
+
+/// 在所有可选择分组中切换到指定地区延迟最低的节点
+/// region 为地区代码（如 HK、JP），没有该地区节点的分组会被跳过
+#[tauri::command]
+pub async fn select_region_in_all_groups(region: String) -> CmdResult<Vec<BulkSelectionItem>> {
+    let region = region.to_uppercase();
+    let proxies = fetch_proxy_map().await?;
+    let plan = proxies
+        .keys()
+        .filter_map(|group| {
+            let candidates = selectable_candidates(&proxies, group)?;
+            let same_region: Vec<String> = candidates
+                .into_iter()
+                .filter(|name| detect_region(name) == Some(region.as_str()))
+                .collect();
+            let proxy =
+                best_by_delay(&same_region, &proxies).or_else(|| same_region.first().cloned())?;
+            Some(BulkSelectionItem {
+                group: group.clone(),
+                changed: current_selection(&proxies, group) != Some(proxy.as_str()),
+                proxy,
+                error: None,
+            })
+        })
+        .collect();
+    Ok(apply_bulk_selection(plan).await)
+}
+
+/// 并发下发需要变更的选择，全部完成后统一记忆和同步
+pub(crate) async fn apply_bulk_selection(mut plan: Vec<BulkSelectionItem>) -> Vec<BulkSelectionItem> {
+    let manager = IpcManager::global();
+    let pending: Vec<usize> = plan
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.changed)
+        .map(|(index, _)| index)
+        .collect();
+    let results = join_all(
+        pending
+            .iter()
+            .map(|&index| manager.update_proxy(&plan[index].group, &plan[index].proxy)),
+    )
+    .await;
+
+    for (index, result) in pending.into_iter().zip(results) {
+        if let Err(e) = result {
+            plan[index].changed = false;
+            plan[index].error = Some(e.to_string());
+        }
+    }
+
+    let applied: Vec<(String, String)> = plan
+        .iter()
+        .filter(|item| item.changed)
+        .map(|item| (item.group.clone(), item.proxy.clone()))
+        .collect();
+    logging!(
+        info,
+        Type::Cmd,
+        "Bulk proxy selection applied to {} of {} groups",
+        applied.len(),
+        plan.len()
+    );
+
+    if !applied.is_empty() {
+        super::selection_memory::remember_selections(&applied).await;
+        Tray::global().refresh_menu_async();
+        if let Some(app_handle) = Handle::global().app_handle() {
+            let _ = app_handle.emit("verge://force-refresh-proxies", ());
+            let _ = app_handle.emit("verge://refresh-proxy-config", ());
+        }
+    }
+
+    plan
+}
+
+pub(crate) async fn fetch_proxy_map() -> CmdResult<serde_json::Map<String, serde_json::Value>> {
+    let proxies = IpcManager::global()
+        .get_proxies()
+        .await
+        .map_err(|e| format!("获取代理列表失败: {e}"))?;
+    Ok(proxies
+        .get("proxies")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// 返回 Selector 分组的候选节点，非 Selector 分组返回 None
+pub(crate) fn selectable_candidates(
+    proxies: &serde_json::Map<String, serde_json::Value>,
+    group: &str,
+) -> Option<Vec<String>> {
+    let info = proxies.get(group)?;
+    if info.get("type").and_then(|t| t.as_str()) != Some("Selector") {
+        return None;
+    }
+    Some(
+        info.get("all")
+            .and_then(|all| all.as_array())
+            .map(|all| {
+                all.iter()
+                    .filter_map(|name| name.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    )
+}
+
+pub(crate) fn current_selection<'a>(
+    proxies: &'a serde_json::Map<String, serde_json::Value>,
+    group: &str,
+) -> Option<&'a str> {
+    proxies.get(group)?.get("now")?.as_str()
+}
                 Type::Cmd,
                 "Proxy and sync completed successfully: {} -> {}",
                 group,
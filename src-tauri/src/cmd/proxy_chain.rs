@@ -0,0 +1,344 @@
+use super::{CmdResult, ErrorCode, WithErrorCode};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{collections::HashSet, fs, path::PathBuf};
+use tokio::sync::Mutex;
+
+/// 不能作为链路节点的类型
+const NON_HOP_TYPES: &[&str] = &["direct", "reject", "dns", "pass"];
+
+/// 基于 UDP 传输的协议，需要上一跳支持 UDP 转发
+const UDP_TRANSPORT_TYPES: &[&str] = &["hysteria", "hysteria2", "tuic", "wireguard"];
+
+/// 不支持 UDP 转发的协议
+const TCP_ONLY_TYPES: &[&str] = &["http", "snell", "ssh"];
+
+static CHAIN_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 链路实现方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChainMode {
+    /// 复制节点并设置 dialer-proxy，不影响原节点
+    #[default]
+    DialerProxy,
+    /// 生成 relay 类型的代理组
+    Relay,
+}
+
+/// 链式代理配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyChain {
+    #[serde(default)]
+    pub id: String,
+    pub name: String, // 生成的代理组名称
+    pub profile_uid: String,
+    pub hops: Vec<String>, // 节点名称，入口在前，出口在后
+    #[serde(default)]
+    pub mode: ChainMode,
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+/// 创建链式代理，校验通过后写入运行时配置，返回链路 ID
+#[tauri::command]
+pub async fn create_proxy_chain(mut chain: ProxyChain) -> CmdResult<String> {
+    let proxies = load_profile_proxies(&chain.profile_uid).await?;
+    validate_chain(&chain, &proxies)?;
+
+    let _guard = CHAIN_LOCK.lock().await;
+    let mut chains = load_chains().context("加载链式代理失败")?;
+    if chains
+        .iter()
+        .any(|c| c.profile_uid == chain.profile_uid && c.name == chain.name)
+    {
+        return Err(format!("链路名称已存在: {}", chain.name).into());
+    }
+
+    chain.id = nanoid::nanoid!();
+    chain.created_at = chrono::Utc::now().timestamp();
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[链式代理] 创建链路 {}: {}",
+        chain.name,
+        chain.hops.join(" -> ")
+    );
+    chains.push(chain.clone());
+    save_chains(&chains).context("保存链式代理失败")?;
+
+    if is_current(&chain.profile_uid).await
+        && let Err(e) = apply_runtime_config().await
+    {
+        chains.retain(|c| c.id != chain.id);
+        save_chains(&chains).context("保存链式代理失败")?;
+        apply_runtime_config().await.ok();
+        return Err(format!("链式代理配置校验失败: {}", e).into());
+    }
+
+    Ok(chain.id)
+}
+
+/// 获取链式代理列表，可按订阅过滤
+#[tauri::command]
+pub async fn list_proxy_chains(profile_uid: Option<String>) -> CmdResult<Vec<ProxyChain>> {
+    let chains = load_chains().context("加载链式代理失败")?;
+    Ok(chains
+        .into_iter()
+        .filter(|c| profile_uid.as_ref().is_none_or(|uid| &c.profile_uid == uid))
+        .collect())
+}
+
+/// 删除链式代理
+#[tauri::command]
+pub async fn delete_proxy_chain(id: String) -> CmdResult<()> {
+    let removed = {
+        let _guard = CHAIN_LOCK.lock().await;
+        let mut chains = load_chains().context("加载链式代理失败")?;
+        let removed = chains
+            .iter()
+            .position(|c| c.id == id)
+            .map(|i| chains.remove(i));
+        save_chains(&chains).context("保存链式代理失败")?;
+        removed
+    };
+
+    if let Some(chain) = removed
+        && chain.enabled
+        && is_current(&chain.profile_uid).await
+    {
+        apply_runtime_config().await?;
+    }
+    Ok(())
+}
+
+/// 将当前订阅的链式代理写入运行时配置
+/// 生成的代理组加入第一个 select 分组供用户选择
+pub async fn use_proxy_chains(mut config: Mapping) -> Mapping {
+    let Some(current) = Config::profiles().await.latest_ref().get_current() else {
+        return config;
+    };
+    let Ok(chains) = load_chains() else {
+        return config;
+    };
+    let chains: Vec<ProxyChain> = chains
+        .into_iter()
+        .filter(|c| c.enabled && c.profile_uid == current)
+        .collect();
+    if chains.is_empty() {
+        return config;
+    }
+
+    let proxies: Vec<Mapping> = config
+        .get("proxies")
+        .and_then(Value::as_sequence)
+        .map(|seq| seq.iter().filter_map(Value::as_mapping).cloned().collect())
+        .unwrap_or_default();
+
+    let mut new_proxies = Vec::new();
+    let mut new_groups = Vec::new();
+    for chain in &chains {
+        if validate_chain(chain, &proxies).is_err() {
+            logging!(
+                warn,
+                Type::Config,
+                true,
+                "[链式代理] 链路 {} 的节点已变化，跳过",
+                chain.name
+            );
+            continue;
+        }
+
+        let mut group = Mapping::new();
+        group.insert("name".into(), chain.name.as_str().into());
+        match chain.mode {
+            ChainMode::Relay => {
+                group.insert("type".into(), "relay".into());
+                group.insert(
+                    "proxies".into(),
+                    chain
+                        .hops
+                        .iter()
+                        .map(|h| Value::from(h.as_str()))
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+            ChainMode::DialerProxy => {
+                // 入口节点保持原样，后续节点复制一份并指向上一跳
+                let mut previous = chain.hops[0].clone();
+                for hop in &chain.hops[1..] {
+                    let Some(mut node) = find_proxy(&proxies, hop).cloned() else {
+                        continue;
+                    };
+                    let name = format!("{}@{}", hop, chain.name);
+                    node.insert("name".into(), name.as_str().into());
+                    node.insert("dialer-proxy".into(), previous.as_str().into());
+                    new_proxies.push(Value::Mapping(node));
+                    previous = name;
+                }
+                group.insert("type".into(), "select".into());
+                group.insert("proxies".into(), vec![Value::from(previous)].into());
+            }
+        }
+        new_groups.push((chain.name.clone(), Value::Mapping(group)));
+    }
+
+    if let Some(Value::Sequence(seq)) = config.get_mut("proxies") {
+        seq.extend(new_proxies);
+    }
+    let Some(Value::Sequence(proxy_groups)) = config.get_mut("proxy-groups") else {
+        return config;
+    };
+    if let Some(Value::Sequence(select_proxies)) = proxy_groups
+        .iter_mut()
+        .find(|g| g.get("type").and_then(Value::as_str) == Some("select"))
+        .and_then(|g| g.get_mut("proxies"))
+    {
+        for (name, _) in new_groups.iter().rev() {
+            select_proxies.insert(0, name.as_str().into());
+        }
+    }
+    proxy_groups.extend(new_groups.into_iter().map(|(_, group)| group));
+
+    config
+}
+
+// ===== 内部实现函数 =====
+
+/// 校验链路节点存在且协议可以串联
+fn validate_chain(chain: &ProxyChain, proxies: &[Mapping]) -> CmdResult<()> {
+    if chain.name.trim().is_empty() {
+        return Err("链路名称不能为空".into());
+    }
+    if chain.hops.len() < 2 {
+        return Err("链式代理至少需要 2 个节点".into());
+    }
+    if find_proxy(proxies, &chain.name).is_some() {
+        return Err(format!("链路名称与现有节点重名: {}", chain.name).into());
+    }
+    let mut seen = HashSet::new();
+    if let Some(hop) = chain.hops.iter().find(|h| !seen.insert(h.as_str())) {
+        return Err(format!("节点重复出现在链路中: {}", hop).into());
+    }
+
+    let mut previous: Option<(&str, &Mapping)> = None;
+    for hop in &chain.hops {
+        let node = find_proxy(proxies, hop).ok_or_else(|| format!("节点不存在: {}", hop))?;
+        let node_type = proxy_type(node);
+        if NON_HOP_TYPES.contains(&node_type.as_str()) {
+            return Err(format!("{} 类型的节点不能作为链路节点: {}", node_type, hop).into());
+        }
+        if chain.mode == ChainMode::DialerProxy && node.contains_key("dialer-proxy") {
+            return Err(format!("节点已设置 dialer-proxy: {}", hop).into());
+        }
+
+        if let Some((prev_name, prev)) = previous
+            && UDP_TRANSPORT_TYPES.contains(&node_type.as_str())
+            && !supports_udp(prev)
+        {
+            return Err(format!(
+                "{} 使用 UDP 传输，上一跳 {} 不支持 UDP 转发",
+                hop, prev_name
+            )
+            .into());
+        }
+        previous = Some((hop, node));
+    }
+    Ok(())
+}
+
+fn find_proxy<'a>(proxies: &'a [Mapping], name: &str) -> Option<&'a Mapping> {
+    proxies
+        .iter()
+        .find(|p| p.get("name").and_then(Value::as_str) == Some(name))
+}
+
+fn proxy_type(node: &Mapping) -> String {
+    node.get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// 节点是否支持转发 UDP
+fn supports_udp(node: &Mapping) -> bool {
+    let node_type = proxy_type(node);
+    if UDP_TRANSPORT_TYPES.contains(&node_type.as_str()) {
+        return true;
+    }
+    !TCP_ONLY_TYPES.contains(&node_type.as_str())
+        && node.get("udp").and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// 读取订阅文件中的节点
+async fn load_profile_proxies(uid: &str) -> CmdResult<Vec<Mapping>> {
+    let file = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        profiles
+            .get_item(&uid.to_string())
+            .with_code(ErrorCode::ProfileNotFound)?
+            .file
+            .clone()
+            .ok_or_else(|| "订阅文件不存在".to_string())?
+    };
+    let path = dirs::app_profiles_dir()?.join(file);
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .context("读取订阅文件失败")?;
+    let config: Mapping = serde_yaml_ng::from_str(&content).context("解析订阅文件失败")?;
+
+    Ok(config
+        .get("proxies")
+        .and_then(Value::as_sequence)
+        .map(|seq| seq.iter().filter_map(Value::as_mapping).cloned().collect())
+        .unwrap_or_default())
+}
+
+async fn is_current(uid: &str) -> bool {
+    Config::profiles()
+        .await
+        .latest_ref()
+        .get_current()
+        .as_deref()
+        == Some(uid)
+}
+
+/// 重新生成运行时配置，内核校验失败时返回错误
+async fn apply_runtime_config() -> Result<()> {
+    let (ok, msg) = CoreManager::global().update_config().await?;
+    if !ok {
+        anyhow::bail!(msg);
+    }
+    handle::Handle::refresh_clash();
+    Ok(())
+}
+
+/// 获取存储文件路径
+fn chains_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("proxy_chains.json"))
+}
+
+fn load_chains() -> Result<Vec<ProxyChain>> {
+    let path = chains_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_chains(chains: &[ProxyChain]) -> Result<()> {
+    fs::write(chains_path()?, serde_json::to_string_pretty(chains)?)?;
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use super::{CmdError, CmdResult, ErrorCode, WithErrorCode};
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+/// 订阅快速补丁
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickPatch {
+    pub yaml: String,
+    pub updated_at: i64,
+}
+
+/// 设置订阅的快速补丁，yaml 为空时删除
+/// 当前订阅会立即重新生成配置，内核验证失败时恢复原补丁
+#[tauri::command]
+pub async fn set_quick_patch(uid: String, yaml: String) -> CmdResult<()> {
+    let is_current = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        profiles
+            .get_item(&uid)
+            .with_code(ErrorCode::ProfileNotFound)?;
+        profiles.get_current().as_deref() == Some(uid.as_str())
+    };
+
+    if !yaml.trim().is_empty() {
+        parse_patch(&yaml)?;
+    }
+
+    let mut patches = load_patches().context("加载快速补丁失败")?;
+    let previous = if yaml.trim().is_empty() {
+        patches.remove(&uid)
+    } else {
+        patches.insert(
+            uid.clone(),
+            QuickPatch {
+                yaml,
+                updated_at: chrono::Utc::now().timestamp(),
+            },
+        )
+    };
+    save_patches(&patches).context("保存快速补丁失败")?;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[快速补丁] 已更新订阅 {} 的补丁",
+        uid
+    );
+
+    if !is_current {
+        return Ok(());
+    }
+
+    match CoreManager::global().update_config().await {
+        Ok((true, _)) => {
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Ok((false, error)) => {
+            match previous {
+                Some(previous) => patches.insert(uid, previous),
+                None => patches.remove(&uid),
+            };
+            save_patches(&patches).context("恢复快速补丁失败")?;
+            Err(CmdError::new(
+                ErrorCode::ConfigValidationFailed,
+                format!("补丁未通过内核验证，已恢复: {}", error),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 获取订阅的快速补丁
+#[tauri::command]
+pub async fn get_quick_patch(uid: String) -> CmdResult<Option<QuickPatch>> {
+    let mut patches = load_patches().context("加载快速补丁失败")?;
+    Ok(patches.remove(&uid))
+}
+
+/// 读取当前订阅的快速补丁，供 enhance 合并
+pub async fn current_quick_patch() -> Option<Mapping> {
+    let current = Config::profiles().await.latest_ref().get_current()?;
+    let patch = load_patches().ok()?.remove(&current)?;
+    match parse_patch(&patch.yaml) {
+        Ok(mapping) => Some(mapping),
+        Err(e) => {
+            logging!(warn, Type::Config, true, "[快速补丁] 忽略无效补丁: {}", e);
+            None
+        }
+    }
+}
+
+// ===== 内部实现函数 =====
+
+/// 解析并校验补丁，prepend-/append- 开头的键必须为列表
+fn parse_patch(yaml: &str) -> Result<Mapping, String> {
+    let mapping: Mapping =
+        serde_yaml_ng::from_str(yaml).map_err(|e| format!("补丁 YAML 格式错误: {}", e))?;
+    for (key, value) in &mapping {
+        let Some(key) = key.as_str() else {
+            return Err("补丁的键必须为字符串".to_string());
+        };
+        if (key.starts_with("prepend-") || key.starts_with("append-"))
+            && !matches!(value, Value::Sequence(_))
+        {
+            return Err(format!("{} 必须为列表", key));
+        }
+    }
+    Ok(mapping)
+}
+
+/// 获取存储文件路径
+fn patches_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("quick_patches.json"))
+}
+
+/// 加载所有快速补丁
+fn load_patches() -> Result<HashMap<String, QuickPatch>> {
+    let path = patches_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存所有快速补丁
+fn save_patches(patches: &HashMap<String, QuickPatch>) -> Result<()> {
+    let json_data = serde_json::to_string_pretty(patches)?;
+    fs::write(patches_path()?, json_data)?;
+    Ok(())
+}
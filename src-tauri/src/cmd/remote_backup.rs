@@ -0,0 +1,78 @@
+use super::CmdResult;
+use crate::{
+    config::*,
+    core::backup::{BackupProvider, BackupStorage, RemoteBackupFile, S3Client},
+    feat,
+    utils::secrets,
+    wrap_err,
+};
+
+/// 保存 S3 兼容存储配置
+#[tauri::command]
+pub async fn save_s3_backup_config(mut config: IS3Backup) -> CmdResult<()> {
+    // 密钥保存到系统钥匙串，配置中只记录引用
+    config.secret_access_key =
+        secrets::protect_secret(secrets::S3_SECRET_ACCESS_KEY, config.secret_access_key);
+    let patch = IVerge {
+        s3_backup: Some(config),
+        ..IVerge::default()
+    };
+    wrap_err!(feat::patch_verge(patch, false).await)?;
+    S3Client::global().reset();
+    Ok(())
+}
+
+/// 设置默认的远程备份存储
+#[tauri::command]
+pub async fn set_remote_backup_provider(provider: BackupProvider) -> CmdResult<()> {
+    let provider = match provider {
+        BackupProvider::WebDav => "webdav",
+        BackupProvider::S3 => "s3",
+    };
+    let patch = IVerge {
+        remote_backup_provider: Some(provider.to_string()),
+        ..IVerge::default()
+    };
+    wrap_err!(feat::patch_verge(patch, false).await)
+}
+
+/// 创建备份并上传到远程存储，返回上传的文件名
+/// provider 为空时使用设置中的默认存储
+#[tauri::command]
+pub async fn sync_to_remote(provider: Option<BackupProvider>) -> CmdResult<String> {
+    let storage = resolve_provider(provider).await.storage();
+    wrap_err!(feat::create_backup_and_upload(storage).await)
+}
+
+/// 从远程存储下载并恢复备份
+#[tauri::command]
+pub async fn sync_from_remote(filename: String, provider: Option<BackupProvider>) -> CmdResult<()> {
+    let storage = resolve_provider(provider).await.storage();
+    wrap_err!(feat::restore_remote_backup(storage, filename).await)
+}
+
+/// 列出远程存储上的备份文件
+#[tauri::command]
+pub async fn list_remote_backups(
+    provider: Option<BackupProvider>,
+) -> CmdResult<Vec<RemoteBackupFile>> {
+    let storage = resolve_provider(provider).await.storage();
+    wrap_err!(feat::list_remote_backup(storage).await)
+}
+
+/// 删除远程存储上的备份文件
+#[tauri::command]
+pub async fn delete_remote_backup(
+    filename: String,
+    provider: Option<BackupProvider>,
+) -> CmdResult<()> {
+    let storage = resolve_provider(provider).await.storage();
+    wrap_err!(feat::delete_remote_backup(storage, filename).await)
+}
+
+async fn resolve_provider(provider: Option<BackupProvider>) -> BackupProvider {
+    match provider {
+        Some(provider) => provider,
+        None => BackupProvider::current().await,
+    }
+}
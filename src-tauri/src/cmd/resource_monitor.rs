@@ -0,0 +1,391 @@
+use super::CmdResult;
+use crate::{
+    config::Config,
+    core::{CoreManager, RunningMode, handle},
+    ipc::{self, IpcManager},
+    logging,
+    process::AsyncHandler,
+    state::proxy::ProxyRequestCache,
+    utils::{
+        dirs,
+        logging::Type,
+        notification::{NotificationEvent, notify_event},
+    },
+};
+use anyhow::{Context, Result};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const RESOURCE_MONITOR_FILE: &str = "resource_monitor.json";
+/// 内存中保留的采样数，默认间隔下约 6 小时
+const MAX_SAMPLES: usize = 720;
+const MAX_MITIGATIONS: usize = 50;
+const MIN_INTERVAL_SECS: u64 = 5;
+const MB: u64 = 1024 * 1024;
+/// 服务模式下负责启动内核的服务进程名称前缀
+const SERVICE_PROCESS_PREFIX: &str = "clash-verge-service";
+
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+static CONFIG: Lazy<RwLock<Option<ResourceMonitorConfig>>> = Lazy::new(|| RwLock::new(None));
+static MONITOR: Lazy<Mutex<MonitorState>> = Lazy::new(|| Mutex::new(MonitorState::default()));
+
+/// 资源监控设置，CPU 为单核百分比，多核时可超过 100
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceMonitorConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub app_memory_limit_mb: u64,
+    pub core_memory_limit_mb: u64,
+    pub core_cpu_limit_percent: f32,
+    /// 连续超限多少次采样后才处理，避免瞬时波动
+    pub sustained_samples: u32,
+    /// 关闭时只提示，不自动执行 GC 和清理连接
+    pub auto_mitigate: bool,
+}
+
+impl Default for ResourceMonitorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 30,
+            app_memory_limit_mb: 1024,
+            core_memory_limit_mb: 1536,
+            core_cpu_limit_percent: 150.0,
+            sustained_samples: 4,
+            auto_mitigate: false,
+        }
+    }
+}
+
+/// 单次采样，内存单位为字节
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceSample {
+    pub timestamp: i64,
+    pub app_memory: u64,
+    pub app_cpu: f32,
+    pub core_pid: Option<u32>,
+    pub core_memory: Option<u64>,
+    pub core_cpu: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MitigationAction {
+    ClashGc,
+    CloseConnections,
+    TrimLogBuffer,
+    SuggestCoreRestart,
+}
+
+/// 已执行的处理措施
+#[derive(Debug, Clone, Serialize)]
+pub struct MitigationRecord {
+    pub timestamp: i64,
+    pub action: MitigationAction,
+    pub reason: String,
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsageHistory {
+    pub samples: Vec<ResourceSample>,
+    pub mitigations: Vec<MitigationRecord>,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    samples: VecDeque<ResourceSample>,
+    mitigations: VecDeque<MitigationRecord>,
+    core_over: u32,
+    core_step: usize, // 内核持续超限时逐级升级处理
+    app_over: u32,
+    app_trimmed: bool,
+}
+
+/// 内核持续超限时依次执行的措施，最后提示重启
+const CORE_ESCALATION: [MitigationAction; 3] = [
+    MitigationAction::ClashGc,
+    MitigationAction::CloseConnections,
+    MitigationAction::SuggestCoreRestart,
+];
+
+/// 获取资源占用历史，minutes 为空时返回全部
+#[tauri::command]
+pub async fn get_resource_usage_history(minutes: Option<u32>) -> CmdResult<ResourceUsageHistory> {
+    let since = minutes.map(|minutes| Local::now().timestamp() - i64::from(minutes) * 60);
+    let state = MONITOR.lock();
+    Ok(ResourceUsageHistory {
+        samples: state
+            .samples
+            .iter()
+            .filter(|sample| since.is_none_or(|since| sample.timestamp >= since))
+            .cloned()
+            .collect(),
+        mitigations: state.mitigations.iter().cloned().collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_resource_monitor_config() -> CmdResult<ResourceMonitorConfig> {
+    Ok(current_config())
+}
+
+#[tauri::command]
+pub async fn set_resource_monitor_config(config: ResourceMonitorConfig) -> CmdResult<()> {
+    if config.interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("采样间隔至少为 {MIN_INTERVAL_SECS} 秒").into());
+    }
+    if config.sustained_samples == 0 {
+        return Err("连续超限次数必须大于 0".into());
+    }
+    save_config(&config).context("保存资源监控设置失败")?;
+    logging!(
+        info,
+        Type::System,
+        true,
+        "[资源监控] 设置已更新: 启用={}, 间隔 {} 秒, 自动处理={}",
+        config.enabled,
+        config.interval_secs,
+        config.auto_mitigate
+    );
+    *CONFIG.write() = Some(config);
+    Ok(())
+}
+
+/// 手动执行一项处理措施
+#[tauri::command]
+pub async fn run_resource_mitigation(action: MitigationAction) -> CmdResult<MitigationRecord> {
+    Ok(mitigate(action, "手动执行".into(), None).await)
+}
+
+/// 启动资源监控
+pub fn init_resource_monitor() {
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut system = System::new();
+        let app_pid = sysinfo::get_current_pid().ok();
+        loop {
+            let config = current_config();
+            tokio::time::sleep(Duration::from_secs(
+                config.interval_secs.max(MIN_INTERVAL_SECS),
+            ))
+            .await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+            if !config.enabled {
+                continue;
+            }
+
+            let core_name = Config::verge()
+                .await
+                .latest_ref()
+                .clash_core
+                .clone()
+                .unwrap_or_else(|| "verge-mihomo".into());
+            let sample = take_sample(&mut system, app_pid, &core_name);
+            for (action, reason) in record_sample(sample, &config) {
+                mitigate(action, reason, Some(&config)).await;
+            }
+        }
+    });
+}
+
+// ===== 内部实现函数 =====
+
+fn take_sample(system: &mut System, app_pid: Option<Pid>, core_name: &str) -> ResourceSample {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_memory().with_cpu(),
+    );
+
+    let app = app_pid.and_then(|pid| system.process(pid));
+    let core = find_core(system, core_name);
+
+    ResourceSample {
+        timestamp: Local::now().timestamp(),
+        app_memory: app.map_or(0, |process| process.memory()),
+        app_cpu: app.map_or(0.0, |process| process.cpu_usage()),
+        core_pid: core.map(|process| process.pid().as_u32()),
+        core_memory: core.map(|process| process.memory()),
+        core_cpu: core.map(|process| process.cpu_usage()),
+    }
+}
+
+/// 只匹配本程序管理的内核：Sidecar 模式使用核心管理器记录的 PID，
+/// 服务模式取由服务进程启动的同名进程，避免误处理其他客户端的内核
+fn find_core<'a>(system: &'a System, core_name: &str) -> Option<&'a sysinfo::Process> {
+    let manager = CoreManager::global();
+    if let Some(pid) = manager.sidecar_pid() {
+        return system.process(Pid::from_u32(pid));
+    }
+    if manager.get_running_mode() != RunningMode::Service {
+        return None;
+    }
+
+    let process_name = |process: &sysinfo::Process| {
+        process
+            .name()
+            .to_string_lossy()
+            .trim_end_matches(".exe")
+            .to_string()
+    };
+    system.processes().values().find(|process| {
+        process_name(process) == core_name
+            && process
+                .parent()
+                .and_then(|parent| system.process(parent))
+                .is_some_and(|parent| process_name(parent).starts_with(SERVICE_PROCESS_PREFIX))
+    })
+}
+
+/// 记录采样并返回需要执行的措施
+fn record_sample(
+    sample: ResourceSample,
+    config: &ResourceMonitorConfig,
+) -> Vec<(MitigationAction, String)> {
+    let mut actions = Vec::new();
+    let mut state = MONITOR.lock();
+
+    let core_memory_mb = sample.core_memory.unwrap_or(0) / MB;
+    let core_cpu = sample.core_cpu.unwrap_or(0.0);
+    if core_memory_mb > config.core_memory_limit_mb || core_cpu > config.core_cpu_limit_percent {
+        state.core_over += 1;
+    } else {
+        state.core_over = 0;
+        state.core_step = 0;
+    }
+    if state.core_over >= config.sustained_samples && state.core_step < CORE_ESCALATION.len() {
+        let step = if config.auto_mitigate {
+            state.core_step
+        } else {
+            CORE_ESCALATION.len() - 1
+        };
+        actions.push((
+            CORE_ESCALATION[step],
+            format!("内核内存 {core_memory_mb} MB，CPU {core_cpu:.0}%"),
+        ));
+        state.core_over = 0;
+        state.core_step = step + 1;
+    }
+
+    let app_memory_mb = sample.app_memory / MB;
+    if app_memory_mb > config.app_memory_limit_mb {
+        state.app_over += 1;
+    } else {
+        state.app_over = 0;
+        state.app_trimmed = false;
+    }
+    if config.auto_mitigate && state.app_over >= config.sustained_samples && !state.app_trimmed {
+        actions.push((
+            MitigationAction::TrimLogBuffer,
+            format!("应用内存 {app_memory_mb} MB"),
+        ));
+        state.app_trimmed = true;
+    }
+
+    state.samples.push_back(sample);
+    while state.samples.len() > MAX_SAMPLES {
+        state.samples.pop_front();
+    }
+    actions
+}
+
+async fn mitigate(
+    action: MitigationAction,
+    reason: String,
+    config: Option<&ResourceMonitorConfig>,
+) -> MitigationRecord {
+    let result = match action {
+        MitigationAction::ClashGc => IpcManager::global().gc().await.map_err(|e| e.to_string()),
+        MitigationAction::CloseConnections => IpcManager::global()
+            .close_all_connections()
+            .await
+            .map_err(|e| e.to_string()),
+        MitigationAction::TrimLogBuffer => {
+            ipc::clear_logs().await;
+            ProxyRequestCache::global().clean_default_keys();
+            Ok(())
+        }
+        MitigationAction::SuggestCoreRestart => {
+            if let Some(app_handle) = handle::Handle::global().app_handle() {
+                notify_event(
+                    app_handle,
+                    NotificationEvent::CoreResourceHigh { usage: &reason },
+                )
+                .await;
+            }
+            Ok(())
+        }
+    };
+
+    let record = MitigationRecord {
+        timestamp: Local::now().timestamp(),
+        action,
+        reason,
+        success: result.is_ok(),
+        message: result.err(),
+    };
+    logging!(
+        warn,
+        Type::System,
+        true,
+        "[资源监控] 执行 {:?}（{}），自动={}，结果: {}",
+        record.action,
+        record.reason,
+        config.is_some_and(|config| config.auto_mitigate),
+        record.message.as_deref().unwrap_or("成功")
+    );
+
+    let mut state = MONITOR.lock();
+    state.mitigations.push_back(record.clone());
+    while state.mitigations.len() > MAX_MITIGATIONS {
+        state.mitigations.pop_front();
+    }
+    record
+}
+
+fn current_config() -> ResourceMonitorConfig {
+    if let Some(config) = CONFIG.read().as_ref() {
+        return config.clone();
+    }
+    let config = load_config().unwrap_or_else(|e| {
+        logging!(warn, Type::System, true, "[资源监控] 加载设置失败: {}", e);
+        ResourceMonitorConfig::default()
+    });
+    *CONFIG.write() = Some(config.clone());
+    config
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(RESOURCE_MONITOR_FILE))
+}
+
+fn load_config() -> Result<ResourceMonitorConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(ResourceMonitorConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_config(config: &ResourceMonitorConfig) -> Result<()> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
@@ -0,0 +1,858 @@
+use super::{CmdResult, app_routes, custom_rules};
+use crate::{
+    config::{Config, PrfItem},
+    ipc::IpcManager,
+    utils::{dirs, help},
+};
+use regex::Regex;
+use serde::Serialize;
+use serde_yaml_ng::{Mapping, Sequence, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 代理组嵌套的最大深度，避免循环引用
+const MAX_GROUP_DEPTH: usize = 16;
+
+/// 规则来源
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSourceKind {
+    CustomRules, // 自定义规则
+    AppRoutes,   // 分应用代理
+    RulesItem,   // 订阅关联的规则增强
+    Merge,       // 订阅关联的 Merge
+    GlobalMerge, // 全局 Merge
+    Profile,     // 订阅本身
+    Generated,   // 脚本或内建增强生成
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleSource {
+    pub kind: RuleSourceKind,
+    pub uid: Option<String>,
+    pub name: Option<String>,
+}
+
+/// 命中的规则
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchedRule {
+    pub index: usize, // 在运行时规则中的位置
+    pub rule: String,
+    pub rule_type: String,
+    pub payload: String,
+    pub target: String,
+    pub sub_rule: Option<String>, // SUB-RULE 中命中的子规则
+    pub source: RuleSource,
+}
+
+/// 无法在本地判断而跳过的规则，内核实际匹配时可能命中
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedRule {
+    pub index: usize,
+    pub rule: String,
+    pub reason: String,
+}
+
+/// 路由解释结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteExplanation {
+    pub host: String,
+    pub port: u16,
+    pub process: Option<String>,
+    pub resolved_ips: Vec<String>, // 使用系统 DNS 解析，可能与内核 DNS 结果不同
+    pub matched: Option<MatchedRule>,
+    pub proxy_chain: Vec<String>, // 从命中策略到最终出站的选择路径
+    pub outbound: Option<String>,
+    pub skipped: Vec<SkippedRule>,
+}
+
+/// 按内核的匹配顺序在本地评估当前规则，解释连接会走哪个策略
+#[tauri::command]
+pub async fn explain_route(
+    host: String,
+    port: u16,
+    process: Option<String>,
+) -> CmdResult<RouteExplanation> {
+    let host = host
+        .trim()
+        .trim_matches(['[', ']'])
+        .trim_end_matches('.')
+        .to_lowercase();
+    if host.is_empty() {
+        return Err("目标地址不能为空".into());
+    }
+    let process = process
+        .map(|process| process.trim().to_string())
+        .filter(|process| !process.is_empty());
+
+    let config = Config::runtime()
+        .await
+        .latest_ref()
+        .config
+        .clone()
+        .ok_or("运行时配置不存在")?;
+    let rules: Vec<String> = config
+        .get("rules")
+        .and_then(Value::as_sequence)
+        .map(|rules| {
+            rules
+                .iter()
+                .filter_map(|rule| rule.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let ctx = RouteContext::new(&host, port, process.as_deref()).await;
+    let mut evaluator = Evaluator {
+        ctx: &ctx,
+        config: &config,
+        providers: HashMap::new(),
+    };
+
+    let mut skipped = Vec::new();
+    let mut matched = None;
+    for (index, rule) in rules.iter().enumerate() {
+        match evaluator.eval_rule(rule) {
+            RuleOutcome::Match { target, sub_rule } => {
+                let parts = split_top_level(rule);
+                matched = Some((index, rule.clone(), parts, target, sub_rule));
+                break;
+            }
+            RuleOutcome::NoMatch => {}
+            RuleOutcome::Unknown(reason) => skipped.push(SkippedRule {
+                index,
+                rule: rule.clone(),
+                reason,
+            }),
+        }
+    }
+
+    let matched = match matched {
+        Some((index, rule, parts, target, sub_rule)) => {
+            let source = rule_source(&rule).await;
+            Some(MatchedRule {
+                index,
+                rule_type: parts.first().map(|s| s.to_string()).unwrap_or_default(),
+                payload: if parts.len() > 2 {
+                    parts[1].to_string()
+                } else {
+                    String::new()
+                },
+                rule,
+                target,
+                sub_rule,
+                source,
+            })
+        }
+        None => None,
+    };
+    let proxy_chain = match &matched {
+        Some(matched) => resolve_proxy_chain(&matched.target).await,
+        None => Vec::new(),
+    };
+
+    Ok(RouteExplanation {
+        host,
+        port,
+        process,
+        resolved_ips: ctx.ips.iter().map(ToString::to_string).collect(),
+        outbound: proxy_chain.last().cloned(),
+        matched,
+        proxy_chain,
+        skipped,
+    })
+}
+
+// ===== 内部实现函数 =====
+
+struct RouteContext {
+    domain: Option<String>, // 目标为 IP 时为空
+    ips: Vec<IpAddr>,
+    port: u16,
+    process_name: Option<String>,
+    process_path: Option<String>,
+}
+
+impl RouteContext {
+    async fn new(host: &str, port: u16, process: Option<&str>) -> Self {
+        let (domain, ips) = match host.parse::<IpAddr>() {
+            Ok(ip) => (None, vec![ip]),
+            Err(_) => {
+                let ips = tokio::time::timeout(
+                    RESOLVE_TIMEOUT,
+                    tokio::net::lookup_host((host.to_string(), port)),
+                )
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .map(|addrs| {
+                    let mut ips: Vec<IpAddr> = addrs.map(|addr| addr.ip()).collect();
+                    ips.sort_unstable();
+                    ips.dedup();
+                    ips
+                })
+                .unwrap_or_default();
+                (Some(host.to_string()), ips)
+            }
+        };
+
+        // 传入路径时同时用于 PROCESS-PATH 和 PROCESS-NAME
+        let (process_name, process_path) = match process {
+            Some(process) if process.contains(['/', '\\']) => (
+                Path::new(&process.replace('\\', "/"))
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string()),
+                Some(process.to_string()),
+            ),
+            Some(process) => (Some(process.to_string()), None),
+            None => (None, None),
+        };
+
+        Self {
+            domain,
+            ips,
+            port,
+            process_name,
+            process_path,
+        }
+    }
+
+    /// IP 类规则可用的地址，域名目标在 no-resolve 时不解析
+    fn ips_for(&self, no_resolve: bool) -> &[IpAddr] {
+        if self.domain.is_some() && no_resolve {
+            &[]
+        } else {
+            &self.ips
+        }
+    }
+}
+
+enum RuleOutcome {
+    Match {
+        target: String,
+        sub_rule: Option<String>,
+    },
+    NoMatch,
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Eval {
+    Match,
+    NoMatch,
+    Unknown(String),
+}
+
+impl From<bool> for Eval {
+    fn from(value: bool) -> Self {
+        if value { Self::Match } else { Self::NoMatch }
+    }
+}
+
+enum ProviderBehavior {
+    Domain,
+    IpCidr,
+    Classical,
+}
+
+struct ProviderRules {
+    behavior: ProviderBehavior,
+    entries: Vec<String>,
+}
+
+struct Evaluator<'a> {
+    ctx: &'a RouteContext,
+    config: &'a Mapping,
+    providers: HashMap<String, Result<ProviderRules, String>>,
+}
+
+impl Evaluator<'_> {
+    fn eval_rule(&mut self, rule: &str) -> RuleOutcome {
+        let parts = split_top_level(rule);
+        let Some(rule_type) = parts.first().map(|s| s.to_uppercase()) else {
+            return RuleOutcome::NoMatch;
+        };
+        match rule_type.as_str() {
+            "MATCH" | "FINAL" => RuleOutcome::Match {
+                target: parts.get(1).map(|s| s.to_string()).unwrap_or_default(),
+                sub_rule: None,
+            },
+            "SUB-RULE" if parts.len() >= 3 => {
+                let condition = strip_parens(parts[1]).unwrap_or(parts[1]);
+                match self.eval_condition_str(condition) {
+                    Eval::Match => self.eval_sub_rules(parts[2]),
+                    Eval::NoMatch => RuleOutcome::NoMatch,
+                    Eval::Unknown(reason) => RuleOutcome::Unknown(reason),
+                }
+            }
+            _ if parts.len() >= 3 => match self.eval_condition(&rule_type, parts[1], &parts[3..]) {
+                Eval::Match => RuleOutcome::Match {
+                    target: parts[2].to_string(),
+                    sub_rule: None,
+                },
+                Eval::NoMatch => RuleOutcome::NoMatch,
+                Eval::Unknown(reason) => RuleOutcome::Unknown(reason),
+            },
+            _ => RuleOutcome::Unknown("规则格式无效".into()),
+        }
+    }
+
+    /// 子规则全部未命中时回到上级规则继续匹配
+    fn eval_sub_rules(&mut self, name: &str) -> RuleOutcome {
+        let rules: Vec<String> = self
+            .config
+            .get("sub-rules")
+            .and_then(|sub_rules| sub_rules.get(name))
+            .and_then(Value::as_sequence)
+            .map(|rules| {
+                rules
+                    .iter()
+                    .filter_map(|rule| rule.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut unknown = None;
+        for rule in rules {
+            match self.eval_rule(&rule) {
+                RuleOutcome::Match { target, .. } => {
+                    return RuleOutcome::Match {
+                        target,
+                        sub_rule: Some(rule),
+                    };
+                }
+                RuleOutcome::NoMatch => {}
+                RuleOutcome::Unknown(reason) => {
+                    unknown.get_or_insert(format!("子规则 {rule}: {reason}"));
+                }
+            }
+        }
+        unknown.map_or(RuleOutcome::NoMatch, RuleOutcome::Unknown)
+    }
+
+    fn eval_condition_str(&mut self, condition: &str) -> Eval {
+        let parts = split_top_level(condition);
+        match parts.as_slice() {
+            [rule_type, payload, params @ ..] => {
+                self.eval_condition(&rule_type.to_uppercase(), payload, params)
+            }
+            _ => Eval::Unknown(format!("条件格式无效: {condition}")),
+        }
+    }
+
+    fn eval_condition(&mut self, rule_type: &str, payload: &str, params: &[&str]) -> Eval {
+        let ctx = self.ctx;
+        let no_resolve = params.iter().any(|p| p.eq_ignore_ascii_case("no-resolve"));
+        let domain = ctx.domain.as_deref();
+        match rule_type {
+            "DOMAIN" => domain.is_some_and(|d| d == payload.to_lowercase()).into(),
+            "DOMAIN-SUFFIX" => domain
+                .is_some_and(|d| match_suffix(d, &payload.to_lowercase()))
+                .into(),
+            "DOMAIN-KEYWORD" => domain
+                .is_some_and(|d| d.contains(&payload.to_lowercase()))
+                .into(),
+            "DOMAIN-WILDCARD" => domain
+                .is_some_and(|d| match_wildcard(d, &payload.to_lowercase()))
+                .into(),
+            "DOMAIN-REGEX" => match_regex(payload, domain),
+            "IP-CIDR" | "IP-CIDR6" => match cidr_matcher(payload) {
+                Some(matches) => ctx.ips_for(no_resolve).iter().any(|ip| matches(*ip)).into(),
+                None => Eval::Unknown(format!("CIDR 无效: {payload}")),
+            },
+            "IP-SUFFIX" => match suffix_matcher(payload) {
+                Some(matches) => ctx.ips_for(no_resolve).iter().any(|ip| matches(*ip)).into(),
+                None => Eval::Unknown(format!("IP 后缀无效: {payload}")),
+            },
+            "GEOIP" if payload.eq_ignore_ascii_case("LAN") => {
+                ctx.ips_for(no_resolve).iter().any(is_lan).into()
+            }
+            "GEOIP" | "IP-ASN" => {
+                if ctx.ips_for(no_resolve).is_empty() {
+                    Eval::NoMatch
+                } else {
+                    Eval::Unknown("需要 GeoIP/ASN 数据库".into())
+                }
+            }
+            "GEOSITE" => match domain {
+                Some(_) => Eval::Unknown("需要 GeoSite 数据库".into()),
+                None => Eval::NoMatch,
+            },
+            "DST-PORT" => match match_port(payload, ctx.port) {
+                Some(matched) => matched.into(),
+                None => Eval::Unknown(format!("端口格式无效: {payload}")),
+            },
+            "NETWORK" => payload.eq_ignore_ascii_case("tcp").into(),
+            "PROCESS-NAME" => match &ctx.process_name {
+                Some(name) => name_eq(name, payload).into(),
+                None => Eval::NoMatch,
+            },
+            "PROCESS-PATH" => match &ctx.process_path {
+                Some(path) => name_eq(path, payload).into(),
+                None => Eval::NoMatch,
+            },
+            "PROCESS-NAME-REGEX" => match &ctx.process_name {
+                Some(name) => match_regex(payload, Some(name)),
+                None => Eval::NoMatch,
+            },
+            "PROCESS-PATH-REGEX" => match &ctx.process_path {
+                Some(path) => match_regex(payload, Some(path)),
+                None => Eval::NoMatch,
+            },
+            "RULE-SET" => self.eval_rule_set(payload, no_resolve),
+            "AND" | "OR" | "NOT" => self.eval_logic(rule_type, payload),
+            "SRC-IP-CIDR" | "SRC-IP-SUFFIX" | "SRC-IP-ASN" | "SRC-GEOIP" | "SRC-PORT"
+            | "IN-PORT" | "IN-TYPE" | "IN-USER" | "IN-NAME" | "UID" | "DSCP" => {
+                Eval::Unknown(format!("{rule_type} 依赖入站连接信息"))
+            }
+            _ => Eval::Unknown(format!("不支持的规则类型 {rule_type}")),
+        }
+    }
+
+    fn eval_logic(&mut self, rule_type: &str, payload: &str) -> Eval {
+        let Some(inner) = strip_parens(payload) else {
+            return Eval::Unknown(format!("逻辑规则格式无效: {payload}"));
+        };
+        let results: Vec<Eval> = split_top_level(inner)
+            .into_iter()
+            .map(|item| match strip_parens(item) {
+                Some(condition) => self.eval_condition_str(condition),
+                None => Eval::Unknown(format!("逻辑规则格式无效: {item}")),
+            })
+            .collect();
+        match rule_type {
+            "AND" if results.contains(&Eval::NoMatch) => return Eval::NoMatch,
+            "OR" if results.contains(&Eval::Match) => return Eval::Match,
+            _ => {}
+        }
+        if let Some(reason) = results.iter().find_map(|result| match result {
+            Eval::Unknown(reason) => Some(reason.clone()),
+            _ => None,
+        }) {
+            return Eval::Unknown(reason);
+        }
+        match (rule_type, results.as_slice()) {
+            ("AND", _) => Eval::Match,
+            ("OR", _) => Eval::NoMatch,
+            (_, [Eval::Match]) => Eval::NoMatch,
+            (_, [Eval::NoMatch]) => Eval::Match,
+            _ => Eval::Unknown(format!("NOT 规则只能包含一个条件: {payload}")),
+        }
+    }
+
+    fn eval_rule_set(&mut self, name: &str, no_resolve: bool) -> Eval {
+        // 暂时取出已加载的规则集，分类规则需要递归评估
+        let provider = self
+            .providers
+            .remove(name)
+            .unwrap_or_else(|| load_provider(self.config, name));
+        let result = match &provider {
+            Ok(rules) => self.eval_provider(name, rules, no_resolve),
+            Err(reason) => Eval::Unknown(format!("规则集 {name}: {reason}")),
+        };
+        self.providers.insert(name.to_string(), provider);
+        result
+    }
+
+    fn eval_provider(&mut self, name: &str, rules: &ProviderRules, no_resolve: bool) -> Eval {
+        let ctx = self.ctx;
+        match rules.behavior {
+            ProviderBehavior::Domain => ctx
+                .domain
+                .as_deref()
+                .is_some_and(|domain| {
+                    rules
+                        .entries
+                        .iter()
+                        .any(|entry| match_domain_entry(domain, entry))
+                })
+                .into(),
+            ProviderBehavior::IpCidr => {
+                let ips = ctx.ips_for(no_resolve);
+                rules
+                    .entries
+                    .iter()
+                    .filter_map(|entry| cidr_matcher(entry))
+                    .any(|matches| ips.iter().any(|ip| matches(*ip)))
+                    .into()
+            }
+            ProviderBehavior::Classical => {
+                let mut unknown = None;
+                for entry in &rules.entries {
+                    let parts = split_top_level(entry);
+                    let [rule_type, payload, params @ ..] = parts.as_slice() else {
+                        continue;
+                    };
+                    let mut params = params.to_vec();
+                    if no_resolve {
+                        params.push("no-resolve");
+                    }
+                    match self.eval_condition(&rule_type.to_uppercase(), payload, &params) {
+                        Eval::Match => return Eval::Match,
+                        Eval::NoMatch => {}
+                        Eval::Unknown(reason) => {
+                            unknown.get_or_insert(format!("规则集 {name}: {reason}"));
+                        }
+                    }
+                }
+                unknown.map_or(Eval::NoMatch, Eval::Unknown)
+            }
+        }
+    }
+}
+
+/// 读取 rule-provider 的本地内容，mrs 等二进制格式无法解析
+fn load_provider(config: &Mapping, name: &str) -> Result<ProviderRules, String> {
+    let provider = config
+        .get("rule-providers")
+        .and_then(|providers| providers.get(name))
+        .and_then(Value::as_mapping)
+        .ok_or("规则集不存在")?;
+    let field = |key: &str| {
+        provider
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    };
+
+    let behavior = match field("behavior").to_lowercase().as_str() {
+        "domain" => ProviderBehavior::Domain,
+        "ipcidr" => ProviderBehavior::IpCidr,
+        "classical" => ProviderBehavior::Classical,
+        other => return Err(format!("不支持的规则集类型 {other}")),
+    };
+
+    let entries = if field("type") == "inline" {
+        sequence_entries(provider.get("payload").and_then(Value::as_sequence))
+    } else {
+        let path = field("path");
+        if path.is_empty() {
+            return Err("未指定本地路径".into());
+        }
+        let path = if Path::new(path).is_absolute() {
+            PathBuf::from(path)
+        } else {
+            dirs::app_home_dir().map_err(|e| e.to_string())?.join(path)
+        };
+        let content = fs::read_to_string(&path).map_err(|_| "规则集尚未下载".to_string())?;
+        match field("format").to_lowercase().as_str() {
+            "text" => content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect(),
+            "" | "yaml" => {
+                let mapping: Mapping =
+                    serde_yaml_ng::from_str(&content).map_err(|e| format!("解析失败: {e}"))?;
+                sequence_entries(mapping.get("payload").and_then(Value::as_sequence))
+            }
+            other => return Err(format!("无法解析 {other} 格式")),
+        }
+    };
+
+    Ok(ProviderRules { behavior, entries })
+}
+
+fn sequence_entries(sequence: Option<&Sequence>) -> Vec<String> {
+    sequence
+        .map(|seq| {
+            seq.iter()
+                .filter_map(|entry| entry.as_str().map(|s| s.trim().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 按顶层逗号拆分规则，括号内的逗号不拆分
+fn split_top_level(rule: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in rule.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(rule[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(rule[start..].trim());
+    parts
+}
+
+fn strip_parens(value: &str) -> Option<&str> {
+    value
+        .trim()
+        .strip_prefix('(')
+        .and_then(|value| value.strip_suffix(')'))
+}
+
+fn match_suffix(domain: &str, suffix: &str) -> bool {
+    domain == suffix
+        || domain
+            .strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// `*` 匹配任意字符，`?` 匹配单个字符
+fn match_wildcard(domain: &str, pattern: &str) -> bool {
+    let pattern = format!(
+        "^{}$",
+        regex::escape(pattern)
+            .replace(r"\*", ".*")
+            .replace(r"\?", ".")
+    );
+    Regex::new(&pattern).is_ok_and(|re| re.is_match(domain))
+}
+
+fn match_regex(pattern: &str, value: Option<&str>) -> Eval {
+    let Some(value) = value else {
+        return Eval::NoMatch;
+    };
+    match Regex::new(pattern) {
+        Ok(re) => re.is_match(value).into(),
+        Err(_) => Eval::Unknown(format!("正则无效: {pattern}")),
+    }
+}
+
+/// 规则集域名条目：`+.` 匹配自身和子域名，`.` 仅匹配子域名，`*.` 匹配一级子域名
+fn match_domain_entry(domain: &str, entry: &str) -> bool {
+    let entry = entry.to_lowercase();
+    if let Some(suffix) = entry.strip_prefix("+.") {
+        match_suffix(domain, suffix)
+    } else if let Some(suffix) = entry.strip_prefix("*.") {
+        domain
+            .strip_suffix(suffix)
+            .and_then(|prefix| prefix.strip_suffix('.'))
+            .is_some_and(|label| !label.is_empty() && !label.contains('.'))
+    } else if entry.starts_with('.') {
+        domain.ends_with(&entry)
+    } else if entry.contains(['*', '?']) {
+        match_wildcard(domain, &entry)
+    } else {
+        domain == entry
+    }
+}
+
+fn ip_bits(ip: IpAddr) -> (u128, u32) {
+    match ip {
+        IpAddr::V4(v4) => (u128::from(u32::from(v4)), 32),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => (u128::from(u32::from(v4)), 32),
+            None => (u128::from(v6), 128),
+        },
+    }
+}
+
+/// 解析 CIDR，返回匹配函数
+fn cidr_matcher(cidr: &str) -> Option<impl Fn(IpAddr) -> bool> {
+    let (addr, prefix) = cidr.split_once('/').unwrap_or((cidr, ""));
+    let (network, width) = ip_bits(addr.trim().parse().ok()?);
+    let prefix: u32 = if prefix.is_empty() {
+        width
+    } else {
+        prefix.trim().parse().ok()?
+    };
+    if prefix > width {
+        return None;
+    }
+    let mask = |bits: u32| {
+        if bits == 0 {
+            0
+        } else {
+            (u128::MAX << (128 - bits)) >> (128 - width)
+        }
+    };
+    let network_mask = mask(prefix);
+    Some(move |ip: IpAddr| {
+        let (value, ip_width) = ip_bits(ip);
+        ip_width == width && value & network_mask == network & network_mask
+    })
+}
+
+/// IP-SUFFIX 匹配地址的低位，如 8.8.8.8/24 匹配末尾 24 位相同的地址
+fn suffix_matcher(suffix: &str) -> Option<impl Fn(IpAddr) -> bool> {
+    let (addr, bits) = suffix.split_once('/')?;
+    let (value, width) = ip_bits(addr.trim().parse().ok()?);
+    let bits: u32 = bits.trim().parse().ok()?;
+    if bits > width {
+        return None;
+    }
+    let mask = if bits == 0 {
+        0
+    } else {
+        u128::MAX >> (128 - bits)
+    };
+    Some(move |ip: IpAddr| {
+        let (ip_value, ip_width) = ip_bits(ip);
+        ip_width == width && ip_value & mask == value & mask
+    })
+}
+
+fn is_lan(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.octets()[0] == 100 && (v4.octets()[1] & 0xc0) == 64 // 100.64.0.0/10
+        }
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (v6.segments()[0] & 0xfe00) == 0xfc00 // fc00::/7
+                || (v6.segments()[0] & 0xffc0) == 0xfe80 // fe80::/10
+        }
+    }
+}
+
+/// 端口格式如 80、443/8443、1000-2000
+fn match_port(spec: &str, port: u16) -> Option<bool> {
+    let mut matched = false;
+    for part in spec.split('/') {
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+        let (start, end): (u16, u16) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+        matched |= (start..=end).contains(&port);
+    }
+    Some(matched)
+}
+
+/// Windows 进程名不区分大小写
+fn name_eq(value: &str, payload: &str) -> bool {
+    if cfg!(target_os = "windows") {
+        value.eq_ignore_ascii_case(payload)
+    } else {
+        value == payload
+    }
+}
+
+fn normalize_rule(rule: &str) -> String {
+    rule.split(',').map(str::trim).collect::<Vec<_>>().join(",")
+}
+
+/// 按增强链的写入顺序查找规则来源，找不到时视为脚本生成
+async fn rule_source(rule: &str) -> RuleSource {
+    let rule = normalize_rule(rule);
+    let contains = |rules: &Sequence| {
+        rules
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|r| normalize_rule(r) == rule)
+    };
+
+    if custom_rules::custom_rules_prepend().is_some_and(|rules| contains(&rules)) {
+        return RuleSource {
+            kind: RuleSourceKind::CustomRules,
+            uid: None,
+            name: None,
+        };
+    }
+    if app_routes::app_routes_prepend().is_some_and(|rules| contains(&rules)) {
+        return RuleSource {
+            kind: RuleSourceKind::AppRoutes,
+            uid: None,
+            name: None,
+        };
+    }
+
+    let (rules_item, merge_item, global_merge, current) = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        let item = |uid: Option<String>| uid.and_then(|uid| profiles.get_item(&uid).ok().cloned());
+        (
+            item(profiles.current_rules()),
+            item(profiles.current_merge()),
+            item(Some("Merge".into())),
+            item(profiles.get_current()),
+        )
+    };
+
+    if let Some(item) = &rules_item
+        && let Some(path) = item_path(item)
+        && let Ok(seq) = help::read_seq_map(&path).await
+        && (contains(&seq.prepend) || contains(&seq.append))
+    {
+        return source_of(RuleSourceKind::RulesItem, item);
+    }
+    for (kind, item) in [
+        (RuleSourceKind::Merge, &merge_item),
+        (RuleSourceKind::GlobalMerge, &global_merge),
+        (RuleSourceKind::Profile, &current),
+    ] {
+        let Some(item) = item else {
+            continue;
+        };
+        let Some(path) = item_path(item) else {
+            continue;
+        };
+        let Ok(mapping) = help::read_mapping(&path).await else {
+            continue;
+        };
+        let found = ["prepend-rules", "append-rules", "rules"]
+            .iter()
+            .any(|key| {
+                mapping
+                    .get(*key)
+                    .and_then(Value::as_sequence)
+                    .is_some_and(contains)
+            });
+        if found {
+            return source_of(kind, item);
+        }
+    }
+
+    RuleSource {
+        kind: RuleSourceKind::Generated,
+        uid: None,
+        name: None,
+    }
+}
+
+fn item_path(item: &PrfItem) -> Option<PathBuf> {
+    Some(dirs::app_profiles_dir().ok()?.join(item.file.as_ref()?))
+}
+
+fn source_of(kind: RuleSourceKind, item: &PrfItem) -> RuleSource {
+    RuleSource {
+        kind,
+        uid: item.uid.clone(),
+        name: item.name.clone(),
+    }
+}
+
+/// 沿代理组当前选择找到最终出站
+async fn resolve_proxy_chain(target: &str) -> Vec<String> {
+    let mut chain = vec![target.to_string()];
+    let Ok(proxies) = IpcManager::global().get_proxies().await else {
+        return chain;
+    };
+    let mut seen = HashSet::from([target.to_string()]);
+    let mut current = target.to_string();
+    while chain.len() < MAX_GROUP_DEPTH {
+        let Some(now) = proxies
+            .get("proxies")
+            .and_then(|proxies| proxies.get(&current))
+            .and_then(|proxy| proxy.get("now"))
+            .and_then(|now| now.as_str())
+            .filter(|now| !now.is_empty())
+        else {
+            break;
+        };
+        if !seen.insert(now.to_string()) {
+            break;
+        }
+        chain.push(now.to_string());
+        current = now.to_string();
+    }
+    chain
+}
@@ -0,0 +1,428 @@
+use super::{CmdResult, custom_rules::is_valid_cidr};
+use crate::{
+    ipc::IpcManager,
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use reqwest::{
+    Client, StatusCode,
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+use tokio::sync::Mutex;
+
+const RULESET_STORE_FILE: &str = "ruleset_sources.json";
+
+/// 默认更新间隔（秒）
+const DEFAULT_INTERVAL: u64 = 86400;
+
+/// 超过更新间隔多少倍视为过期
+const STALE_FACTOR: u64 = 2;
+
+static RULESET_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 规则集类型，对应 rule-provider 的 behavior
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RulesetBehavior {
+    Domain,
+    Ipcidr,
+    Classical,
+}
+
+impl RulesetBehavior {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Domain => "domain",
+            Self::Ipcidr => "ipcidr",
+            Self::Classical => "classical",
+        }
+    }
+}
+
+/// 规则集文件格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RulesetFormat {
+    #[default]
+    Yaml,
+    Text,
+}
+
+impl RulesetFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Yaml => "yaml",
+            Self::Text => "txt",
+        }
+    }
+}
+
+/// 远程规则集来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulesetSource {
+    pub name: String, // rule-provider 名称
+    pub url: String,
+    pub behavior: RulesetBehavior,
+    #[serde(default)]
+    pub format: RulesetFormat,
+    #[serde(default)]
+    pub interval: Option<u64>, // 更新间隔（秒）
+    #[serde(default)]
+    pub added_at: i64,
+}
+
+/// 规则集缓存状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RulesetCacheState {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub last_checked: Option<i64>,
+    pub last_updated: Option<i64>, // 最近一次内容变化的时间
+    pub rule_count: usize,
+    pub file_size: u64,
+    pub last_error: Option<String>,
+}
+
+/// 规则集状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulesetStatus {
+    pub source: RulesetSource,
+    pub state: RulesetCacheState,
+    pub cached: bool,
+    pub stale: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RulesetStore {
+    sources: Vec<RulesetSource>,
+    states: HashMap<String, RulesetCacheState>,
+}
+
+/// 添加远程规则集并立即下载
+#[tauri::command]
+pub async fn add_ruleset_source(mut source: RulesetSource) -> CmdResult<RulesetStatus> {
+    source.name = source.name.trim().to_string();
+    if source.name.is_empty() {
+        return Err("规则集名称不能为空".into());
+    }
+    if !source.url.starts_with("http://") && !source.url.starts_with("https://") {
+        return Err(format!("无效的规则集地址: {}", source.url).into());
+    }
+
+    {
+        let _guard = RULESET_LOCK.lock().await;
+        let mut store = load_store().context("加载规则集失败")?;
+        if store.sources.iter().any(|s| s.name == source.name) {
+            return Err(format!("规则集已存在: {}", source.name).into());
+        }
+        source.added_at = chrono::Utc::now().timestamp();
+        store.sources.push(source.clone());
+        save_store(&store).context("保存规则集失败")?;
+    }
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[规则集] 添加规则集 {}: {}",
+        source.name,
+        source.url
+    );
+
+    refresh_ruleset(source.name).await
+}
+
+/// 刷新规则集，内容未变化时只更新检查时间
+#[tauri::command]
+pub async fn refresh_ruleset(name: String) -> CmdResult<RulesetStatus> {
+    let (source, mut state) = {
+        let _guard = RULESET_LOCK.lock().await;
+        let store = load_store().context("加载规则集失败")?;
+        let source = store
+            .sources
+            .iter()
+            .find(|s| s.name == name)
+            .cloned()
+            .ok_or_else(|| format!("规则集不存在: {}", name))?;
+        let state = store.states.get(&name).cloned().unwrap_or_default();
+        (source, state)
+    };
+
+    let result = download_ruleset(&source, &mut state).await;
+    state.last_checked = Some(chrono::Utc::now().timestamp());
+    state.last_error = result.as_ref().err().cloned();
+
+    let status = {
+        let _guard = RULESET_LOCK.lock().await;
+        let mut store = load_store().context("加载规则集失败")?;
+        store.states.insert(name.clone(), state);
+        save_store(&store).context("保存规则集失败")?;
+        build_status(&store, &source)
+    };
+
+    match result {
+        Ok(true) => {
+            // 内核已加载该规则集时通知其重新读取文件
+            if let Err(e) = IpcManager::global().update_rule_provider(&name).await {
+                logging!(
+                    debug,
+                    Type::Config,
+                    true,
+                    "[规则集] 通知内核刷新失败: {}",
+                    e
+                );
+            }
+            Ok(status)
+        }
+        Ok(false) => Ok(status),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 获取所有规则集的缓存状态
+#[tauri::command]
+pub async fn get_ruleset_status() -> CmdResult<Vec<RulesetStatus>> {
+    let _guard = RULESET_LOCK.lock().await;
+    let store = load_store().context("加载规则集失败")?;
+    Ok(store
+        .sources
+        .iter()
+        .map(|source| build_status(&store, source))
+        .collect())
+}
+
+/// 删除规则集及其缓存文件
+#[tauri::command]
+pub async fn remove_ruleset_source(name: String) -> CmdResult<()> {
+    let _guard = RULESET_LOCK.lock().await;
+    let mut store = load_store().context("加载规则集失败")?;
+    let index = store
+        .sources
+        .iter()
+        .position(|s| s.name == name)
+        .ok_or_else(|| format!("规则集不存在: {}", name))?;
+    let source = store.sources.remove(index);
+    store.states.remove(&name);
+    save_store(&store).context("保存规则集失败")?;
+
+    if let Ok(path) = cache_path(&source) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// 将已缓存的规则集作为本地 rule-provider 写入配置，不覆盖订阅中的同名规则集
+pub fn use_ruleset_sources(mut config: Mapping) -> Mapping {
+    let Ok(store) = load_store() else {
+        return config;
+    };
+
+    let mut providers = config
+        .get("rule-providers")
+        .and_then(Value::as_mapping)
+        .cloned()
+        .unwrap_or_default();
+    let mut changed = false;
+    for source in &store.sources {
+        let Ok(path) = cache_path(source) else {
+            continue;
+        };
+        if !path.exists() || providers.contains_key(source.name.as_str()) {
+            continue;
+        }
+
+        let mut provider = Mapping::new();
+        provider.insert("type".into(), "file".into());
+        provider.insert("behavior".into(), source.behavior.as_str().into());
+        provider.insert(
+            "format".into(),
+            match source.format {
+                RulesetFormat::Yaml => "yaml",
+                RulesetFormat::Text => "text",
+            }
+            .into(),
+        );
+        provider.insert("path".into(), path.to_string_lossy().as_ref().into());
+        providers.insert(source.name.as_str().into(), provider.into());
+        changed = true;
+    }
+
+    if changed {
+        config.insert("rule-providers".into(), providers.into());
+    }
+    config
+}
+
+// ===== 内部实现函数 =====
+
+/// 按 ETag/Last-Modified 条件下载规则集，内容有更新时返回 true
+async fn download_ruleset(
+    source: &RulesetSource,
+    state: &mut RulesetCacheState,
+) -> CmdResult<bool> {
+    let path = cache_path(source)?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("liebseu-clash/ruleset-manager")
+        .build()
+        .context("创建HTTP客户端失败")?;
+
+    let mut request = client.get(&source.url);
+    // 缓存文件丢失时必须重新下载
+    if path.exists() {
+        if let Some(etag) = &state.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &state.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await.context("下载规则集失败")?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        logging!(debug, Type::Config, true, "[规则集] {} 未变化", source.name);
+        return Ok(false);
+    }
+    if !response.status().is_success() {
+        return Err(format!("下载规则集失败: HTTP {}", response.status()).into());
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+    let content = response.text().await.context("读取规则集内容失败")?;
+
+    let rule_count = verify_payload(&content, source.format, source.behavior)?;
+    fs::write(&path, &content).context("保存规则集失败")?;
+
+    state.etag = etag;
+    state.last_modified = last_modified;
+    state.last_updated = Some(chrono::Utc::now().timestamp());
+    state.rule_count = rule_count;
+    state.file_size = content.len() as u64;
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[规则集] {} 已更新，共 {} 条规则",
+        source.name,
+        rule_count
+    );
+    Ok(true)
+}
+
+/// 校验规则集格式与类型，返回规则条数
+fn verify_payload(
+    content: &str,
+    format: RulesetFormat,
+    behavior: RulesetBehavior,
+) -> CmdResult<usize> {
+    let entries: Vec<String> = match format {
+        RulesetFormat::Yaml => {
+            let document: Mapping =
+                serde_yaml_ng::from_str(content).context("规则集不是有效的 YAML")?;
+            document
+                .get("payload")
+                .and_then(Value::as_sequence)
+                .ok_or_else(|| "规则集缺少 payload 字段".to_string())?
+                .iter()
+                .map(|v| v.as_str().unwrap_or_default().trim().to_string())
+                .collect()
+        }
+        RulesetFormat::Text => content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(str::to_string)
+            .collect(),
+    };
+
+    if entries.is_empty() {
+        return Err("规则集为空".into());
+    }
+    if let Some(invalid) = entries.iter().find(|e| !is_valid_entry(e, behavior)) {
+        return Err(format!("规则集内容与类型 {} 不符: {}", behavior.as_str(), invalid).into());
+    }
+    Ok(entries.len())
+}
+
+fn is_valid_entry(entry: &str, behavior: RulesetBehavior) -> bool {
+    match behavior {
+        RulesetBehavior::Domain => {
+            let domain = entry
+                .strip_prefix("+.")
+                .or_else(|| entry.strip_prefix('.'))
+                .unwrap_or(entry);
+            !domain.is_empty()
+                && domain
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '*'))
+        }
+        RulesetBehavior::Ipcidr => is_valid_cidr(entry),
+        RulesetBehavior::Classical => entry.split_once(',').is_some_and(|(rule_type, payload)| {
+            !payload.trim().is_empty()
+                && rule_type
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c == '-' || c.is_ascii_digit())
+        }),
+    }
+}
+
+fn build_status(store: &RulesetStore, source: &RulesetSource) -> RulesetStatus {
+    let state = store.states.get(&source.name).cloned().unwrap_or_default();
+    let cached = cache_path(source).is_ok_and(|p| p.exists());
+    let max_age = source.interval.unwrap_or(DEFAULT_INTERVAL) * STALE_FACTOR;
+    let stale = state.last_error.is_some()
+        || state
+            .last_checked
+            .is_none_or(|at| chrono::Utc::now().timestamp() - at > max_age as i64);
+
+    RulesetStatus {
+        source: source.clone(),
+        state,
+        cached,
+        stale: stale || !cached,
+    }
+}
+
+/// 获取规则集缓存文件路径
+fn cache_path(source: &RulesetSource) -> Result<PathBuf> {
+    let dir = dirs::app_home_dir()?.join("rulesets");
+    fs::create_dir_all(&dir)?;
+    let file_name: String = source
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(dir.join(format!("{}.{}", file_name, source.format.extension())))
+}
+
+fn load_store() -> Result<RulesetStore> {
+    let path = dirs::app_home_dir()?.join(RULESET_STORE_FILE);
+    if !path.exists() {
+        return Ok(RulesetStore::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_store(store: &RulesetStore) -> Result<()> {
+    let path = dirs::app_home_dir()?.join(RULESET_STORE_FILE);
+    fs::write(path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
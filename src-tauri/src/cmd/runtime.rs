@@ -1,5 +1,10 @@
-use super::CmdResult;
-use crate::{config::*, core::CoreManager, log_err, wrap_err};
+use super::{CmdError, CmdResult, ErrorCode};
+use crate::{
+    config::*,
+    core::{CoreManager, handle},
+    enhance::metrics::{GenerationMetrics, generation_metrics},
+    log_err, wrap_err,
+};
 use anyhow::Context;
 use serde_yaml_ng::Mapping;
 use std::collections::HashMap;
@@ -123,3 +128,174 @@ pub async fn update_proxy_chain_config_in_runtime(
 
     Ok(())
 }
+
+/// 按路径读取运行时配置片段，路径形如 `dns.nameserver` 或 `proxy-groups[2]`
+#[tauri::command]
+pub async fn get_runtime_section(path: String) -> CmdResult<String> {
+    let segments = parse_section_path(&path)?;
+    let runtime = Config::runtime().await;
+    let runtime = runtime.latest_ref();
+    let config = runtime
+        .config
+        .as_ref()
+        .ok_or("failed to get runtime config")?;
+
+    let mut current = config
+        .get(segments[0].key())
+        .ok_or_else(|| format!("路径不存在: {path}"))?;
+    for segment in &segments[1..] {
+        current = match segment {
+            SectionSegment::Key(key) => current.get(key.as_str()),
+            SectionSegment::Index(index) => current.get(*index),
+        }
+        .ok_or_else(|| format!("路径不存在: {path}"))?;
+    }
+    wrap_err!(serde_yaml_ng::to_string(current).context("YAML generation failed"))
+}
+
+/// 按路径替换运行时配置片段，内核验证通过后立即生效
+/// 与链式代理的运行时修改相同，重新生成配置后修改会被覆盖
+#[tauri::command]
+pub async fn patch_runtime_section(path: String, yaml_fragment: String) -> CmdResult<()> {
+    let segments = parse_section_path(&path)?;
+    let value: serde_yaml_ng::Value =
+        serde_yaml_ng::from_str(&yaml_fragment).map_err(|e| format!("YAML 片段格式错误: {e}"))?;
+
+    let mut config = Config::runtime()
+        .await
+        .latest_ref()
+        .config
+        .clone()
+        .ok_or("failed to get runtime config")?;
+    set_section(&mut config, &segments, value).map_err(|e| format!("{path}: {e}"))?;
+
+    {
+        let runtime = Config::runtime().await;
+        runtime.draft_mut().config = Some(config);
+    }
+    let res: CmdResult<()> = async {
+        match CoreManager::global().validate_config().await {
+            Ok((true, _)) => {}
+            Ok((false, error)) => {
+                return Err(CmdError::new(
+                    ErrorCode::ConfigValidationFailed,
+                    format!("配置未通过内核验证: {error}"),
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        }
+        let run_path = wrap_err!(Config::generate_file(ConfigType::Run).await)?;
+        CoreManager::global().put_configs_force(run_path).await?;
+        Ok(())
+    }
+    .await;
+
+    // 成功时提交草稿，任一步失败都丢弃草稿，避免未生效的修改残留在运行时配置中
+    match res {
+        Ok(()) => {
+            Config::runtime().await.apply();
+            handle::Handle::refresh_clash();
+            Ok(())
+        }
+        Err(e) => {
+            Config::runtime().await.discard();
+            Err(e)
+        }
+    }
+}
+
+/// 获取最近的配置生成耗时记录，新的在前
+#[tauri::command]
+pub async fn get_config_generation_metrics() -> CmdResult<Vec<GenerationMetrics>> {
+    Ok(generation_metrics())
+}
+
+// ===== 内部实现函数 =====
+
+/// 配置路径中的一段
+#[derive(Debug, PartialEq, Eq)]
+enum SectionSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl SectionSegment {
+    fn key(&self) -> &str {
+        match self {
+            Self::Key(key) => key,
+            Self::Index(_) => "",
+        }
+    }
+}
+
+/// 解析 `a.b[1].c` 形式的路径，首段必须是键名
+fn parse_section_path(path: &str) -> Result<Vec<SectionSegment>, String> {
+    let mut segments = Vec::new();
+    for part in path.trim().split('.') {
+        if part.is_empty() {
+            return Err(format!("路径格式错误: {path}"));
+        }
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(SectionSegment::Key(key.to_string()));
+        }
+        while let Some(stripped) = rest.strip_prefix('[') {
+            let (index, tail) = stripped
+                .split_once(']')
+                .ok_or_else(|| format!("路径格式错误: {path}"))?;
+            let index = index
+                .trim()
+                .parse()
+                .map_err(|_| format!("路径索引无效: {path}"))?;
+            segments.push(SectionSegment::Index(index));
+            rest = tail;
+        }
+        if !rest.is_empty() {
+            return Err(format!("路径格式错误: {path}"));
+        }
+    }
+    match segments.first() {
+        Some(SectionSegment::Key(_)) => Ok(segments),
+        _ => Err(format!("路径格式错误: {path}")),
+    }
+}
+
+/// 写入路径对应的值，最后一段为键名时允许新建，索引必须已存在
+fn set_section(
+    config: &mut Mapping,
+    segments: &[SectionSegment],
+    value: serde_yaml_ng::Value,
+) -> anyhow::Result<()> {
+    let Some((last, parents)) = segments.split_last() else {
+        anyhow::bail!("路径为空");
+    };
+    if parents.is_empty() {
+        config.insert(last.key().into(), value);
+        return Ok(());
+    }
+
+    let mut current = config.get_mut(parents[0].key()).context("父级路径不存在")?;
+    for segment in &parents[1..] {
+        current = match segment {
+            SectionSegment::Key(key) => current.get_mut(key.as_str()),
+            SectionSegment::Index(index) => current.get_mut(*index),
+        }
+        .context("父级路径不存在")?;
+    }
+
+    match last {
+        SectionSegment::Key(key) => {
+            let mapping = current.as_mapping_mut().context("父级不是映射")?;
+            mapping.insert(key.as_str().into(), value);
+        }
+        SectionSegment::Index(index) => {
+            let slot = current
+                .as_sequence_mut()
+                .context("父级不是列表")?
+                .get_mut(*index)
+                .context("索引超出范围")?;
+            *slot = value;
+        }
+    }
+    Ok(())
+}
@@ -30,6 +30,7 @@ pub async fn save_profile_file(index: String, file_data: Option<String>) -> CmdR
 
     // 保存新的配置文件
     let file_data = file_data.ok_or("file_data is None")?;
+    profile_watcher::ProfileWatcher::global().mark_internal_write(&file_path, file_data.as_bytes());
     wrap_err!(fs::write(&file_path, &file_data).await)?;
 
     let file_path_str = file_path.to_string_lossy().to_string();
@@ -88,6 +89,8 @@ pub async fn save_profile_file(index: String, file_data: Option<String>) -> CmdR
                     error_msg
                 );
                 // 恢复原始配置文件
+                profile_watcher::ProfileWatcher::global()
+                    .mark_internal_write(&file_path, original_content.as_bytes());
                 wrap_err!(fs::write(&file_path, original_content).await)?;
                 // 发送合并文件专用错误通知
                 let result = (false, error_msg.clone());
@@ -103,8 +106,10 @@ pub async fn save_profile_file(index: String, file_data: Option<String>) -> CmdR
                     e
                 );
                 // 恢复原始配置文件
+                profile_watcher::ProfileWatcher::global()
+                    .mark_internal_write(&file_path, original_content.as_bytes());
                 wrap_err!(fs::write(&file_path, original_content).await)?;
-                return Err(e.to_string());
+                return Err(e.into());
             }
         }
     }
@@ -127,6 +132,8 @@ pub async fn save_profile_file(index: String, file_data: Option<String>) -> CmdR
                 error_msg
             );
             // 恢复原始配置文件
+            profile_watcher::ProfileWatcher::global()
+                .mark_internal_write(&file_path, original_content.as_bytes());
             wrap_err!(fs::write(&file_path, original_content).await)?;
 
             // 智能判断错误类型
@@ -177,8 +184,10 @@ pub async fn save_profile_file(index: String, file_data: Option<String>) -> CmdR
                 e
             );
             // 恢复原始配置文件
+            profile_watcher::ProfileWatcher::global()
+                .mark_internal_write(&file_path, original_content.as_bytes());
             wrap_err!(fs::write(&file_path, original_content).await)?;
-            Err(e.to_string())
+            Err(e.into())
         }
     }
 }
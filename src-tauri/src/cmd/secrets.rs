@@ -0,0 +1,80 @@
+use super::CmdResult;
+use crate::{
+    config::{Config, IVerge},
+    core::backup::{BackupStorage, S3Client, WebDavClient},
+    feat, logging,
+    utils::{logging::Type, secrets},
+};
+use serde::{Deserialize, Serialize};
+
+/// 凭据迁移结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecretMigrationReport {
+    pub migrated: Vec<String>,
+    pub skipped: Vec<String>,          // 未配置或已在钥匙串中
+    pub failed: Vec<(String, String)>, // (名称, 错误)
+}
+
+/// 将配置文件中的 WebDAV 密码与 S3 密钥迁移到系统钥匙串
+#[tauri::command]
+pub async fn migrate_secrets_to_keychain() -> CmdResult<SecretMigrationReport> {
+    let (webdav_password, s3_backup) = {
+        let verge = Config::verge().await;
+        let verge = verge.latest_ref();
+        (verge.webdav_password.clone(), verge.s3_backup.clone())
+    };
+
+    let mut report = SecretMigrationReport::default();
+    let mut patch = IVerge::default();
+
+    match webdav_password.filter(|p| !p.is_empty() && !secrets::is_reference(p)) {
+        Some(password) => match secrets::store_secret(secrets::WEBDAV_PASSWORD, &password) {
+            Ok(reference) => {
+                patch.webdav_password = Some(reference);
+                report.migrated.push(secrets::WEBDAV_PASSWORD.to_string());
+            }
+            Err(e) => report
+                .failed
+                .push((secrets::WEBDAV_PASSWORD.to_string(), e.to_string())),
+        },
+        None => report.skipped.push(secrets::WEBDAV_PASSWORD.to_string()),
+    }
+
+    match s3_backup
+        .filter(|c| !c.secret_access_key.is_empty() && !secrets::is_reference(&c.secret_access_key))
+    {
+        Some(mut config) => {
+            match secrets::store_secret(secrets::S3_SECRET_ACCESS_KEY, &config.secret_access_key) {
+                Ok(reference) => {
+                    config.secret_access_key = reference;
+                    patch.s3_backup = Some(config);
+                    report
+                        .migrated
+                        .push(secrets::S3_SECRET_ACCESS_KEY.to_string());
+                }
+                Err(e) => report
+                    .failed
+                    .push((secrets::S3_SECRET_ACCESS_KEY.to_string(), e.to_string())),
+            }
+        }
+        None => report
+            .skipped
+            .push(secrets::S3_SECRET_ACCESS_KEY.to_string()),
+    }
+
+    if !report.migrated.is_empty() {
+        feat::patch_verge(patch, false).await?;
+        WebDavClient::global().reset();
+        S3Client::global().reset();
+    }
+
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[钥匙串] 凭据迁移完成: 迁移 {:?}, 失败 {}",
+        report.migrated,
+        report.failed.len()
+    );
+    Ok(report)
+}
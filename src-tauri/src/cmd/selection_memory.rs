@@ -0,0 +1,371 @@
+use super::{CmdError, CmdResult};
+use crate::{config::Config, ipc::IpcManager, logging, utils::dirs, utils::logging::Type};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+use tokio::sync::RwLock;
+
+/// 节点选择记忆存储
+static SELECTION_MEMORY: Lazy<Arc<RwLock<Option<SelectionMemoryStore>>>> =
+    Lazy::new(|| Arc::new(RwLock::new(None)));
+
+/// 原节点不存在时的回退策略
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SelectionFallback {
+    #[default]
+    None, // 不回退，保持订阅默认
+    SameRegion, // 同地区节点
+    BestDelay,  // 延迟最低节点
+}
+
+/// 节点选择记忆策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionMemoryPolicy {
+    pub enabled: bool,
+    pub restore_on_switch: bool, // 切换订阅后恢复
+    pub restore_on_update: bool, // 更新订阅后恢复
+    pub fallback: SelectionFallback,
+}
+
+impl Default for SelectionMemoryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            restore_on_switch: true,
+            restore_on_update: true,
+            fallback: SelectionFallback::SameRegion,
+        }
+    }
+}
+
+/// 单个分组的选择恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionRestoreItem {
+    pub group: String,
+    pub remembered: String,
+    pub applied: Option<String>,
+    pub fallback_used: bool,
+}
+
+/// 记忆存储：策略 + (订阅, 分组) -> 节点
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct SelectionMemoryStore {
+    policy: SelectionMemoryPolicy,
+    selections: HashMap<String, HashMap<String, String>>,
+}
+
+/// 设置节点选择记忆策略
+#[tauri::command]
+pub async fn set_selection_memory_policy(policy: SelectionMemoryPolicy) -> CmdResult<()> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[选择记忆] 更新策略: enabled={}, fallback={:?}",
+        policy.enabled,
+        policy.fallback
+    );
+
+    let mut guard = SELECTION_MEMORY.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    store.policy = policy;
+    save_store(store)
+        .context("保存选择记忆策略失败")
+        .map_err(CmdError::from)
+}
+
+/// 获取节点选择记忆策略
+#[tauri::command]
+pub async fn get_selection_memory_policy() -> CmdResult<SelectionMemoryPolicy> {
+    let mut guard = SELECTION_MEMORY.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    Ok(store.policy.clone())
+}
+
+/// 清除指定订阅（或全部）的选择记忆
+#[tauri::command]
+pub async fn clear_selection_memory(profile_uid: Option<String>) -> CmdResult<()> {
+    let mut guard = SELECTION_MEMORY.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    match profile_uid {
+        Some(uid) => {
+            store.selections.remove(&uid);
+        }
+        None => store.selections.clear(),
+    }
+    save_store(store)
+        .context("保存选择记忆失败")
+        .map_err(CmdError::from)
+}
+
+/// 立即对当前订阅恢复记忆的选择
+#[tauri::command]
+pub async fn restore_selection_memory() -> CmdResult<Vec<SelectionRestoreItem>> {
+    let current = Config::profiles().await.latest_ref().get_current();
+    match current {
+        Some(uid) => Ok(restore_selections(&uid).await?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 记录当前订阅下某个分组的选择
+pub async fn remember_selection(group: &str, proxy: &str) {
+    remember_selections(&[(group.to_string(), proxy.to_string())]).await;
+}
+
+/// 批量记录当前订阅下多个分组的选择，只写盘一次
+pub async fn remember_selections(selections: &[(String, String)]) {
+    if selections.is_empty() {
+        return;
+    }
+    let Some(profile_uid) = Config::profiles().await.latest_ref().get_current() else {
+        return;
+    };
+
+    let mut guard = SELECTION_MEMORY.write().await;
+    let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+    if !store.policy.enabled {
+        return;
+    }
+
+    let remembered = store.selections.entry(profile_uid).or_default();
+    for (group, proxy) in selections {
+        remembered.insert(group.clone(), proxy.clone());
+    }
+
+    if let Err(e) = save_store(store) {
+        logging!(warn, Type::Cmd, "[选择记忆] 保存失败: {}", e);
+    }
+}
+
+/// 订阅切换或更新后按策略恢复选择
+/// is_update: true 表示订阅更新，false 表示订阅切换
+pub async fn restore_selections_after_reload(profile_uid: String, is_update: bool) {
+    let policy = {
+        let mut guard = SELECTION_MEMORY.write().await;
+        guard
+            .get_or_insert_with(|| load_store().unwrap_or_default())
+            .policy
+            .clone()
+    };
+
+    let should_restore = policy.enabled
+        && if is_update {
+            policy.restore_on_update
+        } else {
+            policy.restore_on_switch
+        };
+    if !should_restore {
+        return;
+    }
+
+    match restore_selections(&profile_uid).await {
+        Ok(items) => {
+            logging!(
+                info,
+                Type::Cmd,
+                true,
+                "[选择记忆] 订阅 {} 恢复 {} 个分组选择",
+                profile_uid,
+                items.iter().filter(|i| i.applied.is_some()).count()
+            );
+        }
+        Err(e) => {
+            logging!(warn, Type::Cmd, true, "[选择记忆] 恢复选择失败: {}", e);
+        }
+    }
+}
+
+// ===== 内部实现函数 =====
+
+/// 对指定订阅执行恢复
+async fn restore_selections(profile_uid: &str) -> Result<Vec<SelectionRestoreItem>> {
+    let (policy, remembered) = {
+        let mut guard = SELECTION_MEMORY.write().await;
+        let store = guard.get_or_insert_with(|| load_store().unwrap_or_default());
+        (
+            store.policy.clone(),
+            store
+                .selections
+                .get(profile_uid)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    };
+
+    if remembered.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let proxies = IpcManager::global()
+        .get_proxies()
+        .await
+        .map_err(|e| anyhow::anyhow!("获取代理列表失败: {}", e))?;
+    let proxies = proxies
+        .get("proxies")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut results = Vec::new();
+    for (group, node) in remembered {
+        let Some(group_info) = proxies.get(&group) else {
+            continue;
+        };
+        let candidates: Vec<String> = group_info
+            .get("all")
+            .and_then(|a| a.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let now = group_info.get("now").and_then(|n| n.as_str());
+
+        let (target, fallback_used) = if candidates.contains(&node) {
+            (Some(node.clone()), false)
+        } else {
+            (
+                pick_fallback(&policy.fallback, &node, &candidates, &proxies),
+                true,
+            )
+        };
+
+        let mut applied = None;
+        if let Some(target) = target {
+            if now != Some(target.as_str()) {
+                IpcManager::global()
+                    .update_proxy(&group, &target)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("切换节点失败: {}", e))?;
+            }
+            applied = Some(target);
+        }
+
+        results.push(SelectionRestoreItem {
+            group,
+            remembered: node,
+            applied,
+            fallback_used,
+        });
+    }
+
+    Ok(results)
+}
+
+/// 按回退策略挑选替代节点
+fn pick_fallback(
+    strategy: &SelectionFallback,
+    remembered: &str,
+    candidates: &[String],
+    proxies: &serde_json::Map<String, serde_json::Value>,
+) -> Option<String> {
+    match strategy {
+        SelectionFallback::None => None,
+        SelectionFallback::SameRegion => {
+            let region = detect_region(remembered)?;
+            let same_region: Vec<String> = candidates
+                .iter()
+                .filter(|c| detect_region(c) == Some(region))
+                .cloned()
+                .collect();
+            best_by_delay(&same_region, proxies).or_else(|| same_region.first().cloned())
+        }
+        SelectionFallback::BestDelay => best_by_delay(candidates, proxies),
+    }
+}
+
+/// 根据最近一次延迟记录挑选最佳节点
+pub(crate) fn best_by_delay(
+    candidates: &[String],
+    proxies: &serde_json::Map<String, serde_json::Value>,
+) -> Option<String> {
+    candidates
+        .iter()
+        .filter_map(|name| {
+            let delay = proxies
+                .get(name)?
+                .get("history")?
+                .as_array()?
+                .last()?
+                .get("delay")?
+                .as_u64()?;
+            (delay > 0).then_some((name, delay))
+        })
+        .min_by_key(|(_, delay)| *delay)
+        .map(|(name, _)| name.clone())
+}
+
+/// 从节点名称识别地区
+pub(crate) fn detect_region(name: &str) -> Option<&'static str> {
+    const REGIONS: &[(&str, &[&str])] = &[
+        ("HK", &["香港", "港", "hk", "hong kong", "hongkong", "🇭🇰"]),
+        // 不匹配单字"台"，避免"平台""后台"等名称被误判
+        (
+            "TW",
+            &["台湾", "台灣", "台北", "台中", "高雄", "tw", "taiwan", "🇹🇼"],
+        ),
+        (
+            "JP",
+            &["日本", "东京", "大阪", "jp", "japan", "tokyo", "🇯🇵"],
+        ),
+        ("SG", &["新加坡", "狮城", "sg", "singapore", "🇸🇬"]),
+        ("KR", &["韩国", "首尔", "kr", "korea", "seoul", "🇰🇷"]),
+        (
+            "US",
+            &["美国", "us", "usa", "united states", "america", "🇺🇸"],
+        ),
+        ("UK", &["英国", "伦敦", "uk", "britain", "london", "🇬🇧"]),
+        ("DE", &["德国", "de", "germany", "frankfurt", "🇩🇪"]),
+    ];
+
+    let lower = name.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    REGIONS
+        .iter()
+        .find(|(_, keywords)| {
+            keywords.iter().any(|kw| {
+                if kw.is_ascii() && kw.len() <= 3 {
+                    // 短英文缩写需要整词匹配，避免误判
+                    tokens.iter().any(|t| t == kw)
+                } else {
+                    lower.contains(kw)
+                }
+            })
+        })
+        .map(|(code, _)| *code)
+}
+
+/// 获取存储文件路径
+fn store_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("selection_memory.json"))
+}
+
+/// 加载记忆存储
+fn load_store() -> Result<SelectionMemoryStore> {
+    let path = store_path()?;
+    if !path.exists() {
+        return Ok(SelectionMemoryStore::default());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存记忆存储
+fn save_store(store: &SelectionMemoryStore) -> Result<()> {
+    let path = store_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let json_data = serde_json::to_string_pretty(store)?;
+    fs::write(path, json_data)?;
+    Ok(())
+}
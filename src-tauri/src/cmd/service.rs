@@ -1,4 +1,4 @@
-use super::CmdResult;
+use super::{CmdError, CmdResult, ErrorCode};
 use crate::{
     core::{CoreManager, service},
     utils::i18n::t,
@@ -13,11 +13,14 @@ where
 {
     if let Err(e) = service_op().await {
         let emsg = format!("{} {} failed: {}", op_type, "Service", e.to_string());
-        return Err(t(emsg.as_str()).await);
+        return Err(CmdError::new(
+            ErrorCode::ServiceUnavailable,
+            t(emsg.as_str()).await,
+        ));
     }
     if CoreManager::global().restart_core().await.is_err() {
         let emsg = format!("{} {} failed", "Restart", "Core");
-        return Err(t(emsg.as_str()).await);
+        return Err(t(emsg.as_str()).await.into());
     }
     Ok(())
 }
@@ -37,9 +40,32 @@ pub async fn reinstall_service() -> CmdResult {
     execute_service_operation_sync(service::reinstall_service, "Reinstall").await
 }
 
+/// 按诊断结果修复服务，只执行需要的修复步骤
 #[tauri::command]
 pub async fn repair_service() -> CmdResult {
-    execute_service_operation_sync(service::force_reinstall_service, "Repair").await
+    execute_service_operation_sync(service::repair_service, "Repair").await
+}
+
+/// Linux 下为内核授予 TUN 所需的 capabilities，替代安装完整服务
+#[tauri::command]
+pub async fn setup_linux_capabilities() -> CmdResult<Vec<service::CoreCapabilityStatus>> {
+    let status = service::setup_linux_capabilities().await?;
+    if CoreManager::global().restart_core().await.is_err() {
+        return Err(t("Restart Core failed").await.into());
+    }
+    Ok(status)
+}
+
+/// 当前内核是否已获得 TUN 所需的 capabilities（仅 Linux）
+#[tauri::command]
+pub async fn has_tun_capabilities() -> CmdResult<bool> {
+    Ok(service::core_has_tun_capabilities().await)
+}
+
+/// 诊断服务状态，返回各项检查结果和建议的修复步骤
+#[tauri::command]
+pub async fn diagnose_service() -> CmdResult<service::ServiceDiagnostics> {
+    Ok(service::diagnose_service().await)
 }
 
 #[tauri::command]
@@ -47,5 +73,5 @@ pub async fn is_service_available() -> CmdResult<bool> {
     service::is_service_available()
         .await
         .map(|_| true)
-        .map_err(|e| e.to_string())
+        .map_err(CmdError::from)
 }
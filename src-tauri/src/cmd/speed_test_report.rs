@@ -0,0 +1,475 @@
+use super::{
+    CmdError, CmdResult, ErrorCode,
+    global_speed_test::{GlobalSpeedTestSummary, SpeedTestResult, latest_speed_test_summary},
+};
+use crate::{
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, anyhow};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+const RUNS_DIR: &str = "speed_test_runs";
+/// 最多保留的测速记录数量
+const MAX_RUNS: usize = 20;
+
+/// 报告格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Html,
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// 已保存的测速记录
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeedTestRunInfo {
+    pub run_id: String,
+    pub finished_at: i64,
+    pub total_nodes: usize,
+    pub successful_tests: usize,
+    pub failed_tests: usize,
+    pub duration_seconds: u64,
+    pub best_node: Option<String>,
+}
+
+/// 按地区或订阅汇总的结果
+#[derive(Debug, Clone, Serialize)]
+struct GroupSummary {
+    name: String,
+    nodes: usize,
+    available: usize,
+    availability: f64, // 百分比
+    avg_latency: Option<u64>,
+    min_latency: Option<u64>,
+    best_node: Option<String>,
+    traffic_remaining_percentage: Option<f64>, // 仅订阅汇总
+    expire_days: Option<i64>,                  // 仅订阅汇总
+}
+
+#[derive(Debug, Serialize)]
+struct SpeedTestReport<'a> {
+    run_id: &'a str,
+    finished_at: i64,
+    generated_at: i64,
+    duration_seconds: u64,
+    total_nodes: usize,
+    successful_tests: usize,
+    failed_tests: usize,
+    best_node: Option<&'a SpeedTestResult>,
+    regions: Vec<GroupSummary>,
+    providers: Vec<GroupSummary>,
+    nodes: &'a [SpeedTestResult],
+}
+
+/// 获取已保存的测速记录，最新的在前
+#[tauri::command]
+pub async fn list_speed_test_runs() -> CmdResult<Vec<SpeedTestRunInfo>> {
+    let mut runs: Vec<SpeedTestRunInfo> = load_runs()
+        .context("加载测速记录失败")?
+        .iter()
+        .map(|summary| SpeedTestRunInfo {
+            run_id: summary.run_id.clone(),
+            finished_at: summary.finished_at,
+            total_nodes: summary.total_nodes,
+            successful_tests: summary.successful_tests,
+            failed_tests: summary.failed_tests,
+            duration_seconds: summary.duration_seconds,
+            best_node: summary
+                .best_node
+                .as_ref()
+                .map(|node| node.node_name.clone()),
+        })
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.finished_at));
+    Ok(runs)
+}
+
+/// 导出测速报告到用户选择的路径，用户取消时返回 None
+#[tauri::command]
+pub async fn export_speed_test_report(
+    app_handle: AppHandle,
+    run_id: String,
+    format: ReportFormat,
+) -> CmdResult<Option<String>> {
+    let summary = find_run(&run_id)?;
+    let report = build_report(&summary);
+    let content = match format {
+        ReportFormat::Json => serde_json::to_string_pretty(&report).context("生成报告失败")?,
+        ReportFormat::Csv => render_csv(&report),
+        ReportFormat::Html => render_html(&report).context("生成报告失败")?,
+    };
+
+    let file_name = format!(
+        "speed-test-{}.{}",
+        format_time(summary.finished_at, "%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .set_file_name(file_name)
+        .add_filter(format.extension().to_uppercase(), &[format.extension()])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    let path = path
+        .into_path()
+        .map_err(|e| CmdError::new(ErrorCode::InvalidArgument, format!("无效的保存路径: {e}")))?;
+
+    fs::write(&path, content).context("写入报告失败")?;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[测速报告] 已导出 {} 到 {}",
+        run_id,
+        path.display()
+    );
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// 保存测速结果，超出数量时删除最早的记录
+pub(crate) fn save_run(summary: &GlobalSpeedTestSummary) -> Result<()> {
+    let dir = runs_dir()?;
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join(format!("{}.json", summary.run_id)),
+        serde_json::to_string(summary)?,
+    )?;
+
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    if files.len() > MAX_RUNS {
+        files.sort_by_key(|(modified, _)| *modified);
+        for (_, path) in files.iter().take(files.len() - MAX_RUNS) {
+            let _ = fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
+
+// ===== 内部实现函数 =====
+
+fn runs_dir() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(RUNS_DIR))
+}
+
+fn load_runs() -> Result<Vec<GlobalSpeedTestSummary>> {
+    let dir = runs_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| serde_json::from_str(&fs::read_to_string(entry.path()).ok()?).ok())
+        .collect())
+}
+
+fn find_run(run_id: &str) -> Result<GlobalSpeedTestSummary> {
+    if let Some(summary) = latest_speed_test_summary().filter(|summary| summary.run_id == run_id) {
+        return Ok(summary);
+    }
+    if run_id.is_empty()
+        || !run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(anyhow!("无效的测速记录: {run_id}"));
+    }
+    let path = runs_dir()?.join(format!("{run_id}.json"));
+    if !path.exists() {
+        return Err(anyhow!("测速记录不存在: {run_id}"));
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn build_report(summary: &GlobalSpeedTestSummary) -> SpeedTestReport<'_> {
+    let mut regions: BTreeMap<String, Vec<&SpeedTestResult>> = BTreeMap::new();
+    let mut providers: BTreeMap<String, Vec<&SpeedTestResult>> = BTreeMap::new();
+    for result in &summary.all_results {
+        regions
+            .entry(result.region.clone().unwrap_or_else(|| "其他".to_string()))
+            .or_default()
+            .push(result);
+        providers
+            .entry(result.profile_name.clone())
+            .or_default()
+            .push(result);
+    }
+
+    SpeedTestReport {
+        run_id: &summary.run_id,
+        finished_at: summary.finished_at,
+        generated_at: Local::now().timestamp(),
+        duration_seconds: summary.duration_seconds,
+        total_nodes: summary.total_nodes,
+        successful_tests: summary.successful_tests,
+        failed_tests: summary.failed_tests,
+        best_node: summary.best_node.as_ref(),
+        regions: regions
+            .into_iter()
+            .map(|(name, results)| summarize(name, &results))
+            .collect(),
+        providers: providers
+            .into_iter()
+            .map(|(name, results)| {
+                let traffic = results.iter().find_map(|r| r.traffic_info.as_ref());
+                GroupSummary {
+                    traffic_remaining_percentage: traffic.and_then(|t| t.remaining_percentage),
+                    expire_days: traffic.and_then(|t| t.expire_days),
+                    ..summarize(name, &results)
+                }
+            })
+            .collect(),
+        nodes: &summary.all_results,
+    }
+}
+
+fn summarize(name: String, results: &[&SpeedTestResult]) -> GroupSummary {
+    let available: Vec<&&SpeedTestResult> = results.iter().filter(|r| r.is_available).collect();
+    let latencies: Vec<u64> = available.iter().filter_map(|r| r.latency).collect();
+    let best = available
+        .iter()
+        .filter(|r| r.latency.is_some())
+        .min_by_key(|r| r.latency);
+    GroupSummary {
+        name,
+        nodes: results.len(),
+        available: available.len(),
+        availability: if results.is_empty() {
+            0.0
+        } else {
+            available.len() as f64 / results.len() as f64 * 100.0
+        },
+        avg_latency: (!latencies.is_empty())
+            .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+        min_latency: latencies.iter().min().copied(),
+        best_node: best.map(|r| r.node_name.clone()),
+        traffic_remaining_percentage: None,
+        expire_days: None,
+    }
+}
+
+fn format_time(timestamp: i64, pattern: &str) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format(pattern).to_string())
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(report: &SpeedTestReport) -> String {
+    let mut csv = String::from(
+        "node_name,node_type,server,port,profile_name,region,latency_ms,is_available,score,ipv6_supported,error_message\n",
+    );
+    for node in report.nodes {
+        let fields = [
+            csv_field(&node.node_name),
+            csv_field(&node.node_type),
+            csv_field(&node.server),
+            node.port.to_string(),
+            csv_field(&node.profile_name),
+            csv_field(node.region.as_deref().unwrap_or_default()),
+            node.latency.map(|l| l.to_string()).unwrap_or_default(),
+            node.is_available.to_string(),
+            format!("{:.2}", node.score),
+            node.ipv6_supported
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            csv_field(node.error_message.as_deref().unwrap_or_default()),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn render_group_table(html: &mut String, title: &str, groups: &[GroupSummary], traffic: bool) {
+    let _ = write!(
+        html,
+        "<h2>{title}</h2><table><tr><th>名称</th><th>节点</th><th>可用</th><th>可用率</th>\
+         <th>平均延迟</th><th>最低延迟</th><th>最佳节点</th>"
+    );
+    if traffic {
+        html.push_str("<th>剩余流量</th><th>剩余天数</th>");
+    }
+    html.push_str("</tr>");
+    let ms = |latency: Option<u64>| latency.map_or("-".to_string(), |l| format!("{l} ms"));
+    for group in groups {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}</td><td>{}</td><td>{}</td>",
+            escape_html(&group.name),
+            group.nodes,
+            group.available,
+            group.availability,
+            ms(group.avg_latency),
+            ms(group.min_latency),
+            escape_html(group.best_node.as_deref().unwrap_or("-")),
+        );
+        if traffic {
+            let _ = write!(
+                html,
+                "<td>{}</td><td>{}</td>",
+                group
+                    .traffic_remaining_percentage
+                    .map_or("-".to_string(), |p| format!("{p:.1}%")),
+                group.expire_days.map_or("-".to_string(), |d| d.to_string()),
+            );
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</table>");
+}
+
+/// 生成独立的 HTML 报告，图表数据内嵌在页面中由脚本绘制
+fn render_html(report: &SpeedTestReport) -> Result<String> {
+    // 避免数据中的 </script> 提前结束脚本
+    let data = serde_json::to_string(report)?.replace("</", "<\\/");
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<title>测速报告 {finished}</title>
+<style>
+body {{ font-family: -apple-system, "Segoe UI", "Microsoft YaHei", sans-serif; margin: 24px; color: #222; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 24px; font-size: 13px; }}
+th, td {{ border: 1px solid #ddd; padding: 6px 8px; text-align: left; }}
+th {{ background: #f5f5f5; }}
+.cards {{ display: flex; gap: 12px; margin-bottom: 24px; }}
+.card {{ border: 1px solid #ddd; border-radius: 8px; padding: 12px 16px; min-width: 120px; }}
+.card b {{ display: block; font-size: 22px; }}
+.chart {{ margin-bottom: 24px; }}
+.bar {{ display: flex; align-items: center; margin: 4px 0; font-size: 13px; }}
+.bar span {{ width: 160px; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }}
+.bar div {{ height: 14px; background: #3b82f6; margin: 0 8px; border-radius: 3px; }}
+.fail {{ color: #c00; }}
+</style>
+</head>
+<body>
+<h1>全局测速报告</h1>
+<p>完成时间 {finished}，耗时 {duration} 秒</p>
+<div class="cards">
+<div class="card">节点总数<b>{total}</b></div>
+<div class="card">可用<b>{success}</b></div>
+<div class="card">失败<b>{failed}</b></div>
+<div class="card">最佳节点<b>{best}</b></div>
+</div>
+<h2>各地区平均延迟</h2><div class="chart" id="region-chart"></div>
+<h2>各订阅可用率</h2><div class="chart" id="provider-chart"></div>
+"#,
+        finished = format_time(report.finished_at, "%Y-%m-%d %H:%M:%S"),
+        duration = report.duration_seconds,
+        total = report.total_nodes,
+        success = report.successful_tests,
+        failed = report.failed_tests,
+        best = escape_html(report.best_node.map_or("-", |node| node.node_name.as_str())),
+    );
+
+    render_group_table(&mut html, "地区汇总", &report.regions, false);
+    render_group_table(&mut html, "订阅对比", &report.providers, true);
+
+    html.push_str(
+        "<h2>节点明细</h2><table><tr><th>节点</th><th>类型</th><th>订阅</th><th>地区</th>\
+         <th>延迟</th><th>评分</th><th>IPv6</th><th>错误</th></tr>",
+    );
+    for node in report.nodes {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td{}>{}</td><td>{:.2}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&node.node_name),
+            escape_html(&node.node_type),
+            escape_html(&node.profile_name),
+            escape_html(node.region.as_deref().unwrap_or("-")),
+            if node.is_available {
+                ""
+            } else {
+                " class=\"fail\""
+            },
+            node.latency
+                .filter(|_| node.is_available)
+                .map_or("失败".to_string(), |l| format!("{l} ms")),
+            node.score,
+            node.ipv6_supported
+                .map_or("-", |v| if v { "✔" } else { "✘" }),
+            escape_html(node.error_message.as_deref().unwrap_or_default()),
+        );
+    }
+    html.push_str("</table>\n");
+
+    let _ = write!(
+        html,
+        r#"<script id="report-data" type="application/json">{data}</script>
+<script>
+const report = JSON.parse(document.getElementById("report-data").textContent);
+function drawBars(id, items, value, label) {{
+  const max = Math.max(1, ...items.map(value));
+  const root = document.getElementById(id);
+  for (const item of items) {{
+    const row = document.createElement("div");
+    row.className = "bar";
+    const name = document.createElement("span");
+    name.textContent = item.name;
+    name.title = item.name;
+    const bar = document.createElement("div");
+    bar.style.width = (value(item) / max) * 400 + "px";
+    const text = document.createElement("em");
+    text.textContent = label(item);
+    row.append(name, bar, text);
+    root.append(row);
+  }}
+}}
+drawBars("region-chart", report.regions.filter((r) => r.avg_latency != null),
+  (r) => r.avg_latency, (r) => r.avg_latency + " ms");
+drawBars("provider-chart", report.providers, (p) => p.availability,
+  (p) => p.availability.toFixed(1) + "%");
+</script>
+</body>
+</html>
+"#
+    );
+    Ok(html)
+}
@@ -0,0 +1,179 @@
+use super::CmdResult;
+use crate::{
+    config::Config,
+    core::{CoreManager, handle},
+    logging,
+    utils::logging::Type,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// 启动时最近一次订阅失败，等待用户选择恢复方式
+static PENDING_FAILURE: Mutex<Option<StartupFailure>> = Mutex::new(None);
+
+/// 启动失败所处阶段
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupFailureStage {
+    Generate, // 生成运行时配置失败
+    Validate, // 内核验证配置失败
+}
+
+/// 启动失败详情
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupFailure {
+    pub profile_uid: Option<String>,
+    pub profile_name: Option<String>,
+    pub stage: StartupFailureStage,
+    pub error: String,
+    pub occurred_at: i64,
+}
+
+/// 可切换的其他订阅
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupProfileCandidate {
+    pub uid: String,
+    pub name: String,
+    pub itype: String,
+}
+
+/// 提供给前端的恢复选项
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupRecoveryOptions {
+    pub failure: StartupFailure,
+    pub candidates: Vec<StartupProfileCandidate>,
+    pub can_open_editor: bool,
+}
+
+/// 用户选择的恢复方式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StartupRecoveryChoice {
+    Retry,                         // 重新生成并验证当前订阅
+    SelectProfile { uid: String }, // 切换到其他订阅
+    OpenEditor,                    // 用外部编辑器打开出错的订阅
+    StartMinimal,                  // 保持最小配置运行
+}
+
+/// 获取启动恢复选项，启动正常时返回 None
+#[tauri::command]
+pub async fn get_startup_recovery_options() -> CmdResult<Option<StartupRecoveryOptions>> {
+    let Some(failure) = PENDING_FAILURE.lock().clone() else {
+        return Ok(None);
+    };
+
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    let candidates = profiles
+        .get_items()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| matches!(item.itype.as_deref(), Some("remote" | "local")))
+                .filter_map(|item| {
+                    let uid = item.uid.clone()?;
+                    (failure.profile_uid.as_ref() != Some(&uid)).then(|| StartupProfileCandidate {
+                        name: item.name.clone().unwrap_or_else(|| uid.clone()),
+                        itype: item.itype.clone().unwrap_or_default(),
+                        uid,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let can_open_editor = failure
+        .profile_uid
+        .as_ref()
+        .and_then(|uid| profiles.get_item(uid).ok())
+        .is_some_and(|item| item.file.is_some());
+
+    Ok(Some(StartupRecoveryOptions {
+        failure,
+        candidates,
+        can_open_editor,
+    }))
+}
+
+/// 执行启动恢复选择，返回是否已恢复正常
+/// 打开编辑器后仍保留待恢复状态，编辑完成后可再选择重试
+#[tauri::command]
+pub async fn resolve_startup_recovery(choice: StartupRecoveryChoice) -> CmdResult<bool> {
+    let Some(failure) = PENDING_FAILURE.lock().clone() else {
+        return Ok(true);
+    };
+    logging!(
+        info,
+        Type::Config,
+        true,
+        "[启动恢复] 用户选择: {:?}",
+        choice
+    );
+
+    let resolved = match choice {
+        StartupRecoveryChoice::Retry => match CoreManager::global().update_config().await? {
+            (true, _) => {
+                handle::Handle::refresh_clash();
+                true
+            }
+            (false, error) => {
+                if let Some(pending) = PENDING_FAILURE.lock().as_mut() {
+                    pending.stage = StartupFailureStage::Validate;
+                    pending.error = error;
+                    pending.occurred_at = chrono::Utc::now().timestamp();
+                }
+                false
+            }
+        },
+        StartupRecoveryChoice::SelectProfile { uid } => {
+            if failure.profile_uid.as_ref() == Some(&uid) {
+                return Err("请选择其他订阅".into());
+            }
+            super::patch_profiles_config_by_profile_index(uid).await?
+        }
+        StartupRecoveryChoice::OpenEditor => {
+            let uid = failure
+                .profile_uid
+                .ok_or_else(|| "没有可编辑的订阅".to_string())?;
+            super::view_profile(uid).await?;
+            false
+        }
+        StartupRecoveryChoice::StartMinimal => true,
+    };
+
+    if resolved {
+        *PENDING_FAILURE.lock() = None;
+        logging!(info, Type::Config, true, "[启动恢复] 已处理启动失败");
+    }
+    Ok(resolved)
+}
+
+/// 记录启动阶段的订阅失败，供前端提供恢复选项
+pub async fn record_startup_failure(stage: StartupFailureStage, error: &str) {
+    let (profile_uid, profile_name) = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        let uid = profiles.get_current();
+        let name = uid
+            .as_ref()
+            .and_then(|uid| profiles.get_item(uid).ok())
+            .and_then(|item| item.name.clone());
+        (uid, name)
+    };
+    logging!(
+        warn,
+        Type::Config,
+        true,
+        "[启动恢复] 订阅 {:?} 在 {:?} 阶段失败: {}",
+        profile_uid,
+        stage,
+        error
+    );
+
+    *PENDING_FAILURE.lock() = Some(StartupFailure {
+        profile_uid,
+        profile_name,
+        stage,
+        error: error.to_string(),
+        occurred_at: chrono::Utc::now().timestamp(),
+    });
+}
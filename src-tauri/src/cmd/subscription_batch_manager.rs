@@ -8,6 +8,10 @@
     clippy::needless_pass_by_value
 )]
 // TODO: 后续处理订阅批量管理模块 lint，当前先豁免。
+use super::{
+    CmdResult, OperationKind, ProgressReporter,
+    job_manager::{JobClass, JobKind, JobPriority, run_job_with},
+};
 use crate::config::Config;
 use crate::utils::dirs;
 use anyhow::{Result, anyhow};
@@ -81,7 +85,7 @@ static CLEANUP_SNAPSHOT: once_cell::sync::Lazy<RwLock<CleanupSnapshot>> =
 #[tauri::command]
 pub async fn get_subscription_cleanup_preview(
     options: SubscriptionCleanupOptions,
-) -> Result<CleanupPreview, String> {
+) -> CmdResult<CleanupPreview> {
     let profiles_config = Config::profiles().await;
     let profiles = profiles_config.latest_ref();
 
@@ -163,9 +167,20 @@ pub async fn get_subscription_cleanup_preview(
     Ok(preview)
 }
 
-// 批量更新所有订阅
+// 批量更新所有订阅，通过后台任务队列与定时更新等网络任务协调执行
 #[tauri::command]
-pub async fn update_all_subscriptions() -> Result<BatchUpdateResult, String> {
+pub async fn update_all_subscriptions() -> CmdResult<BatchUpdateResult> {
+    Ok(run_job_with(
+        "Update all subscriptions",
+        JobKind::SubscriptionBatchUpdate,
+        JobClass::Network,
+        JobPriority::High,
+        async { run_all_subscription_updates().await.map_err(String::from) },
+    )
+    .await?)
+}
+
+async fn run_all_subscription_updates() -> CmdResult<BatchUpdateResult> {
     use crate::feat::sync::schedule_subscription_sync;
     use crate::state::subscription_sync::{SUBSCRIPTION_SYNC_STORE, SyncPhase};
     use std::time::Duration;
@@ -193,6 +208,8 @@ pub async fn update_all_subscriptions() -> Result<BatchUpdateResult, String> {
     };
 
     let total_count = remote_profiles.len();
+    let progress = ProgressReporter::new(OperationKind::SubscriptionUpdate, total_count);
+    progress.emit("updating", 0, None);
     let mut updated_subscriptions = Vec::new();
     let mut failed_subscriptions = Vec::new();
     let mut error_messages = HashMap::new();
@@ -233,21 +250,27 @@ pub async fn update_all_subscriptions() -> Result<BatchUpdateResult, String> {
     }
 
     // 等待所有任务完成
-    for handle in handles {
-        match handle.await {
+    for (index, handle) in handles.into_iter().enumerate() {
+        let message = match handle.await {
             Ok(Ok(name)) => {
+                let message = format!("已更新: {}", name);
                 updated_subscriptions.push(name);
+                message
             }
             Ok(Err((name, error))) => {
+                let message = format!("更新失败: {}", name);
                 failed_subscriptions.push(name.clone());
                 error_messages.insert(name, error);
+                message
             }
             Err(e) => {
                 let error_msg = format!("任务执行失败: {}", e);
                 failed_subscriptions.push("未知订阅".to_string());
-                error_messages.insert("未知订阅".to_string(), error_msg);
+                error_messages.insert("未知订阅".to_string(), error_msg.clone());
+                error_msg
             }
-        }
+        };
+        progress.emit("updating", index + 1, Some(message));
     }
 
     let result = BatchUpdateResult {
@@ -260,6 +283,10 @@ pub async fn update_all_subscriptions() -> Result<BatchUpdateResult, String> {
         concurrency_used: concurrency_limit,
         estimated_time_remaining: None,  // 完成后不需要预估时间
     };
+    progress.finish(Some(format!(
+        "更新完成，成功 {} 个，失败 {} 个",
+        result.successful_updates, result.failed_updates
+    )));
 
     Ok(result)
 }
@@ -268,9 +295,9 @@ pub async fn update_all_subscriptions() -> Result<BatchUpdateResult, String> {
 #[tauri::command]
 pub async fn cleanup_expired_subscriptions(
     options: SubscriptionCleanupOptions,
-) -> Result<CleanupResult, String> {
+) -> CmdResult<CleanupResult> {
     if options.preview_only {
-        return Err("预览模式，不执行实际删除操作".to_string());
+        return Err("预览模式，不执行实际删除操作".into());
     }
 
     let preview = get_subscription_cleanup_preview(options.clone()).await?;
@@ -297,7 +324,7 @@ pub async fn cleanup_expired_subscriptions(
                 deleted_subscriptions.push(subscription.name.clone());
             }
             Err(e) => {
-                return Err(format!("删除订阅 {} 失败: {}", subscription.name, e));
+                return Err(format!("删除订阅 {} 失败: {}", subscription.name, e).into());
             }
         }
     }
@@ -324,9 +351,9 @@ pub async fn cleanup_expired_subscriptions(
 #[tauri::command]
 pub async fn cleanup_over_quota_subscriptions(
     options: SubscriptionCleanupOptions,
-) -> Result<CleanupResult, String> {
+) -> CmdResult<CleanupResult> {
     if options.preview_only {
-        return Err("预览模式，不执行实际删除操作".to_string());
+        return Err("预览模式，不执行实际删除操作".into());
     }
 
     let preview = get_over_quota_cleanup_preview(options.clone()).await?;
@@ -353,7 +380,7 @@ pub async fn cleanup_over_quota_subscriptions(
                 deleted_subscriptions.push(subscription.name.clone());
             }
             Err(e) => {
-                return Err(format!("删除订阅 {} 失败: {}", subscription.name, e));
+                return Err(format!("删除订阅 {} 失败: {}", subscription.name, e).into());
             }
         }
     }
@@ -373,7 +400,7 @@ pub async fn cleanup_over_quota_subscriptions(
 pub async fn retry_update_subscriptions(
     uids: Vec<String>,
     retry: Option<u8>,
-) -> Result<BatchUpdateResult, String> {
+) -> CmdResult<BatchUpdateResult> {
     use crate::feat::sync::schedule_subscription_sync;
 
     let max_retry = retry.unwrap_or(2) as usize;
@@ -413,13 +440,13 @@ pub async fn retry_update_subscriptions(
 
 /// 撤销最近一次清理：从内存快照恢复 profiles.yaml
 #[tauri::command]
-pub async fn restore_last_cleanup() -> Result<bool, String> {
+pub async fn restore_last_cleanup() -> CmdResult<bool> {
     let snapshot = CLEANUP_SNAPSHOT.read().await.clone();
     let Some(profiles_yaml) = snapshot.profiles_yaml else {
         return Err("没有可恢复的清理快照".into());
     };
 
-    let path = dirs::profiles_path().map_err(|e| e.to_string())?;
+    let path = dirs::profiles_path()?;
     tokio::fs::write(&path, profiles_yaml)
         .await
         .map_err(|e| format!("写入profiles失败: {}", e))?;
@@ -437,7 +464,7 @@ pub async fn restore_last_cleanup() -> Result<bool, String> {
 #[tauri::command]
 pub async fn get_over_quota_cleanup_preview(
     options: SubscriptionCleanupOptions,
-) -> Result<CleanupPreview, String> {
+) -> CmdResult<CleanupPreview> {
     let profiles_config = Config::profiles().await;
 
     // 提取数据以避免跨await使用不可Send的类型
@@ -535,7 +562,7 @@ fn check_subscription_over_quota(profile: &crate::config::PrfItem) -> bool {
 
 // 获取订阅管理统计信息
 #[tauri::command]
-pub async fn get_subscription_management_stats() -> Result<serde_json::Value, String> {
+pub async fn get_subscription_management_stats() -> CmdResult<serde_json::Value> {
     let profiles_config = Config::profiles().await;
     let profiles = profiles_config.latest_ref();
 
@@ -603,7 +630,7 @@ pub async fn get_subscription_management_stats() -> Result<serde_json::Value, St
 pub async fn set_auto_cleanup_rules(
     enabled: bool,
     cleanup_options: SubscriptionCleanupOptions,
-) -> Result<(), String> {
+) -> CmdResult<()> {
     // TODO: 保存自动清理规则到配置文件
     // 这里应该与任务管理系统集成，创建定时清理任务
 
@@ -620,7 +647,7 @@ pub async fn set_auto_cleanup_rules(
 
 // 获取自动清理规则
 #[tauri::command]
-pub async fn get_auto_cleanup_rules() -> Result<serde_json::Value, String> {
+pub async fn get_auto_cleanup_rules() -> CmdResult<serde_json::Value> {
     // TODO: 从配置文件读取自动清理规则
     let rules = serde_json::json!({
         "enabled": false,
@@ -707,7 +734,7 @@ async fn delete_subscription(uid: &str) -> Result<()> {
 pub async fn set_auto_cleanup_rules(
     enabled: bool,
     cleanup_options: SubscriptionCleanupOptions,
-) -> Result<(), String> {
+) -> CmdResult<()> {
     // TODO: 保存自动清理规则到配置文件
     // 这里应该与任务管理系统集成，创建定时清理任务
 
@@ -724,7 +751,7 @@ pub async fn set_auto_cleanup_rules(
 
 // 获取自动清理规则
 #[tauri::command]
-pub async fn get_auto_cleanup_rules() -> Result<serde_json::Value, String> {
+pub async fn get_auto_cleanup_rules() -> CmdResult<serde_json::Value> {
     // TODO: 从配置文件读取自动清理规则
     let rules = serde_json::json!({
         "enabled": false,
@@ -16,7 +16,11 @@ use crate::config::{
 use crate::core::Timer;
 use crate::logging;
 use crate::process::AsyncHandler;
-use crate::utils::logging::Type;
+use crate::utils::{
+    fetcher::{self, FetchStats},
+    logging::Type,
+    network::ProxyType,
+};
 
 use super::{
     CmdResult,
@@ -25,10 +29,8 @@ use super::{
 
 use anyhow::{Result, anyhow};
 use percent_encoding::percent_decode_str;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::time::Duration;
 use url::Url;
 
 const FETCH_TIMEOUT_SECONDS: u64 = 45;
@@ -50,6 +52,15 @@ pub struct FetchPreviewResult {
     pub preview: Vec<FetchPreviewItem>,
 }
 
+/// 单个订阅的下载统计
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileFetchStatistics {
+    pub uid: String,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub stats: FetchStats,
+}
+
 #[tauri::command]
 pub async fn get_remote_subscription_config() -> CmdResult<RemoteSubscriptionConfig> {
     let verge = Config::verge().await;
@@ -78,6 +89,26 @@ pub async fn save_remote_subscription_config(config: RemoteSubscriptionConfig) -
     Ok(())
 }
 
+/// 获取各远程订阅本次运行以来的下载统计
+#[tauri::command]
+pub async fn get_fetch_statistics() -> CmdResult<Vec<ProfileFetchStatistics>> {
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    Ok(profiles
+        .get_items()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let stats = fetcher::fetch_stats(item.url.as_deref()?)?;
+            Some(ProfileFetchStatistics {
+                uid: item.uid.clone()?,
+                name: item.name.clone(),
+                stats,
+            })
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub async fn fetch_subscription_preview(source_url: String) -> CmdResult<FetchPreviewResult> {
     let text = fetch_remote_text(&source_url).await?;
@@ -199,6 +230,7 @@ async fn perform_sync(
             app_handle.clone(),
             combined_text,
             Some(options),
+            None,
         )
         .await
         .map_err(|e| format!("批量导入失败: {e}"))?
@@ -251,23 +283,23 @@ async fn update_fetch_metadata(summary: FetchSummary) -> CmdResult {
 async fn fetch_remote_text(source_url: &str) -> CmdResult<String> {
     validate_url(source_url).map_err(|err| err.to_string())?;
 
-    let client = Client::builder()
-        .timeout(Duration::from_secs(FETCH_TIMEOUT_SECONDS))
-        .build()
-        .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))?;
-
-    let response = client
-        .get(source_url)
-        .send()
-        .await
-        .map_err(|e| format!("请求订阅列表失败: {e}"))?
-        .error_for_status()
-        .map_err(|e| format!("订阅列表返回异常状态: {e}"))?;
+    let response = fetcher::fetch(
+        source_url,
+        ProxyType::None,
+        None,
+        false,
+        Some(FETCH_TIMEOUT_SECONDS),
+    )
+    .await
+    .map_err(|e| format!("请求订阅列表失败: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("订阅列表返回异常状态: {}", response.status()).into());
+    }
 
     response
-        .text()
-        .await
-        .map_err(|e| format!("读取订阅列表内容失败: {e}"))
+        .text_with_charset()
+        .map(str::to_string)
+        .map_err(|e| format!("读取订阅列表内容失败: {e}").into())
 }
 
 fn parse_subscription_lines(text: &str) -> Vec<String> {
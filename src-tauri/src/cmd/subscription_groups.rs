@@ -10,8 +10,16 @@
     clippy::enum_variant_names
 )]
 // TODO: 后续专门清理订阅分组模块的 lint 警告。
-use super::CmdResult;
-use crate::{config::Config, logging, utils::logging::Type};
+use super::{
+    CmdError, CmdResult, ErrorCode, GlobalSpeedTestSummary, QualityGrade, SubscriptionTestResult,
+    TestResultStatus, TestType, WithErrorCode, latest_speed_test_summary,
+    selection_memory::detect_region, test_subscription,
+};
+use crate::{
+    config::{Config, PrfItem},
+    logging,
+    utils::{dirs, logging::Type},
+};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -61,6 +69,8 @@ pub struct SubscriptionGroup {
     pub is_favorite: bool,
     pub sort_order: i32,
     pub auto_rules: Vec<AutoRule>,
+    #[serde(default)]
+    pub grouping_rule: Option<GroupingRule>, // 基于节点元数据的组合规则
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -102,6 +112,58 @@ pub enum RuleCondition {
     Between,
 }
 
+/// 基于节点元数据的分组规则，支持 AND/OR 组合
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GroupingRule {
+    And {
+        rules: Vec<GroupingRule>,
+    },
+    Or {
+        rules: Vec<GroupingRule>,
+    },
+    Condition {
+        attribute: NodeAttribute,
+        /// RegionRatio 的地区代码（如 HK）或 ProtocolRatio 的协议（如 vmess）
+        #[serde(default)]
+        key: Option<String>,
+        condition: RuleCondition,
+        value: String,
+    },
+}
+
+/// 规则可使用的订阅属性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeAttribute {
+    Name,           // 订阅名称
+    ProviderDomain, // 订阅地址域名
+    NodeCount,      // 节点数量
+    RegionCount,    // 覆盖地区数量
+    RegionRatio,    // 指定地区节点占比（0-100）
+    ProtocolRatio,  // 指定协议节点占比（0-100）
+    AvgLatency,     // 最近一次测速的平均延迟（毫秒）
+}
+
+/// 订阅节点元数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionMetadata {
+    pub uid: String,
+    pub name: Option<String>,
+    pub provider_domain: Option<String>,
+    pub node_count: usize,
+    pub regions: HashMap<String, usize>,
+    pub protocols: HashMap<String, usize>,
+    pub avg_latency_ms: Option<f64>,
+}
+
+/// 规则试算结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupingEvaluation {
+    pub matched: bool,
+    pub metadata: SubscriptionMetadata,
+}
+
 /// 分组统计信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupStatistics {
@@ -114,6 +176,36 @@ pub struct GroupStatistics {
     pub avg_speed_mbps: f64,
     pub health_score: f64,
     pub last_updated: i64,
+    #[serde(default)]
+    pub quality: Option<GroupQualityReport>, // 最近一次分组测速报告
+}
+
+/// 分组测速质量报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupQualityReport {
+    pub group_id: String,
+    pub test_type: TestType,
+    pub total_nodes: usize,
+    pub working_nodes: usize,
+    pub median_latency_ms: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    pub avg_speed_mbps: Option<f64>,
+    pub best_subscription: Option<GroupSubscriptionQuality>,
+    pub region_coverage: HashMap<String, usize>, // 各地区可用节点数
+    pub subscriptions: Vec<GroupSubscriptionQuality>,
+    pub errors: Vec<String>,
+    pub tested_at: i64,
+}
+
+/// 分组内单个订阅的测速概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSubscriptionQuality {
+    pub uid: String,
+    pub name: String,
+    pub quality_grade: QualityGrade,
+    pub total_nodes: usize,
+    pub passed_nodes: usize,
+    pub avg_latency_ms: Option<f64>,
 }
 
 /// 批量操作结果
@@ -148,6 +240,7 @@ pub struct GroupSuggestion {
 struct GroupStorage {
     groups: HashMap<String, SubscriptionGroup>,
     subscription_to_groups: HashMap<String, HashSet<String>>,
+    quality_reports: HashMap<String, GroupQualityReport>,
 }
 
 impl GroupStorage {
@@ -155,10 +248,16 @@ impl GroupStorage {
         Self {
             groups: HashMap::new(),
             subscription_to_groups: HashMap::new(),
+            quality_reports: HashMap::new(),
         }
     }
 }
 
+/// 清空所有分组（覆盖恢复使用）
+pub(crate) async fn clear_subscription_groups() {
+    *SUBSCRIPTION_GROUPS.write().await = GroupStorage::new();
+}
+
 /// 创建分组
 #[tauri::command]
 pub async fn create_subscription_group(group: SubscriptionGroup) -> CmdResult<String> {
@@ -252,6 +351,7 @@ pub async fn delete_subscription_group(group_id: String) -> CmdResult<()> {
                 }
             }
         }
+        storage.quality_reports.remove(&group_id);
     }
 
     Ok(())
@@ -286,7 +386,7 @@ pub async fn get_subscription_group(group_id: String) -> CmdResult<SubscriptionG
         .groups
         .get(&group_id)
         .cloned()
-        .ok_or_else(|| "分组不存在".to_string())
+        .ok_or_else(|| CmdError::new(ErrorCode::NotFound, "分组不存在"))
 }
 
 /// 添加订阅到分组
@@ -319,7 +419,7 @@ pub async fn add_subscription_to_group(
                 .insert(group_id);
         }
     } else {
-        return Err("分组不存在".to_string());
+        return Err(CmdError::new(ErrorCode::NotFound, "分组不存在"));
     }
 
     Ok(())
@@ -528,6 +628,19 @@ pub async fn apply_auto_grouping_rules() -> CmdResult<BatchOperationResult> {
     let start_time = std::time::Instant::now();
     logging!(info, Type::Cmd, true, "[分组管理] 应用自动分组规则");
 
+    // 组合规则需要读取节点元数据，提前收集避免持锁读取文件
+    let needs_metadata = SUBSCRIPTION_GROUPS
+        .read()
+        .await
+        .groups
+        .values()
+        .any(|g| g.grouping_rule.is_some());
+    let metadata = if needs_metadata {
+        collect_all_metadata().await
+    } else {
+        Vec::new()
+    };
+
     let mut storage = SUBSCRIPTION_GROUPS.write().await;
     let mut successful = 0;
     let errors = Vec::new();
@@ -579,6 +692,18 @@ pub async fn apply_auto_grouping_rules() -> CmdResult<BatchOperationResult> {
             }
         }
 
+        if let Some(rule) = &group.grouping_rule {
+            for meta in &metadata {
+                let addition = (group.id.clone(), meta.uid.clone());
+                if !group.subscription_uids.contains(&meta.uid)
+                    && !additions.contains(&addition)
+                    && rule.evaluate(meta)
+                {
+                    additions.push(addition);
+                }
+            }
+        }
+
         group_updates.push(group.id.clone());
     }
 
@@ -616,6 +741,70 @@ pub async fn apply_auto_grouping_rules() -> CmdResult<BatchOperationResult> {
     })
 }
 
+/// 试算分组规则，返回是否命中及订阅的节点元数据
+#[tauri::command]
+pub async fn evaluate_grouping_rule(
+    rule: GroupingRule,
+    uid: String,
+) -> CmdResult<GroupingEvaluation> {
+    let item = {
+        let profiles = Config::profiles().await;
+        profiles
+            .latest_ref()
+            .get_item(&uid)
+            .with_code(ErrorCode::ProfileNotFound)?
+            .clone()
+    };
+    let metadata = collect_subscription_metadata(&item, latest_speed_test_summary().as_ref()).await;
+
+    Ok(GroupingEvaluation {
+        matched: rule.evaluate(&metadata),
+        metadata,
+    })
+}
+
+/// 测试分组内所有订阅的节点，生成分组质量报告
+#[tauri::command]
+pub async fn test_subscription_group(
+    group_id: String,
+    test_type: TestType,
+) -> CmdResult<GroupQualityReport> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[分组管理] 分组测速: {} ({:?})",
+        group_id,
+        test_type
+    );
+
+    let uids = SUBSCRIPTION_GROUPS
+        .read()
+        .await
+        .groups
+        .get(&group_id)
+        .map(|group| group.subscription_uids.clone())
+        .ok_or_else(|| "分组不存在".to_string())?;
+
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for uid in uids {
+        match test_subscription(uid.clone(), test_type.clone(), None).await {
+            Ok(result) => results.push(result),
+            Err(e) => errors.push(format!("{}: {}", uid, e)),
+        }
+    }
+
+    let report = build_quality_report(group_id.clone(), test_type, &results, errors);
+    SUBSCRIPTION_GROUPS
+        .write()
+        .await
+        .quality_reports
+        .insert(group_id, report.clone());
+
+    Ok(report)
+}
+
 /// 获取分组统计信息
 #[tauri::command]
 pub async fn get_group_statistics(group_id: String) -> CmdResult<GroupStatistics> {
@@ -630,22 +819,12 @@ pub async fn get_group_statistics(group_id: String) -> CmdResult<GroupStatistics
     let storage = SUBSCRIPTION_GROUPS.read().await;
 
     if let Some(group) = storage.groups.get(&group_id) {
-        // TODO: 从健康检查和测试结果中获取实际统计数据
-        let stats = GroupStatistics {
-            group_id: group.id.clone(),
-            group_name: group.name.clone(),
-            total_subscriptions: group.subscription_uids.len(),
-            active_subscriptions: group.subscription_uids.len(), // 简化实现
-            total_nodes: 0,                                      // TODO: 从订阅配置中计算节点数
-            avg_latency_ms: 0.0,                                 // TODO: 从测试结果中计算
-            avg_speed_mbps: 0.0,                                 // TODO: 从测试结果中计算
-            health_score: 100.0,                                 // TODO: 从健康检查结果中计算
-            last_updated: group.updated_at,
-        };
-
-        Ok(stats)
+        Ok(build_group_statistics(
+            group,
+            storage.quality_reports.get(&group_id),
+        ))
     } else {
-        Err("分组不存在".to_string())
+        Err(CmdError::new(ErrorCode::NotFound, "分组不存在"))
     }
 }
 
@@ -658,19 +837,10 @@ pub async fn get_all_group_statistics() -> CmdResult<Vec<GroupStatistics>> {
     let mut statistics = Vec::new();
 
     for group in storage.groups.values() {
-        let stats = GroupStatistics {
-            group_id: group.id.clone(),
-            group_name: group.name.clone(),
-            total_subscriptions: group.subscription_uids.len(),
-            active_subscriptions: group.subscription_uids.len(),
-            total_nodes: 0,
-            avg_latency_ms: 0.0,
-            avg_speed_mbps: 0.0,
-            health_score: 100.0,
-            last_updated: group.updated_at,
-        };
-
-        statistics.push(stats);
+        statistics.push(build_group_statistics(
+            group,
+            storage.quality_reports.get(&group.id),
+        ));
     }
 
     Ok(statistics)
@@ -856,6 +1026,7 @@ pub async fn create_default_groups() -> CmdResult<Vec<String>> {
             tags: vec!["favorite".to_string()],
             is_favorite: true,
             sort_order: 0,
+            grouping_rule: None,
             auto_rules: Vec::new(),
             created_at: 0,
             updated_at: 0,
@@ -871,6 +1042,7 @@ pub async fn create_default_groups() -> CmdResult<Vec<String>> {
             tags: vec!["fast".to_string()],
             is_favorite: false,
             sort_order: 1,
+            grouping_rule: None,
             auto_rules: vec![AutoRule {
                 rule_type: RuleType::SpeedRange,
                 condition: RuleCondition::GreaterThan,
@@ -891,6 +1063,7 @@ pub async fn create_default_groups() -> CmdResult<Vec<String>> {
             tags: vec!["gaming".to_string(), "low-latency".to_string()],
             is_favorite: false,
             sort_order: 2,
+            grouping_rule: None,
             auto_rules: vec![
                 AutoRule {
                     rule_type: RuleType::NameContains,
@@ -953,3 +1126,279 @@ fn apply_string_condition(text: &str, condition: &RuleCondition, value: &str) ->
         _ => false,
     }
 }
+
+/// 数值条件，Between 的值格式为 "min,max"
+fn apply_numeric_condition(number: f64, condition: &RuleCondition, value: &str) -> bool {
+    let parse = |v: &str| v.trim().parse::<f64>().ok();
+    match condition {
+        RuleCondition::GreaterThan => parse(value).is_some_and(|v| number > v),
+        RuleCondition::LessThan => parse(value).is_some_and(|v| number < v),
+        RuleCondition::Equals => parse(value).is_some_and(|v| (number - v).abs() < f64::EPSILON),
+        RuleCondition::NotEquals => {
+            parse(value).is_some_and(|v| (number - v).abs() >= f64::EPSILON)
+        }
+        RuleCondition::Between => value
+            .split_once(',')
+            .and_then(|(min, max)| Some((parse(min)?, parse(max)?)))
+            .is_some_and(|(min, max)| number >= min && number <= max),
+        _ => false,
+    }
+}
+
+impl GroupingRule {
+    /// 计算订阅是否满足规则
+    pub fn evaluate(&self, meta: &SubscriptionMetadata) -> bool {
+        match self {
+            Self::And { rules } => rules.iter().all(|r| r.evaluate(meta)),
+            Self::Or { rules } => rules.iter().any(|r| r.evaluate(meta)),
+            Self::Condition {
+                attribute,
+                key,
+                condition,
+                value,
+            } => {
+                let ratio = |counts: &HashMap<String, usize>| {
+                    let key = key.as_deref().unwrap_or_default().to_lowercase();
+                    let count = counts
+                        .iter()
+                        .filter(|(k, _)| k.to_lowercase() == key)
+                        .map(|(_, c)| *c)
+                        .sum::<usize>();
+                    if meta.node_count == 0 {
+                        0.0
+                    } else {
+                        count as f64 / meta.node_count as f64 * 100.0
+                    }
+                };
+
+                match attribute {
+                    NodeAttribute::Name => meta
+                        .name
+                        .as_deref()
+                        .is_some_and(|name| apply_string_condition(name, condition, value)),
+                    NodeAttribute::ProviderDomain => meta
+                        .provider_domain
+                        .as_deref()
+                        .is_some_and(|domain| apply_string_condition(domain, condition, value)),
+                    NodeAttribute::NodeCount => {
+                        apply_numeric_condition(meta.node_count as f64, condition, value)
+                    }
+                    NodeAttribute::RegionCount => {
+                        apply_numeric_condition(meta.regions.len() as f64, condition, value)
+                    }
+                    NodeAttribute::RegionRatio => {
+                        apply_numeric_condition(ratio(&meta.regions), condition, value)
+                    }
+                    NodeAttribute::ProtocolRatio => {
+                        apply_numeric_condition(ratio(&meta.protocols), condition, value)
+                    }
+                    NodeAttribute::AvgLatency => meta
+                        .avg_latency_ms
+                        .is_some_and(|latency| apply_numeric_condition(latency, condition, value)),
+                }
+            }
+        }
+    }
+}
+
+/// 收集所有远程订阅的节点元数据
+async fn collect_all_metadata() -> Vec<SubscriptionMetadata> {
+    let items: Vec<PrfItem> = {
+        let profiles = Config::profiles().await;
+        let profiles_ref = profiles.latest_ref();
+        profiles_ref
+            .items
+            .iter()
+            .flatten()
+            .filter(|item| item.itype.as_deref() == Some("remote") && item.uid.is_some())
+            .cloned()
+            .collect()
+    };
+
+    let summary = latest_speed_test_summary();
+    let mut metadata = Vec::with_capacity(items.len());
+    for item in &items {
+        metadata.push(collect_subscription_metadata(item, summary.as_ref()).await);
+    }
+    metadata
+}
+
+/// 解析订阅文件中的节点，统计地区与协议分布
+async fn collect_subscription_metadata(
+    item: &PrfItem,
+    summary: Option<&GlobalSpeedTestSummary>,
+) -> SubscriptionMetadata {
+    let uid = item.uid.clone().unwrap_or_default();
+    let mut metadata = SubscriptionMetadata {
+        uid: uid.clone(),
+        name: item.name.clone(),
+        provider_domain: item
+            .url
+            .as_deref()
+            .and_then(|url| url::Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string)),
+        ..SubscriptionMetadata::default()
+    };
+
+    let content = match (&item.file, dirs::app_profiles_dir()) {
+        (Some(file), Ok(dir)) => tokio::fs::read_to_string(dir.join(file)).await.ok(),
+        _ => None,
+    };
+    let proxies = content
+        .and_then(|content| serde_yaml_ng::from_str::<serde_yaml_ng::Mapping>(&content).ok())
+        .and_then(|config| config.get("proxies").and_then(|p| p.as_sequence()).cloned())
+        .unwrap_or_default();
+
+    for proxy in &proxies {
+        let name = proxy
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+        let protocol = proxy
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("unknown")
+            .to_lowercase();
+        let region = super::selection_memory::detect_region(name).unwrap_or("OTHER");
+
+        metadata.node_count += 1;
+        *metadata.regions.entry(region.to_string()).or_default() += 1;
+        *metadata.protocols.entry(protocol).or_default() += 1;
+    }
+
+    if let Some(summary) = summary {
+        let latencies: Vec<u64> = summary
+            .all_results
+            .iter()
+            .filter(|r| r.profile_uid == uid && r.is_available)
+            .filter_map(|r| r.latency)
+            .collect();
+        if !latencies.is_empty() {
+            metadata.avg_latency_ms =
+                Some(latencies.iter().sum::<u64>() as f64 / latencies.len() as f64);
+        }
+    }
+
+    metadata
+}
+
+/// 根据分组测速结果汇总质量报告
+fn build_quality_report(
+    group_id: String,
+    test_type: TestType,
+    results: &[SubscriptionTestResult],
+    errors: Vec<String>,
+) -> GroupQualityReport {
+    let mut latencies = Vec::new();
+    let mut speeds = Vec::new();
+    let mut region_coverage: HashMap<String, usize> = HashMap::new();
+
+    for node in results.iter().flat_map(|r| &r.node_results) {
+        if !matches!(node.status, TestResultStatus::Pass) {
+            continue;
+        }
+        if let Some(latency) = node.latency_ms {
+            latencies.push(f64::from(latency));
+        }
+        if let Some(speed) = node.download_speed_mbps {
+            speeds.push(speed);
+        }
+        let region = detect_region(&node.node_name).unwrap_or("OTHER");
+        *region_coverage.entry(region.to_string()).or_default() += 1;
+    }
+
+    let average = |values: &[f64]| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    latencies.sort_by(f64::total_cmp);
+    let median_latency_ms = match latencies.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(latencies[n / 2]),
+        n => Some((latencies[n / 2 - 1] + latencies[n / 2]) / 2.0),
+    };
+
+    let subscriptions: Vec<GroupSubscriptionQuality> = results
+        .iter()
+        .map(|r| GroupSubscriptionQuality {
+            uid: r.subscription_uid.clone(),
+            name: r.subscription_name.clone(),
+            quality_grade: r.quality_grade.clone(),
+            total_nodes: r.total_nodes,
+            passed_nodes: r.passed_nodes,
+            avg_latency_ms: r.avg_latency_ms,
+        })
+        .collect();
+
+    // 可用率最高者优先，相同时取平均延迟更低的订阅
+    let pass_rate = |s: &GroupSubscriptionQuality| {
+        if s.total_nodes == 0 {
+            0.0
+        } else {
+            s.passed_nodes as f64 / s.total_nodes as f64
+        }
+    };
+    let best_subscription = subscriptions
+        .iter()
+        .filter(|s| s.passed_nodes > 0)
+        .max_by(|a, b| {
+            pass_rate(a).total_cmp(&pass_rate(b)).then_with(|| {
+                let latency = |s: &GroupSubscriptionQuality| s.avg_latency_ms.unwrap_or(f64::MAX);
+                latency(b).total_cmp(&latency(a))
+            })
+        })
+        .cloned();
+
+    GroupQualityReport {
+        group_id,
+        test_type,
+        total_nodes: results.iter().map(|r| r.total_nodes).sum(),
+        working_nodes: results.iter().map(|r| r.passed_nodes).sum(),
+        median_latency_ms,
+        avg_latency_ms: average(&latencies),
+        avg_speed_mbps: average(&speeds),
+        best_subscription,
+        region_coverage,
+        subscriptions,
+        errors,
+        tested_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// 生成分组统计，有测速报告时使用报告中的数据
+fn build_group_statistics(
+    group: &SubscriptionGroup,
+    report: Option<&GroupQualityReport>,
+) -> GroupStatistics {
+    let mut stats = GroupStatistics {
+        group_id: group.id.clone(),
+        group_name: group.name.clone(),
+        total_subscriptions: group.subscription_uids.len(),
+        active_subscriptions: group.subscription_uids.len(),
+        total_nodes: 0,
+        avg_latency_ms: 0.0,
+        avg_speed_mbps: 0.0,
+        health_score: 100.0,
+        last_updated: group.updated_at,
+        quality: None,
+    };
+
+    if let Some(report) = report {
+        stats.active_subscriptions = report
+            .subscriptions
+            .iter()
+            .filter(|s| s.passed_nodes > 0)
+            .count();
+        stats.total_nodes = report.total_nodes;
+        stats.avg_latency_ms = report.avg_latency_ms.unwrap_or_default();
+        stats.avg_speed_mbps = report.avg_speed_mbps.unwrap_or_default();
+        stats.health_score = if report.total_nodes == 0 {
+            0.0
+        } else {
+            report.working_nodes as f64 / report.total_nodes as f64 * 100.0
+        };
+        stats.last_updated = stats.last_updated.max(report.tested_at);
+        stats.quality = Some(report.clone());
+    }
+
+    stats
+}
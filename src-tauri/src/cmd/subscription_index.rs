@@ -0,0 +1,501 @@
+use super::{
+    CmdError, CmdResult,
+    batch_import::{ImportResult, ImportStatus, parse_subscription_urls, validate_urls},
+};
+use crate::{
+    config::{Config, PrfOption},
+    core::handle,
+    logging,
+    process::AsyncHandler,
+    utils::{
+        dirs,
+        logging::Type,
+        network::{NetworkManager, ProxyType},
+    },
+};
+use anyhow::{Context, Result, bail};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+/// 调度检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(600);
+/// 拉取索引文件的超时（秒）
+const INDEX_FETCH_TIMEOUT: u64 = 30;
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+/// 同一时间只执行一次索引同步，避免重复导入子订阅
+static SYNC_RUNNING: Mutex<()> = Mutex::const_new(());
+
+/// 订阅索引选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionIndexOptions {
+    pub name: Option<String>,
+    /// 索引自动同步间隔（小时），0 表示仅手动同步
+    pub update_interval_hours: u32,
+    /// 索引中移除的条目是否同时删除对应订阅，仅删除由索引导入的订阅
+    pub remove_missing: bool,
+    /// 子订阅的 User-Agent
+    pub user_agent: Option<String>,
+}
+
+impl Default for SubscriptionIndexOptions {
+    fn default() -> Self {
+        Self {
+            name: None,
+            update_interval_hours: 24,
+            remove_missing: false,
+            user_agent: Some("clash-verge-rev".to_string()),
+        }
+    }
+}
+
+/// 由索引管理的子订阅
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexMember {
+    pub url: String,
+    pub uid: String,
+    /// 由索引导入的订阅；纳入跟踪的已有订阅为 false，不会被索引删除
+    #[serde(default)]
+    pub owned: bool,
+}
+
+/// 订阅索引（订阅的订阅）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionIndex {
+    pub id: String,
+    pub url: String,
+    pub options: SubscriptionIndexOptions,
+    pub members: Vec<IndexMember>,
+    pub last_sync: Option<i64>,
+    pub last_error: Option<String>,
+    #[serde(default)]
+    last_fingerprint: Option<String>, // 上次索引内容摘要，内容未变化时跳过
+}
+
+/// 单次索引同步结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionIndexSyncResult {
+    pub index_id: String,
+    /// 索引内容未变化，跳过同步
+    pub unchanged: bool,
+    pub total: usize,
+    pub kept: usize,
+    pub added: Vec<ImportResult>,
+    /// 被删除或不再跟踪的子订阅 uid
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct SubscriptionIndexState {
+    indexes: Vec<SubscriptionIndex>,
+}
+
+/// 导入订阅索引，已存在相同地址的索引时更新选项并立即同步
+#[tauri::command]
+pub async fn import_subscription_index(
+    url: String,
+    options: Option<SubscriptionIndexOptions>,
+) -> CmdResult<SubscriptionIndexSyncResult> {
+    let url = url.trim().to_string();
+    let (valid, invalid) = validate_urls(vec![url.clone()]);
+    if valid.is_empty() {
+        let reason = invalid
+            .into_iter()
+            .find_map(|r| r.error_message)
+            .unwrap_or_default();
+        return Err(format!("索引地址无效: {}", reason).into());
+    }
+
+    let index_id = {
+        let _guard = SYNC_RUNNING.lock().await;
+        let mut state = load_state().unwrap_or_default();
+        let id = match state.indexes.iter_mut().find(|index| index.url == url) {
+            Some(index) => {
+                if let Some(options) = options {
+                    index.options = options;
+                }
+                // 强制重新同步
+                index.last_fingerprint = None;
+                index.id.clone()
+            }
+            None => {
+                let id = nanoid!();
+                state.indexes.push(SubscriptionIndex {
+                    id: id.clone(),
+                    url: url.clone(),
+                    options: options.unwrap_or_default(),
+                    members: Vec::new(),
+                    last_sync: None,
+                    last_error: None,
+                    last_fingerprint: None,
+                });
+                id
+            }
+        };
+        save_state(&state).context("保存订阅索引失败")?;
+        id
+    };
+
+    logging!(info, Type::Cmd, true, "[订阅索引] 导入索引 {}", index_id);
+    sync_subscription_index(index_id).await
+}
+
+/// 立即同步指定订阅索引
+#[tauri::command]
+pub async fn sync_subscription_index(index_id: String) -> CmdResult<SubscriptionIndexSyncResult> {
+    sync_index(&index_id)
+        .await
+        .context("同步订阅索引失败")
+        .map_err(CmdError::from)
+}
+
+/// 获取所有订阅索引
+#[tauri::command]
+pub async fn list_subscription_indexes() -> CmdResult<Vec<SubscriptionIndex>> {
+    load_state()
+        .map(|state| state.indexes)
+        .context("加载订阅索引失败")
+        .map_err(CmdError::from)
+}
+
+/// 移除订阅索引，delete_members 为 true 时同时删除其管理的子订阅
+#[tauri::command]
+pub async fn remove_subscription_index(index_id: String, delete_members: bool) -> CmdResult<()> {
+    let removed = {
+        let _guard = SYNC_RUNNING.lock().await;
+        let mut state = load_state().unwrap_or_default();
+        let Some(pos) = state.indexes.iter().position(|index| index.id == index_id) else {
+            return Err(format!("订阅索引不存在: {}", index_id).into());
+        };
+        let removed = state.indexes.remove(pos);
+        save_state(&state).context("保存订阅索引失败")?;
+        removed
+    };
+
+    if delete_members {
+        for member in removed.members.into_iter().filter(|m| m.owned) {
+            if let Err(e) = super::delete_profile(member.uid.clone()).await {
+                logging!(
+                    warn,
+                    Type::Cmd,
+                    true,
+                    "[订阅索引] 删除子订阅 {} 失败: {}",
+                    member.uid,
+                    e
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 启动订阅索引同步调度器
+pub fn init_subscription_index_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+
+            let now = chrono::Utc::now().timestamp();
+            let due: Vec<String> = load_state()
+                .unwrap_or_default()
+                .indexes
+                .into_iter()
+                .filter(|index| {
+                    index.options.update_interval_hours > 0
+                        && index.last_sync.is_none_or(|last| {
+                            now - last >= i64::from(index.options.update_interval_hours) * 3600
+                        })
+                })
+                .map(|index| index.id)
+                .collect();
+
+            for index_id in due {
+                if let Err(e) = sync_index(&index_id).await {
+                    logging!(
+                        warn,
+                        Type::Cmd,
+                        true,
+                        "[订阅索引] 定时同步 {} 失败: {}",
+                        index_id,
+                        e
+                    );
+                }
+            }
+        }
+    });
+}
+
+// ===== 内部实现函数 =====
+
+/// 拉取索引并使子订阅与索引条目保持一致
+async fn sync_index(index_id: &str) -> Result<SubscriptionIndexSyncResult> {
+    let _guard = SYNC_RUNNING.lock().await;
+    let mut state = load_state()?;
+    let Some(mut index) = state.indexes.iter().find(|i| i.id == index_id).cloned() else {
+        bail!("订阅索引不存在: {}", index_id);
+    };
+
+    let content = match fetch_index(&index).await {
+        Ok(content) => content,
+        Err(e) => {
+            index.last_error = Some(e.to_string());
+            index.last_sync = Some(chrono::Utc::now().timestamp());
+            store_index(&mut state, index)?;
+            return Err(e);
+        }
+    };
+
+    let fingerprint = format!("{:x}", Sha256::digest(content.as_bytes()));
+    let unchanged = index.last_fingerprint.as_deref() == Some(fingerprint.as_str());
+    let mut result = SubscriptionIndexSyncResult {
+        index_id: index.id.clone(),
+        unchanged,
+        total: 0,
+        kept: 0,
+        added: Vec::new(),
+        removed: Vec::new(),
+    };
+
+    if !unchanged {
+        let urls = parse_index_urls(&content)?;
+        result.total = urls.len();
+        reconcile_members(&mut index, urls, &mut result).await;
+        // 有子订阅导入失败时不记录摘要，下次同步重试
+        if result
+            .added
+            .iter()
+            .all(|r| matches!(r.status, ImportStatus::Success))
+        {
+            index.last_fingerprint = Some(fingerprint);
+        }
+    } else {
+        result.total = index.members.len();
+        result.kept = index.members.len();
+    }
+
+    index.last_error = None;
+    index.last_sync = Some(chrono::Utc::now().timestamp());
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[订阅索引] {} 同步完成: 共 {} 条, 保留 {}, 新增 {}, 移除 {}",
+        index.id,
+        result.total,
+        result.kept,
+        result.added.len(),
+        result.removed.len()
+    );
+    store_index(&mut state, index)?;
+    Ok(result)
+}
+
+/// 拉取索引文件内容
+async fn fetch_index(index: &SubscriptionIndex) -> Result<String> {
+    let resp = NetworkManager::new()
+        .get_with_interrupt(
+            &index.url,
+            ProxyType::None,
+            Some(INDEX_FETCH_TIMEOUT),
+            index.options.user_agent.clone(),
+            false,
+        )
+        .await?;
+    let status = resp.status();
+    if !status.is_success() {
+        bail!("拉取索引失败，状态码 {}", status);
+    }
+    // 登录页、错误页等 HTML 页面中的链接不是订阅地址
+    let is_html = resp
+        .headers()
+        .get("Content-Type")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.to_ascii_lowercase().contains("html"));
+    if is_html {
+        bail!("索引地址返回的是网页而非订阅列表");
+    }
+    Ok(resp.text_with_charset()?.to_string())
+}
+
+/// 解析索引中的订阅地址，保持原有顺序并去重
+fn parse_index_urls(content: &str) -> Result<Vec<String>> {
+    let urls = parse_subscription_urls(content).map_err(anyhow::Error::msg)?;
+    let (valid, _) = validate_urls(urls);
+    let mut seen = HashSet::new();
+    Ok(valid
+        .into_iter()
+        .filter(|url| seen.insert(url.clone()))
+        .collect())
+}
+
+/// 新增条目导入为子订阅，移除条目按选项删除或取消跟踪
+async fn reconcile_members(
+    index: &mut SubscriptionIndex,
+    urls: Vec<String>,
+    result: &mut SubscriptionIndexSyncResult,
+) {
+    let existing: Vec<(String, String)> = {
+        let profiles = Config::profiles().await;
+        let profiles_ref = profiles.latest_ref();
+        profiles_ref
+            .items
+            .as_ref()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| Some((item.uid.clone()?, item.url.clone()?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let uid_exists = |uid: &str| existing.iter().any(|(u, _)| u == uid);
+    let uid_for_url = |url: &str| {
+        existing
+            .iter()
+            .find(|(_, u)| u == url)
+            .map(|(uid, _)| uid.clone())
+    };
+
+    // 用户手动删除的子订阅不再跟踪，若仍在索引中会重新导入
+    index.members.retain(|member| uid_exists(&member.uid));
+
+    let wanted: HashSet<&String> = urls.iter().collect();
+    let (kept, stale): (Vec<IndexMember>, Vec<IndexMember>) = index
+        .members
+        .drain(..)
+        .partition(|member| wanted.contains(&member.url));
+    result.kept = kept.len();
+    index.members = kept;
+
+    for member in stale {
+        if index.options.remove_missing
+            && member.owned
+            && let Err(e) = super::delete_profile(member.uid.clone()).await
+        {
+            logging!(
+                warn,
+                Type::Cmd,
+                true,
+                "[订阅索引] 删除子订阅 {} 失败: {}",
+                member.uid,
+                e
+            );
+        }
+        result.removed.push(member.uid);
+    }
+
+    let tracked: HashSet<String> = index.members.iter().map(|m| m.url.clone()).collect();
+    for url in urls.into_iter().filter(|url| !tracked.contains(url)) {
+        // 已手动导入过的订阅直接纳入索引管理
+        if let Some(uid) = uid_for_url(&url) {
+            index.members.push(IndexMember {
+                url,
+                uid,
+                owned: false,
+            });
+            result.kept += 1;
+            continue;
+        }
+
+        let option = PrfOption {
+            user_agent: index.options.user_agent.clone(),
+            ..Default::default()
+        };
+        let imported = match super::import_profile(url.clone(), Some(option)).await {
+            Ok(()) => find_uid_by_url(&url)
+                .await
+                .ok_or_else(|| "导入后未找到订阅".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match imported {
+            Ok(uid) => {
+                index.members.push(IndexMember {
+                    url: url.clone(),
+                    uid: uid.clone(),
+                    owned: true,
+                });
+                result.added.push(ImportResult {
+                    url,
+                    name: None,
+                    status: ImportStatus::Success,
+                    error_message: None,
+                    uid: Some(uid),
+                });
+            }
+            Err(e) => {
+                result.added.push(ImportResult {
+                    url,
+                    name: None,
+                    status: ImportStatus::Failed,
+                    error_message: Some(e),
+                    uid: None,
+                });
+            }
+        }
+    }
+}
+
+async fn find_uid_by_url(url: &str) -> Option<String> {
+    let profiles = Config::profiles().await;
+    let profiles_ref = profiles.latest_ref();
+    profiles_ref
+        .items
+        .as_ref()?
+        .iter()
+        .find(|item| item.url.as_deref() == Some(url))
+        .and_then(|item| item.uid.clone())
+}
+
+/// 写回单个索引并保存
+fn store_index(state: &mut SubscriptionIndexState, index: SubscriptionIndex) -> Result<()> {
+    if let Some(slot) = state.indexes.iter_mut().find(|i| i.id == index.id) {
+        *slot = index;
+    }
+    save_state(state)
+}
+
+/// 获取存储文件路径
+fn state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("subscription_indexes.json"))
+}
+
+/// 加载索引状态
+fn load_state() -> Result<SubscriptionIndexState> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(SubscriptionIndexState::default());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存索引状态
+fn save_state(state: &SubscriptionIndexState) -> Result<()> {
+    let path = state_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let json_data = serde_json::to_string_pretty(state)?;
+    fs::write(path, json_data)?;
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use super::{CmdError, CmdResult, ErrorCode, WithErrorCode, batch_import::validate_urls};
+use crate::{
+    config::{Config, PrfItem, profiles::profiles_patch_item_safe},
+    feat, logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+use url::Url;
+
+/// 每个订阅最多保留的历史地址数量
+const MAX_HISTORY_PER_PROFILE: usize = 20;
+
+/// 被替换下来的订阅地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionUrlHistoryEntry {
+    pub url: String,
+    pub replaced_at: i64,
+}
+
+/// 单个订阅的地址迁移结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlMigrationResult {
+    pub uid: String,
+    pub name: Option<String>,
+    pub old_url: String,
+    pub new_url: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 订阅 uid -> 历史地址（新的在前）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct UrlHistoryStore {
+    history: HashMap<String, Vec<SubscriptionUrlHistoryEntry>>,
+}
+
+/// 替换订阅地址（如服务商轮换 token）
+/// 订阅 uid 不变，选项、流量统计和选择记忆随之保留；新地址拉取失败时回滚
+#[tauri::command]
+pub async fn replace_subscription_url(
+    uid: String,
+    new_url: String,
+    keep_history: Option<bool>,
+) -> CmdResult<UrlMigrationResult> {
+    let item = {
+        let profiles = Config::profiles().await;
+        let profiles_ref = profiles.latest_ref();
+        profiles_ref
+            .get_item(&uid)
+            .with_code(ErrorCode::ProfileNotFound)?
+            .clone()
+    };
+    let old_url = match (&item.itype, &item.url) {
+        (Some(itype), Some(url)) if itype == "remote" => url.clone(),
+        _ => {
+            return Err(CmdError::new(
+                ErrorCode::ProfileNotRemote,
+                "只有远程订阅可以替换地址",
+            ));
+        }
+    };
+
+    let result = migrate_url(&item, &uid, old_url, new_url.trim().to_string()).await;
+    if result.success && keep_history.unwrap_or(true) {
+        archive_url(&uid, result.old_url.clone()).context("保存历史地址失败")?;
+    }
+    Ok(result)
+}
+
+/// 按正则批量改写同一服务商域名下所有订阅的地址
+/// dry_run 为 true 时只返回改写预览，不做任何修改
+#[tauri::command]
+pub async fn bulk_replace_subscription_urls(
+    domain: String,
+    pattern: String,
+    replacement: String,
+    keep_history: Option<bool>,
+    dry_run: Option<bool>,
+) -> CmdResult<Vec<UrlMigrationResult>> {
+    let regex = Regex::new(&pattern)
+        .map_err(|e| CmdError::new(ErrorCode::InvalidArgument, format!("正则表达式无效: {}", e)))?;
+    let domain = domain.trim().trim_start_matches('.').to_lowercase();
+    let items: Vec<PrfItem> = {
+        let profiles = Config::profiles().await;
+        let profiles_ref = profiles.latest_ref();
+        profiles_ref
+            .get_items()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter(|item| item.itype.as_deref() == Some("remote"))
+                    .filter(|item| {
+                        item.url
+                            .as_deref()
+                            .is_some_and(|u| matches_domain(u, &domain))
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let dry_run = dry_run.unwrap_or(false);
+    let mut results = Vec::new();
+    for item in items {
+        let (Some(uid), Some(old_url)) = (item.uid.clone(), item.url.clone()) else {
+            continue;
+        };
+        let new_url = regex.replace(&old_url, replacement.as_str()).into_owned();
+        if new_url == old_url {
+            continue;
+        }
+
+        if dry_run {
+            let (valid, invalid) = validate_urls(vec![new_url.clone()]);
+            results.push(UrlMigrationResult {
+                uid,
+                name: item.name.clone(),
+                old_url,
+                new_url,
+                success: !valid.is_empty(),
+                error: invalid.into_iter().find_map(|r| r.error_message),
+            });
+            continue;
+        }
+
+        let result = migrate_url(&item, &uid, old_url, new_url).await;
+        if result.success
+            && keep_history.unwrap_or(true)
+            && let Err(e) = archive_url(&uid, result.old_url.clone())
+        {
+            logging!(warn, Type::Cmd, true, "[地址迁移] 保存历史地址失败: {}", e);
+        }
+        results.push(result);
+    }
+
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[地址迁移] 域名 {} 批量改写 {} 个订阅 (dry_run={})",
+        domain,
+        results.len(),
+        dry_run
+    );
+    Ok(results)
+}
+
+/// 获取订阅的历史地址
+#[tauri::command]
+pub async fn get_subscription_url_history(
+    uid: String,
+) -> CmdResult<Vec<SubscriptionUrlHistoryEntry>> {
+    let store = load_store().context("加载历史地址失败")?;
+    Ok(store.history.get(&uid).cloned().unwrap_or_default())
+}
+
+// ===== 内部实现函数 =====
+
+/// 写入新地址并立即更新订阅，失败时恢复原地址
+async fn migrate_url(
+    item: &PrfItem,
+    uid: &str,
+    old_url: String,
+    new_url: String,
+) -> UrlMigrationResult {
+    let mut result = UrlMigrationResult {
+        uid: uid.to_string(),
+        name: item.name.clone(),
+        old_url,
+        new_url,
+        success: false,
+        error: None,
+    };
+
+    let (valid, invalid) = validate_urls(vec![result.new_url.clone()]);
+    if valid.is_empty() {
+        result.error = invalid.into_iter().find_map(|r| r.error_message);
+        return result;
+    }
+    if result.new_url == result.old_url {
+        result.error = Some("新地址与当前地址相同".to_string());
+        return result;
+    }
+
+    if let Err(e) = patch_url(uid, &result.new_url).await {
+        result.error = Some(e.to_string());
+        return result;
+    }
+
+    match feat::update_profile(uid.to_string(), None, Some(true)).await {
+        Ok(()) => {
+            logging!(info, Type::Cmd, true, "[地址迁移] 订阅 {} 地址已替换", uid);
+            result.success = true;
+        }
+        Err(e) => {
+            logging!(
+                warn,
+                Type::Cmd,
+                true,
+                "[地址迁移] 订阅 {} 新地址拉取失败，恢复原地址: {}",
+                uid,
+                e
+            );
+            result.error = Some(e.to_string());
+            if let Err(e) = patch_url(uid, &result.old_url).await {
+                logging!(error, Type::Cmd, true, "[地址迁移] 恢复原地址失败: {}", e);
+            }
+        }
+    }
+    result
+}
+
+async fn patch_url(uid: &str, url: &str) -> Result<()> {
+    profiles_patch_item_safe(
+        uid.to_string(),
+        PrfItem {
+            url: Some(url.to_string()),
+            ..PrfItem::default()
+        },
+    )
+    .await
+}
+
+/// 判断订阅地址是否属于指定域名（含子域名）
+fn matches_domain(url: &str, domain: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase))
+        .is_some_and(|host| host == domain || host.ends_with(&format!(".{domain}")))
+}
+
+/// 归档旧地址
+fn archive_url(uid: &str, url: String) -> Result<()> {
+    let mut store = load_store()?;
+    let history = store.history.entry(uid.to_string()).or_default();
+    history.retain(|entry| entry.url != url);
+    history.insert(
+        0,
+        SubscriptionUrlHistoryEntry {
+            url,
+            replaced_at: chrono::Utc::now().timestamp(),
+        },
+    );
+    history.truncate(MAX_HISTORY_PER_PROFILE);
+    save_store(&store)
+}
+
+/// 获取存储文件路径
+fn store_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("subscription_url_history.json"))
+}
+
+/// 加载历史地址存储
+fn load_store() -> Result<UrlHistoryStore> {
+    let path = store_path()?;
+    if !path.exists() {
+        return Ok(UrlHistoryStore::default());
+    }
+
+    let json_data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存历史地址存储
+fn save_store(store: &UrlHistoryStore) -> Result<()> {
+    let path = store_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let json_data = serde_json::to_string_pretty(store)?;
+    fs::write(path, json_data)?;
+    Ok(())
+}
@@ -10,7 +10,7 @@
     clippy::needless_pass_by_value
 )]
 // TODO: 后续优化订阅测试模块，移除 lint 豁免。
-use super::CmdResult;
+use super::{CmdResult, OperationKind, register_operation};
 use crate::{
     config::{Config, PrfItem},
     logging,
@@ -58,6 +58,8 @@ pub struct NodeTestResult {
     pub upload_speed_mbps: Option<f64>,
     pub packet_loss_rate: Option<f64>,
     pub stability_score: Option<u8>, // 0-100
+    #[serde(default)]
+    pub jitter_ms: Option<f64>,
     pub error_message: Option<String>,
     pub test_duration_ms: u64,
     pub test_time: i64,
@@ -78,6 +80,8 @@ pub struct SubscriptionTestResult {
     pub avg_download_speed_mbps: Option<f64>,
     pub avg_upload_speed_mbps: Option<f64>,
     pub overall_stability_score: Option<u8>,
+    #[serde(default)]
+    pub avg_jitter_ms: Option<f64>,
     pub quality_grade: QualityGrade,
     pub node_results: Vec<NodeTestResult>,
     pub recommendations: Vec<String>,
@@ -122,6 +126,52 @@ pub struct TestSummary {
     pub quality_distribution: HashMap<QualityGrade, usize>,
 }
 
+/// 丢包测试方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum PingMethod {
+    #[default]
+    Tcp, // TCP 握手，适用于屏蔽 ICMP 的服务器
+    Icmp, // 系统 ping 命令，不可用时回退到 TCP
+}
+
+/// 丢包和抖动测量结果
+#[derive(Debug, Clone)]
+struct PacketLossStats {
+    samples: u32,
+    rtts_ms: Vec<f64>,
+}
+
+impl PacketLossStats {
+    fn loss_rate(&self) -> f64 {
+        if self.samples == 0 {
+            return 100.0;
+        }
+        let lost = self.samples.saturating_sub(self.rtts_ms.len() as u32);
+        lost as f64 / self.samples as f64 * 100.0
+    }
+
+    /// 相邻两次往返时间差的平均值
+    fn jitter_ms(&self) -> Option<f64> {
+        if self.rtts_ms.len() < 2 {
+            return None;
+        }
+        let total: f64 = self
+            .rtts_ms
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .sum();
+        Some(total / (self.rtts_ms.len() - 1) as f64)
+    }
+
+    /// 稳定性得分：以送达率为基础，抖动越大扣分越多
+    fn stability_score(&self) -> u8 {
+        let penalty = self
+            .jitter_ms()
+            .map_or(0.0, |jitter| (jitter / 5.0).min(40.0));
+        (100.0 - self.loss_rate() - penalty).clamp(0.0, 100.0) as u8
+    }
+}
+
 /// 测试配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestConfig {
@@ -135,6 +185,14 @@ pub struct TestConfig {
     pub test_urls: Vec<String>,
     pub skip_speed_test: bool,
     pub skip_stability_test: bool,
+    #[serde(default = "default_packet_loss_sample_count")]
+    pub packet_loss_sample_count: u32,
+    #[serde(default)]
+    pub ping_method: PingMethod,
+}
+
+fn default_packet_loss_sample_count() -> u32 {
+    20
 }
 
 impl Default for TestConfig {
@@ -154,6 +212,8 @@ impl Default for TestConfig {
             ],
             skip_speed_test: false,
             skip_stability_test: false,
+            packet_loss_sample_count: default_packet_loss_sample_count(),
+            ping_method: PingMethod::default(),
         }
     }
 }
@@ -196,7 +256,7 @@ pub async fn test_subscription(
     let nodes = parse_subscription_nodes(&subscription).await?;
 
     if nodes.is_empty() {
-        return Err("No nodes found in subscription".to_string());
+        return Err("No nodes found in subscription".into());
     }
 
     logging!(
@@ -239,9 +299,11 @@ pub async fn test_subscription(
 pub async fn test_all_subscriptions(
     test_type: TestType,
     config: Option<TestConfig>,
+    task_id: Option<String>,
 ) -> CmdResult<BatchTestResult> {
     let start_time = Instant::now();
     let test_id = uuid::Uuid::new_v4().to_string();
+    let operation = register_operation(OperationKind::SubscriptionTest, task_id);
 
     logging!(
         info,
@@ -269,7 +331,7 @@ pub async fn test_all_subscriptions(
     };
 
     if subscriptions.is_empty() {
-        return Err("No subscriptions found".to_string());
+        return Err("No subscriptions found".into());
     }
 
     logging!(
@@ -294,10 +356,15 @@ pub async fn test_all_subscriptions(
         let test_type_clone = test_type.clone();
         let test_config_clone = test_config.clone();
         let permit = semaphore.clone();
+        let token = operation.clone();
 
         let task = tokio::spawn(async move {
             let _permit = permit.acquire().await.unwrap();
-            test_subscription(uid, test_type_clone, Some(test_config_clone)).await
+            // 取消后不再启动排队中的测试，已开始的测试正常完成
+            if token.is_cancelled() {
+                return None;
+            }
+            Some(test_subscription(uid, test_type_clone, Some(test_config_clone)).await)
         });
 
         tasks.push(task);
@@ -305,7 +372,7 @@ pub async fn test_all_subscriptions(
 
     // 等待所有测试完成
     for task in tasks {
-        if let Ok(result) = task.await {
+        if let Ok(Some(result)) = task.await {
             if let Ok(test_result) = result {
                 results.push(test_result);
             }
@@ -339,6 +406,9 @@ pub async fn test_all_subscriptions(
         test_duration
     );
 
+    if operation.is_cancelled() {
+        operation.emit_cancelled(Some(&batch_result));
+    }
     Ok(batch_result)
 }
 
@@ -434,10 +504,43 @@ pub async fn schedule_periodic_test(
         interval_hours
     );
 
-    // TODO: 集成到任务管理系统
-    let task_id = uuid::Uuid::new_v4().to_string();
+    use super::task_manager::{
+        SpeedTestMode, SpeedTestTaskOptions, TaskConfig, TaskOptions, TaskStatus, TaskType,
+        create_task,
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let task = TaskConfig {
+        id: String::new(), // 将被重新生成
+        name: "定期测速".to_string(),
+        description: format!(
+            "每{}小时测试 {} 个订阅",
+            interval_hours,
+            subscription_uids.len()
+        ),
+        task_type: TaskType::SpeedTest,
+        status: TaskStatus::Active,
+        interval_minutes: interval_hours.max(1) * 60,
+        enabled: true,
+        target_profiles: subscription_uids,
+        options: TaskOptions {
+            notification_enabled: false,
+            speed_test: Some(SpeedTestTaskOptions {
+                mode: SpeedTestMode::Full,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        created_at: now,
+        updated_at: now,
+        last_run: None,
+        next_run: None,
+        depends_on: vec![],
+        conditions: vec![],
+        trigger_events: vec![],
+    };
 
-    Ok(task_id)
+    create_task(task).await
 }
 
 // ===== 内部实现函数 =====
@@ -453,7 +556,7 @@ async fn parse_subscription_nodes(subscription: &PrfItem) -> CmdResult<Vec<NodeI
                 nodes = parse_clash_config(&content)?;
             }
             Err(e) => {
-                return Err(format!("Failed to read subscription file: {}", e));
+                return Err(format!("Failed to read subscription file: {}", e).into());
             }
         }
     }
@@ -577,6 +680,7 @@ async fn test_single_node(
         upload_speed_mbps: None,
         packet_loss_rate: None,
         stability_score: None,
+        jitter_ms: None,
         error_message: None,
         test_duration_ms: 0,
         test_time,
@@ -611,10 +715,11 @@ async fn test_single_node(
                 TestType::Stability => {
                     // 执行稳定性测试
                     if !config.skip_stability_test
-                        && let Ok((stability, loss_rate)) = test_node_stability(&node, config).await
+                        && let Ok(stats) = test_node_stability(&node, config).await
                     {
-                        result.stability_score = Some(stability);
-                        result.packet_loss_rate = Some(loss_rate);
+                        result.stability_score = Some(stats.stability_score());
+                        result.packet_loss_rate = Some(stats.loss_rate());
+                        result.jitter_ms = stats.jitter_ms();
                     }
                 }
                 TestType::Comprehensive => {
@@ -631,10 +736,11 @@ async fn test_single_node(
                     }
 
                     if !config.skip_stability_test
-                        && let Ok((stability, loss_rate)) = test_node_stability(&node, config).await
+                        && let Ok(stats) = test_node_stability(&node, config).await
                     {
-                        result.stability_score = Some(stability);
-                        result.packet_loss_rate = Some(loss_rate);
+                        result.stability_score = Some(stats.stability_score());
+                        result.packet_loss_rate = Some(stats.loss_rate());
+                        result.jitter_ms = stats.jitter_ms();
                     }
                 }
             }
@@ -759,81 +865,127 @@ async fn test_download_speed(client: &reqwest::Client, _node: &NodeInfo) -> Resu
     }
 }
 
-/// 测试节点稳定性
-async fn test_node_stability(node: &NodeInfo, config: &TestConfig) -> Result<(u8, f64), String> {
-    // 执行多次连接测试来评估稳定性
-    let test_count = std::cmp::min(config.latency_test_count, 10); // 限制最大测试次数
-    let mut successful_connections = 0;
-    let mut failed_connections = 0;
-
-    for i in 0..test_count {
-        let connection_result = timeout(
-            Duration::from_secs(config.connection_timeout_seconds as u64),
-            test_tcp_connection(node),
-        )
-        .await;
-
-        match connection_result {
-            Ok(Ok(_)) => {
-                successful_connections += 1;
-                logging!(
-                    debug,
-                    Type::Cmd,
-                    true,
-                    "[稳定性测试] 连接 {}/{} 成功",
-                    i + 1,
-                    test_count
-                );
-            }
-            Ok(Err(e)) => {
-                failed_connections += 1;
+/// 测试节点稳定性：按配置的采样次数测量丢包率和抖动
+async fn test_node_stability(
+    node: &NodeInfo,
+    config: &TestConfig,
+) -> Result<PacketLossStats, String> {
+    let samples = config.packet_loss_sample_count.clamp(2, 100);
+    let timeout_ms = config.connection_timeout_seconds.max(1) as u64 * 1000;
+
+    let stats = match config.ping_method {
+        PingMethod::Icmp => match icmp_ping(&node.server, samples, timeout_ms).await {
+            Ok(stats) => stats,
+            Err(e) => {
                 logging!(
                     debug,
                     Type::Cmd,
                     true,
-                    "[稳定性测试] 连接 {}/{} 失败: {}",
-                    i + 1,
-                    test_count,
+                    "[稳定性测试] ICMP 测试不可用，回退到 TCP: {}",
                     e
                 );
+                tcp_ping(node, samples, timeout_ms).await?
             }
-            Err(_) => {
-                failed_connections += 1;
-                logging!(
-                    debug,
-                    Type::Cmd,
-                    true,
-                    "[稳定性测试] 连接 {}/{} 超时",
-                    i + 1,
-                    test_count
-                );
-            }
+        },
+        PingMethod::Tcp => tcp_ping(node, samples, timeout_ms).await?,
+    };
+
+    logging!(
+        debug,
+        Type::Cmd,
+        true,
+        "[稳定性测试] {} 丢包率 {:.1}%，抖动 {:?}ms",
+        node.name,
+        stats.loss_rate(),
+        stats.jitter_ms()
+    );
+    Ok(stats)
+}
+
+/// 通过多次 TCP 握手测量往返时间
+async fn tcp_ping(
+    node: &NodeInfo,
+    samples: u32,
+    timeout_ms: u64,
+) -> Result<PacketLossStats, String> {
+    // 只解析一次地址，避免 DNS 查询计入往返时间
+    let addr: SocketAddr = tokio::net::lookup_host(format!("{}:{}", node.server, node.port))
+        .await
+        .map_err(|e| format!("解析地址失败: {}", e))?
+        .next()
+        .ok_or_else(|| format!("无法解析地址: {}", node.server))?;
+
+    let mut rtts_ms = Vec::new();
+    for i in 0..samples {
+        let start = Instant::now();
+        if let Ok(Ok(_stream)) =
+            timeout(Duration::from_millis(timeout_ms), TcpStream::connect(addr)).await
+        {
+            rtts_ms.push(start.elapsed().as_secs_f64() * 1000.0);
         }
 
         // 测试间隔
-        if i < test_count - 1 {
-            tokio::time::sleep(Duration::from_millis(500)).await;
+        if i + 1 < samples {
+            tokio::time::sleep(Duration::from_millis(200)).await;
         }
     }
 
-    // 计算稳定性得分
-    let success_rate = successful_connections as f64 / test_count as f64;
-    let stability_score = (success_rate * 100.0) as u8;
-    let packet_loss_rate = (failed_connections as f64 / test_count as f64) * 100.0;
-
-    Ok((stability_score, packet_loss_rate))
+    Ok(PacketLossStats { samples, rtts_ms })
 }
 
-/// 测试TCP连接
-async fn test_tcp_connection(node: &NodeInfo) -> Result<(), String> {
-    let addr = format!("{}:{}", node.server, node.port);
-    let socket_addr: SocketAddr = addr.parse().map_err(|e| format!("无效的地址格式: {}", e))?;
-
-    let _stream = TcpStream::connect(socket_addr)
-        .await
-        .map_err(|e| format!("TCP连接失败: {}", e))?;
+/// 调用系统 ping 命令并解析每次回复的往返时间
+async fn icmp_ping(host: &str, samples: u32, timeout_ms: u64) -> Result<PacketLossStats, String> {
+    let mut command = tokio::process::Command::new("ping");
+    #[cfg(target_os = "windows")]
+    {
+        command
+            .args(["-n", &samples.to_string(), "-w", &timeout_ms.to_string()])
+            .creation_flags(0x08000000); // CREATE_NO_WINDOW - 隐藏窗口
+    }
+    #[cfg(target_os = "macos")]
+    command.args([
+        "-c",
+        &samples.to_string(),
+        "-i",
+        "0.2",
+        "-W",
+        &timeout_ms.to_string(),
+    ]);
+    #[cfg(target_os = "linux")]
+    command.args([
+        "-c",
+        &samples.to_string(),
+        "-i",
+        "0.2",
+        "-W",
+        &timeout_ms.div_ceil(1000).to_string(),
+    ]);
+
+    let output = timeout(
+        Duration::from_millis(timeout_ms * samples as u64 + 5000),
+        command.arg(host).output(),
+    )
+    .await
+    .map_err(|_| "ping 命令超时".to_string())?
+    .map_err(|e| format!("执行 ping 失败: {}", e))?;
+
+    // 兼容 "time=12.3 ms"、"时间=12ms" 和 "time<1ms" 等格式
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pattern = regex::Regex::new(r"[=<]\s*(\d+(?:\.\d+)?)\s*ms").map_err(|e| e.to_string())?;
+    let rtts_ms: Vec<f64> = stdout
+        .lines()
+        .filter(|line| line.contains("ttl=") || line.contains("TTL="))
+        .filter_map(|line| pattern.captures(line))
+        .filter_map(|caps| caps[1].parse().ok())
+        .collect();
+    if rtts_ms.is_empty() && !output.status.success() {
+        return Err(format!(
+            "ping 失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
 
-    Ok(())
+    Ok(PacketLossStats { samples, rtts_ms })
 }
 
 /// 分析测试结果
@@ -895,12 +1047,23 @@ fn analyze_test_results(
     };
 
     let overall_stability_score = if passed_nodes > 0 {
-        let scores: Vec<u8> = node_results
+        let scores: Vec<u32> = node_results
             .iter()
-            .filter_map(|r| r.stability_score)
+            .filter_map(|r| r.stability_score.map(u32::from))
             .collect();
         if !scores.is_empty() {
-            Some(scores.iter().sum::<u8>() / scores.len() as u8)
+            Some((scores.iter().sum::<u32>() / scores.len() as u32) as u8)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let avg_jitter_ms = if passed_nodes > 0 {
+        let jitters: Vec<f64> = node_results.iter().filter_map(|r| r.jitter_ms).collect();
+        if !jitters.is_empty() {
+            Some(jitters.iter().sum::<f64>() / jitters.len() as f64)
         } else {
             None
         }
@@ -914,6 +1077,7 @@ fn analyze_test_results(
         total_nodes,
         avg_latency_ms,
         avg_download_speed_mbps,
+        avg_jitter_ms,
     );
 
     // 生成建议
@@ -941,6 +1105,7 @@ fn analyze_test_results(
         avg_download_speed_mbps,
         avg_upload_speed_mbps,
         overall_stability_score,
+        avg_jitter_ms,
         quality_grade,
         node_results,
         recommendations,
@@ -955,34 +1120,48 @@ fn calculate_quality_grade(
     total_nodes: usize,
     avg_latency_ms: Option<f64>,
     avg_download_speed_mbps: Option<f64>,
+    avg_jitter_ms: Option<f64>,
 ) -> QualityGrade {
     let pass_rate = passed_nodes as f64 / total_nodes as f64;
     let mut score = pass_rate * 40.0; // 可用性权重40%
 
-    // 延迟评分 (权重30%)
+    let mut measured = 0.0;
+
+    // 延迟评分 (权重25%)
     if let Some(latency) = avg_latency_ms {
         let latency_score = match latency {
-            l if l < 50.0 => 30.0,
-            l if l < 100.0 => 25.0,
-            l if l < 200.0 => 20.0,
-            l if l < 500.0 => 15.0,
-            _ => 5.0,
+            l if l < 50.0 => 25.0,
+            l if l < 100.0 => 21.0,
+            l if l < 200.0 => 17.0,
+            l if l < 500.0 => 12.0,
+            _ => 4.0,
         };
-        score += latency_score;
+        measured += latency_score;
     }
 
-    // 速度评分 (权重30%)
+    // 速度评分 (权重25%)
     if let Some(speed) = avg_download_speed_mbps {
         let speed_score = match speed {
-            s if s > 100.0 => 30.0,
-            s if s > 50.0 => 25.0,
-            s if s > 20.0 => 20.0,
-            s if s > 10.0 => 15.0,
-            _ => 5.0,
+            s if s > 100.0 => 25.0,
+            s if s > 50.0 => 21.0,
+            s if s > 20.0 => 17.0,
+            s if s > 10.0 => 12.0,
+            _ => 4.0,
         };
-        score += speed_score;
+        measured += speed_score;
     }
 
+    // 抖动评分 (权重10%)，未测量抖动时按延迟和速度评分等比折算
+    let jitter_score = match avg_jitter_ms {
+        Some(j) if j < 5.0 => 10.0,
+        Some(j) if j < 20.0 => 8.0,
+        Some(j) if j < 50.0 => 5.0,
+        Some(j) if j < 100.0 => 3.0,
+        Some(_) => 1.0,
+        None => measured * 0.2,
+    };
+    score += measured + jitter_score;
+
     match score as u8 {
         90..=100 => QualityGrade::Excellent,
         70..=89 => QualityGrade::Good,
@@ -0,0 +1,117 @@
+use super::{
+    CmdResult,
+    event_publisher::{self, AutomationEvent},
+    traffic_stats::{self, QuotaSnapshot},
+};
+use crate::{
+    config::{Config, PrfExtra},
+    core::handle,
+    logging,
+    utils::logging::Type,
+};
+use chrono::Local;
+use serde::Serialize;
+use serde_json::json;
+
+/// 订阅流量与到期信息
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionUsage {
+    pub uid: String,
+    pub name: String,
+    pub upload: u64,
+    pub download: u64,
+    pub used: u64,
+    pub total: u64,                   // 0 表示不限量
+    pub remaining: Option<u64>,       // 不限量时为空
+    pub used_percentage: Option<f64>, // 不限量时为空
+    pub expire: Option<i64>,          // 未提供到期时间时为空
+    pub expire_in_days: Option<i64>,
+    pub is_expired: bool,
+    pub updated_at: Option<i64>,
+    pub history: Vec<QuotaSnapshot>,
+}
+
+/// 获取单个订阅的流量与到期信息
+#[tauri::command]
+pub async fn get_subscription_usage(uid: String) -> CmdResult<Option<SubscriptionUsage>> {
+    Ok(get_all_subscription_usage()
+        .await?
+        .into_iter()
+        .find(|usage| usage.uid == uid))
+}
+
+/// 获取所有订阅的流量与到期信息
+/// 额度取自订阅保存的 subscription-userinfo，拉取历史取自流量统计
+#[tauri::command]
+pub async fn get_all_subscription_usage() -> CmdResult<Vec<SubscriptionUsage>> {
+    let items = Config::profiles()
+        .await
+        .latest_ref()
+        .items
+        .clone()
+        .unwrap_or_default();
+
+    let now = Local::now().timestamp();
+    let mut usages = Vec::new();
+    for item in items {
+        let (Some(uid), Some(extra)) = (item.uid, item.extra) else {
+            continue;
+        };
+        let tracking = traffic_stats::quota_tracking(&uid).await;
+        let name = item.name.unwrap_or_else(|| uid.clone());
+        usages.push(build_usage(uid, name, extra, tracking, now));
+    }
+    Ok(usages)
+}
+
+/// 拉取远程订阅后把 subscription-userinfo 记录到流量统计，并在跨过阈值时提醒
+pub async fn record_subscription_usage(uid: &str, name: &str, extra: Option<PrfExtra>) {
+    let Some(extra) = extra else {
+        return;
+    };
+    let Some(alert) = traffic_stats::record_subscription_quota(uid, name, extra).await else {
+        return;
+    };
+
+    logging!(warn, Type::Config, true, "[订阅流量] {}", alert.message);
+    if alert.current_value >= 1.0 {
+        event_publisher::publish_event(
+            AutomationEvent::QuotaExceeded,
+            json!({ "uid": uid, "name": name, "used_ratio": alert.current_value }),
+        );
+    }
+    handle::Handle::notice_message("subscription_usage::alert", alert.message);
+}
+
+// ===== 内部实现函数 =====
+
+fn build_usage(
+    uid: String,
+    name: String,
+    extra: PrfExtra,
+    tracking: Option<traffic_stats::QuotaTracking>,
+    now: i64,
+) -> SubscriptionUsage {
+    let used = extra.upload + extra.download;
+    let unlimited = extra.total == 0;
+    let expire = (extra.expire > 0).then_some(extra.expire as i64);
+    let (updated_at, history) = match tracking {
+        Some(tracking) => (Some(tracking.updated_at), tracking.history),
+        None => (None, Vec::new()),
+    };
+    SubscriptionUsage {
+        uid,
+        name,
+        upload: extra.upload,
+        download: extra.download,
+        used,
+        total: extra.total,
+        remaining: (!unlimited).then(|| extra.total.saturating_sub(used)),
+        used_percentage: (!unlimited).then(|| used as f64 / extra.total as f64 * 100.0),
+        expire,
+        expire_in_days: expire.map(|expire| (expire - now).div_euclid(86400)),
+        is_expired: expire.is_some_and(|expire| expire <= now),
+        updated_at,
+        history,
+    }
+}
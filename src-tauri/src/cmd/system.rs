@@ -1,9 +1,16 @@
-use super::CmdResult;
+use super::{CmdError, CmdResult, ErrorCode};
 use crate::{
-    core::{CoreManager, handle},
+    core::{
+        CoreManager, CoreStartupReport, captive_portal, handle,
+        port_conflict::PortConflict,
+        system_events::{SystemEvent, SystemEventMonitor},
+    },
     logging,
     module::sysinfo::PlatformSpecification,
-    utils::logging::Type,
+    utils::{
+        logging::Type,
+        redact::{self, RedactionPolicy},
+    },
 };
 use once_cell::sync::Lazy;
 use std::{
@@ -45,12 +52,73 @@ pub async fn get_system_info() -> CmdResult<String> {
     Ok(info)
 }
 
+/// 获取日志与导出的脱敏策略
+#[tauri::command]
+pub fn get_redaction_policy() -> CmdResult<RedactionPolicy> {
+    Ok(redact::policy())
+}
+
+/// 设置日志与导出的脱敏策略
+#[tauri::command]
+pub fn set_redaction_policy(policy: RedactionPolicy) -> CmdResult<RedactionPolicy> {
+    redact::set_policy(policy.clone()).map_err(|e| format!("保存脱敏策略失败: {}", e))?;
+    logging!(
+        info,
+        Type::System,
+        true,
+        "[脱敏] 已更新脱敏策略: 日志={}, 导出={}",
+        policy.enabled,
+        policy.redact_exports
+    );
+    Ok(policy)
+}
+
+/// 获取最近的系统事件
+#[tauri::command]
+pub fn get_recent_system_events() -> CmdResult<Vec<SystemEvent>> {
+    Ok(SystemEventMonitor::global().recent_events())
+}
+
+/// 设置检测到强制门户时的处理策略：off | notify | disable_sysproxy | direct
+#[tauri::command]
+pub async fn set_captive_portal_policy(policy: String) -> CmdResult {
+    if !captive_portal::POLICIES.contains(&policy.as_str()) {
+        return Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            format!("未知的强制门户策略: {policy}"),
+        ));
+    }
+    captive_portal::set_policy(&policy)
+        .await
+        .map_err(|e| format!("保存强制门户策略失败: {e}"))?;
+    logging!(
+        info,
+        Type::System,
+        true,
+        "[强制门户] 已更新处理策略: {}",
+        policy
+    );
+    Ok(())
+}
+
 /// 获取当前内核运行模式
 #[tauri::command]
-pub async fn get_running_mode() -> Result<String, String> {
+pub async fn get_running_mode() -> CmdResult<String> {
     Ok(CoreManager::global().get_running_mode().to_string())
 }
 
+/// 获取最近一次内核启动报告，包含每种启动策略及就绪探测的结果
+#[tauri::command]
+pub async fn get_core_startup_report() -> CmdResult<CoreStartupReport> {
+    Ok(CoreManager::global().startup_report())
+}
+
+/// 获取最近一次内核启动前扫描到的端口冲突及占用进程
+#[tauri::command]
+pub async fn get_port_conflicts() -> CmdResult<Vec<PortConflict>> {
+    Ok(CoreManager::global().port_conflicts())
+}
+
 /// 获取应用的运行时间（毫秒）
 #[tauri::command]
 pub fn get_app_uptime() -> CmdResult<i64> {
@@ -8,17 +8,58 @@
     clippy::manual_strip
 )]
 // TODO: 下一阶段逐条处理任务管理模块的 lint 警告。
-use super::CmdResult;
-use crate::{logging, utils::logging::Type};
+use super::{
+    CmdResult,
+    job_manager::{JobClass, JobKind, JobPriority, run_job, submit_job},
+};
+use crate::{
+    config::{Config, PrfItem},
+    core::{
+        handle,
+        system_events::{SystemEventKind, SystemEventMonitor},
+    },
+    logging,
+    process::AsyncHandler,
+    utils::dirs,
+    utils::logging::Type,
+};
+use anyhow::Result;
+use futures::{FutureExt, future::BoxFuture};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 use uuid::Uuid;
 
+/// 调度器检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+
+/// 执行历史最多保留条数
+const MAX_HISTORY_RECORDS: usize = 1000;
+
+/// 调度器是否已启动
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 正在运行的任务ID
+static RUNNING_TASKS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
 /// 任务类型枚举
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TaskType {
     SubscriptionUpdate, // 订阅更新
     HealthCheck,        // 健康检查
     AutoCleanup,        // 自动清理
+    #[serde(alias = "speed_test")]
+    SpeedTest, // 全局测速
+    ExpiryReminder,     // 到期提醒
+    TrafficReport,      // 月度流量报告
+    GeoDataUpdate,      // 地理数据更新
     Custom,             // 自定义任务
 }
 
@@ -56,6 +97,20 @@ pub struct TaskConfig {
     pub updated_at: i64,
     pub last_run: Option<i64>,
     pub next_run: Option<i64>,
+    #[serde(default)]
+    pub depends_on: Vec<String>, // 依赖的任务ID，全部成功后才执行
+    #[serde(default)]
+    pub conditions: Vec<TaskCondition>, // 执行条件，全部满足才执行
+    #[serde(default)]
+    pub trigger_events: Vec<SystemEventKind>, // 触发执行的系统事件
+}
+
+/// 任务执行条件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TaskCondition {
+    WifiSsid(String),       // 仅在连接指定 Wi-Fi 时执行
+    TrafficUsageBelow(f64), // 仅在目标订阅流量使用率低于指定百分比时执行
+    AfterProfileUpdate,     // 仅在目标订阅自上次执行后有更新时执行
 }
 
 /// 任务选项
@@ -67,6 +122,8 @@ pub struct TaskOptions {
     pub auto_cleanup_days: Option<u32>,   // 自动清理天数
     pub health_check_url: Option<String>, // 健康检查URL
     pub notification_enabled: bool,       // 是否启用通知
+    #[serde(default)]
+    pub speed_test: Option<SpeedTestTaskOptions>, // 测速任务选项
 }
 
 impl Default for TaskOptions {
@@ -78,6 +135,35 @@ impl Default for TaskOptions {
             auto_cleanup_days: Some(30),
             health_check_url: None,
             notification_enabled: true,
+            speed_test: None,
+        }
+    }
+}
+
+/// 测速模式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SpeedTestMode {
+    #[default]
+    Light, // 轻量测速：限制节点数量、缩短超时
+    Full, // 完整测速
+}
+
+/// 测速任务选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedTestTaskOptions {
+    pub mode: SpeedTestMode,
+    pub auto_apply_best: bool,     // 测速完成后自动应用最佳节点
+    pub max_nodes: Option<usize>,  // 轻量模式下最多测试的节点数
+    pub node_timeout_seconds: u64, // 单节点超时
+}
+
+impl Default for SpeedTestTaskOptions {
+    fn default() -> Self {
+        Self {
+            mode: SpeedTestMode::Light,
+            auto_apply_best: false,
+            max_nodes: Some(50),
+            node_timeout_seconds: 3,
         }
     }
 }
@@ -202,6 +288,18 @@ pub async fn delete_task(task_id: String) -> CmdResult<()> {
     // 从配置中删除
     remove_task_from_config(&task_id).await?;
 
+    // 移除其它任务对该任务的依赖
+    let mut tasks = load_tasks_from_config().await?;
+    let mut changed = false;
+    for task in tasks.iter_mut() {
+        let before = task.depends_on.len();
+        task.depends_on.retain(|id| id != &task_id);
+        changed |= task.depends_on.len() != before;
+    }
+    if changed {
+        save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e))?;
+    }
+
     // 清理执行历史
     cleanup_task_execution_history(&task_id).await?;
 
@@ -254,12 +352,114 @@ pub async fn execute_task_immediately(task_id: String) -> CmdResult<TaskExecutio
         .find(|t| t.id == task_id)
         .ok_or_else(|| "Task not found".to_string())?;
 
-    let result = execute_task(task).await;
+    // 以高优先级排队，避免与正在运行的同类任务争用 Clash API
+    let submitted_at = chrono::Utc::now().timestamp();
+    let outcome = run_job(
+        task.name.clone(),
+        JobKind::ScheduledTask {
+            task_id: task.id.clone(),
+        },
+        JobClass::for_task(&task.task_type),
+        JobPriority::High,
+    )
+    .await;
+
+    match load_execution_history(&task_id, 1)
+        .await?
+        .into_iter()
+        .next()
+    {
+        Some(result) if result.start_time >= submitted_at => Ok(result),
+        _ => Err(outcome
+            .err()
+            .unwrap_or_else(|| "任务未执行".to_string())
+            .into()),
+    }
+}
 
-    // 保存执行结果
-    save_execution_result(&result).await?;
+/// 设置任务依赖
+#[tauri::command]
+pub async fn set_task_dependencies(task_id: String, depends_on: Vec<String>) -> CmdResult<()> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[任务管理] 设置任务依赖: {} -> {:?}",
+        task_id,
+        depends_on
+    );
 
-    Ok(result)
+    let mut tasks = load_tasks_from_config().await?;
+    for dep in &depends_on {
+        if dep == &task_id {
+            return Err("任务不能依赖自身".into());
+        }
+        if !tasks.iter().any(|t| &t.id == dep) {
+            return Err(format!("依赖的任务不存在: {}", dep).into());
+        }
+    }
+
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| "Task not found".to_string())?;
+    task.depends_on = depends_on;
+    task.updated_at = chrono::Utc::now().timestamp();
+
+    if has_dependency_cycle(&tasks, &task_id) {
+        return Err("任务依赖存在循环".into());
+    }
+
+    save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e).into())
+}
+
+/// 设置任务执行条件
+#[tauri::command]
+pub async fn set_task_conditions(task_id: String, conditions: Vec<TaskCondition>) -> CmdResult<()> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[任务管理] 设置任务条件: {} -> {:?}",
+        task_id,
+        conditions
+    );
+
+    let mut tasks = load_tasks_from_config().await?;
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| "Task not found".to_string())?;
+    task.conditions = conditions;
+    task.updated_at = chrono::Utc::now().timestamp();
+
+    save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e).into())
+}
+
+/// 设置触发任务的系统事件
+#[tauri::command]
+pub async fn set_task_trigger_events(
+    task_id: String,
+    trigger_events: Vec<SystemEventKind>,
+) -> CmdResult<()> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[任务管理] 设置任务触发事件: {} -> {:?}",
+        task_id,
+        trigger_events
+    );
+
+    let mut tasks = load_tasks_from_config().await?;
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| "Task not found".to_string())?;
+    task.trigger_events = trigger_events;
+    task.updated_at = chrono::Utc::now().timestamp();
+
+    save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e).into())
 }
 
 /// 获取任务执行历史
@@ -319,7 +519,7 @@ pub async fn get_task_system_overview() -> CmdResult<TaskSystemOverview> {
             .iter()
             .filter(|t| t.status == TaskStatus::Error)
             .count(),
-        running_tasks: 0, // TODO: 实现运行中任务计数
+        running_tasks: RUNNING_TASKS.lock().len(),
         next_execution: calculate_next_execution(&tasks),
         recent_executions,
     };
@@ -377,6 +577,9 @@ pub async fn create_default_tasks() -> CmdResult<Vec<String>> {
         updated_at: chrono::Utc::now().timestamp(),
         last_run: None,
         next_run: None,
+        depends_on: vec![],
+        conditions: vec![],
+        trigger_events: vec![],
     };
 
     save_task_to_config(&health_check_task).await?;
@@ -403,12 +606,32 @@ pub async fn create_default_tasks() -> CmdResult<Vec<String>> {
         updated_at: chrono::Utc::now().timestamp(),
         last_run: None,
         next_run: None,
+        depends_on: vec![],
+        conditions: vec![],
+        trigger_events: vec![],
     };
 
     save_task_to_config(&cleanup_task).await?;
     register_task_to_timer(&cleanup_task).await?;
     task_ids.push(cleanup_task.id.clone());
 
+    // 到期提醒、月度流量报告与地理数据更新任务，已存在同类任务时不重复创建
+    let existing = load_tasks_from_config().await?;
+    for task in [
+        expiry_reminder_task(),
+        traffic_report_task(),
+        geo_data_update_task(),
+    ] {
+        let exists = existing.iter().any(|t| {
+            std::mem::discriminant(&t.task_type) == std::mem::discriminant(&task.task_type)
+        });
+        if !exists {
+            save_task_to_config(&task).await?;
+            register_task_to_timer(&task).await?;
+            task_ids.push(task.id.clone());
+        }
+    }
+
     logging!(
         info,
         Type::Cmd,
@@ -421,37 +644,504 @@ pub async fn create_default_tasks() -> CmdResult<Vec<String>> {
 
 // ===== 内部实现函数 =====
 
+/// 每日到期提醒任务
+fn expiry_reminder_task() -> TaskConfig {
+    TaskConfig {
+        id: Uuid::new_v4().to_string(),
+        name: "订阅到期提醒".to_string(),
+        description: "每天检查订阅到期时间并发送提醒".to_string(),
+        task_type: TaskType::ExpiryReminder,
+        status: TaskStatus::Active,
+        interval_minutes: 24 * 60, // 每天执行一次
+        enabled: true,
+        target_profiles: vec![],
+        options: TaskOptions {
+            timeout_seconds: 60,
+            ..Default::default()
+        },
+        created_at: chrono::Utc::now().timestamp(),
+        updated_at: chrono::Utc::now().timestamp(),
+        last_run: None,
+        next_run: None,
+        depends_on: vec![],
+        conditions: vec![],
+        trigger_events: vec![],
+    }
+}
+
+/// 每天检查一次，上个月的报告尚未生成时生成，即每月 1 日自动生成
+fn traffic_report_task() -> TaskConfig {
+    TaskConfig {
+        id: Uuid::new_v4().to_string(),
+        name: "月度流量报告".to_string(),
+        description: "每月初自动生成上个月的流量报告".to_string(),
+        task_type: TaskType::TrafficReport,
+        status: TaskStatus::Active,
+        interval_minutes: 24 * 60, // 每天检查一次
+        enabled: true,
+        target_profiles: vec![],
+        options: TaskOptions {
+            timeout_seconds: 60,
+            ..Default::default()
+        },
+        created_at: chrono::Utc::now().timestamp(),
+        updated_at: chrono::Utc::now().timestamp(),
+        last_run: None,
+        next_run: None,
+        depends_on: vec![],
+        conditions: vec![],
+        trigger_events: vec![],
+    }
+}
+
+/// 每周按镜像源检查并更新地理数据集
+fn geo_data_update_task() -> TaskConfig {
+    TaskConfig {
+        id: Uuid::new_v4().to_string(),
+        name: "地理数据更新".to_string(),
+        description: "每周从镜像源检查并更新 GeoIP / GeoSite 数据".to_string(),
+        task_type: TaskType::GeoDataUpdate,
+        status: TaskStatus::Active,
+        interval_minutes: 7 * 24 * 60, // 每周执行一次
+        enabled: true,
+        target_profiles: vec![],
+        options: TaskOptions {
+            timeout_seconds: 600,
+            ..Default::default()
+        },
+        created_at: chrono::Utc::now().timestamp(),
+        updated_at: chrono::Utc::now().timestamp(),
+        last_run: None,
+        next_run: None,
+        depends_on: vec![],
+        conditions: vec![],
+        trigger_events: vec![],
+    }
+}
+
+/// 首次启用到期提醒时创建每日提醒任务，之后用户删除任务也不再重建
+async fn ensure_expiry_reminder_task() -> CmdResult<()> {
+    if crate::cmd::expiry_reminder::is_configured() {
+        return Ok(());
+    }
+    let exists = load_tasks_from_config()
+        .await?
+        .iter()
+        .any(|task| matches!(task.task_type, TaskType::ExpiryReminder));
+    if !exists {
+        let task = expiry_reminder_task();
+        save_task_to_config(&task).await?;
+        register_task_to_timer(&task).await?;
+    }
+    Ok(crate::cmd::expiry_reminder::init_config()?)
+}
+
+/// 启动任务调度器
+pub fn init_task_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    logging!(info, Type::Timer, true, "[任务管理] 启动任务调度器");
+    AsyncHandler::spawn(|| async {
+        if let Err(e) = ensure_expiry_reminder_task().await {
+            logging!(warn, Type::Timer, "[任务管理] 创建到期提醒任务失败: {}", e);
+        }
+
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+            if let Err(e) = run_due_tasks().await {
+                logging!(warn, Type::Timer, "[任务管理] 调度检查失败: {}", e);
+            }
+        }
+    });
+
+    AsyncHandler::spawn(|| async {
+        let mut receiver = SystemEventMonitor::global().subscribe();
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = run_event_tasks(event.kind).await {
+                        logging!(warn, Type::Timer, "[任务管理] 事件触发任务失败: {}", e);
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// 执行订阅了指定系统事件的任务
+async fn run_event_tasks(kind: SystemEventKind) -> CmdResult<()> {
+    let tasks: Vec<TaskConfig> = load_tasks_from_config()
+        .await?
+        .into_iter()
+        .filter(|t| t.enabled && t.status == TaskStatus::Active)
+        .filter(|t| t.trigger_events.contains(&kind))
+        .collect();
+
+    for task in tasks {
+        if let Err(reason) = check_task_runnable(&task).await {
+            logging!(
+                info,
+                Type::Timer,
+                "[任务管理] 事件 {:?} 跳过任务 {}: {}",
+                kind,
+                task.id,
+                reason
+            );
+            continue;
+        }
+
+        logging!(
+            info,
+            Type::Timer,
+            true,
+            "[任务管理] 事件 {:?} 触发任务 {}",
+            kind,
+            task.id
+        );
+        submit_task_job(&task, JobPriority::Normal);
+    }
+
+    Ok(())
+}
+
+/// 执行所有到期任务
+async fn run_due_tasks() -> CmdResult<()> {
+    let now = chrono::Utc::now().timestamp();
+    let due_tasks: Vec<TaskConfig> = load_tasks_from_config()
+        .await?
+        .into_iter()
+        .filter(|t| t.enabled && t.status == TaskStatus::Active)
+        .filter(|t| t.next_run.is_some_and(|next| next <= now))
+        .collect();
+
+    for task in due_tasks {
+        if let Err(reason) = check_task_runnable(&task).await {
+            logging!(
+                info,
+                Type::Timer,
+                "[任务管理] 跳过任务 {}: {}",
+                task.id,
+                reason
+            );
+            postpone_task(&task.id, now).await?;
+            continue;
+        }
+        submit_task_job(&task, JobPriority::Normal);
+    }
+
+    Ok(())
+}
+
+/// 提交到后台任务队列，与测速、备份等任务按并发类别统一调度
+fn submit_task_job(task: &TaskConfig, priority: JobPriority) -> String {
+    submit_job(
+        task.name.clone(),
+        JobKind::ScheduledTask {
+            task_id: task.id.clone(),
+        },
+        JobClass::for_task(&task.task_type),
+        priority,
+    )
+}
+
+/// 由后台任务队列调用，执行任务并把执行结果转换为任务结果
+pub(crate) async fn run_task_job(task_id: &str) -> Result<String, String> {
+    let tasks = load_tasks_from_config().await?;
+    let task = tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| format!("任务不存在: {}", task_id))?;
+
+    let result = run_task_and_record(task).await?;
+    match result.status {
+        ExecutionStatus::Success => Ok(result.message.unwrap_or_default()),
+        _ => Err(result
+            .error_details
+            .unwrap_or_else(|| "任务执行失败".to_string())),
+    }
+}
+
+/// 执行任务并记录结果、更新下次执行时间
+async fn run_task_and_record(task: &TaskConfig) -> CmdResult<TaskExecutionResult> {
+    if !RUNNING_TASKS.lock().insert(task.id.clone()) {
+        return Err(format!("任务正在运行: {}", task.id).into());
+    }
+    let _guard = scopeguard::guard(task.id.clone(), |id| {
+        RUNNING_TASKS.lock().remove(&id);
+    });
+
+    let result = execute_task(task).await;
+    save_execution_result(&result).await?;
+
+    let mut tasks = load_tasks_from_config().await?;
+    if let Some(stored) = tasks.iter_mut().find(|t| t.id == task.id) {
+        stored.last_run = Some(result.start_time);
+        stored.next_run = Some(compute_next_run(stored, result.start_time));
+        save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e))?;
+    }
+
+    if matches!(result.status, ExecutionStatus::Success) {
+        let task_id = task.id.clone();
+        AsyncHandler::spawn(move || trigger_dependent_tasks(task_id));
+    }
+
+    Ok(result)
+}
+
+/// 任务成功后触发依赖它的任务
+fn trigger_dependent_tasks(task_id: String) -> BoxFuture<'static, ()> {
+    async move {
+        let dependents: Vec<TaskConfig> = match load_tasks_from_config().await {
+            Ok(tasks) => tasks
+                .into_iter()
+                .filter(|t| t.enabled && t.status == TaskStatus::Active)
+                .filter(|t| t.depends_on.contains(&task_id))
+                .collect(),
+            Err(e) => {
+                logging!(warn, Type::Timer, "[任务管理] 加载依赖任务失败: {}", e);
+                return;
+            }
+        };
+
+        for task in dependents {
+            if let Err(reason) = check_task_runnable(&task).await {
+                logging!(
+                    debug,
+                    Type::Timer,
+                    "[任务管理] 依赖任务 {} 暂不执行: {}",
+                    task.id,
+                    reason
+                );
+                continue;
+            }
+            logging!(
+                info,
+                Type::Timer,
+                "[任务管理] 任务 {} 完成，触发依赖任务 {}",
+                task_id,
+                task.id
+            );
+            submit_task_job(&task, JobPriority::Normal);
+        }
+    }
+    .boxed()
+}
+
+/// 检查任务依赖和条件是否满足，不满足时返回原因
+async fn check_task_runnable(task: &TaskConfig) -> Result<(), String> {
+    if !task.depends_on.is_empty() {
+        let history = load_history_list().map_err(|e| format!("加载执行历史失败: {}", e))?;
+        for dep in &task.depends_on {
+            let latest = history.iter().find(|r| &r.task_id == dep);
+            match latest {
+                Some(r) if matches!(r.status, ExecutionStatus::Success) => {
+                    if task.last_run.is_some_and(|last| r.start_time < last) {
+                        return Err(format!("依赖任务 {} 自上次执行后未再成功", dep));
+                    }
+                }
+                _ => return Err(format!("依赖任务 {} 尚未成功执行", dep)),
+            }
+        }
+    }
+
+    for condition in &task.conditions {
+        evaluate_condition(task, condition).await?;
+    }
+
+    Ok(())
+}
+
+/// 评估单个执行条件
+async fn evaluate_condition(task: &TaskConfig, condition: &TaskCondition) -> Result<(), String> {
+    match condition {
+        TaskCondition::WifiSsid(expected) => {
+            match crate::utils::network::current_wifi_ssid().await {
+                Some(ssid) if &ssid == expected => Ok(()),
+                Some(ssid) => Err(format!("当前 Wi-Fi 为 {}，需要 {}", ssid, expected)),
+                None => Err(format!("未连接 Wi-Fi {}", expected)),
+            }
+        }
+        TaskCondition::TrafficUsageBelow(limit) => {
+            for item in target_profile_items(task).await {
+                let Some(extra) = item.extra else { continue };
+                if extra.total == 0 {
+                    continue;
+                }
+                let usage = (extra.upload + extra.download) as f64 / extra.total as f64 * 100.0;
+                if usage >= *limit {
+                    return Err(format!(
+                        "订阅 {} 流量使用率 {:.1}% 未低于 {:.1}%",
+                        item.uid.unwrap_or_default(),
+                        usage,
+                        limit
+                    ));
+                }
+            }
+            Ok(())
+        }
+        TaskCondition::AfterProfileUpdate => {
+            let Some(last_run) = task.last_run else {
+                return Ok(());
+            };
+            let updated = target_profile_items(task)
+                .await
+                .iter()
+                .any(|item| item.updated.is_some_and(|t| t as i64 > last_run));
+            if updated {
+                Ok(())
+            } else {
+                Err("目标订阅自上次执行后未更新".to_string())
+            }
+        }
+    }
+}
+
+/// 获取任务的目标订阅，为空时使用当前订阅
+async fn target_profile_items(task: &TaskConfig) -> Vec<PrfItem> {
+    let profiles = Config::profiles().await;
+    let profiles_ref = profiles.latest_ref();
+    let targets = if task.target_profiles.is_empty() {
+        profiles_ref.get_current().into_iter().collect()
+    } else {
+        task.target_profiles.clone()
+    };
+
+    targets
+        .iter()
+        .filter_map(|uid| profiles_ref.get_item(uid).ok().cloned())
+        .collect()
+}
+
+/// 条件不满足或被取消时推迟到下一个周期
+pub(crate) async fn postpone_task(task_id: &str, now: i64) -> CmdResult<()> {
+    let mut tasks = load_tasks_from_config().await?;
+    if let Some(stored) = tasks.iter_mut().find(|t| t.id == task_id) {
+        stored.next_run = Some(compute_next_run(stored, now));
+        save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 检查依赖图中是否存在从指定任务出发的循环
+fn has_dependency_cycle(tasks: &[TaskConfig], start: &str) -> bool {
+    let mut stack: Vec<&str> = vec![start];
+    let mut visited = HashSet::new();
+
+    while let Some(current) = stack.pop() {
+        let Some(task) = tasks.iter().find(|t| t.id == current) else {
+            continue;
+        };
+        for dep in &task.depends_on {
+            if dep == start {
+                return true;
+            }
+            if visited.insert(dep.as_str()) {
+                stack.push(dep);
+            }
+        }
+    }
+
+    false
+}
+
+/// 计算下次执行时间
+fn compute_next_run(task: &TaskConfig, from: i64) -> i64 {
+    from + (task.interval_minutes.max(1) as i64) * 60
+}
+
+/// 获取任务数据目录
+fn get_task_data_dir() -> Result<PathBuf> {
+    let task_dir = dirs::app_home_dir()?.join("tasks");
+    if !task_dir.exists() {
+        fs::create_dir_all(&task_dir)?;
+    }
+    Ok(task_dir)
+}
+
+/// 保存任务列表
+pub(crate) fn save_tasks_list(tasks: &[TaskConfig]) -> Result<()> {
+    let tasks_file = get_task_data_dir()?.join("tasks.json");
+    let json_data = serde_json::to_string_pretty(tasks)?;
+    fs::write(tasks_file, json_data)?;
+    Ok(())
+}
+
+/// 加载任务列表
+pub(crate) fn load_tasks_list() -> Result<Vec<TaskConfig>> {
+    let tasks_file = get_task_data_dir()?.join("tasks.json");
+    if !tasks_file.exists() {
+        return Ok(Vec::new());
+    }
+    let json_data = fs::read_to_string(tasks_file)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
+/// 保存执行历史
+fn save_history_list(history: &[TaskExecutionResult]) -> Result<()> {
+    let history_file = get_task_data_dir()?.join("history.json");
+    let json_data = serde_json::to_string_pretty(history)?;
+    fs::write(history_file, json_data)?;
+    Ok(())
+}
+
+/// 加载执行历史（按时间倒序）
+fn load_history_list() -> Result<Vec<TaskExecutionResult>> {
+    let history_file = get_task_data_dir()?.join("history.json");
+    if !history_file.exists() {
+        return Ok(Vec::new());
+    }
+    let json_data = fs::read_to_string(history_file)?;
+    Ok(serde_json::from_str(&json_data)?)
+}
+
 /// 从配置加载任务
 async fn load_tasks_from_config() -> CmdResult<Vec<TaskConfig>> {
-    // TODO: 实现从配置文件或数据库加载任务
-    // 暂时返回空列表
-    Ok(vec![])
+    load_tasks_list().map_err(|e| format!("加载任务配置失败: {}", e).into())
 }
 
 /// 保存任务到配置
 async fn save_task_to_config(task: &TaskConfig) -> CmdResult<()> {
-    // TODO: 实现保存任务到配置文件或数据库
     logging!(debug, Type::Cmd, "保存任务配置: {}", task.id);
-    Ok(())
+
+    let mut tasks = load_tasks_from_config().await?;
+    match tasks.iter_mut().find(|t| t.id == task.id) {
+        Some(existing) => *existing = task.clone(),
+        None => tasks.push(task.clone()),
+    }
+    save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e).into())
 }
 
 /// 从配置中删除任务
 async fn remove_task_from_config(task_id: &str) -> CmdResult<()> {
-    // TODO: 实现从配置文件或数据库删除任务
     logging!(debug, Type::Cmd, "删除任务配置: {}", task_id);
-    Ok(())
+
+    let mut tasks = load_tasks_from_config().await?;
+    tasks.retain(|t| t.id != task_id);
+    save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e).into())
 }
 
 /// 注册任务到定时器
 async fn register_task_to_timer(task: &TaskConfig) -> CmdResult<()> {
     logging!(debug, Type::Cmd, "注册任务到定时器: {}", task.id);
 
-    // 使用现有的Timer系统
-    // TODO: 实现定时器功能，这里暂时使用 tokio::time::sleep 代替
-    // let timer = Timer::global();
-    // timer.refresh().await
-    //     .map_err(|e| format!("Failed to register task to timer: {}", e))?;
+    let mut tasks = load_tasks_from_config().await?;
+    if let Some(stored) = tasks.iter_mut().find(|t| t.id == task.id) {
+        let from = stored
+            .last_run
+            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+        stored.next_run = Some(compute_next_run(stored, from));
+        save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e))?;
+    }
 
+    init_task_scheduler();
     Ok(())
 }
 
@@ -459,7 +1149,11 @@ async fn register_task_to_timer(task: &TaskConfig) -> CmdResult<()> {
 async fn unregister_task_from_timer(task_id: &str) -> CmdResult<()> {
     logging!(debug, Type::Cmd, "从定时器注销任务: {}", task_id);
 
-    // TODO: 实现从定时器中移除特定任务
+    let mut tasks = load_tasks_from_config().await?;
+    if let Some(stored) = tasks.iter_mut().find(|t| t.id == task_id) {
+        stored.next_run = None;
+        save_tasks_list(&tasks).map_err(|e| format!("保存任务配置失败: {}", e))?;
+    }
     Ok(())
 }
 
@@ -467,6 +1161,7 @@ async fn unregister_task_from_timer(task_id: &str) -> CmdResult<()> {
 async fn execute_task(task: &TaskConfig) -> TaskExecutionResult {
     let execution_id = Uuid::new_v4().to_string();
     let start_time = chrono::Utc::now().timestamp();
+    let started = std::time::Instant::now();
 
     logging!(info, Type::Cmd, "执行任务: {} ({})", task.name, task.id);
 
@@ -474,11 +1169,15 @@ async fn execute_task(task: &TaskConfig) -> TaskExecutionResult {
         TaskType::HealthCheck => execute_health_check_task(task).await,
         TaskType::AutoCleanup => execute_cleanup_task(task).await,
         TaskType::SubscriptionUpdate => execute_subscription_update_task(task).await,
+        TaskType::SpeedTest => execute_speed_test_task(task).await,
+        TaskType::ExpiryReminder => execute_expiry_reminder_task(task).await,
+        TaskType::TrafficReport => execute_traffic_report_task(task).await,
+        TaskType::GeoDataUpdate => execute_geo_data_update_task(task).await,
         TaskType::Custom => execute_custom_task(task).await,
     };
 
     let end_time = chrono::Utc::now().timestamp();
-    let duration_ms = ((end_time - start_time) * 1000) as u64;
+    let duration_ms = started.elapsed().as_millis() as u64;
 
     TaskExecutionResult {
         task_id: task.id.clone(),
@@ -493,7 +1192,7 @@ async fn execute_task(task: &TaskConfig) -> TaskExecutionResult {
         duration_ms: Some(duration_ms),
         message: result.as_ref().ok().cloned(),
         error_details: result.as_ref().err().map(|e| e.to_string()),
-        affected_profiles: vec![], // TODO: 实现受影响的订阅列表
+        affected_profiles: task.target_profiles.clone(),
         retry_count: 0,
     }
 }
@@ -502,17 +1201,12 @@ async fn execute_task(task: &TaskConfig) -> TaskExecutionResult {
 async fn execute_health_check_task(task: &TaskConfig) -> Result<String, String> {
     logging!(info, Type::Cmd, "执行健康检查任务: {}", task.id);
 
-    // 调用健康检查API
-    match crate::cmd::health_check::check_all_subscriptions_health().await {
-        Ok(result) => {
-            let message = format!(
-                "健康检查完成: 总数={}, 健康={}, 警告={}, 不健康={}",
-                result.total, result.healthy, result.warning, result.unhealthy
-            );
-            Ok(message)
-        }
-        Err(e) => Err(format!("健康检查失败: {}", e)),
-    }
+    // 已在任务队列中执行，直接检查，避免再次排队等待自身
+    let result = crate::cmd::health_check::run_all_health_checks().await;
+    Ok(format!(
+        "健康检查完成: 总数={}, 健康={}, 警告={}, 不健康={}",
+        result.total, result.healthy, result.warning, result.unhealthy
+    ))
 }
 
 /// 执行清理任务
@@ -534,6 +1228,89 @@ async fn execute_subscription_update_task(task: &TaskConfig) -> Result<String, S
     Ok("订阅更新完成".to_string())
 }
 
+/// 执行测速任务
+async fn execute_speed_test_task(task: &TaskConfig) -> Result<String, String> {
+    use super::global_speed_test::{
+        SpeedTestConfig, apply_best_node, latest_speed_test_summary, start_global_speed_test,
+    };
+
+    logging!(info, Type::Cmd, "执行测速任务: {}", task.id);
+
+    let options = task.options.speed_test.clone().unwrap_or_default();
+    let app_handle = handle::Handle::global()
+        .app_handle()
+        .ok_or_else(|| "应用句柄未初始化".to_string())?;
+
+    let config = SpeedTestConfig {
+        batch_size: 1,
+        node_timeout_seconds: options.node_timeout_seconds,
+        batch_timeout_seconds: 10,
+        overall_timeout_seconds: task.options.timeout_seconds as u64,
+        max_concurrent: 1,
+        max_nodes: match options.mode {
+            SpeedTestMode::Light => options.max_nodes,
+            SpeedTestMode::Full => None,
+        },
+        profile_uids: if task.target_profiles.is_empty() {
+            None
+        } else {
+            Some(task.target_profiles.clone())
+        },
+    };
+
+    start_global_speed_test(app_handle, Some(config)).await?;
+
+    let summary = latest_speed_test_summary().ok_or_else(|| "没有测速结果".to_string())?;
+    let mut message = format!(
+        "测速完成: 总数={}, 成功={}, 失败={}",
+        summary.tested_nodes, summary.successful_tests, summary.failed_tests
+    );
+    if let Some(best) = &summary.best_node {
+        message.push_str(&format!(
+            ", 最佳节点={} ({}ms)",
+            best.node_name,
+            best.latency.unwrap_or(0)
+        ));
+    }
+
+    if options.auto_apply_best {
+        match apply_best_node().await {
+            Ok(applied) => message.push_str(&format!(", {}", applied)),
+            Err(e) => return Err(format!("{}, 但应用最佳节点失败: {}", message, e)),
+        }
+    }
+
+    Ok(message)
+}
+
+/// 执行到期提醒任务
+async fn execute_expiry_reminder_task(task: &TaskConfig) -> Result<String, String> {
+    logging!(info, Type::Cmd, "执行到期提醒任务: {}", task.id);
+
+    match crate::cmd::expiry_reminder::check_expiring_subscriptions().await {
+        Ok(count) => Ok(format!("到期检查完成，提醒{}个订阅", count)),
+        Err(e) => Err(format!("到期检查失败: {}", e)),
+    }
+}
+
+/// 执行月度流量报告任务：每月 1 日之后首次执行时生成上个月的报告
+async fn execute_traffic_report_task(task: &TaskConfig) -> Result<String, String> {
+    logging!(info, Type::Cmd, "执行月度流量报告任务: {}", task.id);
+
+    crate::cmd::traffic_report::generate_last_month_report()
+        .await
+        .map_err(|e| format!("生成流量报告失败: {}", e))
+}
+
+/// 执行地理数据更新任务：校验镜像源并更新有变化的数据集
+async fn execute_geo_data_update_task(task: &TaskConfig) -> Result<String, String> {
+    logging!(info, Type::Cmd, "执行地理数据更新任务: {}", task.id);
+
+    crate::cmd::geo_data::run_scheduled_update()
+        .await
+        .map_err(|e| format!("地理数据更新失败: {}", e))
+}
+
 /// 执行自定义任务
 async fn execute_custom_task(_task: &TaskConfig) -> Result<String, String> {
     // TODO: 实现自定义任务执行
@@ -542,9 +1319,12 @@ async fn execute_custom_task(_task: &TaskConfig) -> Result<String, String> {
 
 /// 保存执行结果
 async fn save_execution_result(result: &TaskExecutionResult) -> CmdResult<()> {
-    // TODO: 实现保存执行结果到数据库或文件
     logging!(debug, Type::Cmd, "保存执行结果: {}", result.execution_id);
-    Ok(())
+
+    let mut history = load_history_list().unwrap_or_default();
+    history.insert(0, result.clone());
+    history.truncate(MAX_HISTORY_RECORDS);
+    save_history_list(&history).map_err(|e| format!("保存执行结果失败: {}", e).into())
 }
 
 /// 加载执行历史
@@ -552,7 +1332,6 @@ async fn load_execution_history(
     task_id: &str,
     limit: usize,
 ) -> CmdResult<Vec<TaskExecutionResult>> {
-    // TODO: 实现从数据库或文件加载执行历史
     logging!(
         debug,
         Type::Cmd,
@@ -560,14 +1339,21 @@ async fn load_execution_history(
         task_id,
         limit
     );
-    Ok(vec![])
+
+    let history = load_history_list().map_err(|e| format!("加载执行历史失败: {}", e))?;
+    Ok(history
+        .into_iter()
+        .filter(|r| r.task_id == task_id)
+        .take(limit)
+        .collect())
 }
 
 /// 加载最近执行记录
 async fn load_recent_executions(limit: usize) -> CmdResult<Vec<TaskExecutionResult>> {
-    // TODO: 实现加载最近的执行记录
     logging!(debug, Type::Cmd, "加载最近执行记录，限制: {}", limit);
-    Ok(vec![])
+
+    let history = load_history_list().map_err(|e| format!("加载执行历史失败: {}", e))?;
+    Ok(history.into_iter().take(limit).collect())
 }
 
 /// 计算任务统计信息
@@ -613,19 +1399,28 @@ fn calculate_next_execution(tasks: &[TaskConfig]) -> Option<i64> {
 
 /// 清理任务执行历史
 async fn cleanup_task_execution_history(task_id: &str) -> CmdResult<()> {
-    // TODO: 实现清理特定任务的执行历史
     logging!(debug, Type::Cmd, "清理任务执行历史: {}", task_id);
-    Ok(())
+
+    let mut history = load_history_list().map_err(|e| format!("加载执行历史失败: {}", e))?;
+    history.retain(|r| r.task_id != task_id);
+    save_history_list(&history).map_err(|e| format!("保存执行历史失败: {}", e).into())
 }
 
 /// 清理过期的执行历史
 async fn cleanup_old_execution_history(cutoff_time: i64) -> CmdResult<u64> {
-    // TODO: 实现清理过期的执行历史
     logging!(
         debug,
         Type::Cmd,
         "清理过期执行历史，截止时间: {}",
         cutoff_time
     );
-    Ok(0)
+
+    let mut history = load_history_list().map_err(|e| format!("加载执行历史失败: {}", e))?;
+    let before = history.len();
+    history.retain(|r| r.start_time >= cutoff_time);
+    let removed = (before - history.len()) as u64;
+    if removed > 0 {
+        save_history_list(&history).map_err(|e| format!("保存执行历史失败: {}", e))?;
+    }
+    Ok(removed)
 }
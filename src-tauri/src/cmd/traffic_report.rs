@@ -0,0 +1,401 @@
+use super::{
+    CmdError, CmdResult, ErrorCode,
+    traffic_stats::{PeriodUsage, UsageCounter, month_usage},
+};
+use crate::{
+    logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, anyhow};
+use chrono::{Datelike, Local, Months, NaiveDate, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+const REPORTS_DIR: &str = "traffic_reports";
+/// 报告中列出的高峰时段数量
+const PEAK_HOURS: usize = 3;
+
+/// 报告中的分项流量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEntry {
+    pub name: String,
+    pub upload_bytes: u64,
+    pub download_bytes: u64,
+    pub total_bytes: u64,
+    pub share: f64, // 占该分项总流量的比例（0.0-1.0）
+}
+
+/// 配额使用状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ComplianceStatus {
+    Within,   // 未超过警告阈值
+    Warning,  // 超过警告阈值
+    Exceeded, // 超出配额
+}
+
+/// 订阅配额使用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaCompliance {
+    pub quota_bytes: u64,
+    pub used_bytes: u64,
+    pub usage_ratio: f64,
+    pub warning_threshold: f64,
+    pub status: ComplianceStatus,
+}
+
+/// 订阅月度流量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionReportEntry {
+    pub subscription_uid: String,
+    pub subscription_name: String,
+    pub upload_bytes: u64,
+    pub download_bytes: u64,
+    pub total_bytes: u64,
+    pub session_count: u64,
+    pub share: f64,
+    pub quota: Option<QuotaCompliance>,
+}
+
+/// 高峰时段（本地时间的小时）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeakHour {
+    pub hour: u32,
+    pub total_bytes: u64,
+}
+
+/// 月度流量报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficReport {
+    pub month: String, // YYYY-MM
+    pub generated_at: i64,
+    pub total_bytes: u64,
+    pub subscriptions: Vec<SubscriptionReportEntry>,
+    pub apps: Vec<UsageEntry>,
+    pub regions: Vec<UsageEntry>,
+    pub peak_hours: Vec<PeakHour>,
+}
+
+/// 生成并保存指定月份（YYYY-MM）的流量报告，已存在时覆盖
+#[tauri::command]
+pub async fn generate_traffic_report(month: String) -> CmdResult<TrafficReport> {
+    logging!(info, Type::Cmd, true, "[流量报告] 生成报告: {}", month);
+    let report = build_report(parse_month(&month)?).await?;
+    save_report(&report).context("保存流量报告失败")?;
+    Ok(report)
+}
+
+/// 获取已保存的报告月份，最新的在前
+#[tauri::command]
+pub async fn list_traffic_reports() -> CmdResult<Vec<String>> {
+    let dir = reports_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut months: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_suffix(".json")
+                .map(str::to_string)
+        })
+        .collect();
+    months.sort_by(|a, b| b.cmp(a));
+    Ok(months)
+}
+
+/// 获取已保存的报告
+#[tauri::command]
+pub async fn get_traffic_report(month: String) -> CmdResult<TrafficReport> {
+    Ok(load_report(parse_month(&month)?)?)
+}
+
+/// 导出已保存的报告为 CSV，用户取消时返回 None
+#[tauri::command]
+pub async fn export_traffic_report_csv(
+    app_handle: AppHandle,
+    month: String,
+) -> CmdResult<Option<String>> {
+    let report = load_report(parse_month(&month)?)?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .set_file_name(format!("traffic-report-{}.csv", report.month))
+        .add_filter("CSV", &["csv"])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    let path = path
+        .into_path()
+        .map_err(|e| CmdError::new(ErrorCode::InvalidArgument, format!("无效的保存路径: {e}")))?;
+
+    fs::write(&path, render_csv(&report)).context("写入报告失败")?;
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[流量报告] 已导出 {} 到 {}",
+        month,
+        path.display()
+    );
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// 生成上个月的报告（任务管理器每月自动生成），已生成时跳过
+pub(crate) async fn generate_last_month_report() -> Result<String> {
+    let last_month = Local::now()
+        .date_naive()
+        .with_day(1)
+        .and_then(|first| first.checked_sub_months(Months::new(1)))
+        .context("无法计算上个月")?;
+    if report_path(last_month)?.exists() {
+        return Ok(format!("{} 流量报告已存在", last_month.format("%Y-%m")));
+    }
+
+    let report = build_report(last_month)
+        .await
+        .map_err(|e| anyhow!(String::from(e)))?;
+    save_report(&report)?;
+    Ok(format!(
+        "已生成 {} 流量报告，共 {} 个订阅",
+        report.month,
+        report.subscriptions.len()
+    ))
+}
+
+// ===== 内部实现函数 =====
+
+/// 解析 YYYY-MM 格式的月份，返回该月第一天
+fn parse_month(month: &str) -> CmdResult<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d").map_err(|_| {
+        CmdError::new(
+            ErrorCode::InvalidArgument,
+            format!("无效的月份: {month}，格式应为 YYYY-MM"),
+        )
+    })
+}
+
+async fn build_report(first_day: NaiveDate) -> CmdResult<TrafficReport> {
+    let month = first_day.format("%Y-%m").to_string();
+    let (start, end) = month_range(first_day).ok_or_else(|| format!("无效的月份: {month}"))?;
+    // 所有数字均来自持久化的月度流量，重启前后保持一致
+    let usage = month_usage(&month, start, end).await;
+
+    let total_bytes: u64 = usage
+        .subscriptions
+        .iter()
+        .map(|u| u.usage.total_bytes())
+        .sum();
+    let mut subscriptions: Vec<SubscriptionReportEntry> = usage
+        .subscriptions
+        .iter()
+        .map(|period| SubscriptionReportEntry {
+            subscription_uid: period.subscription_uid.clone(),
+            subscription_name: period.subscription_name.clone(),
+            upload_bytes: period.usage.upload_bytes,
+            download_bytes: period.usage.download_bytes,
+            total_bytes: period.usage.total_bytes(),
+            session_count: period.usage.session_count,
+            share: share(period.usage.total_bytes(), total_bytes),
+            quota: quota_compliance(period),
+        })
+        .collect();
+    subscriptions.sort_by_key(|entry| std::cmp::Reverse(entry.total_bytes));
+
+    Ok(TrafficReport {
+        month,
+        generated_at: chrono::Utc::now().timestamp(),
+        total_bytes,
+        subscriptions,
+        apps: usage_entries(usage.apps),
+        regions: usage_entries(usage.regions),
+        peak_hours: peak_hours(&usage.subscriptions),
+    })
+}
+
+/// 月份在本地时间中的起止时间戳 [start, end)
+fn month_range(first: NaiveDate) -> Option<(i64, i64)> {
+    let next = first.checked_add_months(Months::new(1))?;
+    let timestamp = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|time| time.timestamp())
+    };
+    Some((timestamp(first)?, timestamp(next)?))
+}
+
+fn quota_compliance(usage: &PeriodUsage) -> Option<QuotaCompliance> {
+    let quota_info = usage.quota_info.as_ref().filter(|q| !q.is_unlimited)?;
+    let quota_bytes = quota_info.total_quota_bytes.filter(|quota| *quota > 0)?;
+    let used_bytes = if quota_info.used_quota_bytes > 0 {
+        quota_info.used_quota_bytes
+    } else {
+        usage.lifetime_bytes
+    };
+    let usage_ratio = used_bytes as f64 / quota_bytes as f64;
+    Some(QuotaCompliance {
+        quota_bytes,
+        used_bytes,
+        usage_ratio,
+        warning_threshold: quota_info.warning_threshold,
+        status: if usage_ratio >= 1.0 {
+            ComplianceStatus::Exceeded
+        } else if usage_ratio >= quota_info.warning_threshold {
+            ComplianceStatus::Warning
+        } else {
+            ComplianceStatus::Within
+        },
+    })
+}
+
+fn usage_entries(counters: BTreeMap<String, UsageCounter>) -> Vec<UsageEntry> {
+    let total: u64 = counters.values().map(UsageCounter::total_bytes).sum();
+    let mut entries: Vec<UsageEntry> = counters
+        .into_iter()
+        .map(|(name, counter)| {
+            let total_bytes = counter.total_bytes();
+            UsageEntry {
+                name,
+                upload_bytes: counter.upload_bytes,
+                download_bytes: counter.download_bytes,
+                total_bytes,
+                share: share(total_bytes, total),
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.total_bytes));
+    entries
+}
+
+/// 按本地时间的小时汇总所有订阅的流量，取流量最高的时段
+fn peak_hours(usages: &[PeriodUsage]) -> Vec<PeakHour> {
+    let mut by_hour = [0u64; 24];
+    for (hour, bytes) in usages.iter().flat_map(|u| &u.hourly) {
+        if let Some(time) = Local.timestamp_opt(*hour, 0).single() {
+            by_hour[time.hour() as usize] += bytes;
+        }
+    }
+    let mut peaks: Vec<PeakHour> = by_hour
+        .iter()
+        .enumerate()
+        .filter(|(_, bytes)| **bytes > 0)
+        .map(|(hour, bytes)| PeakHour {
+            hour: hour as u32,
+            total_bytes: *bytes,
+        })
+        .collect();
+    peaks.sort_by_key(|peak| std::cmp::Reverse(peak.total_bytes));
+    peaks.truncate(PEAK_HOURS);
+    peaks
+}
+
+fn share(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn reports_dir() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(REPORTS_DIR))
+}
+
+fn report_path(first_day: NaiveDate) -> Result<PathBuf> {
+    Ok(reports_dir()?.join(format!("{}.json", first_day.format("%Y-%m"))))
+}
+
+fn save_report(report: &TrafficReport) -> Result<()> {
+    fs::create_dir_all(reports_dir()?)?;
+    let first_day = NaiveDate::parse_from_str(&format!("{}-01", report.month), "%Y-%m-%d")?;
+    fs::write(
+        report_path(first_day)?,
+        serde_json::to_string_pretty(report)?,
+    )?;
+    Ok(())
+}
+
+fn load_report(first_day: NaiveDate) -> Result<TrafficReport> {
+    let path = report_path(first_day)?;
+    if !path.exists() {
+        return Err(anyhow!("{} 流量报告不存在", first_day.format("%Y-%m")));
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 每行一个分项：订阅、应用、地区与高峰时段
+fn render_csv(report: &TrafficReport) -> String {
+    let mut csv = String::from(
+        "section,name,upload_bytes,download_bytes,total_bytes,share,session_count,quota_bytes,quota_usage_ratio,quota_status\n",
+    );
+    let mut push_row = |fields: [String; 10]| {
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    };
+
+    for entry in &report.subscriptions {
+        let quota = entry.quota.as_ref();
+        push_row([
+            "subscription".to_string(),
+            csv_field(&entry.subscription_name),
+            entry.upload_bytes.to_string(),
+            entry.download_bytes.to_string(),
+            entry.total_bytes.to_string(),
+            format!("{:.4}", entry.share),
+            entry.session_count.to_string(),
+            quota.map(|q| q.quota_bytes.to_string()).unwrap_or_default(),
+            quota
+                .map(|q| format!("{:.4}", q.usage_ratio))
+                .unwrap_or_default(),
+            quota.map(|q| format!("{:?}", q.status)).unwrap_or_default(),
+        ]);
+    }
+    for (section, entries) in [("app", &report.apps), ("region", &report.regions)] {
+        for entry in entries {
+            push_row([
+                section.to_string(),
+                csv_field(&entry.name),
+                entry.upload_bytes.to_string(),
+                entry.download_bytes.to_string(),
+                entry.total_bytes.to_string(),
+                format!("{:.4}", entry.share),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+    }
+    for peak in &report.peak_hours {
+        push_row([
+            "peak_hour".to_string(),
+            format!("{:02}:00", peak.hour),
+            String::new(),
+            String::new(),
+            peak.total_bytes.to_string(),
+            format!("{:.4}", share(peak.total_bytes, report.total_bytes)),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ]);
+    }
+    csv
+}
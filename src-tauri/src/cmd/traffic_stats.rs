@@ -12,18 +12,53 @@
 )]
 // TODO: 保留提醒，待后续清理流量统计模块 lint。
 use super::CmdResult;
-use crate::{config::Config, logging, utils::logging::Type};
+use crate::{
+    config::{Config, PrfExtra},
+    logging,
+    utils::{dirs, logging::Type},
+};
 use anyhow::Result;
+use chrono::{Datelike, TimeZone, Timelike};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
 
 /// 流量统计数据存储
 static TRAFFIC_STATS: Lazy<Arc<RwLock<TrafficStatsStorage>>> =
-    Lazy::new(|| Arc::new(RwLock::new(TrafficStatsStorage::new())));
+    Lazy::new(|| Arc::new(RwLock::new(TrafficStatsStorage::load())));
+
+const HOUR_SECS: i64 = 3600;
+/// 一周的小时数，季节系数按一周中的小时计算
+const WEEK_HOURS: usize = 168;
+/// 小时流量保留 8 周
+const HOURLY_RETENTION_HOURS: i64 = 8 * WEEK_HOURS as i64;
+/// 去季节后小时流量的 EWMA 平滑系数
+const EWMA_ALPHA: f64 = 0.05;
+/// 季节系数下限，避免空闲时段的系数为 0
+const SEASONAL_FLOOR: f64 = 0.05;
+/// 模型预热小时数，预热期内不做异常判断
+const MODEL_WARMUP_HOURS: usize = 48;
+/// 超过预期的标准差倍数才视为突增
+const SPIKE_Z_SCORE: f64 = 4.0;
+/// 实际流量至少为预期的倍数才视为突增
+const SPIKE_MIN_RATIO: f64 = 3.0;
+/// 单小时流量低于该值时不视为突增
+const SPIKE_MIN_BYTES: f64 = 100.0 * 1024.0 * 1024.0;
+/// 耗尽日期最多向后预测一年
+const EXHAUST_HORIZON_HOURS: i64 = 365 * 24;
+/// 月度流量最多保留的月份数
+const MONTHLY_RETENTION: usize = 13;
+/// 连接流量写入磁盘的最小间隔（秒）
+const MONTHLY_FLUSH_SECS: i64 = 60;
+/// 默认的订阅额度告警阈值（已用比例）
+const DEFAULT_QUOTA_THRESHOLDS: [f64; 3] = [0.8, 0.9, 1.0];
+/// 每个订阅保留的额度快照数量
+const QUOTA_HISTORY_LIMIT: usize = 90;
 
 /// 流量单位枚举
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +104,27 @@ pub struct SubscriptionTrafficStats {
     pub quota_info: Option<QuotaInfo>,
 }
 
+impl SubscriptionTrafficStats {
+    fn empty(subscription_uid: String, subscription_name: String) -> Self {
+        Self {
+            subscription_uid,
+            subscription_name,
+            total_upload_bytes: 0,
+            total_download_bytes: 0,
+            total_bytes: 0,
+            session_count: 0,
+            total_duration_seconds: 0,
+            avg_speed_mbps: 0.0,
+            peak_speed_mbps: 0.0,
+            first_used: None,
+            last_used: None,
+            daily_usage: Vec::new(),
+            monthly_usage: Vec::new(),
+            quota_info: None,
+        }
+    }
+}
+
 /// 每日使用量
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyUsage {
@@ -126,6 +182,7 @@ pub enum AlertType {
     HighUsage,       // 高流量使用警告
     SpeedDrop,       // 速度下降警告
     ConnectionIssue, // 连接问题警告
+    UsageSpike,      // 流量突增警告
 }
 
 /// 警告严重程度
@@ -174,11 +231,117 @@ pub enum TrendDirection {
     Decreasing,
 }
 
+/// 异常检测时间范围
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum AnomalyRange {
+    Day,
+    Week,
+    Month,
+}
+
+impl AnomalyRange {
+    fn hours(self) -> i64 {
+        match self {
+            AnomalyRange::Day => 24,
+            AnomalyRange::Week => 7 * 24,
+            AnomalyRange::Month => 30 * 24,
+        }
+    }
+}
+
+/// 流量异常（单小时流量突增）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficAnomaly {
+    pub subscription_uid: String,
+    pub subscription_name: String,
+    pub hour_start: i64,
+    pub actual_bytes: u64,
+    pub expected_bytes: u64,
+    pub score: f64, // 超出预期的标准差倍数
+    pub severity: AlertSeverity,
+}
+
 /// 流量统计存储
+/// 上传 / 下载流量计数
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct UsageCounter {
+    pub upload_bytes: u64,
+    pub download_bytes: u64,
+    #[serde(default)]
+    pub session_count: u64,
+}
+
+impl UsageCounter {
+    fn add(&mut self, upload_bytes: u64, download_bytes: u64) {
+        self.upload_bytes += upload_bytes;
+        self.download_bytes += download_bytes;
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.upload_bytes + self.download_bytes
+    }
+}
+
+/// 单月按订阅、应用与出站地区汇总的流量
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct MonthlyUsageLedger {
+    #[serde(default)]
+    pub subscriptions: BTreeMap<String, UsageCounter>,
+    #[serde(default)]
+    pub apps: BTreeMap<String, UsageCounter>,
+    #[serde(default)]
+    pub regions: BTreeMap<String, UsageCounter>,
+}
+
+/// 一条连接在两次快照之间产生的流量
+pub(crate) struct ConnectionUsage {
+    pub app: String,
+    pub region: String,
+    pub upload_bytes: u64,
+    pub download_bytes: u64,
+}
+
+/// 拉取订阅时记录的已用额度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaSnapshot {
+    pub time: i64,
+    pub used: u64,
+}
+
+/// 订阅额度的拉取历史及当前周期内已触发的告警阈值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct QuotaTracking {
+    pub updated_at: i64,
+    pub history: Vec<QuotaSnapshot>,
+    alerted: Vec<f64>,
+}
+
+/// 额度告警阈值及各订阅的额度跟踪，持久化保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct QuotaState {
+    thresholds: Vec<f64>,
+    subscriptions: HashMap<String, QuotaTracking>,
+}
+
+impl Default for QuotaState {
+    fn default() -> Self {
+        Self {
+            thresholds: DEFAULT_QUOTA_THRESHOLDS.to_vec(),
+            subscriptions: HashMap::new(),
+        }
+    }
+}
+
 struct TrafficStatsStorage {
     records: HashMap<String, Vec<TrafficRecord>>,
     stats: HashMap<String, SubscriptionTrafficStats>,
     alerts: Vec<TrafficAlert>,
+    hourly_usage: HashMap<String, BTreeMap<i64, u64>>, // 按小时起始时间汇总的流量，持久化保存
+    monthly_usage: BTreeMap<String, MonthlyUsageLedger>, // 按月（YYYY-MM）汇总的流量，持久化保存
+    monthly_persisted_at: i64,
+    quota: QuotaState,
     total_upload: AtomicU64,
     total_download: AtomicU64,
 }
@@ -189,10 +352,90 @@ impl TrafficStatsStorage {
             records: HashMap::new(),
             stats: HashMap::new(),
             alerts: Vec::new(),
+            hourly_usage: HashMap::new(),
+            monthly_usage: BTreeMap::new(),
+            monthly_persisted_at: 0,
+            quota: QuotaState::default(),
             total_upload: AtomicU64::new(0),
             total_download: AtomicU64::new(0),
         }
     }
+
+    /// 创建存储并加载持久化的小时流量、月度流量与额度跟踪
+    fn load() -> Self {
+        let mut storage = Self::new();
+        match load_hourly_usage() {
+            Ok(hourly_usage) => storage.hourly_usage = hourly_usage,
+            Err(e) => {
+                logging!(warn, Type::Cmd, true, "[流量统计] 加载小时流量失败: {}", e);
+            }
+        }
+        match load_monthly_usage() {
+            Ok(monthly_usage) => storage.monthly_usage = monthly_usage,
+            Err(e) => {
+                logging!(warn, Type::Cmd, true, "[流量统计] 加载月度流量失败: {}", e);
+            }
+        }
+        match load_quota_state() {
+            Ok(quota) => storage.quota = quota,
+            Err(e) => {
+                logging!(warn, Type::Cmd, true, "[流量统计] 加载额度跟踪失败: {}", e);
+            }
+        }
+        storage
+    }
+
+    /// 按记录结束时间所在的月份累计订阅流量
+    fn add_monthly_usage(&mut self, record: &TrafficRecord) {
+        let counter = self
+            .month_ledger(record.end_time)
+            .subscriptions
+            .entry(record.subscription_uid.clone())
+            .or_default();
+        counter.add(record.upload_bytes, record.download_bytes);
+        counter.session_count += 1;
+    }
+
+    /// 当月的流量汇总，并清理超出保留期的月份
+    fn month_ledger(&mut self, timestamp: i64) -> &mut MonthlyUsageLedger {
+        let month = month_key(timestamp);
+        if !self.monthly_usage.contains_key(&month) {
+            while self.monthly_usage.len() >= MONTHLY_RETENTION {
+                self.monthly_usage.pop_first();
+            }
+        }
+        self.monthly_usage.entry(month).or_default()
+    }
+
+    /// 把记录按时长分摊到各小时，并清理超出保留期的数据
+    fn add_hourly_usage(&mut self, record: &TrafficRecord) {
+        let hourly = self
+            .hourly_usage
+            .entry(record.subscription_uid.clone())
+            .or_default();
+        let start = record.start_time.min(record.end_time);
+        let end = record.end_time.max(start + 1);
+        let duration = (end - start) as u128;
+
+        let mut hour = start - start.rem_euclid(HOUR_SECS);
+        let mut assigned = 0u64;
+        while hour < end {
+            let next = hour + HOUR_SECS;
+            let bytes = if next >= end {
+                record.total_bytes - assigned
+            } else {
+                let overlap = (next - start.max(hour)) as u128;
+                (record.total_bytes as u128 * overlap / duration) as u64
+            };
+            assigned += bytes;
+            *hourly.entry(hour).or_insert(0) += bytes;
+            hour = next;
+        }
+
+        let cutoff =
+            current_hour(chrono::Utc::now().timestamp()) - HOURLY_RETENTION_HOURS * HOUR_SECS;
+        hourly.retain(|hour, _| *hour >= cutoff);
+    }
 }
 
 /// 记录流量使用
@@ -234,6 +477,8 @@ pub async fn record_traffic_usage(
     };
 
     // 添加记录
+    storage.add_hourly_usage(&record);
+    storage.add_monthly_usage(&record);
     storage
         .records
         .entry(subscription_uid.clone())
@@ -258,6 +503,8 @@ pub async fn record_traffic_usage(
         .await
         .map_err(|e| format!("Failed to check and generate alerts: {}", e))?;
 
+    persist_hourly_usage(&storage);
+    persist_monthly_usage(&mut storage);
     Ok(())
 }
 
@@ -284,22 +531,10 @@ pub async fn get_subscription_traffic_stats(
                 .await
                 .unwrap_or_else(|| "Unknown".to_string());
 
-            Ok(SubscriptionTrafficStats {
+            Ok(SubscriptionTrafficStats::empty(
                 subscription_uid,
                 subscription_name,
-                total_upload_bytes: 0,
-                total_download_bytes: 0,
-                total_bytes: 0,
-                session_count: 0,
-                total_duration_seconds: 0,
-                avg_speed_mbps: 0.0,
-                peak_speed_mbps: 0.0,
-                first_used: None,
-                last_used: None,
-                daily_usage: Vec::new(),
-                monthly_usage: Vec::new(),
-                quota_info: None,
-            })
+            ))
         }
     }
 }
@@ -465,6 +700,20 @@ pub async fn cleanup_traffic_history(days_to_keep: u32) -> CmdResult<u64> {
         cleaned_count += (original_len - records.len()) as u64;
     }
 
+    // 清理小时流量
+    for hourly in storage.hourly_usage.values_mut() {
+        hourly.retain(|hour, _| *hour >= cutoff_time);
+    }
+    storage.hourly_usage.retain(|_, hourly| !hourly.is_empty());
+    persist_hourly_usage(&storage);
+
+    // 清理月度流量
+    let cutoff_month = month_key(cutoff_time);
+    storage
+        .monthly_usage
+        .retain(|month, _| *month >= cutoff_month);
+    persist_monthly_usage(&mut storage);
+
     // 清理警告
     let original_alerts_len = storage.alerts.len();
     storage.alerts.retain(|a| a.created_at >= cutoff_time);
@@ -571,26 +820,47 @@ pub async fn set_subscription_quota(
             .await
             .unwrap_or_else(|| "Unknown".to_string());
 
-        let stats = SubscriptionTrafficStats {
-            subscription_uid: subscription_uid.clone(),
-            subscription_name,
-            total_upload_bytes: 0,
-            total_download_bytes: 0,
-            total_bytes: 0,
-            session_count: 0,
-            total_duration_seconds: 0,
-            avg_speed_mbps: 0.0,
-            peak_speed_mbps: 0.0,
-            first_used: None,
-            last_used: None,
-            daily_usage: Vec::new(),
-            monthly_usage: Vec::new(),
-            quota_info: Some(quota_info),
-        };
+        let mut stats =
+            SubscriptionTrafficStats::empty(subscription_uid.clone(), subscription_name);
+        stats.quota_info = Some(quota_info);
+        storage.stats.insert(subscription_uid.clone(), stats);
+    }
+
+    if let Some(alert) = check_quota_thresholds(&mut storage, &subscription_uid) {
+        storage.alerts.push(alert);
+    }
+    persist_quota_state(&storage);
+    Ok(())
+}
+
+/// 获取订阅额度告警阈值（已用比例，0-1）
+#[tauri::command]
+pub async fn get_quota_alert_thresholds() -> CmdResult<Vec<f64>> {
+    Ok(TRAFFIC_STATS.read().await.quota.thresholds.clone())
+}
 
-        storage.stats.insert(subscription_uid, stats);
+/// 设置订阅额度告警阈值（已用比例，0-1），为空时不再按额度告警
+#[tauri::command]
+pub async fn set_quota_alert_thresholds(mut thresholds: Vec<f64>) -> CmdResult<()> {
+    if thresholds
+        .iter()
+        .any(|threshold| !(*threshold > 0.0 && *threshold <= 1.0))
+    {
+        return Err("告警阈值需在 0 到 100% 之间".into());
     }
+    thresholds.sort_by(f64::total_cmp);
+    thresholds.dedup();
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[流量统计] 设置额度告警阈值: {:?}",
+        thresholds
+    );
 
+    let mut storage = TRAFFIC_STATS.write().await;
+    storage.quota.thresholds = thresholds;
+    persist_quota_state(&storage);
     Ok(())
 }
 
@@ -606,17 +876,279 @@ pub async fn get_traffic_prediction(subscription_uid: String) -> CmdResult<Traff
     );
 
     let storage = TRAFFIC_STATS.read().await;
+    let stats = storage.stats.get(&subscription_uid);
+    let hourly = storage.hourly_usage.get(&subscription_uid);
 
-    if let Some(stats) = storage.stats.get(&subscription_uid) {
-        let prediction = calculate_traffic_prediction(stats).await;
-        Ok(prediction)
-    } else {
-        Err("订阅统计数据不存在".to_string())
+    // 重启后明细记录不再保留，仅有持久化的小时流量时也可以预测
+    if stats.is_none() && hourly.is_none() {
+        return Err("订阅统计数据不存在".into());
     }
+    Ok(calculate_traffic_prediction(
+        &subscription_uid,
+        stats,
+        hourly.unwrap_or(&BTreeMap::new()),
+    ))
+}
+
+/// 获取流量异常（各订阅的单小时流量突增）
+#[tauri::command]
+pub async fn get_traffic_anomalies(range: AnomalyRange) -> CmdResult<Vec<TrafficAnomaly>> {
+    logging!(
+        info,
+        Type::Cmd,
+        true,
+        "[流量统计] 获取流量异常: {:?}",
+        range
+    );
+
+    let storage = TRAFFIC_STATS.read().await;
+    let now = chrono::Utc::now().timestamp();
+    let since = current_hour(now) - range.hours() * HOUR_SECS;
+
+    let mut anomalies = Vec::new();
+    for (uid, hourly) in &storage.hourly_usage {
+        let Some(model) = SeasonalModel::fit(hourly, now) else {
+            continue;
+        };
+        let subscription_name = match storage.stats.get(uid) {
+            Some(stats) => stats.subscription_name.clone(),
+            None => get_subscription_name(uid)
+                .await
+                .unwrap_or_else(|| "Unknown".to_string()),
+        };
+        anomalies.extend(
+            model
+                .anomalies(since)
+                .map(|fit| fit.to_anomaly(uid, &subscription_name)),
+        );
+    }
+
+    anomalies.sort_by(|a, b| b.hour_start.cmp(&a.hour_start));
+    Ok(anomalies)
+}
+
+/// 批量导入流量记录（备份恢复、测试数据生成使用）
+pub(crate) async fn import_traffic_records(records: Vec<TrafficRecord>) -> Result<()> {
+    let mut storage = TRAFFIC_STATS.write().await;
+    let mut names = HashMap::new();
+
+    for record in records {
+        storage.add_hourly_usage(&record);
+        storage.add_monthly_usage(&record);
+        storage
+            .total_upload
+            .fetch_add(record.upload_bytes, Ordering::Relaxed);
+        storage
+            .total_download
+            .fetch_add(record.download_bytes, Ordering::Relaxed);
+        names.insert(
+            record.subscription_uid.clone(),
+            record.subscription_name.clone(),
+        );
+        storage
+            .records
+            .entry(record.subscription_uid.clone())
+            .or_insert_with(Vec::new)
+            .push(record);
+    }
+
+    for (uid, name) in names {
+        update_subscription_stats(&mut storage, &uid, &name).await?;
+        check_and_generate_alerts(&mut storage, &uid).await?;
+    }
+
+    persist_hourly_usage(&storage);
+    persist_monthly_usage(&mut storage);
+    Ok(())
+}
+
+/// 导出全部流量记录（备份使用）
+pub(crate) async fn export_traffic_records() -> Vec<TrafficRecord> {
+    let storage = TRAFFIC_STATS.read().await;
+    storage.records.values().flatten().cloned().collect()
+}
+
+/// 订阅在指定月份内的流量（月度报告使用）
+pub(crate) struct PeriodUsage {
+    pub subscription_uid: String,
+    pub subscription_name: String,
+    pub usage: UsageCounter,
+    pub hourly: Vec<(i64, u64)>,
+    pub lifetime_bytes: u64,
+    pub quota_info: Option<QuotaInfo>,
+}
+
+/// 指定月份各订阅、应用与地区的流量，全部来自持久化数据
+pub(crate) struct MonthUsage {
+    pub subscriptions: Vec<PeriodUsage>,
+    pub apps: BTreeMap<String, UsageCounter>,
+    pub regions: BTreeMap<String, UsageCounter>,
+}
+
+/// 汇总 month（YYYY-MM）的流量，[start, end) 为该月的起止时间戳，用于统计高峰时段
+pub(crate) async fn month_usage(month: &str, start: i64, end: i64) -> MonthUsage {
+    let storage = TRAFFIC_STATS.read().await;
+    let ledger = storage
+        .monthly_usage
+        .get(month)
+        .cloned()
+        .unwrap_or_default();
+    let mut subscriptions = Vec::new();
+
+    for (uid, usage) in ledger.subscriptions {
+        let hourly: Vec<(i64, u64)> = storage
+            .hourly_usage
+            .get(&uid)
+            .map(|hourly| {
+                hourly
+                    .range(start..end)
+                    .map(|(hour, bytes)| (*hour, *bytes))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let stats = storage.stats.get(&uid);
+        let subscription_name = match stats {
+            Some(stats) => stats.subscription_name.clone(),
+            None => get_subscription_name(&uid)
+                .await
+                .unwrap_or_else(|| "Unknown".to_string()),
+        };
+
+        subscriptions.push(PeriodUsage {
+            lifetime_bytes: stats.map(|s| s.total_bytes).unwrap_or_default(),
+            quota_info: stats.and_then(|s| s.quota_info.clone()),
+            subscription_uid: uid,
+            subscription_name,
+            usage,
+            hourly,
+        });
+    }
+
+    MonthUsage {
+        subscriptions,
+        apps: ledger.apps,
+        regions: ledger.regions,
+    }
+}
+
+/// 按应用与出站地区累计连接流量（连接流监控调用）
+pub(crate) async fn record_connection_usage(usages: Vec<ConnectionUsage>) {
+    let mut storage = TRAFFIC_STATS.write().await;
+    let ledger = storage.month_ledger(chrono::Utc::now().timestamp());
+    for usage in usages {
+        ledger
+            .apps
+            .entry(usage.app)
+            .or_default()
+            .add(usage.upload_bytes, usage.download_bytes);
+        ledger
+            .regions
+            .entry(usage.region)
+            .or_default()
+            .add(usage.upload_bytes, usage.download_bytes);
+    }
+
+    // 连接快照约每秒一次，限制写入磁盘的频率
+    if chrono::Utc::now().timestamp() - storage.monthly_persisted_at >= MONTHLY_FLUSH_SECS {
+        persist_monthly_usage(&mut storage);
+    }
+}
+
+/// 清空流量统计数据（覆盖恢复使用），保留额度告警阈值设置
+pub(crate) async fn clear_traffic_records() {
+    let mut storage = TRAFFIC_STATS.write().await;
+    let thresholds = std::mem::take(&mut storage.quota.thresholds);
+    *storage = TrafficStatsStorage::new();
+    storage.quota.thresholds = thresholds;
+    persist_hourly_usage(&storage);
+    persist_monthly_usage(&mut storage);
+    persist_quota_state(&storage);
+}
+
+/// 拉取远程订阅后记录服务端返回的额度信息（subscription-userinfo）
+/// 跨过告警阈值时写入警告并返回，一次跨过多个阈值时只返回最高的一个
+pub(crate) async fn record_subscription_quota(
+    subscription_uid: &str,
+    subscription_name: &str,
+    extra: PrfExtra,
+) -> Option<TrafficAlert> {
+    let mut storage = TRAFFIC_STATS.write().await;
+    let now = chrono::Utc::now().timestamp();
+    let used = extra.upload + extra.download;
+
+    let stats = storage
+        .stats
+        .entry(subscription_uid.to_string())
+        .or_insert_with(|| {
+            SubscriptionTrafficStats::empty(
+                subscription_uid.to_string(),
+                subscription_name.to_string(),
+            )
+        });
+    let previous_total = stats
+        .quota_info
+        .as_ref()
+        .and_then(|quota| quota.total_quota_bytes);
+    let warning_threshold = stats
+        .quota_info
+        .as_ref()
+        .map(|quota| quota.warning_threshold);
+    let unlimited = extra.total == 0;
+    stats.quota_info = Some(QuotaInfo {
+        total_quota_bytes: (!unlimited).then_some(extra.total),
+        used_quota_bytes: used,
+        remaining_quota_bytes: (!unlimited).then(|| extra.total.saturating_sub(used)),
+        quota_reset_date: None,
+        expire_date: (extra.expire > 0).then_some(extra.expire as i64),
+        warning_threshold: warning_threshold.unwrap_or(DEFAULT_QUOTA_THRESHOLDS[0]),
+        is_unlimited: unlimited,
+    });
+
+    let tracking = storage
+        .quota
+        .subscriptions
+        .entry(subscription_uid.to_string())
+        .or_default();
+    // 流量重置或套餐变更后重新开始告警
+    let previous_used = tracking.history.last().map(|snapshot| snapshot.used);
+    if previous_used.is_some_and(|previous| used < previous)
+        || previous_total.is_some_and(|total| Some(total) != (!unlimited).then_some(extra.total))
+    {
+        tracking.alerted.clear();
+    }
+    tracking.updated_at = now;
+    tracking.history.push(QuotaSnapshot { time: now, used });
+    if tracking.history.len() > QUOTA_HISTORY_LIMIT {
+        let excess = tracking.history.len() - QUOTA_HISTORY_LIMIT;
+        tracking.history.drain(..excess);
+    }
+
+    let alert = check_quota_thresholds(&mut storage, subscription_uid);
+    if let Some(alert) = &alert {
+        storage.alerts.push(alert.clone());
+    }
+    persist_quota_state(&storage);
+    alert
+}
+
+/// 订阅的额度跟踪记录
+pub(crate) async fn quota_tracking(subscription_uid: &str) -> Option<QuotaTracking> {
+    TRAFFIC_STATS
+        .read()
+        .await
+        .quota
+        .subscriptions
+        .get(subscription_uid)
+        .cloned()
 }
 
 // ===== 内部辅助函数 =====
 
+/// 供其他模块写入警告（如订阅故障检测）
+pub(crate) async fn push_traffic_alert(alert: TrafficAlert) {
+    TRAFFIC_STATS.write().await.alerts.push(alert);
+}
+
 /// 获取订阅名称
 async fn get_subscription_name(subscription_uid: &str) -> Option<String> {
     let profiles = Config::profiles().await;
@@ -770,145 +1302,498 @@ async fn check_and_generate_alerts(
     storage: &mut TrafficStatsStorage,
     subscription_uid: &str,
 ) -> Result<()> {
-    if let Some(stats) = storage.stats.get(subscription_uid)
-        && let Some(quota_info) = &stats.quota_info
-    {
-        // 检查配额使用警告
-        if let Some(total_quota) = quota_info.total_quota_bytes {
-            let usage_ratio = stats.total_bytes as f64 / total_quota as f64;
-
-            if usage_ratio >= quota_info.warning_threshold && !quota_info.is_unlimited {
-                let alert = TrafficAlert {
-                    alert_id: uuid::Uuid::new_v4().to_string(),
-                    subscription_uid: subscription_uid.to_string(),
-                    subscription_name: stats.subscription_name.clone(),
-                    alert_type: AlertType::QuotaUsage,
-                    message: format!("配额使用已达到 {:.1}%", usage_ratio * 100.0),
-                    threshold_value: quota_info.warning_threshold,
-                    current_value: usage_ratio,
-                    created_at: chrono::Utc::now().timestamp(),
-                    is_read: false,
-                    severity: if usage_ratio >= 0.9 {
-                        AlertSeverity::Critical
-                    } else if usage_ratio >= 0.8 {
-                        AlertSeverity::Warning
-                    } else {
-                        AlertSeverity::Info
-                    },
-                };
-
-                // 避免重复警告
-                if !storage.alerts.iter().any(|a| {
-                    a.subscription_uid == subscription_uid
-                        && matches!(a.alert_type, AlertType::QuotaUsage)
-                        && !a.is_read
-                }) {
-                    storage.alerts.push(alert);
-                }
-            }
-        }
+    // 检查配额使用警告
+    if let Some(alert) = check_quota_thresholds(storage, subscription_uid) {
+        storage.alerts.push(alert);
+        persist_quota_state(storage);
+    }
 
-        // 检查到期警告
-        if let Some(expire_date) = quota_info.expire_date {
-            let days_until_expire = (expire_date - chrono::Utc::now().timestamp()) / (24 * 3600);
-
-            if days_until_expire <= 7 && days_until_expire > 0 {
-                let alert = TrafficAlert {
-                    alert_id: uuid::Uuid::new_v4().to_string(),
-                    subscription_uid: subscription_uid.to_string(),
-                    subscription_name: stats.subscription_name.clone(),
-                    alert_type: AlertType::ExpirationDate,
-                    message: format!("订阅将在 {} 天后到期", days_until_expire),
-                    threshold_value: 7.0,
-                    current_value: days_until_expire as f64,
-                    created_at: chrono::Utc::now().timestamp(),
-                    is_read: false,
-                    severity: if days_until_expire <= 3 {
-                        AlertSeverity::Critical
-                    } else {
-                        AlertSeverity::Warning
-                    },
-                };
-
-                // 避免重复警告
-                if !storage.alerts.iter().any(|a| {
-                    a.subscription_uid == subscription_uid
-                        && matches!(a.alert_type, AlertType::ExpirationDate)
-                        && !a.is_read
-                }) {
-                    storage.alerts.push(alert);
-                }
-            }
+    // 到期提醒由 expiry_reminder 按提醒阶段统一发送
+
+    // 检查最近一小时内的流量突增
+    let now = chrono::Utc::now().timestamp();
+    if let Some(hourly) = storage.hourly_usage.get(subscription_uid)
+        && let Some(model) = SeasonalModel::fit(hourly, now)
+        && let Some(spike) = model.anomalies(current_hour(now) - HOUR_SECS).last()
+    {
+        let subscription_name = storage
+            .stats
+            .get(subscription_uid)
+            .map(|s| s.subscription_name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let anomaly = spike.to_anomaly(subscription_uid, &subscription_name);
+
+        // 同一小时的突增只警告一次
+        if !storage.alerts.iter().any(|a| {
+            a.subscription_uid == subscription_uid
+                && matches!(a.alert_type, AlertType::UsageSpike)
+                && a.created_at >= anomaly.hour_start
+        }) {
+            storage.alerts.push(TrafficAlert {
+                alert_id: uuid::Uuid::new_v4().to_string(),
+                subscription_uid: subscription_uid.to_string(),
+                subscription_name,
+                alert_type: AlertType::UsageSpike,
+                message: format!(
+                    "流量突增: 一小时内使用 {:.1} MB，预期约 {:.1} MB",
+                    anomaly.actual_bytes as f64 / (1024.0 * 1024.0),
+                    anomaly.expected_bytes as f64 / (1024.0 * 1024.0)
+                ),
+                threshold_value: anomaly.expected_bytes as f64,
+                current_value: anomaly.actual_bytes as f64,
+                created_at: now,
+                is_read: false,
+                severity: anomaly.severity,
+            });
         }
     }
 
     Ok(())
 }
 
-/// 计算流量预测
-async fn calculate_traffic_prediction(stats: &SubscriptionTrafficStats) -> TrafficPrediction {
-    // 简单的线性预测算法
-    let recent_usage = stats
-        .monthly_usage
-        .iter()
-        .rev()
-        .take(3)
-        .map(|m| m.total_bytes)
-        .collect::<Vec<_>>();
+/// 按已用额度检查告警阈值，每个阈值在一个额度周期内只触发一次
+/// 阈值为全局设置加上订阅配额中的警告阈值
+fn check_quota_thresholds(
+    storage: &mut TrafficStatsStorage,
+    subscription_uid: &str,
+) -> Option<TrafficAlert> {
+    let stats = storage.stats.get(subscription_uid)?;
+    let quota_info = stats.quota_info.as_ref().filter(|q| !q.is_unlimited)?;
+    let total_quota = quota_info.total_quota_bytes.filter(|total| *total > 0)?;
+    let usage_ratio = quota_info.used_quota_bytes as f64 / total_quota as f64;
+
+    let mut thresholds = storage.quota.thresholds.clone();
+    if quota_info.warning_threshold > 0.0 && !thresholds.contains(&quota_info.warning_threshold) {
+        thresholds.push(quota_info.warning_threshold);
+    }
+    let tracking = storage
+        .quota
+        .subscriptions
+        .entry(subscription_uid.to_string())
+        .or_default();
+    let mut crossed = None;
+    for threshold in thresholds {
+        if usage_ratio >= threshold && !tracking.alerted.contains(&threshold) {
+            tracking.alerted.push(threshold);
+            crossed = Some(crossed.map_or(threshold, |max: f64| max.max(threshold)));
+        }
+    }
+    let threshold = crossed?;
 
-    let predicted_monthly_usage = if recent_usage.len() >= 2 {
-        recent_usage.iter().sum::<u64>() / recent_usage.len() as u64
-    } else {
-        stats.total_bytes / std::cmp::max(1, stats.monthly_usage.len() as u64)
+    Some(TrafficAlert {
+        alert_id: uuid::Uuid::new_v4().to_string(),
+        subscription_uid: subscription_uid.to_string(),
+        subscription_name: stats.subscription_name.clone(),
+        alert_type: AlertType::QuotaUsage,
+        message: if usage_ratio >= 1.0 {
+            format!("订阅 {} 的流量已用完", stats.subscription_name)
+        } else {
+            format!(
+                "订阅 {} 已使用 {:.1}% 的流量",
+                stats.subscription_name,
+                usage_ratio * 100.0
+            )
+        },
+        threshold_value: threshold,
+        current_value: usage_ratio,
+        created_at: chrono::Utc::now().timestamp(),
+        is_read: false,
+        severity: if usage_ratio >= 1.0 {
+            AlertSeverity::Critical
+        } else {
+            AlertSeverity::Warning
+        },
+    })
+}
+
+/// 计算流量预测：基于小时流量的 EWMA 水平与按周季节系数
+fn calculate_traffic_prediction(
+    subscription_uid: &str,
+    stats: Option<&SubscriptionTrafficStats>,
+    hourly: &BTreeMap<i64, u64>,
+) -> TrafficPrediction {
+    let now = chrono::Utc::now().timestamp();
+    let Some(model) = SeasonalModel::fit(hourly, now) else {
+        return TrafficPrediction {
+            subscription_uid: subscription_uid.to_string(),
+            predicted_monthly_usage: 0,
+            predicted_exhaust_date: None,
+            recommended_plan: None,
+            confidence_level: 0.0,
+            trend_direction: TrendDirection::Stable,
+        };
     };
 
-    // 预测耗尽日期
-    let predicted_exhaust_date = if let Some(quota_info) = &stats.quota_info {
-        if let Some(total_quota) = quota_info.total_quota_bytes {
-            if predicted_monthly_usage > 0 {
-                let remaining = total_quota.saturating_sub(stats.total_bytes);
-                let months_left = remaining / predicted_monthly_usage;
-                Some(chrono::Utc::now().timestamp() + (months_left as i64 * 30 * 24 * 3600))
+    // 未来 30 天逐小时预测
+    let next_hour = current_hour(now) + HOUR_SECS;
+    let predicted_monthly_usage = (0..30 * 24)
+        .map(|i| model.forecast(next_hour + i * HOUR_SECS))
+        .sum::<f64>() as u64;
+
+    // 预测耗尽日期：累计预测流量超过剩余配额的时间点
+    let predicted_exhaust_date = stats.and_then(|stats| {
+        let quota_info = stats.quota_info.as_ref().filter(|q| !q.is_unlimited)?;
+        let remaining = quota_info
+            .total_quota_bytes?
+            .saturating_sub(stats.total_bytes) as f64;
+        if remaining <= 0.0 {
+            return Some(now);
+        }
+        let mut used = 0.0;
+        (0..EXHAUST_HORIZON_HOURS)
+            .map(|i| next_hour + i * HOUR_SECS)
+            .find(|hour| {
+                used += model.forecast(*hour);
+                used >= remaining
+            })
+    });
+
+    TrafficPrediction {
+        subscription_uid: subscription_uid.to_string(),
+        predicted_monthly_usage,
+        predicted_exhaust_date,
+        recommended_plan: None, // TODO: 实现套餐推荐逻辑
+        confidence_level: model.confidence(),
+        trend_direction: model.trend(),
+    }
+}
+
+/// 单个小时的拟合结果
+struct HourlyFit {
+    hour_start: i64,
+    actual: f64,
+    expected: f64,
+    sigma: f64,
+    level: f64, // 该小时之前的 EWMA 水平
+}
+
+impl HourlyFit {
+    fn is_spike(&self) -> bool {
+        self.actual > spike_threshold(self.expected, self.sigma)
+    }
+
+    fn to_anomaly(&self, subscription_uid: &str, subscription_name: &str) -> TrafficAnomaly {
+        let score = (self.actual - self.expected) / self.sigma.max(1.0);
+        TrafficAnomaly {
+            subscription_uid: subscription_uid.to_string(),
+            subscription_name: subscription_name.to_string(),
+            hour_start: self.hour_start,
+            actual_bytes: self.actual as u64,
+            expected_bytes: self.expected as u64,
+            score,
+            severity: if score >= SPIKE_Z_SCORE * 2.0 {
+                AlertSeverity::Critical
             } else {
-                None
+                AlertSeverity::Warning
+            },
+        }
+    }
+}
+
+/// 小时流量模型：去季节后的 EWMA 水平与方差，季节系数按一周中的小时计算
+struct SeasonalModel {
+    seasonal: Vec<f64>,
+    level: f64,
+    fits: Vec<HourlyFit>,
+    error_ratio: f64, // 预热后去季节平均绝对误差与平均流量之比
+}
+
+impl SeasonalModel {
+    /// 从第一个有数据的小时拟合到上一个完整小时，缺失的小时视为无流量；
+    /// 每个小时的预期只使用之前的数据，当前未结束的小时不参与拟合
+    fn fit(hourly: &BTreeMap<i64, u64>, now: i64) -> Option<Self> {
+        let first = *hourly.keys().next()?;
+        let series: Vec<(i64, f64)> = (first..current_hour(now))
+            .step_by(HOUR_SECS as usize)
+            .map(|hour| (hour, hourly.get(&hour).copied().unwrap_or(0) as f64))
+            .collect();
+        if series.is_empty() {
+            return None;
+        }
+        let mean = series.iter().map(|(_, bytes)| bytes).sum::<f64>() / series.len() as f64;
+
+        let warmup = series.len().min(24);
+        let mut level =
+            series[..warmup].iter().map(|(_, bytes)| bytes).sum::<f64>() / warmup as f64;
+        let mut variance = level * level;
+        let mut fits = Vec::with_capacity(series.len());
+        let mut abs_error = 0.0;
+        let mut history = SeasonalHistory::default();
+
+        for (i, &(hour_start, actual)) in series.iter().enumerate() {
+            let slot = hour_of_week(hour_start);
+            let factor = history.factor(slot);
+            let expected = level * factor;
+            let sigma = variance.sqrt() * factor;
+            // 突增按阈值截断后再更新，避免单次突增拉高基线
+            let residual = actual.min(spike_threshold(expected, sigma)) / factor - level;
+            if i >= warmup {
+                abs_error += residual.abs();
             }
-        } else {
-            None
+            fits.push(HourlyFit {
+                hour_start,
+                actual,
+                expected,
+                sigma,
+                level,
+            });
+            level += EWMA_ALPHA * residual;
+            variance = (1.0 - EWMA_ALPHA) * variance + EWMA_ALPHA * residual * residual;
+            history.push(slot, actual);
         }
-    } else {
-        None
-    };
 
-    // 计算趋势
-    let trend_direction = if recent_usage.len() >= 2 {
-        let first_half_avg = recent_usage
-            .iter()
-            .take(recent_usage.len() / 2)
-            .sum::<u64>() as f64
-            / (recent_usage.len() / 2) as f64;
-        let second_half_avg = recent_usage
+        let error_ratio = if series.len() > warmup && mean > 0.0 {
+            abs_error / (series.len() - warmup) as f64 / mean
+        } else {
+            1.0
+        };
+        Some(Self {
+            seasonal: (0..WEEK_HOURS).map(|slot| history.factor(slot)).collect(),
+            level,
+            fits,
+            error_ratio,
+        })
+    }
+
+    fn forecast(&self, hour: i64) -> f64 {
+        self.level * self.seasonal[hour_of_week(hour)]
+    }
+
+    /// 指定时间之后的突增小时，预热期内的数据不参与判断
+    fn anomalies(&self, since: i64) -> impl Iterator<Item = &HourlyFit> {
+        self.fits
             .iter()
-            .skip(recent_usage.len() / 2)
-            .sum::<u64>() as f64
-            / (recent_usage.len() - recent_usage.len() / 2) as f64;
+            .skip(MODEL_WARMUP_HOURS)
+            .filter(move |fit| fit.hour_start >= since && fit.is_spike())
+    }
+
+    /// 历史越长、拟合误差越小，置信度越高
+    fn confidence(&self) -> f64 {
+        let coverage = (self.fits.len() as f64 / (4 * WEEK_HOURS) as f64).min(1.0);
+        let accuracy = 1.0 - self.error_ratio.min(1.0);
+        0.2 + 0.75 * coverage * accuracy
+    }
 
-        if second_half_avg > first_half_avg * 1.1 {
+    /// 当前水平与一周前（历史不足时取中点）的水平比较
+    fn trend(&self) -> TrendDirection {
+        let count = self.fits.len();
+        if count < MODEL_WARMUP_HOURS {
+            return TrendDirection::Stable;
+        }
+        let previous = self.fits[count - WEEK_HOURS.min(count / 2)].level;
+        if self.level > previous * 1.1 {
             TrendDirection::Increasing
-        } else if second_half_avg < first_half_avg * 0.9 {
+        } else if self.level < previous * 0.9 {
             TrendDirection::Decreasing
         } else {
             TrendDirection::Stable
         }
-    } else {
-        TrendDirection::Stable
-    };
+    }
+}
 
-    TrafficPrediction {
-        subscription_uid: stats.subscription_uid.clone(),
-        predicted_monthly_usage,
-        predicted_exhaust_date,
-        recommended_plan: None, // TODO: 实现套餐推荐逻辑
-        confidence_level: if recent_usage.len() >= 3 { 0.8 } else { 0.5 },
-        trend_direction,
+/// 已拟合小时按一周中的小时累计的流量，用于计算季节系数
+struct SeasonalHistory {
+    sums: Vec<f64>,
+    counts: Vec<usize>,
+    total: f64,
+    len: usize,
+}
+
+impl Default for SeasonalHistory {
+    fn default() -> Self {
+        Self {
+            sums: vec![0.0; WEEK_HOURS],
+            counts: vec![0; WEEK_HOURS],
+            total: 0.0,
+            len: 0,
+        }
+    }
+}
+
+impl SeasonalHistory {
+    fn push(&mut self, slot: usize, bytes: f64) {
+        self.sums[slot] += bytes;
+        self.counts[slot] += 1;
+        self.total += bytes;
+        self.len += 1;
+    }
+
+    /// 满一周后才计算季节系数，以整体均值作为先验收缩，避免样本少的时段过于极端
+    fn factor(&self, slot: usize) -> f64 {
+        if self.len < WEEK_HOURS || self.total <= 0.0 {
+            return 1.0;
+        }
+        let mean = self.total / self.len as f64;
+        let avg = (self.sums[slot] + mean) / (self.counts[slot] + 1) as f64;
+        (avg / mean).max(SEASONAL_FLOOR)
+    }
+}
+
+/// 超过该值视为突增：同时满足标准差倍数、预期倍数和最小流量
+fn spike_threshold(expected: f64, sigma: f64) -> f64 {
+    (expected + SPIKE_Z_SCORE * sigma)
+        .max(expected * SPIKE_MIN_RATIO)
+        .max(SPIKE_MIN_BYTES)
+}
+
+fn current_hour(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(HOUR_SECS)
+}
+
+/// 本地时间中一周的第几个小时（周一 0 点为 0）
+fn hour_of_week(timestamp: i64) -> usize {
+    chrono::Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.weekday().num_days_from_monday() as usize * 24 + time.hour() as usize)
+        .unwrap_or(0)
+}
+
+/// 本地时间的月份（YYYY-MM）
+fn month_key(timestamp: i64) -> String {
+    chrono::Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_else(chrono::Local::now)
+        .format("%Y-%m")
+        .to_string()
+}
+
+fn hourly_usage_file() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("traffic_hourly.json"))
+}
+
+/// 保存小时流量；调用方持有写锁，避免并发写入互相覆盖
+fn persist_hourly_usage(storage: &TrafficStatsStorage) {
+    let result = hourly_usage_file().and_then(|path| {
+        fs::write(path, serde_json::to_string(&storage.hourly_usage)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        logging!(warn, Type::Cmd, true, "[流量统计] 保存小时流量失败: {}", e);
+    }
+}
+
+fn load_hourly_usage() -> Result<HashMap<String, BTreeMap<i64, u64>>> {
+    let path = hourly_usage_file()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn monthly_usage_file() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("traffic_monthly.json"))
+}
+
+/// 保存月度流量；调用方持有写锁，避免并发写入互相覆盖
+fn persist_monthly_usage(storage: &mut TrafficStatsStorage) {
+    storage.monthly_persisted_at = chrono::Utc::now().timestamp();
+    let result = monthly_usage_file().and_then(|path| {
+        fs::write(path, serde_json::to_string(&storage.monthly_usage)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        logging!(warn, Type::Cmd, true, "[流量统计] 保存月度流量失败: {}", e);
+    }
+}
+
+fn quota_state_file() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join("traffic_quota.json"))
+}
+
+/// 保存额度跟踪；调用方持有写锁，避免并发写入互相覆盖
+fn persist_quota_state(storage: &TrafficStatsStorage) {
+    let result = quota_state_file().and_then(|path| {
+        fs::write(path, serde_json::to_string(&storage.quota)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        logging!(warn, Type::Cmd, true, "[流量统计] 保存额度跟踪失败: {}", e);
+    }
+}
+
+fn load_quota_state() -> Result<QuotaState> {
+    let path = quota_state_file()?;
+    if !path.exists() {
+        return Ok(QuotaState::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn load_monthly_usage() -> Result<BTreeMap<String, MonthlyUsageLedger>> {
+    let path = monthly_usage_file()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+    // 2024-01-01 00:00 UTC，之后四周内各时区均无夏令时切换
+    const START: i64 = 1_704_067_200;
+
+    fn flat_usage(hours: i64, bytes: u64) -> BTreeMap<i64, u64> {
+        (0..hours).map(|i| (START + i * HOUR_SECS, bytes)).collect()
+    }
+
+    #[test]
+    fn fit_excludes_incomplete_current_hour() {
+        let mut hourly = flat_usage(72, 10 * MB);
+        let now = START + 72 * HOUR_SECS + HOUR_SECS / 2;
+        hourly.insert(current_hour(now), MB);
+
+        let model = SeasonalModel::fit(&hourly, now).unwrap();
+        let last = model.fits.last().unwrap();
+        assert_eq!(model.fits.len(), 72);
+        assert_eq!(last.hour_start, current_hour(now) - HOUR_SECS);
+        assert!(matches!(model.trend(), TrendDirection::Stable));
+    }
+
+    #[test]
+    fn fit_returns_none_without_complete_hours() {
+        let hourly = BTreeMap::from([(START, 10 * MB)]);
+        assert!(SeasonalModel::fit(&hourly, START + 60).is_none());
+        assert!(SeasonalModel::fit(&BTreeMap::new(), START).is_none());
+    }
+
+    #[test]
+    fn spike_does_not_raise_its_own_expected_value() {
+        let hours = 3 * WEEK_HOURS as i64;
+        let mut hourly = flat_usage(hours, 10 * MB);
+        let spike_hour = START + (hours - 1) * HOUR_SECS;
+        hourly.insert(spike_hour, 500 * MB);
+
+        let model = SeasonalModel::fit(&hourly, spike_hour + HOUR_SECS).unwrap();
+        let spikes: Vec<_> = model.anomalies(START).collect();
+        assert_eq!(spikes.len(), 1);
+        assert_eq!(spikes[0].hour_start, spike_hour);
+        assert!((spikes[0].expected - (10 * MB) as f64).abs() < MB as f64);
+    }
+
+    #[test]
+    fn weekly_peak_is_learned_from_previous_weeks() {
+        let weeks = 4;
+        let mut hourly = flat_usage(weeks * WEEK_HOURS as i64, 10 * MB);
+        // 每周同一时段的固定高峰
+        let peak_offset = 100 * HOUR_SECS;
+        for week in 0..weeks {
+            hourly.insert(
+                START + week * WEEK_HOURS as i64 * HOUR_SECS + peak_offset,
+                200 * MB,
+            );
+        }
+        let last_peak = START + (weeks - 1) * WEEK_HOURS as i64 * HOUR_SECS + peak_offset;
+
+        let model = SeasonalModel::fit(&hourly, last_peak + HOUR_SECS).unwrap();
+        let fit = model.fits.last().unwrap();
+        assert_eq!(fit.hour_start, last_peak);
+        assert!(!fit.is_spike());
+        assert!(fit.expected > (100 * MB) as f64);
+        assert!(model.forecast(last_peak + WEEK_HOURS as i64 * HOUR_SECS) > model.forecast(START));
     }
 }
@@ -1,28 +1,92 @@
-use super::CmdResult;
+use super::{CmdError, CmdResult, ErrorCode};
+use serde::Serialize;
+
+/// UWP 应用及其回环豁免状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UwpApp {
+    pub name: String,
+    pub package_family_name: String,
+    pub version: Option<String>,
+    /// 是否允许访问本机回环地址（即本地代理端口）
+    pub exempted: bool,
+}
 
 /// Platform-specific implementation for UWP functionality
 #[cfg(windows)]
 mod platform {
-    use super::CmdResult;
-    use crate::{core::win_uwp, wrap_err};
+    use super::{CmdError, CmdResult, ErrorCode, UwpApp};
+    use crate::{core::win_uwp, logging, process::AsyncHandler, utils::logging::Type};
+
+    pub async fn list_uwp_apps() -> CmdResult<Vec<UwpApp>> {
+        AsyncHandler::spawn_blocking(win_uwp::list_apps)
+            .await
+            .map_err(|e| format!("枚举 UWP 应用任务失败: {e}"))?
+            .map_err(|e| format!("枚举 UWP 应用失败: {e}").into())
+    }
 
-    pub fn invoke_uwp_tool() -> CmdResult {
-        wrap_err!(win_uwp::invoke_uwptools())
+    pub async fn set_uwp_loopback(package: String, enabled: bool) -> CmdResult {
+        let package = package.trim().to_string();
+        if !win_uwp::is_package_family_name(&package) {
+            return Err(CmdError::new(
+                ErrorCode::InvalidArgument,
+                format!("包系列名无效: {package}"),
+            ));
+        }
+
+        let target = package.clone();
+        AsyncHandler::spawn_blocking(move || {
+            win_uwp::set_loopback(&target, enabled)?;
+            win_uwp::is_exempted(&target)
+        })
+        .await
+        .map_err(|e| format!("设置回环豁免任务失败: {e}"))?
+        .map_err(|e| format!("设置回环豁免失败: {e}"))
+        .and_then(|exempted| {
+            if exempted == enabled {
+                Ok(())
+            } else {
+                Err("回环豁免状态未改变，可能已取消授权".to_string())
+            }
+        })?;
+
+        logging!(
+            info,
+            Type::Cmd,
+            true,
+            "[UWP] {} 回环豁免: {}",
+            package,
+            enabled
+        );
+        Ok(())
     }
 }
 
 /// Stub implementation for non-Windows platforms
 #[cfg(not(windows))]
 mod platform {
-    use super::CmdResult;
+    use super::{CmdError, CmdResult, ErrorCode, UwpApp};
 
-    pub fn invoke_uwp_tool() -> CmdResult {
-        Ok(())
+    pub async fn list_uwp_apps() -> CmdResult<Vec<UwpApp>> {
+        Ok(Vec::new())
+    }
+
+    pub async fn set_uwp_loopback(_package: String, _enabled: bool) -> CmdResult {
+        Err(CmdError::new(
+            ErrorCode::InvalidArgument,
+            "UWP 回环豁免仅支持 Windows",
+        ))
     }
 }
 
-/// Command exposed to Tauri
+/// 列出 UWP 应用及其回环豁免状态
+#[tauri::command]
+pub async fn list_uwp_apps() -> CmdResult<Vec<UwpApp>> {
+    platform::list_uwp_apps().await
+}
+
+/// 为指定包系列名添加或移除回环豁免，允许其访问本地代理
 #[tauri::command]
-pub async fn invoke_uwp_tool() -> CmdResult {
-    platform::invoke_uwp_tool()
+pub async fn set_uwp_loopback(package: String, enabled: bool) -> CmdResult {
+    platform::set_uwp_loopback(package, enabled).await
 }
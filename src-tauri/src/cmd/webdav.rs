@@ -1,10 +1,17 @@
 use super::CmdResult;
-use crate::{config::*, core, feat, wrap_err};
+use crate::{config::*, core, feat, utils::secrets, wrap_err};
 use reqwest_dav::list_cmd::ListFile;
 
 /// 保存 WebDAV 配置
 #[tauri::command]
 pub async fn save_webdav_config(url: String, username: String, password: String) -> CmdResult<()> {
+    // 密码保存到系统钥匙串，配置中只记录引用
+    let password = if password.is_empty() {
+        let _ = secrets::delete_secret(secrets::WEBDAV_PASSWORD);
+        password
+    } else {
+        secrets::protect_secret(secrets::WEBDAV_PASSWORD, password)
+    };
     let patch = IVerge {
         webdav_url: Some(url),
         webdav_username: Some(username),
@@ -1,5 +1,6 @@
 use super::{Draft, IClashTemp, IProfiles, IRuntime, IVerge};
 use crate::{
+    cmd::{self, StartupFailureStage},
     config::{PrfItem, profiles_append_item_safe},
     core::{CoreManager, handle},
     enhance, logging,
@@ -72,16 +73,29 @@ impl Config {
             profiles_append_item_safe(script_item.clone()).await?;
         }
         // 生成运行时配置
-        if let Err(err) = Self::generate().await {
-            logging!(error, Type::Config, true, "生成运行时配置失败: {}", err);
-        } else {
-            logging!(info, Type::Config, true, "生成运行时配置成功");
-        }
+        let generate_error = match Self::generate().await {
+            Ok(()) => {
+                logging!(info, Type::Config, true, "生成运行时配置成功");
+                None
+            }
+            Err(err) => {
+                logging!(error, Type::Config, true, "生成运行时配置失败: {}", err);
+                Some(err.to_string())
+            }
+        };
 
         // 生成运行时配置文件并验证
         let config_result = Self::generate_file(ConfigType::Run).await;
 
-        let validation_result = if config_result.is_ok() {
+        let validation_result = if let Err(err) = &config_result {
+            logging!(warn, Type::Config, true, "生成配置文件失败，使用默认配置");
+            let error_msg = generate_error.unwrap_or_else(|| err.to_string());
+            cmd::record_startup_failure(StartupFailureStage::Generate, &error_msg).await;
+            CoreManager::global()
+                .use_default_config("config_validate::startup_recovery", &error_msg)
+                .await?;
+            Some(("config_validate::startup_recovery", error_msg))
+        } else {
             // 验证配置文件
             logging!(info, Type::Config, true, "开始验证配置");
 
@@ -95,10 +109,12 @@ impl Config {
                             "[首次启动] 配置验证失败，使用默认最小配置启动: {}",
                             error_msg
                         );
+                        cmd::record_startup_failure(StartupFailureStage::Validate, &error_msg)
+                            .await;
                         CoreManager::global()
-                            .use_default_config("config_validate::boot_error", &error_msg)
+                            .use_default_config("config_validate::startup_recovery", &error_msg)
                             .await?;
-                        Some(("config_validate::boot_error", error_msg))
+                        Some(("config_validate::startup_recovery", error_msg))
                     } else {
                         logging!(info, Type::Config, true, "配置验证成功");
                         Some(("config_validate::success", String::new()))
@@ -112,12 +128,6 @@ impl Config {
                     Some(("config_validate::process_terminated", String::new()))
                 }
             }
-        } else {
-            logging!(warn, Type::Config, true, "生成配置文件失败，使用默认配置");
-            CoreManager::global()
-                .use_default_config("config_validate::error", "")
-                .await?;
-            Some(("config_validate::error", String::new()))
         };
 
         // 在单独的任务中发送通知
@@ -145,7 +155,12 @@ impl Config {
             .clone();
         drop(runtime); // 显式释放锁
 
+        let started_at = std::time::Instant::now();
         help::save_yaml(&path, &config, Some("# Generated by Liebesu_Clash")).await?;
+        if matches!(typ, ConfigType::Run) {
+            let bytes = tokio::fs::metadata(&path).await.map_or(0, |m| m.len());
+            enhance::metrics::record_serialization(started_at.elapsed(), bytes);
+        }
         Ok(path)
     }
 
@@ -1,7 +1,12 @@
-use crate::utils::{
-    dirs, help,
-    network::{NetworkManager, ProxyType},
-    tmpl,
+use crate::{
+    core::profile_watcher::ProfileWatcher,
+    utils::{
+        dirs,
+        fetcher::{self, FetchResponse},
+        help,
+        network::ProxyType,
+        tmpl,
+    },
 };
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
@@ -90,8 +95,8 @@ pub struct PrfOption {
     pub update_interval: Option<u64>,
 
     /// for `remote` profile
-    /// HTTP request timeout in seconds
-    /// default is 60 seconds
+    /// HTTP request timeout in seconds, per attempt
+    /// defaults to the global fetch retry policy
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_seconds: Option<u64>,
 
@@ -101,6 +106,17 @@ pub struct PrfOption {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub danger_accept_invalid_certs: Option<bool>,
 
+    /// for `remote` profile
+    /// download routes tried in order until one succeeds,
+    /// overrides `with_proxy` and `self_proxy` when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_policy: Option<Vec<FetchRoute>>,
+
+    /// for `merge` and `script` item
+    /// top-level keys the item reads and writes, enables parallel enhance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_manifest: Option<ChainManifest>,
+
     pub merge: Option<String>,
 
     pub script: Option<String>,
@@ -129,11 +145,79 @@ impl PrfOption {
                 a.proxies = b.proxies.or(a.proxies);
                 a.groups = b.groups.or(a.groups);
                 a.timeout_seconds = b.timeout_seconds.or(a.timeout_seconds);
+                a.fetch_policy = b.fetch_policy.or(a.fetch_policy);
+                a.chain_manifest = b.chain_manifest.or(a.chain_manifest);
                 Some(a)
             }
             t => t.0.or(t.1),
         }
     }
+
+    /// 按顺序尝试的下载路径，未配置拉取策略时由 `with_proxy` / `self_proxy` 决定
+    pub fn fetch_routes(option: Option<&Self>) -> Vec<FetchRoute> {
+        if let Some(policy) = option
+            .and_then(|o| o.fetch_policy.clone())
+            .filter(|policy| !policy.is_empty())
+        {
+            return policy;
+        }
+
+        let with_proxy = option.is_some_and(|o| o.with_proxy.unwrap_or(false));
+        let self_proxy = option.is_some_and(|o| o.self_proxy.unwrap_or(false));
+        let route = if self_proxy {
+            FetchRoute::Clash
+        } else if with_proxy {
+            FetchRoute::System
+        } else {
+            FetchRoute::Direct
+        };
+        vec![route]
+    }
+
+    /// 是否配置了自定义拉取策略
+    pub fn has_fetch_policy(option: Option<&Self>) -> bool {
+        option
+            .and_then(|o| o.fetch_policy.as_ref())
+            .is_some_and(|policy| !policy.is_empty())
+    }
+}
+
+/// 远程订阅的下载路径
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "node", rename_all = "snake_case")]
+pub enum FetchRoute {
+    /// 直连
+    Direct,
+    /// 系统代理
+    System,
+    /// 当前 Clash 代理（混合端口）
+    Clash,
+    /// 经由指定节点，由内核为该节点开放的本地监听端口转发
+    Node(String),
+}
+
+impl FetchRoute {
+    /// 对应的请求代理方式，指定节点在当前配置中不可用时返回 None
+    pub fn proxy_type(&self) -> Option<ProxyType> {
+        match self {
+            Self::Direct => Some(ProxyType::None),
+            Self::System => Some(ProxyType::System),
+            Self::Clash => Some(ProxyType::Localhost),
+            Self::Node(node) => {
+                crate::enhance::fetch_listener::listener_port(node).map(ProxyType::LocalPort)
+            }
+        }
+    }
+}
+
+/// 增强项（Merge / Script）的作用范围声明
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChainManifest {
+    /// 读取和修改的顶层键
+    pub keys: Vec<String>,
+    /// 依赖的其它增强项 uid，声明后整条增强链按顺序执行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
 }
 
 impl PrfItem {
@@ -242,38 +326,28 @@ impl PrfItem {
         option: Option<PrfOption>,
     ) -> Result<PrfItem> {
         let opt_ref = option.as_ref();
-        let with_proxy = opt_ref.is_some_and(|o| o.with_proxy.unwrap_or(false));
-        let self_proxy = opt_ref.is_some_and(|o| o.self_proxy.unwrap_or(false));
+        let routes = PrfOption::fetch_routes(opt_ref);
+        let fetch_policy = opt_ref.and_then(|o| o.fetch_policy.clone());
         let accept_invalid_certs =
             opt_ref.is_some_and(|o| o.danger_accept_invalid_certs.unwrap_or(false));
         let user_agent = opt_ref.and_then(|o| o.user_agent.clone());
         let update_interval = opt_ref.and_then(|o| o.update_interval);
-        let timeout = opt_ref.and_then(|o| o.timeout_seconds).unwrap_or(20);
+        let timeout = opt_ref.and_then(|o| o.timeout_seconds);
         let mut merge = opt_ref.and_then(|o| o.merge.clone());
         let mut script = opt_ref.and_then(|o| o.script.clone());
         let mut rules = opt_ref.and_then(|o| o.rules.clone());
         let mut proxies = opt_ref.and_then(|o| o.proxies.clone());
         let mut groups = opt_ref.and_then(|o| o.groups.clone());
 
-        // 选择代理类型
-        let proxy_type = if self_proxy {
-            ProxyType::Localhost
-        } else if with_proxy {
-            ProxyType::System
-        } else {
-            ProxyType::None
-        };
-
-        // 使用网络管理器发送请求
-        let resp = match NetworkManager::new()
-            .get_with_interrupt(
-                url,
-                proxy_type,
-                Some(timeout),
-                user_agent.clone(),
-                accept_invalid_certs,
-            )
-            .await
+        // 按拉取策略依次尝试各下载路径
+        let resp = match fetch_with_routes(
+            url,
+            &routes,
+            timeout,
+            user_agent.clone(),
+            accept_invalid_certs,
+        )
+        .await
         {
             Ok(r) => r,
             Err(e) => {
@@ -399,6 +473,7 @@ impl PrfItem {
             extra,
             option: Some(PrfOption {
                 update_interval,
+                fetch_policy,
                 merge,
                 script,
                 rules,
@@ -544,6 +619,45 @@ impl PrfItem {
             .clone()
             .ok_or_else(|| anyhow::anyhow!("could not find the file"))?;
         let path = dirs::app_profiles_dir()?.join(file);
+        ProfileWatcher::global().mark_internal_write(&path, data.as_bytes());
         fs::write(path, data.as_bytes()).context("failed to save the file")
     }
 }
+
+/// 按顺序尝试各下载路径，返回第一个成功的响应
+/// 最后一个路径的响应即使状态码异常也原样返回，由调用方报告具体状态
+async fn fetch_with_routes(
+    url: &str,
+    routes: &[FetchRoute],
+    timeout: Option<u64>,
+    user_agent: Option<String>,
+    accept_invalid_certs: bool,
+) -> Result<FetchResponse> {
+    let mut errors = Vec::new();
+    for (index, route) in routes.iter().enumerate() {
+        let Some(proxy_type) = route.proxy_type() else {
+            errors.push(format!("{route:?}: node listener unavailable"));
+            continue;
+        };
+
+        match fetcher::fetch(
+            url,
+            proxy_type,
+            user_agent.clone(),
+            accept_invalid_certs,
+            timeout,
+        )
+        .await
+        {
+            Ok(resp) if resp.status().is_success() || index + 1 == routes.len() => {
+                return Ok(resp);
+            }
+            Ok(resp) => errors.push(format!("{route:?}: status {}", resp.status())),
+            Err(e) => errors.push(format!("{route:?}: {e}")),
+        }
+        if index + 1 < routes.len() {
+            log::warn!(target: "app", "[订阅下载] 路径 {route:?} 失败，尝试下一个");
+        }
+    }
+    bail!("{}", errors.join("; "))
+}
@@ -1,5 +1,6 @@
 use super::{PrfOption, prfitem::PrfItem};
 use crate::{
+    core::profile_watcher::ProfileWatcher,
     logging_error,
     process::AsyncHandler,
     utils::{dirs, help, logging::Type},
@@ -145,6 +146,7 @@ impl IProfiles {
             })?;
             let path = dirs::app_profiles_dir()?.join(&file);
 
+            ProfileWatcher::global().mark_internal_write(&path, file_data.as_bytes());
             fs::write(&path, file_data.as_bytes())
                 .await
                 .with_context(|| format!("failed to write to file \"{file}\""))?;
@@ -248,6 +250,7 @@ impl IProfiles {
 
                         let path = dirs::app_profiles_dir()?.join(&file);
 
+                        ProfileWatcher::global().mark_internal_write(&path, file_data.as_bytes());
                         fs::write(&path, file_data.as_bytes())
                             .await
                             .with_context(|| format!("failed to write to file \"{file}\""))?;
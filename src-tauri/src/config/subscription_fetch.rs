@@ -16,6 +16,8 @@ pub struct RemoteSubscriptionConfig {
     pub custom_interval_minutes: Option<u64>,
     pub last_sync_at: Option<i64>,
     pub last_result: Option<FetchSummary>,
+    /// 单次下载的超时（秒），订阅自身设置了超时则以订阅为准
+    pub fetch_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -9,6 +9,7 @@ use crate::{
 use anyhow::Result;
 use log::LevelFilter;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// ### `verge.yaml` schema
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -141,6 +142,12 @@ pub struct IVerge {
     /// 0: 不清理; 1: 1天；2: 7天; 3: 30天; 4: 90天
     pub auto_log_clean: Option<i32>,
 
+    /// 日志配置档：interactive | server
+    pub logging_profile: Option<String>,
+
+    /// server 模式下日志目录的大小上限（MB）
+    pub log_dir_max_size: Option<u64>,
+
     /// verge 的各种 port 用于覆盖 clash 的各种 port
     #[cfg(not(target_os = "windows"))]
     pub verge_redir_port: Option<u16>,
@@ -195,12 +202,27 @@ pub struct IVerge {
 
     pub enable_tray_icon: Option<bool>,
 
+    /// 托盘实时状态显示项：speed | node | ip，为空时不显示
+    pub tray_stats_items: Option<Vec<String>>,
+
+    /// 托盘实时状态同时显示在菜单栏标题中（macOS / Linux）
+    pub tray_stats_in_title: Option<bool>,
+
     /// 自动进入轻量模式
     pub enable_auto_light_weight_mode: Option<bool>,
 
     /// 自动进入轻量模式的延迟（分钟）
     pub auto_light_weight_minutes: Option<u64>,
 
+    /// 轻量模式下点击托盘图标时退出轻量模式
+    pub light_weight_exit_on_tray: Option<bool>,
+
+    /// 轻量模式下按显示窗口类快捷键时退出轻量模式
+    pub light_weight_exit_on_hotkey: Option<bool>,
+
+    /// 进入轻量模式时销毁窗口释放 WebView，关闭时仅隐藏窗口
+    pub light_weight_unload_webview: Option<bool>,
+
     /// 启用代理页面自动滚动
     pub enable_hover_jump_navigator: Option<bool>,
 
@@ -212,6 +234,25 @@ pub struct IVerge {
 
     /// 远程订阅拉取配置
     pub subscription_fetch: Option<RemoteSubscriptionConfig>,
+    /// 系统从睡眠中恢复后自动重启内核
+    pub auto_restart_core_on_resume: Option<bool>,
+    /// 启动前检测到端口冲突时的处理方式：report 仅报告 | remap 自动换用空闲端口
+    pub port_conflict_strategy: Option<String>,
+    /// 检测到强制门户时的处理方式：off | notify | disable_sysproxy | direct
+    pub captive_portal_policy: Option<String>,
+    /// 远程备份存储：webdav | s3
+    pub remote_backup_provider: Option<String>,
+    /// S3 兼容存储备份配置 (加密存储)
+    #[serde(
+        serialize_with = "serialize_encrypted",
+        deserialize_with = "deserialize_encrypted",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub s3_backup: Option<IS3Backup>,
+
+    /// 代理组显示偏好，按分组名索引
+    pub proxy_group_preferences: Option<HashMap<String, IProxyGroupPreference>>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -222,6 +263,42 @@ pub struct IVergeTestItem {
     pub url: Option<String>,
 }
 
+/// S3 兼容存储（AWS S3 / Cloudflare R2 / Backblaze B2）
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+pub struct IS3Backup {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// 存储路径前缀，默认使用备份目录名
+    pub prefix: Option<String>,
+    /// 使用路径风格访问（R2、B2 等需要）
+    pub path_style: Option<bool>,
+}
+
+/// 代理组内节点排序方式
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IProxyGroupSort {
+    /// 保持配置文件中的顺序
+    #[default]
+    Default,
+    Latency,
+    Name,
+}
+
+/// 单个代理组的显示偏好，由 get_proxies 在返回前统一应用
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct IProxyGroupPreference {
+    /// 在前端和托盘中隐藏该分组
+    pub hidden: bool,
+    pub sort_by: IProxyGroupSort,
+    /// 收藏节点排在最前
+    pub pin_favorites: bool,
+}
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
 pub struct IVergeTheme {
     pub primary_color: Option<String>,
@@ -412,10 +489,16 @@ impl IVerge {
             enable_global_hotkey: Some(true),
             enable_auto_light_weight_mode: Some(false),
             auto_light_weight_minutes: Some(10),
+            light_weight_exit_on_tray: Some(true),
+            light_weight_exit_on_hotkey: Some(true),
+            light_weight_unload_webview: Some(true),
             enable_dns_settings: Some(false),
             home_cards: None,
             service_state: None,
             enable_external_controller: Some(false),
+            auto_restart_core_on_resume: Some(false),
+            port_conflict_strategy: Some("report".into()),
+            captive_portal_policy: Some("notify".into()),
             ..Self::default()
         }
     }
@@ -492,6 +575,8 @@ impl IVerge {
         patch!(proxy_layout_column);
         patch!(test_list);
         patch!(auto_log_clean);
+        patch!(logging_profile);
+        patch!(log_dir_max_size);
         patch!(subscription_fetch);
 
         patch!(webdav_url);
@@ -499,12 +584,23 @@ impl IVerge {
         patch!(webdav_password);
         patch!(enable_tray_speed);
         patch!(enable_tray_icon);
+        patch!(tray_stats_items);
+        patch!(tray_stats_in_title);
         patch!(enable_auto_light_weight_mode);
         patch!(auto_light_weight_minutes);
+        patch!(light_weight_exit_on_tray);
+        patch!(light_weight_exit_on_hotkey);
+        patch!(light_weight_unload_webview);
         patch!(enable_dns_settings);
         patch!(home_cards);
         patch!(service_state);
         patch!(enable_external_controller);
+        patch!(auto_restart_core_on_resume);
+        patch!(port_conflict_strategy);
+        patch!(captive_portal_policy);
+        patch!(remote_backup_provider);
+        patch!(s3_backup);
+        patch!(proxy_group_preferences);
     }
 
     /// 在初始化前尝试拿到单例端口的值
@@ -576,6 +672,8 @@ pub struct IVergeResponse {
     pub proxy_layout_column: Option<i32>,
     pub test_list: Option<Vec<IVergeTestItem>>,
     pub auto_log_clean: Option<i32>,
+    pub logging_profile: Option<String>,
+    pub log_dir_max_size: Option<u64>,
     #[cfg(not(target_os = "windows"))]
     pub verge_redir_port: Option<u16>,
     #[cfg(not(target_os = "windows"))]
@@ -594,13 +692,24 @@ pub struct IVergeResponse {
     pub webdav_password: Option<String>,
     pub enable_tray_speed: Option<bool>,
     pub enable_tray_icon: Option<bool>,
+    pub tray_stats_items: Option<Vec<String>>,
+    pub tray_stats_in_title: Option<bool>,
     pub enable_auto_light_weight_mode: Option<bool>,
     pub auto_light_weight_minutes: Option<u64>,
+    pub light_weight_exit_on_tray: Option<bool>,
+    pub light_weight_exit_on_hotkey: Option<bool>,
+    pub light_weight_unload_webview: Option<bool>,
     pub enable_dns_settings: Option<bool>,
     pub home_cards: Option<serde_json::Value>,
     pub enable_hover_jump_navigator: Option<bool>,
     pub enable_external_controller: Option<bool>,
     pub service_state: Option<crate::core::service::ServiceState>,
+    pub auto_restart_core_on_resume: Option<bool>,
+    pub port_conflict_strategy: Option<String>,
+    pub captive_portal_policy: Option<String>,
+    pub remote_backup_provider: Option<String>,
+    pub s3_backup: Option<IS3Backup>,
+    pub proxy_group_preferences: Option<HashMap<String, IProxyGroupPreference>>,
 }
 
 impl From<IVerge> for IVergeResponse {
@@ -648,6 +757,8 @@ impl From<IVerge> for IVergeResponse {
             proxy_layout_column: verge.proxy_layout_column,
             test_list: verge.test_list,
             auto_log_clean: verge.auto_log_clean,
+            logging_profile: verge.logging_profile,
+            log_dir_max_size: verge.log_dir_max_size,
             #[cfg(not(target_os = "windows"))]
             verge_redir_port: verge.verge_redir_port,
             #[cfg(not(target_os = "windows"))]
@@ -666,13 +777,24 @@ impl From<IVerge> for IVergeResponse {
             webdav_password: verge.webdav_password,
             enable_tray_speed: verge.enable_tray_speed,
             enable_tray_icon: verge.enable_tray_icon,
+            tray_stats_items: verge.tray_stats_items,
+            tray_stats_in_title: verge.tray_stats_in_title,
             enable_auto_light_weight_mode: verge.enable_auto_light_weight_mode,
             auto_light_weight_minutes: verge.auto_light_weight_minutes,
+            light_weight_exit_on_tray: verge.light_weight_exit_on_tray,
+            light_weight_exit_on_hotkey: verge.light_weight_exit_on_hotkey,
+            light_weight_unload_webview: verge.light_weight_unload_webview,
             enable_dns_settings: verge.enable_dns_settings,
             home_cards: verge.home_cards,
             enable_hover_jump_navigator: verge.enable_hover_jump_navigator,
             enable_external_controller: verge.enable_external_controller,
             service_state: verge.service_state,
+            auto_restart_core_on_resume: verge.auto_restart_core_on_resume,
+            port_conflict_strategy: verge.port_conflict_strategy,
+            captive_portal_policy: verge.captive_portal_policy,
+            remote_backup_provider: verge.remote_backup_provider,
+            s3_backup: verge.s3_backup,
+            proxy_group_preferences: verge.proxy_group_preferences,
         }
     }
 }
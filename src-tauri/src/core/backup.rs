@@ -1,8 +1,15 @@
-use crate::{config::Config, utils::dirs};
+use crate::{
+    config::{Config, IS3Backup},
+    utils::{dirs, secrets},
+};
 use anyhow::Error;
+use futures::{FutureExt, future::BoxFuture};
+use hmac::{Hmac, Mac};
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use reqwest_dav::list_cmd::{ListEntity, ListFile};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
     collections::HashMap,
     env::{consts::OS, temp_dir},
@@ -23,6 +30,59 @@ const TIMEOUT_DOWNLOAD: u64 = 300; // 下载超时 5 分钟
 const TIMEOUT_LIST: u64 = 3; // 列表超时 30 秒
 const TIMEOUT_DELETE: u64 = 3; // 删除超时 30 秒
 
+/// 远程备份存储类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupProvider {
+    #[default]
+    WebDav,
+    S3,
+}
+
+impl BackupProvider {
+    /// 读取 verge 配置中选择的远程备份存储
+    pub async fn current() -> Self {
+        match Config::verge()
+            .await
+            .latest_ref()
+            .remote_backup_provider
+            .as_deref()
+        {
+            Some("s3") => Self::S3,
+            _ => Self::WebDav,
+        }
+    }
+
+    /// 获取对应的存储实现
+    pub fn storage(self) -> &'static dyn BackupStorage {
+        match self {
+            Self::WebDav => WebDavClient::global(),
+            Self::S3 => S3Client::global(),
+        }
+    }
+}
+
+/// 远程备份文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteBackupFile {
+    pub name: String,
+    pub size: u64,
+    pub last_modified: Option<String>,
+}
+
+/// 远程备份存储，WebDAV 与 S3 共用同一套上传、下载流程
+pub trait BackupStorage: Send + Sync {
+    fn upload(&self, file_path: PathBuf, file_name: String) -> BoxFuture<'_, Result<(), Error>>;
+    fn download(
+        &self,
+        file_name: String,
+        storage_path: PathBuf,
+    ) -> BoxFuture<'_, Result<(), Error>>;
+    fn list_files(&self) -> BoxFuture<'_, Result<Vec<RemoteBackupFile>, Error>>;
+    fn delete(&self, file_name: String) -> BoxFuture<'_, Result<(), Error>>;
+    fn reset(&self);
+}
+
 #[derive(Clone)]
 struct WebDavConfig {
     url: String,
@@ -97,7 +157,7 @@ impl WebDavClient {
                         .trim_end_matches('/')
                         .to_string(),
                     username: verge.webdav_username.unwrap_or_default(),
-                    password: verge.webdav_password.unwrap_or_default(),
+                    password: secrets::resolve_secret(&verge.webdav_password.unwrap_or_default())?,
                 };
 
                 // 重新获取锁并存储配置
@@ -235,15 +295,443 @@ impl WebDavClient {
     }
 }
 
+impl BackupStorage for WebDavClient {
+    fn upload(&self, file_path: PathBuf, file_name: String) -> BoxFuture<'_, Result<(), Error>> {
+        WebDavClient::upload(self, file_path, file_name).boxed()
+    }
+
+    fn download(
+        &self,
+        file_name: String,
+        storage_path: PathBuf,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        WebDavClient::download(self, file_name, storage_path).boxed()
+    }
+
+    fn list_files(&self) -> BoxFuture<'_, Result<Vec<RemoteBackupFile>, Error>> {
+        async move {
+            let files = self.list().await?;
+            Ok(files
+                .into_iter()
+                .map(|file| RemoteBackupFile {
+                    name: file
+                        .href
+                        .trim_end_matches('/')
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    size: file.content_length.max(0) as u64,
+                    last_modified: Some(file.last_modified.to_rfc3339()),
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn delete(&self, file_name: String) -> BoxFuture<'_, Result<(), Error>> {
+        WebDavClient::delete(self, file_name).boxed()
+    }
+
+    fn reset(&self) {
+        WebDavClient::reset(self);
+    }
+}
+
+/// S3 兼容存储客户端（AWS S3 / Cloudflare R2 / Backblaze B2），使用 SigV4 签名
+pub struct S3Client {
+    config: Mutex<Option<IS3Backup>>,
+    client: OnceCell<reqwest::Client>,
+}
+
+impl S3Client {
+    pub fn global() -> &'static S3Client {
+        static S3_CLIENT: OnceCell<S3Client> = OnceCell::new();
+        S3_CLIENT.get_or_init(|| S3Client {
+            config: Mutex::new(None),
+            client: OnceCell::new(),
+        })
+    }
+
+    async fn get_config(&self) -> Result<IS3Backup, Error> {
+        if let Some(config) = self.config.lock().clone() {
+            return Ok(config);
+        }
+
+        let mut config = Config::verge()
+            .await
+            .latest_ref()
+            .s3_backup
+            .clone()
+            .filter(|c| {
+                !c.endpoint.is_empty()
+                    && !c.bucket.is_empty()
+                    && !c.access_key_id.is_empty()
+                    && !c.secret_access_key.is_empty()
+            })
+            .ok_or_else(|| {
+                anyhow::Error::msg(
+                    "Unable to create S3 client, please make sure the S3 config is correct",
+                )
+            })?;
+        config.secret_access_key = secrets::resolve_secret(&config.secret_access_key)?;
+        *self.config.lock() = Some(config.clone());
+        Ok(config)
+    }
+
+    fn http_client(&self) -> Result<&reqwest::Client, Error> {
+        self.client.get_or_try_init(|| {
+            Ok(reqwest::Client::builder()
+                .timeout(Duration::from_secs(TIMEOUT_UPLOAD))
+                .user_agent(format!("clash-verge/{APP_VERSION} ({OS} S3-Client)"))
+                .build()?)
+        })
+    }
+
+    /// 发送签名请求，key 为对象相对路径，None 表示存储桶本身
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: Option<&str>,
+        query: &[(&str, String)],
+        body: Vec<u8>,
+        op: Operation,
+    ) -> Result<reqwest::Response, Error> {
+        let config = self.get_config().await?;
+        let url = s3_url(&config, key, query)?;
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sign_v4(
+            &config,
+            method.as_str(),
+            &url,
+            &[
+                ("x-amz-content-sha256", payload_hash.clone()),
+                ("x-amz-date", amz_date.clone()),
+            ],
+            &payload_hash,
+            &amz_date,
+        )?;
+
+        let request = self
+            .http_client()?
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body)
+            .timeout(Duration::from_secs(op.timeout()));
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("S3 request failed: {status} {text}"));
+        }
+        Ok(response)
+    }
+
+    async fn object_key(&self, file_name: &str) -> Result<String, Error> {
+        Ok(format!(
+            "{}{}",
+            s3_prefix(&self.get_config().await?),
+            file_name
+        ))
+    }
+}
+
+impl BackupStorage for S3Client {
+    fn upload(&self, file_path: PathBuf, file_name: String) -> BoxFuture<'_, Result<(), Error>> {
+        async move {
+            let key = self.object_key(&file_name).await?;
+            let content = fs::read(&file_path)?;
+            self.send(
+                reqwest::Method::PUT,
+                Some(&key),
+                &[],
+                content,
+                Operation::Upload,
+            )
+            .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn download(
+        &self,
+        file_name: String,
+        storage_path: PathBuf,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        async move {
+            let key = self.object_key(&file_name).await?;
+            let response = self
+                .send(
+                    reqwest::Method::GET,
+                    Some(&key),
+                    &[],
+                    Vec::new(),
+                    Operation::Download,
+                )
+                .await?;
+            fs::write(&storage_path, response.bytes().await?)?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn list_files(&self) -> BoxFuture<'_, Result<Vec<RemoteBackupFile>, Error>> {
+        async move {
+            let prefix = s3_prefix(&self.get_config().await?);
+            let mut files = Vec::new();
+            let mut continuation: Option<String> = None;
+
+            loop {
+                let mut query = vec![("list-type", "2".to_string()), ("prefix", prefix.clone())];
+                if let Some(token) = continuation.take() {
+                    query.push(("continuation-token", token));
+                }
+                let body = self
+                    .send(
+                        reqwest::Method::GET,
+                        None,
+                        &query,
+                        Vec::new(),
+                        Operation::List,
+                    )
+                    .await?
+                    .text()
+                    .await?;
+
+                for contents in xml_elements(&body, "Contents") {
+                    let Some(key) = xml_elements(contents, "Key")
+                        .first()
+                        .map(|k| xml_unescape(k))
+                    else {
+                        continue;
+                    };
+                    let name = key.strip_prefix(&prefix).unwrap_or(&key).to_string();
+                    if name.is_empty() || name.contains('/') {
+                        continue;
+                    }
+                    files.push(RemoteBackupFile {
+                        name,
+                        size: xml_elements(contents, "Size")
+                            .first()
+                            .and_then(|s| s.parse().ok())
+                            .unwrap_or(0),
+                        last_modified: xml_elements(contents, "LastModified")
+                            .first()
+                            .map(|s| s.to_string()),
+                    });
+                }
+
+                let truncated = xml_elements(&body, "IsTruncated").first() == Some(&"true");
+                continuation = xml_elements(&body, "NextContinuationToken")
+                    .first()
+                    .map(|t| xml_unescape(t));
+                if !truncated || continuation.is_none() {
+                    break;
+                }
+            }
+
+            Ok(files)
+        }
+        .boxed()
+    }
+
+    fn delete(&self, file_name: String) -> BoxFuture<'_, Result<(), Error>> {
+        async move {
+            let key = self.object_key(&file_name).await?;
+            self.send(
+                reqwest::Method::DELETE,
+                Some(&key),
+                &[],
+                Vec::new(),
+                Operation::Delete,
+            )
+            .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn reset(&self) {
+        *self.config.lock() = None;
+    }
+}
+
+/// 对象路径前缀，以 `/` 结尾
+fn s3_prefix(config: &IS3Backup) -> String {
+    let prefix = config
+        .prefix
+        .as_deref()
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+        .unwrap_or(dirs::BACKUP_DIR);
+    format!("{prefix}/")
+}
+
+/// 构造请求地址，支持路径风格和虚拟主机风格
+fn s3_url(
+    config: &IS3Backup,
+    key: Option<&str>,
+    query: &[(&str, String)],
+) -> Result<url::Url, Error> {
+    let mut url = url::Url::parse(config.endpoint.trim_end_matches('/'))?;
+    let key = key.map(|k| uri_encode(k, false)).unwrap_or_default();
+    if config.path_style.unwrap_or(true) {
+        url.set_path(&format!("/{}/{}", config.bucket, key));
+    } else {
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid S3 endpoint"))?
+            .to_string();
+        url.set_host(Some(&format!("{}.{host}", config.bucket)))?;
+        url.set_path(&format!("/{key}"));
+    }
+
+    let mut query: Vec<(&str, String)> = query.to_vec();
+    query.sort();
+    let query = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", uri_encode(k, true), uri_encode(v, true)))
+        .collect::<Vec<_>>()
+        .join("&");
+    url.set_query((!query.is_empty()).then_some(query.as_str()));
+    Ok(url)
+}
+
+/// 计算 SigV4 Authorization 头
+/// url 的路径和查询参数需已按 SigV4 规则编码
+fn sign_v4(
+    config: &IS3Backup,
+    method: &str,
+    url: &url::Url,
+    headers: &[(&str, String)],
+    payload_hash: &str,
+    amz_date: &str,
+) -> Result<String, Error> {
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let mut headers: Vec<(String, String)> = headers
+        .iter()
+        .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+        .collect();
+    headers.push(("host".to_string(), host));
+    headers.sort();
+
+    let canonical_headers: String = headers.iter().map(|(k, v)| format!("{k}:{v}\n")).collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{method}\n{}\n{}\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+        url.path(),
+        url.query().unwrap_or_default()
+    );
+
+    let region = if config.region.is_empty() {
+        "auto"
+    } else {
+        config.region.as_str()
+    };
+    let date = &amz_date[..8];
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = format!("AWS4{}", config.secret_access_key).into_bytes();
+    for part in [date, region, "s3", "aws4_request", string_to_sign.as_str()] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key)?;
+        mac.update(part.as_bytes());
+        key = mac.finalize().into_bytes().to_vec();
+    }
+
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+        config.access_key_id,
+        hex::encode(key)
+    ))
+}
+
+/// SigV4 URI 编码，encode_slash 为 false 时保留路径分隔符
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// 提取 XML 中指定标签的内容（S3 列表响应结构简单，无需完整解析）
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut elements = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        elements.push(&rest[..end]);
+        rest = &rest[end + close.len()..];
+    }
+    elements
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
 pub fn create_backup() -> Result<(String, PathBuf), Error> {
+    create_backup_with(true, true)
+}
+
+/// 按内容打包备份：`profiles` 包含订阅文件及订阅列表，`settings` 包含内核与应用配置
+pub fn create_backup_with(profiles: bool, settings: bool) -> Result<(String, PathBuf), Error> {
+    if !profiles && !settings {
+        return Err(anyhow::anyhow!("No backup content selected"));
+    }
     let now = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
     let zip_file_name = format!("{OS}-backup-{now}.zip");
     let zip_path = temp_dir().join(&zip_file_name);
 
     let file = fs::File::create(&zip_path)?;
     let mut zip = zip::ZipWriter::new(file);
-    zip.add_directory("profiles/", SimpleFileOptions::default())?;
     let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    if profiles {
+        write_profiles(&mut zip, options)?;
+    }
+    if settings {
+        write_settings(&mut zip, options)?;
+    }
+    zip.finish()?;
+    Ok((zip_file_name, zip_path))
+}
+
+fn write_profiles(
+    zip: &mut zip::ZipWriter<fs::File>,
+    options: SimpleFileOptions,
+) -> Result<(), Error> {
+    zip.add_directory("profiles/", SimpleFileOptions::default())?;
     if let Ok(entries) = fs::read_dir(dirs::app_profiles_dir()?) {
         for entry in entries {
             let entry = entry?;
@@ -260,6 +748,15 @@ pub fn create_backup() -> Result<(String, PathBuf), Error> {
             }
         }
     }
+    zip.start_file(dirs::PROFILE_YAML, options)?;
+    zip.write_all(fs::read(dirs::profiles_path()?)?.as_slice())?;
+    Ok(())
+}
+
+fn write_settings(
+    zip: &mut zip::ZipWriter<fs::File>,
+    options: SimpleFileOptions,
+) -> Result<(), Error> {
     zip.start_file(dirs::CLASH_CONFIG, options)?;
     zip.write_all(fs::read(dirs::clash_path()?)?.as_slice())?;
 
@@ -269,12 +766,9 @@ pub fn create_backup() -> Result<(String, PathBuf), Error> {
         obj.remove("webdav_username");
         obj.remove("webdav_password");
         obj.remove("webdav_url");
+        obj.remove("s3_backup");
     }
     zip.start_file(dirs::VERGE_CONFIG, options)?;
     zip.write_all(serde_yaml_ng::to_string(&verge_config)?.as_bytes())?;
-
-    zip.start_file(dirs::PROFILE_YAML, options)?;
-    zip.write_all(fs::read(dirs::profiles_path()?)?.as_slice())?;
-    zip.finish()?;
-    Ok((zip_file_name, zip_path))
+    Ok(())
 }
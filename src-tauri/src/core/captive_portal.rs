@@ -0,0 +1,327 @@
+use crate::{
+    config::{Config, IVerge},
+    core::handle,
+    feat, logging,
+    process::AsyncHandler,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Result, bail};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, time::Duration};
+use tokio::sync::Mutex;
+
+/// 检测到强制门户时不做处理
+pub const POLICY_OFF: &str = "off";
+/// 仅通知用户
+pub const POLICY_NOTIFY: &str = "notify";
+/// 临时关闭系统代理，门户认证通过后恢复
+pub const POLICY_DISABLE_SYSPROXY: &str = "disable_sysproxy";
+/// 临时切换到直连模式，门户认证通过后恢复
+pub const POLICY_DIRECT: &str = "direct";
+pub const POLICIES: &[&str] = &[
+    POLICY_OFF,
+    POLICY_NOTIFY,
+    POLICY_DISABLE_SYSPROXY,
+    POLICY_DIRECT,
+];
+
+/// 探测地址，正常网络均返回 204；包含国内可直连的地址，避免被误判为门户
+const PROBE_URLS: &[&str] = &[
+    "http://connectivitycheck.gstatic.com/generate_204",
+    "http://cp.cloudflare.com/generate_204",
+    "http://connect.rom.miui.com/generate_204",
+];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 临时放行期间重新探测的间隔
+const RECHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// 临时放行的改动会写入配置，记录原状态以便应用异常退出后在下次启动时恢复
+const BYPASS_STATE_FILE: &str = "captive_portal_bypass.json";
+
+/// 临时放行前的状态，用于门户认证通过后恢复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BypassState {
+    /// 被关闭的系统代理，None 表示未改动
+    system_proxy: Option<bool>,
+    /// 切换前的代理模式，None 表示未改动
+    mode: Option<String>,
+}
+
+static BYPASS: Lazy<Mutex<Option<BypassState>>> = Lazy::new(|| Mutex::new(None));
+
+/// 探测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// 任一地址返回 204
+    Online,
+    /// 有响应但不是 204，附带跳转地址或状态码
+    Portal(String),
+    /// 所有地址均无响应，可能处于离线状态
+    Unreachable,
+}
+
+/// 并发探测所有地址，任一返回 204 即视为网络正常
+pub async fn probe() -> ProbeOutcome {
+    let Ok(client) = reqwest::Client::builder()
+        .no_proxy()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(PROBE_TIMEOUT)
+        .build()
+    else {
+        return ProbeOutcome::Unreachable;
+    };
+
+    let results =
+        futures::future::join_all(PROBE_URLS.iter().map(|url| probe_url(&client, url))).await;
+    if results.iter().any(|r| *r == ProbeOutcome::Online) {
+        return ProbeOutcome::Online;
+    }
+    results
+        .into_iter()
+        .find(|r| matches!(r, ProbeOutcome::Portal(_)))
+        .unwrap_or(ProbeOutcome::Unreachable)
+}
+
+/// 检测到强制门户后按策略处理
+pub async fn handle_detected(detail: String) {
+    let policy = current_policy().await;
+    if policy == POLICY_OFF {
+        return;
+    }
+    handle::Handle::notice_message("captive_portal::detected", detail);
+    if policy == POLICY_NOTIFY {
+        return;
+    }
+
+    let mut bypass = BYPASS.lock().await;
+    if bypass.is_some() {
+        return;
+    }
+    match start_bypass(&policy).await {
+        Ok(state) => {
+            logging!(
+                info,
+                Type::Network,
+                true,
+                "[强制门户] 已临时放行: {:?}",
+                state
+            );
+            if let Err(e) = save_bypass(&state) {
+                logging!(
+                    warn,
+                    Type::Network,
+                    true,
+                    "[强制门户] 保存放行状态失败: {}",
+                    e
+                );
+            }
+            *bypass = Some(state);
+            AsyncHandler::spawn(|| async { wait_and_restore().await });
+        }
+        Err(e) => {
+            logging!(warn, Type::Network, true, "[强制门户] 临时放行失败: {}", e);
+        }
+    }
+}
+
+/// 启动时恢复上次未还原的临时放行，门户认证通过后还原原状态
+pub async fn resume_pending_bypass() {
+    let Some(state) = load_bypass() else {
+        return;
+    };
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[强制门户] 检测到未还原的临时放行: {:?}",
+        state
+    );
+    *BYPASS.lock().await = Some(state);
+    AsyncHandler::spawn(|| async { wait_and_restore().await });
+}
+
+/// 更新处理策略，不再需要临时放行时立即恢复原状态
+pub async fn set_policy(policy: &str) -> Result<()> {
+    if !POLICIES.contains(&policy) {
+        bail!("unknown captive portal policy: {policy}");
+    }
+    feat::patch_verge(
+        IVerge {
+            captive_portal_policy: Some(policy.into()),
+            ..IVerge::default()
+        },
+        false,
+    )
+    .await?;
+
+    let active = BYPASS.lock().await.clone();
+    let still_applies = match &active {
+        Some(state) if policy == POLICY_DISABLE_SYSPROXY => state.system_proxy.is_some(),
+        Some(state) if policy == POLICY_DIRECT => state.mode.is_some(),
+        _ => false,
+    };
+    if active.is_some() && !still_applies {
+        restore().await;
+    }
+    Ok(())
+}
+
+// ===== 内部实现函数 =====
+
+async fn current_policy() -> String {
+    Config::verge()
+        .await
+        .latest_ref()
+        .captive_portal_policy
+        .clone()
+        .unwrap_or_else(|| POLICY_NOTIFY.into())
+}
+
+async fn probe_url(client: &reqwest::Client, url: &str) -> ProbeOutcome {
+    let Ok(response) = client.get(url).send().await else {
+        return ProbeOutcome::Unreachable;
+    };
+    let status = response.status();
+    if status.as_u16() == 204 {
+        return ProbeOutcome::Online;
+    }
+
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    ProbeOutcome::Portal(location.unwrap_or_else(|| format!("HTTP {}", status.as_u16())))
+}
+
+/// 只改动当前处于生效状态的设置，并记录原值
+async fn start_bypass(policy: &str) -> Result<BypassState> {
+    let mut state = BypassState {
+        system_proxy: None,
+        mode: None,
+    };
+
+    if policy == POLICY_DISABLE_SYSPROXY {
+        let enabled = Config::verge()
+            .await
+            .latest_ref()
+            .enable_system_proxy
+            .unwrap_or(false);
+        if enabled {
+            set_system_proxy(false).await?;
+            state.system_proxy = Some(true);
+        }
+    } else if policy == POLICY_DIRECT {
+        let mode = current_mode().await;
+        if mode != "direct" {
+            feat::change_clash_mode("direct".into()).await;
+            state.mode = Some(mode);
+        }
+    }
+
+    if state.system_proxy.is_none() && state.mode.is_none() {
+        bail!("nothing to bypass under policy {policy}");
+    }
+    Ok(state)
+}
+
+/// 定期重新探测，门户认证通过后恢复原状态
+async fn wait_and_restore() {
+    loop {
+        tokio::time::sleep(RECHECK_INTERVAL).await;
+        if handle::Handle::global().is_exiting() || BYPASS.lock().await.is_none() {
+            return;
+        }
+        if probe().await == ProbeOutcome::Online {
+            restore().await;
+            return;
+        }
+    }
+}
+
+/// 恢复临时放行前的状态；期间用户已手动修改的设置保持不变
+async fn restore() {
+    let Some(state) = BYPASS.lock().await.take() else {
+        return;
+    };
+    if let Ok(path) = bypass_state_path()
+        && path.exists()
+        && let Err(e) = fs::remove_file(path)
+    {
+        logging!(
+            warn,
+            Type::Network,
+            true,
+            "[强制门户] 清理放行状态失败: {}",
+            e
+        );
+    }
+
+    if state.system_proxy == Some(true) {
+        let enabled = Config::verge()
+            .await
+            .latest_ref()
+            .enable_system_proxy
+            .unwrap_or(false);
+        if !enabled && let Err(e) = set_system_proxy(true).await {
+            logging!(
+                warn,
+                Type::Network,
+                true,
+                "[强制门户] 恢复系统代理失败: {}",
+                e
+            );
+        }
+    }
+    if let Some(mode) = state.mode
+        && current_mode().await == "direct"
+    {
+        feat::change_clash_mode(mode).await;
+    }
+
+    logging!(
+        info,
+        Type::Network,
+        true,
+        "[强制门户] 网络已恢复，已还原代理设置"
+    );
+    handle::Handle::notice_message("captive_portal::restored", "ok");
+}
+
+async fn set_system_proxy(enable: bool) -> Result<()> {
+    feat::patch_verge(
+        IVerge {
+            enable_system_proxy: Some(enable),
+            ..IVerge::default()
+        },
+        false,
+    )
+    .await?;
+    handle::Handle::refresh_verge();
+    Ok(())
+}
+
+async fn current_mode() -> String {
+    Config::clash()
+        .await
+        .latest_ref()
+        .0
+        .get("mode")
+        .and_then(|mode| mode.as_str())
+        .unwrap_or("rule")
+        .to_string()
+}
+
+fn bypass_state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(BYPASS_STATE_FILE))
+}
+
+fn save_bypass(state: &BypassState) -> Result<()> {
+    fs::write(bypass_state_path()?, serde_json::to_string(state)?)?;
+    Ok(())
+}
+
+fn load_bypass() -> Option<BypassState> {
+    let content = fs::read_to_string(bypass_state_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
@@ -13,14 +13,21 @@
 )]
 // TODO: 后续阶段逐条处理 CoreManager 相关的 Clippy 警告。
 use crate::{
+    cmd::{
+        CancelToken, core_registry,
+        event_publisher::{self, AutomationEvent},
+        port_overrides::{self, PortOverrides},
+    },
     config::*,
     core::{
         handle,
+        port_conflict::{self, PortConflict},
         service::{self},
         sysopt::Sysopt,
     },
     ipc::IpcManager,
     logging, logging_error,
+    module::offline::OfflineMode,
     process::AsyncHandler,
     singleton_lazy,
     utils::{
@@ -32,19 +39,85 @@ use crate::{
 use anyhow::Result;
 use chrono::Local;
 use parking_lot::Mutex;
+use port_scanner::local_port_available;
+use serde_json::json;
+use serde_yaml_ng::Mapping;
 use std::{
     fmt,
     fs::{File, create_dir_all},
     io::Write,
     path::PathBuf,
     sync::Arc,
+    time::{Duration, Instant},
 };
 use tauri_plugin_shell::{ShellExt, process::CommandChild};
 
+/// IPC 就绪探测的超时时间
+const IPC_READY_TIMEOUT: Duration = Duration::from_secs(10);
+const PROBE_INTERVAL: Duration = Duration::from_millis(300);
+/// 内核监听端口的等待时间
+const PORT_READY_TIMEOUT: Duration = Duration::from_secs(3);
+
 #[derive(Debug)]
 pub struct CoreManager {
     running: Arc<Mutex<RunningMode>>,
     child_sidecar: Arc<Mutex<Option<CommandChild>>>,
+    startup: Arc<Mutex<CoreStartupReport>>,
+    port_conflicts: Arc<Mutex<Vec<PortConflict>>>,
+    core_pid: Arc<Mutex<Option<u32>>>, // 最近以 Sidecar 启动的内核 PID，停止后保留以识别仍在退出的内核
+}
+
+/// 内核启动策略，按顺序尝试
+#[derive(Debug, Clone, Copy, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupStrategy {
+    Service,
+    Sidecar,
+    /// 端口被占用时换用空闲端口以 Sidecar 模式启动
+    AlternatePort,
+}
+
+/// 启动状态机
+#[derive(Debug, Clone, Copy, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupState {
+    Idle,
+    Starting,
+    Probing,
+    Ready,
+    Failed,
+}
+
+/// 启动过程中单个步骤的结果
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StartupStep {
+    pub strategy: StartupStrategy,
+    pub step: String, // start | ipc_probe | port_probe
+    pub success: bool,
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// 最近一次内核启动报告
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CoreStartupReport {
+    pub state: StartupState,
+    pub strategy: Option<StartupStrategy>,
+    pub steps: Vec<StartupStep>,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+}
+
+impl Default for CoreStartupReport {
+    fn default() -> Self {
+        Self {
+            state: StartupState::Idle,
+            strategy: None,
+            steps: Vec::new(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
 }
 
 /// 内核运行模式
@@ -281,9 +354,8 @@ impl CoreManager {
         logging!(info, Type::Config, true, "验证目录: {}", app_dir_str);
 
         // 使用子进程运行clash验证配置
-        let output = app_handle
-            .shell()
-            .sidecar(clash_core)?
+        let output = core_registry::core_command(&app_handle, &clash_core)
+            .await?
             .args(["-t", "-d", app_dir_str, "-f", config_path])
             .output()
             .await?;
@@ -398,6 +470,14 @@ impl CoreManager {
     }
     /// 更新proxies等配置
     pub async fn update_config(&self) -> Result<(bool, String)> {
+        self.update_config_with_cancel(None).await
+    }
+
+    /// 更新配置，在生成、验证和应用之间检查取消令牌，取消时丢弃生成的运行时配置
+    pub async fn update_config_with_cancel(
+        &self,
+        token: Option<&CancelToken>,
+    ) -> Result<(bool, String)> {
         // 检查程序是否正在退出，如果是则跳过完整验证流程
         if handle::Handle::global().is_exiting() {
             logging!(info, Type::Config, true, "应用正在退出，跳过验证");
@@ -409,15 +489,22 @@ impl CoreManager {
         // 1. 先生成新的配置内容
         logging!(info, Type::Config, true, "生成新的配置内容");
         Config::generate().await?;
+        discard_if_cancelled(token).await?;
 
         // 2. 验证配置
         match self.validate_config().await {
             Ok((true, _)) => {
                 logging!(info, Type::Config, true, "配置验证通过");
+                // 3. 应用前最后一次检查取消
+                discard_if_cancelled(token).await?;
                 // 4. 验证通过后，生成正式的运行时配置
                 logging!(info, Type::Config, true, "生成运行时配置");
                 let run_path = Config::generate_file(ConfigType::Run).await?;
-                logging_error!(Type::Config, true, self.put_configs_force(run_path).await);
+                let result = self.put_configs_force(run_path.clone()).await;
+                if result.is_ok() {
+                    OfflineMode::global().record_known_good(&run_path);
+                }
+                logging_error!(Type::Config, true, result);
                 Ok((true, "something".into()))
             }
             Ok((false, error_msg)) => {
@@ -786,9 +873,8 @@ impl CoreManager {
 
         let mut log_file = File::create(log_path)?;
 
-        let (mut rx, child) = app_handle
-            .shell()
-            .sidecar(&clash_core)?
+        let (mut rx, child) = core_registry::core_command(&app_handle, &clash_core)
+            .await?
             .args([
                 "-d",
                 dirs::path_to_str(&config_dir)?,
@@ -822,6 +908,7 @@ impl CoreManager {
             pid
         );
         *self.child_sidecar.lock() = Some(child);
+        *self.core_pid.lock() = Some(pid);
         self.set_running_mode(RunningMode::Sidecar);
         Ok(())
     }
@@ -865,6 +952,9 @@ impl Default for CoreManager {
         CoreManager {
             running: Arc::new(Mutex::new(RunningMode::NotRunning)),
             child_sidecar: Arc::new(Mutex::new(None)),
+            startup: Arc::new(Mutex::new(CoreStartupReport::default())),
+            port_conflicts: Arc::new(Mutex::new(Vec::new())),
+            core_pid: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -936,36 +1026,314 @@ impl CoreManager {
         (*guard).clone()
     }
 
-    /// 启动核心 - 简化版本,优先尝试服务模式,失败则回退到Sidecar模式
+    /// Sidecar 模式下由本程序启动的内核进程 PID，服务模式下为 None
+    pub fn sidecar_pid(&self) -> Option<u32> {
+        self.child_sidecar.lock().as_ref().map(|child| child.pid())
+    }
+
+    /// 启动核心：依次尝试服务模式、Sidecar 模式和备用端口，
+    /// 每种策略启动后探测 IPC 和端口是否就绪，未就绪则停止并换下一种策略
     pub async fn start_core(&self) -> Result<()> {
-        // 先尝试服务模式
-        if service::is_service_available().await.is_ok() {
-            logging!(info, Type::Core, true, "服务可用，尝试使用服务模式启动");
-            match self.start_core_by_service().await {
-                Ok(_) => {
-                    logging!(info, Type::Core, true, "服务模式启动成功");
+        // 内核版本可能已变化，重新探测接口能力
+        IpcManager::global().reset_capabilities();
+        let binary = core_registry::resolve_core_binary().await;
+        // 已下载的内核不交给服务启动，见 service::start_with_existing_service
+        let service_allowed = binary.as_ref().is_none_or(|core| core.bundled);
+        core_registry::record_running_binary(binary.map(|core| core.path));
+        *self.startup.lock() = CoreStartupReport {
+            state: StartupState::Starting,
+            started_at: Some(Local::now().timestamp()),
+            ..CoreStartupReport::default()
+        };
+        // 用户选择自动重映射且启动前已发现冲突时，先换用空闲端口启动
+        let remap_first = self.check_port_conflicts().await;
+
+        let mut strategies = Vec::new();
+        if remap_first {
+            strategies.push(StartupStrategy::AlternatePort);
+        }
+        if service_allowed
+            && !service::prefers_sidecar().await
+            && service::is_service_available().await.is_ok()
+        {
+            strategies.push(StartupStrategy::Service);
+        }
+        strategies.push(StartupStrategy::Sidecar);
+        if !remap_first {
+            strategies.push(StartupStrategy::AlternatePort);
+        }
+
+        let mut last_error = anyhow::anyhow!("没有可用的启动策略");
+        let mut port_conflict = remap_first;
+        for strategy in strategies {
+            // 仅在检测到端口冲突后才尝试备用端口
+            if strategy == StartupStrategy::AlternatePort && !port_conflict {
+                continue;
+            }
+            logging!(info, Type::Core, true, "尝试以 {:?} 策略启动内核", strategy);
+            self.set_startup_state(StartupState::Starting);
+
+            let started = Instant::now();
+            let result = match strategy {
+                StartupStrategy::Service => self.start_core_by_service().await,
+                StartupStrategy::Sidecar => self.start_core_by_sidecar().await,
+                StartupStrategy::AlternatePort => self.start_core_on_alternate_port().await,
+            };
+            self.record_step(strategy, "start", started, result.as_ref().err());
+            if let Err(e) = result {
+                logging!(warn, Type::Core, true, "{:?} 策略启动失败: {}", strategy, e);
+                // 出现失败时，清理可能残留的 unix socket，避免后续 Sidecar bind 失败
+                #[cfg(unix)]
+                if let Ok(ipc) = dirs::ipc_path() {
+                    let _ = std::fs::remove_file(&ipc);
+                }
+                last_error = e;
+                continue;
+            }
+
+            self.set_startup_state(StartupState::Probing);
+            match self.probe_readiness(strategy).await {
+                Ok(()) => {
+                    let mut report = self.startup.lock();
+                    report.state = StartupState::Ready;
+                    report.strategy = Some(strategy);
+                    report.finished_at = Some(Local::now().timestamp());
+                    logging!(
+                        info,
+                        Type::Core,
+                        true,
+                        "内核已就绪，启动策略: {:?}",
+                        strategy
+                    );
+                    event_publisher::publish_event(
+                        AutomationEvent::CoreStarted,
+                        json!({ "strategy": format!("{strategy:?}") }),
+                    );
                     return Ok(());
                 }
-                Err(e) => {
+                Err((e, is_port_conflict)) => {
                     logging!(
                         warn,
                         Type::Core,
                         true,
-                        "服务模式启动失败: {}, 回退到Sidecar模式",
+                        "{:?} 策略就绪探测失败: {}",
+                        strategy,
                         e
                     );
-                    // 出现失败时，清理可能残留的 unix socket，避免后续 Sidecar bind 失败
-                    #[cfg(unix)]
-                    if let Ok(ipc) = dirs::ipc_path() {
-                        let _ = std::fs::remove_file(&ipc);
-                    }
+                    port_conflict |= is_port_conflict;
+                    last_error = e;
+                    let _ = match self.get_running_mode() {
+                        RunningMode::Service => self.stop_core_by_service().await,
+                        RunningMode::Sidecar => self.stop_core_by_sidecar(),
+                        RunningMode::NotRunning => Ok(()),
+                    };
+                }
+            }
+        }
+
+        {
+            let mut report = self.startup.lock();
+            report.state = StartupState::Failed;
+            report.finished_at = Some(Local::now().timestamp());
+        }
+        logging!(error, Type::Core, true, "内核启动失败: {}", last_error);
+        Err(last_error)
+    }
+
+    /// 最近一次内核启动报告
+    pub fn startup_report(&self) -> CoreStartupReport {
+        self.startup.lock().clone()
+    }
+
+    /// 最近一次启动前扫描到的端口冲突
+    pub fn port_conflicts(&self) -> Vec<PortConflict> {
+        self.port_conflicts.lock().clone()
+    }
+
+    /// 启动前扫描配置端口是否被其他程序占用，返回是否按用户偏好直接换用空闲端口
+    async fn check_port_conflicts(&self) -> bool {
+        let ports = port_conflict::configured_ports().await;
+        // 自身内核按内核管理器记录的 PID 识别，不按进程名判断
+        let own_pid = self.sidecar_pid().or(*self.core_pid.lock());
+        let conflicts = AsyncHandler::spawn_blocking(move || port_conflict::scan(&ports, own_pid))
+            .await
+            .unwrap_or_default();
+        let remap = !conflicts.is_empty()
+            && Config::verge()
+                .await
+                .latest_ref()
+                .port_conflict_strategy
+                .as_deref()
+                == Some(port_conflict::STRATEGY_REMAP);
+        // 重映射时在换用端口后统一通知
+        if !conflicts.is_empty() && !remap {
+            let summary = port_conflict::summarize(&conflicts);
+            logging!(warn, Type::Core, true, "启动前检测到端口冲突: {}", summary);
+            handle::Handle::notice_message("core_startup::port_conflict", summary);
+        }
+        *self.port_conflicts.lock() = conflicts;
+        remap
+    }
+
+    /// 将重映射后的端口写入 clash/verge 配置并重新生成运行时配置，
+    /// 同时写回端口覆盖，避免之后应用端口覆盖时换回冲突端口
+    async fn remap_ports(&self, conflicts: &[PortConflict]) -> Result<()> {
+        let mut patch = Mapping::new();
+        let mut remapped = PortOverrides::default();
+        for conflict in conflicts {
+            let Some(port) = conflict.remapped_to else {
+                continue;
+            };
+            match conflict.key.as_str() {
+                "mixed-port" => remapped.mixed_port = Some(port),
+                "socks-port" => remapped.socks_port = Some(port),
+                "port" => remapped.http_port = Some(port),
+                "external-controller" => {
+                    let server = Config::clash()
+                        .await
+                        .latest_ref()
+                        .0
+                        .get("external-controller")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    let controller = port_conflict::replace_controller_port(&server, port);
+                    patch.insert(conflict.key.as_str().into(), controller.clone().into());
+                    remapped.external_controller = Some(controller);
+                    continue;
                 }
+                _ => continue,
             }
+            patch.insert(conflict.key.as_str().into(), port.into());
         }
+        if patch.is_empty() {
+            return Ok(());
+        }
+
+        Config::clash().await.draft_mut().patch_config(patch);
+        Config::clash().await.apply();
+        let clash_data = Config::clash().await.latest_ref().clone();
+        clash_data.save_config().await?;
+
+        Config::verge().await.draft_mut().patch_config(IVerge {
+            verge_mixed_port: remapped.mixed_port,
+            verge_socks_port: remapped.socks_port,
+            verge_port: remapped.http_port,
+            ..IVerge::default()
+        });
+        Config::verge().await.apply();
+        let verge_data = Config::verge().await.latest_ref().clone();
+        verge_data.save_file().await?;
+
+        Config::generate().await?;
+        Config::runtime().await.apply();
+        port_overrides::adopt_remapped_ports(&remapped).await;
+        Ok(())
+    }
+
+    fn set_startup_state(&self, state: StartupState) {
+        self.startup.lock().state = state;
+    }
+
+    fn record_step(
+        &self,
+        strategy: StartupStrategy,
+        step: &str,
+        started: Instant,
+        error: Option<&anyhow::Error>,
+    ) {
+        self.startup.lock().steps.push(StartupStep {
+            strategy,
+            step: step.to_string(),
+            success: error.is_none(),
+            message: error.map(|e| e.to_string()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    /// 就绪探测：IPC 能返回版本，且内核接口报告的 mixed-port 已由内核监听，
+    /// 失败时返回错误及是否为端口问题
+    async fn probe_readiness(
+        &self,
+        strategy: StartupStrategy,
+    ) -> std::result::Result<(), (anyhow::Error, bool)> {
+        let started = Instant::now();
+        let mut ipc_error = None;
+        while started.elapsed() < IPC_READY_TIMEOUT {
+            if self.get_running_mode() == RunningMode::NotRunning {
+                ipc_error = Some("内核进程已退出".to_string());
+                break;
+            }
+            match IpcManager::global().get_version().await {
+                Ok(_) => {
+                    ipc_error = None;
+                    break;
+                }
+                Err(e) => ipc_error = Some(e.to_string()),
+            }
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+        let ipc_result = ipc_error.map(|e| anyhow::anyhow!("IPC 未就绪: {e}"));
+        self.record_step(strategy, "ipc_probe", started, ipc_result.as_ref());
+        if let Some(e) = ipc_result {
+            return Err((e, false));
+        }
+
+        let port = Config::clash().await.latest_ref().get_mixed_port();
+        let started = Instant::now();
+        let mut listening = core_serves_port(port).await;
+        while !listening && started.elapsed() < PORT_READY_TIMEOUT {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+            listening = core_serves_port(port).await;
+        }
+        let port_result = (!listening).then(|| anyhow::anyhow!("内核未监听 mixed-port {port}"));
+        self.record_step(strategy, "port_probe", started, port_result.as_ref());
+        match port_result {
+            Some(e) => Err((e, true)),
+            None => Ok(()),
+        }
+    }
+
+    /// 为被占用的端口换用空闲端口后以 Sidecar 模式启动：启动前扫描到的冲突端口
+    /// 与就绪探测失败的 mixed-port 一并重映射并同步到 clash/verge 配置
+    async fn start_core_on_alternate_port(&self) -> Result<()> {
+        let ports = port_conflict::configured_ports().await;
+        let mut conflicts = self.port_conflicts();
+        if !conflicts.iter().any(|c| c.key == "mixed-port") {
+            conflicts.push(PortConflict {
+                key: "mixed-port".to_string(),
+                port: Config::clash().await.latest_ref().get_mixed_port(),
+                pid: None,
+                process: None,
+                remapped_to: None,
+            });
+        }
+        let mut conflicts = AsyncHandler::spawn_blocking(move || {
+            port_conflict::assign_free_ports(&mut conflicts, &ports);
+            conflicts
+        })
+        .await?;
+        if conflicts.iter().any(|c| c.remapped_to.is_none()) {
+            *self.port_conflicts.lock() = conflicts;
+            anyhow::bail!("找不到可用的备用端口");
+        }
+        self.remap_ports(&conflicts).await?;
+        *self.port_conflicts.lock() = conflicts.clone();
 
-        // 服务模式不可用或失败,使用Sidecar模式
-        logging!(info, Type::Core, true, "使用Sidecar模式启动");
         self.start_core_by_sidecar().await?;
+        // 系统代理与 PAC 读取 verge 中的端口，需随之刷新，避免仍指向旧端口
+        logging_error!(Type::Core, true, Sysopt::global().update_sysproxy().await);
+        handle::Handle::refresh_clash();
+        handle::Handle::refresh_verge();
+        let summary = port_conflict::summarize(&conflicts);
+        logging!(
+            warn,
+            Type::Core,
+            true,
+            "端口被占用，已换用空闲端口: {}",
+            summary
+        );
+        handle::Handle::notice_message("core_startup::port_conflict", summary);
         Ok(())
     }
 
@@ -981,7 +1349,8 @@ impl CoreManager {
             log::info!(target: "app", "✅ [系统代理] 系统代理已重置");
         }
 
-        let result = match self.get_running_mode() {
+        let running_mode = self.get_running_mode();
+        let result = match running_mode {
             RunningMode::Service => {
                 log::info!(target: "app", "🔄 [核心管理] 通过服务方式停止核心");
                 self.stop_core_by_service().await
@@ -997,7 +1366,12 @@ impl CoreManager {
         };
 
         match &result {
-            Ok(_) => log::info!(target: "app", "✅ [核心管理] Clash核心服务已完全停止"),
+            Ok(_) => {
+                log::info!(target: "app", "✅ [核心管理] Clash核心服务已完全停止");
+                if running_mode != RunningMode::NotRunning {
+                    event_publisher::publish_event(AutomationEvent::CoreStopped, json!({}));
+                }
+            }
             Err(e) => log::error!(target: "app", "❌ [核心管理] 停止Clash核心服务失败: {}", e),
         }
 
@@ -1043,7 +1417,30 @@ impl CoreManager {
         })?;
 
         self.put_configs_force(run_path).await?;
+        IpcManager::global().reset_capabilities();
 
         Ok(())
     }
 }
+
+/// 内核的监听器只在绑定成功后才会在 /configs 中报告端口，
+/// 仅检测端口被占用会把占用该端口的其他程序误判为内核
+async fn core_serves_port(port: u16) -> bool {
+    let reported = IpcManager::global()
+        .get_config()
+        .await
+        .ok()
+        .and_then(|config| config.get("mixed-port").and_then(|p| p.as_u64()));
+    reported == Some(u64::from(port)) && !local_port_available(port)
+}
+
+/// 操作已取消时丢弃生成中的运行时配置
+async fn discard_if_cancelled(token: Option<&CancelToken>) -> Result<()> {
+    if let Some(token) = token
+        && let Err(err) = token.checkpoint()
+    {
+        Config::runtime().await.discard();
+        return Err(err.into());
+    }
+    Ok(())
+}
@@ -1,8 +1,13 @@
 use crate::process::AsyncHandler;
 use crate::utils::notification::{NotificationEvent, notify_event};
+use crate::utils::window_manager::WindowManager;
 use crate::{
-    config::Config, core::handle, feat, logging, logging_error,
-    module::lightweight::entry_lightweight_mode, singleton_with_logging, utils::logging::Type,
+    config::Config,
+    core::handle,
+    feat, logging, logging_error,
+    module::lightweight::{self, ExitTrigger, entry_lightweight_mode},
+    singleton_with_logging,
+    utils::logging::Type,
 };
 use anyhow::{Result, bail};
 use parking_lot::Mutex;
@@ -10,8 +15,14 @@ use std::{collections::HashMap, fmt, str::FromStr, sync::Arc};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, ShortcutState};
 
+/// Prefix of the parameterized "next node in group" action, e.g. `next_node_in_group:Proxy`
+const NEXT_NODE_PREFIX: &str = "next_node_in_group:";
+
+/// Prefix of the parameterized "switch to favorite node" action, e.g. `switch_to_favorite:1`
+const FAVORITE_PREFIX: &str = "switch_to_favorite:";
+
 /// Enum representing all available hotkey functions
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HotkeyFunction {
     OpenOrCloseDashboard,
     ClashModeRule,
@@ -20,11 +31,60 @@ pub enum HotkeyFunction {
     ToggleSystemProxy,
     ToggleTunMode,
     EntryLightweightMode,
+    ShowWindow,
+    StartGlobalSpeedTest,
+    CancelGlobalSpeedTest,
+    NextNodeInGroup(String),
+    SwitchToFavorite(usize),
     Quit,
     #[cfg(target_os = "macos")]
     Hide,
 }
 
+/// Describes a bindable action for the hotkey settings UI
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HotkeyActionInfo {
+    pub action: &'static str,
+    /// Whether the action takes a parameter appended to the action id (e.g. a group name)
+    pub parameterized: bool,
+}
+
+impl HotkeyFunction {
+    /// All actions that can be bound to a shortcut
+    pub fn registry() -> Vec<HotkeyActionInfo> {
+        let mut actions: Vec<HotkeyActionInfo> = [
+            "open_or_close_dashboard",
+            "clash_mode_rule",
+            "clash_mode_global",
+            "clash_mode_direct",
+            "toggle_system_proxy",
+            "toggle_tun_mode",
+            "entry_lightweight_mode",
+            "show_window",
+            "start_global_speed_test",
+            "cancel_global_speed_test",
+            "quit",
+            #[cfg(target_os = "macos")]
+            "hide",
+        ]
+        .into_iter()
+        .map(|action| HotkeyActionInfo {
+            action,
+            parameterized: false,
+        })
+        .collect();
+        actions.push(HotkeyActionInfo {
+            action: NEXT_NODE_PREFIX,
+            parameterized: true,
+        });
+        actions.push(HotkeyActionInfo {
+            action: FAVORITE_PREFIX,
+            parameterized: true,
+        });
+        actions
+    }
+}
+
 impl fmt::Display for HotkeyFunction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
@@ -35,6 +95,15 @@ impl fmt::Display for HotkeyFunction {
             HotkeyFunction::ToggleSystemProxy => "toggle_system_proxy",
             HotkeyFunction::ToggleTunMode => "toggle_tun_mode",
             HotkeyFunction::EntryLightweightMode => "entry_lightweight_mode",
+            HotkeyFunction::ShowWindow => "show_window",
+            HotkeyFunction::StartGlobalSpeedTest => "start_global_speed_test",
+            HotkeyFunction::CancelGlobalSpeedTest => "cancel_global_speed_test",
+            HotkeyFunction::NextNodeInGroup(group) => {
+                return write!(f, "{NEXT_NODE_PREFIX}{group}");
+            }
+            HotkeyFunction::SwitchToFavorite(slot) => {
+                return write!(f, "{FAVORITE_PREFIX}{slot}");
+            }
             HotkeyFunction::Quit => "quit",
             #[cfg(target_os = "macos")]
             HotkeyFunction::Hide => "hide",
@@ -55,10 +124,26 @@ impl FromStr for HotkeyFunction {
             "toggle_system_proxy" => Ok(HotkeyFunction::ToggleSystemProxy),
             "toggle_tun_mode" => Ok(HotkeyFunction::ToggleTunMode),
             "entry_lightweight_mode" => Ok(HotkeyFunction::EntryLightweightMode),
+            "show_window" => Ok(HotkeyFunction::ShowWindow),
+            "start_global_speed_test" => Ok(HotkeyFunction::StartGlobalSpeedTest),
+            "cancel_global_speed_test" => Ok(HotkeyFunction::CancelGlobalSpeedTest),
             "quit" => Ok(HotkeyFunction::Quit),
             #[cfg(target_os = "macos")]
             "hide" => Ok(HotkeyFunction::Hide),
-            _ => bail!("invalid hotkey function: {}", s),
+            other => {
+                if let Some(group) = other.strip_prefix(NEXT_NODE_PREFIX)
+                    && !group.trim().is_empty()
+                {
+                    return Ok(HotkeyFunction::NextNodeInGroup(group.trim().to_string()));
+                }
+                match other
+                    .strip_prefix(FAVORITE_PREFIX)
+                    .and_then(|slot| slot.trim().parse::<usize>().ok())
+                {
+                    Some(slot) if slot > 0 => Ok(HotkeyFunction::SwitchToFavorite(slot)),
+                    _ => bail!("invalid hotkey function: {}", s),
+                }
+            }
         }
     }
 }
@@ -109,7 +194,7 @@ impl Hotkey {
         match function {
             HotkeyFunction::OpenOrCloseDashboard => {
                 AsyncHandler::spawn(async move || {
-                    crate::feat::open_or_close_dashboard().await;
+                    crate::feat::open_or_close_dashboard(ExitTrigger::Hotkey).await;
                     notify_event(app_handle, NotificationEvent::DashboardToggled).await;
                 });
             }
@@ -161,6 +246,86 @@ impl Hotkey {
                     notify_event(app_handle, NotificationEvent::LightweightModeEntered).await;
                 });
             }
+            HotkeyFunction::ShowWindow => {
+                AsyncHandler::spawn(async move || {
+                    if lightweight::should_stay_lightweight(ExitTrigger::Hotkey).await {
+                        return;
+                    }
+                    if !lightweight::exit_lightweight_mode().await {
+                        WindowManager::show_main_window().await;
+                    }
+                });
+            }
+            HotkeyFunction::StartGlobalSpeedTest => {
+                AsyncHandler::spawn(async move || {
+                    if let Err(e) =
+                        crate::cmd::global_speed_test::start_global_speed_test(app_handle, None)
+                            .await
+                    {
+                        logging!(warn, Type::Hotkey, true, "Global speed test failed: {}", e);
+                    }
+                });
+            }
+            HotkeyFunction::CancelGlobalSpeedTest => {
+                AsyncHandler::spawn(async move || {
+                    let _ =
+                        crate::cmd::global_speed_test::cancel_global_speed_test(app_handle).await;
+                });
+            }
+            HotkeyFunction::NextNodeInGroup(group) => {
+                AsyncHandler::spawn(
+                    async move || match feat::switch_to_next_proxy(&group).await {
+                        Ok(proxy) => {
+                            notify_event(
+                                app_handle,
+                                NotificationEvent::ProxySwitched {
+                                    group: &group,
+                                    proxy: &proxy,
+                                },
+                            )
+                            .await;
+                        }
+                        Err(e) => {
+                            logging!(
+                                warn,
+                                Type::Hotkey,
+                                true,
+                                "Failed to switch to next node in {}: {}",
+                                group,
+                                e
+                            );
+                        }
+                    },
+                );
+            }
+            HotkeyFunction::SwitchToFavorite(slot) => {
+                AsyncHandler::spawn(async move || {
+                    match crate::cmd::favorite_nodes::switch_to_favorite(slot).await {
+                        Ok(items) => {
+                            if let Some(item) = items.iter().find(|item| item.error.is_none()) {
+                                notify_event(
+                                    app_handle,
+                                    NotificationEvent::ProxySwitched {
+                                        group: &item.group,
+                                        proxy: &item.proxy,
+                                    },
+                                )
+                                .await;
+                            }
+                        }
+                        Err(e) => {
+                            logging!(
+                                warn,
+                                Type::Hotkey,
+                                true,
+                                "Failed to switch to favorite #{}: {}",
+                                slot,
+                                e
+                            );
+                        }
+                    }
+                });
+            }
             HotkeyFunction::Quit => {
                 AsyncHandler::spawn(async move || {
                     notify_event(app_handle, NotificationEvent::AppQuit).await;
@@ -224,11 +389,12 @@ impl Hotkey {
         }
 
         let is_quit = matches!(function, HotkeyFunction::Quit);
+        let function_name = function.to_string();
 
         let _ = manager.on_shortcut(hotkey, move |app_handle, hotkey_event, event| {
             let hotkey_event_owned = *hotkey_event;
             let event_owned = event;
-            let function_owned = function;
+            let function_owned = function.clone();
             let is_quit_owned = is_quit;
 
             let app_handle_cloned = app_handle.clone();
@@ -261,7 +427,6 @@ impl Hotkey {
                         if is_enable_global_hotkey {
                             Self::execute_function(function_owned, &app_handle_cloned);
                         } else {
-                            use crate::utils::window_manager::WindowManager;
                             let is_visible = WindowManager::is_main_window_visible();
                             let is_focused = WindowManager::is_main_window_focused();
 
@@ -279,7 +444,7 @@ impl Hotkey {
             Type::Hotkey,
             "Successfully registered hotkey {} for {}",
             hotkey,
-            function
+            function_name
         );
         Ok(())
     }
@@ -318,9 +483,10 @@ impl Hotkey {
             );
 
             for hotkey in hotkeys.iter() {
-                let mut iter = hotkey.split(',');
-                let func = iter.next();
-                let key = iter.next();
+                let (func, key) = match Self::parse_binding(hotkey) {
+                    Some((func, key)) => (Some(func), Some(key)),
+                    None => (None, None),
+                };
 
                 match (key, func) {
                     (Some(key), Some(func)) => {
@@ -424,19 +590,20 @@ impl Hotkey {
         let mut map = HashMap::new();
 
         hotkeys.iter().for_each(|hotkey| {
-            let mut iter = hotkey.split(',');
-            let func = iter.next();
-            let key = iter.next();
-
-            if let (Some(func), Some(key)) = (func, key) {
-                let func = func.trim();
-                let key = key.trim();
+            if let Some((func, key)) = Self::parse_binding(hotkey) {
                 map.insert(key, func);
             }
         });
         map
     }
 
+    /// Split a `function,accelerator` binding; the function may contain commas (group names)
+    pub fn parse_binding(hotkey: &str) -> Option<(&str, &str)> {
+        let (func, key) = hotkey.rsplit_once(',')?;
+        let (func, key) = (func.trim(), key.trim());
+        (!func.is_empty() && !key.is_empty()).then_some((func, key))
+    }
+
     fn get_diff<'a>(
         old_map: HashMap<&'a str, &'a str>,
         new_map: HashMap<&'a str, &'a str>,
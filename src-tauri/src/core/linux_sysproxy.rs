@@ -0,0 +1,283 @@
+#![cfg(target_os = "linux")]
+
+use crate::{
+    core::sysopt::{BackendApplyResult, SysproxyBackendReport, SysproxyBackendStatus},
+    logging,
+    utils::logging::Type,
+};
+use anyhow::{Result, anyhow, bail};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{collections::HashMap, env, fs, path::PathBuf, process::Command};
+use sysproxy::{Autoproxy, Sysproxy};
+
+/// environment.d 中的会话环境变量文件，下次登录时生效
+const ENV_FILE_NAME: &str = "90-liebesu-clash-proxy.conf";
+const KIOSLAVERC_GROUP: &str = "Proxy Settings";
+
+/// 各后端最近一次应用的结果
+static LAST_APPLY: Lazy<Mutex<HashMap<SysproxyBackend, BackendApplyResult>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SysproxyBackend {
+    /// GNOME 及基于 GTK 的桌面，通过 gsettings 写入 org.gnome.system.proxy
+    Gsettings,
+    /// KDE Plasma，通过 kwriteconfig 写入 kioslaverc
+    Kde,
+    /// 无桌面代理设置时写入会话环境变量文件
+    EnvFile,
+}
+
+impl SysproxyBackend {
+    fn as_str(self) -> &'static str {
+        match self {
+            SysproxyBackend::Gsettings => "gsettings",
+            SysproxyBackend::Kde => "kde",
+            SysproxyBackend::EnvFile => "env-file",
+        }
+    }
+}
+
+/// 检测到的可用机制
+struct Detection {
+    gsettings: bool,
+    kde_tool: Option<&'static str>,
+    kde_session: bool,
+    env_file: Option<PathBuf>,
+}
+
+impl Detection {
+    fn detect() -> Self {
+        Detection {
+            gsettings: Command::new("gsettings")
+                .args(["get", "org.gnome.system.proxy", "mode"])
+                .output()
+                .is_ok_and(|output| output.status.success()),
+            kde_tool: ["kwriteconfig6", "kwriteconfig5"]
+                .into_iter()
+                .find(|tool| command_exists(tool)),
+            kde_session: is_kde_session(),
+            env_file: env_file_path(),
+        }
+    }
+
+    fn is_active(&self, backend: SysproxyBackend) -> bool {
+        match backend {
+            SysproxyBackend::Gsettings => self.gsettings,
+            SysproxyBackend::Kde => self.kde_session && self.kde_tool.is_some(),
+            // 桌面环境没有可写入的代理设置时（如 sway、i3）才使用环境变量
+            SysproxyBackend::EnvFile => {
+                self.env_file.is_some()
+                    && !self.is_active(SysproxyBackend::Gsettings)
+                    && !self.is_active(SysproxyBackend::Kde)
+            }
+        }
+    }
+}
+
+/// 按检测结果依次应用到各后端，任一后端成功即视为成功
+pub fn apply(sys: &Sysproxy, auto: &Autoproxy) -> Result<()> {
+    let detection = Detection::detect();
+    let mut first_error = None;
+    let mut any_success = false;
+
+    for backend in [
+        SysproxyBackend::Gsettings,
+        SysproxyBackend::Kde,
+        SysproxyBackend::EnvFile,
+    ] {
+        if !detection.is_active(backend) {
+            continue;
+        }
+        let result = match backend {
+            SysproxyBackend::Gsettings => apply_gsettings(sys, auto),
+            SysproxyBackend::Kde => {
+                apply_kde(detection.kde_tool.unwrap_or("kwriteconfig5"), sys, auto)
+            }
+            SysproxyBackend::EnvFile => apply_env_file(detection.env_file.as_ref(), sys),
+        };
+        if let Err(e) = &result {
+            logging!(
+                warn,
+                Type::System,
+                true,
+                "[系统代理] {} 后端应用失败: {}",
+                backend.as_str(),
+                e
+            );
+        }
+        LAST_APPLY.lock().insert(
+            backend,
+            BackendApplyResult {
+                success: result.is_ok(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+                time: Local::now().timestamp(),
+            },
+        );
+        match result {
+            Ok(()) => any_success = true,
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    match (any_success, first_error) {
+        (true, _) => Ok(()),
+        (false, Some(e)) => Err(e),
+        (false, None) => bail!("no system proxy backend available"),
+    }
+}
+
+/// 当前桌面环境及各后端的检测与应用状态
+pub fn backend_report() -> SysproxyBackendReport {
+    let detection = Detection::detect();
+    let last_apply = LAST_APPLY.lock().clone();
+    let status =
+        |backend: SysproxyBackend, available: bool, detail: Option<String>| SysproxyBackendStatus {
+            backend: backend.as_str().into(),
+            available,
+            active: detection.is_active(backend),
+            detail,
+            last_apply: last_apply.get(&backend).cloned(),
+        };
+
+    SysproxyBackendReport {
+        desktop: env::var("XDG_CURRENT_DESKTOP").ok(),
+        backends: vec![
+            status(
+                SysproxyBackend::Gsettings,
+                detection.gsettings,
+                Some("org.gnome.system.proxy".into()),
+            ),
+            status(
+                SysproxyBackend::Kde,
+                detection.kde_tool.is_some(),
+                detection.kde_tool.map(str::to_string),
+            ),
+            status(
+                SysproxyBackend::EnvFile,
+                detection.env_file.is_some(),
+                detection
+                    .env_file
+                    .as_ref()
+                    .map(|path| path.display().to_string()),
+            ),
+        ],
+    }
+}
+
+// ===== 内部实现函数 =====
+
+/// 先关闭再开启，避免切换模式时两种代理同时生效
+fn apply_gsettings(sys: &Sysproxy, auto: &Autoproxy) -> Result<()> {
+    if auto.enable {
+        sys.set_system_proxy()?;
+        auto.set_auto_proxy()?;
+    } else {
+        auto.set_auto_proxy()?;
+        sys.set_system_proxy()?;
+    }
+    Ok(())
+}
+
+/// kioslaverc 的 ProxyType：0 不使用代理，1 手动，2 PAC
+fn apply_kde(tool: &str, sys: &Sysproxy, auto: &Autoproxy) -> Result<()> {
+    let write = |key: &str, value: &str| -> Result<()> {
+        let status = Command::new(tool)
+            .args([
+                "--file",
+                "kioslaverc",
+                "--group",
+                KIOSLAVERC_GROUP,
+                "--key",
+                key,
+                value,
+            ])
+            .status()?;
+        if !status.success() {
+            bail!("{tool} failed to write {key}");
+        }
+        Ok(())
+    };
+
+    if auto.enable {
+        write("Proxy Config Script", &auto.url)?;
+        write("ProxyType", "2")?;
+    } else if sys.enable {
+        // KDE 的代理地址格式为 "scheme://host port"
+        let http = format!("http://{} {}", sys.host, sys.port);
+        write("httpProxy", &http)?;
+        write("httpsProxy", &http)?;
+        write("ftpProxy", &http)?;
+        write("socksProxy", &format!("socks://{} {}", sys.host, sys.port))?;
+        write("NoProxyFor", &sys.bypass)?;
+        write("ProxyType", "1")?;
+    } else {
+        write("ProxyType", "0")?;
+    }
+
+    // 通知正在运行的 KIO 进程重新读取配置，失败不影响结果
+    let _ = Command::new("dbus-send")
+        .args([
+            "--type=signal",
+            "/KIO/Scheduler",
+            "org.kde.KIO.Scheduler.reparseSlaveConfiguration",
+            "string:",
+        ])
+        .status();
+    Ok(())
+}
+
+/// 手动代理时写入环境变量文件；PAC 无法用环境变量表达，与关闭时一样移除文件
+fn apply_env_file(path: Option<&PathBuf>, sys: &Sysproxy) -> Result<()> {
+    let path = path.ok_or_else(|| anyhow!("config directory not found"))?;
+    if !sys.enable {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    let http = format!("http://{}:{}", sys.host, sys.port);
+    let socks = format!("socks5://{}:{}", sys.host, sys.port);
+    let mut content =
+        String::from("# Managed by Liebesu_Clash, removed when system proxy is off\n");
+    for (key, value) in [
+        ("http_proxy", &http),
+        ("https_proxy", &http),
+        ("all_proxy", &socks),
+        ("no_proxy", &sys.bypass),
+    ] {
+        content.push_str(&format!("{key}={value}\n"));
+        content.push_str(&format!("{}={value}\n", key.to_ascii_uppercase()));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn env_file_path() -> Option<PathBuf> {
+    Some(
+        ::dirs::config_dir()?
+            .join("environment.d")
+            .join(ENV_FILE_NAME),
+    )
+}
+
+fn is_kde_session() -> bool {
+    let desktop_is_kde = env::var("XDG_CURRENT_DESKTOP")
+        .is_ok_and(|desktop| desktop.split(':').any(|d| d.eq_ignore_ascii_case("KDE")));
+    desktop_is_kde || env::var("KDE_FULL_SESSION").is_ok_and(|v| v == "true")
+}
+
+fn command_exists(name: &str) -> bool {
+    Command::new("which")
+        .arg(name)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
@@ -1,15 +1,23 @@
 pub mod async_proxy_query;
 pub mod backup;
+pub mod captive_portal;
 #[allow(clippy::module_inception)]
 mod core;
 pub mod event_driven_proxy;
 pub mod handle;
 pub mod hotkey;
+pub mod linux_sysproxy;
+pub mod port_conflict;
+pub mod profile_watcher;
+pub mod sandbox;
 pub mod service;
 pub mod service_ipc;
 pub mod sysopt;
+pub mod system_events;
 pub mod timer;
 pub mod tray;
+pub mod vpn_detect;
+pub mod win_firewall;
 pub mod win_uwp;
 
 pub use self::{core::*, event_driven_proxy::EventDrivenProxyManager, timer::Timer};
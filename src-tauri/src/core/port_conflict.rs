@@ -0,0 +1,207 @@
+use crate::config::Config;
+use port_scanner::local_port_available;
+use serde::Serialize;
+use serde_yaml_ng::Value;
+use std::{collections::HashSet, process::Command as StdCommand};
+
+/// 启动前检测到端口冲突时自动换用空闲端口，默认仅报告
+pub const STRATEGY_REMAP: &str = "remap";
+
+/// 重映射时向上搜索空闲端口的范围
+const REMAP_RANGE: u16 = 100;
+
+/// 被占用的端口及占用进程
+#[derive(Debug, Clone, Serialize)]
+pub struct PortConflict {
+    /// 配置项：mixed-port | socks-port | port | external-controller
+    pub key: String,
+    pub port: u16,
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+    /// 自动重映射后使用的端口
+    pub remapped_to: Option<u16>,
+}
+
+/// 当前配置中需要监听的端口，external-controller 仅在启用时检查
+pub async fn configured_ports() -> Vec<(&'static str, u16)> {
+    let enable_controller = Config::verge()
+        .await
+        .latest_ref()
+        .enable_external_controller
+        .unwrap_or(false);
+    let clash = Config::clash().await;
+    let clash = clash.latest_ref();
+    let port = |key: &str| {
+        clash
+            .0
+            .get(key)
+            .and_then(|value| match value {
+                Value::String(s) => s.parse().ok(),
+                Value::Number(n) => n.as_u64().and_then(|n| u16::try_from(n).ok()),
+                _ => None,
+            })
+            .filter(|port: &u16| *port != 0)
+    };
+
+    let mut ports: Vec<(&'static str, u16)> = [
+        ("mixed-port", port("mixed-port")),
+        ("socks-port", port("socks-port")),
+        ("port", port("port")),
+    ]
+    .into_iter()
+    .filter_map(|(key, port)| Some((key, port?)))
+    .collect();
+    if enable_controller && let Some(port) = controller_port(&clash.get_client_info().server) {
+        ports.push(("external-controller", port));
+    }
+    ports
+}
+
+/// 检测已被其他程序占用的端口，own_pid 为内核管理器记录的自身内核 PID，阻塞调用
+pub fn scan(ports: &[(&'static str, u16)], own_pid: Option<u32>) -> Vec<PortConflict> {
+    ports
+        .iter()
+        .filter(|(_, port)| !local_port_available(*port))
+        .filter_map(|(key, port)| {
+            let owner = find_port_owner(*port);
+            if own_pid.is_some() && owner.as_ref().map(|(pid, _)| *pid) == own_pid {
+                return None;
+            }
+            Some(PortConflict {
+                key: key.to_string(),
+                port: *port,
+                pid: owner.as_ref().map(|(pid, _)| *pid),
+                process: owner.map(|(_, name)| name),
+                remapped_to: None,
+            })
+        })
+        .collect()
+}
+
+/// 为冲突端口向上寻找空闲端口，避开其他配置端口与已分配的端口
+pub fn assign_free_ports(conflicts: &mut [PortConflict], ports: &[(&'static str, u16)]) {
+    let mut taken: HashSet<u16> = ports.iter().map(|(_, port)| *port).collect();
+    for conflict in conflicts.iter_mut() {
+        conflict.remapped_to = (1..=REMAP_RANGE)
+            .filter_map(|offset| conflict.port.checked_add(offset))
+            .find(|port| !taken.contains(port) && local_port_available(*port));
+        if let Some(port) = conflict.remapped_to {
+            taken.insert(port);
+        }
+    }
+}
+
+/// 用于日志与通知的冲突摘要，已重映射的端口附带新端口
+pub fn summarize(conflicts: &[PortConflict]) -> String {
+    conflicts
+        .iter()
+        .map(|c| {
+            let owner = match (&c.process, c.pid) {
+                (Some(name), Some(pid)) if !name.is_empty() => format!("{name} ({pid})"),
+                (_, Some(pid)) => format!("PID {pid}"),
+                _ => "未知进程".to_string(),
+            };
+            match c.remapped_to {
+                Some(port) => format!("{} {} [{}] -> {}", c.key, c.port, owner, port),
+                None => format!("{} {} [{}]", c.key, c.port, owner),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 替换 host:port 形式地址中的端口
+pub fn replace_controller_port(server: &str, port: u16) -> String {
+    match server.rsplit_once(':') {
+        Some((host, _)) => format!("{host}:{port}"),
+        None => format!("{server}:{port}"),
+    }
+}
+
+fn controller_port(server: &str) -> Option<u16> {
+    server.rsplit_once(':')?.1.parse().ok()
+}
+
+/// 查找监听该 TCP 端口的进程 (pid, 进程名)
+#[cfg(target_os = "windows")]
+fn find_port_owner(port: u16) -> Option<(u32, String)> {
+    use std::os::windows::process::CommandExt;
+
+    let output = StdCommand::new("netstat")
+        .args(["-ano", "-p", "TCP"])
+        .creation_flags(0x08000000)
+        .output()
+        .ok()?;
+    let suffix = format!(":{port}");
+    // netstat 列：协议 本地地址 外部地址 状态 PID；状态文字随系统语言变化，按外部地址端口为 0 判断监听
+    let pid: u32 = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            let listening =
+                cols.len() >= 5 && cols[1].ends_with(&suffix) && cols[2].ends_with(":0");
+            listening.then(|| cols[4].parse().ok()).flatten()
+        })?;
+
+    let output = StdCommand::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .creation_flags(0x08000000)
+        .output()
+        .ok()?;
+    let name = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|line| line.split(',').next())
+        .map(|name| name.trim_matches('"').to_string())
+        .filter(|name| !name.is_empty() && !name.starts_with("INFO:"))
+        .unwrap_or_default();
+    Some((pid, name))
+}
+
+#[cfg(target_os = "linux")]
+fn find_port_owner(port: u16) -> Option<(u32, String)> {
+    // ss 输出示例：LISTEN 0 4096 127.0.0.1:7890 0.0.0.0:* users:(("clash",pid=1234,fd=7))
+    let output = StdCommand::new("ss")
+        .args(["-ltnpH", &format!("sport = :{port}")])
+        .output();
+    let from_ss = output.ok().and_then(|output| {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let users = stdout.split("users:((").nth(1)?;
+        let name = users.split('"').nth(1)?.to_string();
+        let pid = users
+            .split("pid=")
+            .nth(1)?
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        Some((pid, name))
+    });
+    from_ss.or_else(|| find_port_owner_lsof(port))
+}
+
+#[cfg(target_os = "macos")]
+fn find_port_owner(port: u16) -> Option<(u32, String)> {
+    find_port_owner_lsof(port)
+}
+
+/// lsof -F 输出以字段标识开头：p 为 pid，c 为进程名
+#[cfg(unix)]
+fn find_port_owner_lsof(port: u16) -> Option<(u32, String)> {
+    let output = StdCommand::new("lsof")
+        .args(["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-Fpc"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pid = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix('p'))?
+        .parse()
+        .ok()?;
+    let name = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix('c'))
+        .unwrap_or_default()
+        .to_string();
+    Some((pid, name))
+}
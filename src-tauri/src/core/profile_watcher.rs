@@ -0,0 +1,218 @@
+use crate::{
+    config::{Config, IProfiles},
+    core::{CoreManager, handle},
+    logging,
+    process::AsyncHandler,
+    singleton,
+    utils::{dirs, logging::Type},
+};
+use anyhow::Result;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tauri::Emitter;
+use tokio::sync::mpsc;
+
+/// 编辑器保存时通常会连续触发多次写入，静默该时长后再处理
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 订阅文件被外部修改事件
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileFileChanged {
+    pub uid: String,
+    pub file: String,
+    pub valid: bool,
+    pub error: Option<String>,
+    pub active: bool, // 是否属于当前订阅，属于时会重新生成运行配置
+}
+
+/// 订阅目录监听器
+/// 检测外部编辑器对订阅文件的修改，应用自身的写入通过内容摘要区分
+pub struct ProfileWatcher {
+    started: AtomicBool,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    known: Mutex<HashMap<String, u64>>, // 按文件名记录最近一次已知内容的摘要
+}
+
+singleton!(ProfileWatcher, PROFILE_WATCHER);
+
+impl ProfileWatcher {
+    fn new() -> Self {
+        Self {
+            started: AtomicBool::new(false),
+            watcher: Mutex::new(None),
+            known: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 记录应用自身写入的内容，监听到相同内容时不视为外部修改
+    pub fn mark_internal_write(&self, path: &Path, data: &[u8]) {
+        if let Some(file) = path.file_name() {
+            self.known
+                .lock()
+                .insert(file.to_string_lossy().to_string(), content_digest(data));
+        }
+    }
+
+    /// 启动监听
+    pub fn start(&'static self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        match create_watcher(sender) {
+            Ok(watcher) => *self.watcher.lock() = Some(watcher),
+            Err(e) => {
+                logging!(warn, Type::Config, true, "[订阅监听] 启动失败: {}", e);
+                return;
+            }
+        }
+
+        AsyncHandler::spawn(move || self.run(receiver));
+    }
+
+    async fn run(&'static self, mut receiver: mpsc::UnboundedReceiver<PathBuf>) {
+        let mut pending = HashSet::new();
+        while let Some(path) = receiver.recv().await {
+            pending.insert(path);
+            while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, receiver.recv()).await {
+                pending.insert(path);
+            }
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+
+            let mut regenerate = false;
+            for path in pending.drain() {
+                if let Some(changed) = self.handle_change(&path).await {
+                    regenerate |= changed.valid && changed.active;
+                    if let Some(app_handle) = handle::Handle::global().app_handle() {
+                        let _ = app_handle.emit("profile-file-changed", &changed);
+                    }
+                }
+            }
+
+            if regenerate {
+                logging!(
+                    info,
+                    Type::Config,
+                    true,
+                    "[订阅监听] 当前订阅文件已修改，重新生成运行配置"
+                );
+                match CoreManager::global().update_config().await {
+                    Ok(_) => handle::Handle::refresh_clash(),
+                    Err(e) => {
+                        logging!(warn, Type::Config, true, "[订阅监听] 更新配置失败: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 处理单个文件的变化，内容与已知内容相同或不属于任何订阅时返回 None
+    async fn handle_change(&self, path: &Path) -> Option<ProfileFileChanged> {
+        // 监听器上报的路径可能与应用写入时的路径形式不同（如符号链接），按文件名比较
+        let file = path.file_name()?.to_string_lossy().to_string();
+        let data = tokio::fs::read(path).await.ok()?;
+        let digest = content_digest(&data);
+        if self.known.lock().insert(file.clone(), digest) == Some(digest) {
+            return None;
+        }
+
+        let (uid, is_merge, active) = {
+            let profiles = Config::profiles().await;
+            let profiles = profiles.latest_ref();
+            let item = profiles
+                .get_items()?
+                .iter()
+                .find(|item| item.file.as_deref() == Some(file.as_str()))?;
+            let uid = item.uid.clone()?;
+            let is_merge = item.itype.as_deref() == Some("merge");
+            let active = active_uids(&profiles).contains(&uid);
+            (uid, is_merge, active)
+        };
+
+        logging!(
+            info,
+            Type::Config,
+            true,
+            "[订阅监听] 检测到外部修改: {} ({})",
+            file,
+            uid
+        );
+        let (valid, error) = match CoreManager::global()
+            .validate_config_file(&path.to_string_lossy(), Some(is_merge))
+            .await
+        {
+            Ok((true, _)) => (true, None),
+            Ok((false, error)) => (false, Some(error)),
+            Err(e) => (false, Some(e.to_string())),
+        };
+        if let Some(error) = &error {
+            logging!(
+                warn,
+                Type::Config,
+                true,
+                "[订阅监听] {} 验证失败: {}",
+                file,
+                error
+            );
+        }
+
+        Some(ProfileFileChanged {
+            uid,
+            file,
+            valid,
+            error,
+            active,
+        })
+    }
+}
+
+fn create_watcher(sender: mpsc::UnboundedSender<PathBuf>) -> Result<RecommendedWatcher> {
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                for path in event.paths {
+                    let _ = sender.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!(target: "app", "profile watcher error: {e}"),
+        })?;
+    watcher.watch(&dirs::app_profiles_dir()?, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// 当前订阅及其关联的增强项，全局 Merge / Script 也参与生成运行配置
+fn active_uids(profiles: &IProfiles) -> HashSet<String> {
+    let mut uids: HashSet<String> = [
+        profiles.get_current(),
+        profiles.current_merge(),
+        profiles.current_script(),
+        profiles.current_rules(),
+        profiles.current_proxies(),
+        profiles.current_groups(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    uids.insert("Merge".to_string());
+    uids.insert("Script".to_string());
+    uids.insert(crate::cmd::dns_hosts::HOSTS_MERGE_UID.to_string());
+    uids
+}
+
+fn content_digest(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,465 @@
+use crate::{
+    cmd::{
+        core_registry,
+        delay_targets::{self, DelayTargetResult},
+    },
+    config::Config,
+    core::handle,
+    logging,
+    process::AsyncHandler,
+    utils::{dirs, help, logging::Type},
+};
+use anyhow::{Result, anyhow, bail};
+use futures::{StreamExt, stream};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    collections::VecDeque,
+    fs,
+    net::TcpListener,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+
+const SANDBOX_DIR: &str = "sandbox";
+const SANDBOX_CONFIG: &str = "sandbox-config.yaml";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(20);
+const PROBE_TIMEOUT_MS: u64 = 5000;
+/// 最多测试的节点数量
+const MAX_PROBED_PROXIES: usize = 20;
+const PROBE_CONCURRENCY: usize = 5;
+/// 失败时返回的内核输出行数
+const MAX_OUTPUT_LINES: usize = 30;
+/// 沙盒使用与主内核相同的地理数据，避免重新下载
+const GEO_FILES: &[&str] = &[
+    "Country.mmdb",
+    "geoip.metadb",
+    "geoip.dat",
+    "geosite.dat",
+    "GeoLite2-ASN.mmdb",
+];
+/// 不参与测速的内置策略和代理组类型
+const NON_NODE_TYPES: &[&str] = &[
+    "Selector",
+    "URLTest",
+    "Fallback",
+    "LoadBalance",
+    "Relay",
+    "Direct",
+    "Reject",
+    "RejectDrop",
+    "Compatible",
+    "Pass",
+    "Dns",
+];
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 沙盒中单个节点的延迟
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxProxyDelay {
+    pub name: String,
+    pub proxy_type: String,
+    pub delay: Option<u64>,
+}
+
+/// 沙盒测试报告
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxTestReport {
+    pub uid: String,
+    pub name: String,
+    pub started: bool,
+    pub startup_ms: Option<u64>,
+    pub error: Option<String>,
+    pub core_output: Vec<String>,             // 启动失败时的内核输出
+    pub connectivity: Vec<DelayTargetResult>, // 按订阅规则通过沙盒代理访问测速目标
+    pub total_proxies: usize,
+    pub available_proxies: usize,
+    pub proxy_delays: Vec<SandboxProxyDelay>,
+    pub duration_ms: u64,
+}
+
+/// 沙盒内核进程，离开作用域时结束进程
+struct SandboxProcess {
+    child: Option<CommandChild>,
+    output: Arc<Mutex<VecDeque<String>>>,
+    exited: Arc<AtomicBool>,
+}
+
+impl SandboxProcess {
+    fn output(&self) -> Vec<String> {
+        self.output.lock().iter().cloned().collect()
+    }
+}
+
+impl Drop for SandboxProcess {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.take() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// 沙盒使用的端口和控制器密钥
+struct SandboxEndpoint {
+    mixed_port: u16,
+    controller: String,
+    secret: String,
+}
+
+/// 用临时端口启动第二个内核加载订阅，探测连通性和节点延迟后退出，不影响正在运行的内核
+pub async fn test_profile(uid: &str) -> Result<SandboxTestReport> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        bail!("已有沙盒测试正在进行");
+    }
+    let result = run_sandbox(uid).await;
+    RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+// ===== 内部实现函数 =====
+
+async fn run_sandbox(uid: &str) -> Result<SandboxTestReport> {
+    let started_at = Instant::now();
+    let (name, content) = {
+        let profiles = Config::profiles().await;
+        let profiles = profiles.latest_ref();
+        let item = profiles.get_item(&uid.to_string())?;
+        (
+            item.name.clone().unwrap_or_else(|| uid.to_string()),
+            item.read_file()?,
+        )
+    };
+    let profile: Mapping =
+        serde_yaml_ng::from_str(&content).map_err(|e| anyhow!("订阅内容解析失败: {e}"))?;
+
+    let endpoint = SandboxEndpoint {
+        mixed_port: free_port()?,
+        controller: format!("127.0.0.1:{}", free_port()?),
+        secret: uuid::Uuid::new_v4().simple().to_string(),
+    };
+    let sandbox_dir = dirs::app_home_dir()?.join(SANDBOX_DIR);
+    fs::create_dir_all(&sandbox_dir)?;
+    copy_geo_files(&sandbox_dir)?;
+    let config_path = sandbox_dir.join(SANDBOX_CONFIG);
+    help::save_yaml(
+        &config_path,
+        &sandbox_config(profile, &endpoint),
+        Some("# Generated by Liebesu_Clash sandbox"),
+    )
+    .await?;
+
+    logging!(
+        info,
+        Type::Core,
+        true,
+        "[沙盒] 启动沙盒内核测试订阅 {}，代理端口 {}",
+        name,
+        endpoint.mixed_port
+    );
+    let mut report = SandboxTestReport {
+        uid: uid.to_string(),
+        name,
+        started: false,
+        startup_ms: None,
+        error: None,
+        core_output: Vec::new(),
+        connectivity: Vec::new(),
+        total_proxies: 0,
+        available_proxies: 0,
+        proxy_delays: Vec::new(),
+        duration_ms: 0,
+    };
+
+    let result = async {
+        let process = spawn_core(&sandbox_dir, &config_path).await?;
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(Duration::from_millis(PROBE_TIMEOUT_MS + 2000))
+            .build()?;
+        if let Err(e) = wait_ready(&client, &endpoint, &process).await {
+            report.core_output = process.output();
+            return Err(e);
+        }
+        report.started = true;
+        report.startup_ms = Some(started_at.elapsed().as_millis() as u64);
+
+        report.connectivity = probe_connectivity(endpoint.mixed_port).await;
+        let proxies = list_proxies(&client, &endpoint).await?;
+        report.total_proxies = proxies.len();
+        report.proxy_delays = probe_proxies(&client, &endpoint, proxies).await;
+        report.available_proxies = report
+            .proxy_delays
+            .iter()
+            .filter(|proxy| proxy.delay.is_some())
+            .count();
+        drop(process);
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+
+    let _ = fs::remove_file(&config_path);
+    if let Err(e) = result {
+        logging!(warn, Type::Core, true, "[沙盒] 订阅测试失败: {}", e);
+        report.error = Some(e.to_string());
+    }
+    report.duration_ms = started_at.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// 只保留订阅的代理和规则，监听端口、控制器和 TUN 均替换为沙盒设置
+fn sandbox_config(mut config: Mapping, endpoint: &SandboxEndpoint) -> Mapping {
+    for key in [
+        "port",
+        "socks-port",
+        "redir-port",
+        "tproxy-port",
+        "listeners",
+        "external-controller-tls",
+        "external-controller-unix",
+        "external-controller-pipe",
+        "external-controller-cors",
+        "external-ui",
+        "external-ui-url",
+        "tunnels",
+    ] {
+        config.remove(key);
+    }
+    config.insert("mixed-port".into(), endpoint.mixed_port.into());
+    config.insert("allow-lan".into(), false.into());
+    config.insert("bind-address".into(), "127.0.0.1".into());
+    config.insert(
+        "external-controller".into(),
+        endpoint.controller.as_str().into(),
+    );
+    config.insert("secret".into(), endpoint.secret.as_str().into());
+
+    let mut tun = Mapping::new();
+    tun.insert("enable".into(), false.into());
+    config.insert("tun".into(), tun.into());
+
+    // 沙盒 DNS 不监听端口
+    if let Some(dns) = config.get_mut("dns").and_then(Value::as_mapping_mut) {
+        dns.remove("listen");
+    }
+
+    let mut profile = Mapping::new();
+    profile.insert("store-selected".into(), false.into());
+    profile.insert("store-fake-ip".into(), false.into());
+    config.insert("profile".into(), profile.into());
+    config
+}
+
+fn free_port() -> Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+fn copy_geo_files(sandbox_dir: &Path) -> Result<()> {
+    let home = dirs::app_home_dir()?;
+    for file in GEO_FILES {
+        let source = home.join(file);
+        let target = sandbox_dir.join(file);
+        let outdated = match (fs::metadata(&source), fs::metadata(&target)) {
+            (Ok(source), Ok(target)) => source.modified().ok() > target.modified().ok(),
+            (Ok(_), Err(_)) => true,
+            _ => false,
+        };
+        if outdated {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+async fn spawn_core(sandbox_dir: &Path, config_path: &Path) -> Result<SandboxProcess> {
+    let app_handle = handle::Handle::global()
+        .app_handle()
+        .ok_or(anyhow!("failed to get app handle"))?;
+    let clash_core = Config::verge().await.latest_ref().get_valid_clash_core();
+    let (mut rx, child) = core_registry::core_command(&app_handle, &clash_core)
+        .await?
+        .args([
+            "-d",
+            dirs::path_to_str(&sandbox_dir.to_path_buf())?,
+            "-f",
+            dirs::path_to_str(&config_path.to_path_buf())?,
+        ])
+        .spawn()?;
+
+    let output = Arc::new(Mutex::new(VecDeque::new()));
+    let exited = Arc::new(AtomicBool::new(false));
+    let (output_writer, exited_flag) = (output.clone(), exited.clone());
+    AsyncHandler::spawn(move || async move {
+        while let Some(event) = rx.recv().await {
+            let line = match event {
+                CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
+                    String::from_utf8_lossy(&line).trim_end().to_string()
+                }
+                CommandEvent::Terminated(payload) => {
+                    exited_flag.store(true, Ordering::SeqCst);
+                    format!("进程退出，退出码: {:?}", payload.code)
+                }
+                _ => continue,
+            };
+            let mut output = output_writer.lock();
+            output.push_back(line);
+            if output.len() > MAX_OUTPUT_LINES {
+                output.pop_front();
+            }
+        }
+    });
+
+    Ok(SandboxProcess {
+        child: Some(child),
+        output,
+        exited,
+    })
+}
+
+async fn wait_ready(
+    client: &reqwest::Client,
+    endpoint: &SandboxEndpoint,
+    process: &SandboxProcess,
+) -> Result<()> {
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    while Instant::now() < deadline {
+        if process.exited.load(Ordering::SeqCst) {
+            bail!("沙盒内核启动失败");
+        }
+        let response = client
+            .get(format!("http://{}/version", endpoint.controller))
+            .bearer_auth(&endpoint.secret)
+            .send()
+            .await;
+        if response.is_ok_and(|response| response.status().is_success()) {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    }
+    bail!("沙盒内核启动超时")
+}
+
+/// 通过沙盒代理端口访问测速目标，走订阅自身的规则
+async fn probe_connectivity(mixed_port: u16) -> Vec<DelayTargetResult> {
+    let client = reqwest::Proxy::all(format!("http://127.0.0.1:{mixed_port}")).and_then(|proxy| {
+        reqwest::Client::builder()
+            .proxy(proxy)
+            .timeout(Duration::from_millis(PROBE_TIMEOUT_MS))
+            .build()
+    });
+    let mut results = Vec::new();
+    for target in delay_targets::configured_targets() {
+        let started = Instant::now();
+        let (delay, error) = match &client {
+            Ok(client) => match client.get(&target.url).send().await {
+                Ok(response) if target.accepts(response.status().as_u16()) => {
+                    (Some(started.elapsed().as_millis() as u64), None)
+                }
+                Ok(response) => (None, Some(format!("HTTP {}", response.status()))),
+                Err(e) => (None, Some(e.to_string())),
+            },
+            Err(e) => (None, Some(e.to_string())),
+        };
+        results.push(DelayTargetResult {
+            name: target.name,
+            url: target.url,
+            delay,
+            error,
+        });
+    }
+    results
+}
+
+async fn list_proxies(
+    client: &reqwest::Client,
+    endpoint: &SandboxEndpoint,
+) -> Result<Vec<(String, String)>> {
+    let proxies: serde_json::Value = client
+        .get(format!("http://{}/proxies", endpoint.controller))
+        .bearer_auth(&endpoint.secret)
+        .send()
+        .await?
+        .json()
+        .await?;
+    let mut nodes: Vec<(String, String)> = proxies
+        .get("proxies")
+        .and_then(|proxies| proxies.as_object())
+        .map(|proxies| {
+            proxies
+                .iter()
+                .filter_map(|(name, proxy)| {
+                    let proxy_type = proxy.get("type")?.as_str()?;
+                    (!NON_NODE_TYPES.contains(&proxy_type))
+                        .then(|| (name.clone(), proxy_type.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    nodes.sort();
+    Ok(nodes)
+}
+
+/// 均匀抽取部分节点测试延迟
+async fn probe_proxies(
+    client: &reqwest::Client,
+    endpoint: &SandboxEndpoint,
+    proxies: Vec<(String, String)>,
+) -> Vec<SandboxProxyDelay> {
+    let step = proxies.len().div_ceil(MAX_PROBED_PROXIES).max(1);
+    let test_url = delay_targets::configured_targets()
+        .into_iter()
+        .next()
+        .map(|target| target.url)
+        .unwrap_or_default();
+
+    stream::iter(proxies.into_iter().step_by(step))
+        .map(|(name, proxy_type)| {
+            let test_url = test_url.clone();
+            async move {
+                let delay = proxy_delay(client, endpoint, &name, &test_url).await;
+                SandboxProxyDelay {
+                    name,
+                    proxy_type,
+                    delay,
+                }
+            }
+        })
+        .buffer_unordered(PROBE_CONCURRENCY)
+        .collect()
+        .await
+}
+
+async fn proxy_delay(
+    client: &reqwest::Client,
+    endpoint: &SandboxEndpoint,
+    name: &str,
+    test_url: &str,
+) -> Option<u64> {
+    let mut url = reqwest::Url::parse(&format!("http://{}/", endpoint.controller)).ok()?;
+    url.path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .extend(["proxies", name, "delay"]);
+    url.query_pairs_mut()
+        .append_pair("url", test_url)
+        .append_pair("timeout", &PROBE_TIMEOUT_MS.to_string());
+    let response: serde_json::Value = client
+        .get(url)
+        .bearer_auth(&endpoint.secret)
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    response
+        .get("delay")
+        .and_then(|delay| delay.as_u64())
+        .filter(|delay| *delay > 0)
+}
@@ -740,11 +740,21 @@ pub(super) async fn start_with_existing_service(config_file: &PathBuf) -> Result
     log::info!(target:"app", "尝试使用现有服务启动核心 (IPC)");
     // logging!(info, Type::Service, true, "尝试使用现有服务启动核心");
 
-    let clash_core = Config::verge().await.latest_ref().get_valid_clash_core();
-
-    let bin_ext = if cfg!(windows) { ".exe" } else { "" };
-    let clash_bin = format!("{clash_core}{bin_ext}");
-    let bin_path = current_exe()?.with_file_name(clash_bin);
+    let (clash_core, bin_path) = match crate::cmd::core_registry::resolve_core_binary().await {
+        // 订阅固定了内置内核时使用该内核
+        Some(core) if core.bundled => (core.core_type, core.path),
+        // 已下载的内核位于用户可写目录，不能交给以 root/SYSTEM 运行的服务启动
+        Some(core) => bail!(
+            "服务模式不支持已下载的内核 {}，请使用 Sidecar 模式",
+            core.path.display()
+        ),
+        None => {
+            let clash_core = Config::verge().await.latest_ref().get_valid_clash_core();
+            let bin_ext = if cfg!(windows) { ".exe" } else { "" };
+            let clash_bin = format!("{clash_core}{bin_ext}");
+            (clash_core, current_exe()?.with_file_name(clash_bin))
+        }
+    };
     let bin_path = dirs::path_to_str(&bin_path)?;
 
     let config_dir = dirs::app_home_dir()?;
@@ -900,7 +910,7 @@ pub(super) async fn run_core_by_service(config_file: &PathBuf) -> Result<()> {
 }
 
 /// 通过服务停止core
-pub(super) async fn stop_core_by_service() -> Result<()> {
+pub(crate) async fn stop_core_by_service() -> Result<()> {
     logging!(info, Type::Service, true, "通过服务停止核心 (IPC)");
 
     let payload = serde_json::json!({});
@@ -985,3 +995,522 @@ pub async fn force_reinstall_service() -> Result<()> {
         }
     }
 }
+
+/// 系统服务管理器中注册的服务名称
+/// Windows 与 packages/windows/installer.nsi 中的名称一致，其余平台由 install-service 注册
+#[cfg(target_os = "windows")]
+const OS_SERVICE_NAME: &str = "Liebesu_Clash";
+#[cfg(target_os = "linux")]
+const OS_SERVICE_NAME: &str = "clash_verge_service";
+#[cfg(target_os = "macos")]
+const OS_SERVICE_NAME: &str = "io.github.clash-verge-rev.clash-verge-rev.service";
+
+/// 服务诊断发现的问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceIssue {
+    BinaryMissing,
+    InstallerMissing,
+    PermissionDenied,
+    NotInstalled,
+    NotRunning,
+    IpcUnreachable,
+    VersionMismatch,
+}
+
+/// 修复动作，按需执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceFix {
+    FixPermissions,
+    Install,
+    Start,
+    Reinstall,
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceDiagnosticStep {
+    pub check: &'static str, // binary / installer / permissions / os_service / ipc / version
+    pub passed: bool,
+    pub reason: Option<ServiceIssue>,
+    pub detail: String,
+}
+
+/// 服务诊断结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceDiagnostics {
+    pub healthy: bool,
+    pub version: Option<String>,
+    pub required_version: String,
+    pub steps: Vec<ServiceDiagnosticStep>,
+    pub fixes: Vec<ServiceFix>,
+}
+
+impl ServiceDiagnostics {
+    fn has(&self, issue: ServiceIssue) -> bool {
+        self.steps.iter().any(|step| step.reason == Some(issue))
+    }
+}
+
+/// 系统服务管理器中的服务状态
+enum OsServiceState {
+    Running,
+    Stopped,
+    NotInstalled,
+    Unknown(String),
+}
+
+/// 诊断服务：检查服务文件、权限、系统服务状态、IPC 连通性和版本
+pub async fn diagnose_service() -> ServiceDiagnostics {
+    let mut steps = Vec::new();
+    let mut step = |check, reason: Option<ServiceIssue>, detail: String| {
+        steps.push(ServiceDiagnosticStep {
+            check,
+            passed: reason.is_none(),
+            reason,
+            detail,
+        });
+    };
+
+    let binary = service_binary_path();
+    match &binary {
+        Ok(path) if path.exists() => step("binary", None, path.display().to_string()),
+        Ok(path) => step(
+            "binary",
+            Some(ServiceIssue::BinaryMissing),
+            path.display().to_string(),
+        ),
+        Err(e) => step("binary", Some(ServiceIssue::BinaryMissing), e.to_string()),
+    }
+
+    if let Ok(path) = &binary {
+        let installer = installer_path(path);
+        if installer.exists() {
+            step("installer", None, installer.display().to_string());
+        } else {
+            step(
+                "installer",
+                Some(ServiceIssue::InstallerMissing),
+                installer.display().to_string(),
+            );
+        }
+        if path.exists() {
+            match is_executable(path) {
+                Ok(true) => step("permissions", None, "executable".into()),
+                Ok(false) => step(
+                    "permissions",
+                    Some(ServiceIssue::PermissionDenied),
+                    "not executable".into(),
+                ),
+                Err(e) => step(
+                    "permissions",
+                    Some(ServiceIssue::PermissionDenied),
+                    e.to_string(),
+                ),
+            }
+        }
+    }
+
+    match query_os_service() {
+        OsServiceState::Running => step("os_service", None, "running".into()),
+        OsServiceState::Stopped => step(
+            "os_service",
+            Some(ServiceIssue::NotRunning),
+            "stopped".into(),
+        ),
+        OsServiceState::NotInstalled => step(
+            "os_service",
+            Some(ServiceIssue::NotInstalled),
+            "not installed".into(),
+        ),
+        // 无法查询时不判定，交由 IPC 检查
+        OsServiceState::Unknown(detail) => step("os_service", None, detail),
+    }
+
+    let version = match check_service_version().await {
+        Ok(version) => {
+            step("ipc", None, "reachable".into());
+            if version == REQUIRED_SERVICE_VERSION {
+                step("version", None, version.clone());
+            } else {
+                step(
+                    "version",
+                    Some(ServiceIssue::VersionMismatch),
+                    format!("{version} != {REQUIRED_SERVICE_VERSION}"),
+                );
+            }
+            Some(version)
+        }
+        Err(e) => {
+            step("ipc", Some(ServiceIssue::IpcUnreachable), e.to_string());
+            None
+        }
+    };
+
+    let mut diagnostics = ServiceDiagnostics {
+        healthy: steps.iter().all(|step| step.passed),
+        version,
+        required_version: REQUIRED_SERVICE_VERSION.to_string(),
+        steps,
+        fixes: Vec::new(),
+    };
+    diagnostics.fixes = plan_fixes(&diagnostics);
+    logging!(
+        info,
+        Type::Service,
+        true,
+        "服务诊断完成: healthy={}, fixes={:?}",
+        diagnostics.healthy,
+        diagnostics.fixes
+    );
+    diagnostics
+}
+
+/// 根据诊断结果执行需要的修复，修复后重新诊断确认
+pub async fn repair_service() -> Result<()> {
+    let diagnostics = diagnose_service().await;
+    if diagnostics.healthy {
+        logging!(info, Type::Service, true, "服务状态正常，无需修复");
+        return Ok(());
+    }
+    if diagnostics.has(ServiceIssue::BinaryMissing)
+        || diagnostics.has(ServiceIssue::InstallerMissing)
+    {
+        bail!("服务文件缺失，请重新安装应用");
+    }
+
+    for fix in &diagnostics.fixes {
+        logging!(info, Type::Service, true, "执行服务修复: {:?}", fix);
+        match fix {
+            ServiceFix::FixPermissions => fix_permissions()?,
+            ServiceFix::Install => install_service().await?,
+            ServiceFix::Start => start_os_service().await?,
+            ServiceFix::Reinstall => force_reinstall_service().await?,
+        }
+    }
+
+    let after = diagnose_service().await;
+    if !after.healthy {
+        let issues: Vec<String> = after
+            .steps
+            .iter()
+            .filter_map(|step| step.reason.map(|reason| format!("{reason:?}")))
+            .collect();
+        bail!("修复后服务仍存在问题: {}", issues.join(", "));
+    }
+    Ok(())
+}
+
+/// 由诊断结果推导修复动作：重装可以覆盖其他问题，因此只在必要时重装
+fn plan_fixes(diagnostics: &ServiceDiagnostics) -> Vec<ServiceFix> {
+    if diagnostics.healthy
+        || diagnostics.has(ServiceIssue::BinaryMissing)
+        || diagnostics.has(ServiceIssue::InstallerMissing)
+    {
+        return Vec::new();
+    }
+
+    let mut fixes = Vec::new();
+    if diagnostics.has(ServiceIssue::PermissionDenied) {
+        fixes.push(ServiceFix::FixPermissions);
+    }
+    if diagnostics.has(ServiceIssue::VersionMismatch) {
+        fixes.push(ServiceFix::Reinstall);
+    } else if diagnostics.has(ServiceIssue::NotInstalled) {
+        fixes.push(ServiceFix::Install);
+    } else if diagnostics.has(ServiceIssue::NotRunning) {
+        fixes.push(ServiceFix::Start);
+    } else if diagnostics.has(ServiceIssue::IpcUnreachable) {
+        // 系统服务显示运行中但无法通信
+        fixes.push(ServiceFix::Reinstall);
+    }
+    fixes
+}
+
+/// 服务程序路径，Linux 下与主程序放在一起
+#[cfg(target_os = "linux")]
+fn service_binary_path() -> Result<PathBuf> {
+    Ok(current_exe()?.with_file_name("clash-verge-service"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn service_binary_path() -> Result<PathBuf> {
+    dirs::service_path()
+}
+
+fn installer_path(binary: &std::path::Path) -> PathBuf {
+    let name = if cfg!(windows) {
+        "install-service.exe"
+    } else {
+        "install-service"
+    };
+    binary.with_file_name(name)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(std::fs::metadata(path)?.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+fn is_executable(path: &std::path::Path) -> Result<bool> {
+    std::fs::File::open(path)?;
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn fix_permissions() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = service_binary_path()?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("无法修改服务文件权限: {}", path.display()))
+}
+
+#[cfg(windows)]
+fn fix_permissions() -> Result<()> {
+    bail!("服务文件无法访问，请以管理员身份重新安装应用")
+}
+
+#[cfg(target_os = "windows")]
+fn query_os_service() -> OsServiceState {
+    use std::os::windows::process::CommandExt;
+    match StdCommand::new("sc")
+        .args(["query", OS_SERVICE_NAME])
+        .creation_flags(0x08000000)
+        .output()
+    {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if stdout.contains("RUNNING") {
+                OsServiceState::Running
+            } else if stdout.contains("STOPPED") || stdout.contains("PAUSED") {
+                OsServiceState::Stopped
+            } else if output.status.code() == Some(1060) {
+                // ERROR_SERVICE_DOES_NOT_EXIST
+                OsServiceState::NotInstalled
+            } else {
+                OsServiceState::Unknown(stdout.trim().to_string())
+            }
+        }
+        Err(e) => OsServiceState::Unknown(e.to_string()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn query_os_service() -> OsServiceState {
+    match StdCommand::new("systemctl")
+        .args(["show", "-p", "LoadState,ActiveState", OS_SERVICE_NAME])
+        .output()
+    {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if stdout.contains("LoadState=not-found") {
+                OsServiceState::NotInstalled
+            } else if stdout.contains("ActiveState=active") {
+                OsServiceState::Running
+            } else if stdout.contains("ActiveState=") {
+                OsServiceState::Stopped
+            } else {
+                OsServiceState::Unknown(stdout.trim().to_string())
+            }
+        }
+        Err(e) => OsServiceState::Unknown(e.to_string()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn query_os_service() -> OsServiceState {
+    match StdCommand::new("launchctl")
+        .args(["print", &format!("system/{OS_SERVICE_NAME}")])
+        .output()
+    {
+        Ok(output) if !output.status.success() => OsServiceState::NotInstalled,
+        Ok(output) => {
+            if String::from_utf8_lossy(&output.stdout).contains("state = running") {
+                OsServiceState::Running
+            } else {
+                OsServiceState::Stopped
+            }
+        }
+        Err(e) => OsServiceState::Unknown(e.to_string()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn start_os_service() -> Result<()> {
+    use runas::Command as RunasCommand;
+    let status = RunasCommand::new("sc")
+        .args(&["start", OS_SERVICE_NAME])
+        .show(false)
+        .status()?;
+    if !status.success() {
+        bail!("启动服务失败: {}", status.code().unwrap_or(-1));
+    }
+    Ok(())
+}
+
+#[allow(clippy::unused_async)]
+#[cfg(target_os = "linux")]
+async fn start_os_service() -> Result<()> {
+    use users::get_effective_uid;
+    let status = match get_effective_uid() {
+        0 => StdCommand::new("systemctl")
+            .args(["start", OS_SERVICE_NAME])
+            .status()?,
+        _ => StdCommand::new(crate::utils::help::linux_elevator())
+            .args(["systemctl", "start", OS_SERVICE_NAME])
+            .status()?,
+    };
+    if !status.success() {
+        bail!("启动服务失败: {}", status.code().unwrap_or(-1));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+async fn start_os_service() -> Result<()> {
+    use crate::utils::i18n::t;
+    let prompt = t("Service Administrator Prompt").await;
+    let command = format!(
+        r#"do shell script "launchctl kickstart -k system/{OS_SERVICE_NAME}" with administrator privileges with prompt "{prompt}""#
+    );
+    let status = StdCommand::new("osascript")
+        .args(["-e", &command])
+        .status()?;
+    if !status.success() {
+        bail!("启动服务失败: {}", status.code().unwrap_or(-1));
+    }
+    Ok(())
+}
+
+/// 内核二进制的 capabilities 状态
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreCapabilityStatus {
+    pub path: String,
+    pub granted: bool,
+}
+
+/// TUN 所需的 capabilities
+#[cfg(target_os = "linux")]
+const CORE_CAPABILITIES: &str = "cap_net_admin,cap_net_bind_service=+eip";
+
+/// 不安装完整服务，通过 pkexec 为内核二进制授予 TUN 所需的 capabilities，
+/// 成功后优先使用 sidecar 模式启动
+#[cfg(target_os = "linux")]
+pub async fn setup_linux_capabilities() -> Result<Vec<CoreCapabilityStatus>> {
+    use users::get_effective_uid;
+
+    let mut binaries: Vec<PathBuf> = crate::config::IVerge::VALID_CLASH_CORES
+        .iter()
+        .map(|core| current_exe().map(|exe| exe.with_file_name(core)))
+        .collect::<Result<_, _>>()?;
+    if let Some(core) = crate::cmd::core_registry::resolve_core_binary().await
+        && !binaries.contains(&core.path)
+    {
+        binaries.push(core.path);
+    }
+    binaries.retain(|path| path.exists());
+    if binaries.is_empty() {
+        bail!("未找到内核文件");
+    }
+
+    let pending: Vec<&PathBuf> = binaries
+        .iter()
+        .filter(|path| !has_core_capabilities(path))
+        .collect();
+    if !pending.is_empty() {
+        let script = pending
+            .iter()
+            .map(|path| {
+                format!(
+                    "setcap {CORE_CAPABILITIES} '{}'",
+                    path.to_string_lossy().replace('\'', r"'\''")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" && ");
+        logging!(
+            info,
+            Type::Service,
+            true,
+            "授予内核 capabilities: {}",
+            script
+        );
+
+        let status = match get_effective_uid() {
+            0 => StdCommand::new("sh").arg("-c").arg(&script).status()?,
+            _ => StdCommand::new(crate::utils::help::linux_elevator())
+                .arg("sh")
+                .arg("-c")
+                .arg(&script)
+                .status()?,
+        };
+        if !status.success() {
+            bail!(
+                "failed to set capabilities with status {}",
+                status.code().unwrap_or(-1)
+            );
+        }
+    }
+
+    let result: Vec<CoreCapabilityStatus> = binaries
+        .iter()
+        .map(|path| CoreCapabilityStatus {
+            path: path.to_string_lossy().to_string(),
+            granted: has_core_capabilities(path),
+        })
+        .collect();
+    if result.iter().any(|status| !status.granted) {
+        bail!("部分内核未能获得 capabilities，文件系统可能不支持扩展属性");
+    }
+
+    let mut state = ServiceState::get().await;
+    state.prefer_sidecar = true;
+    state.last_error = None;
+    state.save().await?;
+    Ok(result)
+}
+
+#[cfg(not(target_os = "linux"))]
+#[allow(clippy::unused_async)]
+pub async fn setup_linux_capabilities() -> Result<Vec<CoreCapabilityStatus>> {
+    bail!("仅 Linux 支持通过 capabilities 启用 TUN")
+}
+
+/// 当前使用的内核是否已获得 TUN 所需的 capabilities，此时 sidecar 模式即可启用 TUN
+#[cfg(target_os = "linux")]
+pub async fn core_has_tun_capabilities() -> bool {
+    let path = match crate::cmd::core_registry::resolve_core_binary().await {
+        Some(core) => core.path,
+        None => {
+            let core = Config::verge().await.latest_ref().get_valid_clash_core();
+            match current_exe() {
+                Ok(exe) => exe.with_file_name(core),
+                Err(_) => return false,
+            }
+        }
+    };
+    has_core_capabilities(&path)
+}
+
+#[cfg(not(target_os = "linux"))]
+#[allow(clippy::unused_async)]
+pub async fn core_has_tun_capabilities() -> bool {
+    false
+}
+
+/// 用户偏好 sidecar 模式（拒绝或无法安装服务）且内核已获得 capabilities 时，不再通过服务启动
+pub async fn prefers_sidecar() -> bool {
+    ServiceState::get().await.prefer_sidecar && core_has_tun_capabilities().await
+}
+
+#[cfg(target_os = "linux")]
+fn has_core_capabilities(path: &std::path::Path) -> bool {
+    StdCommand::new("getcap")
+        .arg(path)
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("cap_net_admin"))
+        .unwrap_or(false)
+}
@@ -7,7 +7,8 @@ use crate::{
     utils::logging::Type,
 };
 use anyhow::Result;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{net::IpAddr, sync::Arc};
 #[cfg(not(target_os = "windows"))]
 use sysproxy::{Autoproxy, Sysproxy};
 use tauri::async_runtime::Mutex as TokioMutex;
@@ -26,29 +27,292 @@ static DEFAULT_BYPASS: &str =
 #[cfg(target_os = "macos")]
 static DEFAULT_BYPASS: &str = "127.0.0.1,192.168.0.0/16,10.0.0.0/8,172.16.0.0/12,172.29.0.0/16,localhost,*.local,*.crashlytics.com,<local>";
 
-async fn get_bypass() -> String {
-    let use_default = Config::verge()
-        .await
-        .latest_ref()
-        .use_default_bypass
-        .unwrap_or(true);
-    let res = {
+/// 代理排除规则类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BypassKind {
+    Local,    // <local>，不含点的主机名
+    Domain,   // example.com
+    Wildcard, // *.example.com、192.168.*
+    Ip,       // 127.0.0.1、::1
+    Cidr,     // 10.0.0.0/8
+}
+
+/// 代理排除规则
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BypassRule {
+    pub value: String,
+    pub kind: BypassKind,
+}
+
+impl BypassRule {
+    /// 解析并校验单条排除规则
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let invalid = || format!("无效的排除规则: {value}");
+        let rule = |value: &str, kind| Self {
+            value: value.to_string(),
+            kind,
+        };
+
+        if value.eq_ignore_ascii_case("<local>") {
+            return Ok(rule("<local>", BypassKind::Local));
+        }
+        if let Some((ip, prefix)) = value.split_once('/') {
+            let ip: IpAddr = ip.parse().map_err(|_| invalid())?;
+            let prefix: u8 = prefix.parse().map_err(|_| invalid())?;
+            let max = if ip.is_ipv4() { 32 } else { 128 };
+            if prefix > max {
+                return Err(invalid());
+            }
+            return Ok(rule(value, BypassKind::Cidr));
+        }
+        if value.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+            return Ok(rule(value, BypassKind::Ip));
+        }
+
+        // ".example.com" 与 "*.example.com" 含义相同
+        let normalized = match value.strip_prefix('.') {
+            Some(rest) => format!("*.{rest}"),
+            None => value.to_string(),
+        };
+        let valid_labels = !normalized.is_empty()
+            && normalized.split('.').all(|label| {
+                !label.is_empty()
+                    && label
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '*'))
+            });
+        if !valid_labels {
+            return Err(invalid());
+        }
+        let kind = if normalized.contains('*') {
+            BypassKind::Wildcard
+        } else {
+            BypassKind::Domain
+        };
+        Ok(Self {
+            value: normalized.to_lowercase(),
+            kind,
+        })
+    }
+}
+
+/// 解析排除列表字符串，跳过无效条目
+pub fn parse_bypass_list(list: &str) -> Vec<BypassRule> {
+    list.split([',', ';', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| match BypassRule::parse(s) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                logging!(warn, Type::Core, true, "{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// 默认排除规则
+pub fn default_bypass_rules() -> Vec<BypassRule> {
+    parse_bypass_list(DEFAULT_BYPASS)
+}
+
+/// 合并默认规则与自定义规则
+pub(crate) async fn bypass_rules() -> Vec<BypassRule> {
+    let (use_default, custom_bypass) = {
         let verge = Config::verge().await;
         let verge = verge.latest_ref();
-        verge.system_proxy_bypass.clone()
-    };
-    let custom_bypass = match res {
-        Some(bypass) => bypass,
-        None => "".to_string(),
+        (
+            verge.use_default_bypass.unwrap_or(true),
+            verge.system_proxy_bypass.clone().unwrap_or_default(),
+        )
     };
 
-    if custom_bypass.is_empty() {
-        DEFAULT_BYPASS.to_string()
-    } else if use_default {
-        format!("{DEFAULT_BYPASS},{custom_bypass}")
+    let custom = parse_bypass_list(&custom_bypass);
+    let mut rules = if custom.is_empty() || use_default {
+        default_bypass_rules()
     } else {
-        custom_bypass
+        Vec::new()
+    };
+    for rule in custom {
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+    rules
+}
+
+/// 单个系统代理后端最近一次应用的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendApplyResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SysproxyBackendStatus {
+    /// gsettings | kde | env-file
+    pub backend: String,
+    /// 系统中存在该机制所需的工具或目录
+    pub available: bool,
+    /// 应用系统代理时是否使用该机制
+    pub active: bool,
+    pub detail: Option<String>,
+    pub last_apply: Option<BackendApplyResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SysproxyBackendReport {
+    pub desktop: Option<String>,
+    pub backends: Vec<SysproxyBackendStatus>,
+}
+
+/// 系统代理后端状态，目前仅 Linux 存在多种机制，其他平台返回空列表
+pub fn sysproxy_backend_report() -> SysproxyBackendReport {
+    #[cfg(target_os = "linux")]
+    {
+        crate::core::linux_sysproxy::backend_report()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        SysproxyBackendReport::default()
+    }
+}
+
+/// 按当前平台的格式生成排除列表
+pub(crate) async fn get_bypass() -> String {
+    render_bypass(&bypass_rules().await)
+}
+
+/// 应用系统代理与自动代理，先关闭再开启，避免切换时两者同时生效
+/// Linux 上按桌面环境分别写入 gsettings、KDE 或会话环境变量
+#[cfg(not(target_os = "windows"))]
+fn apply_proxy(sys: &Sysproxy, auto: &Autoproxy) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::core::linux_sysproxy::apply(sys, auto)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        if auto.enable {
+            sys.set_system_proxy()?;
+            auto.set_auto_proxy()?;
+        } else {
+            auto.set_auto_proxy()?;
+            sys.set_system_proxy()?;
+        }
+        Ok(())
+    }
+}
+
+/// Windows 注册表使用分号分隔，不支持 CIDR，需展开为通配符
+#[cfg(target_os = "windows")]
+pub fn render_bypass(rules: &[BypassRule]) -> String {
+    let mut entries: Vec<String> = Vec::new();
+    for rule in rules {
+        let rendered = match rule.kind {
+            BypassKind::Cidr => cidr_to_wildcards(&rule.value),
+            _ => vec![rule.value.clone()],
+        };
+        for entry in rendered {
+            if !entries.contains(&entry) {
+                entries.push(entry);
+            }
+        }
+    }
+    entries.join(";")
+}
+
+/// macOS networksetup 与 Linux GNOME/KDE 使用逗号分隔，支持 CIDR，不支持 IP 通配符
+#[cfg(not(target_os = "windows"))]
+pub fn render_bypass(rules: &[BypassRule]) -> String {
+    let mut entries: Vec<String> = Vec::new();
+    for rule in rules {
+        let rendered = match rule.kind {
+            // macOS 没有 <local>，以 *.local 代替；GNOME 不识别该写法
+            #[cfg(target_os = "macos")]
+            BypassKind::Local => Some("*.local".to_string()),
+            #[cfg(not(target_os = "macos"))]
+            BypassKind::Local => None,
+            BypassKind::Wildcard => {
+                Some(wildcard_to_cidr(&rule.value).unwrap_or(rule.value.clone()))
+            }
+            _ => Some(rule.value.clone()),
+        };
+        if let Some(entry) = rendered
+            && !entries.contains(&entry)
+        {
+            entries.push(entry);
+        }
+    }
+    entries.join(",")
+}
+
+/// 将 IPv4 CIDR 展开为 Windows 通配符，例如 172.16.0.0/12 展开为 172.16.* 至 172.31.*
+#[cfg(target_os = "windows")]
+fn cidr_to_wildcards(cidr: &str) -> Vec<String> {
+    let Some((ip, prefix)) = cidr.split_once('/') else {
+        return Vec::new();
+    };
+    let (Ok(std::net::IpAddr::V4(ip)), Ok(prefix)) = (ip.parse::<IpAddr>(), prefix.parse::<u8>())
+    else {
+        logging!(
+            debug,
+            Type::Core,
+            true,
+            "Windows 不支持 IPv6 CIDR 排除规则: {}",
+            cidr
+        );
+        return Vec::new();
+    };
+    let octets = ip.octets();
+    let full = usize::from(prefix / 8);
+    let join = |parts: &[u8], wildcard: bool| {
+        let mut s: Vec<String> = parts.iter().map(u8::to_string).collect();
+        if wildcard {
+            s.push("*".to_string());
+        }
+        s.join(".")
+    };
+
+    if prefix.is_multiple_of(8) {
+        return vec![join(&octets[..full], full < 4)];
+    }
+    let span = 1u16 << (8 - prefix % 8);
+    let start = octets[full] & !((span - 1) as u8);
+    (0..span)
+        .map(|i| {
+            let mut parts = octets[..full].to_vec();
+            parts.push(start + i as u8);
+            join(&parts, full < 3)
+        })
+        .collect()
+}
+
+/// 将 192.168.* 形式的 IP 通配符转换为 CIDR
+#[cfg(not(target_os = "windows"))]
+fn wildcard_to_cidr(wildcard: &str) -> Option<String> {
+    let prefix = wildcard.strip_suffix(".*")?;
+    let octets: Vec<u8> = prefix
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    if octets.is_empty() || octets.len() > 3 {
+        return None;
     }
+    let mut full = octets.clone();
+    full.resize(4, 0);
+    Some(format!(
+        "{}.{}.{}.{}/{}",
+        full[0],
+        full[1],
+        full[2],
+        full[3],
+        octets.len() * 8
+    ))
 }
 
 impl Default for Sysopt {
@@ -113,8 +377,7 @@ impl Sysopt {
             };
 
             if !sys_enable {
-                sys.set_system_proxy()?;
-                auto.set_auto_proxy()?;
+                apply_proxy(&sys, &auto)?;
                 let proxy_manager = EventDrivenProxyManager::global();
                 proxy_manager.notify_config_changed();
                 return Ok(());
@@ -123,8 +386,7 @@ impl Sysopt {
             if pac_enable {
                 sys.enable = false;
                 auto.enable = true;
-                sys.set_system_proxy()?;
-                auto.set_auto_proxy()?;
+                apply_proxy(&sys, &auto)?;
                 let proxy_manager = EventDrivenProxyManager::global();
                 proxy_manager.notify_config_changed();
                 return Ok(());
@@ -133,8 +395,7 @@ impl Sysopt {
             if sys_enable {
                 auto.enable = false;
                 sys.enable = true;
-                auto.set_auto_proxy()?;
-                sys.set_system_proxy()?;
+                apply_proxy(&sys, &auto)?;
                 let proxy_manager = EventDrivenProxyManager::global();
                 proxy_manager.notify_config_changed();
                 return Ok(());
@@ -217,8 +478,7 @@ impl Sysopt {
             };
             sysproxy.enable = false;
             autoproxy.enable = false;
-            autoproxy.set_auto_proxy()?;
-            sysproxy.set_system_proxy()?;
+            apply_proxy(&sysproxy, &autoproxy)?;
         }
 
         #[cfg(target_os = "windows")]
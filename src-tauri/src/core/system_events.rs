@@ -0,0 +1,239 @@
+use crate::{
+    config::Config,
+    core::{
+        CoreManager,
+        captive_portal::{self, ProbeOutcome},
+        handle,
+        vpn_detect::{self, VpnState},
+    },
+    logging, logging_error,
+    process::AsyncHandler,
+    singleton,
+    utils::logging::Type,
+};
+use futures::StreamExt;
+use if_watch::tokio::IfWatcher;
+use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, SystemTime},
+};
+use tauri::Emitter;
+use tokio::sync::broadcast;
+
+/// 墙上时钟检查间隔，睡眠恢复没有跨平台的系统通知，通过时钟跳变判断
+const CLOCK_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// 网络变化通知通常成批到达，静默该时长后再比较接口状态
+const NETWORK_SETTLE: Duration = Duration::from_secs(2);
+/// 墙上时间跳变超过该阈值视为从睡眠中恢复
+const SLEEP_DETECT_THRESHOLD: Duration = Duration::from_secs(30);
+/// 保留的最近事件数量
+const MAX_RECENT_EVENTS: usize = 50;
+
+/// 系统事件类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SystemEventKind {
+    NetworkInterfaceChanged, // 网络接口变化
+    ResumedFromSleep,        // 从睡眠中恢复
+    CaptivePortalDetected,   // 检测到强制门户
+    VpnConnected,            // 其他 VPN 已连接
+    VpnDisconnected,         // 其他 VPN 已断开
+}
+
+/// 系统事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemEvent {
+    pub kind: SystemEventKind,
+    pub timestamp: i64,
+    pub detail: Option<String>,
+}
+
+/// 系统事件监听器
+/// 订阅系统的网络地址变化通知（netlink / SCDynamicStore / NotifyIpInterfaceChange）检测网络与 VPN 变化，
+/// 通过墙上时钟跳变检测睡眠恢复，并广播给订阅者
+pub struct SystemEventMonitor {
+    sender: broadcast::Sender<SystemEvent>,
+    started: AtomicBool,
+    recent: RwLock<VecDeque<SystemEvent>>,
+}
+
+singleton!(SystemEventMonitor, SYSTEM_EVENT_MONITOR);
+
+impl SystemEventMonitor {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(64);
+        Self {
+            sender,
+            started: AtomicBool::new(false),
+            recent: RwLock::new(VecDeque::with_capacity(MAX_RECENT_EVENTS)),
+        }
+    }
+
+    /// 订阅系统事件
+    pub fn subscribe(&self) -> broadcast::Receiver<SystemEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 获取最近的系统事件
+    pub fn recent_events(&self) -> Vec<SystemEvent> {
+        self.recent.read().iter().cloned().collect()
+    }
+
+    /// 发布系统事件
+    pub fn publish(&self, kind: SystemEventKind, detail: Option<String>) {
+        let event = SystemEvent {
+            kind,
+            timestamp: chrono::Utc::now().timestamp(),
+            detail,
+        };
+
+        logging!(info, Type::System, true, "系统事件: {:?}", event);
+
+        {
+            let mut recent = self.recent.write();
+            if recent.len() >= MAX_RECENT_EVENTS {
+                recent.pop_front();
+            }
+            recent.push_back(event.clone());
+        }
+
+        if let Some(app_handle) = handle::Handle::global().app_handle() {
+            let _ = app_handle.emit("verge://system-event", &event);
+        }
+
+        // 没有订阅者时发送会失败，可以忽略
+        let _ = self.sender.send(event);
+    }
+
+    /// 启动事件监听
+    pub fn start(&'static self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        AsyncHandler::spawn(move || async move { self.watch_network().await });
+
+        AsyncHandler::spawn(move || async move {
+            let mut last_wall = SystemTime::now();
+            loop {
+                tokio::time::sleep(CLOCK_CHECK_INTERVAL).await;
+                if handle::Handle::global().is_exiting() {
+                    break;
+                }
+
+                let now = SystemTime::now();
+                let elapsed = now.duration_since(last_wall).unwrap_or_default();
+                last_wall = now;
+                if elapsed > CLOCK_CHECK_INTERVAL + SLEEP_DETECT_THRESHOLD {
+                    self.publish(
+                        SystemEventKind::ResumedFromSleep,
+                        Some(format!("挂起约 {} 秒", elapsed.as_secs())),
+                    );
+                }
+            }
+        });
+
+        AsyncHandler::spawn(|| async {
+            let mut receiver = SystemEventMonitor::global().subscribe();
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.kind == SystemEventKind::ResumedFromSleep => {
+                        restart_core_after_resume().await;
+                    }
+                    Ok(event) if event.kind == SystemEventKind::CaptivePortalDetected => {
+                        captive_portal::handle_detected(event.detail.unwrap_or_default()).await;
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+impl SystemEventMonitor {
+    /// 等待系统的网络地址变化通知，接口状态确实变化时发布事件并重新检测 VPN
+    async fn watch_network(&'static self) {
+        let mut watcher = match IfWatcher::new() {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                logging!(warn, Type::System, true, "无法订阅网络变化通知: {}", e);
+                return;
+            }
+        };
+        let mut last_snapshot = network_snapshot();
+        let mut last_vpn = vpn_detect::detect().await;
+
+        while let Some(event) = watcher.next().await {
+            if let Err(e) = event {
+                logging!(warn, Type::System, true, "网络变化通知出错: {}", e);
+                continue;
+            }
+            while let Ok(Some(_)) = tokio::time::timeout(NETWORK_SETTLE, watcher.next()).await {}
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+
+            let snapshot = network_snapshot();
+            if snapshot == last_snapshot {
+                continue;
+            }
+            last_snapshot = snapshot;
+            self.publish(SystemEventKind::NetworkInterfaceChanged, None);
+
+            let vpn = vpn_detect::detect().await;
+            if vpn != last_vpn {
+                self.publish_vpn_change(&last_vpn, &vpn);
+                last_vpn = vpn;
+            }
+
+            if let ProbeOutcome::Portal(detail) = captive_portal::probe().await {
+                self.publish(SystemEventKind::CaptivePortalDetected, Some(detail));
+            }
+        }
+    }
+
+    fn publish_vpn_change(&self, previous: &VpnState, current: &VpnState) {
+        match (previous.is_active(), current.is_active()) {
+            (_, true) => self.publish(SystemEventKind::VpnConnected, Some(current.describe())),
+            (true, false) => {
+                self.publish(SystemEventKind::VpnDisconnected, Some(previous.describe()))
+            }
+            (false, false) => {}
+        }
+    }
+}
+
+/// 生成网络接口快照，用于检测变化
+fn network_snapshot() -> Vec<String> {
+    let mut snapshot: Vec<String> = NetworkInterface::show()
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|iface| {
+            iface
+                .addr
+                .into_iter()
+                .map(move |addr| format!("{}:{}", iface.name, addr.ip()))
+        })
+        .collect();
+    snapshot.sort();
+    snapshot
+}
+
+/// 从睡眠恢复后按配置重启内核
+async fn restart_core_after_resume() {
+    let enabled = Config::verge()
+        .await
+        .latest_ref()
+        .auto_restart_core_on_resume
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    logging!(info, Type::Core, true, "系统从睡眠中恢复，重启内核");
+    logging_error!(Type::Core, true, CoreManager::global().restart_core().await);
+}
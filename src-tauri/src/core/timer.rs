@@ -1,7 +1,12 @@
 use crate::{
-    cmd::subscription_groups::get_favorite_subscription_uids,
+    cmd::{
+        job_manager::{JobClass, JobKind, JobPriority, run_job_with},
+        subscription_groups::get_favorite_subscription_uids,
+    },
     config::Config,
-    feat, logging, logging_error, singleton,
+    feat, logging, logging_error,
+    module::offline::OfflineMode,
+    singleton,
     state::subscription_sync::{SUBSCRIPTION_SYNC_STORE, SubscriptionSyncState, SyncPhase},
     utils::logging::Type,
 };
@@ -20,6 +25,9 @@ use std::{
 
 type TaskID = u64;
 
+/// 单次定时任务的超时时间
+const TIMER_TASK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(40);
+
 #[derive(Debug, Clone)]
 pub struct TimerTask {
     pub task_id: TaskID,
@@ -415,64 +423,60 @@ impl Timer {
     async fn async_task(uid: String) {
         let task_start = std::time::Instant::now();
         logging!(info, Type::Timer, "Running timer task for profile: {}", uid);
+        Self::emit_update_event(&uid, true);
+
+        let result = if uid.starts_with("remote-fetch-") {
+            tokio::time::timeout(TIMER_TASK_TIMEOUT, Self::sync_remote_subscriptions())
+                .await
+                .unwrap_or_else(|_| Err("timed out".to_string()))
+        } else if OfflineMode::global().updates_suppressed() {
+            logging!(info, Type::Timer, "离线模式中，跳过订阅 {} 的自动更新", uid);
+            Ok(())
+        } else {
+            let is_current = Config::profiles().await.latest_ref().current.as_ref() == Some(&uid);
+            logging!(
+                info,
+                Type::Timer,
+                "配置 {} 是否为当前激活配置: {}",
+                uid,
+                is_current
+            );
 
-        match tokio::time::timeout(std::time::Duration::from_secs(40), async {
-            Self::emit_update_event(&uid, true);
-
-            if uid.starts_with("remote-fetch-") {
-                logging!(info, Type::Timer, "执行远程订阅自动同步任务: {}", uid);
-                let handle = match crate::core::handle::Handle::global().app_handle() {
-                    Some(h) => h,
-                    None => {
-                        logging_error!(
-                            Type::Timer,
-                            false,
-                            "自动同步远程订阅失败: {}",
-                            "AppHandle 不可用"
-                        );
-                        return Ok(());
+            // 通过后台任务队列执行，与手动更新、测速等任务共享并发上限；超时只计算实际执行时间
+            let job_uid = uid.clone();
+            run_job_with(
+                format!("Update profile {uid}"),
+                JobKind::ProfileUpdate { uid: uid.clone() },
+                JobClass::Network,
+                JobPriority::Low,
+                async move {
+                    match tokio::time::timeout(
+                        TIMER_TASK_TIMEOUT,
+                        feat::update_profile(job_uid, None, Some(is_current)),
+                    )
+                    .await
+                    {
+                        Ok(result) => result.map_err(|e| e.to_string()),
+                        Err(_) => Err("timed out".to_string()),
                     }
-                };
+                },
+            )
+            .await
+        };
 
-                if let Err(err) =
-                    crate::cmd::sync_subscription_from_remote(handle, None, None).await
-                {
-                    logging_error!(Type::Timer, false, "自动同步远程订阅失败: {}", err);
-                }
-                Ok(())
-            } else {
-                let is_current =
-                    Config::profiles().await.latest_ref().current.as_ref() == Some(&uid);
+        match result {
+            Ok(()) => {
+                let duration = task_start.elapsed().as_millis();
                 logging!(
                     info,
                     Type::Timer,
-                    "配置 {} 是否为当前激活配置: {}",
+                    "Timer task completed successfully for uid: {} (took {}ms)",
                     uid,
-                    is_current
+                    duration
                 );
-
-                feat::update_profile(uid.clone(), None, Some(is_current)).await
             }
-        })
-        .await
-        {
-            Ok(result) => match result {
-                Ok(_) => {
-                    let duration = task_start.elapsed().as_millis();
-                    logging!(
-                        info,
-                        Type::Timer,
-                        "Timer task completed successfully for uid: {} (took {}ms)",
-                        uid,
-                        duration
-                    );
-                }
-                Err(e) => {
-                    logging_error!(Type::Timer, "Failed to update profile uid {}: {}", uid, e);
-                }
-            },
-            Err(_) => {
-                logging_error!(Type::Timer, false, "Timer task timed out for uid: {}", uid);
+            Err(e) => {
+                logging_error!(Type::Timer, "Failed to update profile uid {}: {}", uid, e);
             }
         }
 
@@ -480,6 +484,25 @@ impl Timer {
         Self::emit_update_event(&uid, false);
     }
 
+    /// 自动同步远程订阅
+    async fn sync_remote_subscriptions() -> Result<(), String> {
+        logging!(info, Type::Timer, "执行远程订阅自动同步任务");
+        let Some(handle) = crate::core::handle::Handle::global().app_handle() else {
+            logging_error!(
+                Type::Timer,
+                false,
+                "自动同步远程订阅失败: {}",
+                "AppHandle 不可用"
+            );
+            return Ok(());
+        };
+
+        if let Err(err) = crate::cmd::sync_subscription_from_remote(handle, None, None).await {
+            logging_error!(Type::Timer, false, "自动同步远程订阅失败: {}", err);
+        }
+        Ok(())
+    }
+
     async fn prepare_profiles(&self) -> Result<Vec<(String, SubscriptionSyncState)>> {
         let (items, current_uid) = {
             let profiles = Config::profiles().await;
@@ -3,6 +3,7 @@ use tauri::Emitter;
 use tauri::tray::TrayIconBuilder;
 #[cfg(target_os = "macos")]
 pub mod speed_rate;
+pub mod stats;
 use crate::ipc::Rate;
 use crate::module::lightweight;
 use crate::process::AsyncHandler;
@@ -13,8 +14,9 @@ use crate::{
     feat,
     ipc::IpcManager,
     logging,
-    module::lightweight::is_in_lightweight_mode,
+    module::lightweight::{ExitTrigger, is_in_lightweight_mode},
     singleton_lazy,
+    state::proxy::{CacheEndpoint, ProxyRequestCache},
     utils::{dirs::find_target_icons, i18n::t},
 };
 
@@ -23,6 +25,7 @@ use anyhow::Result;
 use futures::future::join_all;
 use parking_lot::Mutex;
 use std::{
+    collections::HashMap,
     fs,
     sync::atomic::{AtomicBool, Ordering},
     time::{Duration, Instant},
@@ -59,16 +62,23 @@ fn should_handle_tray_click() -> bool {
     }
 }
 
+/// 可在托盘中手动选择节点的代理组类型
+const SELECTABLE_GROUP_TYPES: &[&str] = &["Selector"];
+
 #[cfg(target_os = "macos")]
 pub struct Tray {
     last_menu_update: Mutex<Option<Instant>>,
     menu_updating: AtomicBool,
+    /// 节点菜单项 ID 到 (代理组, 节点) 的映射，避免名称中的下划线影响解析
+    proxy_items: Mutex<HashMap<String, (String, String)>>,
 }
 
 #[cfg(not(target_os = "macos"))]
 pub struct Tray {
     last_menu_update: Mutex<Option<Instant>>,
     menu_updating: AtomicBool,
+    /// 节点菜单项 ID 到 (代理组, 节点) 的映射，避免名称中的下划线影响解析
+    proxy_items: Mutex<HashMap<String, (String, String)>>,
 }
 
 impl TrayState {
@@ -179,6 +189,7 @@ impl Default for Tray {
         Tray {
             last_menu_update: Mutex::new(None),
             menu_updating: AtomicBool::new(false),
+            proxy_items: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -212,6 +223,23 @@ impl Tray {
         Ok(())
     }
 
+    /// 后台刷新托盘菜单，清除代理缓存以显示最新的节点选择和延迟
+    pub fn refresh_menu_async(&'static self) {
+        ProxyRequestCache::global().invalidate(CacheEndpoint::Proxies);
+        stats::TrayStats::global().invalidate();
+
+        AsyncHandler::spawn(move || async move {
+            if let Err(e) = self.update_menu().await {
+                logging!(error, Type::Tray, "Failed to refresh tray menu: {e}");
+            }
+        });
+    }
+
+    /// 托盘节点菜单项对应的代理组和节点
+    fn proxy_item(&self, id: &str) -> Option<(String, String)> {
+        self.proxy_items.lock().get(id).cloned()
+    }
+
     /// 更新托盘菜单
     pub async fn update_menu(&self) -> Result<()> {
         // 调整最小更新间隔，确保状态及时刷新
@@ -228,7 +256,8 @@ impl Tray {
             _ => {
                 let last_update = self.last_menu_update.lock();
                 if let Some(last_time) = *last_update {
-                    last_time.elapsed() >= MIN_UPDATE_INTERVAL
+                    last_time.elapsed()
+                        >= crate::utils::logging::sampling_interval(MIN_UPDATE_INTERVAL)
                 } else {
                     true
                 }
@@ -283,7 +312,7 @@ impl Tray {
         let is_lightweight_mode = is_in_lightweight_mode();
 
         // 获取代理节点
-        let proxy_nodes_data = cmd::get_proxies().await.unwrap_or_else(|e| {
+        let proxy_nodes_data = cmd::get_proxies(None).await.unwrap_or_else(|e| {
             logging!(
                 error,
                 Type::Cmd,
@@ -444,7 +473,7 @@ impl Tray {
 
         let version = env!("CARGO_PKG_VERSION");
         if let Some(tray) = app_handle.tray_by_id("main") {
-            let _ = tray.set_tooltip(Some(&format!(
+            let mut tooltip = format!(
                 "Liebesu_Clash {version}\n{}: {}\n{}: {}\n{}: {}",
                 sys_proxy_text,
                 switch_map[system_proxy],
@@ -452,7 +481,14 @@ impl Tray {
                 switch_map[tun_mode],
                 profile_text,
                 current_profile_name
-            )));
+            );
+            // 附加实时状态（速度/节点/IP）
+            let stats = stats::TrayStats::global().text();
+            if !stats.is_empty() {
+                tooltip.push('\n');
+                tooltip.push_str(&stats);
+            }
+            let _ = tray.set_tooltip(Some(&tooltip));
         } else {
             log::warn!(target: "app", "更新托盘提示失败: 托盘不存在");
         }
@@ -530,6 +566,9 @@ impl Tray {
                             feat::toggle_tun_mode(None).await;
                         }),
                         "main_window" => Box::pin(async move {
+                            if lightweight::should_stay_lightweight(ExitTrigger::Tray).await {
+                                return;
+                            }
                             if !lightweight::exit_lightweight_mode().await {
                                 WindowManager::show_main_window().await;
                             };
@@ -626,11 +665,12 @@ async fn create_tray_menu(
     };
 
     // 代理组子菜单
+    let mut proxy_items = HashMap::new();
     let proxy_submenus: Vec<Submenu<Wry>> = {
         let mut submenus = Vec::new();
 
         if let Some(proxies) = proxy_nodes_data.get("proxies").and_then(|v| v.as_object()) {
-            for (group_name, group_data) in proxies.iter() {
+            for (group_index, (group_name, group_data)) in proxies.iter().enumerate() {
                 // Filter groups based on mode
                 let should_show = match mode {
                     "global" => group_name == "GLOBAL",
@@ -641,6 +681,19 @@ async fn create_tray_menu(
                     continue;
                 }
 
+                // 只显示可手动选择且未隐藏的代理组
+                let selectable = group_data
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|t| SELECTABLE_GROUP_TYPES.contains(&t));
+                let hidden = group_data
+                    .get("hidden")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                if !selectable || hidden {
+                    continue;
+                }
+
                 let Some(all_proxies) = group_data.get("all").and_then(|v| v.as_array()) else {
                     continue;
                 };
@@ -651,26 +704,25 @@ async fn create_tray_menu(
                 let group_items: Vec<CheckMenuItem<Wry>> = all_proxies
                     .iter()
                     .filter_map(|proxy_name| proxy_name.as_str())
-                    .filter_map(|proxy_str| {
+                    .enumerate()
+                    .filter_map(|(proxy_index, proxy_str)| {
                         let is_selected = proxy_str == now_proxy;
-                        let item_id = format!("proxy_{}_{}", group_name, proxy_str);
-
-                        // Get delay for display
-                        let delay_text = proxies
+                        let item_id = format!("proxy_{group_index}_{proxy_index}");
+                        proxy_items.insert(
+                            item_id.clone(),
+                            (group_name.to_string(), proxy_str.to_string()),
+                        );
+
+                        // 最近一次测速的延迟
+                        let delay = proxies
                             .get(proxy_str)
                             .and_then(|p| p.get("history"))
                             .and_then(|h| h.as_array())
                             .and_then(|h| h.last())
                             .and_then(|r| r.get("delay"))
-                            .and_then(|d| d.as_i64())
-                            .map(|delay| match delay {
-                                -1 => "-ms".to_string(),
-                                delay if delay >= 10000 => "-ms".to_string(),
-                                _ => format!("{}ms", delay),
-                            })
-                            .unwrap_or_else(|| "-ms".to_string());
+                            .and_then(|d| d.as_i64());
 
-                        let display_text = format!("{}   | {}", proxy_str, delay_text);
+                        let display_text = format!("{}   | {}", proxy_str, latency_badge(delay));
 
                         CheckMenuItem::with_id(
                             app_handle,
@@ -728,6 +780,40 @@ async fn create_tray_menu(
 
         submenus
     };
+    *Tray::global().proxy_items.lock() = proxy_items;
+
+    // 收藏节点快速切换
+    let favorite_items: Vec<CheckMenuItem<Wry>> = {
+        let favorites = cmd::favorite_nodes::current_favorites().await;
+        let proxies = proxy_nodes_data
+            .get("proxies")
+            .and_then(|v| v.as_object())
+            .cloned()
+            .unwrap_or_default();
+        cmd::favorite_nodes::annotate_favorites(favorites, &proxies)
+            .into_iter()
+            .filter_map(|favorite| {
+                let display_text = format!(
+                    "{}. {}   | {}",
+                    favorite.slot,
+                    favorite.node,
+                    latency_badge(favorite.delay.map(|d| d as i64))
+                );
+                CheckMenuItem::with_id(
+                    app_handle,
+                    format!("favorite_{}", favorite.slot),
+                    display_text,
+                    true,
+                    !favorite.selected_in.is_empty(),
+                    hotkeys
+                        .get(&format!("switch_to_favorite:{}", favorite.slot))
+                        .map(|s| s.as_str()),
+                )
+                .map_err(|e| log::warn!(target: "app", "创建收藏节点菜单项失败: {}", e))
+                .ok()
+            })
+            .collect()
+    };
 
     // Pre-fetch all localized strings
     let dashboard_text = t("Dashboard").await;
@@ -736,6 +822,7 @@ async fn create_tray_menu(
     let direct_mode_text = t("Direct Mode").await;
     let profiles_text = t("Profiles").await;
     let proxies_text = t("Proxies").await;
+    let favorite_nodes_text = t("Favorite Nodes").await;
     let system_proxy_text = t("System Proxy").await;
     let tun_mode_text = t("TUN Mode").await;
     let lightweight_mode_text = t("LightWeight Mode").await;
@@ -817,6 +904,23 @@ async fn create_tray_menu(
         None
     };
 
+    let favorites_submenu = if !favorite_items.is_empty() {
+        let favorite_item_refs: Vec<&dyn IsMenuItem<Wry>> = favorite_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem<Wry>)
+            .collect();
+
+        Some(Submenu::with_id_and_items(
+            app_handle,
+            "favorite_nodes",
+            favorite_nodes_text,
+            true,
+            &favorite_item_refs,
+        )?)
+    } else {
+        None
+    };
+
     let system_proxy = &CheckMenuItem::with_id(
         app_handle,
         "system_proxy",
@@ -925,6 +1029,10 @@ async fn create_tray_menu(
         profiles,
     ];
 
+    if let Some(ref favorites_menu) = favorites_submenu {
+        menu_items.push(favorites_menu);
+    }
+
     // 如果有代理节点，添加代理节点菜单
     if let Some(ref proxies_menu) = proxies_submenu {
         menu_items.push(proxies_menu);
@@ -949,6 +1057,16 @@ async fn create_tray_menu(
     Ok(menu)
 }
 
+/// 延迟徽标：绿色 < 200ms，黄色 < 500ms，红色为更高延迟，未测试或超时显示 -ms
+fn latency_badge(delay: Option<i64>) -> String {
+    match delay {
+        Some(delay) if (1..200).contains(&delay) => format!("🟢 {delay}ms"),
+        Some(delay) if (200..500).contains(&delay) => format!("🟡 {delay}ms"),
+        Some(delay) if (500..10000).contains(&delay) => format!("🔴 {delay}ms"),
+        _ => "⚪ -ms".to_string(),
+    }
+}
+
 fn on_menu_event(_: &AppHandle, event: MenuEvent) {
     AsyncHandler::spawn(|| async move {
         match event.id.as_ref() {
@@ -966,7 +1084,9 @@ fn on_menu_event(_: &AppHandle, event: MenuEvent) {
             "open_window" => {
                 log::info!(target: "app", "托盘菜单点击: 打开窗口");
 
-                if !should_handle_tray_click() {
+                if !should_handle_tray_click()
+                    || lightweight::should_stay_lightweight(ExitTrigger::Tray).await
+                {
                     return;
                 }
                 if !lightweight::exit_lightweight_mode().await {
@@ -1004,13 +1124,18 @@ fn on_menu_event(_: &AppHandle, event: MenuEvent) {
                 let profile_index = &id["profiles_".len()..];
                 feat::toggle_proxy_profile(profile_index.into()).await; // Await async function
             }
+            id if id.starts_with("favorite_") => {
+                let Ok(slot) = id["favorite_".len()..].parse::<usize>() else {
+                    return;
+                };
+                if let Err(e) = cmd::favorite_nodes::switch_to_favorite(slot).await {
+                    log::error!(target: "app", "切换收藏节点失败: #{}, 错误: {}", slot, e);
+                }
+            }
             id if id.starts_with("proxy_") => {
-                // proxy_{group_name}_{proxy_name}
-                let parts: Vec<&str> = id.splitn(3, '_').collect();
-
-                if parts.len() == 3 && parts[0] == "proxy" {
-                    let group_name = parts[1];
-                    let proxy_name = parts[2];
+                if let Some((group_name, proxy_name)) = Tray::global().proxy_item(id) {
+                    let group_name = group_name.as_str();
+                    let proxy_name = proxy_name.as_str();
 
                     match cmd::proxy::update_proxy_and_sync(
                         group_name.to_string(),
@@ -0,0 +1,218 @@
+use crate::{
+    cmd,
+    config::Config,
+    core::handle,
+    ipc::get_formatted_traffic,
+    logging,
+    process::AsyncHandler,
+    singleton_lazy,
+    utils::logging::{Type, sampling_interval},
+};
+use parking_lot::Mutex;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use super::Tray;
+
+const STATS_TICK: Duration = Duration::from_secs(1);
+/// 当前节点的刷新间隔，节点切换时会主动刷新代理缓存
+const NODE_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+/// 公网 IP 的刷新间隔，节点变化时立即刷新
+const IP_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+/// 沿 now 字段追踪代理组的最大深度
+const MAX_GROUP_DEPTH: usize = 10;
+
+static STATS_STARTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Default)]
+struct StatsCache {
+    node: Option<String>,
+    node_updated: Option<Instant>,
+    ip: Option<String>,
+    ip_node: Option<String>,
+    ip_updated: Option<Instant>,
+}
+
+/// 托盘实时状态：速度、当前节点、公网 IP，显示在托盘提示和菜单栏标题中
+#[derive(Default)]
+pub struct TrayStats {
+    text: Mutex<String>,
+    in_title: AtomicBool,
+    cache: Mutex<StatsCache>,
+}
+
+singleton_lazy!(TrayStats, TRAY_STATS, TrayStats::default);
+
+impl TrayStats {
+    /// 当前显示的状态文本，未启用时为空
+    pub fn text(&self) -> String {
+        self.text.lock().clone()
+    }
+
+    async fn refresh(&self) {
+        let (items, in_title) = {
+            let verge = Config::verge().await;
+            let verge = verge.latest_ref();
+            (
+                verge.tray_stats_items.clone().unwrap_or_default(),
+                verge.tray_stats_in_title.unwrap_or(false),
+            )
+        };
+
+        let mut parts = Vec::new();
+        for item in &items {
+            match item.as_str() {
+                "speed" => parts.push(self.speed_text().await),
+                "node" => {
+                    if let Some(node) = self.active_node().await {
+                        parts.push(node);
+                    }
+                }
+                "ip" => {
+                    if let Some(ip) = self.public_ip().await {
+                        parts.push(ip);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let text = parts.join(" | ");
+
+        // 内容未变化时不重绘
+        let title_changed = self.in_title.swap(in_title, Ordering::Relaxed) != in_title;
+        if *self.text.lock() == text && !title_changed {
+            return;
+        }
+        *self.text.lock() = text.clone();
+        self.apply(&text, in_title).await;
+    }
+
+    async fn apply(&self, text: &str, in_title: bool) {
+        if let Some(tray) = handle::Handle::global()
+            .app_handle()
+            .and_then(|app_handle| app_handle.tray_by_id("main"))
+        {
+            let title = (in_title && !text.is_empty()).then_some(text);
+            let _ = tray.set_title(title);
+        }
+        if let Err(e) = Tray::global().update_tooltip().await {
+            logging!(warn, Type::Tray, "Failed to update tray stats tooltip: {e}");
+        }
+    }
+
+    async fn speed_text(&self) -> String {
+        let (up, down, _, _, is_fresh) = get_formatted_traffic().await;
+        if is_fresh {
+            format!("↑ {up}/s ↓ {down}/s")
+        } else {
+            "↑ -/s ↓ -/s".to_string()
+        }
+    }
+
+    async fn active_node(&self) -> Option<String> {
+        let expired = self
+            .cache
+            .lock()
+            .node_updated
+            .is_none_or(|at| at.elapsed() >= NODE_REFRESH_INTERVAL);
+        if expired {
+            let node = resolve_active_node().await;
+            let mut cache = self.cache.lock();
+            cache.node = node;
+            cache.node_updated = Some(Instant::now());
+        }
+        self.cache.lock().node.clone()
+    }
+
+    async fn public_ip(&self) -> Option<String> {
+        let expired = {
+            let cache = self.cache.lock();
+            cache.ip_node != cache.node
+                || cache
+                    .ip_updated
+                    .is_none_or(|at| at.elapsed() >= IP_REFRESH_INTERVAL)
+        };
+        if expired {
+            let ip = cmd::get_ip_info()
+                .await
+                .ok()
+                .and_then(|info| info.get("ip").and_then(|ip| ip.as_str()).map(String::from))
+                .filter(|ip| ip != "unknown");
+            let mut cache = self.cache.lock();
+            cache.ip = ip;
+            cache.ip_node = cache.node.clone();
+            cache.ip_updated = Some(Instant::now());
+        }
+        self.cache.lock().ip.clone()
+    }
+
+    /// 节点切换后立即刷新当前节点和公网 IP
+    pub fn invalidate(&self) {
+        let mut cache = self.cache.lock();
+        cache.node_updated = None;
+    }
+}
+
+/// 按模式找到入口代理组，沿 now 字段追踪到实际使用的节点
+async fn resolve_active_node() -> Option<String> {
+    let mode = Config::clash()
+        .await
+        .latest_ref()
+        .0
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("rule")
+        .to_string();
+    if mode == "direct" {
+        return Some("DIRECT".to_string());
+    }
+
+    let data = cmd::get_proxies(None).await.ok()?;
+    let proxies = data.get("proxies")?.as_object()?;
+    let is_group = |name: &str| proxies.get(name).and_then(|p| p.get("all")).is_some();
+
+    let mut name = if mode == "global" {
+        "GLOBAL".to_string()
+    } else {
+        proxies
+            .get("GLOBAL")?
+            .get("all")?
+            .as_array()?
+            .iter()
+            .filter_map(|v| v.as_str())
+            .find(|name| is_group(name))?
+            .to_string()
+    };
+    for _ in 0..MAX_GROUP_DEPTH {
+        match proxies
+            .get(&name)
+            .and_then(|p| p.get("now"))
+            .and_then(|v| v.as_str())
+        {
+            Some(now) if !now.is_empty() => name = now.to_string(),
+            _ => break,
+        }
+    }
+    Some(name)
+}
+
+/// 启动托盘实时状态刷新
+pub fn start_tray_stats() {
+    if STATS_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut interval = tokio::time::interval(sampling_interval(STATS_TICK));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+            TrayStats::global().refresh().await;
+        }
+    });
+}
@@ -0,0 +1,187 @@
+use crate::{logging, utils::logging::Type};
+use anyhow::Result;
+use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+use std::net::IpAddr;
+
+/// 常见 VPN 网卡名称前缀（小写比较）
+const VPN_NAME_PREFIXES: &[&str] = &[
+    "tun",
+    "tap",
+    "utun",
+    "wg",
+    "ppp",
+    "ipsec",
+    "tailscale",
+    "zt",
+    "nordlynx",
+];
+/// Windows 网卡描述中常见的 VPN 关键字（小写比较）
+const VPN_NAME_KEYWORDS: &[&str] = &["vpn", "wireguard", "tap-windows", "wintun", "openvpn"];
+
+/// 当前的 VPN 状态
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VpnState {
+    pub interfaces: Vec<String>,    // 有可路由地址的 VPN 网卡
+    pub routed_via: Option<String>, // 默认路由（含 0/1 + 128/1 拆分路由）所在的 VPN 网卡
+}
+
+impl VpnState {
+    pub fn is_active(&self) -> bool {
+        !self.interfaces.is_empty()
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.routed_via {
+            Some(name) => format!("{}，默认路由经过 {name}", self.interfaces.join(", ")),
+            None => self.interfaces.join(", "),
+        }
+    }
+}
+
+/// 指向某网卡的默认路由
+struct DefaultRoute {
+    interface: String,
+    split: bool, // 0/1 + 128/1 拆分路由，VPN 常用来覆盖默认路由
+}
+
+/// 按网卡名称与路由表检测 VPN：名称像 VPN 的网卡，或承载拆分默认路由的网卡
+pub async fn detect() -> VpnState {
+    let candidates = routable_interfaces();
+    let routes = default_routes(&candidates).await.unwrap_or_else(|e| {
+        logging!(debug, Type::Network, true, "读取路由表失败: {}", e);
+        Vec::new()
+    });
+
+    let mut interfaces: Vec<String> = candidates
+        .iter()
+        .map(|(name, _)| name.clone())
+        .filter(|name| {
+            looks_like_vpn(name)
+                || routes
+                    .iter()
+                    .any(|route| route.split && &route.interface == name)
+        })
+        .collect();
+    interfaces.sort();
+    interfaces.dedup();
+
+    let routed_via = routes
+        .iter()
+        .map(|route| &route.interface)
+        .find(|name| interfaces.contains(name))
+        .cloned();
+    VpnState {
+        interfaces,
+        routed_via,
+    }
+}
+
+fn looks_like_vpn(name: &str) -> bool {
+    let name = name.to_lowercase();
+    VPN_NAME_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+        || VPN_NAME_KEYWORDS
+            .iter()
+            .any(|keyword| name.contains(keyword))
+}
+
+/// 有可路由地址的网卡及其地址；排除回环、链路本地地址与本应用 TUN 使用的 198.18.0.0/15
+fn routable_interfaces() -> Vec<(String, Vec<IpAddr>)> {
+    NetworkInterface::show()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|iface| {
+            let addrs: Vec<IpAddr> = iface.addr.iter().map(|addr| addr.ip()).collect();
+            let own_tun = addrs.iter().any(|ip| match ip {
+                IpAddr::V4(v4) => v4.octets()[0] == 198 && v4.octets()[1] & 0xFE == 18,
+                IpAddr::V6(_) => false,
+            });
+            let routable = addrs.iter().any(|ip| match ip {
+                IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local(),
+                IpAddr::V6(v6) => !v6.is_loopback() && !v6.is_unicast_link_local(),
+            });
+            (routable && !own_tun).then_some((iface.name, addrs))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+async fn default_routes(_interfaces: &[(String, Vec<IpAddr>)]) -> Result<Vec<DefaultRoute>> {
+    // 列依次为 Iface Destination Gateway Flags RefCnt Use Metric Mask ...，地址为小端十六进制
+    let table = tokio::fs::read_to_string("/proc/net/route").await?;
+    Ok(table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let (interface, destination, mask) =
+                (columns.first()?, columns.get(1)?, columns.get(7)?);
+            let split = match (*destination, *mask) {
+                ("00000000", "00000000") => false,
+                ("00000000" | "00000080", "00000080") => true,
+                _ => return None,
+            };
+            Some(DefaultRoute {
+                interface: interface.to_string(),
+                split,
+            })
+        })
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+async fn default_routes(_interfaces: &[(String, Vec<IpAddr>)]) -> Result<Vec<DefaultRoute>> {
+    // 列依次为 Destination Gateway Flags Netif ...
+    let output = tokio::process::Command::new("netstat")
+        .args(["-rn", "-f", "inet"])
+        .output()
+        .await?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let split = match *columns.first()? {
+                "default" => false,
+                "0/1" | "128.0/1" => true,
+                _ => return None,
+            };
+            Some(DefaultRoute {
+                interface: columns.get(3)?.to_string(),
+                split,
+            })
+        })
+        .collect())
+}
+
+#[cfg(target_os = "windows")]
+async fn default_routes(interfaces: &[(String, Vec<IpAddr>)]) -> Result<Vec<DefaultRoute>> {
+    // 列依次为 Network Destination / Netmask / Gateway / Interface / Metric，网卡以地址表示
+    let output = tokio::process::Command::new("route")
+        .args(["print", "-4"])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW - 隐藏窗口
+        .output()
+        .await?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() != 5 {
+                return None;
+            }
+            let split = match (columns[0], columns[1]) {
+                ("0.0.0.0", "0.0.0.0") => false,
+                ("0.0.0.0" | "128.0.0.0", "128.0.0.0") => true,
+                _ => return None,
+            };
+            let address: IpAddr = columns[3].parse().ok()?;
+            let (interface, _) = interfaces
+                .iter()
+                .find(|(_, addrs)| addrs.contains(&address))?;
+            Some(DefaultRoute {
+                interface: interface.clone(),
+                split,
+            })
+        })
+        .collect())
+}
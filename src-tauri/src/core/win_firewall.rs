@@ -0,0 +1,184 @@
+#![cfg(target_os = "windows")]
+
+use crate::cmd::{FirewallRuleStatus, FirewallRulesStatus};
+use anyhow::{Result, bail};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use deelevate::{PrivilegeLevel, Token};
+use runas::Command as RunasCommand;
+use serde::Deserialize;
+use std::{os::windows::process::CommandExt, process::Command as StdCommand};
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// 所有规则归入同一分组，便于校验与整体移除；局域网共享也使用该分组
+const RULE_GROUP: &str = "Liebesu_Clash";
+/// 只放行专用网络中来自本地子网的连接
+const RULE_PROFILE: &str = "Private";
+const RULE_REMOTE_ADDRESS: &str = "LocalSubnet";
+
+/// 期望存在的入站放行规则
+#[derive(Debug, Clone)]
+pub struct DesiredRule {
+    pub name: String,
+    pub protocol: &'static str,
+    pub port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ExistingRule {
+    name: String,
+    enabled: String,
+    profile: String,
+    protocol: String,
+    port: String,
+    remote_address: String,
+}
+
+impl ExistingRule {
+    fn satisfies(&self, rule: &DesiredRule) -> bool {
+        self.name == rule.name
+            && self.enabled == "True"
+            && self.profile.eq_ignore_ascii_case(RULE_PROFILE)
+            && self.protocol.eq_ignore_ascii_case(rule.protocol)
+            && self.port == rule.port.to_string()
+            && self
+                .remote_address
+                .eq_ignore_ascii_case(RULE_REMOTE_ADDRESS)
+    }
+}
+
+/// 各代理端口的 TCP/UDP 规则
+pub fn desired_rules(ports: &[(&str, u16)]) -> Vec<DesiredRule> {
+    let mut rules = Vec::new();
+    for (key, port) in ports {
+        for protocol in ["TCP", "UDP"] {
+            rules.push(DesiredRule {
+                name: format!("{RULE_GROUP} {key} {protocol}"),
+                protocol,
+                port: *port,
+            });
+        }
+    }
+    rules
+}
+
+/// 校验规则，缺失或不一致时重建整个分组，只弹出一次 UAC
+pub fn ensure_rules(desired: &[DesiredRule]) -> Result<FirewallRulesStatus> {
+    let status = check(desired, &existing_rules()?);
+    if status.rules.iter().all(|rule| rule.present) {
+        return Ok(status);
+    }
+
+    let mut script = format!(
+        "Remove-NetFirewallRule -Group {} -ErrorAction SilentlyContinue\n",
+        quote(RULE_GROUP)
+    );
+    for rule in desired {
+        script.push_str(&format!(
+            "New-NetFirewallRule -DisplayName {} -Group {} -Direction Inbound -Action Allow -Profile {RULE_PROFILE} -RemoteAddress {RULE_REMOTE_ADDRESS} -Protocol {} -LocalPort {} | Out-Null\n",
+            quote(&rule.name),
+            quote(RULE_GROUP),
+            rule.protocol,
+            rule.port,
+        ));
+    }
+    run_elevated(&script)?;
+
+    let mut status = check(desired, &existing_rules()?);
+    if !status.rules.iter().all(|rule| rule.present) {
+        bail!("firewall rules were not applied, authorization may have been cancelled");
+    }
+    status.changed = true;
+    Ok(status)
+}
+
+/// 移除分组内的所有规则，没有规则时不请求提权
+pub fn remove_rules() -> Result<bool> {
+    if existing_rules()?.is_empty() {
+        return Ok(false);
+    }
+    run_elevated(&format!(
+        "Remove-NetFirewallRule -Group {}",
+        quote(RULE_GROUP)
+    ))?;
+    if !existing_rules()?.is_empty() {
+        bail!("firewall rules were not removed, authorization may have been cancelled");
+    }
+    Ok(true)
+}
+
+fn check(desired: &[DesiredRule], existing: &[ExistingRule]) -> FirewallRulesStatus {
+    FirewallRulesStatus {
+        rules: desired
+            .iter()
+            .map(|rule| FirewallRuleStatus {
+                name: rule.name.clone(),
+                protocol: rule.protocol.to_string(),
+                port: rule.port,
+                present: existing.iter().any(|e| e.satisfies(rule)),
+            })
+            .collect(),
+        changed: false,
+    }
+}
+
+/// 读取分组内的规则，无需管理员权限
+fn existing_rules() -> Result<Vec<ExistingRule>> {
+    let script = format!(
+        "[Console]::OutputEncoding = [Text.Encoding]::UTF8\n\
+         ConvertTo-Json -Compress -InputObject @(Get-NetFirewallRule -Group {} -ErrorAction SilentlyContinue | ForEach-Object {{\n\
+           $port = $_ | Get-NetFirewallPortFilter\n\
+           $address = $_ | Get-NetFirewallAddressFilter\n\
+           [pscustomobject]@{{ Name = $_.DisplayName; Enabled = [string]$_.Enabled; Profile = [string]$_.Profile; Protocol = [string]$port.Protocol; Port = [string]$port.LocalPort; RemoteAddress = [string]$address.RemoteAddress }}\n\
+         }})",
+        quote(RULE_GROUP)
+    );
+    let output = StdCommand::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-EncodedCommand"])
+        .arg(encode(&script))
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "Get-NetFirewallRule failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// 未提权时通过 UAC 以管理员身份执行脚本
+fn run_elevated(script: &str) -> Result<()> {
+    let encoded = encode(script);
+    let args = ["-NoProfile", "-NonInteractive", "-EncodedCommand", &encoded];
+
+    let token = Token::with_current_process()?;
+    let status = match token.privilege_level()? {
+        PrivilegeLevel::NotPrivileged => RunasCommand::new("powershell")
+            .args(&args)
+            .show(false)
+            .status()?,
+        _ => StdCommand::new("powershell")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()?,
+    };
+    if !status.success() {
+        bail!(
+            "powershell exited with status {}",
+            status.code().unwrap_or(-1)
+        );
+    }
+    Ok(())
+}
+
+/// -EncodedCommand 需要 UTF-16LE 的 base64，避免命令行转义问题
+fn encode(script: &str) -> String {
+    let bytes: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    STANDARD.encode(bytes)
+}
+
+/// PowerShell 单引号字符串
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
@@ -1,26 +1,131 @@
 #![cfg(target_os = "windows")]
 
-use crate::utils::dirs;
+use crate::cmd::UwpApp;
 use anyhow::{Result, bail};
 use deelevate::{PrivilegeLevel, Token};
 use runas::Command as RunasCommand;
-use std::process::Command as StdCommand;
+use serde::Deserialize;
+use std::{collections::HashSet, os::windows::process::CommandExt, process::Command as StdCommand};
 
-pub fn invoke_uwptools() -> Result<()> {
-    let resource_dir = dirs::app_resources_dir()?;
-    let tool_path = resource_dir.join("enableLoopback.exe");
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+const CHECK_NET_ISOLATION: &str = "CheckNetIsolation.exe";
 
-    if !tool_path.exists() {
-        bail!("enableLoopback exe not found");
+/// 枚举当前用户安装的 UWP 包（排除框架包）
+const LIST_PACKAGES_SCRIPT: &str = "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+    ConvertTo-Json -Compress -InputObject @(Get-AppxPackage | \
+    Where-Object { -not $_.IsFramework } | \
+    Select-Object Name, PackageFamilyName, Version)";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AppxPackage {
+    name: String,
+    package_family_name: String,
+    version: Option<String>,
+}
+
+/// 列出 UWP 应用及其回环豁免状态
+pub fn list_apps() -> Result<Vec<UwpApp>> {
+    let output = StdCommand::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            LIST_PACKAGES_SCRIPT,
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "Get-AppxPackage failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
+    let packages: Vec<AppxPackage> = serde_json::from_slice(&output.stdout)?;
+    let exempted = exempted_families()?;
 
-    let token = Token::with_current_process()?;
-    let level = token.privilege_level()?;
+    let mut apps: Vec<UwpApp> = packages
+        .into_iter()
+        .map(|package| UwpApp {
+            exempted: exempted.contains(&package.package_family_name.to_ascii_lowercase()),
+            name: package.name,
+            package_family_name: package.package_family_name,
+            version: package.version,
+        })
+        .collect();
+    apps.sort_by(|a, b| {
+        b.exempted
+            .cmp(&a.exempted)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    apps.dedup_by(|a, b| a.package_family_name == b.package_family_name);
+    Ok(apps)
+}
 
-    match level {
-        PrivilegeLevel::NotPrivileged => RunasCommand::new(tool_path).status()?,
-        _ => StdCommand::new(tool_path).status()?,
-    };
+/// 添加或移除回环豁免，未提权时通过 UAC 请求管理员权限
+pub fn set_loopback(package_family_name: &str, enabled: bool) -> Result<()> {
+    let name_arg = format!("-n={package_family_name}");
+    let args = [
+        "LoopbackExempt",
+        if enabled { "-a" } else { "-d" },
+        name_arg.as_str(),
+    ];
 
+    let token = Token::with_current_process()?;
+    let status = match token.privilege_level()? {
+        PrivilegeLevel::NotPrivileged => RunasCommand::new(CHECK_NET_ISOLATION)
+            .args(&args)
+            .show(false)
+            .status()?,
+        _ => StdCommand::new(CHECK_NET_ISOLATION)
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()?,
+    };
+    if !status.success() {
+        bail!(
+            "CheckNetIsolation failed with status {}",
+            status.code().unwrap_or(-1)
+        );
+    }
     Ok(())
 }
+
+/// 是否已豁免
+pub fn is_exempted(package_family_name: &str) -> Result<bool> {
+    Ok(exempted_families()?.contains(&package_family_name.to_ascii_lowercase()))
+}
+
+/// 解析 `CheckNetIsolation LoopbackExempt -s` 输出中的包系列名
+/// 输出标签随系统语言变化，按 "<名称>_<13 位发布者 ID>" 的格式识别
+fn exempted_families() -> Result<HashSet<String>> {
+    let output = StdCommand::new(CHECK_NET_ISOLATION)
+        .args(["LoopbackExempt", "-s"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "CheckNetIsolation failed with status {}",
+            output.status.code().unwrap_or(-1)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(_, value)| value.trim().to_ascii_lowercase())
+        .filter(|value| is_package_family_name(value))
+        .collect())
+}
+
+pub fn is_package_family_name(value: &str) -> bool {
+    let Some((name, publisher_id)) = value.rsplit_once('_') else {
+        return false;
+    };
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        && publisher_id.len() == 13
+        && publisher_id.chars().all(|c| c.is_ascii_alphanumeric())
+}
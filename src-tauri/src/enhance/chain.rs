@@ -1,6 +1,6 @@
 use super::SeqMap;
 use crate::{
-    config::PrfItem,
+    config::{ChainManifest, PrfItem},
     utils::{dirs, help},
 };
 use serde_yaml_ng::Mapping;
@@ -10,6 +10,8 @@ use std::fs;
 pub struct ChainItem {
     pub uid: String,
     pub data: ChainType,
+    /// 作用范围声明，用于判断能否并行执行
+    pub manifest: Option<ChainManifest>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +78,7 @@ impl AsyncChainItemFrom for Option<ChainItem> {
         let itype = item.itype.as_ref()?.as_str();
         let file = item.file.clone()?;
         let uid = item.uid.clone().unwrap_or("".into());
+        let manifest = item.option.as_ref().and_then(|o| o.chain_manifest.clone());
         let path = dirs::app_profiles_dir().ok()?.join(file);
 
         if !path.exists() {
@@ -86,16 +89,19 @@ impl AsyncChainItemFrom for Option<ChainItem> {
             "script" => Some(ChainItem {
                 uid,
                 data: ChainType::Script(fs::read_to_string(path).ok()?),
+                manifest,
             }),
             "merge" => Some(ChainItem {
                 uid,
                 data: ChainType::Merge(help::read_mapping(&path).await.ok()?),
+                manifest,
             }),
             "rules" => {
                 let seq_map = help::read_seq_map(&path).await.ok()?;
                 Some(ChainItem {
                     uid,
                     data: ChainType::Rules(seq_map),
+                    manifest: None,
                 })
             }
             "proxies" => {
@@ -103,6 +109,7 @@ impl AsyncChainItemFrom for Option<ChainItem> {
                 Some(ChainItem {
                     uid,
                     data: ChainType::Proxies(seq_map),
+                    manifest: None,
                 })
             }
             "groups" => {
@@ -110,6 +117,7 @@ impl AsyncChainItemFrom for Option<ChainItem> {
                 Some(ChainItem {
                     uid,
                     data: ChainType::Groups(seq_map),
+                    manifest: None,
                 })
             }
             _ => None,
@@ -147,6 +155,7 @@ impl ChainItem {
         Self {
             uid: uid.into(),
             data: ChainType::Script(data.into()),
+            manifest: None,
         }
     }
 }
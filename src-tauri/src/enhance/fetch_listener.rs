@@ -0,0 +1,95 @@
+use crate::config::{Config, FetchRoute};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use port_scanner::local_port_available;
+use serde_yaml_ng::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+
+/// 订阅下载专用监听端口的起始值
+const BASE_PORT: u16 = 17990;
+const PORT_RANGE: u16 = 200;
+
+/// 节点 -> 内核为订阅下载开放的本地端口
+static LISTENER_PORTS: Lazy<Mutex<HashMap<String, u16>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 为订阅拉取策略中指定的节点注入只监听本机的 mixed 监听器
+/// 已分配的端口在配置重新生成时保持不变
+pub async fn use_fetch_listeners(mut config: Mapping) -> Mapping {
+    let nodes = policy_nodes().await;
+    let available: HashSet<&str> = ["proxies", "proxy-groups"]
+        .iter()
+        .filter_map(|key| config.get(*key).and_then(Value::as_sequence))
+        .flatten()
+        .filter_map(|item| item.get("name").and_then(Value::as_str))
+        .collect();
+    let nodes: Vec<String> = nodes
+        .into_iter()
+        .filter(|node| available.contains(node.as_str()))
+        .collect();
+
+    let mut ports = LISTENER_PORTS.lock();
+    ports.retain(|node, _| nodes.contains(node));
+    for node in &nodes {
+        if ports.contains_key(node) {
+            continue;
+        }
+        let used: HashSet<u16> = ports.values().copied().collect();
+        let Some(port) = (0..PORT_RANGE)
+            .filter_map(|offset| BASE_PORT.checked_add(offset))
+            .find(|port| !used.contains(port) && local_port_available(*port))
+        else {
+            log::warn!(target: "app", "[订阅下载] 没有可用端口为节点 {node} 开放监听");
+            continue;
+        };
+        ports.insert(node.clone(), port);
+    }
+
+    if ports.is_empty() {
+        return config;
+    }
+
+    let mut listeners = config
+        .get("listeners")
+        .and_then(Value::as_sequence)
+        .cloned()
+        .unwrap_or_default();
+    for (node, port) in ports.iter() {
+        let mut listener = Mapping::new();
+        listener.insert("name".into(), format!("profile-fetch-{port}").into());
+        listener.insert("type".into(), "mixed".into());
+        listener.insert("port".into(), (*port).into());
+        listener.insert("listen".into(), "127.0.0.1".into());
+        listener.insert("proxy".into(), node.as_str().into());
+        listeners.push(Value::Mapping(listener));
+    }
+    config.insert("listeners".into(), Value::Sequence(listeners));
+    config
+}
+
+/// 指定节点的订阅下载监听端口
+pub fn listener_port(node: &str) -> Option<u16> {
+    LISTENER_PORTS.lock().get(node).copied()
+}
+
+/// 所有订阅拉取策略中引用的节点
+async fn policy_nodes() -> Vec<String> {
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    let mut nodes = Vec::new();
+    for item in profiles.get_items().into_iter().flatten() {
+        for route in item
+            .option
+            .as_ref()
+            .and_then(|o| o.fetch_policy.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            if let FetchRoute::Node(node) = route
+                && !nodes.contains(node)
+            {
+                nodes.push(node.clone());
+            }
+        }
+    }
+    nodes
+}
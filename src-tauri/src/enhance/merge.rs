@@ -1,4 +1,7 @@
-use super::use_lowercase;
+use super::{
+    seq::{SeqMap, use_seq},
+    use_lowercase,
+};
 use serde_yaml_ng::{self, Mapping, Value};
 
 fn deep_merge(a: &mut Value, b: &Value) {
@@ -24,6 +27,30 @@ pub fn use_merge(merge: Mapping, config: Mapping) -> Mapping {
     })
 }
 
+/// 快速补丁中追加到列表字段的键
+const QUICK_PATCH_SEQ_FIELDS: [&str; 3] = ["rules", "proxies", "proxy-groups"];
+
+/// 合并订阅快速补丁
+/// `prepend-rules`/`append-rules` 等键插入到对应列表，其余键按 Merge 方式覆盖
+pub fn use_quick_patch(mut patch: Mapping, mut config: Mapping) -> Mapping {
+    for field in QUICK_PATCH_SEQ_FIELDS {
+        let mut take = |prefix: &str| match patch.remove(format!("{prefix}-{field}").as_str()) {
+            Some(Value::Sequence(seq)) => seq,
+            _ => Default::default(),
+        };
+        let seq = SeqMap {
+            prepend: take("prepend"),
+            append: take("append"),
+            delete: Vec::new(),
+        };
+        if !seq.prepend.is_empty() || !seq.append.is_empty() {
+            config = use_seq(seq, config, field);
+        }
+    }
+
+    use_merge(patch, config)
+}
+
 #[test]
 fn test_merge() -> anyhow::Result<()> {
     let merge = r"
@@ -59,3 +86,34 @@ fn test_merge() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_quick_patch() -> anyhow::Result<()> {
+    let patch = r"
+    mixed-port: 7899
+    prepend-rules:
+      - DOMAIN,example.com,DIRECT
+  ";
+
+    let config = r"
+    mixed-port: 7897
+    rules:
+      - MATCH,PROXY
+  ";
+
+    let patch = serde_yaml_ng::from_str::<Mapping>(patch)?;
+    let config = serde_yaml_ng::from_str::<Mapping>(config)?;
+    let result = use_quick_patch(patch, config);
+
+    assert_eq!(result.get("mixed-port"), Some(&Value::from(7899)));
+    assert_eq!(
+        result.get("rules"),
+        Some(&Value::Sequence(vec![
+            "DOMAIN,example.com,DIRECT".into(),
+            "MATCH,PROXY".into(),
+        ]))
+    );
+    assert!(!result.contains_key("prepend-rules"));
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::{
+    core::handle,
+    logging,
+    process::AsyncHandler,
+    utils::{dirs, logging::Type},
+};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+use tauri::Emitter;
+
+const METRICS_FILE: &str = "config_generation_metrics.json";
+const SLOW_GENERATION_EVENT: &str = "verge://config-generation-slow";
+/// 保留最近的生成记录数量
+const MAX_RECORDS: usize = 50;
+/// 生成耗时超过该值时发出警告
+const SLOW_GENERATION_MS: u64 = 3000;
+/// 记录变化后延迟写入文件，合并这段时间内的多次生成
+const FLUSH_DELAY: Duration = Duration::from_secs(5);
+
+/// 最近的生成记录，新的在后
+static RECORDS: Lazy<Mutex<Option<Vec<GenerationMetrics>>>> = Lazy::new(|| Mutex::new(None));
+/// 是否已安排写入
+static FLUSH_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// 单个阶段的耗时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: String,
+    pub duration_ms: u64,
+}
+
+/// 一次配置生成的耗时与规模
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationMetrics {
+    pub generated_at: i64,
+    pub profile_uid: String,
+    pub total_ms: u64,
+    pub stages: Vec<StageTiming>,
+    pub proxy_count: usize,
+    pub group_count: usize,
+    pub rule_count: usize,
+    /// 写入运行时配置文件时补充
+    pub serialized_bytes: Option<u64>,
+}
+
+/// 按阶段累计耗时，同名阶段多次出现时合并
+pub struct StageTimer {
+    started_at: Instant,
+    last_mark: Instant,
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl StageTimer {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started_at: now,
+            last_mark: now,
+            stages: Vec::new(),
+        }
+    }
+
+    /// 将上次记录以来的耗时计入指定阶段
+    pub fn mark(&mut self, stage: &'static str) {
+        let now = Instant::now();
+        let elapsed = now - self.last_mark;
+        self.last_mark = now;
+        match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += elapsed,
+            None => self.stages.push((stage, elapsed)),
+        }
+    }
+
+    /// 记录本次生成，超过阈值时通知前端
+    pub fn finish(self, profile_uid: &str, config: &Mapping) {
+        let count = |key: &str| {
+            config
+                .get(key)
+                .and_then(Value::as_sequence)
+                .map_or(0, Vec::len)
+        };
+        let metrics = GenerationMetrics {
+            generated_at: chrono::Utc::now().timestamp(),
+            profile_uid: profile_uid.to_string(),
+            total_ms: self.started_at.elapsed().as_millis() as u64,
+            stages: self
+                .stages
+                .into_iter()
+                .map(|(stage, duration)| StageTiming {
+                    stage: stage.to_string(),
+                    duration_ms: duration.as_millis() as u64,
+                })
+                .collect(),
+            proxy_count: count("proxies"),
+            group_count: count("proxy-groups"),
+            rule_count: count("rules"),
+            serialized_bytes: None,
+        };
+
+        if metrics.total_ms > SLOW_GENERATION_MS {
+            logging!(
+                warn,
+                Type::Config,
+                true,
+                "[配置生成] 耗时 {}ms，节点 {} 个",
+                metrics.total_ms,
+                metrics.proxy_count
+            );
+            if let Some(app_handle) = handle::Handle::global().app_handle() {
+                let _ = app_handle.emit(SLOW_GENERATION_EVENT, &metrics);
+            }
+        }
+
+        update_records(|records| {
+            records.push(metrics);
+            let overflow = records.len().saturating_sub(MAX_RECORDS);
+            records.drain(..overflow);
+        });
+    }
+}
+
+/// 运行时配置写入文件后补充序列化耗时与大小
+pub fn record_serialization(duration: Duration, bytes: u64) {
+    update_records(|records| {
+        if let Some(latest) = records.last_mut()
+            && latest.serialized_bytes.is_none()
+        {
+            latest.serialized_bytes = Some(bytes);
+            latest.total_ms += duration.as_millis() as u64;
+            latest.stages.push(StageTiming {
+                stage: "serialization".to_string(),
+                duration_ms: duration.as_millis() as u64,
+            });
+        }
+    });
+}
+
+/// 最近的生成记录，新的在前
+pub fn generation_metrics() -> Vec<GenerationMetrics> {
+    let mut guard = RECORDS.lock();
+    let records = guard.get_or_insert_with(|| load_records().unwrap_or_default());
+    records.iter().rev().cloned().collect()
+}
+
+// ===== 内部实现函数 =====
+
+/// 只更新内存中的记录，文件由后台任务延迟写入，不阻塞配置生成
+fn update_records(f: impl FnOnce(&mut Vec<GenerationMetrics>)) {
+    {
+        let mut guard = RECORDS.lock();
+        let records = guard.get_or_insert_with(|| load_records().unwrap_or_default());
+        f(records);
+    }
+    schedule_flush();
+}
+
+fn schedule_flush() {
+    if FLUSH_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    AsyncHandler::spawn(|| async {
+        tokio::time::sleep(FLUSH_DELAY).await;
+        // 先清除标记，写入期间的新记录会安排下一次写入
+        FLUSH_PENDING.store(false, Ordering::SeqCst);
+        let content = RECORDS.lock().as_deref().map(serde_json::to_string);
+        let result = match content {
+            Some(Ok(content)) => save_records(content).await,
+            Some(Err(e)) => Err(e.into()),
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            logging!(
+                warn,
+                Type::Config,
+                true,
+                "[配置生成] 保存耗时记录失败: {}",
+                e
+            );
+        }
+    });
+}
+
+fn records_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(METRICS_FILE))
+}
+
+fn load_records() -> Result<Vec<GenerationMetrics>> {
+    let path = records_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+async fn save_records(content: String) -> Result<()> {
+    tokio::fs::write(records_path()?, content).await?;
+    Ok(())
+}
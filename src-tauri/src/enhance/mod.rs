@@ -1,13 +1,18 @@
 mod chain;
+pub mod fetch_listener;
 pub mod field;
 mod merge;
+pub mod metrics;
+mod parallel;
 mod script;
 pub mod seq;
 mod tun;
 
-use self::{chain::*, field::*, merge::*, script::*, seq::*, tun::*};
+use self::{
+    chain::*, field::*, merge::*, metrics::StageTimer, parallel::*, script::*, seq::*, tun::*,
+};
 use crate::{config::Config, utils::tmpl};
-use serde_yaml_ng::Mapping;
+use serde_yaml_ng::{Mapping, Sequence};
 use std::collections::{HashMap, HashSet};
 
 type ResultLog = Vec<(String, String)>;
@@ -15,6 +20,8 @@ type ResultLog = Vec<(String, String)>;
 /// Enhance mode
 /// 返回最终订阅、该订阅包含的键、和script执行的结果
 pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
+    let mut timer = StageTimer::new();
+
     // config.yaml 的订阅
     let clash_config = { Config::clash().await.latest_ref().0.clone() };
 
@@ -54,6 +61,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         global_merge,
         global_script,
         profile_name,
+        profile_uid,
     ) = {
         // 收集所有需要的数据，然后释放profiles锁
         let (
@@ -63,7 +71,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
             rules_uid,
             proxies_uid,
             groups_uid,
-            _current_profile_uid,
+            current_profile_uid,
             name,
         ) = {
             // 分离async调用和数据获取，避免借用检查问题
@@ -118,6 +126,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "".into(),
             data: ChainType::Merge(Mapping::new()),
+            manifest: None,
         });
 
         let script = {
@@ -135,6 +144,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "".into(),
             data: ChainType::Script(tmpl::ITEM_SCRIPT.into()),
+            manifest: None,
         });
 
         let rules = {
@@ -152,6 +162,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "".into(),
             data: ChainType::Rules(SeqMap::default()),
+            manifest: None,
         });
 
         let proxies = {
@@ -169,6 +180,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "".into(),
             data: ChainType::Proxies(SeqMap::default()),
+            manifest: None,
         });
 
         let groups = {
@@ -186,6 +198,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "".into(),
             data: ChainType::Groups(SeqMap::default()),
+            manifest: None,
         });
 
         let global_merge = {
@@ -203,6 +216,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "Merge".into(),
             data: ChainType::Merge(Mapping::new()),
+            manifest: None,
         });
 
         let global_script = {
@@ -220,6 +234,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         .unwrap_or_else(|| ChainItem {
             uid: "Script".into(),
             data: ChainType::Script(tmpl::ITEM_SCRIPT.into()),
+            manifest: None,
         });
 
         (
@@ -232,65 +247,161 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
             global_merge,
             global_script,
             name,
+            current_profile_uid,
         )
     };
 
     let mut result_map = HashMap::new(); // 保存脚本日志
     let mut exists_keys = use_keys(&config); // 保存出现过的keys
+    timer.mark("load");
 
-    // 全局Merge和Script
-    if let ChainType::Merge(merge) = global_merge.data {
-        exists_keys.extend(use_keys(&merge));
-        config = use_merge(merge, config.to_owned());
-    }
+    let chain = [
+        global_merge,
+        global_script,
+        rules_item,
+        proxies_item,
+        groups_item,
+        merge_item,
+        script_item,
+    ];
+    if can_parallelize(&chain) {
+        // 各项作用范围互不相交，并行执行后按顺序合并
+        let (res_config, res_keys, logs) =
+            use_parallel_chain(config, Vec::from(chain), &profile_name).await;
+        config = res_config;
+        exists_keys.extend(res_keys);
+        result_map.extend(logs);
+        timer.mark("chain");
+    } else {
+        let [
+            global_merge,
+            global_script,
+            rules_item,
+            proxies_item,
+            groups_item,
+            merge_item,
+            script_item,
+        ] = chain;
+
+        // 全局Merge和Script
+        if let ChainType::Merge(merge) = global_merge.data {
+            exists_keys.extend(use_keys(&merge));
+            config = use_merge(merge, config.to_owned());
+        }
+        timer.mark("global_merge");
 
-    if let ChainType::Script(script) = global_script.data {
-        let mut logs = vec![];
+        if let ChainType::Script(script) = global_script.data {
+            let mut logs = vec![];
 
-        match use_script(script, config.to_owned(), profile_name.to_owned()) {
-            Ok((res_config, res_logs)) => {
-                exists_keys.extend(use_keys(&res_config));
-                config = res_config;
-                logs.extend(res_logs);
+            match use_script(script, config.to_owned(), profile_name.to_owned()) {
+                Ok((res_config, res_logs)) => {
+                    exists_keys.extend(use_keys(&res_config));
+                    config = res_config;
+                    logs.extend(res_logs);
+                }
+                Err(err) => logs.push(("exception".into(), err.to_string())),
             }
-            Err(err) => logs.push(("exception".into(), err.to_string())),
+
+            result_map.insert(global_script.uid, logs);
         }
+        timer.mark("global_script");
 
-        result_map.insert(global_script.uid, logs);
-    }
+        // 订阅关联的Merge、Script、Rules、Proxies、Groups
+        if let ChainType::Rules(rules) = rules_item.data {
+            config = use_seq(rules, config.to_owned(), "rules");
+        }
+
+        if let ChainType::Proxies(proxies) = proxies_item.data {
+            config = use_seq(proxies, config.to_owned(), "proxies");
+        }
+
+        if let ChainType::Groups(groups) = groups_item.data {
+            config = use_seq(groups, config.to_owned(), "proxy-groups");
+        }
+        timer.mark("profile_seq");
 
-    // 订阅关联的Merge、Script、Rules、Proxies、Groups
-    if let ChainType::Rules(rules) = rules_item.data {
-        config = use_seq(rules, config.to_owned(), "rules");
+        if let ChainType::Merge(merge) = merge_item.data {
+            exists_keys.extend(use_keys(&merge));
+            config = use_merge(merge, config.to_owned());
+        }
+        timer.mark("profile_merge");
+
+        if let ChainType::Script(script) = script_item.data {
+            let mut logs = vec![];
+
+            match use_script(script, config.to_owned(), profile_name.to_owned()) {
+                Ok((res_config, res_logs)) => {
+                    exists_keys.extend(use_keys(&res_config));
+                    config = res_config;
+                    logs.extend(res_logs);
+                }
+                Err(err) => logs.push(("exception".into(), err.to_string())),
+            }
+
+            result_map.insert(script_item.uid, logs);
+        }
+        timer.mark("profile_script");
     }
 
-    if let ChainType::Proxies(proxies) = proxies_item.data {
-        config = use_seq(proxies, config.to_owned(), "proxies");
+    // 节点过滤，被过滤的节点不会进入内核
+    let (filtered, filter_logs) = crate::cmd::node_filters::use_node_filters(config, &profile_uid);
+    config = filtered;
+    if !filter_logs.is_empty() {
+        result_map.insert(profile_uid.clone(), filter_logs);
     }
 
-    if let ChainType::Groups(groups) = groups_item.data {
-        config = use_seq(groups, config.to_owned(), "proxy-groups");
+    // 节点重命名，之后的分组和链式代理使用重命名后的名称
+    config = crate::cmd::node_rename::use_node_rename(config);
+
+    // 本地缓存的远程规则集
+    config = crate::cmd::ruleset_manager::use_ruleset_sources(config);
+
+    // 分应用代理规则，进程匹配需要内核开启进程查找
+    if let Some(prepend) = crate::cmd::app_routes::app_routes_prepend() {
+        let rules = SeqMap {
+            prepend: crate::cmd::custom_rules::retain_resolvable_rules(
+                prepend,
+                &config,
+                "分应用代理",
+            ),
+            append: Sequence::new(),
+            delete: Vec::new(),
+        };
+        config = use_seq(rules, config, "rules");
+        if config.get("find-process-mode").and_then(|v| v.as_str()) == Some("off") {
+            config.insert("find-process-mode".into(), "strict".into());
+        }
     }
 
-    if let ChainType::Merge(merge) = merge_item.data {
-        exists_keys.extend(use_keys(&merge));
-        config = use_merge(merge, config.to_owned());
+    // 自定义规则，插入到订阅规则之前，优先于分应用代理规则
+    if let Some(prepend) = crate::cmd::custom_rules::custom_rules_prepend() {
+        let rules = SeqMap {
+            prepend: crate::cmd::custom_rules::retain_resolvable_rules(
+                prepend,
+                &config,
+                "自定义规则",
+            ),
+            append: Sequence::new(),
+            delete: Vec::new(),
+        };
+        config = use_seq(rules, config, "rules");
     }
 
-    if let ChainType::Script(script) = script_item.data {
-        let mut logs = vec![];
+    // 健康度负载均衡分组
+    config = crate::cmd::load_balance::use_load_balance_groups(config).await;
 
-        match use_script(script, config.to_owned(), profile_name.to_owned()) {
-            Ok((res_config, res_logs)) => {
-                exists_keys.extend(use_keys(&res_config));
-                config = res_config;
-                logs.extend(res_logs);
-            }
-            Err(err) => logs.push(("exception".into(), err.to_string())),
-        }
+    // 链式代理
+    config = crate::cmd::proxy_chain::use_proxy_chains(config).await;
 
-        result_map.insert(script_item.uid, logs);
-    }
+    // 自动选择分组的测速策略
+    config = crate::cmd::group_test_policy::use_group_test_policies(config);
+
+    // 代理组带宽限制，写入 Hysteria 系节点的 up / down
+    config = crate::cmd::bandwidth_limits::use_bandwidth_limits(config);
+
+    // 订阅经指定节点下载所需的本地监听器
+    config = fetch_listener::use_fetch_listeners(config).await;
+    timer.mark("transform");
 
     // 合并默认的config
     for (key, value) in clash_config.into_iter() {
@@ -352,6 +463,17 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         }
     }
 
+    // 订阅快速补丁在基础配置之后合并，可覆盖端口等设置
+    if let Some(patch) = crate::cmd::quick_patch::current_quick_patch().await {
+        exists_keys.extend(
+            use_keys(&patch)
+                .into_iter()
+                .filter(|key| !key.starts_with("prepend-") && !key.starts_with("append-")),
+        );
+        config = use_quick_patch(patch, config);
+    }
+    timer.mark("base_config");
+
     // 内建脚本最后跑
     if enable_builtin {
         ChainItem::builtin()
@@ -372,6 +494,7 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
                 }
             });
     }
+    timer.mark("builtin_script");
 
     config = use_tun(config, enable_tun);
     config = use_sort(config);
@@ -438,9 +561,18 @@ pub async fn enhance() -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
         }
     }
 
+    // 托管的 hosts 覆盖，优先于订阅和独立 DNS 配置中的 hosts
+    config = crate::cmd::dns_hosts::use_dns_host_overrides(config).await;
+
+    // fake-ip 排除列表，追加到 dns.fake-ip-filter
+    config = crate::cmd::fakeip_filter::use_fakeip_exclusions(config);
+
     let mut exists_set = HashSet::new();
     exists_set.extend(exists_keys);
     exists_keys = exists_set.into_iter().collect();
 
+    timer.mark("finalize");
+    timer.finish(&profile_uid, &config);
+
     (config, exists_keys, result_map)
 }
@@ -0,0 +1,261 @@
+use super::{
+    ResultLog,
+    chain::{ChainItem, ChainType},
+    field::use_keys,
+    merge::use_merge,
+    script::use_script,
+    seq::{SeqMap, use_seq},
+};
+use crate::{
+    logging,
+    process::AsyncHandler,
+    utils::{logging::Type, tmpl},
+};
+use futures::future::join_all;
+use serde_yaml_ng::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+
+/// 增强链能否并行执行
+/// 需要至少两个脚本，所有脚本都声明了作用键，各项作用键互不相交，且没有声明依赖
+pub fn can_parallelize(items: &[ChainItem]) -> bool {
+    let scripts = items
+        .iter()
+        .filter(|item| matches!(&item.data, ChainType::Script(s) if s != tmpl::ITEM_SCRIPT))
+        .count();
+    // 少于两个脚本时并行没有收益
+    if scripts < 2 {
+        return false;
+    }
+
+    let has_dependency = items.iter().any(|item| {
+        item.manifest
+            .as_ref()
+            .is_some_and(|manifest| !manifest.depends_on.is_empty())
+    });
+    if has_dependency {
+        return false;
+    }
+
+    let mut seen = HashSet::new();
+    items.iter().all(|item| {
+        step_keys(item).is_some_and(|keys| keys.into_iter().all(|key| seen.insert(key)))
+    })
+}
+
+/// 以同一份配置为输入并行执行增强链，再按链中顺序写回各项声明的键
+/// 返回合并后的配置、新出现的键和脚本日志
+pub async fn use_parallel_chain(
+    config: Mapping,
+    items: Vec<ChainItem>,
+    name: &str,
+) -> (Mapping, Vec<String>, HashMap<String, ResultLog>) {
+    let tasks = items.into_iter().map(|item| {
+        let keys = step_keys(&item).unwrap_or_default();
+        let base = config.clone();
+        let name = name.to_string();
+        AsyncHandler::spawn_blocking(move || {
+            let (output, logs) = match item.data {
+                ChainType::Merge(merge) => (Some(use_merge(merge, base)), None),
+                ChainType::Script(script) => match use_script(script, base, name) {
+                    Ok((output, logs)) => (Some(output), Some(logs)),
+                    Err(err) => (None, Some(vec![("exception".into(), err.to_string())])),
+                },
+                ChainType::Rules(seq) => (Some(use_seq(seq, base, "rules")), None),
+                ChainType::Proxies(seq) => (Some(use_seq(seq, base, "proxies")), None),
+                ChainType::Groups(seq) => (Some(use_seq(seq, base, "proxy-groups")), None),
+            };
+            (item.uid, keys, output, logs)
+        })
+    });
+
+    let mut config = config;
+    let mut exists_keys = Vec::new();
+    let mut result_map = HashMap::new();
+    for result in join_all(tasks).await {
+        let (uid, keys, output, logs) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                logging!(
+                    error,
+                    Type::Config,
+                    true,
+                    "[增强链] 并行任务执行失败: {}",
+                    e
+                );
+                continue;
+            }
+        };
+        if let Some(output) = output {
+            for key in keys {
+                match output.get(key.as_str()) {
+                    Some(value) => {
+                        config.insert(Value::from(key.as_str()), value.clone());
+                        exists_keys.push(key);
+                    }
+                    None => {
+                        config.remove(key.as_str());
+                    }
+                }
+            }
+        }
+        if let Some(logs) = logs {
+            result_map.insert(uid, logs);
+        }
+    }
+    (config, exists_keys, result_map)
+}
+
+// ===== 内部实现函数 =====
+
+/// 增强项的作用键，无法确定时返回 None
+/// Merge 和 Rules/Proxies/Groups 由内容推断，脚本必须声明
+fn step_keys(item: &ChainItem) -> Option<Vec<String>> {
+    let declared: Option<Vec<String>> = item.manifest.as_ref().map(|manifest| {
+        manifest
+            .keys
+            .iter()
+            .map(|key| key.to_ascii_lowercase())
+            .collect()
+    });
+    let mut keys = match &item.data {
+        ChainType::Merge(merge) => use_keys(merge),
+        ChainType::Script(script) if script == tmpl::ITEM_SCRIPT => Vec::new(),
+        ChainType::Script(_) => declared.clone()?,
+        ChainType::Rules(seq) => seq_keys(seq, "rules"),
+        ChainType::Proxies(seq) => seq_keys(seq, "proxies"),
+        ChainType::Groups(seq) => seq_keys(seq, "proxy-groups"),
+    };
+    keys.extend(declared.unwrap_or_default());
+    keys.sort();
+    keys.dedup();
+    Some(keys)
+}
+
+fn seq_keys(seq: &SeqMap, field: &str) -> Vec<String> {
+    if seq.prepend.is_empty() && seq.append.is_empty() && seq.delete.is_empty() {
+        Vec::new()
+    } else {
+        vec![field.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChainManifest;
+    use serde_yaml_ng::Sequence;
+
+    fn script(uid: &str, keys: &[&str]) -> ChainItem {
+        ChainItem {
+            uid: uid.into(),
+            data: ChainType::Script(format!(
+                "function main(config) {{ return config }} // {uid}"
+            )),
+            manifest: (!keys.is_empty()).then(|| ChainManifest {
+                keys: keys.iter().map(|key| key.to_string()).collect(),
+                depends_on: Vec::new(),
+            }),
+        }
+    }
+
+    fn merge(uid: &str, keys: &[&str]) -> ChainItem {
+        let mut mapping = Mapping::new();
+        for key in keys {
+            mapping.insert(Value::from(*key), Value::Null);
+        }
+        ChainItem {
+            uid: uid.into(),
+            data: ChainType::Merge(mapping),
+            manifest: None,
+        }
+    }
+
+    fn rules(uid: &str, prepend: bool) -> ChainItem {
+        let mut seq = SeqMap::default();
+        if prepend {
+            seq.prepend = Sequence::from(vec![Value::from("DOMAIN,example.com,DIRECT")]);
+        }
+        ChainItem {
+            uid: uid.into(),
+            data: ChainType::Rules(seq),
+            manifest: None,
+        }
+    }
+
+    #[test]
+    fn step_keys_requires_declaration_for_scripts() {
+        assert_eq!(step_keys(&script("s1", &[])), None);
+        assert_eq!(
+            step_keys(&script("s1", &["DNS", "rules", "dns"])),
+            Some(vec!["dns".to_string(), "rules".to_string()])
+        );
+    }
+
+    #[test]
+    fn step_keys_infers_merge_and_seq_keys() {
+        assert_eq!(
+            step_keys(&merge("m1", &["Proxies", "dns"])),
+            Some(vec!["dns".to_string(), "proxies".to_string()])
+        );
+        assert_eq!(
+            step_keys(&rules("r1", true)),
+            Some(vec!["rules".to_string()])
+        );
+        assert_eq!(step_keys(&rules("r1", false)), Some(Vec::new()));
+    }
+
+    #[test]
+    fn step_keys_ignores_template_script() {
+        let item = ChainItem {
+            uid: "s1".into(),
+            data: ChainType::Script(tmpl::ITEM_SCRIPT.into()),
+            manifest: None,
+        };
+        assert_eq!(step_keys(&item), Some(Vec::new()));
+    }
+
+    #[test]
+    fn disjoint_declared_scripts_run_in_parallel() {
+        let items = vec![
+            script("s1", &["dns"]),
+            script("s2", &["rules"]),
+            merge("m1", &["tun"]),
+        ];
+        assert!(can_parallelize(&items));
+    }
+
+    #[test]
+    fn overlapping_keys_run_in_order() {
+        let items = vec![
+            script("s1", &["rules"]),
+            script("s2", &["dns"]),
+            rules("r1", true),
+        ];
+        assert!(!can_parallelize(&items));
+
+        let items = vec![script("s1", &["dns"]), script("s2", &["DNS"])];
+        assert!(!can_parallelize(&items));
+    }
+
+    #[test]
+    fn undeclared_script_runs_in_order() {
+        let items = vec![script("s1", &["dns"]), script("s2", &[])];
+        assert!(!can_parallelize(&items));
+    }
+
+    #[test]
+    fn declared_dependency_runs_in_order() {
+        let mut dependent = script("s2", &["rules"]);
+        if let Some(manifest) = dependent.manifest.as_mut() {
+            manifest.depends_on = vec!["s1".into()];
+        }
+        let items = vec![script("s1", &["dns"]), dependent];
+        assert!(!can_parallelize(&items));
+    }
+
+    #[test]
+    fn single_script_runs_in_order() {
+        let items = vec![script("s1", &["dns"]), merge("m1", &["rules"])];
+        assert!(!can_parallelize(&items));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     config::{Config, IVerge},
-    core::backup,
+    core::backup::{self, BackupStorage, RemoteBackupFile},
     logging_error,
     utils::{dirs::app_home_dir, logging::Type},
 };
@@ -8,18 +8,28 @@ use anyhow::Result;
 use reqwest_dav::list_cmd::ListFile;
 use std::fs;
 
-/// Create a backup and upload to WebDAV
-pub async fn create_backup_and_upload_webdav() -> Result<()> {
-    let (file_name, temp_file_path) = backup::create_backup().map_err(|err| {
-        log::error!(target: "app", "Failed to create backup: {err:#?}");
-        err
-    })?;
+/// Create a backup and upload to the remote storage, returns the uploaded file name
+pub async fn create_backup_and_upload(storage: &dyn BackupStorage) -> Result<String> {
+    create_backup_and_upload_with(storage, true, true).await
+}
+
+/// Create a backup containing only the selected content and upload it
+pub async fn create_backup_and_upload_with(
+    storage: &dyn BackupStorage,
+    profiles: bool,
+    settings: bool,
+) -> Result<String> {
+    let (file_name, temp_file_path) =
+        backup::create_backup_with(profiles, settings).map_err(|err| {
+            log::error!(target: "app", "Failed to create backup: {err:#?}");
+            err
+        })?;
 
-    if let Err(err) = backup::WebDavClient::global()
-        .upload(temp_file_path.clone(), file_name)
+    if let Err(err) = storage
+        .upload(temp_file_path.clone(), file_name.clone())
         .await
     {
-        log::error!(target: "app", "Failed to upload to WebDAV: {err:#?}");
+        log::error!(target: "app", "Failed to upload backup: {err:#?}");
         return Err(err);
     }
 
@@ -27,7 +37,30 @@ pub async fn create_backup_and_upload_webdav() -> Result<()> {
         log::warn!(target: "app", "Failed to remove temp file: {err:#?}");
     }
 
-    Ok(())
+    Ok(file_name)
+}
+
+/// Create a backup and upload to WebDAV
+pub async fn create_backup_and_upload_webdav() -> Result<()> {
+    create_backup_and_upload(backup::WebDavClient::global())
+        .await
+        .map(|_| ())
+}
+
+/// List remote backups
+pub async fn list_remote_backup(storage: &dyn BackupStorage) -> Result<Vec<RemoteBackupFile>> {
+    storage.list_files().await.map_err(|err| {
+        log::error!(target: "app", "Failed to list remote backup files: {err:#?}");
+        err
+    })
+}
+
+/// Delete remote backup
+pub async fn delete_remote_backup(storage: &dyn BackupStorage, filename: String) -> Result<()> {
+    storage.delete(filename).await.map_err(|err| {
+        log::error!(target: "app", "Failed to delete remote backup file: {err:#?}");
+        err
+    })
 }
 
 /// List WebDAV backups
@@ -40,31 +73,32 @@ pub async fn list_wevdav_backup() -> Result<Vec<ListFile>> {
 
 /// Delete WebDAV backup
 pub async fn delete_webdav_backup(filename: String) -> Result<()> {
-    backup::WebDavClient::global()
-        .delete(filename)
-        .await
-        .map_err(|err| {
-            log::error!(target: "app", "Failed to delete WebDAV backup file: {err:#?}");
-            err
-        })
+    delete_remote_backup(backup::WebDavClient::global(), filename).await
 }
 
 /// Restore WebDAV backup
 pub async fn restore_webdav_backup(filename: String) -> Result<()> {
+    restore_remote_backup(backup::WebDavClient::global(), filename).await
+}
+
+/// Restore remote backup, keeping the local remote storage credentials
+pub async fn restore_remote_backup(storage: &dyn BackupStorage, filename: String) -> Result<()> {
     let verge = Config::verge().await;
     let verge_data = verge.latest_ref().clone();
     let webdav_url = verge_data.webdav_url.clone();
     let webdav_username = verge_data.webdav_username.clone();
     let webdav_password = verge_data.webdav_password.clone();
+    let remote_backup_provider = verge_data.remote_backup_provider.clone();
+    let s3_backup = verge_data.s3_backup.clone();
 
     let backup_storage_path = app_home_dir()
         .map_err(|e| anyhow::anyhow!("Failed to get app home dir: {e}"))?
         .join(&filename);
-    backup::WebDavClient::global()
+    storage
         .download(filename, backup_storage_path.clone())
         .await
         .map_err(|err| {
-            log::error!(target: "app", "Failed to download WebDAV backup file: {err:#?}");
+            log::error!(target: "app", "Failed to download remote backup file: {err:#?}");
             err
         })?;
 
@@ -79,6 +113,8 @@ pub async fn restore_webdav_backup(filename: String) -> Result<()> {
                 webdav_url,
                 webdav_username,
                 webdav_password,
+                remote_backup_provider,
+                s3_backup,
                 ..IVerge::default()
             },
             false
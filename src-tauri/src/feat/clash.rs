@@ -102,6 +102,11 @@ pub async fn change_clash_mode(mode: String) {
 
 /// Test connection delay to a URL
 pub async fn test_delay(url: String) -> anyhow::Result<u32> {
+    test_delay_with(url, |status| (200..300).contains(&status)).await
+}
+
+/// Test connection delay to a URL, `accept` decides which status codes count as success
+pub async fn test_delay_with(url: String, accept: impl Fn(u16) -> bool) -> anyhow::Result<u32> {
     use crate::utils::network::{NetworkManager, ProxyType};
     use tokio::time::Instant;
 
@@ -129,7 +134,7 @@ pub async fn test_delay(url: String) -> anyhow::Result<u32> {
     match response {
         Ok(response) => {
             log::trace!(target: "app", "test_delay response: {response:#?}");
-            if response.status().is_success() {
+            if accept(response.status().as_u16()) {
                 Ok(start.elapsed().as_millis() as u32)
             } else {
                 Ok(10000u32)
@@ -17,7 +17,10 @@ pub async fn patch_clash(patch: Mapping) -> Result<()> {
 
     let res = {
         // 激活订阅
-        if patch.get("secret").is_some() || patch.get("external-controller").is_some() {
+        if patch.get("secret").is_some()
+            || patch.get("external-controller").is_some()
+            || patch.get("external-controller-cors").is_some()
+        {
             Config::generate().await?;
             // 变更控制端/密钥后，预清理可能残留的 unix socket
             #[cfg(unix)]
@@ -3,9 +3,12 @@ use crate::{
     config::{Config, PrfItem, PrfOption, profiles::profiles_draft_update_item_safe},
     core::{CoreManager, handle, tray},
     logging,
+    module::offline::OfflineMode,
+    state::subscription_sync::SUBSCRIPTION_SYNC_STORE,
     utils::logging::Type,
 };
 use anyhow::{Result, bail};
+use futures::{StreamExt, stream};
 
 /// Toggle proxy profile
 pub async fn toggle_proxy_profile(profile_index: String) {
@@ -60,6 +63,7 @@ pub async fn update_profile(
         }
     };
 
+    let is_remote = url_opt.is_some();
     let should_update = match url_opt {
         Some((url, opt)) => {
             log::info!(target: "app", "[订阅更新] 开始下载新的订阅内容");
@@ -69,6 +73,13 @@ pub async fn update_profile(
             match PrfItem::from_url(&url, None, None, merged_opt.clone()).await {
                 Ok(item) => {
                     log::info!(target: "app", "[订阅更新] 更新订阅配置成功");
+                    let profile_name = item.name.clone().unwrap_or_else(|| uid.clone());
+                    cmd::subscription_usage::record_subscription_usage(
+                        &uid,
+                        &profile_name,
+                        item.extra,
+                    )
+                    .await;
                     let profiles = Config::profiles().await;
 
                     // 使用Send-safe helper函数
@@ -79,6 +90,15 @@ pub async fn update_profile(
                     log::info!(target: "app", "[订阅更新] 是否为当前使用的订阅: {is_current}");
                     is_current && auto_refresh
                 }
+                Err(err) if PrfOption::has_fetch_policy(merged_opt.as_ref()) => {
+                    // 自定义拉取策略已按顺序尝试过所有路径，不再额外回退
+                    log::error!(target: "app", "[订阅更新] 按拉取策略更新失败: {err}");
+                    handle::Handle::notice_message("update_failed", format!("{err}"));
+                    OfflineMode::global()
+                        .record_update_failure(&uid, &err.to_string())
+                        .await;
+                    return Err(err);
+                }
                 Err(err) => {
                     // 首次更新失败，尝试使用Clash代理
                     log::warn!(target: "app", "[订阅更新] 正常更新失败: {err}，尝试使用Clash代理更新");
@@ -114,6 +134,12 @@ pub async fn update_profile(
 
                             // 获取配置名称用于通知
                             let profile_name = item.name.clone().unwrap_or_else(|| uid.clone());
+                            cmd::subscription_usage::record_subscription_usage(
+                                &uid,
+                                &profile_name,
+                                item.extra,
+                            )
+                            .await;
 
                             // 发送通知告知用户自动更新使用了回退机制
                             handle::Handle::notice_message("update_with_clash_proxy", profile_name);
@@ -128,6 +154,9 @@ pub async fn update_profile(
                                 "update_failed_even_with_clash",
                                 format!("{retry_err}"),
                             );
+                            OfflineMode::global()
+                                .record_update_failure(&uid, &retry_err.to_string())
+                                .await;
                             return Err(retry_err);
                         }
                     }
@@ -137,11 +166,15 @@ pub async fn update_profile(
         None => auto_refresh,
     };
 
+    // 重新生成引用该订阅的组合订阅
+    cmd::composite_profile::refresh_composites_for_source(&uid).await;
+
     if should_update {
         logging!(info, Type::Config, true, "[订阅更新] 更新内核配置");
         match CoreManager::global().update_config().await {
             Ok(_) => {
                 logging!(info, Type::Config, true, "[订阅更新] 更新成功");
+                cmd::selection_memory::restore_selections_after_reload(uid.clone(), true).await;
                 handle::Handle::refresh_clash();
                 if let Err(err) = cmd::proxy::force_refresh_proxies().await {
                     logging!(
@@ -166,13 +199,43 @@ pub async fn update_profile(
         handle::Handle::notify_profile_changed("updated".to_string());
     }
 
+    if is_remote {
+        OfflineMode::global().record_update_success(&uid).await;
+    }
+
+    cmd::event_publisher::publish_event(
+        cmd::event_publisher::AutomationEvent::ProfileUpdated,
+        serde_json::json!({ "uid": uid }),
+    );
     Ok(())
 }
 
+/// 并发更新多个订阅，并发数沿用订阅同步设置，结果按完成顺序返回
+pub async fn update_profiles(
+    uids: Vec<String>,
+    auto_refresh: Option<bool>,
+) -> Vec<(String, Result<()>)> {
+    let parallel = SUBSCRIPTION_SYNC_STORE
+        .inner
+        .read()
+        .preferences()
+        .max_concurrency
+        .max(1);
+
+    stream::iter(uids)
+        .map(|uid| async move {
+            let result = update_profile(uid.clone(), None, auto_refresh).await;
+            (uid, result)
+        })
+        .buffer_unordered(parallel)
+        .collect()
+        .await
+}
+
 /// 增强配置
-pub async fn enhance_profiles() -> Result<()> {
+pub async fn enhance_profiles(token: Option<&cmd::CancelToken>) -> Result<()> {
     crate::core::CoreManager::global()
-        .update_config()
+        .update_config_with_cancel(token)
         .await
         .map(|_| ())
 }
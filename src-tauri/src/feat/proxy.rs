@@ -65,6 +65,38 @@ pub async fn toggle_tun_mode(not_save_file: Option<bool>) {
     }
 }
 
+/// Switch a selector group to the node after its current selection, returns the new node
+pub async fn switch_to_next_proxy(group: &str) -> anyhow::Result<String> {
+    let proxies = IpcManager::global()
+        .get_proxies()
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    let info = proxies
+        .get("proxies")
+        .and_then(|proxies| proxies.get(group))
+        .ok_or_else(|| anyhow::anyhow!("proxy group not found: {group}"))?;
+    if info.get("type").and_then(|t| t.as_str()) != Some("Selector") {
+        anyhow::bail!("proxy group {group} is not a selector");
+    }
+    let all: Vec<&str> = info
+        .get("all")
+        .and_then(|all| all.as_array())
+        .map(|all| all.iter().filter_map(|name| name.as_str()).collect())
+        .unwrap_or_default();
+    if all.is_empty() {
+        anyhow::bail!("proxy group {group} is empty");
+    }
+    let now = info.get("now").and_then(|now| now.as_str());
+    let next = match all.iter().position(|name| Some(*name) == now) {
+        Some(index) => all[(index + 1) % all.len()],
+        None => all[0],
+    };
+    crate::cmd::proxy::update_proxy_and_sync(group.to_string(), next.to_string())
+        .await
+        .map_err(anyhow::Error::msg)?;
+    Ok(next.to_string())
+}
+
 /// Copy proxy environment variables to clipboard
 pub async fn copy_clash_env() {
     // 从环境变量获取IP地址，如果没有则从配置中获取 proxy_host，默认为 127.0.0.1
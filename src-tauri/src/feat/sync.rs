@@ -1,10 +1,20 @@
 use crate::cmd::subscription_groups::get_favorite_subscription_uids;
-use crate::config::{Config, PrfItem, PrfOption};
+use crate::config::{
+    Config, IVerge, PrfItem, PrfOption, profiles_append_item_safe, profiles_delete_item_safe,
+    profiles_patch_item_safe,
+};
+use crate::core::backup::{BackupProvider, BackupStorage};
 use crate::core::{CoreManager, handle};
 use crate::state::subscription_sync::{SUBSCRIPTION_SYNC_STORE, SubscriptionSyncState, SyncPhase};
+use crate::utils::dirs;
 use crate::utils::network::{resolve_mixed_port, wait_for_port_ready};
 use crate::{logging, utils::logging::Type};
 use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::env::temp_dir;
+use std::path::{Path, PathBuf};
 use tokio::time::{Duration, sleep};
 
 pub async fn schedule_subscription_sync(uid: String, phase: SyncPhase) -> Result<()> {
@@ -89,3 +99,532 @@ async fn load_profile_for_sync(uid: &str) -> Result<(PrfItem, Option<PrfOption>)
         .context("profile not found")?;
     Ok((profile.clone(), profile.option.clone()))
 }
+
+// ===== 多设备配置同步 =====
+
+/// 远程清单文件，记录每个同步条目的最新版本
+const SYNC_MANIFEST: &str = "liebesu-sync-manifest.json";
+const SYNC_ITEM_PREFIX: &str = "liebesu-sync-";
+const SYNC_STATE_FILE: &str = "device_sync.json";
+const SETTINGS_KEY: &str = "settings";
+
+/// 与设备相关的设置，不参与同步
+const DEVICE_LOCAL_SETTINGS: &[&str] = &[
+    "webdav_url",
+    "webdav_username",
+    "webdav_password",
+    "remote_backup_provider",
+    "s3_backup",
+    "enable_tun_mode",
+    "enable_system_proxy",
+    "enable_auto_launch",
+    "enable_silent_start",
+    "service_state",
+    "startup_script",
+];
+
+/// 同步过程串行执行
+static DEVICE_SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 远程清单中的条目版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncItemMeta {
+    pub name: String,
+    pub hash: String,
+    pub revision: String,
+    pub device_id: String,
+    pub modified_at: i64,
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncManifest {
+    items: HashMap<String, SyncItemMeta>,
+}
+
+/// 上次同步完成时双方一致的版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncBase {
+    hash: String,
+    revision: String,
+}
+
+/// 本地与远程同时修改的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub item: String,
+    pub name: String,
+    pub local_modified_at: Option<i64>, // None 表示本地已删除
+    pub remote_modified_at: i64,
+    pub remote_device: String,
+    pub remote_deleted: bool,
+}
+
+/// 冲突处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    KeepLocal,
+    KeepRemote,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub uploaded: usize,
+    pub downloaded: usize,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DeviceSyncState {
+    device_id: String,
+    last_sync: Option<i64>,
+    last_error: Option<String>,
+    items: HashMap<String, SyncBase>,
+    conflicts: Vec<SyncConflict>,
+}
+
+/// 同步条目内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SyncPayload {
+    Profile { item: PrfItem, content: String },
+    Settings { settings: serde_json::Value },
+}
+
+struct LocalItem {
+    name: String,
+    hash: String,
+    modified_at: i64,
+    payload: SyncPayload,
+}
+
+/// 与远程存储进行双向同步，双方都修改过的条目记为冲突，不做覆盖
+pub async fn sync_devices() -> Result<SyncReport> {
+    let _guard = DEVICE_SYNC_LOCK.lock().await;
+    let storage = BackupProvider::current().await.storage();
+    let mut state = load_sync_state()?;
+
+    let result = run_device_sync(storage, &mut state).await;
+    state.last_sync = Some(chrono::Utc::now().timestamp());
+    state.last_error = result.as_ref().err().map(|e| e.to_string());
+    save_sync_state(&state)?;
+    result
+}
+
+/// 获取未解决的同步冲突
+pub fn get_device_sync_conflicts() -> Result<Vec<SyncConflict>> {
+    Ok(load_sync_state()?.conflicts)
+}
+
+/// 按指定方式解决冲突
+pub async fn resolve_device_sync_conflict(item: &str, strategy: ConflictStrategy) -> Result<()> {
+    let _guard = DEVICE_SYNC_LOCK.lock().await;
+    let storage = BackupProvider::current().await.storage();
+    let mut state = load_sync_state()?;
+    if !state.conflicts.iter().any(|c| c.item == item) {
+        return Err(anyhow!("sync conflict not found: {item}"));
+    }
+
+    let mut manifest = download_manifest(storage).await?;
+    match strategy {
+        ConflictStrategy::KeepLocal => {
+            let mut local = collect_local_items().await?;
+            push_item(storage, &mut manifest, &mut state, item, local.remove(item)).await?;
+            upload_json(storage, SYNC_MANIFEST, &manifest).await?;
+        }
+        ConflictStrategy::KeepRemote => {
+            let remote = manifest
+                .items
+                .get(item)
+                .cloned()
+                .ok_or_else(|| anyhow!("remote sync item not found: {item}"))?;
+            if pull_item(storage, &mut state, item, &remote).await? {
+                refresh_after_sync().await;
+            }
+        }
+    }
+
+    state.conflicts.retain(|c| c.item != item);
+    save_sync_state(&state)?;
+    logging!(
+        info,
+        Type::Config,
+        "[多设备同步] 冲突 {} 已按 {:?} 解决",
+        item,
+        strategy
+    );
+    Ok(())
+}
+
+async fn run_device_sync(
+    storage: &dyn BackupStorage,
+    state: &mut DeviceSyncState,
+) -> Result<SyncReport> {
+    let mut local = collect_local_items().await?;
+    let mut manifest = download_manifest(storage).await?;
+    let mut report = SyncReport::default();
+    let mut manifest_changed = false;
+    let mut needs_refresh = false;
+
+    let keys: BTreeSet<String> = local
+        .keys()
+        .chain(manifest.items.keys())
+        .chain(state.items.keys())
+        .cloned()
+        .collect();
+
+    for key in keys {
+        let local_item = local.remove(&key);
+        let remote = manifest.items.get(&key).cloned();
+        let base = state.items.get(&key).cloned();
+
+        let local_hash = local_item.as_ref().map(|i| i.hash.as_str());
+        let remote_hash = remote
+            .as_ref()
+            .filter(|r| !r.deleted)
+            .map(|r| r.hash.as_str());
+        let local_changed = local_hash != base.as_ref().map(|b| b.hash.as_str());
+        let remote_changed =
+            remote.as_ref().map(|r| &r.revision) != base.as_ref().map(|b| &b.revision);
+
+        if !local_changed && !remote_changed {
+            continue;
+        }
+        if local_hash == remote_hash {
+            // 双方内容一致，仅记录基线
+            match remote.as_ref().filter(|r| !r.deleted) {
+                Some(remote) => {
+                    state.items.insert(
+                        key,
+                        SyncBase {
+                            hash: remote.hash.clone(),
+                            revision: remote.revision.clone(),
+                        },
+                    );
+                }
+                None => {
+                    state.items.remove(&key);
+                }
+            }
+            continue;
+        }
+
+        match (local_changed, remote) {
+            (true, Some(remote)) if remote_changed => {
+                report.conflicts.push(SyncConflict {
+                    name: local_item
+                        .as_ref()
+                        .map_or_else(|| remote.name.clone(), |i| i.name.clone()),
+                    local_modified_at: local_item.as_ref().map(|i| i.modified_at),
+                    remote_modified_at: remote.modified_at,
+                    remote_device: remote.device_id.clone(),
+                    remote_deleted: remote.deleted,
+                    item: key,
+                });
+            }
+            (false, Some(remote)) => {
+                needs_refresh |= pull_item(storage, state, &key, &remote).await?;
+                report.downloaded += 1;
+            }
+            // 仅本地修改，或远程清单缺少该条目
+            (true, Some(_)) | (_, None) => {
+                push_item(storage, &mut manifest, state, &key, local_item).await?;
+                manifest_changed = true;
+                report.uploaded += 1;
+            }
+        }
+    }
+
+    if manifest_changed {
+        upload_json(storage, SYNC_MANIFEST, &manifest).await?;
+    }
+    if needs_refresh {
+        refresh_after_sync().await;
+    }
+
+    state.conflicts = report.conflicts.clone();
+    logging!(
+        info,
+        Type::Config,
+        "[多设备同步] 上传 {} 项，下载 {} 项，冲突 {} 项",
+        report.uploaded,
+        report.downloaded,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+/// 上传本地条目（None 表示本地已删除）并更新清单
+async fn push_item(
+    storage: &dyn BackupStorage,
+    manifest: &mut SyncManifest,
+    state: &mut DeviceSyncState,
+    key: &str,
+    local_item: Option<LocalItem>,
+) -> Result<()> {
+    let revision = nanoid::nanoid!();
+    let meta = match local_item {
+        Some(item) => {
+            upload_json(storage, &item_file_name(key), &item.payload).await?;
+            state.items.insert(
+                key.to_string(),
+                SyncBase {
+                    hash: item.hash.clone(),
+                    revision: revision.clone(),
+                },
+            );
+            SyncItemMeta {
+                name: item.name,
+                hash: item.hash,
+                revision,
+                device_id: state.device_id.clone(),
+                modified_at: item.modified_at,
+                deleted: false,
+            }
+        }
+        None => {
+            state.items.remove(key);
+            let name = manifest
+                .items
+                .get(key)
+                .map(|m| m.name.clone())
+                .unwrap_or_default();
+            SyncItemMeta {
+                name,
+                hash: String::new(),
+                revision,
+                device_id: state.device_id.clone(),
+                modified_at: chrono::Utc::now().timestamp(),
+                deleted: true,
+            }
+        }
+    };
+    manifest.items.insert(key.to_string(), meta);
+    Ok(())
+}
+
+/// 下载远程条目并应用到本地，返回是否需要刷新运行时配置
+async fn pull_item(
+    storage: &dyn BackupStorage,
+    state: &mut DeviceSyncState,
+    key: &str,
+    remote: &SyncItemMeta,
+) -> Result<bool> {
+    let needs_refresh = if remote.deleted {
+        state.items.remove(key);
+        delete_local_item(key).await?
+    } else {
+        let payload: SyncPayload = download_json(storage, &item_file_name(key)).await?;
+        let needs_refresh = apply_payload(payload).await?;
+        state.items.insert(
+            key.to_string(),
+            SyncBase {
+                hash: remote.hash.clone(),
+                revision: remote.revision.clone(),
+            },
+        );
+        needs_refresh
+    };
+    logging!(info, Type::Config, "[多设备同步] 已应用远程条目 {}", key);
+    Ok(needs_refresh)
+}
+
+/// 收集本地参与同步的条目
+async fn collect_local_items() -> Result<HashMap<String, LocalItem>> {
+    let mut items = HashMap::new();
+
+    let profile_items = Config::profiles()
+        .await
+        .latest_ref()
+        .get_items()
+        .cloned()
+        .unwrap_or_default();
+    for item in profile_items {
+        let (Some(uid), Some(file)) = (item.uid.clone(), item.file.clone()) else {
+            continue;
+        };
+        let path = dirs::app_profiles_dir()?.join(&file);
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let is_remote = item.itype.as_deref() == Some("remote");
+
+        // 远程订阅各设备自行更新，只比较订阅信息，不比较内容
+        let synced = PrfItem {
+            selected: None,
+            extra: None,
+            updated: None,
+            ..item.clone()
+        };
+        let hash = content_hash(&(&synced, (!is_remote).then_some(&content)))?;
+        items.insert(
+            format!("profile:{uid}"),
+            LocalItem {
+                name: item.name.clone().unwrap_or_else(|| uid.clone()),
+                hash,
+                modified_at: file_modified_at(&path),
+                payload: SyncPayload::Profile { item, content },
+            },
+        );
+    }
+
+    let mut settings = serde_json::to_value(Config::verge().await.latest_ref().as_ref())?;
+    if let Some(settings) = settings.as_object_mut() {
+        settings.retain(|key, value| {
+            !DEVICE_LOCAL_SETTINGS.contains(&key.as_str()) && !value.is_null()
+        });
+    }
+    items.insert(
+        SETTINGS_KEY.to_string(),
+        LocalItem {
+            name: "Settings".to_string(),
+            hash: content_hash(&settings)?,
+            modified_at: file_modified_at(&dirs::verge_path()?),
+            payload: SyncPayload::Settings { settings },
+        },
+    );
+
+    Ok(items)
+}
+
+/// 应用远程条目，返回是否影响当前运行配置
+async fn apply_payload(payload: SyncPayload) -> Result<bool> {
+    match payload {
+        SyncPayload::Profile { item, content } => {
+            let uid = item.uid.clone().context("sync profile missing uid")?;
+            let (existing, is_current) = {
+                let profiles = Config::profiles().await;
+                let profiles = profiles.latest_ref();
+                (
+                    profiles.get_item(&uid).ok().cloned(),
+                    profiles.get_current().as_deref() == Some(uid.as_str()),
+                )
+            };
+
+            match existing {
+                Some(existing) => {
+                    if existing.itype.as_deref() != Some("remote") {
+                        existing.save_file(content)?;
+                    }
+                    let patch = PrfItem {
+                        selected: None,
+                        extra: None,
+                        updated: None,
+                        file: None,
+                        ..item
+                    };
+                    profiles_patch_item_safe(uid, patch).await?;
+                }
+                None => {
+                    let item = PrfItem {
+                        selected: None,
+                        file_data: Some(content),
+                        ..item
+                    };
+                    profiles_append_item_safe(item).await?;
+                }
+            }
+            Ok(is_current)
+        }
+        SyncPayload::Settings { settings } => {
+            let patch: IVerge = serde_json::from_value(settings)?;
+            super::patch_verge(patch, false).await?;
+            Ok(false)
+        }
+    }
+}
+
+/// 删除远程已删除的本地条目
+async fn delete_local_item(key: &str) -> Result<bool> {
+    let Some(uid) = key.strip_prefix("profile:") else {
+        return Ok(false);
+    };
+    let exists = Config::profiles()
+        .await
+        .latest_ref()
+        .get_item(&uid.to_string())
+        .is_ok();
+    if !exists {
+        return Ok(false);
+    }
+    profiles_delete_item_safe(uid.to_string()).await
+}
+
+async fn refresh_after_sync() {
+    if let Err(e) = CoreManager::global().update_config().await {
+        logging!(warn, Type::Config, "[多设备同步] 刷新配置失败: {}", e);
+    }
+    handle::Handle::refresh_clash();
+    handle::Handle::refresh_verge();
+}
+
+async fn download_manifest(storage: &dyn BackupStorage) -> Result<SyncManifest> {
+    let files = storage.list_files().await?;
+    if !files.iter().any(|f| f.name == SYNC_MANIFEST) {
+        return Ok(SyncManifest::default());
+    }
+    download_json(storage, SYNC_MANIFEST).await
+}
+
+async fn upload_json<T: Serialize + Sync>(
+    storage: &dyn BackupStorage,
+    file_name: &str,
+    value: &T,
+) -> Result<()> {
+    let path = temp_dir().join(format!("{}-{file_name}", nanoid::nanoid!()));
+    std::fs::write(&path, serde_json::to_vec(value)?)?;
+    let result = storage.upload(path.clone(), file_name.to_string()).await;
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+async fn download_json<T: DeserializeOwned>(
+    storage: &dyn BackupStorage,
+    file_name: &str,
+) -> Result<T> {
+    let path = temp_dir().join(format!("{}-{file_name}", nanoid::nanoid!()));
+    storage
+        .download(file_name.to_string(), path.clone())
+        .await?;
+    let data = std::fs::read(&path);
+    let _ = std::fs::remove_file(&path);
+    Ok(serde_json::from_slice(&data?)?)
+}
+
+fn item_file_name(key: &str) -> String {
+    format!("{SYNC_ITEM_PREFIX}{}.json", key.replace(':', "_"))
+}
+
+fn content_hash<T: Serialize>(value: &T) -> Result<String> {
+    Ok(hex::encode(Sha256::digest(serde_json::to_vec(value)?)))
+}
+
+fn file_modified_at(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp())
+        .unwrap_or_default()
+}
+
+fn sync_state_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(SYNC_STATE_FILE))
+}
+
+fn load_sync_state() -> Result<DeviceSyncState> {
+    let path = sync_state_path()?;
+    let mut state: DeviceSyncState = if path.exists() {
+        serde_json::from_str(&std::fs::read_to_string(path)?)?
+    } else {
+        DeviceSyncState::default()
+    };
+    if state.device_id.is_empty() {
+        state.device_id = nanoid::nanoid!();
+    }
+    Ok(state)
+}
+
+fn save_sync_state(state: &DeviceSyncState) -> Result<()> {
+    std::fs::write(sync_state_path()?, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
@@ -9,12 +9,16 @@ use crate::{
 };
 
 /// Open or close the dashboard window
-pub async fn open_or_close_dashboard() {
-    open_or_close_dashboard_internal().await
+pub async fn open_or_close_dashboard(trigger: lightweight::ExitTrigger) {
+    open_or_close_dashboard_internal(trigger).await
 }
 
 /// Internal implementation for opening/closing dashboard
-async fn open_or_close_dashboard_internal() {
+async fn open_or_close_dashboard_internal(trigger: lightweight::ExitTrigger) {
+    // 轻量模式策略不允许该来源退出时不打开窗口
+    if lightweight::should_stay_lightweight(trigger).await {
+        return;
+    }
     let _ = lightweight::exit_lightweight_mode().await;
     let result = WindowManager::toggle_main_window().await;
     log::info!(target: "app", "Window toggle result: {result:?}");
@@ -0,0 +1,94 @@
+use kode_bridge::errors::AnyResult;
+use serde::Serialize;
+
+use super::IpcManager;
+use crate::{logging, utils::logging::Type};
+
+// 可选接口所需的最低 mihomo 版本
+const GROUP_DELAY_SINCE: (u32, u32, u32) = (1, 14, 0);
+const DNS_QUERY_SINCE: (u32, u32, u32) = (1, 14, 3);
+const GEO_UPDATE_SINCE: (u32, u32, u32) = (1, 15, 0);
+const UPGRADE_SINCE: (u32, u32, u32) = (1, 16, 0);
+
+/// 当前内核支持的可选接口
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreCapabilities {
+    pub version: String,
+    pub meta: bool,
+    pub group_delay: bool, // /group/{name}/delay
+    pub dns_query: bool,   // /dns/query
+    pub geo_update: bool,  // /configs/geo
+    pub upgrade: bool,     // /upgrade
+}
+
+impl CoreCapabilities {
+    /// 根据 /version 返回值推断接口能力
+    /// alpha 等无法解析的版本号视为最新版本
+    fn from_version(version: &serde_json::Value) -> Self {
+        let meta = version["meta"].as_bool().unwrap_or(false);
+        let version = version["version"].as_str().unwrap_or_default().to_string();
+        let parsed = parse_version(&version);
+        let since = |min: (u32, u32, u32)| meta && parsed.is_none_or(|v| v >= min);
+
+        Self {
+            meta,
+            group_delay: since(GROUP_DELAY_SINCE),
+            dns_query: since(DNS_QUERY_SINCE),
+            geo_update: since(GEO_UPDATE_SINCE),
+            upgrade: since(UPGRADE_SINCE),
+            version,
+        }
+    }
+}
+
+/// 解析 "v1.19.2"、"1.18.10-alpha" 形式的版本号
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let version = version.trim().trim_start_matches('v');
+    let end = version
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(version.len());
+    let mut parts = version[..end].split('.').map(|p| p.parse::<u32>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+impl IpcManager {
+    /// 获取内核接口能力，首次调用时探测 /version 并缓存
+    pub async fn capabilities(&self) -> AnyResult<CoreCapabilities> {
+        if let Some(capabilities) = self.capabilities.read().clone() {
+            return Ok(capabilities);
+        }
+
+        let version = self.get_version().await?;
+        let capabilities = CoreCapabilities::from_version(&version);
+        logging!(
+            info,
+            Type::Ipc,
+            true,
+            "内核版本 {} 接口能力: {:?}",
+            capabilities.version,
+            capabilities
+        );
+        *self.capabilities.write() = Some(capabilities.clone());
+        Ok(capabilities)
+    }
+
+    /// 内核重启或切换后清除缓存，下次使用时重新探测
+    pub fn reset_capabilities(&self) {
+        *self.capabilities.write() = None;
+    }
+
+    /// 判断可选接口是否可用，探测失败时不做限制
+    pub(super) async fn supports(&self, check: fn(&CoreCapabilities) -> bool) -> bool {
+        match self.capabilities().await {
+            Ok(capabilities) => check(&capabilities),
+            Err(e) => {
+                logging!(warn, Type::Ipc, true, "探测内核版本失败: {}", e);
+                true
+            }
+        }
+    }
+}
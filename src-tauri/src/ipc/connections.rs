@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tokio::{sync::RwLock, time::Duration};
+
+use crate::{
+    cmd::{
+        selection_memory::detect_region,
+        traffic_stats::{ConnectionUsage, record_connection_usage},
+    },
+    ipc::{
+        monitor::{IpcStreamMonitor, MonitorData, StreamingParser},
+        multiplexer::StreamChannel,
+    },
+    process::AsyncHandler,
+    singleton_lazy_with_logging,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+struct ConnectionsSnapshot {
+    #[serde(default)]
+    connections: Vec<serde_json::Value>,
+}
+
+/// 连接流监控状态，记录各连接上次看到的累计流量，用于计算增量
+#[derive(Debug, Clone)]
+pub struct ConnectionMonitorState {
+    seen: HashMap<String, (u64, u64)>, // 连接 ID -> 累计上传 / 下载
+    last_updated: Instant,
+}
+
+impl Default for ConnectionMonitorState {
+    fn default() -> Self {
+        Self {
+            seen: HashMap::new(),
+            last_updated: Instant::now(),
+        }
+    }
+}
+
+impl MonitorData for ConnectionMonitorState {
+    fn mark_fresh(&mut self) {
+        self.last_updated = Instant::now();
+    }
+
+    fn is_fresh_within(&self, duration: Duration) -> bool {
+        self.last_updated.elapsed() < duration
+    }
+}
+
+impl StreamingParser for ConnectionMonitorState {
+    fn parse_and_update(
+        line: &str,
+        current: Arc<RwLock<Self>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let snapshot = serde_json::from_str::<ConnectionsSnapshot>(line.trim())?;
+        AsyncHandler::spawn(move || async move {
+            let usages = {
+                let mut state = current.write().await;
+                let usages = state.ingest(&snapshot.connections);
+                state.mark_fresh();
+                usages
+            };
+            if !usages.is_empty() {
+                record_connection_usage(usages).await;
+            }
+        });
+        Ok(())
+    }
+}
+
+impl ConnectionMonitorState {
+    /// 连接快照中的流量为累计值，与上次快照比较得到增量；只保留仍存在的连接
+    fn ingest(&mut self, connections: &[serde_json::Value]) -> Vec<ConnectionUsage> {
+        let mut current = HashMap::with_capacity(connections.len());
+        let mut usages = Vec::new();
+        for connection in connections {
+            let Some(id) = connection["id"].as_str() else {
+                continue;
+            };
+            let upload = connection["upload"].as_u64().unwrap_or_default();
+            let download = connection["download"].as_u64().unwrap_or_default();
+            let (last_upload, last_download) = self.seen.get(id).copied().unwrap_or_default();
+            let usage = ConnectionUsage {
+                app: connection_app(connection),
+                region: connection_region(connection),
+                upload_bytes: upload.saturating_sub(last_upload),
+                download_bytes: download.saturating_sub(last_download),
+            };
+            if usage.upload_bytes > 0 || usage.download_bytes > 0 {
+                usages.push(usage);
+            }
+            current.insert(id.to_string(), (upload, download));
+        }
+        self.seen = current;
+        usages
+    }
+}
+
+/// 连接流监控：按应用与出站地区累计流量到流量统计
+pub struct ConnectionMonitor {
+    _monitor: IpcStreamMonitor<ConnectionMonitorState>, // 统计结果直接写入流量统计，无需读取状态
+}
+
+impl Default for ConnectionMonitor {
+    fn default() -> Self {
+        ConnectionMonitor {
+            _monitor: IpcStreamMonitor::new(StreamChannel::Connections, Duration::from_secs(5)),
+        }
+    }
+}
+
+singleton_lazy_with_logging!(
+    ConnectionMonitor,
+    INSTANCE,
+    "ConnectionMonitor",
+    ConnectionMonitor::default
+);
+
+/// 启动连接流监控
+pub fn init_connection_monitor() {
+    ConnectionMonitor::global();
+}
+
+fn connection_app(connection: &serde_json::Value) -> String {
+    connection["metadata"]["process"]
+        .as_str()
+        .filter(|process| !process.is_empty())
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
+/// 出站节点为连接链的第一项，直连与拒绝单独统计
+fn connection_region(connection: &serde_json::Value) -> String {
+    match connection["chains"][0].as_str() {
+        Some(node @ ("DIRECT" | "REJECT" | "REJECT-DROP")) => node.to_string(),
+        Some(node) => detect_region(node).unwrap_or("OTHER").to_string(),
+        None => "OTHER".to_string(),
+    }
+}
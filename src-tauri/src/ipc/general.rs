@@ -1,13 +1,18 @@
 use std::time::Duration;
 
+use futures::future::join_all;
 use kode_bridge::{
     ClientConfig, IpcHttpClient, LegacyResponse,
     errors::{AnyError, AnyResult},
 };
+use parking_lot::RwLock;
 use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
 
+use super::capabilities::CoreCapabilities;
+
 use crate::{
-    logging, singleton_with_logging,
+    cmd::delay_targets, logging, singleton_with_logging,
+    state::proxy::{CacheEndpoint, ProxyRequestCache},
     utils::{dirs::ipc_path, logging::Type},
 };
 
@@ -27,6 +32,7 @@ fn create_error(msg: impl Into<String>) -> AnyError {
 
 pub struct IpcManager {
     client: IpcHttpClient,
+    pub(super) capabilities: RwLock<Option<CoreCapabilities>>,
 }
 
 impl IpcManager {
@@ -52,7 +58,10 @@ impl IpcManager {
         };
         #[allow(clippy::unwrap_used)]
         let client = IpcHttpClient::with_config(ipc_path, config).unwrap();
-        Self { client }
+        Self {
+            client,
+            capabilities: RwLock::new(None),
+        }
     }
 }
 
@@ -173,6 +182,8 @@ impl IpcManager {
             "path": clash_config_path,
         });
         let _response = self.send_request("PUT", url, Some(&payload)).await?;
+        // 配置整体重载，代理、提供者和配置都可能变化
+        ProxyRequestCache::global().invalidate_all();
         Ok(())
     }
 
@@ -180,6 +191,7 @@ impl IpcManager {
         let url = "/configs";
         let response = self.send_request("PATCH", url, Some(&config)).await?;
         if response["code"] == 204 {
+            ProxyRequestCache::global().invalidate(CacheEndpoint::ClashConfig);
             Ok(())
         } else {
             Err(create_error(
@@ -191,18 +203,33 @@ impl IpcManager {
         }
     }
 
+    /// expected 为期望的状态码，格式同内核，如 204 或 200-299/302
     pub async fn test_proxy_delay(
         &self,
         name: &str,
         test_url: Option<String>,
+        expected: Option<&str>,
         timeout: i32,
     ) -> AnyResult<serde_json::Value> {
-        let test_url =
-            test_url.unwrap_or_else(|| "https://cp.cloudflare.com/generate_204".to_string());
+        // 未指定测速地址时使用测速目标设置中的默认目标
+        let default_target;
+        let (test_url, expected) = match test_url {
+            Some(test_url) => (test_url, expected),
+            None => {
+                default_target = delay_targets::default_target();
+                (
+                    default_target.url.clone(),
+                    expected.or(default_target.expected_status.as_deref()),
+                )
+            }
+        };
 
         let encoded_name = utf8_percent_encode(name, URL_PATH_ENCODE_SET).to_string();
         // 测速URL不再编码，直接传递
-        let url = format!("/proxies/{encoded_name}/delay?url={test_url}&timeout={timeout}");
+        let mut url = format!("/proxies/{encoded_name}/delay?url={test_url}&timeout={timeout}");
+        if let Some(expected) = expected {
+            url.push_str(&format!("&expected={expected}"));
+        }
 
         self.send_request("GET", &url, None).await
     }
@@ -219,6 +246,9 @@ impl IpcManager {
     }
 
     pub async fn update_geo_data(&self) -> AnyResult<()> {
+        if !self.supports(|c| c.geo_update).await {
+            return Err(create_error("当前内核版本不支持更新 GeoData"));
+        }
         let url = "/configs/geo";
         let response = self.send_request("POST", url, None).await?;
         if response["code"] == 204 {
@@ -234,6 +264,9 @@ impl IpcManager {
     }
 
     pub async fn upgrade_core(&self) -> AnyResult<()> {
+        if !self.supports(|c| c.upgrade).await {
+            return Err(create_error("当前内核版本不支持在线升级"));
+        }
         let url = "/upgrade";
         let response = self.send_request("POST", url, None).await?;
         if response["code"] == 204 {
@@ -284,7 +317,10 @@ impl IpcManager {
             "name": proxy
         });
         match self.send_request("PUT", &url, Some(&payload)).await {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                ProxyRequestCache::global().invalidate(CacheEndpoint::Proxies);
+                Ok(())
+            }
             Err(e) => {
                 logging!(
                     error,
@@ -319,6 +355,8 @@ impl IpcManager {
         let url = format!("/providers/proxies/{encoded_name}");
         let response = self.send_request("PUT", &url, None).await?;
         if response["code"] == 204 {
+            ProxyRequestCache::global().invalidate(CacheEndpoint::Providers);
+            ProxyRequestCache::global().invalidate(CacheEndpoint::Proxies);
             Ok(())
         } else {
             Err(create_error(
@@ -335,17 +373,89 @@ impl IpcManager {
         &self,
         group_name: &str,
         url: Option<String>,
+        expected: Option<&str>,
         timeout: i32,
     ) -> AnyResult<serde_json::Value> {
-        let test_url = url.unwrap_or_else(|| "https://cp.cloudflare.com/generate_204".to_string());
+        // 未指定测速地址时使用测速目标设置中的默认目标
+        let default_target;
+        let (test_url, expected) = match url {
+            Some(url) => (url, expected),
+            None => {
+                default_target = delay_targets::default_target();
+                (
+                    default_target.url.clone(),
+                    expected.or(default_target.expected_status.as_deref()),
+                )
+            }
+        };
+
+        if !self.supports(|c| c.group_delay).await {
+            return self
+                .test_group_members_delay(group_name, test_url, expected, timeout)
+                .await;
+        }
 
         let encoded_group_name = utf8_percent_encode(group_name, URL_PATH_ENCODE_SET).to_string();
         // 测速URL不再编码，直接传递
-        let url = format!("/group/{encoded_group_name}/delay?url={test_url}&timeout={timeout}");
+        let mut url = format!("/group/{encoded_group_name}/delay?url={test_url}&timeout={timeout}");
+        if let Some(expected) = expected {
+            url.push_str(&format!("&expected={expected}"));
+        }
 
         self.send_request("GET", &url, None).await
     }
 
+    /// 内核不支持 /group 接口时，逐个测试组内节点，返回相同格式的结果
+    async fn test_group_members_delay(
+        &self,
+        group_name: &str,
+        test_url: String,
+        expected: Option<&str>,
+        timeout: i32,
+    ) -> AnyResult<serde_json::Value> {
+        let encoded_group_name = utf8_percent_encode(group_name, URL_PATH_ENCODE_SET).to_string();
+        let group = self
+            .send_request("GET", &format!("/proxies/{encoded_group_name}"), None)
+            .await?;
+        let members: Vec<&str> = group["all"]
+            .as_array()
+            .map(|all| all.iter().filter_map(|p| p.as_str()).collect())
+            .unwrap_or_default();
+
+        let results = join_all(
+            members
+                .iter()
+                .map(|name| self.test_proxy_delay(name, Some(test_url.clone()), expected, timeout)),
+        )
+        .await;
+
+        let delays: serde_json::Map<String, serde_json::Value> = members
+            .iter()
+            .zip(results)
+            .filter_map(|(name, result)| {
+                Some((name.to_string(), result.ok()?.get("delay")?.clone()))
+            })
+            .collect();
+        Ok(serde_json::Value::Object(delays))
+    }
+
+    // DNS 查询
+    pub async fn dns_query(
+        &self,
+        name: &str,
+        query_type: Option<&str>,
+    ) -> AnyResult<serde_json::Value> {
+        if !self.supports(|c| c.dns_query).await {
+            return Err(create_error("当前内核版本不支持 DNS 查询"));
+        }
+        let encoded_name = utf8_percent_encode(name, URL_PATH_ENCODE_SET).to_string();
+        let url = format!(
+            "/dns/query?name={encoded_name}&type={}",
+            query_type.unwrap_or("A")
+        );
+        self.send_request("GET", &url, None).await
+    }
+
     // 调试相关
     pub async fn is_debug_enabled(&self) -> AnyResult<bool> {
         let url = "/debug/pprof";
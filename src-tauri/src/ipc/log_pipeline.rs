@@ -0,0 +1,482 @@
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tokio::time::Duration;
+use zip::write::SimpleFileOptions;
+
+use crate::{
+    core::handle,
+    ipc::{
+        logs::LogData,
+        multiplexer::{StreamChannel, spawn_consumer},
+    },
+    logging,
+    process::AsyncHandler,
+    singleton_with_logging,
+    utils::{dirs, logging::Type},
+};
+
+/// 内存中保留的日志条数
+const RING_CAPACITY: usize = 5000;
+/// 当前日志文件超过该大小时压缩归档
+const ROTATE_SIZE: u64 = 5 * 1024 * 1024;
+/// 保留的归档数量
+const MAX_ARCHIVES: usize = 10;
+/// 写入磁盘的间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+/// 查询默认返回条数
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+const CURRENT_FILE: &str = "current.jsonl";
+const ARCHIVE_PREFIX: &str = "clash-";
+
+/// 连接日志中的连接信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LogConnection {
+    pub network: Option<String>, // TCP/UDP
+    pub source: Option<String>,
+    pub process: Option<String>,
+    pub destination: Option<String>,
+    pub rule: Option<String>,
+    pub rule_payload: Option<String>,
+    pub proxy: Option<String>,
+}
+
+/// 结构化日志
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub time: i64, // 毫秒时间戳
+    pub level: String,
+    pub log_type: Option<String>, // 日志前缀，例如 TCP、UDP、DNS、Sniffer
+    pub message: String,
+    pub connection: Option<LogConnection>,
+}
+
+impl LogRecord {
+    /// 解析内核日志，例如
+    /// "[TCP] 127.0.0.1:51234(chrome) --> www.google.com:443 match DomainSuffix(google.com) using Proxy[HK]"
+    pub fn parse(level: &str, payload: &str, time: i64) -> Self {
+        let payload = payload.trim();
+        let (log_type, body) = match payload
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+        {
+            Some((log_type, body)) => (Some(log_type.trim().to_string()), body.trim()),
+            None => (None, payload),
+        };
+
+        Self {
+            time,
+            level: normalize_level(level),
+            connection: parse_connection(log_type.as_deref(), body),
+            log_type,
+            message: payload.to_string(),
+        }
+    }
+}
+
+fn normalize_level(level: &str) -> String {
+    match level.to_lowercase().as_str() {
+        "warn" => "warning".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_connection(log_type: Option<&str>, body: &str) -> Option<LogConnection> {
+    let (source, rest) = body.split_once(" --> ")?;
+    let (destination, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+
+    let (source, process) = match source.trim().split_once('(') {
+        Some((addr, process)) => (
+            addr.to_string(),
+            Some(process.trim_end_matches(')').to_string()),
+        ),
+        None => (source.trim().to_string(), None),
+    };
+
+    let mut connection = LogConnection {
+        network: log_type
+            .filter(|t| matches!(*t, "TCP" | "UDP"))
+            .map(str::to_string),
+        source: Some(source),
+        process,
+        destination: Some(destination.to_string()),
+        ..LogConnection::default()
+    };
+
+    let (matched, proxy) = match rest.split_once(" using ") {
+        Some((matched, proxy)) => (matched, Some(proxy.trim())),
+        None => (rest, None),
+    };
+    connection.proxy = proxy.map(str::to_string);
+    if let Some(rule) = matched.trim().strip_prefix("match ") {
+        match rule.split_once('(') {
+            Some((name, payload)) => {
+                connection.rule = Some(name.to_string());
+                connection.rule_payload = Some(payload.trim_end_matches(')').to_string());
+            }
+            None => connection.rule = Some(rule.to_string()),
+        }
+    } else if matched.contains("doesn't match any rule") {
+        connection.rule = Some("Match".to_string());
+    }
+    Some(connection)
+}
+
+/// 日志过滤条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogFilter {
+    pub levels: Option<Vec<String>>,
+    pub log_type: Option<String>,
+    pub keyword: Option<String>,
+    pub proxy: Option<String>,
+    pub rule: Option<String>,
+}
+
+/// 查询时间范围（毫秒时间戳）
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LogTimeRange {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl LogTimeRange {
+    fn contains(&self, time: i64) -> bool {
+        self.start.is_none_or(|s| time >= s) && self.end.is_none_or(|e| time <= e)
+    }
+
+    fn overlaps(&self, first: i64, last: i64) -> bool {
+        self.start.is_none_or(|s| last >= s) && self.end.is_none_or(|e| first <= e)
+    }
+}
+
+impl LogFilter {
+    fn matches(&self, record: &LogRecord) -> bool {
+        let contains = |value: Option<&String>, needle: &str| {
+            value.is_some_and(|v| v.to_lowercase().contains(&needle.to_lowercase()))
+        };
+        let connection = record.connection.as_ref();
+
+        self.levels
+            .as_ref()
+            .is_none_or(|levels| levels.iter().any(|l| normalize_level(l) == record.level))
+            && self.log_type.as_ref().is_none_or(|t| {
+                record
+                    .log_type
+                    .as_ref()
+                    .is_some_and(|r| r.eq_ignore_ascii_case(t))
+            })
+            && self
+                .keyword
+                .as_ref()
+                .is_none_or(|k| contains(Some(&record.message), k))
+            && self
+                .proxy
+                .as_ref()
+                .is_none_or(|p| contains(connection.and_then(|c| c.proxy.as_ref()), p))
+            && self
+                .rule
+                .as_ref()
+                .is_none_or(|r| contains(connection.and_then(|c| c.rule.as_ref()), r))
+    }
+}
+
+/// 日志导出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogExportFormat {
+    Json,
+    Csv,
+    Text,
+}
+
+/// 内核日志管道：解析日志流，保存在环形缓冲区并按大小压缩归档
+pub struct LogPipeline {
+    buffer: Mutex<VecDeque<LogRecord>>,
+    unflushed: Mutex<Vec<LogRecord>>,
+    started: AtomicBool,
+}
+
+singleton_with_logging!(LogPipeline, INSTANCE, "LogPipeline");
+
+impl LogPipeline {
+    fn new() -> Self {
+        Self {
+            buffer: Mutex::new(VecDeque::with_capacity(RING_CAPACITY)),
+            unflushed: Mutex::new(Vec::new()),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// 订阅日志流并定期写入磁盘
+    pub fn start(&'static self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        spawn_consumer(StreamChannel::Logs, move |line| self.ingest(line));
+
+        AsyncHandler::spawn(move || async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                let exiting = handle::Handle::global().is_exiting();
+                if let Err(e) = self.flush() {
+                    logging!(warn, Type::Ipc, true, "[日志管道] 写入日志失败: {}", e);
+                }
+                if exiting {
+                    break;
+                }
+            }
+        });
+    }
+
+    fn ingest(&self, line: &str) {
+        let Ok(data) = serde_json::from_str::<LogData>(line) else {
+            return;
+        };
+        let record = LogRecord::parse(
+            &data.log_type,
+            &data.payload,
+            chrono::Utc::now().timestamp_millis(),
+        );
+
+        {
+            let mut buffer = self.buffer.lock();
+            if buffer.len() >= RING_CAPACITY {
+                buffer.pop_front();
+            }
+            buffer.push_back(record.clone());
+        }
+        self.unflushed.lock().push(record);
+    }
+
+    /// 追加写入当前文件，超过大小后压缩归档
+    fn flush(&self) -> Result<()> {
+        let records = std::mem::take(&mut *self.unflushed.lock());
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let dir = log_dir()?;
+        let path = dir.join(CURRENT_FILE);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        for record in &records {
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+        }
+        drop(file);
+
+        if fs::metadata(&path)?.len() >= ROTATE_SIZE {
+            rotate(&dir, &path)?;
+        }
+        Ok(())
+    }
+
+    /// 查询日志，按时间倒序返回
+    pub fn query(&self, filter: &LogFilter, range: LogTimeRange, limit: usize) -> Vec<LogRecord> {
+        let limit = if limit == 0 {
+            DEFAULT_QUERY_LIMIT
+        } else {
+            limit
+        };
+        let mut records = self.collect(range);
+        records.retain(|r| filter.matches(r));
+        records.reverse();
+        records.truncate(limit);
+        records
+    }
+
+    /// 导出时间范围内的日志，返回文件路径
+    pub fn export(&self, range: LogTimeRange, format: LogExportFormat) -> Result<PathBuf> {
+        let records = self.collect(range);
+        let now = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+        let (extension, content) = match format {
+            LogExportFormat::Json => ("json", serde_json::to_string_pretty(&records)?),
+            LogExportFormat::Csv => {
+                let escape = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+                let mut content =
+                    "time,level,type,source,destination,rule,proxy,message\n".to_string();
+                for r in &records {
+                    let c = r.connection.clone().unwrap_or_default();
+                    content.push_str(&format!(
+                        "{},{},{},{},{},{},{},{}\n",
+                        format_time(r.time),
+                        r.level,
+                        escape(r.log_type.as_deref().unwrap_or_default()),
+                        escape(c.source.as_deref().unwrap_or_default()),
+                        escape(c.destination.as_deref().unwrap_or_default()),
+                        escape(c.rule.as_deref().unwrap_or_default()),
+                        escape(c.proxy.as_deref().unwrap_or_default()),
+                        escape(&r.message)
+                    ));
+                }
+                ("csv", content)
+            }
+            LogExportFormat::Text => (
+                "log",
+                records
+                    .iter()
+                    .map(|r| format!("{} [{}] {}\n", format_time(r.time), r.level, r.message))
+                    .collect(),
+            ),
+        };
+
+        let path = dirs::app_logs_dir()?.join(format!("clash-export-{now}.{extension}"));
+        fs::write(&path, content)?;
+        logging!(
+            info,
+            Type::Ipc,
+            true,
+            "[日志管道] 已导出 {} 条日志到 {}",
+            records.len(),
+            path.display()
+        );
+        Ok(path)
+    }
+
+    /// 收集范围内的日志，内存中不足时读取磁盘文件，按时间正序返回
+    fn collect(&self, range: LogTimeRange) -> Vec<LogRecord> {
+        let (buffered, oldest) = {
+            let buffer = self.buffer.lock();
+            let oldest = buffer.front().map(|r| r.time);
+            (
+                buffer
+                    .iter()
+                    .filter(|r| range.contains(r.time))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                oldest,
+            )
+        };
+
+        // 请求范围全部在内存中
+        if oldest.is_some_and(|oldest| range.start.is_some_and(|s| s >= oldest)) {
+            return buffered;
+        }
+
+        let mut records = match read_disk_records(range) {
+            Ok(records) => records,
+            Err(e) => {
+                logging!(warn, Type::Ipc, true, "[日志管道] 读取日志文件失败: {}", e);
+                Vec::new()
+            }
+        };
+        // 内存中的日志可能尚未写入磁盘，以时间去重合并
+        let last_disk = records.last().map(|r| r.time).unwrap_or(i64::MIN);
+        records.extend(buffered.into_iter().filter(|r| r.time > last_disk));
+        records
+    }
+}
+
+fn log_dir() -> Result<PathBuf> {
+    let dir = dirs::app_logs_dir()?.join("clash");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn format_time(time: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(time)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S%.3f")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// 压缩当前文件为 clash-{首条时间}-{末条时间}.zip，并清理多余归档
+fn rotate(dir: &Path, current: &Path) -> Result<()> {
+    let records = read_jsonl(BufReader::new(fs::File::open(current)?));
+    let first = records.first().map(|r| r.time).unwrap_or_default();
+    let last = records.last().map(|r| r.time).unwrap_or_default();
+
+    let archive = dir.join(format!("{ARCHIVE_PREFIX}{first}-{last}.zip"));
+    let mut zip = zip::ZipWriter::new(fs::File::create(&archive)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(CURRENT_FILE, options)?;
+    zip.write_all(&fs::read(current)?)?;
+    zip.finish()?;
+    fs::remove_file(current)?;
+
+    let mut archives = list_archives(dir)?;
+    while archives.len() > MAX_ARCHIVES {
+        let (_, _, oldest) = archives.remove(0);
+        let _ = fs::remove_file(oldest);
+    }
+    logging!(
+        info,
+        Type::Ipc,
+        true,
+        "[日志管道] 已归档 {}",
+        archive.display()
+    );
+    Ok(())
+}
+
+/// 按时间正序列出归档文件 (首条时间, 末条时间, 路径)
+fn list_archives(dir: &Path) -> Result<Vec<(i64, i64, PathBuf)>> {
+    let mut archives: Vec<(i64, i64, PathBuf)> = fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (first, last) = name
+                .strip_prefix(ARCHIVE_PREFIX)?
+                .strip_suffix(".zip")?
+                .split_once('-')?;
+            Some((first.parse().ok()?, last.parse().ok()?, entry.path()))
+        })
+        .collect();
+    archives.sort_by_key(|(first, _, _)| *first);
+    Ok(archives)
+}
+
+fn read_disk_records(range: LogTimeRange) -> Result<Vec<LogRecord>> {
+    let dir = log_dir()?;
+    let mut records = Vec::new();
+    for (first, last, path) in list_archives(&dir)? {
+        if !range.overlaps(first, last) {
+            continue;
+        }
+        let mut zip = zip::ZipArchive::new(fs::File::open(path)?)?;
+        let mut content = String::new();
+        zip.by_index(0)?.read_to_string(&mut content)?;
+        records.extend(read_jsonl(content.as_bytes()));
+    }
+
+    let current = dir.join(CURRENT_FILE);
+    if current.exists() {
+        records.extend(read_jsonl(BufReader::new(fs::File::open(current)?)));
+    }
+    records.retain(|r| range.contains(r.time));
+    Ok(records)
+}
+
+fn read_jsonl(reader: impl BufRead) -> Vec<LogRecord> {
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+pub fn start_log_pipeline() {
+    LogPipeline::global().start();
+}
+
+pub fn query_logs(filter: &LogFilter, range: LogTimeRange, limit: usize) -> Vec<LogRecord> {
+    LogPipeline::global().query(filter, range, limit)
+}
+
+pub fn export_logs(range: LogTimeRange, format: LogExportFormat) -> Result<PathBuf> {
+    LogPipeline::global().export(range, format)
+}
@@ -4,11 +4,14 @@ use tauri::async_runtime::JoinHandle;
 use tokio::{sync::RwLock, time::Duration};
 
 use crate::{
-    ipc::monitor::MonitorData,
+    ipc::{
+        monitor::MonitorData,
+        multiplexer::{StreamChannel, StreamMultiplexer, spawn_consumer},
+    },
     logging,
     process::AsyncHandler,
     singleton_with_logging,
-    utils::{dirs::ipc_path, logging::Type},
+    utils::logging::Type,
 };
 
 const MAX_LOGS: usize = 1000; // Maximum number of logs to keep in memory
@@ -162,42 +165,11 @@ impl LogsMonitor {
 
         let monitor_current = Arc::clone(&self.current);
 
-        let task = AsyncHandler::spawn(move || async move {
-            loop {
-                // Get fresh IPC path and client for each connection attempt
-                let (_ipc_path_buf, client) = match Self::create_ipc_client() {
-                    Ok((path, client)) => (path, client),
-                    Err(e) => {
-                        logging!(error, Type::Ipc, true, "Failed to create IPC client: {}", e);
-                        tokio::time::sleep(Duration::from_secs(2)).await;
-                        continue;
-                    }
-                };
-
-                let url = if filter_level == "all" {
-                    "/logs".to_string()
-                } else {
-                    format!("/logs?level={filter_level}")
-                };
-
-                logging!(
-                    info,
-                    Type::Ipc,
-                    true,
-                    "LogsMonitor: Starting stream for {}",
-                    url
-                );
-
-                let _ = client
-                    .get(&url)
-                    .timeout(Duration::from_secs(30))
-                    .process_lines(|line| {
-                        Self::process_log_line(line, Arc::clone(&monitor_current))
-                    })
-                    .await;
-
-                // Wait before retrying
-                tokio::time::sleep(Duration::from_secs(2)).await;
+        // 上游日志流保持完整级别供日志管道使用，界面级别只在本地过滤
+        StreamMultiplexer::global().set_logs_level(Some(filter_level.clone()));
+        let task = spawn_consumer(StreamChannel::Logs, move |line| {
+            if log_line_visible(&filter_level, line) {
+                let _ = Self::process_log_line(line, Arc::clone(&monitor_current));
             }
         });
 
@@ -236,18 +208,7 @@ impl LogsMonitor {
             let mut monitoring_level = self.current_monitoring_level.write().await;
             *monitoring_level = None;
         }
-    }
-
-    fn create_ipc_client() -> Result<
-        (std::path::PathBuf, kode_bridge::IpcStreamClient),
-        Box<dyn std::error::Error + Send + Sync>,
-    > {
-        use kode_bridge::IpcStreamClient;
-
-        let ipc_path_buf = ipc_path()?;
-        let ipc_path = ipc_path_buf.to_str().ok_or("Invalid IPC path")?;
-        let client = IpcStreamClient::new(ipc_path)?;
-        Ok((ipc_path_buf, client))
+        // 日志流由日志管道继续使用，不在此处断开
     }
 
     fn process_log_line(
@@ -255,8 +216,6 @@ impl LogsMonitor {
         current: Arc<RwLock<CurrentLogs>>,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         if let Ok(log_data) = serde_json::from_str::<LogData>(line.trim()) {
-            // Server-side filtering via query parameters handles the level filtering
-            // We only need to accept all logs since filtering is done at the endpoint level
             let log_item = LogItem::new(log_data.log_type, log_data.payload);
 
             AsyncHandler::spawn(move || async move {
@@ -313,6 +272,26 @@ impl LogsMonitor {
     }
 }
 
+/// 日志级别排序，数值越大越重要
+fn level_rank(level: &str) -> u8 {
+    match level.to_lowercase().as_str() {
+        "debug" => 0,
+        "warn" | "warning" => 2,
+        "error" => 3,
+        "silent" => 4,
+        _ => 1,
+    }
+}
+
+/// 日志是否达到界面选择的级别，"all" 显示全部
+pub fn log_line_visible(view_level: &str, line: &str) -> bool {
+    if view_level == "all" {
+        return true;
+    }
+    serde_json::from_str::<LogData>(line.trim())
+        .is_ok_and(|data| level_rank(&data.log_type) >= level_rank(view_level))
+}
+
 pub async fn start_logs_monitoring(level: Option<String>) {
     LogsMonitor::global().start_monitoring(level).await;
 }
@@ -3,7 +3,10 @@ use std::{sync::Arc, time::Instant};
 use tokio::{sync::RwLock, time::Duration};
 
 use crate::{
-    ipc::monitor::{IpcStreamMonitor, MonitorData, StreamingParser},
+    ipc::{
+        monitor::{IpcStreamMonitor, MonitorData, StreamingParser},
+        multiplexer::StreamChannel,
+    },
     process::AsyncHandler,
     singleton_lazy_with_logging,
     utils::format::fmt_bytes,
@@ -67,12 +70,7 @@ pub struct MemoryMonitor {
 impl Default for MemoryMonitor {
     fn default() -> Self {
         MemoryMonitor {
-            monitor: IpcStreamMonitor::new(
-                "/memory".to_string(),
-                Duration::from_secs(10),
-                Duration::from_secs(2),
-                Duration::from_secs(10),
-            ),
+            monitor: IpcStreamMonitor::new(StreamChannel::Memory, Duration::from_secs(10)),
         }
     }
 }
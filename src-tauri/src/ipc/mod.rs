@@ -1,12 +1,19 @@
+pub mod capabilities;
+pub mod connections;
 pub mod general;
+pub mod log_pipeline;
 pub mod logs;
 pub mod memory;
 pub mod monitor;
+pub mod multiplexer;
 pub mod traffic;
 
+pub use capabilities::CoreCapabilities;
 pub use general::IpcManager;
+pub use log_pipeline::{LogExportFormat, LogFilter, LogRecord, LogTimeRange};
 pub use logs::{clear_logs, get_logs_json, start_logs_monitoring, stop_logs_monitoring};
 pub use memory::{get_current_memory, get_formatted_memory};
+pub use multiplexer::{StreamChannel, StreamMultiplexer};
 pub use traffic::{get_current_traffic, get_formatted_traffic};
 
 pub struct Rate {
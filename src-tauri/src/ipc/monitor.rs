@@ -1,11 +1,10 @@
-use kode_bridge::IpcStreamClient;
 use std::sync::Arc;
 use tokio::{sync::RwLock, time::Duration};
 
 use crate::{
+    ipc::multiplexer::{StreamChannel, spawn_consumer},
     logging,
-    process::AsyncHandler,
-    utils::{dirs::ipc_path, logging::Type},
+    utils::logging::Type,
 };
 
 /// Generic base structure for IPC monitoring data with freshness tracking
@@ -26,18 +25,12 @@ pub trait StreamingParser: MonitorData {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
 }
 
-/// Generic IPC stream monitor that handles the common streaming pattern
+/// Generic IPC stream monitor that consumes one channel of the stream multiplexer
 pub struct IpcStreamMonitor<T>
 where
     T: MonitorData + StreamingParser + Default,
 {
     current: Arc<RwLock<T>>,
-    #[allow(dead_code)]
-    endpoint: String,
-    #[allow(dead_code)]
-    timeout: Duration,
-    #[allow(dead_code)]
-    retry_interval: Duration,
     freshness_duration: Duration,
 }
 
@@ -45,26 +38,26 @@ impl<T> IpcStreamMonitor<T>
 where
     T: MonitorData + StreamingParser + Default,
 {
-    pub fn new(
-        endpoint: String,
-        timeout: Duration,
-        retry_interval: Duration,
-        freshness_duration: Duration,
-    ) -> Self {
+    pub fn new(channel: StreamChannel, freshness_duration: Duration) -> Self {
         let current = Arc::new(RwLock::new(T::default()));
         let monitor_current = Arc::clone(&current);
-        let endpoint_clone = endpoint.clone();
 
-        // Start the monitoring task
-        AsyncHandler::spawn(move || async move {
-            Self::streaming_task(monitor_current, endpoint_clone, timeout, retry_interval).await;
+        // 上游连接与重连由多路复用器统一管理
+        spawn_consumer(channel, move |line| {
+            if let Err(e) = T::parse_and_update(line, Arc::clone(&monitor_current)) {
+                logging!(
+                    debug,
+                    Type::Ipc,
+                    true,
+                    "Failed to parse {:?} data: {}",
+                    channel,
+                    e
+                );
+            }
         });
 
         Self {
             current,
-            endpoint,
-            timeout,
-            retry_interval,
             freshness_duration,
         }
     }
@@ -79,42 +72,4 @@ where
             .await
             .is_fresh_within(self.freshness_duration)
     }
-
-    /// The core streaming task that can be specialized per monitor type
-    async fn streaming_task(
-        current: Arc<RwLock<T>>,
-        endpoint: String,
-        timeout: Duration,
-        retry_interval: Duration,
-    ) {
-        loop {
-            let ipc_path_buf = match ipc_path() {
-                Ok(path) => path,
-                Err(e) => {
-                    logging!(error, Type::Ipc, true, "Failed to get IPC path: {}", e);
-                    tokio::time::sleep(retry_interval).await;
-                    continue;
-                }
-            };
-
-            let ipc_path = ipc_path_buf.to_str().unwrap_or_default();
-
-            let client = match IpcStreamClient::new(ipc_path) {
-                Ok(client) => client,
-                Err(e) => {
-                    logging!(error, Type::Ipc, true, "Failed to create IPC client: {}", e);
-                    tokio::time::sleep(retry_interval).await;
-                    continue;
-                }
-            };
-
-            let _ = client
-                .get(&endpoint)
-                .timeout(timeout)
-                .process_lines(|line| T::parse_and_update(line, Arc::clone(&current)))
-                .await;
-
-            tokio::time::sleep(retry_interval).await;
-        }
-    }
 }
@@ -0,0 +1,385 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
+use tauri::{Emitter, async_runtime::JoinHandle};
+use tokio::{sync::broadcast, time::Duration};
+
+use crate::{
+    core::handle,
+    ipc::logs::log_line_visible,
+    logging,
+    process::AsyncHandler,
+    singleton_with_logging,
+    utils::{
+        dirs::ipc_path,
+        logging::{Type, sampling_interval},
+    },
+};
+
+/// 单次连接的超时时间，超时后重新建立连接
+const STREAM_TIMEOUT: Duration = Duration::from_secs(30);
+/// 重连退避的初始与最大间隔
+const RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(30);
+/// 每次推送给前端的最大日志条数
+const MAX_PENDING_LOGS: usize = 200;
+/// 前端推送及空闲数据流检查间隔
+const EMIT_TICK: Duration = Duration::from_millis(250);
+/// 前端事件名称
+const STREAM_EVENT: &str = "verge://clash-stream";
+/// 上游日志流的订阅级别
+const UPSTREAM_LOG_LEVEL: &str = "debug";
+
+/// 内核数据流
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamChannel {
+    Traffic,
+    Memory,
+    Logs,
+    Connections,
+}
+
+impl StreamChannel {
+    pub const ALL: [Self; 4] = [Self::Traffic, Self::Memory, Self::Logs, Self::Connections];
+
+    /// 广播队列容量，消费者落后超过该数量时丢弃最旧的消息
+    const fn capacity(self) -> usize {
+        match self {
+            Self::Logs => 512,
+            Self::Connections => 32,
+            Self::Traffic | Self::Memory => 16,
+        }
+    }
+
+    /// 推送给前端的最小间隔，高频数据只推送最新值
+    const fn emit_interval(self) -> Duration {
+        match self {
+            Self::Traffic => Duration::from_secs(1),
+            Self::Memory => Duration::from_secs(2),
+            Self::Logs => Duration::from_millis(250),
+            Self::Connections => Duration::from_secs(1),
+        }
+    }
+}
+
+/// 单个数据流的连接状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChannelStatus {
+    pub running: bool,
+    pub connected: bool,
+    pub reconnects: u64,
+    pub messages: u64,
+    pub dropped: u64, // 前端推送时合并或丢弃的消息数
+    pub last_message_at: Option<i64>,
+}
+
+/// 等待推送给前端的数据
+#[derive(Debug, Default)]
+struct PendingEmit {
+    latest: Option<Arc<str>>,
+    logs: Vec<Arc<str>>,
+    last_emit: Option<Instant>,
+}
+
+/// 内核数据流多路复用器
+/// 每个接口只建立一个上游连接，通过各自的广播分发给内部消费者，并合并后推送给前端
+/// 数据流只在有内部消费者或前端订阅时连接，全部取消后自动断开
+pub struct StreamMultiplexer {
+    senders: HashMap<StreamChannel, broadcast::Sender<Arc<str>>>,
+    tasks: Mutex<HashMap<StreamChannel, JoinHandle<()>>>,
+    status: Mutex<HashMap<StreamChannel, ChannelStatus>>,
+    pending: Mutex<HashMap<StreamChannel, PendingEmit>>,
+    /// 界面选择的日志级别，只用于过滤推送给前端的日志
+    logs_level: Mutex<String>,
+    /// 前端已订阅的数据流
+    frontend: Mutex<HashSet<StreamChannel>>,
+    supervisor_started: AtomicBool,
+}
+
+singleton_with_logging!(StreamMultiplexer, INSTANCE, "StreamMultiplexer");
+
+impl StreamMultiplexer {
+    fn new() -> Self {
+        Self {
+            senders: StreamChannel::ALL
+                .into_iter()
+                .map(|channel| (channel, broadcast::channel(channel.capacity()).0))
+                .collect(),
+            tasks: Mutex::new(HashMap::new()),
+            status: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            logs_level: Mutex::new("info".to_string()),
+            frontend: Mutex::new(HashSet::new()),
+            supervisor_started: AtomicBool::new(false),
+        }
+    }
+
+    /// 订阅指定数据流，没有连接时随之启动
+    pub fn subscribe(&'static self, channel: StreamChannel) -> broadcast::Receiver<Arc<str>> {
+        let receiver = self.senders[&channel].subscribe();
+        self.start_channel(channel);
+        receiver
+    }
+
+    /// 前端订阅数据流，通过 verge://clash-stream 事件推送
+    pub fn subscribe_frontend(&'static self, channel: StreamChannel) {
+        self.frontend.lock().insert(channel);
+        self.start_channel(channel);
+    }
+
+    /// 前端取消订阅，没有内部消费者时断开数据流
+    pub fn unsubscribe_frontend(&self, channel: StreamChannel) {
+        self.frontend.lock().remove(&channel);
+        self.pending.lock().remove(&channel);
+        if self.is_idle(channel) {
+            self.stop_channel(channel);
+        }
+    }
+
+    /// 启动数据流，已在运行时不重复连接
+    fn start_channel(&'static self, channel: StreamChannel) {
+        let mut tasks = self.tasks.lock();
+        if tasks.contains_key(&channel) {
+            return;
+        }
+
+        let endpoint = match channel {
+            StreamChannel::Traffic => "/traffic".to_string(),
+            StreamChannel::Memory => "/memory".to_string(),
+            StreamChannel::Connections => "/connections".to_string(),
+            // 日志管道需要完整日志，上游始终按最详细级别订阅，界面级别在本地过滤
+            StreamChannel::Logs => format!("/logs?level={UPSTREAM_LOG_LEVEL}"),
+        };
+        self.status.lock().entry(channel).or_default().running = true;
+
+        let task = AsyncHandler::spawn(move || async move {
+            self.upstream_task(channel, endpoint).await;
+        });
+        tasks.insert(channel, task);
+        drop(tasks);
+        self.ensure_supervisor();
+    }
+
+    /// 停止数据流
+    fn stop_channel(&self, channel: StreamChannel) {
+        if let Some(task) = self.tasks.lock().remove(&channel) {
+            task.abort();
+        }
+        if let Some(status) = self.status.lock().get_mut(&channel) {
+            status.running = false;
+            status.connected = false;
+        }
+        logging!(info, Type::Ipc, true, "[数据流] 已停止 {:?}", channel);
+    }
+
+    /// 修改界面日志级别，只影响推送给前端的日志，不会重连上游或影响日志管道
+    pub fn set_logs_level(&self, level: Option<String>) {
+        *self.logs_level.lock() = level.unwrap_or_else(|| "info".to_string());
+        if let Some(pending) = self.pending.lock().get_mut(&StreamChannel::Logs) {
+            pending.logs.clear();
+        }
+    }
+
+    /// 既没有前端订阅也没有内部消费者
+    fn is_idle(&self, channel: StreamChannel) -> bool {
+        !self.frontend.lock().contains(&channel) && self.senders[&channel].receiver_count() == 0
+    }
+
+    /// 定期推送前端数据，并断开消费者已全部退出的数据流
+    fn ensure_supervisor(&'static self) {
+        if self.supervisor_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        AsyncHandler::spawn(move || async move {
+            let mut interval = tokio::time::interval(EMIT_TICK);
+            loop {
+                interval.tick().await;
+                if handle::Handle::global().is_exiting() {
+                    break;
+                }
+                self.flush_frontend();
+
+                let running: Vec<StreamChannel> = self.tasks.lock().keys().copied().collect();
+                for channel in running {
+                    if self.is_idle(channel) {
+                        self.stop_channel(channel);
+                    }
+                }
+            }
+        });
+    }
+
+    /// 获取各数据流状态
+    pub fn status(&self) -> HashMap<StreamChannel, ChannelStatus> {
+        self.status.lock().clone()
+    }
+
+    /// 连接上游接口，断开后按指数退避重连
+    async fn upstream_task(&'static self, channel: StreamChannel, endpoint: String) {
+        let sender = &self.senders[&channel];
+        let mut retry = RETRY_MIN;
+        loop {
+            let client = ipc_path().map_err(|e| e.to_string()).and_then(|path| {
+                let path = path.to_str().unwrap_or_default().to_string();
+                kode_bridge::IpcStreamClient::new(&path).map_err(|e| e.to_string())
+            });
+            let client = match client {
+                Ok(client) => client,
+                Err(e) => {
+                    logging!(error, Type::Ipc, true, "Failed to create IPC client: {}", e);
+                    tokio::time::sleep(sampling_interval(retry)).await;
+                    retry = (retry * 2).min(RETRY_MAX);
+                    continue;
+                }
+            };
+
+            let mut received = false;
+            let _ = client
+                .get(&endpoint)
+                .timeout(STREAM_TIMEOUT)
+                .process_lines(|line| {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        return Ok(());
+                    }
+                    received = true;
+                    let line: Arc<str> = Arc::from(line);
+
+                    {
+                        let mut status = self.status.lock();
+                        let status = status.entry(channel).or_default();
+                        status.connected = true;
+                        status.messages += 1;
+                        status.last_message_at = Some(chrono::Utc::now().timestamp());
+                    }
+                    if self.frontend.lock().contains(&channel) {
+                        self.queue_frontend(channel, Arc::clone(&line));
+                    }
+
+                    // 没有订阅者时发送会失败，可以忽略
+                    let _ = sender.send(line);
+                    Ok(())
+                })
+                .await;
+
+            {
+                let mut status = self.status.lock();
+                let status = status.entry(channel).or_default();
+                status.connected = false;
+                status.reconnects += 1;
+            }
+            // 收到过数据说明连接正常，只是被超时或内核重启中断
+            if received {
+                retry = RETRY_MIN;
+            } else {
+                retry = (retry * 2).min(RETRY_MAX);
+            }
+            tokio::time::sleep(sampling_interval(retry)).await;
+        }
+    }
+
+    /// 缓存待推送数据：日志累积，其余只保留最新值
+    fn queue_frontend(&self, channel: StreamChannel, line: Arc<str>) {
+        let dropped = {
+            let mut pending = self.pending.lock();
+            let entry = pending.entry(channel).or_default();
+            if channel == StreamChannel::Logs {
+                if !log_line_visible(&self.logs_level.lock(), &line) {
+                    return;
+                }
+                entry.logs.push(line);
+                let overflow = entry.logs.len().saturating_sub(MAX_PENDING_LOGS);
+                entry.logs.drain(..overflow);
+                overflow
+            } else {
+                usize::from(entry.latest.replace(line).is_some())
+            }
+        };
+        if dropped > 0
+            && let Some(status) = self.status.lock().get_mut(&channel)
+        {
+            status.dropped += dropped as u64;
+        }
+    }
+
+    fn flush_frontend(&self) {
+        let Some(app_handle) = handle::Handle::global().app_handle() else {
+            return;
+        };
+
+        let mut batches = Vec::new();
+        {
+            let mut pending = self.pending.lock();
+            for (channel, entry) in pending.iter_mut() {
+                if entry
+                    .last_emit
+                    .is_some_and(|at| at.elapsed() < sampling_interval(channel.emit_interval()))
+                {
+                    continue;
+                }
+                let lines = if *channel == StreamChannel::Logs {
+                    std::mem::take(&mut entry.logs)
+                } else {
+                    entry.latest.take().into_iter().collect()
+                };
+                if lines.is_empty() {
+                    continue;
+                }
+                entry.last_emit = Some(Instant::now());
+                batches.push((*channel, lines));
+            }
+        }
+
+        for (channel, lines) in batches {
+            let values: Vec<serde_json::Value> = lines
+                .iter()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect();
+            let data = if channel == StreamChannel::Logs {
+                serde_json::Value::Array(values)
+            } else {
+                values.into_iter().next().unwrap_or_default()
+            };
+            let _ = app_handle.emit(
+                STREAM_EVENT,
+                serde_json::json!({ "channel": channel, "data": data }),
+            );
+        }
+    }
+}
+
+/// 订阅指定数据流，按顺序处理每一行，消费者落后时跳过丢失的消息
+/// 中止返回的任务即取消订阅，没有其他订阅者时数据流随之断开
+pub fn spawn_consumer<F>(channel: StreamChannel, mut handler: F) -> JoinHandle<()>
+where
+    F: FnMut(&str) + Send + 'static,
+{
+    let mut receiver = StreamMultiplexer::global().subscribe(channel);
+
+    AsyncHandler::spawn(move || async move {
+        loop {
+            match receiver.recv().await {
+                Ok(line) => handler(&line),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    logging!(
+                        debug,
+                        Type::Ipc,
+                        true,
+                        "[数据流] {:?} 消费者落后，跳过 {} 条消息",
+                        channel,
+                        skipped
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
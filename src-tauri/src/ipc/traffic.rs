@@ -3,7 +3,10 @@ use std::{sync::Arc, time::Instant};
 use tokio::{sync::RwLock, time::Duration};
 
 use crate::{
-    ipc::monitor::{IpcStreamMonitor, MonitorData, StreamingParser},
+    ipc::{
+        monitor::{IpcStreamMonitor, MonitorData, StreamingParser},
+        multiplexer::StreamChannel,
+    },
     process::AsyncHandler,
     singleton_lazy_with_logging,
     utils::format::fmt_bytes,
@@ -106,12 +109,7 @@ pub struct TrafficMonitor {
 impl Default for TrafficMonitor {
     fn default() -> Self {
         TrafficMonitor {
-            monitor: IpcStreamMonitor::new(
-                "/traffic".to_string(),
-                Duration::from_secs(10),
-                Duration::from_secs(1),
-                Duration::from_secs(5),
-            ),
+            monitor: IpcStreamMonitor::new(StreamChannel::Traffic, Duration::from_secs(5)),
         }
     }
 }
@@ -17,6 +17,7 @@
 #![allow(non_snake_case)]
 #![recursion_limit = "512"]
 
+pub mod cli;
 mod cmd;
 pub mod config;
 mod core;
@@ -230,17 +231,21 @@ mod app_init {
     }
 
     /// Generate all command handlers for the application
+    /// 应用锁定时拒绝敏感命令
     pub fn generate_handlers()
     -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static {
-        tauri::generate_handler![
+        let handler: fn(tauri::ipc::Invoke<tauri::Wry>) -> bool = tauri::generate_handler![
             // Common commands
             cmd::get_sys_proxy,
+            cmd::get_sysproxy_backend_status,
             cmd::get_auto_proxy,
             cmd::open_app_dir,
             cmd::open_logs_dir,
+            cmd::set_logging_profile,
             cmd::open_web_url,
             cmd::open_core_dir,
             cmd::get_portable_flag,
+            cmd::create_portable_bundle,
             cmd::get_network_interfaces,
             cmd::get_system_hostname,
             cmd::restart_app,
@@ -253,46 +258,116 @@ mod app_init {
             cmd::update_ui_stage,
             cmd::reset_ui_ready_state,
             cmd::get_running_mode,
+            cmd::get_core_startup_report,
+            cmd::get_port_conflicts,
             cmd::get_app_uptime,
             cmd::get_auto_launch_status,
             cmd::is_admin,
             // Lightweight mode
             cmd::entry_lightweight_mode,
             cmd::exit_lightweight_mode,
+            cmd::get_lightweight_status,
+            cmd::set_lightweight_policy,
+            cmd::get_offline_status,
+            cmd::exit_offline_mode,
             // Service management
             cmd::install_service,
             cmd::uninstall_service,
             cmd::reinstall_service,
             cmd::repair_service,
             cmd::is_service_available,
+            cmd::diagnose_service,
+            cmd::setup_linux_capabilities,
+            cmd::has_tun_capabilities,
             // Clash core commands
             cmd::get_clash_info,
             cmd::patch_clash_config,
+            cmd::rotate_external_controller_secret,
+            cmd::get_external_controller_origins,
+            cmd::set_external_controller_origins,
             cmd::patch_clash_mode,
             cmd::change_clash_core,
             cmd::get_runtime_config,
             cmd::get_runtime_yaml,
+            cmd::get_runtime_section,
+            cmd::patch_runtime_section,
+            cmd::get_config_generation_metrics,
             cmd::get_runtime_exists,
             cmd::get_runtime_logs,
             cmd::get_runtime_proxy_chain_config,
             cmd::update_proxy_chain_config_in_runtime,
-            cmd::invoke_uwp_tool,
+            cmd::create_proxy_chain,
+            cmd::list_proxy_chains,
+            cmd::delete_proxy_chain,
+            cmd::get_custom_rules,
+            cmd::add_custom_rule,
+            cmd::remove_custom_rule,
+            cmd::reorder_custom_rules,
+            cmd::add_ruleset_source,
+            cmd::refresh_ruleset,
+            cmd::get_ruleset_status,
+            cmd::remove_ruleset_source,
+            cmd::add_app_route,
+            cmd::remove_app_route,
+            cmd::list_app_routes,
+            cmd::list_running_applications,
+            cmd::set_network_rule,
+            cmd::remove_network_rule,
+            cmd::list_network_rules,
+            cmd::get_network_context,
+            cmd::get_pac_script,
+            cmd::set_pac_script,
+            cmd::validate_pac_script,
+            cmd::get_proxy_bypass_rules,
+            cmd::set_proxy_bypass_rules,
+            cmd::list_uwp_apps,
+            cmd::set_uwp_loopback,
             cmd::copy_clash_env,
             cmd::get_proxies,
             cmd::force_refresh_proxies,
+            cmd::get_ipc_cache_stats,
             cmd::get_providers_proxies,
             cmd::sync_tray_proxy_selection,
             cmd::update_proxy_and_sync,
+            cmd::bulk_update_proxy_choices,
+            cmd::select_region_in_all_groups,
+            cmd::set_group_preferences,
+            cmd::set_group_bandwidth_limit,
+            cmd::set_group_test_policy,
+            cmd::list_group_test_policies,
+            cmd::add_favorite_node,
+            cmd::remove_favorite_node,
+            cmd::list_favorite_nodes,
+            cmd::switch_to_favorite,
+            cmd::set_selection_memory_policy,
+            cmd::get_selection_memory_policy,
+            cmd::clear_selection_memory,
+            cmd::restore_selection_memory,
             cmd::save_dns_config,
             cmd::apply_dns_config,
             cmd::check_dns_config_exists,
             cmd::get_dns_config_content,
             cmd::validate_dns_config,
+            cmd::run_dns_benchmark,
+            cmd::get_dns_benchmark_report,
+            cmd::generate_dns_config_from_benchmark,
+            cmd::add_dns_host_override,
+            cmd::remove_dns_host_override,
+            cmd::list_dns_host_overrides,
+            cmd::list_fakeip_exclusions,
+            cmd::add_fakeip_exclusion,
+            cmd::remove_fakeip_exclusion,
+            cmd::import_fakeip_exclusions,
+            cmd::get_fakeip_exclusion_presets,
+            cmd::suggest_fakeip_exclusions,
             cmd::get_clash_version,
             cmd::get_clash_config,
             cmd::force_refresh_clash_config,
             cmd::get_ip_info,
             cmd::update_geo_data,
+            cmd::get_geo_data_status,
+            cmd::set_geo_data_settings,
+            cmd::update_geo_datasets,
             cmd::upgrade_clash_core,
             cmd::get_clash_rules,
             cmd::update_proxy_choice,
@@ -305,12 +380,22 @@ mod app_init {
             cmd::check_subscription_health,
             cmd::check_all_subscriptions_health,
             cmd::get_subscription_details,
+            cmd::get_subscription_health_history,
             cmd::cleanup_health_check_cache,
+            cmd::get_provider_outages,
+            cmd::acknowledge_outage,
             // Batch import commands
             cmd::batch_import_from_text,
             cmd::batch_import_from_file,
             cmd::batch_import_from_clipboard,
             cmd::preview_batch_import,
+            cmd::import_subscription_index,
+            cmd::sync_subscription_index,
+            cmd::list_subscription_indexes,
+            cmd::remove_subscription_index,
+            cmd::replace_subscription_url,
+            cmd::bulk_replace_subscription_urls,
+            cmd::get_subscription_url_history,
             // Batch export commands
             cmd::batch_export_subscriptions,
             cmd::export_subscriptions_to_file,
@@ -322,6 +407,9 @@ mod app_init {
             cmd::update_task,
             cmd::delete_task,
             cmd::toggle_task,
+            cmd::set_task_dependencies,
+            cmd::set_task_conditions,
+            cmd::set_task_trigger_events,
             cmd::execute_task_immediately,
             cmd::get_task_execution_history,
             cmd::get_task_statistics,
@@ -339,11 +427,19 @@ mod app_init {
             cmd::get_remote_subscription_config,
             cmd::save_remote_subscription_config,
             cmd::fetch_subscription_preview,
+            cmd::get_fetch_statistics,
             // Global speed test commands
             cmd::start_global_speed_test,
             cmd::cancel_global_speed_test,
             cmd::switch_to_node,
             cmd::apply_best_node,
+            // Operation cancellation commands
+            cmd::cancel_operation,
+            cmd::get_running_operations,
+            // Background job queue commands
+            cmd::list_jobs,
+            cmd::cancel_job,
+            cmd::get_job_log,
             // Traffic stats commands
             cmd::record_traffic_usage,
             cmd::get_subscription_traffic_stats,
@@ -354,7 +450,15 @@ mod app_init {
             cmd::cleanup_traffic_history,
             cmd::export_traffic_data,
             cmd::set_subscription_quota,
+            cmd::get_quota_alert_thresholds,
+            cmd::set_quota_alert_thresholds,
             cmd::get_traffic_prediction,
+            cmd::get_traffic_anomalies,
+            // Traffic report commands
+            cmd::generate_traffic_report,
+            cmd::list_traffic_reports,
+            cmd::get_traffic_report,
+            cmd::export_traffic_report_csv,
             // Subscription groups commands
             cmd::create_subscription_group,
             cmd::update_subscription_group,
@@ -367,12 +471,94 @@ mod app_init {
             cmd::batch_add_subscriptions_to_group,
             cmd::batch_remove_subscriptions_from_group,
             cmd::apply_auto_grouping_rules,
+            cmd::evaluate_grouping_rule,
+            cmd::test_subscription_group,
             cmd::get_group_statistics,
             cmd::get_all_group_statistics,
             cmd::export_subscription_groups,
             cmd::import_subscription_groups,
             cmd::get_smart_grouping_suggestions,
             cmd::create_default_groups,
+            // Load balance group commands
+            cmd::save_load_balance_group,
+            cmd::get_load_balance_groups,
+            cmd::delete_load_balance_group,
+            cmd::get_load_balance_candidates,
+            cmd::rebuild_load_balance_groups,
+            // Composite profile commands
+            cmd::create_composite_profile,
+            cmd::get_composite_profiles,
+            cmd::update_composite_profile,
+            cmd::regenerate_composite_profile,
+            // Profile template commands
+            cmd::get_profile_templates,
+            cmd::create_profile_from_template,
+            // Profile sharing commands
+            cmd::share_profile,
+            cmd::import_shared_profile,
+            // Node deduplication commands
+            cmd::deduplicate_nodes,
+            // Node rename commands
+            cmd::get_node_rename_rules,
+            cmd::set_node_rename_rules,
+            cmd::preview_node_rename,
+            // Node filter commands
+            cmd::get_profile_node_filters,
+            cmd::set_profile_node_filters,
+            // Port override commands
+            cmd::get_port_overrides,
+            cmd::set_port_overrides,
+            // Core registry commands
+            cmd::list_available_cores,
+            cmd::install_core,
+            cmd::remove_core,
+            cmd::set_profile_core,
+            // Core upgrade commands
+            cmd::get_core_release_notes,
+            cmd::get_core_upgrade_status,
+            // App update commands
+            cmd::check_app_update,
+            cmd::download_app_update,
+            cmd::install_app_update,
+            // IPv6 commands
+            cmd::get_ipv6_status,
+            // Subscription usage commands
+            cmd::get_subscription_usage,
+            cmd::get_all_subscription_usage,
+            // Expiry reminder commands
+            cmd::get_expiring_subscriptions,
+            cmd::get_expiry_reminder_config,
+            cmd::set_expiry_reminder,
+            // Delay test target commands
+            cmd::get_delay_test_presets,
+            cmd::get_delay_test_targets,
+            cmd::set_delay_test_targets,
+            cmd::test_delay_targets,
+            // Speed test report commands
+            cmd::list_speed_test_runs,
+            cmd::export_speed_test_report,
+            // Event publisher commands
+            cmd::get_event_webhooks,
+            cmd::set_event_webhooks,
+            cmd::test_event_webhooks,
+            // Route explain commands
+            cmd::explain_route,
+            // Config sandbox commands
+            cmd::dry_run_patch_clash_config,
+            cmd::apply_staged_patch,
+            cmd::discard_staged_patch,
+            // Profile sandbox commands
+            cmd::test_profile_in_sandbox,
+            // Hotkey commands
+            cmd::get_hotkey_actions,
+            cmd::set_hotkey,
+            // Resource monitor commands
+            cmd::get_resource_usage_history,
+            cmd::get_resource_monitor_config,
+            cmd::set_resource_monitor_config,
+            cmd::run_resource_mitigation,
+            // Client migration commands
+            cmd::migrate_from_client,
             // Backup and restore commands
             cmd::create_backup,
             cmd::get_all_backups,
@@ -380,6 +566,8 @@ mod app_init {
             cmd::restore_backup,
             cmd::delete_backup,
             cmd::validate_backup,
+            cmd::rotate_backup_password,
+            cmd::preview_restore,
             cmd::export_backup,
             cmd::import_backup,
             cmd::set_webdav_config,
@@ -387,7 +575,13 @@ mod app_init {
             cmd::sync_to_webdav,
             cmd::sync_from_webdav,
             cmd::get_sync_status,
+            cmd::sync_now,
+            cmd::get_sync_conflicts,
+            cmd::resolve_sync_conflict,
             cmd::cleanup_old_backups,
+            cmd::set_backup_schedule,
+            cmd::get_backup_schedule,
+            cmd::run_scheduled_backup_now,
             // Advanced search commands
             cmd::advanced_search,
             cmd::quick_search,
@@ -416,10 +610,14 @@ mod app_init {
             cmd::delete_clash_connection,
             cmd::close_all_clash_connections,
             cmd::get_group_proxy_delays,
+            cmd::get_core_capabilities,
+            cmd::query_clash_dns,
             cmd::is_clash_debug_enabled,
             cmd::clash_gc,
             // Logging and monitoring
             cmd::get_clash_logs,
+            cmd::query_logs,
+            cmd::export_logs,
             cmd::start_logs_monitoring,
             cmd::stop_logs_monitoring,
             cmd::clear_logs,
@@ -428,8 +626,9 @@ mod app_init {
             cmd::get_formatted_traffic_data,
             cmd::get_formatted_memory_data,
             cmd::get_system_monitor_overview,
-            cmd::start_traffic_service,
-            cmd::stop_traffic_service,
+            cmd::subscribe_clash_stream,
+            cmd::get_stream_status,
+            cmd::unsubscribe_clash_stream,
             // Verge configuration
             cmd::get_verge_config,
             cmd::patch_verge_config,
@@ -440,6 +639,10 @@ mod app_init {
             cmd::open_devtools,
             cmd::exit_app,
             cmd::get_network_interfaces_info,
+            cmd::enable_lan_sharing,
+            cmd::disable_lan_sharing,
+            cmd::ensure_firewall_rules,
+            cmd::remove_firewall_rules,
             // Profile management
             cmd::get_profiles,
             cmd::enhance_profiles,
@@ -454,6 +657,11 @@ mod app_init {
             cmd::read_profile_file,
             cmd::save_profile_file,
             cmd::get_next_update_time,
+            cmd::set_quick_patch,
+            cmd::get_quick_patch,
+            // Startup recovery
+            cmd::get_startup_recovery_options,
+            cmd::resolve_startup_recovery,
             // Script validation
             cmd::script_validate_notice,
             cmd::validate_script_file,
@@ -465,16 +673,62 @@ mod app_init {
             cmd::list_webdav_backup,
             cmd::delete_webdav_backup,
             cmd::restore_webdav_backup,
+            // Remote backup storage commands
+            cmd::save_s3_backup_config,
+            cmd::set_remote_backup_provider,
+            cmd::sync_to_remote,
+            cmd::sync_from_remote,
+            cmd::list_remote_backups,
+            cmd::delete_remote_backup,
+            cmd::migrate_secrets_to_keychain,
             // Diagnostics and system info
             cmd::export_diagnostic_info,
+            cmd::generate_diagnostics_bundle,
+            cmd::get_redaction_policy,
+            cmd::set_redaction_policy,
             cmd::get_system_info,
+            cmd::get_recent_system_events,
+            cmd::set_captive_portal_policy,
+            // Development fixtures
+            #[cfg(feature = "dev-fixtures")]
+            cmd::generate_dev_fixtures,
+            #[cfg(feature = "dev-fixtures")]
+            cmd::clear_dev_fixtures,
             // Media unlock checker
             cmd::get_unlock_items,
             cmd::check_media_unlock,
-        ]
+            cmd::get_unlock_check_definitions,
+            cmd::add_unlock_check_definition,
+            cmd::remove_unlock_check_definition,
+            cmd::check_unlock_for_node,
+            cmd::get_unlock_matrix,
+            // App lock
+            cmd::get_app_lock_status,
+            cmd::set_app_passcode,
+            cmd::set_app_lock_options,
+            cmd::unlock_app,
+            cmd::lock_app,
+            cmd::get_api_server_config,
+            cmd::set_api_server_config,
+            cmd::regenerate_api_server_token,
+        ];
+
+        move |invoke| {
+            if let Err(e) = module::app_lock::AppLock::global().check_command(invoke.message.command()) {
+                invoke
+                    .resolver
+                    .reject(cmd::CmdError::new(cmd::ErrorCode::AppLocked, e));
+                return true;
+            }
+            handler(invoke)
+        }
     }
 }
 
+fn app_context() -> tauri::Context {
+    tauri::generate_context!()
+}
+
 pub fn run() {
     // 🔧 修复：初始化日志系统
     init_logger();
@@ -652,6 +906,17 @@ pub fn run() {
 
                 logging!(info, Type::System, true, "没有可见窗口，尝试显示主窗口");
 
+                // 点击 Dock 图标与托盘一样遵循轻量模式的退出策略
+                if crate::module::lightweight::should_stay_lightweight(
+                    crate::module::lightweight::ExitTrigger::Tray,
+                )
+                .await
+                {
+                    return;
+                }
+                if crate::module::lightweight::exit_lightweight_mode().await {
+                    return;
+                }
                 let result = WindowManager::show_main_window().await;
                 logging!(
                     info,
@@ -788,7 +1053,7 @@ pub fn run() {
     println!("构建 Tauri 应用程序...");
     // Build the application
     let app = builder
-        .build(tauri::generate_context!())
+        .build(app_context())
         .unwrap_or_else(|e| {
             println!("❌ 构建 Tauri 应用程序失败: {}", e);
             eprintln!("❌ 构建 Tauri 应用程序失败: {}", e);
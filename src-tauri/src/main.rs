@@ -1,6 +1,16 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // 无界面命令行模式
+    if std::env::args().any(|arg| arg == "--cli") {
+        #[cfg(all(windows, not(debug_assertions)))]
+        unsafe {
+            use winapi::um::wincon::{ATTACH_PARENT_PROCESS, AttachConsole};
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+        std::process::exit(app_lib::cli::run());
+    }
+
     // 在 Windows 上分配控制台用于诊断启动问题
     #[cfg(all(windows, not(debug_assertions)))]
     {
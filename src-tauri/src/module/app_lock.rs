@@ -0,0 +1,324 @@
+use crate::{
+    core::handle,
+    logging,
+    module::lightweight::is_in_lightweight_mode,
+    process::AsyncHandler,
+    singleton_with_logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Result, bail};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tauri::Emitter;
+
+const APP_LOCK_FILE: &str = "app_lock.json";
+const APP_LOCK_EVENT: &str = "verge://app-lock";
+const SALT_LENGTH: usize = 16;
+const MIN_PASSCODE_LENGTH: usize = 4;
+/// 连续失败超过该次数后开始延迟
+const FREE_ATTEMPTS: u32 = 3;
+const MAX_RETRY_DELAY_SECS: u64 = 300;
+
+/// 锁定时仍允许执行的命令，其余命令一律拒绝，新增的命令默认受保护
+/// 仅包含解锁、界面启动和不涉及订阅与凭据的状态查询
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "get_app_lock_status",
+    "unlock_app",
+    "lock_app",
+    "notify_ui_ready",
+    "update_ui_stage",
+    "reset_ui_ready_state",
+    "get_running_mode",
+    "get_core_startup_report",
+    "get_app_uptime",
+    "get_portable_flag",
+    "is_admin",
+    "get_lightweight_status",
+    "entry_lightweight_mode",
+    "exit_lightweight_mode",
+    "get_offline_status",
+    "get_clash_version",
+    "get_traffic_data",
+    "get_memory_data",
+    "get_formatted_traffic_data",
+    "get_formatted_memory_data",
+    "exit_app",
+    "restart_app",
+];
+
+/// 密码错误或尝试过于频繁，retry_after_secs 为需要等待的秒数
+#[derive(Debug)]
+pub struct InvalidPasscode {
+    pub message: String,
+    pub retry_after_secs: Option<u64>,
+}
+
+impl std::fmt::Display for InvalidPasscode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for InvalidPasscode {}
+
+/// 应用锁配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AppLockConfig {
+    passcode_hash: Option<String>,
+    salt: Option<String>,
+    /// 进入轻量模式后自动锁定的延迟分钟数，None 表示不自动锁定
+    auto_lock_minutes: Option<u64>,
+    /// 连续解锁失败次数与下次允许尝试的时间戳，重启后仍然有效
+    #[serde(default)]
+    failed_attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<i64>,
+}
+
+/// 应用锁状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub auto_lock_minutes: Option<u64>,
+    pub failed_attempts: u32,
+    pub retry_after_secs: Option<u64>,
+}
+
+pub struct AppLock {
+    config: Mutex<AppLockConfig>,
+    locked: AtomicBool,
+}
+
+singleton_with_logging!(AppLock, INSTANCE, "AppLock");
+
+impl AppLock {
+    fn new() -> Self {
+        Self {
+            config: Mutex::new(AppLockConfig::default()),
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    /// 加载配置，已设置密码时以锁定状态启动
+    pub fn init(&self) -> Result<()> {
+        let config = load_config()?;
+        let enabled = config.passcode_hash.is_some();
+        *self.config.lock() = config;
+        self.locked.store(enabled, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn status(&self) -> AppLockStatus {
+        let config = self.config.lock();
+        AppLockStatus {
+            enabled: config.passcode_hash.is_some(),
+            locked: self.is_locked(),
+            auto_lock_minutes: config.auto_lock_minutes,
+            failed_attempts: config.failed_attempts,
+            retry_after_secs: retry_remaining(&config).map(|d| d.as_secs().max(1)),
+        }
+    }
+
+    /// 设置、修改或清除密码，已设置密码时需要提供当前密码
+    pub async fn set_passcode(
+        &self,
+        current: Option<String>,
+        passcode: Option<String>,
+    ) -> Result<()> {
+        if self.is_locked() {
+            bail!("应用已锁定");
+        }
+        let existing = self.config.lock().clone();
+        if existing.passcode_hash.is_some() {
+            let current = current.unwrap_or_default();
+            if !verify(&existing, current).await? {
+                bail!("当前密码错误");
+            }
+        }
+
+        let mut config = existing;
+        match passcode {
+            Some(passcode) => {
+                if passcode.chars().count() < MIN_PASSCODE_LENGTH {
+                    bail!("密码至少需要 {} 位", MIN_PASSCODE_LENGTH);
+                }
+                let mut salt = [0u8; SALT_LENGTH];
+                getrandom::fill(&mut salt).map_err(|e| anyhow::anyhow!("生成随机数失败: {}", e))?;
+                let hash = tokio::task::spawn_blocking(move || derive(&passcode, &salt)).await??;
+                config.passcode_hash = Some(hex::encode(hash));
+                config.salt = Some(hex::encode(salt));
+            }
+            None => {
+                config.passcode_hash = None;
+                config.salt = None;
+            }
+        }
+        save_config(&config)?;
+        *self.config.lock() = config;
+        logging!(info, Type::System, true, "[应用锁] 已更新应用密码");
+        self.emit_status();
+        Ok(())
+    }
+
+    /// 设置进入轻量模式后自动锁定的延迟
+    pub fn set_auto_lock(&self, minutes: Option<u64>) -> Result<()> {
+        let mut config = self.config.lock().clone();
+        config.auto_lock_minutes = minutes;
+        save_config(&config)?;
+        *self.config.lock() = config;
+        Ok(())
+    }
+
+    /// 使用密码解锁，连续失败后按指数延迟
+    pub async fn unlock(&self, passcode: String) -> Result<()> {
+        if !self.is_locked() {
+            return Ok(());
+        }
+        let config = self.config.lock().clone();
+        if let Some(remaining) = retry_remaining(&config) {
+            let secs = remaining.as_secs().max(1);
+            return Err(InvalidPasscode {
+                message: format!("尝试次数过多，请在 {} 秒后重试", secs),
+                retry_after_secs: Some(secs),
+            }
+            .into());
+        }
+
+        if !verify(&config, passcode).await? {
+            // 失败次数与等待时间写入文件，重启应用不会重置
+            let mut config = self.config.lock().clone();
+            config.failed_attempts = config.failed_attempts.saturating_add(1);
+            let attempts = config.failed_attempts;
+            if attempts >= FREE_ATTEMPTS {
+                let delay = 2u64
+                    .saturating_pow(attempts - FREE_ATTEMPTS)
+                    .min(MAX_RETRY_DELAY_SECS);
+                config.retry_after = Some(chrono::Utc::now().timestamp() + delay as i64);
+            }
+            save_config(&config)?;
+            *self.config.lock() = config;
+            logging!(
+                warn,
+                Type::System,
+                true,
+                "[应用锁] 解锁失败，已连续失败 {} 次",
+                attempts
+            );
+            return Err(InvalidPasscode {
+                message: "密码错误".to_string(),
+                retry_after_secs: retry_remaining(&self.config.lock()).map(|d| d.as_secs().max(1)),
+            }
+            .into());
+        }
+
+        if config.failed_attempts > 0 || config.retry_after.is_some() {
+            let mut config = self.config.lock().clone();
+            config.failed_attempts = 0;
+            config.retry_after = None;
+            save_config(&config)?;
+            *self.config.lock() = config;
+        }
+        self.locked.store(false, Ordering::SeqCst);
+        logging!(info, Type::System, true, "[应用锁] 已解锁");
+        self.emit_status();
+        Ok(())
+    }
+
+    /// 立即锁定，未设置密码时不生效
+    pub fn lock(&self) -> Result<()> {
+        if self.config.lock().passcode_hash.is_none() {
+            bail!("未设置应用密码");
+        }
+        if !self.locked.swap(true, Ordering::SeqCst) {
+            logging!(info, Type::System, true, "[应用锁] 已锁定");
+            self.emit_status();
+        }
+        Ok(())
+    }
+
+    /// 进入轻量模式后开始自动锁定计时，期间退出轻量模式则取消
+    pub fn on_enter_lightweight(&'static self) {
+        let Some(minutes) = self.config.lock().auto_lock_minutes else {
+            return;
+        };
+        AsyncHandler::spawn(move || async move {
+            tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+            if is_in_lightweight_mode() && self.config.lock().passcode_hash.is_some() {
+                let _ = self.lock();
+            }
+        });
+    }
+
+    /// IPC 命令检查，锁定时只允许白名单中的命令
+    pub fn check_command(&self, command: &str) -> Result<(), String> {
+        if self.is_locked() && !ALLOWED_WHILE_LOCKED.contains(&command) {
+            return Err(format!("应用已锁定，请先解锁后再执行 {}", command));
+        }
+        Ok(())
+    }
+
+    fn emit_status(&self) {
+        if let Some(app_handle) = handle::Handle::global().app_handle() {
+            let _ = app_handle.emit(APP_LOCK_EVENT, self.status());
+        }
+    }
+}
+
+fn derive(passcode: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut hash = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passcode.as_bytes(), salt, &mut hash)
+        .map_err(|e| anyhow::anyhow!("密码哈希失败: {}", e))?;
+    Ok(hash)
+}
+
+/// 距离下次允许尝试解锁的剩余时间
+fn retry_remaining(config: &AppLockConfig) -> Option<Duration> {
+    let remaining = config.retry_after? - chrono::Utc::now().timestamp();
+    (remaining > 0).then(|| Duration::from_secs(remaining as u64))
+}
+
+async fn verify(config: &AppLockConfig, passcode: String) -> Result<bool> {
+    let (Some(expected), Some(salt)) = (&config.passcode_hash, &config.salt) else {
+        return Ok(true);
+    };
+    let expected = hex::decode(expected)?;
+    let salt = hex::decode(salt)?;
+    let hash = tokio::task::spawn_blocking(move || derive(&passcode, &salt)).await??;
+
+    // 固定时间比较
+    Ok(expected.len() == hash.len()
+        && expected
+            .iter()
+            .zip(hash.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0)
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(APP_LOCK_FILE))
+}
+
+fn load_config() -> Result<AppLockConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(AppLockConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_config(config: &AppLockConfig) -> Result<()> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
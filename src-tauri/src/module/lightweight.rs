@@ -2,9 +2,10 @@ use crate::{
     config::Config,
     core::{handle, timer::Timer, tray::Tray},
     log_err, logging,
+    module::app_lock::AppLock,
     process::AsyncHandler,
     state::proxy::ProxyRequestCache,
-    utils::logging::Type,
+    utils::{init, logging::Type},
 };
 
 #[cfg(target_os = "macos")]
@@ -13,7 +14,8 @@ use crate::logging_error;
 use crate::utils::window_manager::WindowManager;
 use anyhow::{Context, Result};
 use delay_timer::prelude::TaskBuilder;
-use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicI64, AtomicU8, AtomicU32, Ordering};
 use tauri::Listener;
 
 const LIGHT_WEIGHT_TASK_UID: &str = "light_weight_task";
@@ -44,6 +46,9 @@ impl LightweightState {
 
 static LIGHTWEIGHT_STATE: AtomicU8 = AtomicU8::new(LightweightState::Normal as u8);
 
+// 进入轻量模式的时间戳，0 表示未进入
+static ENTERED_AT: AtomicI64 = AtomicI64::new(0);
+
 static WINDOW_CLOSE_HANDLER: AtomicU32 = AtomicU32::new(0);
 static WEBVIEW_FOCUS_HANDLER: AtomicU32 = AtomicU32::new(0);
 
@@ -71,6 +76,95 @@ pub fn is_in_lightweight_mode() -> bool {
     get_state() == LightweightState::In
 }
 
+/// 轻量模式策略，进入条件沿用自动轻量模式的设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightweightPolicy {
+    /// 窗口关闭后空闲一段时间自动进入
+    pub enter_on_idle: bool,
+    pub idle_minutes: u64,
+    /// 点击托盘图标时退出
+    pub exit_on_tray: bool,
+    /// 按显示窗口类快捷键时退出
+    pub exit_on_hotkey: bool,
+    /// 进入时销毁窗口释放 WebView，否则仅隐藏
+    pub unload_webview: bool,
+}
+
+/// 轻量模式运行状态
+#[derive(Debug, Clone, Serialize)]
+pub struct LightweightStatus {
+    pub state: &'static str,
+    pub entered_at: Option<i64>,
+    /// 自动进入的窗口监听是否已挂载
+    pub idle_watch_active: bool,
+    /// 计时中时预计自动进入的时间戳
+    pub idle_deadline: Option<i64>,
+    pub webview_loaded: bool,
+    pub policy: LightweightPolicy,
+}
+
+/// 退出轻量模式的触发来源
+#[derive(Debug, Clone, Copy)]
+pub enum ExitTrigger {
+    Tray,
+    Hotkey,
+}
+
+pub async fn current_policy() -> LightweightPolicy {
+    let verge = Config::verge().await;
+    let verge = verge.latest_ref();
+    LightweightPolicy {
+        enter_on_idle: verge.enable_auto_light_weight_mode.unwrap_or(false),
+        idle_minutes: verge.auto_light_weight_minutes.unwrap_or(10),
+        exit_on_tray: verge.light_weight_exit_on_tray.unwrap_or(true),
+        exit_on_hotkey: verge.light_weight_exit_on_hotkey.unwrap_or(true),
+        unload_webview: verge.light_weight_unload_webview.unwrap_or(true),
+    }
+}
+
+pub async fn lightweight_status() -> LightweightStatus {
+    let idle_deadline = Timer::global()
+        .timer_map
+        .read()
+        .get(LIGHT_WEIGHT_TASK_UID)
+        .map(|task| task.last_run + task.interval_minutes as i64 * 60);
+    let entered_at = ENTERED_AT.load(Ordering::Acquire);
+    LightweightStatus {
+        state: match get_state() {
+            LightweightState::Normal => "normal",
+            LightweightState::In => "lightweight",
+            LightweightState::Exiting => "exiting",
+        },
+        entered_at: (entered_at > 0).then_some(entered_at),
+        idle_watch_active: WINDOW_CLOSE_HANDLER.load(Ordering::Acquire) != 0,
+        idle_deadline,
+        webview_loaded: WindowManager::get_main_window().is_some(),
+        policy: current_policy().await,
+    }
+}
+
+/// 处于轻量模式且策略不允许该来源退出时返回 true
+pub async fn should_stay_lightweight(trigger: ExitTrigger) -> bool {
+    if !is_in_lightweight_mode() {
+        return false;
+    }
+    let policy = current_policy().await;
+    let allowed = match trigger {
+        ExitTrigger::Tray => policy.exit_on_tray,
+        ExitTrigger::Hotkey => policy.exit_on_hotkey,
+    };
+    if !allowed {
+        logging!(
+            info,
+            Type::Lightweight,
+            true,
+            "轻量模式策略不允许通过{:?}退出，保持轻量模式",
+            trigger
+        );
+    }
+    !allowed
+}
+
 // 设置轻量模式状态（仅 Normal <-> In）
 async fn set_lightweight_mode(value: bool) {
     let current = get_state();
@@ -175,13 +269,20 @@ pub async fn entry_lightweight_mode() -> bool {
         return false;
     }
 
-    WindowManager::destroy_main_window();
+    if current_policy().await.unload_webview {
+        WindowManager::destroy_main_window();
+    } else if let Some(window) = WindowManager::get_main_window() {
+        let _ = window.hide();
+    }
+    ENTERED_AT.store(chrono::Local::now().timestamp(), Ordering::Release);
 
     set_lightweight_mode(true).await;
     let _ = cancel_light_weight_timer();
 
     // 回到 In
     set_state(LightweightState::In);
+    init::apply_logging_profile().await;
+    AppLock::global().on_enter_lightweight();
 
     ProxyRequestCache::global().clean_default_keys();
     true
@@ -215,6 +316,8 @@ pub async fn exit_lightweight_mode() -> bool {
 
     // 回到 Normal
     set_state(LightweightState::Normal);
+    ENTERED_AT.store(0, Ordering::Release);
+    init::apply_logging_profile().await;
 
     logging!(info, Type::Lightweight, true, "轻量模式退出完成");
     true
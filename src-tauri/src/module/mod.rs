@@ -1,2 +1,4 @@
+pub mod app_lock;
 pub mod lightweight;
+pub mod offline;
 pub mod sysinfo;
@@ -0,0 +1,300 @@
+use crate::{
+    config::Config,
+    core::{CoreManager, captive_portal, handle},
+    logging,
+    process::AsyncHandler,
+    singleton_with_logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tauri::Emitter;
+
+const KNOWN_GOOD_FILE: &str = "last-known-good.yaml";
+const OFFLINE_EVENT: &str = "verge://offline-status";
+/// 订阅连续更新失败达到该次数后进入离线模式
+const FAILURE_THRESHOLD: u32 = 3;
+const MONITOR_TICK: Duration = Duration::from_secs(60);
+/// 因更新失败离线时，每隔该数量的检测周期重试一次当前订阅
+const RETRY_EVERY_TICKS: u32 = 10;
+
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 进入离线模式的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineReason {
+    UpdateFailures,
+    NoNetwork,
+}
+
+/// 离线模式状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OfflineStatus {
+    pub active: bool,
+    pub reason: Option<OfflineReason>,
+    pub since: Option<i64>,
+    /// 各订阅中最多的连续更新失败次数
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// 最近一次成功应用的运行时配置的保存时间
+    pub known_good_at: Option<i64>,
+    /// 内核当前是否运行在固定的旧配置上
+    pub pinned: bool,
+}
+
+pub struct OfflineMode {
+    status: Mutex<OfflineStatus>,
+    /// 按订阅 uid 统计的连续更新失败次数
+    failures: Mutex<HashMap<String, u32>>,
+}
+
+singleton_with_logging!(OfflineMode, INSTANCE, "OfflineMode");
+
+impl OfflineMode {
+    fn new() -> Self {
+        Self {
+            status: Mutex::new(OfflineStatus {
+                known_good_at: known_good_path()
+                    .ok()
+                    .and_then(|path| fs::metadata(path).ok())
+                    .and_then(|meta| meta.modified().ok())
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp()),
+                ..OfflineStatus::default()
+            }),
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn status(&self) -> OfflineStatus {
+        self.status.lock().clone()
+    }
+
+    /// 离线期间暂停订阅自动更新
+    pub fn updates_suppressed(&self) -> bool {
+        self.status.lock().active
+    }
+
+    /// 运行时配置成功应用到内核后保存一份副本，此时内核已不再使用固定的旧配置
+    pub fn record_known_good(&self, run_path: &Path) {
+        let result = known_good_path().and_then(|path| Ok(fs::copy(run_path, path).map(|_| ())?));
+        let mut status = self.status.lock();
+        status.pinned = false;
+        match result {
+            Ok(()) => status.known_good_at = Some(chrono::Utc::now().timestamp()),
+            Err(e) => logging!(
+                warn,
+                Type::Config,
+                true,
+                "[离线模式] 保存可用配置失败: {}",
+                e
+            ),
+        }
+    }
+
+    pub async fn record_update_success(&self, uid: &str) {
+        let max_failures = {
+            let mut failures = self.failures.lock();
+            failures.remove(uid);
+            failures.values().copied().max().unwrap_or(0)
+        };
+        let should_exit = {
+            let mut status = self.status.lock();
+            status.consecutive_failures = max_failures;
+            if max_failures == 0 {
+                status.last_error = None;
+            }
+            status.active
+                && status.reason == Some(OfflineReason::UpdateFailures)
+                && max_failures < FAILURE_THRESHOLD
+        };
+        if should_exit {
+            self.exit().await;
+        }
+    }
+
+    /// 同一订阅连续失败达到阈值才进入离线模式，避免多个失效订阅的失败次数累加
+    pub async fn record_update_failure(&self, uid: &str, error: &str) {
+        let (count, max_failures) = {
+            let mut failures = self.failures.lock();
+            let count = failures.entry(uid.to_string()).or_default();
+            *count += 1;
+            let count = *count;
+            (count, failures.values().copied().max().unwrap_or(count))
+        };
+        let should_enter = {
+            let mut status = self.status.lock();
+            status.consecutive_failures = max_failures;
+            status.last_error = Some(error.to_string());
+            !status.active && count >= FAILURE_THRESHOLD
+        };
+        if should_enter {
+            self.enter(OfflineReason::UpdateFailures).await;
+        } else {
+            self.emit_status();
+        }
+    }
+
+    /// 进入离线模式，并将内核固定到最近一次可用的运行时配置
+    pub async fn enter(&self, reason: OfflineReason) {
+        {
+            let mut status = self.status.lock();
+            if status.active {
+                return;
+            }
+            status.active = true;
+            status.reason = Some(reason);
+            status.since = Some(chrono::Utc::now().timestamp());
+        }
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[离线模式] 已进入，原因: {:?}",
+            reason
+        );
+
+        let pinned = match known_good_path() {
+            Ok(path) if path.exists() => {
+                match CoreManager::global().put_configs_force(path).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        logging!(
+                            error,
+                            Type::Config,
+                            true,
+                            "[离线模式] 固定可用配置失败: {}",
+                            e
+                        );
+                        false
+                    }
+                }
+            }
+            _ => {
+                logging!(warn, Type::Config, true, "[离线模式] 没有可用的历史配置");
+                false
+            }
+        };
+        self.status.lock().pinned = pinned;
+        if pinned {
+            handle::Handle::refresh_clash();
+        }
+        self.emit_status();
+    }
+
+    /// 退出离线模式，恢复订阅更新并按当前配置重新生成运行时配置
+    pub async fn exit(&self) {
+        let was_pinned = {
+            let mut status = self.status.lock();
+            if !status.active {
+                return;
+            }
+            let was_pinned = status.pinned;
+            *status = OfflineStatus {
+                known_good_at: status.known_good_at,
+                consecutive_failures: status.consecutive_failures,
+                last_error: status.last_error.take(),
+                ..OfflineStatus::default()
+            };
+            was_pinned
+        };
+        logging!(info, Type::Config, true, "[离线模式] 已退出");
+
+        if was_pinned {
+            match CoreManager::global().update_config().await {
+                Ok((true, _)) => handle::Handle::refresh_clash(),
+                Ok((false, msg)) => {
+                    logging!(
+                        warn,
+                        Type::Config,
+                        true,
+                        "[离线模式] 恢复当前配置失败: {}",
+                        msg
+                    )
+                }
+                Err(e) => {
+                    logging!(
+                        error,
+                        Type::Config,
+                        true,
+                        "[离线模式] 恢复当前配置失败: {}",
+                        e
+                    )
+                }
+            }
+        }
+        self.emit_status();
+    }
+
+    fn emit_status(&self) {
+        if let Some(app_handle) = handle::Handle::global().app_handle() {
+            let _ = app_handle.emit(OFFLINE_EVENT, self.status());
+        }
+    }
+}
+
+/// 定期探测网络可达性，断网时进入离线模式，恢复后自动退出并更新当前订阅
+/// 因更新失败离线时定期重试当前订阅，成功后自动退出
+pub fn init_offline_monitor() {
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut interval = tokio::time::interval(MONITOR_TICK);
+        let mut ticks_since_retry = 0u32;
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+
+            // 只有所有探测地址都无响应才视为断网，门户页面等无法判断的情况按在线处理
+            let connected =
+                captive_portal::probe().await != captive_portal::ProbeOutcome::Unreachable;
+            let offline = OfflineMode::global();
+            let status = offline.status();
+            if !connected && !status.active {
+                offline.enter(OfflineReason::NoNetwork).await;
+            } else if connected && status.active && status.reason == Some(OfflineReason::NoNetwork)
+            {
+                offline.exit().await;
+                retry_current_profile().await;
+            } else if status.reason == Some(OfflineReason::UpdateFailures) {
+                ticks_since_retry += 1;
+                if ticks_since_retry >= RETRY_EVERY_TICKS {
+                    ticks_since_retry = 0;
+                    retry_current_profile().await;
+                }
+            }
+        }
+    });
+}
+
+/// 网络恢复后立即更新当前订阅一次
+async fn retry_current_profile() {
+    let Some(uid) = Config::profiles().await.latest_ref().get_current() else {
+        return;
+    };
+    if let Err(e) = crate::feat::update_profile(uid, None, Some(true)).await {
+        logging!(
+            warn,
+            Type::Config,
+            true,
+            "[离线模式] 网络恢复后更新订阅失败: {}",
+            e
+        );
+    }
+}
+
+fn known_good_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(KNOWN_GOOD_FILE))
+}
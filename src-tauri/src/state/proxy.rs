@@ -1,100 +1,172 @@
-// use crate::utils::logging::Type;
-// use crate::{logging, singleton};
 use crate::singleton;
 use dashmap::DashMap;
+use serde::Serialize;
 use serde_json::Value;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::OnceCell;
 
+/// 经由缓存访问的 IPC 接口，每个接口有各自的过期时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheEndpoint {
+    Proxies,
+    Providers,
+    ClashConfig,
+}
+
+impl CacheEndpoint {
+    pub const ALL: [CacheEndpoint; 3] = [Self::Proxies, Self::Providers, Self::ClashConfig];
+
+    /// 修改操作会主动失效缓存，过期时间只用于兜底延迟等被动变化
+    pub const fn ttl(self) -> Duration {
+        match self {
+            Self::Proxies => Duration::from_secs(10),
+            Self::Providers => Duration::from_secs(30),
+            Self::ClashConfig => Duration::from_secs(60),
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
 pub struct CacheEntry {
     pub value: Arc<Value>,
+    pub fetched_at: Instant,
     pub expires_at: Instant,
 }
 
+#[derive(Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// 单个接口的缓存统计
+#[derive(Debug, Clone, Serialize)]
+pub struct IpcCacheStats {
+    pub endpoint: CacheEndpoint,
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// 等待同一次进行中请求的次数，即被合并的并发请求
+    pub coalesced: u64,
+    pub invalidations: u64,
+    pub cached: bool,
+    pub age_ms: Option<u64>,
+}
+
 pub struct ProxyRequestCache {
-    pub map: DashMap<String, Arc<OnceCell<Box<CacheEntry>>>>,
+    map: DashMap<CacheEndpoint, Arc<OnceCell<CacheEntry>>>,
+    counters: [CacheCounters; 3],
 }
 
 impl ProxyRequestCache {
     fn new() -> Self {
         ProxyRequestCache {
             map: DashMap::new(),
+            counters: Default::default(),
         }
     }
 
-    pub fn make_key(prefix: &str, id: &str) -> String {
-        format!("{prefix}:{id}")
-    }
-
-    pub async fn get_or_fetch<F, Fut>(&self, key: String, ttl: Duration, fetch_fn: F) -> Arc<Value>
+    /// 读取缓存，过期或不存在时请求；并发请求只会触发一次 IPC 调用
+    pub async fn get_or_fetch<F, Fut>(&self, endpoint: CacheEndpoint, fetch_fn: F) -> Arc<Value>
     where
-        F: Fn() -> Fut + Send + 'static,
+        F: Fn() -> Fut + Send + Sync + 'static,
         Fut: std::future::Future<Output = Value> + Send + 'static,
     {
+        let counters = &self.counters[endpoint.index()];
         loop {
-            let now = Instant::now();
-            let key_cloned = key.clone();
-
-            // Get or create the cell
             let cell = self
                 .map
-                .entry(key_cloned.clone())
+                .entry(endpoint)
                 .or_insert_with(|| Arc::new(OnceCell::new()))
                 .clone();
 
-            // Check if we have a valid cached entry
             if let Some(entry) = cell.get() {
-                if entry.expires_at > now {
+                if entry.expires_at > Instant::now() {
+                    counters.hits.fetch_add(1, Ordering::Relaxed);
                     return Arc::clone(&entry.value);
                 }
-                // Entry is expired, remove it
-                self.map
-                    .remove_if(&key_cloned, |_, v| Arc::ptr_eq(v, &cell));
-                continue; // Retry with fresh cell
+                // 已过期，替换为新的 cell 后重试
+                self.map.remove_if(&endpoint, |_, v| Arc::ptr_eq(v, &cell));
+                continue;
             }
 
-            // Try to set a new value
-            let value = fetch_fn().await;
-            let entry = Box::new(CacheEntry {
-                value: Arc::new(value),
-                expires_at: Instant::now() + ttl,
-            });
-
-            match cell.set(entry) {
-                Ok(_) => {
-                    // Successfully set the value, it must exist now
-                    if let Some(set_entry) = cell.get() {
-                        return Arc::clone(&set_entry.value);
-                    }
-                }
-                Err(_) => {
-                    if let Some(existing_entry) = cell.get() {
-                        if existing_entry.expires_at > Instant::now() {
-                            return Arc::clone(&existing_entry.value);
-                        }
-                        self.map
-                            .remove_if(&key_cloned, |_, v| Arc::ptr_eq(v, &cell));
+            let mut fetched = false;
+            let fetched_flag = &mut fetched;
+            let fetch_fn = &fetch_fn;
+            let entry = cell
+                .get_or_init(|| async move {
+                    *fetched_flag = true;
+                    let value = fetch_fn().await;
+                    let now = Instant::now();
+                    CacheEntry {
+                        value: Arc::new(value),
+                        fetched_at: now,
+                        expires_at: now + endpoint.ttl(),
                     }
-                }
+                })
+                .await;
+            if fetched {
+                counters.misses.fetch_add(1, Ordering::Relaxed);
+            } else {
+                counters.coalesced.fetch_add(1, Ordering::Relaxed);
             }
+            return Arc::clone(&entry.value);
+        }
+    }
+
+    /// 使接口缓存失效，下次读取时重新请求
+    pub fn invalidate(&self, endpoint: CacheEndpoint) {
+        if self.map.remove(&endpoint).is_some() {
+            self.counters[endpoint.index()]
+                .invalidations
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn invalidate_all(&self) {
+        for endpoint in CacheEndpoint::ALL {
+            self.invalidate(endpoint);
         }
     }
 
-    // TODO
+    /// 释放代理和提供者缓存，轻量模式或内存紧张时调用
     pub fn clean_default_keys(&self) {
-        // logging!(info, Type::Cache, "Cleaning proxies keys");
-        // let proxies_key = Self::make_key("proxies", "default");
-        // self.map.remove(&proxies_key);
-
-        // logging!(info, Type::Cache, "Cleaning providers keys");
-        // let providers_key = Self::make_key("providers", "default");
-        // self.map.remove(&providers_key);
-
-        // !The frontend goes crash if we clean the clash_config cache
-        // logging!(info, Type::Cache, "Cleaning clash config keys");
-        // let clash_config_key = Self::make_key("clash_config", "default");
-        // self.map.remove(&clash_config_key);
+        // 不清理 clash_config，前端依赖其常驻
+        self.invalidate(CacheEndpoint::Proxies);
+        self.invalidate(CacheEndpoint::Providers);
+    }
+
+    pub fn stats(&self) -> Vec<IpcCacheStats> {
+        let now = Instant::now();
+        CacheEndpoint::ALL
+            .into_iter()
+            .map(|endpoint| {
+                let counters = &self.counters[endpoint.index()];
+                let entry = self.map.get(&endpoint);
+                let live = entry
+                    .as_ref()
+                    .and_then(|cell| cell.get())
+                    .filter(|entry| entry.expires_at > now);
+                IpcCacheStats {
+                    endpoint,
+                    ttl_secs: endpoint.ttl().as_secs(),
+                    hits: counters.hits.load(Ordering::Relaxed),
+                    misses: counters.misses.load(Ordering::Relaxed),
+                    coalesced: counters.coalesced.load(Ordering::Relaxed),
+                    invalidations: counters.invalidations.load(Ordering::Relaxed),
+                    cached: live.is_some(),
+                    age_ms: live.map(|entry| (now - entry.fetched_at).as_millis() as u64),
+                }
+            })
+            .collect()
     }
 }
 
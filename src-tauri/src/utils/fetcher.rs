@@ -0,0 +1,198 @@
+use crate::config::Config;
+use crate::utils::network::{ProxyType, resolve_proxy_url};
+use anyhow::{Result, bail};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use reqwest::{
+    Client, StatusCode, Version,
+    header::{HeaderMap, USER_AGENT},
+    redirect::Policy,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use tauri::Url;
+
+/// 按代理地址与证书校验方式复用的 HTTP 客户端，保持连接池与 HTTP/2 会话
+static CLIENTS: Lazy<Mutex<HashMap<(Option<String>, bool), Client>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 未设置超时时单次下载的超时（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+
+/// 订阅地址 -> 下载统计
+static FETCH_STATS: Lazy<Mutex<HashMap<String, FetchStats>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 单个订阅地址的下载统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FetchStats {
+    pub requests: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub total_bytes: u64,
+    pub last_duration_ms: Option<u64>,
+    pub avg_duration_ms: Option<u64>,
+    pub last_status: Option<u16>,
+    pub last_http_version: Option<String>,
+    pub last_error: Option<String>,
+    pub last_fetched_at: Option<i64>,
+}
+
+/// 下载响应
+#[derive(Debug)]
+pub struct FetchResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    version: Version,
+    body: String,
+}
+
+impl FetchResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn text_with_charset(&self) -> Result<&str> {
+        Ok(&self.body)
+    }
+}
+
+/// 获取共享客户端，直连时忽略环境代理
+pub fn shared_client(proxy_url: Option<&str>, accept_invalid_certs: bool) -> Result<Client> {
+    let key = (proxy_url.map(str::to_string), accept_invalid_certs);
+    let mut clients = CLIENTS.lock();
+    if let Some(client) = clients.get(&key) {
+        return Ok(client.clone());
+    }
+
+    let mut builder = Client::builder()
+        .pool_max_idle_per_host(8)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_adaptive_window(true)
+        .redirect(Policy::limited(10))
+        .danger_accept_invalid_certs(accept_invalid_certs);
+    builder = match proxy_url {
+        Some(proxy_url) => builder.proxy(reqwest::Proxy::all(proxy_url)?),
+        None => builder.no_proxy(),
+    };
+    let client = builder.build()?;
+    clients.insert(key, client.clone());
+    Ok(client)
+}
+
+/// 下载订阅内容，只请求一次，失败重试由订阅同步统一处理
+/// timeout_secs 为本次请求的超时，未指定时使用全局设置
+pub async fn fetch(
+    url: &str,
+    proxy_type: ProxyType,
+    user_agent: Option<String>,
+    accept_invalid_certs: bool,
+    timeout_secs: Option<u64>,
+) -> Result<FetchResponse> {
+    let timeout = match timeout_secs {
+        Some(secs) => secs,
+        None => default_timeout_secs().await,
+    };
+    let proxy_url = resolve_proxy_url(proxy_type).await;
+    let client = shared_client(proxy_url.as_deref(), accept_invalid_certs)?;
+    let user_agent =
+        user_agent.unwrap_or_else(|| format!("liebseu-clash/v{}", env!("CARGO_PKG_VERSION")));
+
+    // 地址中的账号密码改为 Basic 认证
+    let mut parsed = Url::parse(url)?;
+    let credentials = (!parsed.username().is_empty()).then(|| {
+        (
+            parsed.username().to_string(),
+            parsed.password().map(str::to_string),
+        )
+    });
+    parsed.set_username("").ok();
+    parsed.set_password(None).ok();
+
+    let started_at = Instant::now();
+    let mut request = client
+        .get(parsed.as_str())
+        .timeout(Duration::from_secs(timeout.max(1)))
+        .header(USER_AGENT, user_agent);
+    if let Some((username, password)) = credentials {
+        request = request.basic_auth(username, password);
+    }
+
+    let result = async {
+        let response = request.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let version = response.version();
+        let body = response.text().await?;
+        Ok::<_, reqwest::Error>(FetchResponse {
+            status,
+            headers,
+            version,
+            body,
+        })
+    }
+    .await;
+    record(url, &result, started_at.elapsed());
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => bail!("{e}"),
+    }
+}
+
+/// 指定订阅地址的下载统计
+pub fn fetch_stats(url: &str) -> Option<FetchStats> {
+    FETCH_STATS.lock().get(url).cloned()
+}
+
+// ===== 内部实现函数 =====
+
+async fn default_timeout_secs() -> u64 {
+    Config::verge()
+        .await
+        .latest_ref()
+        .subscription_fetch
+        .as_ref()
+        .and_then(|config| config.fetch_timeout_secs)
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+}
+
+fn record(url: &str, result: &Result<FetchResponse, reqwest::Error>, elapsed: Duration) {
+    let mut all_stats = FETCH_STATS.lock();
+    let stats = all_stats.entry(url.to_string()).or_default();
+    let elapsed_ms = elapsed.as_millis() as u64;
+    stats.requests += 1;
+    stats.last_duration_ms = Some(elapsed_ms);
+    stats.avg_duration_ms = Some(match stats.avg_duration_ms {
+        Some(avg) => (avg * (stats.requests - 1) + elapsed_ms) / stats.requests,
+        None => elapsed_ms,
+    });
+    stats.last_fetched_at = Some(chrono::Utc::now().timestamp());
+    match result {
+        Ok(response) if response.status.is_success() => {
+            stats.successes += 1;
+            stats.total_bytes += response.body.len() as u64;
+            stats.last_status = Some(response.status.as_u16());
+            stats.last_http_version = Some(format!("{:?}", response.version));
+            stats.last_error = None;
+        }
+        Ok(response) => {
+            stats.failures += 1;
+            stats.last_status = Some(response.status.as_u16());
+            stats.last_http_version = Some(format!("{:?}", response.version));
+            stats.last_error = Some(format!("status {}", response.status));
+        }
+        Err(e) => {
+            stats.failures += 1;
+            stats.last_status = e.status().map(|s| s.as_u16());
+            stats.last_error = Some(e.to_string());
+        }
+    }
+}
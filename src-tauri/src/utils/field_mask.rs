@@ -0,0 +1,111 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// 字段路径前缀树
+#[derive(Debug, Default)]
+struct MaskNode {
+    terminal: bool,
+    children: HashMap<String, MaskNode>,
+}
+
+impl MaskNode {
+    fn build(paths: &[String]) -> Self {
+        let mut root = Self::default();
+        for path in paths {
+            let mut node = &mut root;
+            for segment in path.split('.').filter(|s| !s.is_empty()) {
+                node = node.children.entry(segment.to_string()).or_default();
+            }
+            node.terminal = true;
+        }
+        root
+    }
+}
+
+/// 按字段路径裁剪 JSON，仅保留请求的字段
+///
+/// 路径以 `.` 分隔，`*` 匹配对象的任意键，数组会对每个元素应用同一路径，
+/// 例如 `proxies.*.now`、`connections.metadata.host`。
+/// 未提供路径时返回原值。
+pub fn apply_field_mask(value: Value, paths: Option<&[String]>) -> Value {
+    let Some(paths) = paths.filter(|p| !p.is_empty()) else {
+        return value;
+    };
+
+    let root = MaskNode::build(paths);
+    if root.terminal {
+        return value;
+    }
+    match value {
+        Value::Object(_) => mask_value(value, &[&root]).unwrap_or(Value::Object(Map::new())),
+        Value::Array(_) => mask_value(value, &[&root]).unwrap_or(Value::Array(Vec::new())),
+        other => other,
+    }
+}
+
+/// 递归裁剪，nodes 为当前位置匹配到的所有路径节点（具体键与 `*` 可能同时匹配）
+fn mask_value(value: Value, nodes: &[&MaskNode]) -> Option<Value> {
+    if nodes.iter().any(|n| n.terminal) {
+        return Some(value);
+    }
+
+    match value {
+        Value::Object(map) => {
+            let mut masked = Map::new();
+            for (key, child) in map {
+                let matched: Vec<&MaskNode> = nodes
+                    .iter()
+                    .flat_map(|n| [n.children.get(&key), n.children.get("*")])
+                    .flatten()
+                    .collect();
+                if matched.is_empty() {
+                    continue;
+                }
+                if let Some(child) = mask_value(child, &matched) {
+                    masked.insert(key, child);
+                }
+            }
+            Some(Value::Object(masked))
+        }
+        Value::Array(items) => Some(Value::Array(
+            items
+                .into_iter()
+                .filter_map(|item| mask_value(item, nodes))
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_field_mask() {
+        let value = json!({
+            "proxies": {
+                "GLOBAL": { "now": "HK", "all": ["HK", "JP"], "history": [{ "delay": 10 }] },
+                "HK": { "type": "ss", "history": [{ "delay": 20, "time": "t" }] }
+            }
+        });
+
+        let paths = vec![
+            "proxies.*.now".to_string(),
+            "proxies.*.history.delay".to_string(),
+        ];
+        assert_eq!(
+            apply_field_mask(value.clone(), Some(&paths)),
+            json!({
+                "proxies": {
+                    "GLOBAL": { "now": "HK", "history": [{ "delay": 10 }] },
+                    "HK": { "history": [{ "delay": 20 }] }
+                }
+            })
+        );
+
+        assert_eq!(apply_field_mask(value.clone(), None), value);
+        assert_eq!(apply_field_mask(value.clone(), Some(&[])), value);
+    }
+}
@@ -2,8 +2,12 @@ use crate::{
     config::*,
     core::handle,
     logging,
+    module::lightweight,
     process::AsyncHandler,
-    utils::{dirs, help, logging::Type},
+    utils::{
+        dirs, help,
+        logging::{self as log_profile, LoggingProfile, Type},
+    },
 };
 use anyhow::Result;
 use chrono::{Local, TimeZone};
@@ -11,13 +15,58 @@ use log::LevelFilter;
 use log4rs::{
     append::{console::ConsoleAppender, file::FileAppender},
     config::{Appender, Logger, Root},
-    encode::pattern::PatternEncoder,
+    encode::{Encode, pattern::PatternEncoder, writer::simple::SimpleWriter},
+};
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
-use std::{path::PathBuf, str::FromStr};
 use tauri_plugin_shell::ShellExt;
 use tokio::fs;
 use tokio::fs::DirEntry;
 
+/// server 模式默认的日志目录上限（MB）
+const DEFAULT_LOG_DIR_MAX_SIZE_MB: u64 = 100;
+
+/// 日志目录清理间隔
+const LOG_PRUNE_TICK: Duration = Duration::from_secs(3600);
+
+static LOG_PRUNER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// server 模式下去掉日志中的 emoji
+#[derive(Debug)]
+struct ProfileEncoder(PatternEncoder);
+
+impl Encode for ProfileEncoder {
+    fn encode(
+        &self,
+        w: &mut dyn log4rs::encode::Write,
+        record: &log::Record<'_>,
+    ) -> anyhow::Result<()> {
+        if !log_profile::is_server_mode() {
+            return self.0.encode(w, record);
+        }
+
+        let mut buf = SimpleWriter(Vec::new());
+        self.0.encode(&mut buf, record)?;
+        let plain: String = String::from_utf8_lossy(&buf.0)
+            .chars()
+            .filter(|c| !is_emoji(*c))
+            .collect();
+        w.write_all(plain.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D
+    )
+}
+
 /// initialize this instance's log file
 async fn init_log() -> Result<()> {
     let log_dir = dirs::app_logs_dir()?;
@@ -39,10 +88,12 @@ async fn init_log() -> Result<()> {
         _ => "{d(%Y-%m-%d %H:%M:%S)} {l} - {m}{n}",
     };
 
-    let encode = Box::new(PatternEncoder::new(log_pattern));
-
-    let stdout = ConsoleAppender::builder().encoder(encode.clone()).build();
-    let tofile = FileAppender::builder().encoder(encode).build(log_file)?;
+    let stdout = ConsoleAppender::builder()
+        .encoder(Box::new(ProfileEncoder(PatternEncoder::new(log_pattern))))
+        .build();
+    let tofile = FileAppender::builder()
+        .encoder(Box::new(ProfileEncoder(PatternEncoder::new(log_pattern))))
+        .build(log_file)?;
 
     let mut logger_builder = Logger::builder();
     let mut root_builder = Root::builder();
@@ -148,6 +199,111 @@ pub async fn delete_log() -> Result<()> {
     Ok(())
 }
 
+/// 按当前日志配置档调整日志行为，轻量模式下自动使用 server 模式
+pub async fn apply_logging_profile() {
+    let (configured, log_level, max_size_mb) = {
+        let verge = Config::verge().await;
+        let verge = verge.latest_ref();
+        (
+            verge
+                .logging_profile
+                .as_deref()
+                .and_then(LoggingProfile::parse)
+                .unwrap_or_default(),
+            verge.get_log_level(),
+            verge
+                .log_dir_max_size
+                .unwrap_or(DEFAULT_LOG_DIR_MAX_SIZE_MB),
+        )
+    };
+
+    let profile = if lightweight::is_in_lightweight_mode() {
+        LoggingProfile::Server
+    } else {
+        configured
+    };
+    let server = profile == LoggingProfile::Server;
+    if server == log_profile::is_server_mode() {
+        return;
+    }
+
+    log_profile::set_server_mode(server);
+    if server {
+        log::set_max_level(log_level.min(LevelFilter::Info));
+    } else {
+        log::set_max_level(log_level);
+    }
+    logging!(info, Type::Setup, "日志配置档切换为 {}", profile.as_str());
+
+    if server && let Err(e) = prune_log_dir(max_size_mb).await {
+        logging!(warn, Type::Setup, "清理日志目录失败: {}", e);
+    }
+}
+
+/// 启动 server 模式下的日志目录定期清理
+pub fn init_log_pruner() {
+    if LOG_PRUNER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    AsyncHandler::spawn(|| async {
+        let mut interval = tokio::time::interval(LOG_PRUNE_TICK);
+        loop {
+            interval.tick().await;
+            if handle::Handle::global().is_exiting() {
+                break;
+            }
+            if !log_profile::is_server_mode() {
+                continue;
+            }
+
+            let max_size_mb = Config::verge()
+                .await
+                .latest_ref()
+                .log_dir_max_size
+                .unwrap_or(DEFAULT_LOG_DIR_MAX_SIZE_MB);
+            if let Err(e) = prune_log_dir(max_size_mb).await {
+                logging!(warn, Type::Setup, "清理日志目录失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 从最旧的日志开始删除，直到日志目录（含 service 子目录）不超过上限
+async fn prune_log_dir(max_size_mb: u64) -> Result<()> {
+    let log_dir = dirs::app_logs_dir()?;
+    let mut files = Vec::new();
+    for dir in [log_dir.clone(), log_dir.join("service")] {
+        if !dir.exists() {
+            continue;
+        }
+        let mut read_dir = fs::read_dir(&dir).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() && entry.file_name().to_string_lossy().ends_with(".log") {
+                files.push((metadata.modified()?, metadata.len(), entry.path()));
+            }
+        }
+    }
+
+    let max_size = max_size_mb * 1024 * 1024;
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort_by_key(|(modified, _, _)| *modified);
+    // 保留最新的文件，当前实例正在写入
+    files.pop();
+
+    for (_, len, path) in files {
+        if total <= max_size {
+            break;
+        }
+        if fs::remove_file(&path).await.is_ok() {
+            total -= len;
+            logging!(info, Type::Setup, "delete log file: {}", path.display());
+        }
+    }
+    Ok(())
+}
+
 /// 初始化DNS配置文件
 async fn init_dns_config() -> Result<()> {
     use serde_yaml_ng::Value;
@@ -360,6 +516,7 @@ pub async fn init_config() -> Result<()> {
     if let Err(e) = init_log().await {
         eprintln!("Failed to initialize logging: {}", e);
     }
+    apply_logging_profile().await;
 
     ensure_directories().await?;
 
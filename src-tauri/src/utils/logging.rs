@@ -1,4 +1,7 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Type {
@@ -45,6 +48,57 @@ impl fmt::Display for Type {
     }
 }
 
+/// 日志配置档
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoggingProfile {
+    /// 日常交互使用，按配置输出全部日志
+    #[default]
+    Interactive,
+    /// 长时间无人值守运行：关闭调试日志与控制台输出，降低采样频率，限制日志目录大小
+    Server,
+}
+
+impl LoggingProfile {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "interactive" => Some(Self::Interactive),
+            "server" => Some(Self::Server),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Server => "server",
+        }
+    }
+}
+
+static SERVER_MODE: AtomicBool = AtomicBool::new(false);
+
+/// server 模式下采样间隔的放大倍数
+const SERVER_SAMPLING_FACTOR: u32 = 5;
+
+/// 当前是否处于 server 日志模式
+pub fn is_server_mode() -> bool {
+    SERVER_MODE.load(Ordering::Relaxed)
+}
+
+pub fn set_server_mode(enabled: bool) {
+    SERVER_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// 根据日志模式调整周期性采样的间隔
+pub fn sampling_interval(base: Duration) -> Duration {
+    if is_server_mode() {
+        base * SERVER_SAMPLING_FACTOR
+    } else {
+        base
+    }
+}
+
 #[macro_export]
 macro_rules! error {
     ($result: expr) => {
@@ -77,7 +131,7 @@ macro_rules! trace_err {
 }
 
 /// wrap the anyhow error
-/// transform the error to String
+/// transform the error to CmdError
 #[macro_export]
 macro_rules! wrap_err {
     // Case 1: Future<Result<T, E>>
@@ -86,7 +140,7 @@ macro_rules! wrap_err {
             Ok(a) => Ok(a),
             Err(err) => {
                 log::error!(target: "app", "{}", err);
-                Err(err.to_string())
+                Err($crate::cmd::CmdError::from(err))
             }
         }
     }};
@@ -97,7 +151,7 @@ macro_rules! wrap_err {
             Ok(a) => Ok(a),
             Err(err) => {
                 log::error!(target: "app", "{}", err);
-                Err(err.to_string())
+                Err($crate::cmd::CmdError::from(err))
             }
         }
     }};
@@ -107,18 +161,21 @@ macro_rules! wrap_err {
 macro_rules! logging {
     // 带 println 的版本（支持格式化参数）
     ($level:ident, $type:expr, true, $($arg:tt)*) => {
-        println!("{} {}", $type, format_args!($($arg)*));
-        log::$level!(target: "app", "{} {}", $type, format_args!($($arg)*));
+        let message = $crate::utils::redact::redact_log(format!($($arg)*));
+        if !$crate::utils::logging::is_server_mode() {
+            println!("{} {}", $type, message);
+        }
+        log::$level!(target: "app", "{} {}", $type, message);
     };
 
     // 带 println 的版本（使用 false 明确不打印）
     ($level:ident, $type:expr, false, $($arg:tt)*) => {
-        log::$level!(target: "app", "{} {}", $type, format_args!($($arg)*));
+        log::$level!(target: "app", "{} {}", $type, $crate::utils::redact::redact_log(format!($($arg)*)));
     };
 
     // 不带 print 参数的版本（默认不打印）
     ($level:ident, $type:expr, $($arg:tt)*) => {
-        log::$level!(target: "app", "{} {}", $type, format_args!($($arg)*));
+        log::$level!(target: "app", "{} {}", $type, $crate::utils::redact::redact_log(format!($($arg)*)));
     };
 }
 
@@ -129,10 +186,11 @@ macro_rules! logging_error {
         match $expr {
             Ok(_) => {},
             Err(err) => {
-                if $print {
-                    println!("[{}] Error: {}", $type, err);
+                let message = $crate::utils::redact::redact_log(err.to_string());
+                if $print && !$crate::utils::logging::is_server_mode() {
+                    println!("[{}] Error: {}", $type, message);
                 }
-                log::error!(target: "app", "[{}] {}", $type, err);
+                log::error!(target: "app", "[{}] {}", $type, message);
             }
         }
     };
@@ -140,16 +198,17 @@ macro_rules! logging_error {
     // 2. 处理 Result<T, E>，默认不打印
     ($type:expr, $expr:expr) => {
         if let Err(err) = $expr {
-            log::error!(target: "app", "[{}] {}", $type, err);
+            log::error!(target: "app", "[{}] {}", $type, $crate::utils::redact::redact_log(err.to_string()));
         }
     };
 
     // 3. 处理格式化字符串，带打印控制
     ($type:expr, $print:expr, $fmt:literal $(, $arg:expr)*) => {
-        if $print {
-            println!("[{}] {}", $type, format_args!($fmt $(, $arg)*));
+        let message = $crate::utils::redact::redact_log(format!($fmt $(, $arg)*));
+        if $print && !$crate::utils::logging::is_server_mode() {
+            println!("[{}] {}", $type, message);
         }
-        log::error!(target: "app", "[{}] {}", $type, format_args!($fmt $(, $arg)*));
+        log::error!(target: "app", "[{}] {}", $type, message);
     };
 
     // 4. 处理格式化字符串，不带 bool 时，默认 `false`
@@ -1,5 +1,7 @@
 pub mod autostart;
 pub mod dirs;
+pub mod fetcher;
+pub mod field_mask;
 pub mod format;
 pub mod help;
 pub mod i18n;
@@ -7,7 +9,9 @@ pub mod init;
 pub mod logging;
 pub mod network;
 pub mod notification;
+pub mod redact;
 pub mod resolve;
+pub mod secrets;
 pub mod server;
 pub mod singleton;
 pub mod tmpl;
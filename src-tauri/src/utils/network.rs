@@ -94,6 +94,33 @@ pub enum ProxyType {
     None,
     Localhost,
     System,
+    /// 本机指定端口上的内核监听器
+    LocalPort(u16),
+}
+
+/// 解析代理类型对应的代理地址，直连或系统代理未开启时返回 None
+pub async fn resolve_proxy_url(proxy_type: ProxyType) -> Option<String> {
+    match proxy_type {
+        ProxyType::None => None,
+        ProxyType::Localhost => {
+            let port = {
+                let verge_port = Config::verge().await.latest_ref().verge_mixed_port;
+                match verge_port {
+                    Some(port) => port,
+                    None => Config::clash().await.latest_ref().get_mixed_port(),
+                }
+            };
+            Some(format!("http://127.0.0.1:{port}"))
+        }
+        ProxyType::System => {
+            if let Ok(p @ Sysproxy { enable: true, .. }) = Sysproxy::get_system_proxy() {
+                Some(format!("http://{}:{}", p.host, p.port))
+            } else {
+                None
+            }
+        }
+        ProxyType::LocalPort(port) => Some(format!("http://127.0.0.1:{port}")),
+    }
 }
 
 pub struct NetworkManager {
@@ -191,28 +218,9 @@ impl NetworkManager {
         user_agent: Option<String>,
         accept_invalid_certs: bool,
     ) -> Result<HttpClient> {
-        let proxy_uri = match proxy_type {
-            ProxyType::None => None,
-            ProxyType::Localhost => {
-                let port = {
-                    let verge_port = Config::verge().await.latest_ref().verge_mixed_port;
-                    match verge_port {
-                        Some(port) => port,
-                        None => Config::clash().await.latest_ref().get_mixed_port(),
-                    }
-                };
-                let proxy_scheme = format!("http://127.0.0.1:{port}");
-                proxy_scheme.parse::<Uri>().ok()
-            }
-            ProxyType::System => {
-                if let Ok(p @ Sysproxy { enable: true, .. }) = Sysproxy::get_system_proxy() {
-                    let proxy_scheme = format!("http://{}:{}", p.host, p.port);
-                    proxy_scheme.parse::<Uri>().ok()
-                } else {
-                    None
-                }
-            }
-        };
+        let proxy_uri = resolve_proxy_url(proxy_type)
+            .await
+            .and_then(|proxy_scheme| proxy_scheme.parse::<Uri>().ok());
 
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -295,3 +303,229 @@ impl NetworkManager {
         Ok(response)
     }
 }
+
+/// 获取当前连接的 Wi-Fi SSID，未连接或无法识别时返回 None
+pub async fn current_wifi_ssid() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    let output = {
+        // Wi-Fi 网卡不一定是 en0，从硬件端口列表中查找
+        let device = list_hardware_ports()
+            .await
+            .into_iter()
+            .find(|(port, _)| {
+                let port = port.to_lowercase();
+                port.contains("wi-fi") || port.contains("airport")
+            })
+            .map(|(_, device)| device)?;
+        tokio::process::Command::new("networksetup")
+            .args(["-getairportnetwork", &device])
+            .output()
+            .await
+            .ok()?
+    };
+
+    #[cfg(target_os = "linux")]
+    let output = tokio::process::Command::new("iwgetid")
+        .output()
+        .await
+        .ok()?;
+
+    #[cfg(target_os = "windows")]
+    let output = {
+        #[allow(unused_imports)] // creation_flags必须
+        use std::os::windows::process::CommandExt;
+        tokio::process::Command::new("netsh")
+            .args(["wlan", "show", "interfaces"])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW - 隐藏窗口
+            .output()
+            .await
+            .ok()?
+    };
+
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_wifi_ssid(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// 解析各平台命令输出中的 SSID，只认可明确的 SSID 字段
+fn parse_wifi_ssid(stdout: &str) -> Option<String> {
+    // macOS: "Current Wi-Fi Network: MyWifi"
+    // Windows: "    SSID                   : MyWifi"
+    // Linux (iwgetid): "wlan0     ESSID:\"MyWifi\""
+    let ssid = stdout.lines().find_map(|line| {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("Current Wi-Fi Network:") {
+            return Some(rest.trim().to_string());
+        }
+        if line.starts_with("SSID") && !line.starts_with("SSID BSSID") {
+            return line.split_once(':').map(|(_, v)| v.trim().to_string());
+        }
+        if let Some((_, rest)) = line.split_once("ESSID:") {
+            return Some(rest.trim().trim_matches('"').to_string());
+        }
+        None
+    })?;
+
+    (!ssid.is_empty()).then_some(ssid)
+}
+
+/// 当前网络环境
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NetworkContext {
+    pub ssid: Option<String>,
+    pub wired: bool, // 是否有已连接的有线网卡
+}
+
+/// 检测当前 Wi-Fi SSID 与有线网络连接状态
+pub async fn current_network_context() -> NetworkContext {
+    NetworkContext {
+        ssid: current_wifi_ssid().await,
+        wired: has_wired_connection().await,
+    }
+}
+
+/// Linux: 非虚拟、非无线且处于 up 状态的网卡
+#[cfg(target_os = "linux")]
+async fn has_wired_connection() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        let is_virtual = std::fs::canonicalize(&path)
+            .map(|p| p.starts_with("/sys/devices/virtual"))
+            .unwrap_or(true);
+        let is_up = std::fs::read_to_string(path.join("operstate"))
+            .map(|s| s.trim() == "up")
+            .unwrap_or(false);
+        !is_virtual && is_up && !path.join("wireless").exists()
+    })
+}
+
+/// macOS: 列出硬件端口及其网卡名，如 ("Wi-Fi", "en0")
+#[cfg(target_os = "macos")]
+async fn list_hardware_ports() -> Vec<(String, String)> {
+    let Ok(output) = tokio::process::Command::new("networksetup")
+        .arg("-listallhardwareports")
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    parse_hardware_ports(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// 解析 networksetup -listallhardwareports 输出
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_hardware_ports(stdout: &str) -> Vec<(String, String)> {
+    // "Hardware Port: Ethernet" 的下一行为 "Device: en5"
+    let mut ports = Vec::new();
+    let mut lines = stdout.lines();
+    while let Some(line) = lines.next() {
+        let Some(port) = line.strip_prefix("Hardware Port:") else {
+            continue;
+        };
+        if let Some(device) = lines.next().and_then(|l| l.strip_prefix("Device:")) {
+            ports.push((port.trim().to_string(), device.trim().to_string()));
+        }
+    }
+    ports
+}
+
+/// macOS: 硬件端口为以太网且已分配 IPv4 地址的网卡
+#[cfg(target_os = "macos")]
+async fn has_wired_connection() -> bool {
+    use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+
+    let devices: Vec<String> = list_hardware_ports()
+        .await
+        .into_iter()
+        .filter(|(port, _)| {
+            let port = port.to_lowercase();
+            (port.contains("ethernet") || port.contains("lan")) && !port.contains("wi-fi")
+        })
+        .map(|(_, device)| device)
+        .collect();
+
+    NetworkInterface::show()
+        .unwrap_or_default()
+        .into_iter()
+        .any(|iface| {
+            devices.contains(&iface.name) && iface.addr.iter().any(|addr| addr.ip().is_ipv4())
+        })
+}
+
+/// Windows: 已连接的以太网适配器
+#[cfg(target_os = "windows")]
+async fn has_wired_connection() -> bool {
+    #[allow(unused_imports)] // creation_flags必须
+    use std::os::windows::process::CommandExt;
+
+    let Ok(output) = tokio::process::Command::new("netsh")
+        .args(["interface", "show", "interface"])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW - 隐藏窗口
+        .output()
+        .await
+    else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+        let line = line.to_lowercase();
+        (line.contains("connected") || line.contains("已连接"))
+            && !line.contains("disconnected")
+            && (line.contains("ethernet") || line.contains("以太网"))
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+async fn has_wired_connection() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wifi_ssid_reads_platform_fields() {
+        assert_eq!(
+            parse_wifi_ssid("Current Wi-Fi Network: Home").as_deref(),
+            Some("Home")
+        );
+        assert_eq!(
+            parse_wifi_ssid(
+                "    Name : WLAN\n    SSID                   : Office\n    BSSID : aa:bb"
+            )
+            .as_deref(),
+            Some("Office")
+        );
+        assert_eq!(
+            parse_wifi_ssid("wlan0     ESSID:\"Cafe\"\n").as_deref(),
+            Some("Cafe")
+        );
+    }
+
+    #[test]
+    fn parse_wifi_ssid_ignores_status_messages() {
+        assert_eq!(
+            parse_wifi_ssid("You are not associated with an AirPort network.\n"),
+            None
+        );
+        assert_eq!(parse_wifi_ssid("en0 is not a Wi-Fi interface."), None);
+        assert_eq!(parse_wifi_ssid(""), None);
+    }
+
+    #[test]
+    fn parse_hardware_ports_pairs_devices() {
+        let stdout = "\nHardware Port: Ethernet\nDevice: en0\nEthernet Address: aa\n\nHardware Port: Wi-Fi\nDevice: en1\n";
+        assert_eq!(
+            parse_hardware_ports(stdout),
+            vec![
+                ("Ethernet".to_string(), "en0".to_string()),
+                ("Wi-Fi".to_string(), "en1".to_string())
+            ]
+        );
+    }
+}
@@ -14,6 +14,24 @@ pub enum NotificationEvent<'a> {
     AppQuit,
     #[cfg(target_os = "macos")]
     AppHidden,
+    ScheduledBackupFailed {
+        error: &'a str,
+    },
+    ProviderOutage {
+        name: &'a str,
+        reason: &'a str,
+    },
+    SubscriptionExpiring {
+        name: &'a str,
+        days: i64,
+    },
+    ProxySwitched {
+        group: &'a str,
+        proxy: &'a str,
+    },
+    CoreResourceHigh {
+        usage: &'a str,
+    },
 }
 
 fn notify(app: &AppHandle, title: &str, body: &str) {
@@ -69,6 +87,61 @@ pub async fn notify_event<'a>(app: AppHandle, event: NotificationEvent<'a>) {
         NotificationEvent::AppHidden => {
             notify(&app, &t("AppHiddenTitle").await, &t("AppHiddenBody").await);
         }
+        NotificationEvent::ScheduledBackupFailed { error } => {
+            notify(
+                &app,
+                &t("ScheduledBackupFailedTitle").await,
+                &t("ScheduledBackupFailedBody")
+                    .await
+                    .replace("{error}", error),
+            );
+        }
+        NotificationEvent::ProviderOutage { name, reason } => {
+            notify(
+                &app,
+                &t("ProviderOutageTitle").await,
+                &t("ProviderOutageBody")
+                    .await
+                    .replace("{name}", name)
+                    .replace("{reason}", reason),
+            );
+        }
+        NotificationEvent::SubscriptionExpiring { name, days: 0 } => {
+            notify(
+                &app,
+                &t("SubscriptionExpiringTitle").await,
+                &t("SubscriptionExpiresTodayBody")
+                    .await
+                    .replace("{name}", name),
+            );
+        }
+        NotificationEvent::SubscriptionExpiring { name, days } => {
+            notify(
+                &app,
+                &t("SubscriptionExpiringTitle").await,
+                &t("SubscriptionExpiringBody")
+                    .await
+                    .replace("{name}", name)
+                    .replace("{days}", &days.to_string()),
+            );
+        }
+        NotificationEvent::ProxySwitched { group, proxy } => {
+            notify(
+                &app,
+                &t("ProxySwitchedTitle").await,
+                &t("ProxySwitchedBody")
+                    .await
+                    .replace("{group}", group)
+                    .replace("{proxy}", proxy),
+            );
+        }
+        NotificationEvent::CoreResourceHigh { usage } => {
+            notify(
+                &app,
+                &t("CoreResourceHighTitle").await,
+                &t("CoreResourceHighBody").await.replace("{usage}", usage),
+            );
+        }
     }
 }
 
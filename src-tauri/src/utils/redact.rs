@@ -0,0 +1,231 @@
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+use std::{fs, path::PathBuf};
+
+use crate::utils::dirs;
+
+const REDACTION_POLICY_FILE: &str = "redaction_policy.json";
+
+pub const MASK: &str = "******";
+
+/// 默认脱敏的字段，按包含关系匹配（不区分大小写）
+const SENSITIVE_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "uuid",
+    "private-key",
+    "private_key",
+    "pre-shared-key",
+    "psk",
+    "auth",
+    "username",
+    "short-id",
+];
+
+/// 值整体脱敏的字段，例如 ws-opts.headers
+const SENSITIVE_MAPS: &[&str] = &["headers"];
+
+/// 允许列表中包含该字段时不处理 URL
+const URL_KEY: &str = "url";
+
+#[allow(clippy::expect_used)]
+static URL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\b([a-z][a-z0-9+.-]*://)([^/\s"'@]+@)?([^/\s"'?#]+)[^\s"']*"#)
+        .expect("invalid url pattern")
+});
+
+static STATE: Lazy<RwLock<RedactionState>> =
+    Lazy::new(|| RwLock::new(RedactionState::new(RedactionPolicy::default())));
+
+/// 脱敏策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionPolicy {
+    pub enabled: bool,           // 应用日志脱敏
+    pub redact_exports: bool,    // 订阅导出默认脱敏
+    pub allow_keys: Vec<String>, // 不脱敏的字段，例如 uuid、url
+    pub extra_keys: Vec<String>, // 额外需要脱敏的字段
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            redact_exports: false,
+            allow_keys: Vec::new(),
+            extra_keys: Vec::new(),
+        }
+    }
+}
+
+struct RedactionState {
+    policy: RedactionPolicy,
+    keys: Vec<String>,
+    credential_pattern: Option<Regex>,
+    redact_urls: bool,
+}
+
+impl RedactionState {
+    fn new(policy: RedactionPolicy) -> Self {
+        let allow: Vec<String> = policy
+            .allow_keys
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .collect();
+        let keys: Vec<String> = SENSITIVE_KEYS
+            .iter()
+            .map(|k| k.to_string())
+            .chain(policy.extra_keys.iter().map(|k| k.trim().to_lowercase()))
+            .filter(|k| !k.is_empty() && !allow.contains(k))
+            .collect();
+
+        // 匹配 key=value、key: value 以及 JSON 中的 "key":"value"
+        let credential_pattern = (!keys.is_empty())
+            .then(|| {
+                let alternatives: Vec<String> = keys.iter().map(|k| regex::escape(k)).collect();
+                Regex::new(&format!(
+                    r#"(?i)\b({})(["']?\s*[=:]\s*["']?)[^\s"',&]+"#,
+                    alternatives.join("|")
+                ))
+                .ok()
+            })
+            .flatten();
+
+        Self {
+            redact_urls: !allow.iter().any(|k| k == URL_KEY),
+            keys,
+            credential_pattern,
+            policy,
+        }
+    }
+
+    fn is_sensitive(&self, key: &str) -> bool {
+        self.keys.iter().any(|k| key.contains(k.as_str()))
+    }
+
+    fn redact_text(&self, text: &str) -> String {
+        let text = if self.redact_urls && text.contains("://") {
+            URL_PATTERN
+                .replace_all(text, |caps: &regex::Captures| {
+                    let full = &caps[0];
+                    let prefix = format!("{}{}", &caps[1], &caps[3]);
+                    if caps.get(2).is_none() && full.len() == prefix.len() {
+                        full.to_string()
+                    } else {
+                        format!("{prefix}/{MASK}")
+                    }
+                })
+                .to_string()
+        } else {
+            text.to_string()
+        };
+        match &self.credential_pattern {
+            Some(pattern) => pattern
+                .replace_all(&text, format!("${{1}}${{2}}{MASK}").as_str())
+                .to_string(),
+            None => text,
+        }
+    }
+
+    fn redact_value(&self, value: Value) -> Value {
+        match value {
+            Value::Mapping(map) => Value::Mapping(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let name = key.as_str().unwrap_or_default().to_lowercase();
+                        let value = if SENSITIVE_MAPS.contains(&name.as_str()) {
+                            mask_all(value)
+                        } else if self.is_sensitive(&name) {
+                            match value {
+                                Value::Mapping(_) => self.redact_value(value),
+                                other => mask_all(other),
+                            }
+                        } else {
+                            self.redact_value(value)
+                        };
+                        (key, value)
+                    })
+                    .collect::<Mapping>(),
+            ),
+            Value::Sequence(seq) => {
+                Value::Sequence(seq.into_iter().map(|v| self.redact_value(v)).collect())
+            }
+            Value::String(s) => Value::String(self.redact_text(&s)),
+            other => other,
+        }
+    }
+}
+
+fn mask_all(value: Value) -> Value {
+    match value {
+        Value::Mapping(map) => Value::Mapping(
+            map.into_iter()
+                .map(|(key, value)| (key, mask_all(value)))
+                .collect(),
+        ),
+        Value::Sequence(seq) => Value::Sequence(seq.into_iter().map(mask_all).collect()),
+        Value::Null => Value::Null,
+        _ => Value::String(MASK.to_string()),
+    }
+}
+
+/// 当前脱敏策略
+pub fn policy() -> RedactionPolicy {
+    STATE.read().policy.clone()
+}
+
+/// 更新并保存脱敏策略
+pub fn set_policy(policy: RedactionPolicy) -> Result<()> {
+    fs::write(policy_path()?, serde_json::to_string_pretty(&policy)?)?;
+    *STATE.write() = RedactionState::new(policy);
+    Ok(())
+}
+
+/// 启动时加载已保存的脱敏策略
+pub fn load_policy() -> Result<()> {
+    let path = policy_path()?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let policy: RedactionPolicy = serde_json::from_str(&fs::read_to_string(path)?)?;
+    *STATE.write() = RedactionState::new(policy);
+    Ok(())
+}
+
+/// 文本脱敏：URL 只保留协议和主机，隐藏 key=value 形式的凭据
+pub fn redact_text(text: &str) -> String {
+    STATE.read().redact_text(text)
+}
+
+/// 递归脱敏 YAML 中的凭据字段与订阅地址
+pub fn redact_yaml(value: Value) -> Value {
+    STATE.read().redact_value(value)
+}
+
+/// 递归脱敏 JSON 中的凭据字段与订阅地址
+pub fn redact_json(value: serde_json::Value) -> serde_json::Value {
+    let Ok(yaml) = serde_yaml_ng::to_value(&value) else {
+        return value;
+    };
+    serde_json::to_value(redact_yaml(yaml)).unwrap_or(value)
+}
+
+/// 日志脱敏，由 logging! 宏调用，策略关闭时原样返回
+pub fn redact_log(message: String) -> String {
+    let state = STATE.read();
+    if state.policy.enabled {
+        state.redact_text(&message)
+    } else {
+        message
+    }
+}
+
+fn policy_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(REDACTION_POLICY_FILE))
+}
@@ -3,7 +3,10 @@ use tauri::AppHandle;
 
 use crate::{
     config::Config,
-    core::{CoreManager, Timer, handle, hotkey::Hotkey, sysopt, tray::Tray},
+    core::{
+        CoreManager, Timer, handle, hotkey::Hotkey, sysopt, system_events::SystemEventMonitor,
+        tray::Tray,
+    },
     logging, logging_error,
     module::lightweight::auto_lightweight_mode_init,
     process::AsyncHandler,
@@ -29,6 +32,8 @@ pub fn resolve_setup_sync() {
 
 pub fn resolve_setup_async() {
     let start_time = std::time::Instant::now();
+    init_redaction_policy();
+    init_app_lock();
     logging!(
         info,
         Type::Setup,
@@ -46,10 +51,25 @@ pub fn resolve_setup_async() {
         );
 
         init_timer().await;
+        init_system_events();
+        init_profile_watcher();
+        init_job_manager();
+        init_task_scheduler();
+        init_network_rules();
+        init_load_balance_rebuilder();
+        init_backup_scheduler();
+        init_subscription_index_scheduler();
+        init_log_pruner();
+        init_resource_monitor();
+        init_offline_monitor();
+        init_log_pipeline();
+        init_connection_monitor();
+        init_api_server();
         init_auto_lightweight_mode().await;
 
         init_verge_config().await;
         init_core_manager().await;
+        init_captive_portal().await;
 
         init_system_proxy().await;
         AsyncHandler::spawn_blocking(|| {
@@ -68,6 +88,7 @@ pub fn resolve_setup_async() {
                 );
             }
             init_tray().await;
+            init_tray_stats();
             refresh_tray_menu().await;
         };
         futures::join!(init_window(), tray_and_refresh,);
@@ -155,6 +176,117 @@ pub(super) async fn init_timer() {
     logging_error!(Type::Setup, true, Timer::global().init().await);
 }
 
+pub(super) fn init_system_events() {
+    logging!(
+        info,
+        Type::Setup,
+        true,
+        "Initializing system event monitor..."
+    );
+    SystemEventMonitor::global().start();
+}
+
+pub(super) fn init_profile_watcher() {
+    logging!(info, Type::Setup, true, "Initializing profile watcher...");
+    crate::core::profile_watcher::ProfileWatcher::global().start();
+}
+
+pub(super) fn init_job_manager() {
+    logging!(info, Type::Setup, true, "Initializing job manager...");
+    crate::cmd::job_manager::init_job_manager();
+}
+
+pub(super) fn init_task_scheduler() {
+    logging!(info, Type::Setup, true, "Initializing task scheduler...");
+    crate::cmd::task_manager::init_task_scheduler();
+}
+
+pub(super) fn init_network_rules() {
+    logging!(info, Type::Setup, true, "Initializing network rules...");
+    crate::cmd::network_rules::init_network_rules();
+}
+
+pub(super) fn init_load_balance_rebuilder() {
+    logging!(
+        info,
+        Type::Setup,
+        true,
+        "Initializing load balance rebuilder..."
+    );
+    crate::cmd::load_balance::init_load_balance_rebuilder();
+}
+
+pub(super) fn init_backup_scheduler() {
+    logging!(info, Type::Setup, true, "Initializing backup scheduler...");
+    crate::cmd::backup_schedule::init_backup_scheduler();
+}
+
+pub(super) fn init_subscription_index_scheduler() {
+    logging!(
+        info,
+        Type::Setup,
+        true,
+        "Initializing subscription index scheduler..."
+    );
+    crate::cmd::subscription_index::init_subscription_index_scheduler();
+}
+
+pub(super) fn init_resource_monitor() {
+    logging!(info, Type::Setup, true, "Initializing resource monitor...");
+    crate::cmd::resource_monitor::init_resource_monitor();
+}
+
+pub(super) async fn init_captive_portal() {
+    logging!(info, Type::Setup, true, "Resuming captive portal bypass...");
+    crate::core::captive_portal::resume_pending_bypass().await;
+}
+
+pub(super) fn init_offline_monitor() {
+    logging!(info, Type::Setup, true, "Initializing offline monitor...");
+    crate::module::offline::init_offline_monitor();
+}
+
+pub(super) fn init_log_pruner() {
+    logging!(info, Type::Setup, true, "Initializing log pruner...");
+    init::init_log_pruner();
+}
+
+pub(super) fn init_redaction_policy() {
+    logging_error!(Type::Setup, true, crate::utils::redact::load_policy());
+}
+
+pub(super) fn init_app_lock() {
+    logging_error!(
+        Type::Setup,
+        true,
+        crate::module::app_lock::AppLock::global().init()
+    );
+}
+
+pub(super) fn init_log_pipeline() {
+    logging!(info, Type::Setup, true, "Initializing log pipeline...");
+    crate::ipc::log_pipeline::start_log_pipeline();
+}
+
+pub(super) fn init_connection_monitor() {
+    logging!(
+        info,
+        Type::Setup,
+        true,
+        "Initializing connection monitor..."
+    );
+    crate::ipc::connections::init_connection_monitor();
+}
+
+pub(super) fn init_api_server() {
+    logging!(info, Type::Setup, true, "Initializing API server...");
+    logging_error!(
+        Type::Setup,
+        true,
+        crate::utils::server::api::ApiServer::global().init()
+    );
+}
+
 pub(super) async fn init_hotkey() {
     logging!(info, Type::Setup, true, "Initializing hotkey...");
     logging_error!(Type::Setup, true, Hotkey::global().init().await);
@@ -185,6 +317,11 @@ pub(super) async fn init_tray() {
     logging_error!(Type::Setup, true, Tray::global().init().await);
 }
 
+pub(super) fn init_tray_stats() {
+    logging!(info, Type::Setup, true, "Starting tray live stats...");
+    crate::core::tray::stats::start_tray_stats();
+}
+
 pub(super) async fn init_verge_config() {
     logging!(
         info,
@@ -1,8 +1,106 @@
 use anyhow::{Result, bail};
 use percent_encoding::percent_decode_str;
 use tauri::Url;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
-use crate::{config::PrfItem, core::handle, logging, utils::logging::Type, wrap_err};
+use crate::{
+    cmd, config::PrfItem, core::handle, feat, ipc::IpcManager, logging, module::app_lock::AppLock,
+    process::AsyncHandler, utils::logging::Type, wrap_err,
+};
+
+/// 应用自身注册的 URL Scheme
+const APP_SCHEME: &str = "liebesu-clash";
+const INSTALL_CONFIG: &str = "install-config";
+const CLASH_MODES: &[&str] = &["rule", "global", "direct"];
+
+/// 深度链接操作，例如 `liebesu-clash://switch-mode/rule`
+#[derive(Debug, PartialEq, Eq)]
+enum DeepLinkAction {
+    SwitchMode(String),
+    SelectNode { group: String, name: String },
+    RunSpeedTest,
+    ToggleSystemProxy,
+}
+
+impl DeepLinkAction {
+    fn parse(url: &Url) -> Result<Self> {
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+                .filter(|v| !v.is_empty())
+        };
+
+        match url.host_str().unwrap_or_default() {
+            "switch-mode" => {
+                let mode = url.path().trim_matches('/').to_lowercase();
+                if !CLASH_MODES.contains(&mode.as_str()) {
+                    bail!("unsupported mode: {mode}");
+                }
+                Ok(Self::SwitchMode(mode))
+            }
+            "select-node" => match (query("group"), query("name")) {
+                (Some(group), Some(name)) => Ok(Self::SelectNode { group, name }),
+                _ => bail!("select-node requires group and name"),
+            },
+            "run-speed-test" => Ok(Self::RunSpeedTest),
+            "toggle-system-proxy" => Ok(Self::ToggleSystemProxy),
+            other => bail!("unknown deep link action: {other}"),
+        }
+    }
+
+    /// 所有外部触发的操作都需要用户确认，测速也会产生大量外部请求
+    fn confirm_message(&self) -> String {
+        match self {
+            Self::SwitchMode(mode) => {
+                format!("外部链接请求将代理模式切换为 {mode}，是否允许？")
+            }
+            Self::SelectNode { group, name } => {
+                format!("外部链接请求将代理组 {group} 切换到节点 {name}，是否允许？")
+            }
+            Self::RunSpeedTest => "外部链接请求对所有节点进行测速，是否允许？".to_string(),
+            Self::ToggleSystemProxy => "外部链接请求切换系统代理开关，是否允许？".to_string(),
+        }
+    }
+
+    async fn execute(self) -> Result<String> {
+        match self {
+            Self::SwitchMode(mode) => {
+                feat::change_clash_mode(mode.clone()).await;
+                Ok(format!("已切换到 {mode} 模式"))
+            }
+            Self::SelectNode { group, name } => {
+                IpcManager::global()
+                    .update_proxy(&group, &name)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{e}"))?;
+                handle::Handle::refresh_clash();
+                Ok(format!("{group} 已切换到 {name}"))
+            }
+            Self::RunSpeedTest => {
+                let Some(app_handle) = handle::Handle::global().app_handle() else {
+                    bail!("app handle not available");
+                };
+                AsyncHandler::spawn(move || async move {
+                    if let Err(e) = cmd::start_global_speed_test(app_handle, None).await {
+                        logging!(
+                            warn,
+                            Type::Setup,
+                            true,
+                            "deep link speed test failed: {}",
+                            e
+                        );
+                    }
+                });
+                Ok("已开始全局测速".to_string())
+            }
+            Self::ToggleSystemProxy => {
+                feat::toggle_system_proxy().await;
+                Ok("已切换系统代理".to_string())
+            }
+        }
+    }
+}
 
 pub(super) async fn resolve_scheme(param: String) -> Result<()> {
     log::info!(target:"app", "received deep link: {param}");
@@ -23,52 +121,122 @@ pub(super) async fn resolve_scheme(param: String) -> Result<()> {
         }
     };
 
-    if link_parsed.scheme() == "clash" || link_parsed.scheme() == "clash-verge" {
-        let name = link_parsed
-            .query_pairs()
-            .find(|(key, _)| key == "name")
-            .map(|(_, value)| value.into_owned());
-
-        let url_param = if let Some(query) = link_parsed.query() {
-            let prefix = "url=";
-            if let Some(pos) = query.find(prefix) {
-                let raw_url = &query[pos + prefix.len()..];
-                Some(percent_decode_str(raw_url).decode_utf8_lossy().to_string())
-            } else {
-                None
-            }
+    match link_parsed.scheme() {
+        "clash" | "clash-verge" => import_profile(&link_parsed).await,
+        APP_SCHEME if link_parsed.host_str() == Some(INSTALL_CONFIG) => {
+            import_profile(&link_parsed).await
+        }
+        APP_SCHEME => resolve_action(&link_parsed).await,
+        _ => Ok(()),
+    }
+}
+
+async fn resolve_action(link_parsed: &Url) -> Result<()> {
+    let action = match DeepLinkAction::parse(link_parsed) {
+        Ok(action) => action,
+        Err(e) => {
+            handle::Handle::notice_message("deep_link::error", e.to_string());
+            return Err(e);
+        }
+    };
+    if AppLock::global().is_locked() {
+        handle::Handle::notice_message("deep_link::error", "应用已锁定");
+        bail!("app is locked, ignore deep link action {:?}", action);
+    }
+    if !confirm(action.confirm_message()).await {
+        logging!(
+            info,
+            Type::Setup,
+            true,
+            "deep link action rejected: {:?}",
+            action
+        );
+        return Ok(());
+    }
+
+    logging!(
+        info,
+        Type::Setup,
+        true,
+        "executing deep link action: {:?}",
+        action
+    );
+    match action.execute().await {
+        Ok(message) => handle::Handle::notice_message("deep_link::ok", message),
+        Err(e) => handle::Handle::notice_message("deep_link::error", e.to_string()),
+    }
+    Ok(())
+}
+
+/// 弹出确认对话框，没有 AppHandle 时视为拒绝
+async fn confirm(message: String) -> bool {
+    let Some(app_handle) = handle::Handle::global().app_handle() else {
+        return false;
+    };
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app_handle
+        .dialog()
+        .message(message)
+        .title("Liebesu_Clash")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancel)
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+async fn import_profile(link_parsed: &Url) -> Result<()> {
+    let name = link_parsed
+        .query_pairs()
+        .find(|(key, _)| key == "name")
+        .map(|(_, value)| value.into_owned());
+
+    let url_param = if let Some(query) = link_parsed.query() {
+        let prefix = "url=";
+        if let Some(pos) = query.find(prefix) {
+            let raw_url = &query[pos + prefix.len()..];
+            Some(percent_decode_str(raw_url).decode_utf8_lossy().to_string())
         } else {
             None
-        };
+        }
+    } else {
+        None
+    };
 
-        match url_param {
-            Some(url) => {
-                log::info!(target:"app", "decoded subscription url: {url}");
-                // create_window(false).await;
-                match PrfItem::from_url(url.as_ref(), name, None, None).await {
-                    Ok(item) => {
-                        let uid = match item.uid.clone() {
-                            Some(uid) => uid,
-                            None => {
-                                logging!(error, Type::Config, true, "Profile item missing UID");
-                                handle::Handle::notice_message(
-                                    "import_sub_url::error",
-                                    "Profile item missing UID".to_string(),
-                                );
-                                return Ok(());
-                            }
-                        };
-                        let result = crate::config::profiles::profiles_append_item_safe(item).await;
-                        let _ = wrap_err!(result);
-                        handle::Handle::notice_message("import_sub_url::ok", uid);
-                    }
-                    Err(e) => {
-                        handle::Handle::notice_message("import_sub_url::error", e.to_string());
-                    }
+    match url_param {
+        Some(url) => {
+            log::info!(target:"app", "decoded subscription url: {url}");
+            // create_window(false).await;
+            match PrfItem::from_url(url.as_ref(), name, None, None).await {
+                Ok(item) => {
+                    let uid = match item.uid.clone() {
+                        Some(uid) => uid,
+                        None => {
+                            logging!(error, Type::Config, true, "Profile item missing UID");
+                            handle::Handle::notice_message(
+                                "import_sub_url::error",
+                                "Profile item missing UID".to_string(),
+                            );
+                            return Ok(());
+                        }
+                    };
+                    crate::cmd::subscription_usage::record_subscription_usage(
+                        &uid,
+                        item.name.as_deref().unwrap_or(&uid),
+                        item.extra,
+                    )
+                    .await;
+                    let result = crate::config::profiles::profiles_append_item_safe(item).await;
+                    let _ = wrap_err!(result);
+                    handle::Handle::notice_message("import_sub_url::ok", uid);
+                }
+                Err(e) => {
+                    handle::Handle::notice_message("import_sub_url::error", e.to_string());
                 }
             }
-            None => bail!("failed to get profile url"),
         }
+        None => bail!("failed to get profile url"),
     }
 
     Ok(())
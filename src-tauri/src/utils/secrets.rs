@@ -0,0 +1,249 @@
+use anyhow::{Result, bail};
+
+use crate::{
+    logging,
+    utils::{dirs::APP_ID, logging::Type},
+};
+
+/// 配置中保存的钥匙串引用前缀，例如 `keychain:webdav_password`
+pub const KEYCHAIN_PREFIX: &str = "keychain:";
+
+/// 钥匙串中的条目名称
+pub const WEBDAV_PASSWORD: &str = "webdav_password";
+pub const S3_SECRET_ACCESS_KEY: &str = "s3_secret_access_key";
+pub const MQTT_PASSWORD: &str = "mqtt_password";
+
+/// 是否为钥匙串引用
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(KEYCHAIN_PREFIX)
+}
+
+/// 保存凭据到系统钥匙串，返回写入配置的引用
+pub fn store_secret(account: &str, secret: &str) -> Result<String> {
+    backend::store(account, secret)?;
+    Ok(format!("{KEYCHAIN_PREFIX}{account}"))
+}
+
+/// 保存凭据到钥匙串并返回引用，钥匙串不可用时保留原值
+pub fn protect_secret(account: &str, secret: String) -> String {
+    if secret.is_empty() || is_reference(&secret) {
+        return secret;
+    }
+    match store_secret(account, &secret) {
+        Ok(reference) => reference,
+        Err(e) => {
+            logging!(
+                warn,
+                Type::Config,
+                true,
+                "[钥匙串] 保存 {} 失败，使用配置文件存储: {}",
+                account,
+                e
+            );
+            secret
+        }
+    }
+}
+
+/// 从系统钥匙串读取凭据，不存在时返回 None
+pub fn read_secret(account: &str) -> Result<Option<String>> {
+    backend::read(account)
+}
+
+/// 删除系统钥匙串中的凭据
+pub fn delete_secret(account: &str) -> Result<()> {
+    backend::delete(account)
+}
+
+/// 解析配置中的凭据：钥匙串引用从钥匙串读取，其余视为旧版明文原样返回
+pub fn resolve_secret(value: &str) -> Result<String> {
+    match value.strip_prefix(KEYCHAIN_PREFIX) {
+        Some(account) => match read_secret(account)? {
+            Some(secret) => Ok(secret),
+            None => bail!("钥匙串中找不到凭据: {}", account),
+        },
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Windows 凭据管理器
+#[cfg(target_os = "windows")]
+mod backend {
+    use super::APP_ID;
+    use anyhow::{Result, bail};
+    use std::{mem, ptr};
+    use winapi::{
+        shared::winerror::ERROR_NOT_FOUND,
+        um::{
+            errhandlingapi::GetLastError,
+            wincred::{
+                CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC, CREDENTIALW, CredDeleteW, CredFree,
+                CredReadW, CredWriteW, PCREDENTIALW,
+            },
+        },
+    };
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn target(account: &str) -> Vec<u16> {
+        wide(&format!("{APP_ID}/{account}"))
+    }
+
+    pub fn store(account: &str, secret: &str) -> Result<()> {
+        let mut target = target(account);
+        let mut user = wide(account);
+        let mut blob = secret.as_bytes().to_vec();
+
+        unsafe {
+            let mut credential: CREDENTIALW = mem::zeroed();
+            credential.Type = CRED_TYPE_GENERIC;
+            credential.TargetName = target.as_mut_ptr();
+            credential.UserName = user.as_mut_ptr();
+            credential.CredentialBlobSize = blob.len() as u32;
+            credential.CredentialBlob = blob.as_mut_ptr();
+            credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+
+            if CredWriteW(&mut credential, 0) == 0 {
+                bail!("写入凭据管理器失败: {}", GetLastError());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read(account: &str) -> Result<Option<String>> {
+        let target = target(account);
+        unsafe {
+            let mut credential: PCREDENTIALW = ptr::null_mut();
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                let error = GetLastError();
+                if error == ERROR_NOT_FOUND {
+                    return Ok(None);
+                }
+                bail!("读取凭据管理器失败: {}", error);
+            }
+
+            let blob = std::slice::from_raw_parts(
+                (*credential).CredentialBlob,
+                (*credential).CredentialBlobSize as usize,
+            );
+            let secret = String::from_utf8_lossy(blob).to_string();
+            CredFree(credential as *mut _);
+            Ok(Some(secret))
+        }
+    }
+
+    pub fn delete(account: &str) -> Result<()> {
+        let target = target(account);
+        unsafe {
+            if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == 0 {
+                let error = GetLastError();
+                if error != ERROR_NOT_FOUND {
+                    bail!("删除凭据失败: {}", error);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// macOS 钥匙串，通过 Security 框架访问，凭据不经过进程参数
+#[cfg(target_os = "macos")]
+mod backend {
+    use super::APP_ID;
+    use anyhow::{Result, bail};
+    use security_framework::passwords::{
+        delete_generic_password, get_generic_password, set_generic_password,
+    };
+
+    /// errSecItemNotFound：钥匙串中没有该条目
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn store(account: &str, secret: &str) -> Result<()> {
+        if let Err(e) = set_generic_password(APP_ID, account, secret.as_bytes()) {
+            bail!("写入钥匙串失败: {}", e);
+        }
+        Ok(())
+    }
+
+    pub fn read(account: &str) -> Result<Option<String>> {
+        match get_generic_password(APP_ID, account) {
+            Ok(secret) => Ok(Some(String::from_utf8_lossy(&secret).to_string())),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => bail!("读取钥匙串失败: {}", e),
+        }
+    }
+
+    pub fn delete(account: &str) -> Result<()> {
+        match delete_generic_password(APP_ID, account) {
+            Ok(()) => Ok(()),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(()),
+            Err(e) => bail!("删除钥匙串条目失败: {}", e),
+        }
+    }
+}
+
+/// Linux Secret Service（libsecret），通过 secret-tool 访问
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod backend {
+    use super::APP_ID;
+    use anyhow::{Context, Result, bail};
+    use std::{
+        io::Write,
+        process::{Command, Stdio},
+    };
+
+    pub fn store(account: &str, secret: &str) -> Result<()> {
+        // 密码通过标准输入传递，避免出现在进程参数中
+        let mut child = Command::new("secret-tool")
+            .args(["store", "--label", &format!("{APP_ID} {account}")])
+            .args(["service", APP_ID, "account", account])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("无法运行 secret-tool，请安装 libsecret")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(secret.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "写入 Secret Service 失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn read(account: &str) -> Result<Option<String>> {
+        let output = Command::new("secret-tool")
+            .args(["lookup", "service", APP_ID, "account", account])
+            .output()
+            .context("无法运行 secret-tool，请安装 libsecret")?;
+        // 找不到条目时退出码为 1 且没有输出
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.trim().is_empty() {
+                return Ok(None);
+            }
+            bail!("读取 Secret Service 失败: {}", stderr.trim());
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+    }
+
+    pub fn delete(account: &str) -> Result<()> {
+        let output = Command::new("secret-tool")
+            .args(["clear", "service", APP_ID, "account", account])
+            .output()
+            .context("无法运行 secret-tool，请安装 libsecret")?;
+        if !output.status.success() && !output.stderr.is_empty() {
+            bail!(
+                "删除 Secret Service 条目失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
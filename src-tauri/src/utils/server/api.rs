@@ -0,0 +1,418 @@
+use crate::{
+    cmd::{
+        self, SpeedTestConfig,
+        job_manager::{JobClass, JobKind, JobPriority, run_job_with},
+    },
+    config::Config,
+    core::handle,
+    feat,
+    ipc::IpcManager,
+    logging,
+    module::app_lock::AppLock,
+    process::AsyncHandler,
+    singleton_with_logging,
+    utils::{dirs, logging::Type},
+};
+use anyhow::{Context, Result, bail};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{
+    convert::Infallible,
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tauri::async_runtime::JoinHandle;
+use warp::{Filter, Rejection, Reply, http::StatusCode};
+
+const API_SERVER_FILE: &str = "api_server.json";
+const DEFAULT_API_PORT: u16 = 33332;
+const TOKEN_LENGTH: usize = 24;
+const CLASH_MODES: &[&str] = &["rule", "global", "direct"];
+
+static SPEED_TEST_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 本地 REST API 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String, // 请求需携带 Authorization: Bearer <token>
+}
+
+impl Default for ApiServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_API_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+/// 本地 REST API 服务，仅监听 127.0.0.1，供 Raycast、Stream Deck 及脚本调用
+pub struct ApiServer {
+    config: Mutex<ApiServerConfig>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+singleton_with_logging!(ApiServer, INSTANCE, "ApiServer");
+
+impl ApiServer {
+    fn new() -> Self {
+        Self {
+            config: Mutex::new(ApiServerConfig::default()),
+            task: Mutex::new(None),
+        }
+    }
+
+    /// 加载配置，已启用时启动服务
+    pub fn init(&self) -> Result<()> {
+        let config = load_config()?;
+        *self.config.lock() = config.clone();
+        if config.enabled {
+            self.start(&config)?;
+        }
+        Ok(())
+    }
+
+    pub fn config(&self) -> ApiServerConfig {
+        self.config.lock().clone()
+    }
+
+    /// 更新配置并按需重启服务，首次启用或 regenerate_token 时生成新令牌
+    pub async fn update(
+        &self,
+        enabled: bool,
+        port: Option<u16>,
+        regenerate_token: bool,
+    ) -> Result<ApiServerConfig> {
+        let mut config = self.config();
+        config.enabled = enabled;
+        if let Some(port) = port {
+            if port == 0 {
+                bail!("端口无效");
+            }
+            config.port = port;
+        }
+        if regenerate_token || config.token.is_empty() {
+            config.token = generate_token()?;
+        }
+
+        self.stop().await;
+        if config.enabled {
+            self.start(&config)?;
+        }
+        save_config(&config)?;
+        *self.config.lock() = config.clone();
+        Ok(config)
+    }
+
+    fn start(&self, config: &ApiServerConfig) -> Result<()> {
+        if config.token.is_empty() {
+            bail!("未设置 API 令牌");
+        }
+
+        // 先同步绑定端口，占用等错误直接返回给调用方，而不是在后台任务中 panic
+        let port = config.port;
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .inspect_err(|e| {
+                logging!(
+                    error,
+                    Type::System,
+                    true,
+                    "[API] 绑定端口 {} 失败: {}",
+                    port,
+                    e
+                );
+            })
+            .with_context(|| format!("端口 {port} 绑定失败"))?;
+
+        let token = config.token.clone();
+        let task = AsyncHandler::spawn(move || async move {
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    logging!(error, Type::System, true, "[API] 监听端口失败: {}", e);
+                    return;
+                }
+            };
+            warp::serve(routes(token).recover(handle_rejection))
+                .incoming(listener)
+                .run()
+                .await;
+        });
+        *self.task.lock() = Some(task);
+        logging!(
+            info,
+            Type::System,
+            true,
+            "[API] 本地 API 已启动，端口 {}",
+            port
+        );
+        Ok(())
+    }
+
+    /// 中止服务并等待任务退出，确保端口已释放后再重新绑定
+    async fn stop(&self) {
+        let task = self.task.lock().take();
+        if let Some(task) = task {
+            task.abort();
+            let _ = task.await;
+            logging!(info, Type::System, true, "[API] 本地 API 已停止");
+        }
+    }
+}
+
+/// API 错误，转换为 JSON 响应
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl warp::reject::Reject for ApiError {}
+
+fn reject(status: StatusCode, message: impl Into<String>) -> Rejection {
+    warp::reject::custom(ApiError(status, message.into()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ModeBody {
+    mode: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProxyBody {
+    group: String,
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SpeedTestBody {
+    max_nodes: Option<usize>,
+    profile_uids: Option<Vec<String>>,
+}
+
+fn routes(token: String) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let api = warp::path("api")
+        .and(warp::path("v1"))
+        .and(authorize(token));
+
+    let list_profiles = warp::path!("profiles")
+        .and(warp::get())
+        .and_then(|| async { respond(list_profiles().await) });
+    let update_profile = warp::path!("profiles" / String / "update")
+        .and(warp::post())
+        .and_then(|uid: String| async move { respond(update_profile(uid).await) });
+    let get_mode = warp::path!("mode")
+        .and(warp::get())
+        .and_then(|| async { respond(get_mode().await) });
+    let set_mode = warp::path!("mode")
+        .and(warp::put())
+        .and(warp::body::json())
+        .and_then(|body: ModeBody| async move { respond(set_mode(body.mode).await) });
+    let switch_proxy = warp::path!("proxy")
+        .and(warp::put())
+        .and(warp::body::json())
+        .and_then(|body: ProxyBody| async move { respond(switch_proxy(body).await) });
+    let start_speed_test = warp::path!("speed-test")
+        .and(warp::post())
+        .and(
+            warp::body::json()
+                .or(warp::any().map(SpeedTestBody::default))
+                .unify(),
+        )
+        .and_then(|body: SpeedTestBody| async move { respond(start_speed_test(body)) });
+    let speed_test_result = warp::path!("speed-test")
+        .and(warp::get())
+        .and_then(|| async { respond(speed_test_result()) });
+
+    api.and(
+        list_profiles
+            .or(update_profile)
+            .or(get_mode)
+            .or(set_mode)
+            .or(switch_proxy)
+            .or(start_speed_test)
+            .or(speed_test_result),
+    )
+}
+
+/// 校验 Bearer 令牌，应用锁定时拒绝所有请求
+fn authorize(token: String) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let token = token.clone();
+            async move {
+                let provided = header
+                    .as_deref()
+                    .and_then(|h| h.strip_prefix("Bearer "))
+                    .unwrap_or_default();
+                if !constant_time_eq(provided.as_bytes(), token.as_bytes()) {
+                    return Err(reject(StatusCode::UNAUTHORIZED, "令牌无效"));
+                }
+                if AppLock::global().is_locked() {
+                    return Err(reject(StatusCode::LOCKED, "应用已锁定"));
+                }
+                Ok(())
+            }
+        })
+        .untuple_one()
+}
+
+fn respond(result: Result<Value, String>) -> Result<warp::reply::Response, Rejection> {
+    match result {
+        Ok(data) => Ok(warp::reply::json(&data).into_response()),
+        Err(e) => Err(reject(StatusCode::BAD_REQUEST, e)),
+    }
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let (status, message) = if let Some(ApiError(status, message)) = rejection.find() {
+        (*status, message.clone())
+    } else if rejection.is_not_found() {
+        (StatusCode::NOT_FOUND, "接口不存在".to_string())
+    } else if rejection.find::<warp::reject::MethodNotAllowed>().is_some() {
+        (StatusCode::METHOD_NOT_ALLOWED, "请求方法不支持".to_string())
+    } else {
+        (StatusCode::BAD_REQUEST, format!("{rejection:?}"))
+    };
+    Ok(warp::reply::with_status(
+        warp::reply::json(&json!({ "error": message })),
+        status,
+    ))
+}
+
+// ===== 接口实现 =====
+
+async fn list_profiles() -> Result<Value, String> {
+    let profiles = Config::profiles().await;
+    let profiles = profiles.latest_ref();
+    let items: Vec<Value> = profiles
+        .items
+        .iter()
+        .flatten()
+        .filter(|item| matches!(item.itype.as_deref(), Some("remote" | "local")))
+        .map(|item| {
+            json!({
+                "uid": item.uid,
+                "name": item.name,
+                "type": item.itype,
+                "updated": item.updated,
+                "current": item.uid.is_some() && item.uid == profiles.current,
+            })
+        })
+        .collect();
+    Ok(json!({ "current": profiles.current, "items": items }))
+}
+
+async fn update_profile(uid: String) -> Result<Value, String> {
+    feat::update_profile(uid.clone(), None, Some(true))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(json!({ "uid": uid }))
+}
+
+async fn get_mode() -> Result<Value, String> {
+    let clash = Config::clash().await;
+    let mode = clash
+        .latest_ref()
+        .0
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("rule")
+        .to_string();
+    Ok(json!({ "mode": mode }))
+}
+
+async fn set_mode(mode: String) -> Result<Value, String> {
+    let mode = mode.to_lowercase();
+    if !CLASH_MODES.contains(&mode.as_str()) {
+        return Err(format!("不支持的模式: {mode}"));
+    }
+    cmd::patch_clash_mode(mode.clone()).await?;
+    Ok(json!({ "mode": mode }))
+}
+
+async fn switch_proxy(body: ProxyBody) -> Result<Value, String> {
+    IpcManager::global()
+        .update_proxy(&body.group, &body.name)
+        .await
+        .map_err(|e| e.to_string())?;
+    handle::Handle::refresh_clash();
+    Ok(json!({ "group": body.group, "name": body.name }))
+}
+
+/// 后台启动测速，结果通过 GET /speed-test 查询
+fn start_speed_test(body: SpeedTestBody) -> Result<Value, String> {
+    if SPEED_TEST_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("测速正在进行中".to_string());
+    }
+    let config = SpeedTestConfig {
+        max_nodes: body.max_nodes,
+        profile_uids: body.profile_uids,
+        ..SpeedTestConfig::default()
+    };
+    AsyncHandler::spawn(move || async move {
+        let app_handle = handle::Handle::global().app_handle();
+        let result = run_job_with(
+            "Global speed test",
+            JobKind::SpeedTest,
+            JobClass::ClashApi,
+            JobPriority::Normal,
+            cmd::run_global_speed_test(app_handle, Some(config)),
+        )
+        .await;
+        if let Err(e) = result {
+            logging!(warn, Type::System, true, "[API] 测速失败: {}", e);
+        }
+        SPEED_TEST_RUNNING.store(false, Ordering::SeqCst);
+    });
+    Ok(json!({ "started": true }))
+}
+
+fn speed_test_result() -> Result<Value, String> {
+    let running = SPEED_TEST_RUNNING.load(Ordering::SeqCst);
+    let summary = cmd::latest_speed_test_summary().map(|summary| {
+        json!({
+            "total_nodes": summary.total_nodes,
+            "successful_tests": summary.successful_tests,
+            "failed_tests": summary.failed_tests,
+            "duration_seconds": summary.duration_seconds,
+            "best_node": summary.best_node,
+            "top_10_nodes": summary.top_10_nodes,
+        })
+    });
+    Ok(json!({ "running": running, "summary": summary }))
+}
+
+// ===== 内部实现函数 =====
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; TOKEN_LENGTH];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow::anyhow!("生成随机数失败: {}", e))?;
+    Ok(hex::encode(bytes))
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::app_home_dir()?.join(API_SERVER_FILE))
+}
+
+fn load_config() -> Result<ApiServerConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(ApiServerConfig::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_config(config: &ApiServerConfig) -> Result<()> {
+    fs::write(config_path()?, serde_json::to_string_pretty(config)?)?;
+    Ok(())
+}
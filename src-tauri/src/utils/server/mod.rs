@@ -1,14 +1,11 @@
 use super::resolve;
-use crate::{
-    config::{Config, DEFAULT_PAC, IVerge},
-    logging_error,
-    process::AsyncHandler,
-    utils::logging::Type,
-};
+use crate::{config::IVerge, logging_error, process::AsyncHandler, utils::logging::Type};
 use anyhow::{Result, bail};
 use port_scanner::local_port_available;
 use warp::Filter;
 
+pub mod api;
+
 #[derive(serde::Deserialize, Debug)]
 struct QueryParam {
     param: String,
@@ -23,7 +20,15 @@ pub async fn check_singleton() -> Result<()> {
             #[cfg(not(target_os = "macos"))]
             {
                 let param = argvs[1].as_str();
-                if param.starts_with("clash:") {
+                if ["clash:", "clash-verge:", "liebesu-clash:"]
+                    .iter()
+                    .any(|scheme| param.starts_with(scheme))
+                {
+                    // 编码后转发，避免深度链接中的 & 被当作查询参数分隔符
+                    let param = percent_encoding::utf8_percent_encode(
+                        param,
+                        percent_encoding::NON_ALPHANUMERIC,
+                    );
                     let _ = reqwest::get(format!(
                         "http://127.0.0.1:{port}/commands/scheme?param={param}"
                     ))
@@ -52,29 +57,15 @@ pub fn embed_server() {
             ))
         });
 
-        let verge_config = Config::verge().await;
-        let clash_config = Config::clash().await;
-
-        let content = verge_config
-            .latest_ref()
-            .pac_file_content
-            .clone()
-            .unwrap_or(DEFAULT_PAC.to_string());
-
-        let mixed_port = verge_config
-            .latest_ref()
-            .verge_mixed_port
-            .unwrap_or(clash_config.latest_ref().get_mixed_port());
-
-        // Clone the content and port for the closure to avoid borrowing issues
-        let pac_content = content.clone();
-        let pac_port = mixed_port;
-        let pac = warp::path!("commands" / "pac").map(move || {
-            let processed_content = pac_content.replace("%mixed-port%", &format!("{pac_port}"));
-            warp::http::Response::builder()
-                .header("Content-Type", "application/x-ns-proxy-autoconfig")
-                .body(processed_content)
-                .unwrap_or_default()
+        // 每次请求时渲染，保证脚本和端口修改后立即生效
+        let pac = warp::path!("commands" / "pac").and_then(|| async {
+            let processed_content = crate::cmd::pac::render_pac_script().await;
+            Ok::<_, warp::Rejection>(
+                warp::http::Response::builder()
+                    .header("Content-Type", "application/x-ns-proxy-autoconfig")
+                    .body(processed_content)
+                    .unwrap_or_default(),
+            )
         });
 
         // Use map instead of and_then to avoid Send issues
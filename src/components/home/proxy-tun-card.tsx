@@ -141,13 +141,11 @@ export const ProxyTunCard: FC = () => {
   );
 
   const { verge } = useVerge();
-  const { isAdminMode, isServiceMode } = useSystemState();
+  const { isTunAvailable } = useSystemState();
   const { actualState: systemProxyActualState } = useSystemProxyState();
 
   const { enable_tun_mode } = verge ?? {};
 
-  const isTunAvailable = isServiceMode || isAdminMode;
-
   const handleError = (err: Error) => {
     showNotice("error", err.message || err.toString());
   };
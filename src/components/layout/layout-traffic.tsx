@@ -5,13 +5,12 @@ import {
   ArrowUpwardRounded,
   MemoryRounded,
 } from "@mui/icons-material";
-import { useClashInfo } from "@/hooks/use-clash";
 import { useVerge } from "@/hooks/use-verge";
 import { TrafficGraph, type TrafficRef } from "./traffic-graph";
 import { useVisibility } from "@/hooks/use-visibility";
 import parseTraffic from "@/utils/parse-traffic";
 import { useTranslation } from "react-i18next";
-import { isDebugEnabled, gc, startTrafficService } from "@/services/cmds";
+import { isDebugEnabled, gc } from "@/services/cmds";
 import { useTrafficDataEnhanced } from "@/hooks/use-traffic-monitor";
 import { LightweightTrafficErrorBoundary } from "@/components/common/traffic-error-boundary";
 import useSWR from "swr";
@@ -31,7 +30,6 @@ export const LayoutTraffic = () => {
     console.debug("[Traffic][LayoutTraffic] 组件正在渲染");
   }
   const { t } = useTranslation();
-  const { clashInfo } = useClashInfo();
   const { verge } = useVerge();
 
   // whether hide traffic graph
@@ -43,22 +41,6 @@ export const LayoutTraffic = () => {
   // 使用增强版的统一流量数据Hook
   const { traffic, memory } = useTrafficDataEnhanced();
 
-  // 启动流量服务
-  useEffect(() => {
-    console.log(
-      "[Traffic][LayoutTraffic] useEffect 触发，clashInfo:",
-      clashInfo,
-      "pageVisible:",
-      pageVisible,
-    );
-
-    // 简化条件，只要组件挂载就尝试启动服务
-    console.log("[Traffic][LayoutTraffic] 开始启动流量服务");
-    startTrafficService().catch((error) => {
-      console.error("[Traffic][LayoutTraffic] 启动流量服务失败:", error);
-    });
-  }, []); // 移除依赖，只在组件挂载时启动一次
-
   // 监听数据变化，为图表添加数据点
   useEffect(() => {
     if (traffic?.raw && trafficRef.current) {
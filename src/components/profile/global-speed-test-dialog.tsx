@@ -68,6 +68,13 @@ interface SpeedTestResult {
   score: number;
   region?: string;
   traffic_info?: TrafficInfo;
+  ipv6_supported?: boolean;
+  target_latencies?: {
+    name: string;
+    url: string;
+    delay: number | null;
+    error: string | null;
+  }[];
 }
 
 interface GlobalSpeedTestProgress {
@@ -104,6 +111,8 @@ interface GlobalSpeedTestSummary {
   all_results: SpeedTestResult[]; // 所有节点结果（按评分排序）
   results_by_profile: Record<string, SpeedTestResult[]>;
   duration_seconds: number;
+  run_id?: string; // 测速批次，用于导出报告
+  finished_at?: number;
 }
 
 interface GlobalSpeedTestDialogProps {
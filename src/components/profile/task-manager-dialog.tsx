@@ -140,6 +140,12 @@ const TaskManagerDialog: React.FC<TaskManagerDialogProps> = ({
         return "健康检查";
       case "AutoCleanup":
         return "自动清理";
+      case "ExpiryReminder":
+        return "到期提醒";
+      case "TrafficReport":
+        return "月度流量报告";
+      case "GeoDataUpdate":
+        return "地理数据更新";
       case "Custom":
         return "自定义任务";
       default:
@@ -21,6 +21,7 @@ import {
   Tabs,
   Paper,
   LinearProgress,
+  TextField,
   Accordion,
   AccordionSummary,
   AccordionDetails,
@@ -40,6 +41,17 @@ import {
   Settings,
   NotificationsActive,
 } from "@mui/icons-material";
+import {
+  exportTrafficReportCsv,
+  generateTrafficReport,
+  getQuotaAlertThresholds,
+  getTrafficReport,
+  listTrafficReports,
+  setQuotaAlertThresholds,
+  type TrafficReport,
+  type TrafficUsageEntry,
+} from "@/services/cmds";
+import { showNotice } from "@/services/noticeService";
 
 // 模拟数据接口 - 等待后端集成
 interface _TrafficRecord {
@@ -109,7 +121,8 @@ interface TrafficAlert {
     | "ExpirationDate"
     | "HighUsage"
     | "SpeedDrop"
-    | "ConnectionIssue";
+    | "ConnectionIssue"
+    | "UsageSpike";
   message: string;
   threshold_value: number;
   current_value: number;
@@ -176,6 +189,20 @@ const TrafficStatsDialog: React.FC<TrafficStatsDialogProps> = ({
   const [alerts, setAlerts] = useState<TrafficAlert[]>([]);
   const [selectedSubscription, setSelectedSubscription] = useState<string>("");
 
+  // 月度报告状态
+  const [reportMonths, setReportMonths] = useState<string[]>([]);
+  const [reportMonth, setReportMonth] = useState<string>(() => {
+    const date = new Date();
+    date.setDate(1);
+    date.setMonth(date.getMonth() - 1);
+    return `${date.getFullYear()}-${String(date.getMonth() + 1).padStart(2, "0")}`;
+  });
+  const [report, setReport] = useState<TrafficReport | null>(null);
+  const [reportLoading, setReportLoading] = useState(false);
+
+  // 额度告警阈值（百分比，逗号分隔）
+  const [quotaThresholds, setQuotaThresholds] = useState("");
+
   // 格式化字节数
   const formatBytes = (bytes: number, decimals = 2) => {
     if (bytes === 0) return "0 B";
@@ -345,10 +372,91 @@ const TrafficStatsDialog: React.FC<TrafficStatsDialogProps> = ({
     }
   };
 
+  // 加载已保存的报告月份
+  const loadReportMonths = async () => {
+    try {
+      setReportMonths(await listTrafficReports());
+    } catch (error) {
+      console.error("加载流量报告列表失败:", error);
+    }
+  };
+
+  // 查看已保存的报告
+  const handleSelectReport = async (month: string) => {
+    setReportMonth(month);
+    setReportLoading(true);
+    try {
+      setReport(await getTrafficReport(month));
+    } catch (error) {
+      showNotice("error", "加载流量报告失败: " + error);
+    } finally {
+      setReportLoading(false);
+    }
+  };
+
+  // 生成（或重新生成）所选月份的报告
+  const handleGenerateReport = async () => {
+    setReportLoading(true);
+    try {
+      setReport(await generateTrafficReport(reportMonth));
+      await loadReportMonths();
+    } catch (error) {
+      showNotice("error", "生成流量报告失败: " + error);
+    } finally {
+      setReportLoading(false);
+    }
+  };
+
+  // 导出报告为 CSV
+  const handleExportReport = async () => {
+    if (!report) return;
+    try {
+      const path = await exportTrafficReportCsv(report.month);
+      if (path) {
+        showNotice("success", "已导出到 " + path);
+      }
+    } catch (error) {
+      showNotice("error", "导出流量报告失败: " + error);
+    }
+  };
+
+  // 加载额度告警阈值
+  const loadQuotaThresholds = async () => {
+    try {
+      const thresholds = await getQuotaAlertThresholds();
+      setQuotaThresholds(
+        thresholds.map((t) => Math.round(t * 1000) / 10).join(", "),
+      );
+    } catch (error) {
+      console.error("加载额度告警阈值失败:", error);
+    }
+  };
+
+  // 保存额度告警阈值
+  const handleSaveQuotaThresholds = async () => {
+    const thresholds = quotaThresholds
+      .split(/[,，\s]+/)
+      .filter(Boolean)
+      .map((value) => Number(value) / 100);
+    if (thresholds.some((t) => !Number.isFinite(t) || t <= 0 || t > 1)) {
+      showNotice("error", "告警阈值需为 1-100 之间的百分比");
+      return;
+    }
+    try {
+      await setQuotaAlertThresholds(thresholds);
+      await loadQuotaThresholds();
+      showNotice("success", "额度告警阈值已保存");
+    } catch (error) {
+      showNotice("error", "保存额度告警阈值失败: " + error);
+    }
+  };
+
   // 组件挂载时加载数据
   useEffect(() => {
     if (open) {
       loadData();
+      loadReportMonths();
+      loadQuotaThresholds();
     }
   }, [open]);
 
@@ -760,6 +868,21 @@ const TrafficStatsDialog: React.FC<TrafficStatsDialogProps> = ({
         流量警告 ({alerts.filter((a) => !a.is_read).length} 条未读)
       </Typography>
 
+      <Box display="flex" alignItems="center" gap={2} sx={{ mb: 2 }}>
+        <TextField
+          size="small"
+          label="额度告警阈值 (%)"
+          placeholder="80, 90, 100"
+          value={quotaThresholds}
+          onChange={(e) => setQuotaThresholds(e.target.value)}
+          helperText="已用流量达到这些比例时提醒，留空则不提醒"
+          sx={{ flex: 1 }}
+        />
+        <Button variant="outlined" onClick={handleSaveQuotaThresholds}>
+          保存
+        </Button>
+      </Box>
+
       {alerts.length > 0 ? (
         <List>
           {alerts.map((alert) => (
@@ -829,6 +952,136 @@ const TrafficStatsDialog: React.FC<TrafficStatsDialogProps> = ({
     </Box>
   );
 
+  // 渲染分项流量列表
+  const renderUsageEntries = (title: string, entries: TrafficUsageEntry[]) => (
+    <Card variant="outlined">
+      <CardContent>
+        <Typography variant="subtitle1" gutterBottom>
+          {title}
+        </Typography>
+        {entries.length > 0 ? (
+          entries.slice(0, 10).map((entry) => (
+            <Box
+              key={entry.name}
+              display="flex"
+              justifyContent="space-between"
+              sx={{ py: 0.5 }}
+            >
+              <Typography variant="body2" noWrap sx={{ mr: 2 }}>
+                {entry.name}
+              </Typography>
+              <Typography variant="body2" color="text.secondary">
+                {formatBytes(entry.total_bytes)} (
+                {(entry.share * 100).toFixed(1)}%)
+              </Typography>
+            </Box>
+          ))
+        ) : (
+          <Typography variant="body2" color="text.secondary">
+            暂无数据
+          </Typography>
+        )}
+      </CardContent>
+    </Card>
+  );
+
+  // 渲染月度报告面板
+  const renderReports = () => (
+    <Box>
+      <Box display="flex" alignItems="center" gap={2} sx={{ mb: 3 }}>
+        <TextField
+          size="small"
+          type="month"
+          label="报告月份"
+          value={reportMonth}
+          onChange={(e) => setReportMonth(e.target.value)}
+          slotProps={{ inputLabel: { shrink: true } }}
+        />
+        <Button
+          variant="contained"
+          startIcon={<BarChart />}
+          onClick={handleGenerateReport}
+          disabled={reportLoading || !reportMonth}
+        >
+          生成报告
+        </Button>
+        <Button
+          variant="outlined"
+          startIcon={<GetApp />}
+          onClick={handleExportReport}
+          disabled={!report}
+        >
+          导出 CSV
+        </Button>
+        {reportMonths.length > 0 && (
+          <FormControl size="small" sx={{ minWidth: 160 }}>
+            <InputLabel>已保存的报告</InputLabel>
+            <Select
+              label="已保存的报告"
+              value={
+                report && reportMonths.includes(report.month)
+                  ? report.month
+                  : ""
+              }
+              onChange={(e) => handleSelectReport(e.target.value)}
+            >
+              {reportMonths.map((month) => (
+                <MenuItem key={month} value={month}>
+                  {month}
+                </MenuItem>
+              ))}
+            </Select>
+          </FormControl>
+        )}
+      </Box>
+
+      {reportLoading && <LinearProgress sx={{ mb: 2 }} />}
+
+      {report ? (
+        <>
+          <Typography variant="h6" gutterBottom>
+            {report.month} 流量报告：共 {formatBytes(report.total_bytes)}
+          </Typography>
+          <Typography variant="body2" color="text.secondary" sx={{ mb: 2 }}>
+            生成时间: {new Date(report.generated_at * 1000).toLocaleString()}
+            {report.peak_hours.length > 0 &&
+              " | 高峰时段: " +
+                report.peak_hours
+                  .map((peak) => `${String(peak.hour).padStart(2, "0")}:00`)
+                  .join("、")}
+          </Typography>
+          <Grid container spacing={2}>
+            <Grid size={{ xs: 12, md: 4 }}>
+              {renderUsageEntries(
+                "订阅",
+                report.subscriptions.map((entry) => ({
+                  name: entry.subscription_name,
+                  upload_bytes: entry.upload_bytes,
+                  download_bytes: entry.download_bytes,
+                  total_bytes: entry.total_bytes,
+                  share: entry.share,
+                })),
+              )}
+            </Grid>
+            <Grid size={{ xs: 12, md: 4 }}>
+              {renderUsageEntries("应用", report.apps)}
+            </Grid>
+            <Grid size={{ xs: 12, md: 4 }}>
+              {renderUsageEntries("地区", report.regions)}
+            </Grid>
+          </Grid>
+        </>
+      ) : (
+        <Paper variant="outlined" sx={{ p: 3, textAlign: "center" }}>
+          <BarChart sx={{ fontSize: 48, color: "text.secondary", mb: 2 }} />
+          <Typography color="text.secondary">
+            选择月份生成报告，或在任务管理中启用"月度流量报告"任务每月自动生成
+          </Typography>
+        </Paper>
+      )}
+    </Box>
+  );
+
   return (
     <Dialog open={open} onClose={onClose} maxWidth="xl" fullWidth>
       <DialogTitle>
@@ -848,6 +1101,7 @@ const TrafficStatsDialog: React.FC<TrafficStatsDialogProps> = ({
             <Tab label="总览" />
             <Tab label="订阅详情" />
             <Tab label="警告中心" />
+            <Tab label="月度报告" />
           </Tabs>
         </Box>
 
@@ -864,6 +1118,10 @@ const TrafficStatsDialog: React.FC<TrafficStatsDialogProps> = ({
         <TabPanel value={currentTab} index={2}>
           {renderAlerts()}
         </TabPanel>
+
+        <TabPanel value={currentTab} index={3}>
+          {renderReports()}
+        </TabPanel>
       </DialogContent>
 
       <DialogActions>
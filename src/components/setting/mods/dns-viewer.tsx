@@ -21,7 +21,7 @@ import yaml from "js-yaml";
 import MonacoEditor from "react-monaco-editor";
 import { useThemeMode } from "@/services/states";
 import getSystem from "@/utils/get-system";
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "@/services/cmd-error";
 import { showNotice } from "@/services/noticeService";
 
 const Item = styled(ListItem)(() => ({
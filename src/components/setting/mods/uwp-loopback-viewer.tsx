@@ -0,0 +1,110 @@
+import { forwardRef, useImperativeHandle, useMemo, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { useLockFn } from "ahooks";
+import useSWR from "swr";
+import {
+  Box,
+  Button,
+  List,
+  ListItem,
+  ListItemText,
+  TextField,
+  Typography,
+} from "@mui/material";
+import { BaseDialog, DialogRef, Switch } from "@/components/base";
+import { listUwpApps, setUwpLoopback } from "@/services/cmds";
+import { showNotice } from "@/services/noticeService";
+
+export const UwpLoopbackViewer = forwardRef<DialogRef>((props, ref) => {
+  const { t } = useTranslation();
+  const [open, setOpen] = useState(false);
+  const [keyword, setKeyword] = useState("");
+
+  useImperativeHandle(ref, () => ({
+    open: () => setOpen(true),
+    close: () => setOpen(false),
+  }));
+
+  const {
+    data: apps = [],
+    isLoading,
+    mutate,
+  } = useSWR(open ? "clash-verge-rev-internal://uwp-apps" : null, listUwpApps);
+
+  const filtered = useMemo(() => {
+    const text = keyword.trim().toLowerCase();
+    if (!text) return apps;
+    return apps.filter(
+      (app) =>
+        app.name.toLowerCase().includes(text) ||
+        app.packageFamilyName.toLowerCase().includes(text),
+    );
+  }, [apps, keyword]);
+
+  const onToggle = useLockFn(async (pkg: string, enabled: boolean) => {
+    try {
+      await setUwpLoopback(pkg, enabled);
+      await mutate();
+    } catch (err: any) {
+      showNotice("error", err?.message || err.toString());
+    }
+  });
+
+  return (
+    <BaseDialog
+      open={open}
+      title={
+        <Box display="flex" justifyContent="space-between">
+          {t("UWP Loopback Exemption")}
+          <Button variant="contained" size="small" onClick={() => mutate()}>
+            {t("Refresh")}
+          </Button>
+        </Box>
+      }
+      contentSx={{ width: 500, height: 480 }}
+      disableOk
+      cancelBtn={t("Close")}
+      onClose={() => setOpen(false)}
+      onCancel={() => setOpen(false)}
+    >
+      <Typography variant="body2" color="text.secondary" sx={{ mb: 1 }}>
+        {t("Open UWP tool Info")}
+      </Typography>
+      <TextField
+        size="small"
+        fullWidth
+        value={keyword}
+        placeholder={t("Filter UWP apps")}
+        onChange={(e) => setKeyword(e.target.value)}
+      />
+      {isLoading ? (
+        <Typography sx={{ mt: 2 }} color="text.secondary">
+          {t("Loading UWP apps")}
+        </Typography>
+      ) : (
+        <List dense>
+          {filtered.map((app) => (
+            <ListItem
+              key={app.packageFamilyName}
+              secondaryAction={
+                <Switch
+                  edge="end"
+                  checked={app.exempted}
+                  onChange={(_, checked) =>
+                    onToggle(app.packageFamilyName, checked)
+                  }
+                />
+              }
+            >
+              <ListItemText
+                primary={app.name}
+                secondary={app.packageFamilyName}
+                secondaryTypographyProps={{ noWrap: true }}
+              />
+            </ListItem>
+          ))}
+        </List>
+      )}
+    </BaseDialog>
+  );
+});
@@ -3,12 +3,11 @@ import { TooltipIcon } from "@/components/base/base-tooltip-icon";
 import { useClash } from "@/hooks/use-clash";
 import { useVerge } from "@/hooks/use-verge";
 import { updateGeoData } from "@/services/cmds";
-import { invoke_uwp_tool } from "@/services/cmds";
 import { showNotice } from "@/services/noticeService";
 import getSystem from "@/utils/get-system";
 import { LanRounded, SettingsRounded } from "@mui/icons-material";
 import { MenuItem, Select, TextField, Typography } from "@mui/material";
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "@/services/cmd-error";
 import { useLockFn } from "ahooks";
 import { useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
@@ -21,6 +20,7 @@ import { NetworkInterfaceViewer } from "./mods/network-interface-viewer";
 import { SettingItem, SettingList } from "./mods/setting-comp";
 import { WebUIViewer } from "./mods/web-ui-viewer";
 import { HeaderConfiguration } from "./mods/external-controller-cors";
+import { UwpLoopbackViewer } from "./mods/uwp-loopback-viewer";
 
 const isWIN = getSystem() === "windows";
 
@@ -55,6 +55,7 @@ const SettingClash = ({ onError }: Props) => {
   const networkRef = useRef<DialogRef>(null);
   const dnsRef = useRef<DialogRef>(null);
   const corsRef = useRef<DialogRef>(null);
+  const uwpRef = useRef<DialogRef>(null);
 
   const onSwitchFormat = (_e: any, value: boolean) => value;
   const onChangeData = (patch: Partial<IConfigData>) => {
@@ -97,6 +98,7 @@ const SettingClash = ({ onError }: Props) => {
       <NetworkInterfaceViewer ref={networkRef} />
       <DnsViewer ref={dnsRef} />
       <HeaderConfiguration ref={corsRef} />
+      <UwpLoopbackViewer ref={uwpRef} />
 
       <SettingItem
         label={t("Allow Lan")}
@@ -243,7 +245,7 @@ const SettingClash = ({ onError }: Props) => {
 
       {isWIN && (
         <SettingItem
-          onClick={invoke_uwp_tool}
+          onClick={() => uwpRef.current?.open()}
           label={t("Open UWP tool")}
           extra={
             <TooltipIcon
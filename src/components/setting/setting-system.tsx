@@ -9,11 +9,15 @@ import { GuardState } from "./mods/guard-state";
 import { SysproxyViewer } from "./mods/sysproxy-viewer";
 import { TunViewer } from "./mods/tun-viewer";
 import { TooltipIcon } from "@/components/base/base-tooltip-icon";
-import { Tooltip } from "@mui/material";
+import { MenuItem, Select, Tooltip } from "@mui/material";
 import { useSystemState } from "@/hooks/use-system-state";
 import ProxyControlSwitches from "@/components/shared/ProxyControlSwitches";
 
 import { showNotice } from "@/services/noticeService";
+import {
+  CaptivePortalPolicy,
+  setCaptivePortalPolicy,
+} from "@/services/cmds";
 
 interface Props {
   onError?: (err: Error) => void;
@@ -26,7 +30,8 @@ const SettingSystem = ({ onError }: Props) => {
 
   const { isAdminMode } = useSystemState();
 
-  const { enable_auto_launch, enable_silent_start } = verge ?? {};
+  const { enable_auto_launch, enable_silent_start, captive_portal_policy } =
+    verge ?? {};
 
   const sysproxyRef = useRef<DialogRef>(null);
   const tunRef = useRef<DialogRef>(null);
@@ -111,6 +116,33 @@ const SettingSystem = ({ onError }: Props) => {
           <Switch edge="end" />
         </GuardState>
       </SettingItem>
+
+      <SettingItem
+        label={t("Captive Portal")}
+        extra={
+          <TooltipIcon
+            title={t("Captive Portal Policy Info")}
+            sx={{ opacity: "0.7" }}
+          />
+        }
+      >
+        <GuardState
+          value={captive_portal_policy ?? "notify"}
+          onCatch={onError}
+          onFormat={(e: any) => e.target.value}
+          onChange={(e) => onChangeData({ captive_portal_policy: e })}
+          onGuard={(e: CaptivePortalPolicy) => setCaptivePortalPolicy(e)}
+        >
+          <Select size="small" sx={{ width: 160, "> div": { py: "7.5px" } }}>
+            <MenuItem value="off">{t("Disable")}</MenuItem>
+            <MenuItem value="notify">{t("Notify Only")}</MenuItem>
+            <MenuItem value="disable_sysproxy">
+              {t("Pause System Proxy")}
+            </MenuItem>
+            <MenuItem value="direct">{t("Switch to Direct")}</MenuItem>
+          </Select>
+        </GuardState>
+      </SettingItem>
     </SettingList>
   );
 };
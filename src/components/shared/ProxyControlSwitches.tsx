@@ -7,6 +7,7 @@ import {
   BuildRounded,
   DeleteForeverRounded,
   WarningRounded,
+  AdminPanelSettingsRounded,
 } from "@mui/icons-material";
 import { Box, Typography, alpha, useTheme } from "@mui/material";
 import { DialogRef, Switch } from "@/components/base";
@@ -19,9 +20,17 @@ import { useSystemProxyState } from "@/hooks/use-system-proxy-state";
 import { useSystemState } from "@/hooks/use-system-state";
 import { showNotice } from "@/services/noticeService";
 import { useServiceInstaller } from "@/hooks/useServiceInstaller";
-import { uninstallService, restartCore, stopCore } from "@/services/cmds";
+import {
+  uninstallService,
+  restartCore,
+  stopCore,
+  setupLinuxCapabilities,
+} from "@/services/cmds";
+import getSystem from "@/utils/get-system";
 import { useLockFn } from "ahooks";
 
+const isLinux = getSystem() === "linux";
+
 interface ProxySwitchProps {
   label?: string;
   onError?: (err: Error) => void;
@@ -45,9 +54,8 @@ const ProxyControlSwitches = ({
   const { actualState: systemProxyActualState, toggleSystemProxy } =
     useSystemProxyState();
 
-  const { isAdminMode, isServiceMode, mutateRunningMode } = useSystemState();
-
-  const isTunAvailable = isServiceMode || isAdminMode;
+  const { isServiceMode, isTunAvailable, mutateRunningMode, mutateTunCapable } =
+    useSystemState();
 
   const sysproxyRef = useRef<DialogRef>(null);
   const tunRef = useRef<DialogRef>(null);
@@ -69,6 +77,19 @@ const ProxyControlSwitches = ({
   // 安装系统服务
   const onInstallService = installServiceAndRestartCore;
 
+  // Linux 下为内核授予 TUN 所需的 capabilities，无需安装服务
+  const onGrantCapabilities = useLockFn(async () => {
+    try {
+      showNotice("info", t("Granting TUN Capabilities..."));
+      await setupLinuxCapabilities();
+      await mutateTunCapable();
+      await mutateRunningMode();
+      showNotice("success", t("TUN Capabilities Granted"));
+    } catch (err: unknown) {
+      showNotice("error", (err as Error)?.message || err?.toString());
+    }
+  });
+
   // 卸载系统服务
   const onUninstallService = useLockFn(async () => {
     try {
@@ -201,6 +222,16 @@ const ProxyControlSwitches = ({
               />
             )}
 
+            {!isTunAvailable && isLinux && (
+              <TooltipIcon
+                title={t("Grant TUN Capabilities")}
+                icon={AdminPanelSettingsRounded}
+                color="primary"
+                onClick={onGrantCapabilities}
+                sx={{ ml: 1 }}
+              />
+            )}
+
             {isServiceMode && (
               <TooltipIcon
                 title={t("Uninstall Service")}
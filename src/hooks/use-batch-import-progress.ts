@@ -25,6 +25,7 @@ const fallbacks: Partial<Record<string, string>> = {
   importing: "Importing",
   finalizing: "Finalizing",
   completed: "Completed",
+  cancelled: "Cancelled",
 };
 
 const clampPercent = (value: number) => {
@@ -104,7 +105,9 @@ export function useBatchImportProgress(
     payload,
     percent,
     isCompleted: payload?.stage === "completed",
-    isActive: Boolean(payload && payload.stage !== "completed"),
+    isActive: Boolean(
+      payload && payload.stage !== "completed" && payload.stage !== "cancelled",
+    ),
     stageLabel,
     displayMessage: payload?.message || null,
     reset,
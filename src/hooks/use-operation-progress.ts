@@ -0,0 +1,71 @@
+import { useCallback, useEffect, useState } from "react";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import type { OperationProgress } from "@/services/cmds";
+
+const FINISHED_STAGES = new Set(["completed", "failed", "cancelled"]);
+
+export interface OperationProgressState {
+  // 按 task_id 保存每个操作的最新进度
+  operations: OperationProgress[];
+  activeCount: number;
+  dismiss: (taskId: string) => void;
+  clearFinished: () => void;
+}
+
+export const isOperationFinished = (operation: OperationProgress) =>
+  FINISHED_STAGES.has(operation.stage);
+
+export function useOperationProgress(): OperationProgressState {
+  const [operations, setOperations] = useState<OperationProgress[]>([]);
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+    let disposed = false;
+
+    (async () => {
+      try {
+        unlisten = await listen<OperationProgress>(
+          "operation-progress",
+          (event) => {
+            if (disposed) return;
+            const payload = event.payload;
+            setOperations((prev) => {
+              const index = prev.findIndex(
+                (item) => item.task_id === payload.task_id,
+              );
+              if (index === -1) return [...prev, payload];
+              const next = [...prev];
+              next[index] = payload;
+              return next;
+            });
+          },
+        );
+      } catch (error) {
+        console.error("Failed to listen operation-progress", error);
+      }
+    })();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  const dismiss = useCallback((taskId: string) => {
+    setOperations((prev) => prev.filter((item) => item.task_id !== taskId));
+  }, []);
+
+  const clearFinished = useCallback(() => {
+    setOperations((prev) => prev.filter((item) => !isOperationFinished(item)));
+  }, []);
+
+  return {
+    operations,
+    activeCount: operations.filter((item) => !isOperationFinished(item))
+      .length,
+    dismiss,
+    clearFinished,
+  };
+}
@@ -1,9 +1,17 @@
 import useSWR from "swr";
-import { getRunningMode, isAdmin, isServiceAvailable } from "@/services/cmds";
+import {
+  getRunningMode,
+  hasTunCapabilities,
+  isAdmin,
+  isServiceAvailable,
+} from "@/services/cmds";
+import getSystem from "@/utils/get-system";
+
+const isLinux = getSystem() === "linux";
 
 /**
  * 自定义 hook 用于获取系统运行状态
- * 包括运行模式、管理员状态、系统服务是否可用
+ * 包括运行模式、管理员状态、系统服务是否可用、TUN 是否可用
  */
 export function useSystemState() {
   // 获取运行模式
@@ -34,12 +42,26 @@ export function useSystemState() {
     },
   );
 
+  // Linux 下内核获得 capabilities 后，sidecar 模式也可启用 TUN
+  const { data: tunCapable, mutate: mutateTunCapable } = useSWR(
+    isLinux ? "hasTunCapabilities" : null,
+    hasTunCapabilities,
+    {
+      suspense: false,
+      revalidateOnFocus: false,
+    },
+  );
+
   return {
     runningMode,
     isAdminMode,
     isSidecarMode: runningMode === "Sidecar",
     isServiceMode: runningMode === "Service",
     isServiceOk,
+    isTunCapable: tunCapable ?? false,
+    isTunAvailable: isServiceMode || isAdminMode || (tunCapable ?? false),
+    isTunCheckPending: isLinux && tunCapable === undefined,
     mutateRunningMode,
+    mutateTunCapable,
   };
 }
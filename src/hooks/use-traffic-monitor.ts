@@ -1,8 +1,6 @@
-import { useState, useEffect, useRef, useCallback } from "react";
-import useSWR from "swr";
-import { useClashInfo } from "@/hooks/use-clash";
-import { useVisibility } from "@/hooks/use-visibility";
-import { getSystemMonitorOverviewSafe } from "@/services/cmds";
+import { useState, useEffect, useCallback } from "react";
+import { subscribeClashStream } from "@/services/clash-stream";
+import parseTraffic from "@/utils/parse-traffic";
 
 // 增强的流量数据点接口
 export interface ITrafficDataPoint {
@@ -171,132 +169,139 @@ class TrafficDataSampler {
   }
 }
 
+// 内核推送的原始数据，traffic 为每秒速率
+interface ITrafficStreamData {
+  up: number;
+  down: number;
+  upTotal?: number;
+  downTotal?: number;
+}
+
+interface IMemoryStreamData {
+  inuse: number;
+  oslimit?: number;
+}
+
+// 超过该时间未收到推送视为数据过期
+const TRAFFIC_FRESH_MS = 5000;
+const MEMORY_FRESH_MS = 10000;
+
+const formatBytes = (num: number) => parseTraffic(num).join("");
+
+const formatTime = (timestamp: number) =>
+  new Date(timestamp).toLocaleTimeString("en-US", {
+    hour12: false,
+    hour: "2-digit",
+    minute: "2-digit",
+    second: "2-digit",
+  });
+
 // 全局单例
 const refCounter = new ReferenceCounter();
-let globalSampler: TrafficDataSampler | null = null;
-let lastValidData: ISystemMonitorOverview | null = null;
+const globalSampler = new TrafficDataSampler({
+  rawDataMinutes: 10, // 原始数据保持10分钟
+  compressedDataMinutes: 60, // 压缩数据保持1小时
+  compressionRatio: 5, // 每5个原始点压缩成1个
+});
+const updateListeners = new Set<() => void>();
+let lastTraffic: ITrafficStreamData | null = null;
+let lastMemory: IMemoryStreamData | null = null;
+let trafficUpdatedAt = 0;
+let memoryUpdatedAt = 0;
+let stopStreams: (() => void) | null = null;
+
+const notifyUpdate = () => updateListeners.forEach((listener) => listener());
+
+const handleTraffic = (data: ITrafficStreamData) => {
+  lastTraffic = data;
+  trafficUpdatedAt = Date.now();
+  globalSampler.addDataPoint({
+    up: data.up || 0,
+    down: data.down || 0,
+    timestamp: trafficUpdatedAt,
+    name: formatTime(trafficUpdatedAt),
+  });
+  notifyUpdate();
+};
+
+const handleMemory = (data: IMemoryStreamData) => {
+  lastMemory = data;
+  memoryUpdatedAt = Date.now();
+  notifyUpdate();
+};
+
+// 有组件使用时才订阅内核数据流，全部卸载后取消订阅
+refCounter.onCountChange(() => {
+  if (refCounter.getCount() > 0 && !stopStreams) {
+    const offTraffic = subscribeClashStream("traffic", handleTraffic);
+    const offMemory = subscribeClashStream("memory", handleMemory);
+    stopStreams = () => {
+      offTraffic();
+      offMemory();
+    };
+  } else if (refCounter.getCount() === 0 && stopStreams) {
+    stopStreams();
+    stopStreams = null;
+  }
+});
+
+const buildTrafficData = (): ISystemMonitorOverview["traffic"] => {
+  const up = lastTraffic?.up || 0;
+  const down = lastTraffic?.down || 0;
+  const totalUp = lastTraffic?.upTotal || 0;
+  const totalDown = lastTraffic?.downTotal || 0;
+  return {
+    raw: { up: totalUp, down: totalDown, up_rate: up, down_rate: down },
+    formatted: {
+      up_rate: formatBytes(up),
+      down_rate: formatBytes(down),
+      total_up: formatBytes(totalUp),
+      total_down: formatBytes(totalDown),
+    },
+    is_fresh: Date.now() - trafficUpdatedAt < TRAFFIC_FRESH_MS,
+  };
+};
+
+const buildMemoryData = (): ISystemMonitorOverview["memory"] => {
+  const inuse = lastMemory?.inuse || 0;
+  const oslimit = lastMemory?.oslimit || 0;
+  const usagePercent = oslimit > 0 ? (inuse / oslimit) * 100 : 0;
+  return {
+    raw: { inuse, oslimit, usage_percent: usagePercent },
+    formatted: {
+      inuse: formatBytes(inuse),
+      oslimit: formatBytes(oslimit),
+      usage_percent: usagePercent,
+    },
+    is_fresh: Date.now() - memoryUpdatedAt < MEMORY_FRESH_MS,
+  };
+};
 
 /**
- * 增强的流量监控Hook - 支持数据压缩、采样和引用计数
+ * 增强的流量监控Hook - 通过 verge://clash-stream 推送接收数据，支持数据压缩、采样和引用计数
  */
 export const useTrafficMonitorEnhanced = () => {
-  const { clashInfo } = useClashInfo();
-  const pageVisible = useVisibility();
-
-  // 初始化采样器
-  if (!globalSampler) {
-    globalSampler = new TrafficDataSampler({
-      rawDataMinutes: 10, // 原始数据保持10分钟
-      compressedDataMinutes: 60, // 压缩数据保持1小时
-      compressionRatio: 5, // 每5个原始点压缩成1个
-    });
-  }
-
   const [, forceUpdate] = useState({});
-  const cleanupRef = useRef<(() => void) | null>(null);
 
   // 强制组件更新
   const triggerUpdate = useCallback(() => {
     forceUpdate({});
   }, []);
 
-  // 注册引用计数
+  // 注册引用计数与数据更新回调
   useEffect(() => {
-    console.log("[TrafficMonitorEnhanced] 组件挂载，注册引用计数");
+    updateListeners.add(triggerUpdate);
     const cleanup = refCounter.increment();
-    cleanupRef.current = cleanup;
 
     return () => {
-      console.log("[TrafficMonitorEnhanced] 组件卸载，清理引用计数");
+      updateListeners.delete(triggerUpdate);
       cleanup();
-      cleanupRef.current = null;
-    };
-  }, []);
-
-  // 设置引用计数变化回调
-  useEffect(() => {
-    const handleCountChange = () => {
-      console.log(
-        `[TrafficMonitorEnhanced] 引用计数变化: ${refCounter.getCount()}`,
-      );
-      if (refCounter.getCount() === 0) {
-        console.log("[TrafficMonitorEnhanced] 所有组件已卸载，暂停数据收集");
-      } else {
-        console.log("[TrafficMonitorEnhanced] 开始数据收集");
-      }
     };
-
-    refCounter.onCountChange(handleCountChange);
-  }, []);
-
-  // 只有在有引用时才启用SWR
-  const shouldFetch = clashInfo && pageVisible && refCounter.getCount() > 0;
-
-  const { data: monitorData, error } = useSWR<ISystemMonitorOverview>(
-    shouldFetch ? "getSystemMonitorOverviewSafe" : null,
-    getSystemMonitorOverviewSafe,
-    {
-      refreshInterval: shouldFetch ? 1000 : 0, // 只有在需要时才刷新
-      keepPreviousData: true,
-      onSuccess: (data) => {
-        // console.log("[TrafficMonitorEnhanced] 获取到监控数据:", data);
-
-        if (data?.traffic?.raw && globalSampler) {
-          // 保存最后有效数据
-          lastValidData = data;
-
-          // 添加到采样器
-          const timestamp = Date.now();
-          const dataPoint: ITrafficDataPoint = {
-            up: data.traffic.raw.up_rate || 0,
-            down: data.traffic.raw.down_rate || 0,
-            timestamp,
-            name: new Date(timestamp).toLocaleTimeString("en-US", {
-              hour12: false,
-              hour: "2-digit",
-              minute: "2-digit",
-              second: "2-digit",
-            }),
-          };
-
-          globalSampler.addDataPoint(dataPoint);
-          triggerUpdate();
-        }
-      },
-      onError: (error) => {
-        console.error(
-          "[TrafficMonitorEnhanced] 网络错误，使用最后有效数据. 错误详情:",
-          {
-            message: error?.message || "未知错误",
-            stack: error?.stack || "无堆栈信息",
-          },
-        );
-        // 网络错误时不清空数据，继续使用最后有效值
-        // 但是添加一个错误标记的数据点（流量为0）
-        if (globalSampler) {
-          const timestamp = Date.now();
-          const errorPoint: ITrafficDataPoint = {
-            up: 0,
-            down: 0,
-            timestamp,
-            name: new Date(timestamp).toLocaleTimeString("en-US", {
-              hour12: false,
-              hour: "2-digit",
-              minute: "2-digit",
-              second: "2-digit",
-            }),
-          };
-          globalSampler.addDataPoint(errorPoint);
-          triggerUpdate();
-        }
-      },
-    },
-  );
+  }, [triggerUpdate]);
 
   // 获取指定时间范围的数据
   const getDataForTimeRange = useCallback(
     (minutes: number): ITrafficDataPoint[] => {
-      if (!globalSampler) return [];
       return globalSampler.getDataForTimeRange(minutes);
     },
     [],
@@ -304,42 +309,13 @@ export const useTrafficMonitorEnhanced = () => {
 
   // 清空数据
   const clearData = useCallback(() => {
-    if (globalSampler) {
-      globalSampler.clear();
-      triggerUpdate();
-    }
+    globalSampler.clear();
+    triggerUpdate();
   }, [triggerUpdate]);
 
-  // 获取采样器统计信息
-  const getSamplerStats = useCallback(() => {
-    return (
-      globalSampler?.getStats() || {
-        rawBufferSize: 0,
-        compressedBufferSize: 0,
-        compressionQueueSize: 0,
-        totalMemoryPoints: 0,
-      }
-    );
-  }, []);
-
-  // 构建返回的监控数据，优先使用当前数据，fallback到最后有效数据
-  const currentData = monitorData || lastValidData;
   const trafficMonitorData = {
-    traffic: currentData?.traffic || {
-      raw: { up: 0, down: 0, up_rate: 0, down_rate: 0 },
-      formatted: {
-        up_rate: "0B",
-        down_rate: "0B",
-        total_up: "0B",
-        total_down: "0B",
-      },
-      is_fresh: false,
-    },
-    memory: currentData?.memory || {
-      raw: { inuse: 0, oslimit: 0, usage_percent: 0 },
-      formatted: { inuse: "0B", oslimit: "0B", usage_percent: 0 },
-      is_fresh: false,
-    },
+    traffic: buildTrafficData(),
+    memory: buildMemoryData(),
   };
 
   return {
@@ -348,19 +324,19 @@ export const useTrafficMonitorEnhanced = () => {
 
     // 图表数据管理
     graphData: {
-      dataPoints: globalSampler?.getDataForTimeRange(60) || [], // 默认获取1小时数据
+      dataPoints: globalSampler.getDataForTimeRange(60), // 默认获取1小时数据
       getDataForTimeRange,
       clearData,
     },
 
     // 状态信息
-    isLoading: !currentData && !error,
-    error,
-    isDataFresh: currentData?.traffic?.is_fresh || false,
-    hasValidData: !!lastValidData,
+    isLoading: !lastTraffic,
+    error: undefined,
+    isDataFresh: trafficMonitorData.traffic.is_fresh,
+    hasValidData: !!lastTraffic,
 
     // 性能统计
-    samplerStats: getSamplerStats(),
+    samplerStats: globalSampler.getStats(),
     referenceCount: refCounter.getCount(),
   };
 };
@@ -7,7 +7,7 @@ import { showNotice } from "@/services/noticeService";
 
 export const useVerge = () => {
   const { t } = useTranslation();
-  const { isAdminMode, isServiceMode } = useSystemState();
+  const { isTunAvailable, isTunCheckPending } = useSystemState();
 
   const { data: verge, mutate: mutateVerge } = useSWR(
     "getVergeConfig",
@@ -22,12 +22,11 @@ export const useVerge = () => {
     mutateVerge();
   };
 
-  const isTunAvailable = isServiceMode || isAdminMode;
   const { enable_tun_mode } = verge ?? {};
 
   // 当服务不可用且TUN模式开启时自动关闭TUN
   useEffect(() => {
-    if (enable_tun_mode && !isTunAvailable) {
+    if (enable_tun_mode && !isTunAvailable && !isTunCheckPending) {
       console.log("[useVerge] 检测到服务不可用，自动关闭TUN模式");
 
       patchVergeConfig({ enable_tun_mode: false })
@@ -43,7 +42,7 @@ export const useVerge = () => {
           showNotice("error", t("Failed to disable TUN Mode automatically"));
         });
     }
-  }, [isTunAvailable, enable_tun_mode, mutateVerge, t]);
+  }, [isTunAvailable, isTunCheckPending, enable_tun_mode, mutateVerge, t]);
 
   return {
     verge,
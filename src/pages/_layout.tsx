@@ -29,7 +29,7 @@ import { useListen } from "@/hooks/use-listen";
 import { listen } from "@tauri-apps/api/event";
 import { useClashInfo } from "@/hooks/use-clash";
 import { initGlobalLogService } from "@/services/global-log-service";
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "@/services/cmd-error";
 import { showNotice } from "@/services/noticeService";
 import { NoticeManager } from "@/components/base/NoticeManager";
 import { useLocalStorage } from "foxact/use-local-storage";
@@ -84,6 +84,10 @@ const handleNoticeMessage = (
     case "config_validate::boot_error":
       showNotice("error", `${t("Boot Config Validation Failed")} ${msg}`);
       break;
+    case "config_validate::startup_recovery":
+      navigate("/profile");
+      showNotice("error", `${t("Boot Config Validation Failed")} ${msg}`);
+      break;
     case "config_validate::core_change":
       showNotice(
         "error",
@@ -144,6 +148,39 @@ const handleNoticeMessage = (
     case "config_core::change_error":
       showNotice("error", `${t("Failed to Change Core")}: ${msg}`);
       break;
+    case "deep_link::ok":
+      showNotice("success", msg);
+      break;
+    case "deep_link::error":
+      showNotice("error", msg);
+      break;
+    case "core_upgrade::success":
+      showNotice("success", `${t("Core upgraded to")} ${msg}`);
+      break;
+    case "core_upgrade::rolled_back":
+      showNotice("error", `${t("Core upgrade rolled back")}: ${msg}`);
+      break;
+    case "app_update::ready":
+      showNotice(
+        "success",
+        `${t("Update downloaded, restart to install")}: ${msg}`,
+      );
+      break;
+    case "subscription_usage::alert":
+      showNotice("info", msg);
+      break;
+    case "core_startup::port_conflict":
+      showNotice("info", `${t("Port conflicts detected")}: ${msg}`);
+      break;
+    case "captive_portal::detected":
+      showNotice("info", `${t("Captive portal detected")}: ${msg}`);
+      break;
+    case "captive_portal::restored":
+      showNotice(
+        "success",
+        t("Captive portal passed, proxy settings restored"),
+      );
+      break;
     default: // Optional: Log unhandled statuses
       console.warn(`[通知监听 V2] 未处理的状态: ${status}`);
       break;
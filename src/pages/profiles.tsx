@@ -47,6 +47,7 @@ import {
   reorderProfile,
   createProfile,
   getProfiles,
+  type ProfileFileChanged,
 } from "@/services/cmds";
 import { useSetLoadingCache, useThemeMode } from "@/services/states";
 import { closeAllConnections } from "@/services/cmds";
@@ -956,6 +957,29 @@ const ProfilePage = () => {
     };
   }, [mutateProfiles]);
 
+  // 监听外部编辑器对订阅文件的修改
+  useEffect(() => {
+    const unlistenPromise = listen<ProfileFileChanged>(
+      "profile-file-changed",
+      (event) => {
+        const { file, valid, error } = event.payload;
+        if (valid) {
+          showNotice("info", t("Profile File Reloaded", { file }), 2000);
+        } else {
+          showNotice(
+            "error",
+            `${t("Profile File Invalid", { file })}: ${error ?? ""}`,
+          );
+        }
+        mutateProfiles().catch(console.error);
+      },
+    );
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten()).catch(console.error);
+    };
+  }, [mutateProfiles, t]);
+
   // 组件卸载时清理中断控制器
   useEffect(() => {
     return () => {
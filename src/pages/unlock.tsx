@@ -13,7 +13,7 @@ import {
   Grid,
 } from "@mui/material";
 import { useTranslation } from "react-i18next";
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "@/services/cmd-error";
 import { BasePage, BaseEmpty } from "@/components/base";
 import { useLockFn } from "ahooks";
 import {
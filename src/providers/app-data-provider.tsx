@@ -4,6 +4,7 @@ import React, {
   useEffect,
   useMemo,
   useRef,
+  useState,
 } from "react";
 import { useVerge } from "@/hooks/use-verge";
 import useSWR from "swr";
@@ -13,9 +14,6 @@ import {
   getClashConfig,
   getProxyProviders,
   getRuleProviders,
-  getConnections,
-  getTrafficData,
-  getMemoryData,
 } from "@/services/cmds";
 import {
   getSystemProxy,
@@ -27,6 +25,7 @@ import {
 import { useClashInfo } from "@/hooks/use-clash";
 import { useVisibility } from "@/hooks/use-visibility";
 import { listen } from "@tauri-apps/api/event";
+import { subscribeClashStream } from "@/services/clash-stream";
 
 // 连接速度计算接口
 interface ConnectionSpeedData {
@@ -57,8 +56,6 @@ interface AppDataContextType {
     uploadTotal: number;
     downloadTotal: number;
   };
-  traffic: { up: number; down: number };
-  memory: { inuse: number };
   systemProxyAddress: string;
 
   refreshProxy: () => Promise<any>;
@@ -507,18 +504,18 @@ export const AppDataProvider = ({
     suspense: false,
   });
 
-  // 连接数据 - 使用IPC轮询更新并计算速度
-  const {
-    data: connectionsData = {
-      connections: [],
-      uploadTotal: 0,
-      downloadTotal: 0,
-    },
-  } = useSWR(
-    clashInfo && pageVisible ? "getConnections" : null,
-    async () => {
-      const data = await getConnections();
-      const rawConnections: IConnectionsItem[] = data.connections || [];
+  // 连接数据 - 通过数据流推送更新并计算速度
+  const [connectionsData, setConnectionsData] = useState<{
+    connections: ConnectionWithSpeed[];
+    uploadTotal: number;
+    downloadTotal: number;
+  }>({ connections: [], uploadTotal: 0, downloadTotal: 0 });
+
+  useEffect(() => {
+    if (!clashInfo || !pageVisible) return;
+
+    return subscribeClashStream<IConnections>("connections", (data) => {
+      const rawConnections: IConnectionsItem[] = data?.connections || [];
 
       // 计算带速度的连接数据
       const connectionsWithSpeed = calculateConnectionSpeeds(rawConnections);
@@ -536,57 +533,13 @@ export const AppDataProvider = ({
       });
       previousConnectionsRef.current = currentMap;
 
-      return {
+      setConnectionsData({
         connections: connectionsWithSpeed,
-        uploadTotal: data.uploadTotal || 0,
-        downloadTotal: data.downloadTotal || 0,
-      };
-    },
-    {
-      refreshInterval: 2000, // ⚡ 降低轮询频率到2秒，减少IPC压力
-      dedupingInterval: 1000, // ⚡ 1秒内去重，避免重复请求
-      revalidateOnFocus: false, // ⚡ 窗口聚焦时不重新验证
-      shouldRetryOnError: false, // ⚡ 错误时不重试，快速失败
-      errorRetryInterval: 5000, // ⚡ 错误重试间隔5秒
-      errorRetryCount: 2, // ⚡ 最多重试2次
-      fallbackData: { connections: [], uploadTotal: 0, downloadTotal: 0 },
-      keepPreviousData: true,
-      onError: (error) => {
-        console.error("[Connections] IPC 获取数据错误:", error);
-      },
-    },
-  );
-
-  // 流量数据 - 使用IPC轮询更新
-  const { data: trafficData = { up: 0, down: 0 } } = useSWR(
-    clashInfo && pageVisible ? "getTrafficData" : null,
-    getTrafficData,
-    {
-      refreshInterval: 1000, // 1秒刷新一次
-      fallbackData: { up: 0, down: 0 },
-      keepPreviousData: true,
-      onSuccess: () => {
-        // console.log("[Traffic][AppDataProvider] IPC 获取到流量数据:", data);
-      },
-      onError: (error) => {
-        console.error("[Traffic][AppDataProvider] IPC 获取数据错误:", error);
-      },
-    },
-  );
-
-  // 内存数据 - 使用IPC轮询更新
-  const { data: memoryData = { inuse: 0 } } = useSWR(
-    clashInfo && pageVisible ? "getMemoryData" : null,
-    getMemoryData,
-    {
-      refreshInterval: 2000, // 2秒刷新一次
-      fallbackData: { inuse: 0 },
-      keepPreviousData: true,
-      onError: (error) => {
-        console.error("[Memory] IPC 获取数据错误:", error);
-      },
-    },
-  );
+        uploadTotal: data?.uploadTotal || 0,
+        downloadTotal: data?.downloadTotal || 0,
+      });
+    });
+  }, [clashInfo, pageVisible]);
 
   // 提供统一的刷新方法
   const refreshAll = async () => {
@@ -654,10 +607,6 @@ export const AppDataProvider = ({
         downloadTotal: connectionsData.downloadTotal || 0,
       },
 
-      // 实时流量数据
-      traffic: trafficData,
-      memory: memoryData,
-
       systemProxyAddress: calculateSystemProxyAddress(),
 
       // 刷新方法
@@ -677,8 +626,6 @@ export const AppDataProvider = ({
     runningMode,
     uptimeData,
     connectionsData,
-    trafficData,
-    memoryData,
     proxyProviders,
     ruleProviders,
     verge,
@@ -0,0 +1,61 @@
+// 内核数据流订阅服务，同一数据流的多个订阅者共享后端的一次订阅
+import { listen } from "@tauri-apps/api/event";
+import {
+  subscribeStreamChannel,
+  unsubscribeStreamChannel,
+  type StreamChannel,
+} from "@/services/cmds";
+
+const STREAM_EVENT = "verge://clash-stream";
+
+interface IStreamEvent {
+  channel: StreamChannel;
+  data: any;
+}
+
+type StreamHandler = (data: any) => void;
+
+const handlers = new Map<StreamChannel, Set<StreamHandler>>();
+let listening = false;
+
+const ensureListener = () => {
+  if (listening) return;
+  listening = true;
+  listen<IStreamEvent>(STREAM_EVENT, (event) => {
+    const { channel, data } = event.payload;
+    handlers.get(channel)?.forEach((handler) => handler(data));
+  });
+};
+
+// 订阅数据流，返回取消订阅函数；第一个订阅者出现时通知后端连接，最后一个离开时断开
+export const subscribeClashStream = <T = any>(
+  channel: StreamChannel,
+  handler: (data: T) => void,
+): (() => void) => {
+  ensureListener();
+
+  let channelHandlers = handlers.get(channel);
+  if (!channelHandlers) {
+    channelHandlers = new Set();
+    handlers.set(channel, channelHandlers);
+  }
+  const set = channelHandlers;
+  set.add(handler);
+  if (set.size === 1) {
+    subscribeStreamChannel(channel).catch((error) => {
+      console.error(`[ClashStream] 订阅 ${channel} 失败:`, error);
+    });
+  }
+
+  let active = true;
+  return () => {
+    if (!active) return;
+    active = false;
+    set.delete(handler);
+    if (set.size === 0) {
+      unsubscribeStreamChannel(channel).catch((error) => {
+        console.error(`[ClashStream] 取消订阅 ${channel} 失败:`, error);
+      });
+    }
+  };
+};
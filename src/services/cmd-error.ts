@@ -0,0 +1,61 @@
+import { invoke as tauriInvoke, InvokeArgs } from "@tauri-apps/api/core";
+
+// 与后端 cmd/error.rs 中的 ErrorCode 保持一致
+export type CmdErrorCode =
+  | "common.unknown"
+  | "common.invalid_argument"
+  | "common.not_found"
+  | "common.io"
+  | "common.parse"
+  | "common.timeout"
+  | "common.cancelled"
+  | "network.connect"
+  | "network.timeout"
+  | "network.http_status"
+  | "profile.not_found"
+  | "profile.not_remote"
+  | "config.validation_failed"
+  | "core.ipc"
+  | "service.unavailable"
+  | "app_lock.locked"
+  | "app_lock.invalid_passcode";
+
+interface ICmdErrorPayload {
+  code: CmdErrorCode;
+  message: string;
+  details?: string;
+  retryable: boolean;
+}
+
+// 命令返回的结构化错误，toString 只返回 message，兼容原先按字符串处理错误的调用方
+export class CmdError extends Error {
+  code: CmdErrorCode;
+  details?: string;
+  retryable: boolean;
+
+  constructor(payload: ICmdErrorPayload) {
+    super(payload.message);
+    this.name = "CmdError";
+    this.code = payload.code;
+    this.details = payload.details;
+    this.retryable = payload.retryable;
+  }
+
+  toString() {
+    return this.message;
+  }
+}
+
+const isCmdErrorPayload = (err: unknown): err is ICmdErrorPayload =>
+  typeof err === "object" &&
+  err !== null &&
+  typeof (err as ICmdErrorPayload).code === "string" &&
+  typeof (err as ICmdErrorPayload).message === "string";
+
+export async function invoke<T>(cmd: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (err) {
+    throw isCmdErrorPayload(err) ? new CmdError(err) : err;
+  }
+}
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke } from "@/services/cmd-error";
 import { showNotice } from "@/services/noticeService";
 
 export async function copyClashEnv() {
@@ -9,8 +9,17 @@ export async function getProfiles() {
   return invoke<IProfilesConfig>("get_profiles");
 }
 
-export async function enhanceProfiles() {
-  return invoke<void>("enhance_profiles");
+// profile-file-changed 事件，订阅文件被外部编辑器修改时发送
+export interface ProfileFileChanged {
+  uid: string;
+  file: string;
+  valid: boolean;
+  error?: string | null;
+  active: boolean;
+}
+
+export async function enhanceProfiles(taskId?: string) {
+  return invoke<void>("enhance_profiles", { taskId });
 }
 
 export async function patchProfilesConfig(profiles: IProfilesConfig) {
@@ -69,6 +78,23 @@ export async function getClashInfo() {
   return invoke<IClashInfo | null>("get_clash_info");
 }
 
+/** 生成新的外部控制器密钥并重启内核，返回新的控制器信息 */
+export async function rotateExternalControllerSecret() {
+  return invoke<IClashInfo>("rotate_external_controller_secret");
+}
+
+export async function getExternalControllerOrigins() {
+  return invoke<string[]>("get_external_controller_origins");
+}
+
+/**
+ * 设置外部控制器允许的 CORS 来源，应用自身的来源始终保留
+ * @param origins 例如 http://192.168.1.10:8080，"*" 表示任意来源
+ */
+export async function setExternalControllerOrigins(origins: string[]) {
+  return invoke<string[]>("set_external_controller_origins", { origins });
+}
+
 // Get runtime config which controlled by verge
 export async function getRuntimeConfig() {
   return invoke<IConfigData | null>("get_runtime_config");
@@ -78,6 +104,29 @@ export async function getRuntimeYaml() {
   return invoke<string | null>("get_runtime_yaml");
 }
 
+export async function getRuntimeSection(path: string) {
+  return invoke<string>("get_runtime_section", { path });
+}
+
+export async function patchRuntimeSection(path: string, yamlFragment: string) {
+  return invoke<void>("patch_runtime_section", { path, yamlFragment });
+}
+
+export interface ConfigGenerationMetrics {
+  generated_at: number;
+  profile_uid: string;
+  total_ms: number;
+  stages: { stage: string; duration_ms: number }[];
+  proxy_count: number;
+  group_count: number;
+  rule_count: number;
+  serialized_bytes?: number | null;
+}
+
+export async function getConfigGenerationMetrics() {
+  return invoke<ConfigGenerationMetrics[]>("get_config_generation_metrics");
+}
+
 export async function getRuntimeExists() {
   return invoke<string[]>("get_runtime_exists");
 }
@@ -96,6 +145,197 @@ export async function updateProxyChainConfigInRuntime(proxyChainConfig: any) {
   });
 }
 
+export interface ProxyChain {
+  id?: string;
+  name: string;
+  profile_uid: string;
+  hops: string[]; // 入口在前，出口在后
+  mode?: "dialer-proxy" | "relay";
+  enabled: boolean;
+  created_at?: number;
+}
+
+export async function createProxyChain(chain: ProxyChain) {
+  return invoke<string>("create_proxy_chain", { chain });
+}
+
+export async function listProxyChains(profileUid?: string) {
+  return invoke<ProxyChain[]>("list_proxy_chains", { profileUid });
+}
+
+export async function deleteProxyChain(id: string) {
+  return invoke<void>("delete_proxy_chain", { id });
+}
+
+export interface CustomRule {
+  rule_type: "DOMAIN-SUFFIX" | "IP-CIDR" | "PROCESS-NAME" | "RULE-SET";
+  payload: string;
+  target: string;
+  no_resolve?: boolean;
+}
+
+export async function getCustomRules() {
+  return invoke<CustomRule[]>("get_custom_rules");
+}
+
+export async function addCustomRule(rule: CustomRule, index?: number) {
+  return invoke<CustomRule[]>("add_custom_rule", { rule, index });
+}
+
+export async function removeCustomRule(index: number) {
+  return invoke<CustomRule[]>("remove_custom_rule", { index });
+}
+
+export async function reorderCustomRules(order: number[]) {
+  return invoke<CustomRule[]>("reorder_custom_rules", { order });
+}
+
+export interface RulesetSource {
+  name: string;
+  url: string;
+  behavior: "domain" | "ipcidr" | "classical";
+  format?: "yaml" | "text";
+  interval?: number;
+  added_at?: number;
+}
+
+export interface RulesetStatus {
+  source: RulesetSource;
+  state: {
+    etag?: string;
+    last_modified?: string;
+    last_checked?: number;
+    last_updated?: number;
+    rule_count: number;
+    file_size: number;
+    last_error?: string;
+  };
+  cached: boolean;
+  stale: boolean;
+}
+
+export async function addRulesetSource(source: RulesetSource) {
+  return invoke<RulesetStatus>("add_ruleset_source", { source });
+}
+
+export async function refreshRuleset(name: string) {
+  return invoke<RulesetStatus>("refresh_ruleset", { name });
+}
+
+export async function getRulesetStatus() {
+  return invoke<RulesetStatus[]>("get_ruleset_status");
+}
+
+export async function removeRulesetSource(name: string) {
+  return invoke<void>("remove_ruleset_source", { name });
+}
+
+export interface AppRoute {
+  process: string;
+  match_type: "PROCESS-NAME" | "PROCESS-PATH";
+  outbound: string;
+}
+
+export interface RunningApplication {
+  name: string;
+  display_name: string;
+  path?: string;
+  pid: number;
+}
+
+export async function addAppRoute(process: string, outbound: string) {
+  return invoke<AppRoute[]>("add_app_route", { process, outbound });
+}
+
+export async function removeAppRoute(process: string) {
+  return invoke<AppRoute[]>("remove_app_route", { process });
+}
+
+export async function listAppRoutes() {
+  return invoke<AppRoute[]>("list_app_routes");
+}
+
+export async function listRunningApplications() {
+  return invoke<RunningApplication[]>("list_running_applications");
+}
+
+// 网络规则的 ssid 为 "<wired>" 时匹配有线网络
+export type NetworkAction =
+  | { type: "system_proxy"; enable: boolean }
+  | { type: "tun"; enable: boolean }
+  | { type: "switch_profile"; uid: string };
+
+export interface NetworkRule {
+  ssid: string;
+  actions: NetworkAction[];
+  updated_at: number;
+}
+
+export interface NetworkContext {
+  ssid?: string;
+  wired: boolean;
+}
+
+export async function setNetworkRule(ssid: string, action: NetworkAction) {
+  return invoke<NetworkRule[]>("set_network_rule", { ssid, action });
+}
+
+export async function removeNetworkRule(ssid: string) {
+  return invoke<NetworkRule[]>("remove_network_rule", { ssid });
+}
+
+export async function listNetworkRules() {
+  return invoke<NetworkRule[]>("list_network_rules");
+}
+
+export async function getNetworkContext() {
+  return invoke<NetworkContext>("get_network_context");
+}
+
+export interface PacValidation {
+  valid: boolean;
+  error?: string;
+  samples: {
+    url: string;
+    host: string;
+    result?: string;
+    error?: string;
+  }[];
+}
+
+// 模板变量：%mixed-port% %socks-port% %http-port% %proxy-host% %bypass%
+export async function getPacScript() {
+  return invoke<string>("get_pac_script");
+}
+
+export async function setPacScript(content: string) {
+  return invoke<PacValidation>("set_pac_script", { content });
+}
+
+export async function validatePacScript(content: string) {
+  return invoke<PacValidation>("validate_pac_script", { content });
+}
+
+export interface BypassRule {
+  value: string;
+  kind: "local" | "domain" | "wildcard" | "ip" | "cidr";
+}
+
+export interface ProxyBypassRules {
+  use_default: boolean;
+  default_rules: BypassRule[];
+  rules: BypassRule[];
+  rendered: string;
+}
+
+export async function getProxyBypassRules() {
+  return invoke<ProxyBypassRules>("get_proxy_bypass_rules");
+}
+
+export async function setProxyBypassRules(rules: string[]) {
+  return invoke<ProxyBypassRules>("set_proxy_bypass_rules", { rules });
+}
+
 export async function patchClashConfig(payload: Partial<IConfigData>) {
   return invoke<void>("patch_clash_config", { payload });
 }
@@ -136,8 +376,65 @@ export async function updateGeoData() {
   return invoke<void>("update_geo_data");
 }
 
-export async function upgradeCore() {
-  return invoke<void>("upgrade_clash_core");
+export type GeoDataset = "country" | "geo_ip" | "geo_site" | "asn";
+
+export interface GeoSource {
+  name: string;
+  base_url: string;
+}
+
+export interface GeoDataSettings {
+  sources: GeoSource[];
+  use_proxy: boolean;
+}
+
+export interface GeoDatasetStatus {
+  dataset: GeoDataset;
+  file_name: string;
+  present: boolean;
+  size?: number | null;
+  sha256?: string | null;
+  version?: string | null;
+  source?: string | null;
+  updated_at?: number | null;
+  checked_at?: number | null;
+  last_result?: "updated" | "unchanged" | "failed" | null;
+  error?: string | null;
+}
+
+export interface GeoUpdateSummary {
+  started_at: number;
+  finished_at: number;
+  updated: number;
+  unchanged: number;
+  failed: number;
+}
+
+export interface GeoDataStatus {
+  settings: GeoDataSettings;
+  datasets: GeoDatasetStatus[];
+  last_run?: GeoUpdateSummary | null;
+  updating: boolean;
+}
+
+export async function getGeoDataStatus() {
+  return invoke<GeoDataStatus>("get_geo_data_status");
+}
+
+export async function setGeoDataSettings(settings: GeoDataSettings) {
+  return invoke<GeoDataSettings>("set_geo_data_settings", { settings });
+}
+
+/**
+ * 从镜像源更新地理数据集并校验 sha256
+ * @param force 为 true 时即使校验和未变化也重新下载
+ */
+export async function updateGeoDatasets(force = false) {
+  return invoke<GeoUpdateSummary>("update_geo_datasets", { force });
+}
+
+export async function upgradeCore(channel?: CoreChannel) {
+  return invoke<void>("upgrade_clash_core", { channel });
 }
 
 export async function getRules() {
@@ -172,6 +469,97 @@ export async function updateProxyAndSync(group: string, proxy: string) {
   return invoke<void>("update_proxy_and_sync", { group, proxy });
 }
 
+export interface BulkSelectionItem {
+  group: string;
+  proxy: string;
+  changed: boolean;
+  error: string | null;
+}
+
+export async function bulkUpdateProxyChoices(selections: [string, string][]) {
+  return invoke<BulkSelectionItem[]>("bulk_update_proxy_choices", {
+    selections,
+  });
+}
+
+export async function selectRegionInAllGroups(region: string) {
+  return invoke<BulkSelectionItem[]>("select_region_in_all_groups", {
+    region,
+  });
+}
+
+export async function setGroupPreferences(
+  group: string,
+  prefs: IProxyGroupPreference | null,
+) {
+  return invoke<void>("set_group_preferences", { group, prefs });
+}
+
+export interface GroupTestPolicy {
+  url?: string;
+  interval?: number; // 秒
+  tolerance?: number; // 毫秒，仅 url-test 生效
+  strategy?: "url-test" | "fallback";
+}
+
+// 所有字段为空时移除该分组的覆盖
+export async function setGroupTestPolicy(
+  group: string,
+  policy: GroupTestPolicy,
+) {
+  return invoke<Record<string, GroupTestPolicy>>("set_group_test_policy", {
+    group,
+    policy,
+  });
+}
+
+export async function listGroupTestPolicies() {
+  return invoke<Record<string, GroupTestPolicy>>("list_group_test_policies");
+}
+
+export interface GroupBandwidthResult {
+  group: string;
+  limit: { up_kbps: number; down_kbps: number };
+  applied: string[];
+  unsupported: string[];
+}
+
+// 仅 Hysteria / Hysteria2 节点支持，上传下载均为 0 时移除限制
+export async function setGroupBandwidthLimit(
+  group: string,
+  upKbps: number,
+  downKbps: number,
+) {
+  return invoke<GroupBandwidthResult>("set_group_bandwidth_limit", {
+    group,
+    upKbps,
+    downKbps,
+  });
+}
+
+export interface FavoriteNode {
+  slot: number;
+  node: string;
+  delay: number | null;
+  selected_in: string[];
+}
+
+export async function addFavoriteNode(profileUid: string, node: string) {
+  return invoke<void>("add_favorite_node", { profileUid, node });
+}
+
+export async function removeFavoriteNode(profileUid: string, node: string) {
+  return invoke<void>("remove_favorite_node", { profileUid, node });
+}
+
+export async function listFavoriteNodes() {
+  return invoke<FavoriteNode[]>("list_favorite_nodes");
+}
+
+export async function switchToFavorite(slot: number) {
+  return invoke<BulkSelectionItem[]>("switch_to_favorite", { slot });
+}
+
 export async function getProxies(): Promise<{
   global: IProxyGroupItem;
   direct: IProxyItem;
@@ -363,6 +751,134 @@ export async function getGroupProxyDelays(
   });
 }
 
+export interface CoreCapabilities {
+  version: string;
+  meta: boolean;
+  group_delay: boolean;
+  dns_query: boolean;
+  geo_update: boolean;
+  upgrade: boolean;
+}
+
+export async function getCoreCapabilities() {
+  return invoke<CoreCapabilities>("get_core_capabilities");
+}
+
+export async function queryClashDns(name: string, queryType?: string) {
+  return invoke<any>("query_clash_dns", { name, queryType });
+}
+
+export type DnsProtocol = "doh" | "dot" | "doq";
+
+export interface DnsPathResult {
+  tested: boolean;
+  latency_ms?: number | null;
+  connect_ms?: number | null;
+  success: number;
+  attempts: number;
+  poisoned?: boolean | null;
+  error?: string | null;
+}
+
+export interface DnsBenchmarkEntry {
+  upstream: string;
+  protocol: DnsProtocol;
+  direct: DnsPathResult;
+  proxied: DnsPathResult;
+  best_path?: "direct" | "proxy" | null;
+  best_latency_ms?: number | null;
+  rank?: number | null;
+}
+
+export interface DnsBenchmarkReport {
+  started_at: number;
+  finished_at: number;
+  proxy_url?: string | null;
+  entries: DnsBenchmarkEntry[];
+}
+
+/**
+ * 测试 DoH / DoT / DoQ 上游的延迟与抗污染能力（直连与经代理）
+ * @param upstreams mihomo nameserver 格式的上游列表
+ */
+export async function runDnsBenchmark(upstreams: string[]) {
+  return invoke<DnsBenchmarkReport>("run_dns_benchmark", { upstreams });
+}
+
+export async function getDnsBenchmarkReport() {
+  return invoke<DnsBenchmarkReport | null>("get_dns_benchmark_report");
+}
+
+/** 按最近一次基准测试结果写入 dns_config.yaml，返回写入内容 */
+export async function generateDnsConfigFromBenchmark() {
+  return invoke<string>("generate_dns_config_from_benchmark");
+}
+
+/** 添加或更新托管的 hosts 覆盖，与已有 hosts 冲突时报错 */
+export async function addDnsHostOverride(domain: string, ip: string) {
+  return invoke<Record<string, string>>("add_dns_host_override", {
+    domain,
+    ip,
+  });
+}
+
+export async function removeDnsHostOverride(domain: string) {
+  return invoke<Record<string, string>>("remove_dns_host_override", {
+    domain,
+  });
+}
+
+export async function listDnsHostOverrides() {
+  return invoke<Record<string, string>>("list_dns_host_overrides");
+}
+
+export interface FakeIpPreset {
+  id: string;
+  name: string;
+  patterns: string[];
+}
+
+export interface FakeIpImportResult {
+  added: string[];
+  skipped: string[];
+  invalid: string[];
+  exclusions: string[];
+}
+
+export interface FakeIpSuggestion {
+  pattern: string;
+  failures: number;
+  domains: string[];
+  processes: string[];
+  lan: boolean;
+}
+
+export async function listFakeipExclusions() {
+  return invoke<string[]>("list_fakeip_exclusions");
+}
+
+/** 添加 fake-ip 排除项，语法与 fake-ip-filter 一致 */
+export async function addFakeipExclusion(pattern: string) {
+  return invoke<string[]>("add_fakeip_exclusion", { pattern });
+}
+
+export async function removeFakeipExclusion(pattern: string) {
+  return invoke<string[]>("remove_fakeip_exclusion", { pattern });
+}
+
+export async function importFakeipExclusions(patterns: string[]) {
+  return invoke<FakeIpImportResult>("import_fakeip_exclusions", { patterns });
+}
+
+export async function getFakeipExclusionPresets() {
+  return invoke<FakeIpPreset[]>("get_fakeip_exclusion_presets");
+}
+
+/** 根据最近的连接失败日志建议排除的域名 */
+export async function suggestFakeipExclusions() {
+  return invoke<FakeIpSuggestion[]>("suggest_fakeip_exclusions");
+}
+
 export async function getTrafficData() {
   // console.log("[Traffic][Service] 开始调用 get_traffic_data");
   const result = await invoke<ITrafficItem>("get_traffic_data");
@@ -442,23 +958,30 @@ export async function getSystemMonitorOverviewSafe() {
   }
 }
 
-export async function startTrafficService() {
-  console.log("[Traffic][Service] 开始调用 start_traffic_service");
-  try {
-    const result = await invoke<void>("start_traffic_service");
-    console.log("[Traffic][Service] start_traffic_service 调用成功");
-    return result;
-  } catch (error) {
-    console.error("[Traffic][Service] start_traffic_service 调用失败:", error);
-    throw error;
-  }
+// 订阅后通过 "verge://clash-stream" 事件推送 { channel, data }，一般通过 clash-stream 服务使用
+export type StreamChannel = "traffic" | "memory" | "logs" | "connections";
+
+export async function subscribeStreamChannel(channel: StreamChannel) {
+  return invoke<void>("subscribe_clash_stream", { channel });
 }
 
-export async function stopTrafficService() {
-  console.log("[Traffic][Service] 开始调用 stop_traffic_service");
-  const result = await invoke<void>("stop_traffic_service");
-  console.log("[Traffic][Service] stop_traffic_service 调用成功");
-  return result;
+export async function unsubscribeStreamChannel(channel: StreamChannel) {
+  return invoke<void>("unsubscribe_clash_stream", { channel });
+}
+
+export interface StreamChannelStatus {
+  running: boolean;
+  connected: boolean;
+  reconnects: number;
+  messages: number;
+  dropped: number;
+  last_message_at?: number;
+}
+
+export async function getStreamStatus() {
+  return invoke<Partial<Record<StreamChannel, StreamChannelStatus>>>(
+    "get_stream_status",
+  );
 }
 
 export async function isDebugEnabled() {
@@ -473,6 +996,52 @@ export async function getClashLogs() {
   return invoke<any>("get_clash_logs");
 }
 
+export interface LogConnection {
+  network?: string;
+  source?: string;
+  process?: string;
+  destination?: string;
+  rule?: string;
+  rule_payload?: string;
+  proxy?: string;
+}
+
+export interface LogRecord {
+  time: number;
+  level: string;
+  log_type?: string;
+  message: string;
+  connection?: LogConnection;
+}
+
+export interface LogFilter {
+  levels?: string[];
+  log_type?: string;
+  keyword?: string;
+  proxy?: string;
+  rule?: string;
+}
+
+export interface LogTimeRange {
+  start?: number;
+  end?: number;
+}
+
+export async function queryLogs(
+  filter?: LogFilter,
+  range?: LogTimeRange,
+  limit?: number,
+) {
+  return invoke<LogRecord[]>("query_logs", { filter, range, limit });
+}
+
+export async function exportLogs(
+  range: LogTimeRange | undefined,
+  format: "json" | "csv" | "text",
+) {
+  return invoke<string>("export_logs", { range, format });
+}
+
 export async function startLogsMonitoring(level?: string) {
   return invoke<void>("start_logs_monitoring", { level });
 }
@@ -570,6 +1139,10 @@ export async function openLogsDir() {
   );
 }
 
+export async function setLoggingProfile(profile: "interactive" | "server") {
+  return invoke<void>("set_logging_profile", { profile });
+}
+
 export const openWebUrl = async (url: string) => {
   try {
     await invoke("open_web_url", { url });
@@ -583,8 +1156,8 @@ export async function cmdGetProxyDelay(
   timeout: number,
   url?: string,
 ) {
-  // 确保URL不为空
-  const testUrl = url || "https://cp.cloudflare.com/generate_204";
+  // 未指定URL时由后端使用配置的测速目标
+  const testUrl = url || undefined;
 
   try {
     // 不再在前端编码代理名称，由后端统一处理编码
@@ -592,7 +1165,7 @@ export async function cmdGetProxyDelay(
       "clash_api_get_proxy_delay",
       {
         name,
-        url: testUrl, // 传递经过验证的URL
+        url: testUrl,
         timeout,
       },
     );
@@ -624,16 +1197,40 @@ export async function cmdTestDelay(url: string) {
   return invoke<number>("test_delay", { url });
 }
 
-export async function invoke_uwp_tool() {
-  return invoke<void>("invoke_uwp_tool").catch((err) =>
-    showNotice("error", err?.message || err.toString(), 1500),
-  );
+export interface UwpApp {
+  name: string;
+  packageFamilyName: string;
+  version?: string | null;
+  exempted: boolean;
+}
+
+export async function listUwpApps() {
+  return invoke<UwpApp[]>("list_uwp_apps");
+}
+
+export async function setUwpLoopback(pkg: string, enabled: boolean) {
+  return invoke<void>("set_uwp_loopback", { package: pkg, enabled });
 }
 
 export async function getPortableFlag() {
   return invoke<boolean>("get_portable_flag");
 }
 
+export interface PortableBundleSummary {
+  target_dir: string;
+  files_copied: number;
+  bytes_copied: number;
+  rewritten_files: string[];
+  warnings: string[];
+}
+
+/**
+ * 创建可在移动存储上运行的便携包，目标目录必须不存在或为空
+ */
+export async function createPortableBundle(targetDir: string) {
+  return invoke<PortableBundleSummary>("create_portable_bundle", { targetDir });
+}
+
 export async function openDevTools() {
   return invoke("open_devtools");
 }
@@ -646,15 +1243,737 @@ export async function exportDiagnosticInfo() {
   return invoke("export_diagnostic_info");
 }
 
-export async function getSystemInfo() {
-  return invoke<string>("get_system_info");
+export interface DiagnosticsOptions {
+  include_verge_config?: boolean;
+  include_runtime_config?: boolean;
+  include_logs?: boolean;
+  log_lines?: number;
+  check_ports?: boolean;
+  output_dir?: string;
 }
 
-export async function copyIconFile(
-  path: string,
-  name: "common" | "sysproxy" | "tun",
-) {
-  const key = `icon_${name}_update_time`;
+export interface DiagnosticsBundle {
+  path: string;
+  size: number;
+  files: string[];
+  generated_at: number;
+}
+
+export async function generateDiagnosticsBundle(options?: DiagnosticsOptions) {
+  return invoke<DiagnosticsBundle>("generate_diagnostics_bundle", { options });
+}
+
+export interface RedactionPolicy {
+  enabled: boolean;
+  redact_exports: boolean;
+  allow_keys: string[];
+  extra_keys: string[];
+}
+
+export async function getRedactionPolicy() {
+  return invoke<RedactionPolicy>("get_redaction_policy");
+}
+
+export async function setRedactionPolicy(policy: RedactionPolicy) {
+  return invoke<RedactionPolicy>("set_redaction_policy", { policy });
+}
+
+export interface AppLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  auto_lock_minutes?: number;
+  failed_attempts: number;
+  retry_after_secs?: number;
+}
+
+export async function getAppLockStatus() {
+  return invoke<AppLockStatus>("get_app_lock_status");
+}
+
+export async function setAppPasscode(current?: string, passcode?: string) {
+  return invoke<AppLockStatus>("set_app_passcode", { current, passcode });
+}
+
+export async function setAppLockOptions(autoLockMinutes?: number) {
+  return invoke<AppLockStatus>("set_app_lock_options", {
+    autoLockMinutes,
+  });
+}
+
+export async function unlockApp(passcode: string) {
+  return invoke<AppLockStatus>("unlock_app", { passcode });
+}
+
+export async function lockApp() {
+  return invoke<AppLockStatus>("lock_app");
+}
+
+export interface ApiServerConfig {
+  enabled: boolean;
+  port: number;
+  token: string;
+}
+
+export async function getApiServerConfig() {
+  return invoke<ApiServerConfig>("get_api_server_config");
+}
+
+export async function setApiServerConfig(enabled: boolean, port?: number) {
+  return invoke<ApiServerConfig>("set_api_server_config", { enabled, port });
+}
+
+export async function regenerateApiServerToken() {
+  return invoke<ApiServerConfig>("regenerate_api_server_token");
+}
+
+export interface ProfileTemplateInfo {
+  id: string;
+  name: string;
+  description: string;
+}
+
+export interface ProfileTemplateParams {
+  name?: string;
+  provider_url?: string;
+  provider_interval?: number;
+}
+
+export async function getProfileTemplates() {
+  return invoke<ProfileTemplateInfo[]>("get_profile_templates");
+}
+
+export async function createProfileFromTemplate(
+  templateId: string,
+  params?: ProfileTemplateParams,
+) {
+  return invoke<string>("create_profile_from_template", {
+    templateId,
+    params,
+  });
+}
+
+export type ShareCredentials = "keep" | "strip" | "encrypt";
+
+export interface ShareProfileOptions {
+  credentials?: ShareCredentials;
+  passphrase?: string;
+  output_path?: string;
+}
+
+export interface SharedProfilePackage {
+  link: string;
+  file_path?: string | null;
+  credentials: ShareCredentials;
+  credential_count: number;
+}
+
+export async function shareProfile(
+  uid: string,
+  options?: ShareProfileOptions,
+) {
+  return invoke<SharedProfilePackage>("share_profile", { uid, options });
+}
+
+export async function importSharedProfile(
+  pathOrPayload: string,
+  passphrase?: string,
+) {
+  return invoke<string>("import_shared_profile", {
+    pathOrPayload,
+    passphrase,
+  });
+}
+
+export interface DuplicateNode {
+  profile_uid: string;
+  profile_name: string;
+  name: string;
+}
+
+export interface DedupReport {
+  total_nodes: number;
+  unique_nodes: number;
+  groups: { endpoint: string; nodes: DuplicateNode[] }[];
+  providers: {
+    uid: string;
+    name: string;
+    total: number;
+    duplicates: number;
+  }[];
+  merged_uid?: string;
+}
+
+export async function deduplicateNodes(strategy: "report" | "merge") {
+  return invoke<DedupReport>("deduplicate_nodes", { strategy });
+}
+
+export interface NodeRenameRule {
+  pattern: string;
+  template: string;
+  enabled: boolean;
+}
+
+export interface NodeRenamePreview {
+  original: string;
+  renamed: string;
+}
+
+export async function getNodeRenameRules() {
+  return invoke<NodeRenameRule[]>("get_node_rename_rules");
+}
+
+export async function setNodeRenameRules(rules: NodeRenameRule[]) {
+  return invoke<void>("set_node_rename_rules", { rules });
+}
+
+export async function previewNodeRename(uid: string, rules?: NodeRenameRule[]) {
+  return invoke<NodeRenamePreview[]>("preview_node_rename", { uid, rules });
+}
+
+export interface NodeFilters {
+  exclude_keywords: string[];
+  include_keywords: string[];
+  exclude_types: string[];
+  include_regions: string[];
+  exclude_regions: string[];
+  min_multiplier?: number;
+}
+
+export async function getProfileNodeFilters(uid: string) {
+  return invoke<NodeFilters>("get_profile_node_filters", { uid });
+}
+
+export async function setProfileNodeFilters(uid: string, filters: NodeFilters) {
+  return invoke<void>("set_profile_node_filters", { uid, filters });
+}
+
+export interface PortOverrides {
+  mixed_port?: number;
+  socks_port?: number;
+  http_port?: number;
+  external_controller?: string;
+  secret?: string;
+}
+
+export interface PortOverridesConfig {
+  global: PortOverrides;
+  profiles: Record<string, PortOverrides>;
+}
+
+export async function getPortOverrides() {
+  return invoke<PortOverridesConfig>("get_port_overrides");
+}
+
+export async function setPortOverrides(
+  uid: string | null,
+  overrides: PortOverrides | null,
+) {
+  return invoke<void>("set_port_overrides", { uid, overrides });
+}
+
+export interface CoreStartupStep {
+  strategy: "service" | "sidecar" | "alternate_port";
+  step: "start" | "ipc_probe" | "port_probe";
+  success: boolean;
+  message?: string;
+  duration_ms: number;
+}
+
+export interface CoreStartupReport {
+  state: "idle" | "starting" | "probing" | "ready" | "failed";
+  strategy?: CoreStartupStep["strategy"];
+  steps: CoreStartupStep[];
+  started_at?: number;
+  finished_at?: number;
+}
+
+export async function getCoreStartupReport() {
+  return invoke<CoreStartupReport>("get_core_startup_report");
+}
+
+export interface PortConflict {
+  key: "mixed-port" | "socks-port" | "port" | "external-controller";
+  port: number;
+  pid?: number | null;
+  process?: string | null;
+  remapped_to?: number | null;
+}
+
+export async function getPortConflicts() {
+  return invoke<PortConflict[]>("get_port_conflicts");
+}
+
+export type CaptivePortalPolicy =
+  | "off"
+  | "notify"
+  | "disable_sysproxy"
+  | "direct";
+
+export async function setCaptivePortalPolicy(policy: CaptivePortalPolicy) {
+  return invoke<void>("set_captive_portal_policy", { policy });
+}
+
+export type CoreChannel = "stable" | "alpha" | "smart";
+
+export interface InstalledCore {
+  id: string;
+  channel: CoreChannel;
+  version: string;
+  path: string;
+  sha256: string;
+  installed_at: number;
+}
+
+export interface AvailableCore {
+  id: string;
+  channel: CoreChannel | null;
+  version: string | null;
+  bundled: boolean;
+  path: string;
+  sha256: string | null;
+  installed_at: number | null;
+  pinned_by: string[];
+  is_default: boolean;
+}
+
+export async function listAvailableCores() {
+  return invoke<AvailableCore[]>("list_available_cores");
+}
+
+export async function installCore(channel: CoreChannel, sha256?: string) {
+  return invoke<InstalledCore>("install_core", { channel, sha256 });
+}
+
+export async function removeCore(id: string) {
+  return invoke<void>("remove_core", { id });
+}
+
+export async function setProfileCore(uid: string, coreId: string | null) {
+  return invoke<void>("set_profile_core", { uid, coreId });
+}
+
+export interface CoreUpgradeStatus {
+  stage:
+    | "idle"
+    | "downloading"
+    | "validating"
+    | "switching"
+    | "completed"
+    | "rolled_back"
+    | "failed";
+  channel: CoreChannel | null;
+  version: string | null;
+  downloaded: number;
+  total: number | null;
+  error: string | null;
+  started_at: number | null;
+  finished_at: number | null;
+}
+
+export interface CoreReleaseNotes {
+  channel: CoreChannel;
+  version: string;
+  published_at: string | null;
+  notes: string;
+}
+
+export async function getCoreReleaseNotes(channel: CoreChannel) {
+  return invoke<CoreReleaseNotes>("get_core_release_notes", { channel });
+}
+
+export async function getCoreUpgradeStatus() {
+  return invoke<CoreUpgradeStatus>("get_core_upgrade_status");
+}
+
+export interface AppUpdateInfo {
+  current_version: string;
+  version: string;
+  available: boolean;
+  notes: string;
+  published_at: string | null;
+  asset_name: string | null;
+  size: number | null;
+}
+
+export interface AppUpdateProgress {
+  version: string;
+  downloaded: number;
+  total: number | null;
+}
+
+export async function checkAppUpdate() {
+  return invoke<AppUpdateInfo>("check_app_update");
+}
+
+export async function downloadAppUpdate() {
+  return invoke<string>("download_app_update");
+}
+
+export async function installAppUpdate() {
+  return invoke<void>("install_app_update");
+}
+
+export interface UnlockCheckDefinition {
+  name: string;
+  url: string;
+  expected_markers: string[];
+  blocked_markers?: string[];
+  markers_are_regex?: boolean;
+  region_regex?: string | null;
+  timeout_secs?: number | null;
+}
+
+export async function getUnlockCheckDefinitions() {
+  return invoke<UnlockCheckDefinition[]>("get_unlock_check_definitions");
+}
+
+export async function addUnlockCheckDefinition(
+  definition: UnlockCheckDefinition,
+) {
+  return invoke<void>("add_unlock_check_definition", { definition });
+}
+
+export async function removeUnlockCheckDefinition(name: string) {
+  return invoke<void>("remove_unlock_check_definition", { name });
+}
+
+export interface UnlockResult {
+  name: string;
+  status: string;
+  region: string | null;
+  check_time: string | null;
+}
+
+export interface UnlockMatrix {
+  services: string[];
+  nodes: {
+    node: string;
+    checked_at: number;
+    results: Record<string, UnlockResult>;
+  }[];
+}
+
+export async function checkUnlockForNode(
+  profileUid: string,
+  node: string,
+  services?: string[],
+  force?: boolean,
+) {
+  return invoke<UnlockResult[]>("check_unlock_for_node", {
+    profileUid,
+    node,
+    services,
+    force,
+  });
+}
+
+export async function getUnlockMatrix(profileUid: string) {
+  return invoke<UnlockMatrix>("get_unlock_matrix", { profileUid });
+}
+
+export interface Ipv6Status {
+  host_supported: boolean;
+  host_address: string | null;
+  core_ipv6_enabled: boolean;
+  node_supported: boolean | null;
+  node_address: string | null;
+  checked_at: number;
+}
+
+export async function getIpv6Status() {
+  return invoke<Ipv6Status>("get_ipv6_status");
+}
+
+export interface DelayTestTarget {
+  name: string;
+  url: string;
+  expected_status?: string | null;
+}
+
+export interface DelayTargetResult {
+  name: string;
+  url: string;
+  delay: number | null;
+  error: string | null;
+}
+
+export async function getDelayTestPresets() {
+  return invoke<DelayTestTarget[]>("get_delay_test_presets");
+}
+
+export async function getDelayTestTargets() {
+  return invoke<DelayTestTarget[]>("get_delay_test_targets");
+}
+
+export async function setDelayTestTargets(targets: DelayTestTarget[]) {
+  return invoke<void>("set_delay_test_targets", { targets });
+}
+
+export async function testDelayTargets() {
+  return invoke<DelayTargetResult[]>("test_delay_targets");
+}
+
+export interface SubscriptionUsage {
+  uid: string;
+  name: string;
+  upload: number;
+  download: number;
+  used: number;
+  total: number;
+  remaining: number | null;
+  used_percentage: number | null;
+  expire: number | null;
+  expire_in_days: number | null;
+  is_expired: boolean;
+  updated_at: number | null;
+  history: { time: number; used: number }[];
+}
+
+export async function getSubscriptionUsage(uid: string) {
+  return invoke<SubscriptionUsage | null>("get_subscription_usage", { uid });
+}
+
+export async function getAllSubscriptionUsage() {
+  return invoke<SubscriptionUsage[]>("get_all_subscription_usage");
+}
+
+export interface ExpiryReminder {
+  enabled: boolean;
+  days_before: number;
+}
+
+export interface ExpiryReminderConfig {
+  global: ExpiryReminder;
+  profiles: Record<string, ExpiryReminder>;
+}
+
+export async function getExpiringSubscriptions(days: number) {
+  return invoke<SubscriptionUsage[]>("get_expiring_subscriptions", { days });
+}
+
+export async function getExpiryReminderConfig() {
+  return invoke<ExpiryReminderConfig>("get_expiry_reminder_config");
+}
+
+export async function setExpiryReminder(
+  uid?: string,
+  reminder?: ExpiryReminder,
+) {
+  return invoke<void>("set_expiry_reminder", { uid, reminder });
+}
+
+export interface SpeedTestRunInfo {
+  run_id: string;
+  finished_at: number;
+  total_nodes: number;
+  successful_tests: number;
+  failed_tests: number;
+  duration_seconds: number;
+  best_node: string | null;
+}
+
+export async function listSpeedTestRuns() {
+  return invoke<SpeedTestRunInfo[]>("list_speed_test_runs");
+}
+
+export async function exportSpeedTestReport(
+  runId: string,
+  format: "html" | "json" | "csv",
+) {
+  return invoke<string | null>("export_speed_test_report", { runId, format });
+}
+
+export interface EventWebhookConfig {
+  enabled: boolean;
+  webhooks: { url: string; headers?: Record<string, string> }[];
+  mqtt?: {
+    broker_url: string;
+    client_id?: string | null;
+    username?: string | null;
+    password?: string | null;
+    topic_prefix: string;
+  } | null;
+  events: {
+    core_started: boolean;
+    core_stopped: boolean;
+    profile_updated: boolean;
+    quota_exceeded: boolean;
+    speed_test_finished: boolean;
+    best_node_changed: boolean;
+  };
+  retry: { max_retries: number; retry_interval_secs: number };
+}
+
+export async function getEventWebhooks() {
+  return invoke<EventWebhookConfig>("get_event_webhooks");
+}
+
+export async function setEventWebhooks(config: EventWebhookConfig) {
+  return invoke<void>("set_event_webhooks", { config });
+}
+
+export async function testEventWebhooks() {
+  return invoke<string[]>("test_event_webhooks");
+}
+
+export interface RouteExplanation {
+  host: string;
+  port: number;
+  process: string | null;
+  resolved_ips: string[];
+  matched: {
+    index: number;
+    rule: string;
+    rule_type: string;
+    payload: string;
+    target: string;
+    sub_rule: string | null;
+    source: {
+      kind:
+        | "custom_rules"
+        | "app_routes"
+        | "rules_item"
+        | "merge"
+        | "global_merge"
+        | "profile"
+        | "generated";
+      uid: string | null;
+      name: string | null;
+    };
+  } | null;
+  proxy_chain: string[];
+  outbound: string | null;
+  skipped: { index: number; rule: string; reason: string }[];
+}
+
+export async function explainRoute(
+  host: string,
+  port: number,
+  process?: string,
+) {
+  return invoke<RouteExplanation>("explain_route", { host, port, process });
+}
+
+export interface DryRunResult {
+  valid: boolean;
+  validation_output: string;
+  changes: {
+    path: string;
+    kind: "added" | "removed" | "modified";
+    before: any;
+    after: any;
+  }[];
+  staged: boolean;
+  checked_at: number;
+}
+
+export async function dryRunPatchClashConfig(payload: Partial<IConfigData>) {
+  return invoke<DryRunResult>("dry_run_patch_clash_config", { payload });
+}
+
+export async function applyStagedPatch() {
+  return invoke<void>("apply_staged_patch");
+}
+
+export async function discardStagedPatch() {
+  return invoke<void>("discard_staged_patch");
+}
+
+export interface SandboxTestReport {
+  uid: string;
+  name: string;
+  started: boolean;
+  startup_ms: number | null;
+  error: string | null;
+  core_output: string[];
+  connectivity: DelayTargetResult[];
+  total_proxies: number;
+  available_proxies: number;
+  proxy_delays: { name: string; proxy_type: string; delay: number | null }[];
+  duration_ms: number;
+}
+
+export async function testProfileInSandbox(uid: string) {
+  return invoke<SandboxTestReport>("test_profile_in_sandbox", { uid });
+}
+
+export interface HotkeyActionInfo {
+  action: string;
+  parameterized: boolean;
+}
+
+export async function getHotkeyActions() {
+  return invoke<HotkeyActionInfo[]>("get_hotkey_actions");
+}
+
+export async function setHotkey(action: string, accelerator?: string | null) {
+  return invoke<string[]>("set_hotkey", {
+    action,
+    accelerator: accelerator ?? null,
+  });
+}
+
+export interface ResourceMonitorConfig {
+  enabled: boolean;
+  interval_secs: number;
+  app_memory_limit_mb: number;
+  core_memory_limit_mb: number;
+  core_cpu_limit_percent: number;
+  sustained_samples: number;
+  auto_mitigate: boolean;
+}
+
+export interface ResourceSample {
+  timestamp: number;
+  app_memory: number;
+  app_cpu: number;
+  core_pid: number | null;
+  core_memory: number | null;
+  core_cpu: number | null;
+}
+
+export type MitigationAction =
+  | "clash_gc"
+  | "close_connections"
+  | "trim_log_buffer"
+  | "suggest_core_restart";
+
+export interface MitigationRecord {
+  timestamp: number;
+  action: MitigationAction;
+  reason: string;
+  success: boolean;
+  message: string | null;
+}
+
+export async function getResourceUsageHistory(minutes?: number) {
+  return invoke<{ samples: ResourceSample[]; mitigations: MitigationRecord[] }>(
+    "get_resource_usage_history",
+    { minutes: minutes ?? null },
+  );
+}
+
+export async function getResourceMonitorConfig() {
+  return invoke<ResourceMonitorConfig>("get_resource_monitor_config");
+}
+
+export async function setResourceMonitorConfig(config: ResourceMonitorConfig) {
+  return invoke<void>("set_resource_monitor_config", { config });
+}
+
+export async function runResourceMitigation(action: MitigationAction) {
+  return invoke<MitigationRecord>("run_resource_mitigation", { action });
+}
+
+export async function getSystemInfo() {
+  return invoke<string>("get_system_info");
+}
+
+export async function copyIconFile(
+  path: string,
+  name: "common" | "sysproxy" | "tun",
+) {
+  const key = `icon_${name}_update_time`;
   const previousTime = localStorage.getItem(key) || "";
 
   const currentTime = String(Date.now());
@@ -673,6 +1992,27 @@ export async function downloadIconCache(url: string, name: string) {
   return invoke<string>("download_icon_cache", { url, name });
 }
 
+export interface SysproxyBackendStatus {
+  backend: "gsettings" | "kde" | "env-file";
+  available: boolean;
+  active: boolean;
+  detail?: string | null;
+  last_apply?: {
+    success: boolean;
+    error?: string | null;
+    time: number;
+  } | null;
+}
+
+export interface SysproxyBackendReport {
+  desktop?: string | null;
+  backends: SysproxyBackendStatus[];
+}
+
+export async function getSysproxyBackendStatus() {
+  return invoke<SysproxyBackendReport>("get_sysproxy_backend_status");
+}
+
 export async function getNetworkInterfaces() {
   return invoke<string[]>("get_network_interfaces");
 }
@@ -685,6 +2025,55 @@ export async function getNetworkInterfacesInfo() {
   return invoke<INetworkInterface[]>("get_network_interfaces_info");
 }
 
+export interface LanSharingOptions {
+  bindAddress?: string;
+  openFirewall?: boolean;
+  authentication?: string; // 未配置代理认证时必填，格式为 用户名:密码
+}
+
+export interface LanShareSummary {
+  port: number;
+  bindAddress: string;
+  lanIps: string[];
+  httpProxy?: string | null;
+  socksProxy?: string | null;
+  qrPayload?: string | null;
+  authRequired: boolean;
+  firewall: {
+    backend?: "windows" | "ufw" | "firewalld" | null;
+    opened: boolean;
+    message?: string | null;
+  };
+}
+
+export async function enableLanSharing(options: LanSharingOptions) {
+  return invoke<LanShareSummary>("enable_lan_sharing", { options });
+}
+
+export async function disableLanSharing() {
+  return invoke<void>("disable_lan_sharing");
+}
+
+export interface FirewallRuleStatus {
+  name: string;
+  protocol: string;
+  port: number;
+  present: boolean;
+}
+
+export interface FirewallRulesStatus {
+  rules: FirewallRuleStatus[];
+  changed: boolean;
+}
+
+export async function ensureFirewallRules() {
+  return invoke<FirewallRulesStatus>("ensure_firewall_rules");
+}
+
+export async function removeFirewallRules() {
+  return invoke<boolean>("remove_firewall_rules");
+}
+
 export async function createWebdavBackup() {
   return invoke<void>("create_webdav_backup");
 }
@@ -755,6 +2144,45 @@ export const repairService = async () => {
   return invoke<void>("repair_service");
 };
 
+export type ServiceIssue =
+  | "binary_missing"
+  | "installer_missing"
+  | "permission_denied"
+  | "not_installed"
+  | "not_running"
+  | "ipc_unreachable"
+  | "version_mismatch";
+
+export interface ServiceDiagnostics {
+  healthy: boolean;
+  version: string | null;
+  required_version: string;
+  steps: {
+    check: string;
+    passed: boolean;
+    reason: ServiceIssue | null;
+    detail: string;
+  }[];
+  fixes: ("fix_permissions" | "install" | "start" | "reinstall")[];
+}
+
+// Linux 下为内核授予 TUN 所需的 capabilities
+export const setupLinuxCapabilities = async () => {
+  return invoke<{ path: string; granted: boolean }[]>(
+    "setup_linux_capabilities",
+  );
+};
+
+// 当前内核是否已获得 TUN 所需的 capabilities（仅 Linux）
+export const hasTunCapabilities = async () => {
+  return invoke<boolean>("has_tun_capabilities");
+};
+
+// 诊断系统服务
+export const diagnoseService = async () => {
+  return invoke<ServiceDiagnostics>("diagnose_service");
+};
+
 // 系统服务是否可用
 export const isServiceAvailable = async () => {
   try {
@@ -772,6 +2200,49 @@ export const exit_lightweight_mode = async () => {
   return invoke<void>("exit_lightweight_mode");
 };
 
+export interface LightweightPolicy {
+  enter_on_idle: boolean;
+  idle_minutes: number;
+  exit_on_tray: boolean;
+  exit_on_hotkey: boolean;
+  unload_webview: boolean;
+}
+
+export interface LightweightStatus {
+  state: "normal" | "lightweight" | "exiting";
+  entered_at: number | null;
+  idle_watch_active: boolean;
+  idle_deadline: number | null;
+  webview_loaded: boolean;
+  policy: LightweightPolicy;
+}
+
+export const getLightweightStatus = async () => {
+  return invoke<LightweightStatus>("get_lightweight_status");
+};
+
+export const setLightweightPolicy = async (policy: LightweightPolicy) => {
+  return invoke<LightweightStatus>("set_lightweight_policy", { policy });
+};
+
+export interface OfflineStatus {
+  active: boolean;
+  reason: "update_failures" | "no_network" | null;
+  since: number | null;
+  consecutive_failures: number;
+  last_error: string | null;
+  known_good_at: number | null;
+  pinned: boolean;
+}
+
+export const getOfflineStatus = async () => {
+  return invoke<OfflineStatus>("get_offline_status");
+};
+
+export const exitOfflineMode = async () => {
+  return invoke<void>("exit_offline_mode");
+};
+
 export const isAdmin = async () => {
   try {
     return await invoke<boolean>("is_admin");
@@ -785,6 +2256,63 @@ export async function getNextUpdateTime(uid: string) {
   return invoke<number | null>("get_next_update_time", { uid });
 }
 
+export interface QuickPatch {
+  yaml: string;
+  updated_at: number;
+}
+
+/**
+ * 设置订阅快速补丁，yaml 为空时删除
+ */
+export async function setQuickPatch(uid: string, yaml: string) {
+  return invoke<void>("set_quick_patch", { uid, yaml });
+}
+
+/**
+ * 获取订阅快速补丁
+ */
+export async function getQuickPatch(uid: string) {
+  return invoke<QuickPatch | null>("get_quick_patch", { uid });
+}
+
+// ===== 启动恢复相关 =====
+
+export interface StartupFailure {
+  profile_uid?: string;
+  profile_name?: string;
+  stage: "generate" | "validate";
+  error: string;
+  occurred_at: number;
+}
+
+export interface StartupRecoveryOptions {
+  failure: StartupFailure;
+  candidates: { uid: string; name: string; itype: string }[];
+  can_open_editor: boolean;
+}
+
+export type StartupRecoveryChoice =
+  | { action: "retry" }
+  | { action: "select_profile"; uid: string }
+  | { action: "open_editor" }
+  | { action: "start_minimal" };
+
+/**
+ * 获取启动恢复选项，启动正常时返回 null
+ */
+export async function getStartupRecoveryOptions() {
+  return invoke<StartupRecoveryOptions | null>(
+    "get_startup_recovery_options",
+  );
+}
+
+/**
+ * 执行启动恢复选择，返回是否已恢复正常
+ */
+export async function resolveStartupRecovery(choice: StartupRecoveryChoice) {
+  return invoke<boolean>("resolve_startup_recovery", { choice });
+}
+
 // ===== 订阅健康检查相关 =====
 
 export interface SubscriptionHealthResult {
@@ -797,6 +2325,27 @@ export interface SubscriptionHealthResult {
   last_update?: number;
   error_message?: string;
   last_checked: number;
+  report?: SubscriptionHealthReport;
+}
+
+export interface SubscriptionHealthReport {
+  checked_at: number;
+  http_status?: number;
+  error_kind?: "timeout" | "connect" | "tls" | "http_status" | "body";
+  error_message?: string;
+  tls_error?: string;
+  response_time?: number; // 毫秒
+  content_length?: number;
+  userinfo?: {
+    upload: number;
+    download: number;
+    total: number;
+    expire: number;
+  };
+  expires_in_days?: number;
+  traffic_used_percent?: number;
+  node_count?: number;
+  node_count_delta?: number;
 }
 
 export interface BatchHealthResult {
@@ -829,6 +2378,16 @@ export async function getSubscriptionDetails(uid: string) {
   return invoke<SubscriptionHealthResult>("get_subscription_details", { uid });
 }
 
+/**
+ * 获取订阅的诊断历史（从新到旧）
+ */
+export async function getSubscriptionHealthHistory(uid: string) {
+  return invoke<SubscriptionHealthReport[]>(
+    "get_subscription_health_history",
+    { uid },
+  );
+}
+
 /**
  * 清理健康检查缓存
  */
@@ -836,6 +2395,34 @@ export async function cleanupHealthCheckCache() {
   return invoke<void>("cleanup_health_check_cache");
 }
 
+export interface ProviderOutage {
+  id: string;
+  uid: string;
+  name: string;
+  kind: "endpoint" | "nodes";
+  message: string;
+  consecutive_failures: number;
+  started_at: number;
+  last_seen: number;
+  resolved_at?: number;
+  acknowledged: boolean;
+  alert_id?: string;
+}
+
+/**
+ * 获取订阅故障列表（未恢复的在前）
+ */
+export async function getProviderOutages() {
+  return invoke<ProviderOutage[]>("get_provider_outages");
+}
+
+/**
+ * 确认订阅故障
+ */
+export async function acknowledgeOutage(id: string) {
+  return invoke<void>("acknowledge_outage", { id });
+}
+
 // ===== 批量导入相关 =====
 
 export interface BatchImportResult {
@@ -870,12 +2457,14 @@ export interface BatchImportOptions {
 export async function batchImportFromText(
   textContent: string,
   options?: BatchImportOptions,
+  taskId?: string,
 ) {
   // 兼容不同后端参数命名（snake_case 与 camelCase）
   return invoke<BatchImportResult>("batch_import_from_text", {
     text_content: textContent,
     textContent: textContent,
     options,
+    taskId,
   });
 }
 
@@ -885,12 +2474,14 @@ export async function batchImportFromText(
 export async function batchImportFromFile(
   filePath: string,
   options?: BatchImportOptions,
+  taskId?: string,
 ) {
   // 兼容不同后端参数命名（snake_case 与 camelCase）
   return invoke<BatchImportResult>("batch_import_from_file", {
     file_path: filePath,
     filePath: filePath,
     options,
+    taskId,
   });
 }
 
@@ -916,6 +2507,119 @@ export async function previewBatchImport(
   });
 }
 
+// ===== 订阅索引 =====
+
+export interface SubscriptionIndexOptions {
+  name?: string | null;
+  update_interval_hours: number; // 0 表示仅手动同步
+  remove_missing: boolean; // 索引中移除的条目同时删除由索引导入的订阅
+  user_agent?: string | null;
+}
+
+export interface SubscriptionIndex {
+  id: string;
+  url: string;
+  options: SubscriptionIndexOptions;
+  members: { url: string; uid: string; owned: boolean }[];
+  last_sync: number | null;
+  last_error: string | null;
+}
+
+export interface SubscriptionIndexSyncResult {
+  index_id: string;
+  unchanged: boolean;
+  total: number;
+  kept: number;
+  added: ImportResult[];
+  removed: string[];
+}
+
+/**
+ * 导入订阅索引（订阅的订阅）
+ */
+export async function importSubscriptionIndex(
+  url: string,
+  options?: SubscriptionIndexOptions,
+) {
+  return invoke<SubscriptionIndexSyncResult>("import_subscription_index", {
+    url,
+    options: options ?? null,
+  });
+}
+
+export async function syncSubscriptionIndex(indexId: string) {
+  return invoke<SubscriptionIndexSyncResult>("sync_subscription_index", {
+    indexId,
+  });
+}
+
+export async function listSubscriptionIndexes() {
+  return invoke<SubscriptionIndex[]>("list_subscription_indexes");
+}
+
+export async function removeSubscriptionIndex(
+  indexId: string,
+  deleteMembers: boolean,
+) {
+  return invoke<void>("remove_subscription_index", { indexId, deleteMembers });
+}
+
+// ===== 订阅地址迁移 =====
+
+export interface UrlMigrationResult {
+  uid: string;
+  name: string | null;
+  old_url: string;
+  new_url: string;
+  success: boolean;
+  error: string | null;
+}
+
+export interface SubscriptionUrlHistoryEntry {
+  url: string;
+  replaced_at: number;
+}
+
+/**
+ * 替换订阅地址（服务商轮换 token 时使用）
+ */
+export async function replaceSubscriptionUrl(
+  uid: string,
+  newUrl: string,
+  keepHistory?: boolean,
+) {
+  return invoke<UrlMigrationResult>("replace_subscription_url", {
+    uid,
+    newUrl,
+    keepHistory: keepHistory ?? null,
+  });
+}
+
+/**
+ * 按正则批量改写同一域名下的订阅地址
+ */
+export async function bulkReplaceSubscriptionUrls(
+  domain: string,
+  pattern: string,
+  replacement: string,
+  options?: { keepHistory?: boolean; dryRun?: boolean },
+) {
+  return invoke<UrlMigrationResult[]>("bulk_replace_subscription_urls", {
+    domain,
+    pattern,
+    replacement,
+    keepHistory: options?.keepHistory ?? null,
+    dryRun: options?.dryRun ?? null,
+  });
+}
+
+export async function getSubscriptionUrlHistory(uid: string) {
+  return invoke<SubscriptionUrlHistoryEntry[]>(
+    "get_subscription_url_history",
+    { uid },
+  );
+}
+
 // ===== 远程订阅抓取 =====
 
 export interface RemoteSubscriptionConfig {
@@ -931,6 +2635,22 @@ export interface RemoteSubscriptionConfig {
     failed: number;
     message?: string | null;
   } | null;
+  fetch_timeout_secs?: number | null;
+}
+
+export interface ProfileFetchStatistics {
+  uid: string;
+  name?: string | null;
+  requests: number;
+  successes: number;
+  failures: number;
+  total_bytes: number;
+  last_duration_ms: number | null;
+  avg_duration_ms: number | null;
+  last_status: number | null;
+  last_http_version: string | null;
+  last_error: string | null;
+  last_fetched_at: number | null;
 }
 
 export interface FetchPreviewItem {
@@ -978,6 +2698,10 @@ export async function syncSubscriptionFromRemote(sourceUrl?: string) {
   });
 }
 
+export async function getFetchStatistics() {
+  return invoke<ProfileFetchStatistics[]>("get_fetch_statistics");
+}
+
 // ===== 批量导出相关 =====
 
 export interface ExportOptions {
@@ -987,6 +2711,7 @@ export interface ExportOptions {
   compress: boolean; // 是否压缩
   encrypt: boolean; // 是否加密
   password?: string; // 加密密码
+  redact?: boolean; // 是否脱敏，未指定时使用脱敏策略
 }
 
 export interface ExportPreview {
@@ -1014,12 +2739,14 @@ export interface ExportableSubscription {
 export async function batchExportSubscriptions(
   subscriptionUids: string[],
   options: ExportOptions,
+  taskId?: string,
 ) {
   // 兼容 snake_case 与 camelCase
   return invoke<string>("batch_export_subscriptions", {
     subscription_uids: subscriptionUids,
     subscriptionUids: subscriptionUids,
     options,
+    taskId,
   });
 }
 
@@ -1030,6 +2757,7 @@ export async function exportSubscriptionsToFile(
   subscriptionUids: string[],
   filePath: string,
   options: ExportOptions,
+  taskId?: string,
 ) {
   // 兼容 snake_case 与 camelCase
   return invoke<void>("export_subscriptions_to_file", {
@@ -1038,6 +2766,7 @@ export async function exportSubscriptionsToFile(
     file_path: filePath,
     filePath: filePath,
     options,
+    taskId,
   });
 }
 
@@ -1069,7 +2798,14 @@ export interface TaskConfig {
   id: string;
   name: string;
   description: string;
-  task_type: "SubscriptionUpdate" | "HealthCheck" | "AutoCleanup" | "Custom";
+  task_type:
+    | "SubscriptionUpdate"
+    | "HealthCheck"
+    | "AutoCleanup"
+    | "ExpiryReminder"
+    | "TrafficReport"
+    | "GeoDataUpdate"
+    | "Custom";
   status: "Active" | "Paused" | "Disabled" | "Error";
   interval_minutes: number;
   enabled: boolean;
@@ -1218,6 +2954,7 @@ export interface NodeTestResult {
   upload_speed_mbps?: number;
   packet_loss_rate?: number;
   stability_score?: number;
+  jitter_ms?: number;
   error_message?: string;
   test_duration_ms: number;
   test_time: number;
@@ -1236,6 +2973,7 @@ export interface SubscriptionTestResult {
   avg_download_speed_mbps?: number;
   avg_upload_speed_mbps?: number;
   overall_stability_score?: number;
+  avg_jitter_ms?: number;
   quality_grade: "Excellent" | "Good" | "Fair" | "Poor" | "VeryPoor";
   node_results: NodeTestResult[];
   recommendations: string[];
@@ -1277,6 +3015,8 @@ export interface TestConfig {
   test_urls: string[];
   skip_speed_test: boolean;
   skip_stability_test: boolean;
+  packet_loss_sample_count?: number;
+  ping_method?: "Tcp" | "Icmp";
 }
 
 export type TestType =
@@ -1307,10 +3047,12 @@ export async function testSubscription(
 export async function testAllSubscriptions(
   testType: TestType,
   config?: TestConfig,
+  taskId?: string,
 ) {
   return invoke<BatchTestResult>("test_all_subscriptions", {
     test_type: testType,
     config,
+    taskId,
   });
 }
 
@@ -1429,7 +3171,8 @@ export interface TrafficAlert {
     | "ExpirationDate"
     | "HighUsage"
     | "SpeedDrop"
-    | "ConnectionIssue";
+    | "ConnectionIssue"
+    | "UsageSpike";
   message: string;
   threshold_value: number;
   current_value: number;
@@ -1463,6 +3206,18 @@ export interface TrafficPrediction {
   trend_direction: "Increasing" | "Stable" | "Decreasing";
 }
 
+export type AnomalyRange = "Day" | "Week" | "Month";
+
+export interface TrafficAnomaly {
+  subscription_uid: string;
+  subscription_name: string;
+  hour_start: number;
+  actual_bytes: number;
+  expected_bytes: number;
+  score: number;
+  severity: "Info" | "Warning" | "Critical" | "Emergency";
+}
+
 /**
  * 记录流量使用
  */
@@ -1556,6 +3311,20 @@ export async function setSubscriptionQuota(
   });
 }
 
+/**
+ * 获取订阅额度告警阈值（已用比例，0-1）
+ */
+export async function getQuotaAlertThresholds() {
+  return invoke<number[]>("get_quota_alert_thresholds");
+}
+
+/**
+ * 设置订阅额度告警阈值（已用比例，0-1）
+ */
+export async function setQuotaAlertThresholds(thresholds: number[]) {
+  return invoke<void>("set_quota_alert_thresholds", { thresholds });
+}
+
 /**
  * 获取流量预测
  */
@@ -1565,6 +3334,74 @@ export async function getTrafficPrediction(subscriptionUid: string) {
   });
 }
 
+/**
+ * 获取流量异常（单小时流量突增）
+ */
+export async function getTrafficAnomalies(range: AnomalyRange) {
+  return invoke<TrafficAnomaly[]>("get_traffic_anomalies", { range });
+}
+
+// ===== 月度流量报告 =====
+
+export interface TrafficUsageEntry {
+  name: string;
+  upload_bytes: number;
+  download_bytes: number;
+  total_bytes: number;
+  share: number;
+}
+
+export interface QuotaCompliance {
+  quota_bytes: number;
+  used_bytes: number;
+  usage_ratio: number;
+  warning_threshold: number;
+  status: "Within" | "Warning" | "Exceeded";
+}
+
+export interface SubscriptionReportEntry {
+  subscription_uid: string;
+  subscription_name: string;
+  upload_bytes: number;
+  download_bytes: number;
+  total_bytes: number;
+  session_count: number;
+  share: number;
+  quota?: QuotaCompliance;
+}
+
+export interface TrafficReport {
+  month: string;
+  generated_at: number;
+  total_bytes: number;
+  subscriptions: SubscriptionReportEntry[];
+  apps: TrafficUsageEntry[];
+  regions: TrafficUsageEntry[];
+  peak_hours: { hour: number; total_bytes: number }[];
+}
+
+/**
+ * 生成并保存月度流量报告（month 格式为 YYYY-MM）
+ */
+export async function generateTrafficReport(month: string) {
+  return invoke<TrafficReport>("generate_traffic_report", { month });
+}
+
+export async function listTrafficReports() {
+  return invoke<string[]>("list_traffic_reports");
+}
+
+export async function getTrafficReport(month: string) {
+  return invoke<TrafficReport>("get_traffic_report", { month });
+}
+
+/**
+ * 导出月度流量报告为 CSV，用户取消时返回 null
+ */
+export async function exportTrafficReportCsv(month: string) {
+  return invoke<string | null>("export_traffic_report_csv", { month });
+}
+
 // ===== 订阅分组相关 =====
 
 export interface SubscriptionGroup {
@@ -1579,6 +3416,7 @@ export interface SubscriptionGroup {
   is_favorite: boolean;
   sort_order: number;
   auto_rules: AutoRule[];
+  grouping_rule?: GroupingRule;
   created_at: number;
   updated_at: number;
 }
@@ -1608,6 +3446,39 @@ export interface AutoRule {
   is_enabled: boolean;
 }
 
+export type GroupingRule =
+  | { op: "and"; rules: GroupingRule[] }
+  | { op: "or"; rules: GroupingRule[] }
+  | {
+      op: "condition";
+      attribute:
+        | "name"
+        | "provider_domain"
+        | "node_count"
+        | "region_count"
+        | "region_ratio"
+        | "protocol_ratio"
+        | "avg_latency";
+      key?: string; // region_ratio 的地区代码或 protocol_ratio 的协议
+      condition: AutoRule["condition"];
+      value: string;
+    };
+
+export interface SubscriptionMetadata {
+  uid: string;
+  name?: string;
+  provider_domain?: string;
+  node_count: number;
+  regions: Record<string, number>;
+  protocols: Record<string, number>;
+  avg_latency_ms?: number;
+}
+
+export interface GroupingEvaluation {
+  matched: boolean;
+  metadata: SubscriptionMetadata;
+}
+
 export interface GroupStatistics {
   group_id: string;
   group_name: string;
@@ -1618,6 +3489,31 @@ export interface GroupStatistics {
   avg_speed_mbps: number;
   health_score: number;
   last_updated: number;
+  quality?: GroupQualityReport;
+}
+
+export interface GroupSubscriptionQuality {
+  uid: string;
+  name: string;
+  quality_grade: SubscriptionTestResult["quality_grade"];
+  total_nodes: number;
+  passed_nodes: number;
+  avg_latency_ms?: number;
+}
+
+export interface GroupQualityReport {
+  group_id: string;
+  test_type: TestType;
+  total_nodes: number;
+  working_nodes: number;
+  median_latency_ms?: number;
+  avg_latency_ms?: number;
+  avg_speed_mbps?: number;
+  best_subscription?: GroupSubscriptionQuality;
+  region_coverage: Record<string, number>;
+  subscriptions: GroupSubscriptionQuality[];
+  errors: string[];
+  tested_at: number;
 }
 
 export interface BatchOperationResult {
@@ -1747,6 +3643,26 @@ export async function applyAutoGroupingRules() {
   return invoke<BatchOperationResult>("apply_auto_grouping_rules");
 }
 
+/**
+ * 试算分组规则
+ */
+export async function evaluateGroupingRule(rule: GroupingRule, uid: string) {
+  return invoke<GroupingEvaluation>("evaluate_grouping_rule", { rule, uid });
+}
+
+/**
+ * 分组测速，生成分组质量报告
+ */
+export async function testSubscriptionGroup(
+  groupId: string,
+  testType: TestType,
+) {
+  return invoke<GroupQualityReport>("test_subscription_group", {
+    groupId,
+    testType,
+  });
+}
+
 /**
  * 获取分组统计信息
  */
@@ -1809,6 +3725,7 @@ export interface BackupData {
   groups?: GroupsBackup;
   traffic_stats?: TrafficStatsBackup;
   tasks?: TasksBackup;
+  saved_searches?: SavedSearchesBackup;
 }
 
 export type BackupType = "Full" | "Profiles" | "Settings" | "Custom";
@@ -1826,6 +3743,8 @@ export interface ProfileBackup {
   option?: string;
   home?: string;
   extra?: string;
+  itype?: string;
+  content?: string;
 }
 
 export interface SettingsBackup {
@@ -1846,6 +3765,10 @@ export interface TasksBackup {
   tasks_data: string;
 }
 
+export interface SavedSearchesBackup {
+  searches_data: string;
+}
+
 export interface BackupOptions {
   backup_type: BackupType;
   include_profiles: boolean;
@@ -1853,6 +3776,7 @@ export interface BackupOptions {
   include_groups: boolean;
   include_traffic_stats: boolean;
   include_tasks: boolean;
+  include_saved_searches?: boolean;
   encrypt: boolean;
   password?: string;
   compression_level: number;
@@ -1867,6 +3791,8 @@ export interface RestoreOptions {
   restore_groups: boolean;
   restore_traffic_stats: boolean;
   restore_tasks: boolean;
+  restore_saved_searches?: boolean;
+  profile_uids?: string[];
   merge_mode: boolean;
   password?: string;
   create_backup_before_restore: boolean;
@@ -1885,6 +3811,8 @@ export interface BackupInfo {
   is_encrypted: boolean;
   checksum: string;
   is_valid: boolean;
+  scheduled?: boolean;
+  base_backup_id?: string | null;
 }
 
 export interface RestoreResult {
@@ -1897,6 +3825,28 @@ export interface RestoreResult {
   backup_created?: string;
 }
 
+export type BackupCategory =
+  | "Profiles"
+  | "Settings"
+  | "Groups"
+  | "TrafficStats"
+  | "SavedSearches"
+  | "Tasks";
+
+export interface RestorePreviewItem {
+  category: BackupCategory;
+  id: string;
+  name: string;
+  action: "Create" | "Overwrite";
+}
+
+export interface RestorePreview {
+  backup_id: string;
+  backup_name: string;
+  created_at: number;
+  items: RestorePreviewItem[];
+}
+
 export interface WebDAVConfig {
   enabled: boolean;
   server_url: string;
@@ -1919,11 +3869,90 @@ export interface SyncStatus {
   is_syncing: boolean;
 }
 
+export type BackupProvider = "webdav" | "s3";
+
+export interface S3BackupConfig {
+  endpoint: string;
+  region: string;
+  bucket: string;
+  access_key_id: string;
+  secret_access_key: string;
+  prefix?: string;
+  path_style?: boolean;
+}
+
+export interface RemoteBackupFile {
+  name: string;
+  size: number;
+  last_modified?: string;
+}
+
+/**
+ * 保存 S3 兼容存储配置
+ */
+export async function saveS3BackupConfig(config: S3BackupConfig) {
+  return invoke<void>("save_s3_backup_config", { config });
+}
+
+export interface SecretMigrationReport {
+  migrated: string[];
+  skipped: string[];
+  failed: [string, string][];
+}
+
+/**
+ * 将配置中的 WebDAV 密码与 S3 密钥迁移到系统钥匙串
+ */
+export async function migrateSecretsToKeychain() {
+  return invoke<SecretMigrationReport>("migrate_secrets_to_keychain");
+}
+
+/**
+ * 设置默认远程备份存储
+ */
+export async function setRemoteBackupProvider(provider: BackupProvider) {
+  return invoke<void>("set_remote_backup_provider", { provider });
+}
+
+/**
+ * 备份并上传到远程存储
+ */
+export async function syncToRemote(provider?: BackupProvider) {
+  return invoke<string>("sync_to_remote", { provider });
+}
+
+/**
+ * 从远程存储恢复备份
+ */
+export async function syncFromRemote(
+  filename: string,
+  provider?: BackupProvider,
+) {
+  return invoke<void>("sync_from_remote", { filename, provider });
+}
+
+/**
+ * 列出远程存储上的备份
+ */
+export async function listRemoteBackups(provider?: BackupProvider) {
+  return invoke<RemoteBackupFile[]>("list_remote_backups", { provider });
+}
+
+/**
+ * 删除远程存储上的备份
+ */
+export async function deleteRemoteBackup(
+  filename: string,
+  provider?: BackupProvider,
+) {
+  return invoke<void>("delete_remote_backup", { filename, provider });
+}
+
 /**
  * 创建备份
  */
-export async function createBackup(options: BackupOptions) {
-  return invoke<string>("create_backup", { options });
+export async function createBackup(options: BackupOptions, taskId?: string) {
+  return invoke<string>("create_backup", { options, taskId });
 }
 
 /**
@@ -1936,8 +3965,11 @@ export async function getAllBackups() {
 /**
  * 获取备份详情
  */
-export async function getBackupDetails(backupId: string) {
-  return invoke<BackupData>("get_backup_details", { backup_id: backupId });
+export async function getBackupDetails(backupId: string, password?: string) {
+  return invoke<BackupData>("get_backup_details", {
+    backup_id: backupId,
+    password,
+  });
 }
 
 /**
@@ -1947,6 +3979,16 @@ export async function restoreBackup(options: RestoreOptions) {
   return invoke<RestoreResult>("restore_backup", { options });
 }
 
+/**
+ * 预览恢复备份
+ */
+export async function previewRestore(backupId: string, password?: string) {
+  return invoke<RestorePreview>("preview_restore", {
+    backupId,
+    password,
+  });
+}
+
 /**
  * 删除备份
  */
@@ -1957,8 +3999,23 @@ export async function deleteBackup(backupId: string) {
 /**
  * 验证备份
  */
-export async function validateBackup(backupId: string) {
-  return invoke<boolean>("validate_backup", { backup_id: backupId });
+export async function validateBackup(backupId: string, password?: string) {
+  return invoke<boolean>("validate_backup", { backup_id: backupId, password });
+}
+
+/**
+ * 轮换备份密码
+ */
+export async function rotateBackupPassword(
+  oldPassword: string,
+  newPassword: string,
+  backupIds?: string[],
+) {
+  return invoke<number>("rotate_backup_password", {
+    oldPassword,
+    newPassword,
+    backupIds,
+  });
 }
 
 /**
@@ -2016,6 +4073,45 @@ export async function getSyncStatus() {
   return invoke<SyncStatus>("get_sync_status");
 }
 
+export interface SyncConflict {
+  item: string;
+  name: string;
+  local_modified_at?: number;
+  remote_modified_at: number;
+  remote_device: string;
+  remote_deleted: boolean;
+}
+
+export interface SyncReport {
+  uploaded: number;
+  downloaded: number;
+  conflicts: SyncConflict[];
+}
+
+/**
+ * 立即进行多设备同步
+ */
+export async function syncNow() {
+  return invoke<SyncReport>("sync_now");
+}
+
+/**
+ * 获取未解决的同步冲突
+ */
+export async function getSyncConflicts() {
+  return invoke<SyncConflict[]>("get_sync_conflicts");
+}
+
+/**
+ * 解决同步冲突
+ */
+export async function resolveSyncConflict(
+  item: string,
+  strategy: "keep_local" | "keep_remote",
+) {
+  return invoke<void>("resolve_sync_conflict", { item, strategy });
+}
+
 /**
  * 清理旧备份
  */
@@ -2026,6 +4122,43 @@ export async function cleanupOldBackups(keepDays: number, keepCount: number) {
   });
 }
 
+export interface BackupSchedule {
+  enabled: boolean;
+  interval_hours: number;
+  keep_daily: number;
+  keep_weekly: number;
+  categories: BackupCategory[];
+  upload_to_remote: boolean;
+}
+
+export interface BackupScheduleState {
+  schedule: BackupSchedule;
+  last_run?: number;
+  last_backup_id?: string;
+  last_error?: string;
+}
+
+/**
+ * 设置定时备份计划
+ */
+export async function setBackupSchedule(schedule: BackupSchedule) {
+  return invoke<void>("set_backup_schedule", { schedule });
+}
+
+/**
+ * 获取定时备份计划及运行状态
+ */
+export async function getBackupSchedule() {
+  return invoke<BackupScheduleState>("get_backup_schedule");
+}
+
+/**
+ * 立即执行一次定时备份，内容未变化时返回 null
+ */
+export async function runScheduledBackupNow() {
+  return invoke<string | null>("run_scheduled_backup_now");
+}
+
 // ===== 高级搜索相关 =====
 
 export interface SearchCriteria {
@@ -2473,3 +4606,150 @@ export async function switchToNode(
   });
 }
 
+// ===== 可取消的长时间操作 =====
+
+export type OperationKind =
+  | "batch_import"
+  | "enhance"
+  | "subscription_test"
+  | "backup"
+  | "export"
+  | "subscription_update"
+  | "restore"
+  | "geo_update";
+
+export interface OperationInfo {
+  task_id: string;
+  kind: OperationKind;
+  started_at: number;
+  cancelled: boolean;
+}
+
+// operation-progress 事件，所有批量操作共用
+export interface OperationProgress {
+  task_id: string;
+  kind: OperationKind;
+  stage: string;
+  completed: number;
+  total: number;
+  message?: string | null;
+}
+
+// operation-started 事件
+export interface OperationStartedPayload {
+  task_id: string;
+  kind: OperationKind;
+}
+
+// operation-cancelled 事件，partial 为取消前已完成部分的结果
+export interface OperationCancelledPayload<T = unknown> {
+  task_id: string;
+  kind: OperationKind;
+  partial: T | null;
+}
+
+/**
+ * 取消正在运行的操作，taskId 可在调用时指定或从 operation-started 事件获得
+ */
+export async function cancelOperation(taskId: string) {
+  return invoke<void>("cancel_operation", { taskId });
+}
+
+/**
+ * 获取正在运行的可取消操作
+ */
+export async function getRunningOperations() {
+  return invoke<OperationInfo[]>("get_running_operations");
+}
+
+// ===== 后台任务队列 =====
+export type JobPriority = "low" | "normal" | "high";
+
+export type JobClass = "clash_api" | "network" | "disk";
+
+export type JobKind =
+  | { type: "scheduled_task"; task_id: string }
+  | { type: "scheduled_backup" };
+
+export type JobStatus =
+  | "pending"
+  | "running"
+  | "succeeded"
+  | "failed"
+  | "cancelled";
+
+// 同时作为 job-updated 事件的载荷
+export interface JobInfo {
+  id: string;
+  name: string;
+  kind: JobKind;
+  class: JobClass;
+  priority: JobPriority;
+  status: JobStatus;
+  submitted_at: number;
+  started_at?: number | null;
+  finished_at?: number | null;
+  message?: string | null;
+}
+
+export interface JobLogEntry {
+  time: number;
+  message: string;
+}
+
+/**
+ * 获取后台任务列表，未结束的任务在前
+ */
+export async function listJobs() {
+  return invoke<JobInfo[]>("list_jobs");
+}
+
+/**
+ * 取消排队中或运行中的后台任务
+ */
+export async function cancelJob(id: string) {
+  return invoke<void>("cancel_job", { id });
+}
+
+/**
+ * 获取后台任务日志
+ */
+export async function getJobLog(id: string) {
+  return invoke<JobLogEntry[]>("get_job_log", { id });
+}
+
+// ===== 从其他客户端迁移 =====
+export type MigrationClient = "clash_verge" | "clash_for_windows" | "v2rayn";
+
+export type MigrationCategory =
+  | "profile"
+  | "selected_nodes"
+  | "custom_rules"
+  | "enhancement"
+  | "settings";
+
+export interface MigrationItem {
+  category: MigrationCategory;
+  name: string;
+  imported: boolean;
+  reason?: string | null;
+}
+
+export interface MigrationSummary {
+  client: MigrationClient;
+  source_dir: string;
+  imported: number;
+  skipped: number;
+  items: MigrationItem[];
+}
+
+/**
+ * 从其他客户端迁移订阅、节点选择、自定义规则和基础设置
+ * @param path 客户端数据目录，为空时自动查找
+ */
+export async function migrateFromClient(
+  client: MigrationClient,
+  path?: string,
+) {
+  return invoke<MigrationSummary>("migrate_from_client", { client, path });
+}
@@ -1,4 +1,6 @@
-import { cmdGetProxyDelay } from "./cmds";
+import { cmdGetProxyDelay, getDelayTestTargets } from "./cmds";
+
+const FALLBACK_TEST_URL = "https://cp.cloudflare.com/generate_204";
 
 const hashKey = (name: string, group: string) => `${group ?? ""}::${name}`;
 
@@ -12,6 +14,22 @@ class DelayManager {
   // 每个分组的监听
   private groupListenerMap = new Map<string, () => void>();
 
+  // 未设置分组 URL 时使用的默认地址，取自测速目标设置的第一项
+  private defaultUrl = FALLBACK_TEST_URL;
+
+  constructor() {
+    this.loadDefaultUrl();
+  }
+
+  async loadDefaultUrl() {
+    try {
+      const [target] = await getDelayTestTargets();
+      this.defaultUrl = target?.url || FALLBACK_TEST_URL;
+    } catch (error) {
+      console.warn("[DelayManager] 加载默认测速目标失败:", error);
+    }
+  }
+
   setUrl(group: string, url: string) {
     console.log(`[DelayManager] 设置测试URL，组: ${group}, URL: ${url}`);
     this.urlMap.set(group, url);
@@ -22,8 +40,8 @@ class DelayManager {
     console.log(
       `[DelayManager] 获取测试URL，组: ${group}, URL: ${url || "未设置"}`,
     );
-    // 如果未设置URL，返回默认URL
-    return url || "https://cp.cloudflare.com/generate_204";
+    // 如果未设置URL，返回测速目标设置中的默认URL
+    return url || this.defaultUrl;
   }
 
   setListener(name: string, group: string, listener: (time: number) => void) {
@@ -155,6 +173,8 @@ class DelayManager {
     console.log(
       `[DelayManager] 批量测试延迟开始，组: ${group}, 数量: ${nameList.length}, 并发数: ${concurrency}`,
     );
+    // 测速目标设置可能已修改，批量测试前重新读取默认地址
+    await this.loadDefaultUrl();
     const names = nameList.filter(Boolean);
     // 设置正在延迟测试中
     names.forEach((name) => this.setDelay(name, group, -2));
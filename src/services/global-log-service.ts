@@ -6,6 +6,8 @@ import {
   stopLogsStreaming,
   clearLogs as clearLogsIPC,
 } from "@/services/ipc-log-service";
+import { subscribeClashStream } from "@/services/clash-stream";
+import dayjs from "dayjs";
 
 // 最大日志数量
 const MAX_LOG_NUM = 1000;
@@ -28,6 +30,7 @@ interface GlobalLogStore {
   setCurrentLevel: (level: LogLevel) => void;
   clearLogs: () => void;
   appendLog: (log: ILogItem) => void;
+  appendLogs: (logs: ILogItem[]) => void;
   setLogs: (logs: ILogItem[]) => void;
 }
 
@@ -48,11 +51,13 @@ export const useGlobalLogStore = create<GlobalLogStore>((set) => ({
           : [...state.logs, log];
       return { logs: newLogs };
     }),
+  appendLogs: (logs: ILogItem[]) =>
+    set((state) => ({ logs: [...state.logs, ...logs].slice(-MAX_LOG_NUM) })),
   setLogs: (logs: ILogItem[]) => set({ logs }),
 }));
 
-// IPC 日志获取函数
-export const fetchLogsViaIPCPeriodically = async () => {
+// 获取后端已缓存的日志，新日志通过 verge://clash-stream 推送
+export const fetchCachedLogs = async () => {
   try {
     const logs = await fetchLogsViaIPC();
     useGlobalLogStore.getState().setLogs(logs);
@@ -62,8 +67,17 @@ export const fetchLogsViaIPCPeriodically = async () => {
   }
 };
 
+// 推送的日志只有 type 和 payload，补充接收时间
+const handleLogBatch = (batch: ILogItem[]) => {
+  if (!Array.isArray(batch) || batch.length === 0) return;
+  const time = dayjs().format("HH:mm:ss");
+  useGlobalLogStore
+    .getState()
+    .appendLogs(batch.map((log) => ({ ...log, time: log.time || time })));
+};
+
 // 初始化全局日志服务 (仅IPC模式)
-let ipcPollingInterval: number | null = null;
+let unsubscribeLogStream: (() => void) | null = null;
 let isInitializing = false; // 添加初始化标志
 
 export const initGlobalLogService = (
@@ -84,7 +98,7 @@ export const initGlobalLogService = (
 
   // 如果不启用，则不初始化
   if (!enabled) {
-    clearIpcPolling();
+    stopLogStream();
     useGlobalLogStore.setState({ isConnected: false });
     return;
   }
@@ -97,14 +111,14 @@ export const initGlobalLogService = (
   // 启动流式监控
   startLogsStreaming(logLevel);
 
-  // 立即获取一次日志
-  fetchLogsViaIPCPeriodically();
+  // 先获取已缓存的日志，之后由数据流推送新日志
+  fetchCachedLogs();
 
-  // 设置定期轮询来同步流式缓存的数据
-  clearIpcPolling();
-  ipcPollingInterval = setInterval(() => {
-    fetchLogsViaIPCPeriodically();
-  }, 1000); // 每1秒同步一次流式缓存
+  stopLogStream();
+  unsubscribeLogStream = subscribeClashStream<ILogItem[]>(
+    "logs",
+    handleLogBatch,
+  );
 
   // 设置连接状态
   useGlobalLogStore.setState({ isConnected: true });
@@ -112,18 +126,18 @@ export const initGlobalLogService = (
   isInitializing = false;
 };
 
-// 清除IPC轮询
-const clearIpcPolling = () => {
-  if (ipcPollingInterval) {
-    clearInterval(ipcPollingInterval);
-    ipcPollingInterval = null;
-    console.log("[GlobalLog-IPC] 轮询已停止");
+// 取消日志数据流订阅
+const stopLogStream = () => {
+  if (unsubscribeLogStream) {
+    unsubscribeLogStream();
+    unsubscribeLogStream = null;
+    console.log("[GlobalLog-IPC] 日志推送已停止");
   }
 };
 
 // 停止日志监控 (仅IPC模式)
 export const stopGlobalLogMonitoring = async () => {
-  clearIpcPolling();
+  stopLogStream();
   isInitializing = false; // 重置初始化标志
 
   // 调用后端停止监控
@@ -152,7 +166,7 @@ export const changeLogLevel = (level: LogLevel) => {
   if (enabled) {
     // IPC流式模式下重新启动监控
     startLogsStreaming(level);
-    fetchLogsViaIPCPeriodically();
+    fetchCachedLogs();
   }
 };
 
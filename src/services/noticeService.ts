@@ -32,7 +32,8 @@ export function showNotice(
   const newNotice: NoticeItem = {
     id,
     type,
-    message,
+    // 命令错误是 Error 实例，直接渲染会报错，只展示其 message
+    message: message instanceof Error ? message.message : message,
     duration: effectiveDuration,
   };
 
@@ -98,6 +98,7 @@ interface IProxyItem {
   icon?: string;
   provider?: string; // 记录是否来自provider
   fixed?: string; // 记录固定(优先)的节点
+  bandwidthLimit?: { up_kbps: number; down_kbps: number }; // 代理组带宽限制
 }
 
 type IProxyGroupItem = Omit<IProxyItem, "all"> & {
@@ -270,6 +271,8 @@ interface IProfileOption {
   update_interval?: number;
   timeout_seconds?: number;
   danger_accept_invalid_certs?: boolean;
+  fetch_policy?: IProfileFetchRoute[];
+  chain_manifest?: IChainManifest;
   merge?: string;
   script?: string;
   rules?: string;
@@ -277,6 +280,17 @@ interface IProfileOption {
   groups?: string;
 }
 
+type IProfileFetchRoute =
+  | { type: "direct" }
+  | { type: "system" }
+  | { type: "clash" }
+  | { type: "node"; node: string };
+
+interface IChainManifest {
+  keys: string[];
+  depends_on?: string[];
+}
+
 interface IProfilesConfig {
   current?: string;
   valid?: string[];
@@ -801,9 +815,14 @@ interface IVergeConfig {
   tun_tray_icon?: boolean;
   enable_tray_speed?: boolean;
   enable_tray_icon?: boolean;
+  tray_stats_items?: ("speed" | "node" | "ip")[];
+  tray_stats_in_title?: boolean;
   enable_tun_mode?: boolean;
   enable_auto_light_weight_mode?: boolean;
   auto_light_weight_minutes?: number;
+  light_weight_exit_on_tray?: boolean;
+  light_weight_exit_on_hotkey?: boolean;
+  light_weight_unload_webview?: boolean;
   enable_auto_launch?: boolean;
   enable_silent_start?: boolean;
   enable_system_proxy?: boolean;
@@ -850,14 +869,35 @@ interface IVergeConfig {
   default_latency_timeout?: number;
   enable_builtin_enhanced?: boolean;
   auto_log_clean?: 0 | 1 | 2 | 3 | 4;
+  logging_profile?: "interactive" | "server";
+  log_dir_max_size?: number;
   proxy_layout_column?: number;
   test_list?: IVergeTestItem[];
   webdav_url?: string;
   webdav_username?: string;
   webdav_password?: string;
+  remote_backup_provider?: "webdav" | "s3";
+  s3_backup?: {
+    endpoint: string;
+    region: string;
+    bucket: string;
+    access_key_id: string;
+    secret_access_key: string;
+    prefix?: string;
+    path_style?: boolean;
+  };
   home_cards?: Record<string, boolean>;
   enable_hover_jump_navigator?: boolean;
   enable_external_controller?: boolean;
+  port_conflict_strategy?: "report" | "remap";
+  captive_portal_policy?: "off" | "notify" | "disable_sysproxy" | "direct";
+  proxy_group_preferences?: Record<string, IProxyGroupPreference>;
+}
+
+interface IProxyGroupPreference {
+  hidden: boolean;
+  sort_by: "default" | "latency" | "name";
+  pin_favorites: boolean;
 }
 
 interface IWebDavFile {